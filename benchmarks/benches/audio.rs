@@ -0,0 +1,95 @@
+use clack_host::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const CHANNEL_COUNT: usize = 2;
+const FRAME_COUNTS: [usize; 3] = [64, 512, 4096];
+
+/// Splits interleaved samples into consecutive per-channel buffers.
+fn deinterleave(interleaved: &[f32], channels: &mut [Vec<f32>]) {
+    let channel_count = channels.len();
+
+    for (channel_index, channel) in channels.iter_mut().enumerate() {
+        for (frame, sample) in channel.iter_mut().enumerate() {
+            *sample = interleaved[frame * channel_count + channel_index];
+        }
+    }
+}
+
+/// Merges per-channel buffers into interleaved samples.
+fn interleave(channels: &[Vec<f32>], interleaved: &mut [f32]) {
+    let channel_count = channels.len();
+
+    for (channel_index, channel) in channels.iter().enumerate() {
+        for (frame, sample) in channel.iter().enumerate() {
+            interleaved[frame * channel_count + channel_index] = *sample;
+        }
+    }
+}
+
+fn interleaving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("interleaving");
+
+    for frames in FRAME_COUNTS {
+        group.throughput(Throughput::Elements((frames * CHANNEL_COUNT) as u64));
+
+        let interleaved: Vec<f32> = (0..frames * CHANNEL_COUNT).map(|i| i as f32).collect();
+        let mut channels = vec![vec![0.0f32; frames]; CHANNEL_COUNT];
+
+        group.bench_function(BenchmarkId::new("deinterleave", frames), |b| {
+            b.iter(|| deinterleave(black_box(&interleaved), &mut channels))
+        });
+
+        let mut output = vec![0.0f32; frames * CHANNEL_COUNT];
+        group.bench_function(BenchmarkId::new("interleave", frames), |b| {
+            b.iter(|| interleave(black_box(&channels), &mut output))
+        });
+    }
+
+    group.finish();
+}
+
+fn buffer_wrapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("audio_buffers");
+
+    for frames in FRAME_COUNTS {
+        let interleaved: Vec<f32> = (0..frames * CHANNEL_COUNT).map(|i| i as f32).collect();
+        let mut input_channels = vec![vec![0.0f32; frames]; CHANNEL_COUNT];
+        let mut output_channels = vec![vec![0.0f32; frames]; CHANNEL_COUNT];
+        let mut output = vec![0.0f32; frames * CHANNEL_COUNT];
+
+        let mut input_ports = AudioPorts::with_capacity(CHANNEL_COUNT, 1);
+        let mut output_ports = AudioPorts::with_capacity(CHANNEL_COUNT, 1);
+
+        // This is what a host does around every process call: copy the device's audio in, wrap the
+        // plugin buffers, and copy the plugin's output back out.
+        group.bench_function(BenchmarkId::new("prepare_and_copy", frames), |b| {
+            b.iter(|| {
+                deinterleave(black_box(&interleaved), &mut input_channels);
+
+                let inputs = input_ports.with_input_buffers([AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_input_only(
+                        input_channels.iter_mut().map(InputChannel::variable),
+                    ),
+                }]);
+
+                let outputs = output_ports.with_output_buffers([AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_output_only(
+                        output_channels.iter_mut().map(|c| c.as_mut_slice()),
+                    ),
+                }]);
+
+                black_box(inputs.min_available_frames_with(&outputs));
+
+                interleave(&output_channels, &mut output);
+                black_box(&output);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, interleaving, buffer_wrapping);
+criterion_main!(benches);
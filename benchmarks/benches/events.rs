@@ -0,0 +1,168 @@
+use clack_common::events::event_types::{NoteOnEvent, ParamValueEvent};
+use clack_common::events::io::{EventBuffer, InputEvents};
+use clack_common::events::{Event, Pckn, UnknownEvent};
+use clack_common::utils::{ClapId, Cookie};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+const EVENT_COUNTS: [u32; 3] = [16, 256, 4096];
+
+/// Creates a buffer of `count` note and parameter events, in sorted order.
+fn sorted_events(count: u32) -> EventBuffer {
+    let mut buffer = EventBuffer::with_capacity(count as usize);
+
+    for i in 0..count {
+        if i % 2 == 0 {
+            buffer.push(&NoteOnEvent::new(i, Pckn::new(0u16, 0u16, 60u16, i), 1.0));
+        } else {
+            buffer.push(&ParamValueEvent::new(
+                i,
+                ClapId::new(i % 8),
+                Pckn::match_all(),
+                0.5,
+                Cookie::empty(),
+            ));
+        }
+    }
+
+    buffer
+}
+
+/// Returns `count` events with pseudo-random timestamps.
+fn shuffled_events(count: u32) -> Vec<NoteOnEvent> {
+    (0..count)
+        .map(|i| {
+            let time = i.wrapping_mul(2_654_435_761) % count;
+            NoteOnEvent::new(time, Pckn::new(0u16, 0u16, 60u16, i), 1.0)
+        })
+        .collect()
+}
+
+fn iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("input_events");
+
+    for count in EVENT_COUNTS {
+        let buffer = sorted_events(count);
+        let events = InputEvents::from_buffer(&buffer);
+
+        group.bench_with_input(BenchmarkId::new("iter", count), &events, |b, events| {
+            b.iter(|| {
+                let mut sum = 0u64;
+                for event in events {
+                    sum += u64::from(event.header().time());
+                }
+                black_box(sum)
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("get", count), &events, |b, events| {
+            b.iter(|| {
+                let mut sum = 0u64;
+                for i in 0..events.len() {
+                    // PANIC: i is always in bounds.
+                    sum += u64::from(events.get(i).unwrap().header().time());
+                }
+                black_box(sum)
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("batch", count), &events, |b, events| {
+            b.iter(|| {
+                let mut batches = 0u32;
+                for batch in events.batch() {
+                    batches += 1;
+                    black_box(batch.sample_bounds());
+                }
+                black_box(batches)
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("as_core_event", count),
+            &events,
+            |b, events| {
+                b.iter(|| {
+                    let mut notes = 0u32;
+                    for event in events {
+                        if let Some(e) = event.as_event::<NoteOnEvent>() {
+                            notes += u32::from(e.velocity() > 0.5);
+                        }
+                    }
+                    black_box(notes)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn insertion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_buffer");
+
+    for count in EVENT_COUNTS {
+        let events = shuffled_events(count);
+
+        group.bench_with_input(BenchmarkId::new("push", count), &events, |b, events| {
+            b.iter_batched_ref(
+                || EventBuffer::with_capacity(events.len()),
+                |buffer| buffer.push_all(events.iter().map(|e| e.as_unknown())),
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("push_then_sort", count),
+            &events,
+            |b, events| {
+                b.iter_batched_ref(
+                    || EventBuffer::with_capacity(events.len()),
+                    |buffer| {
+                        buffer.push_all(events.iter().map(|e| e.as_unknown()));
+                        buffer.sort();
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("insertion_sort", count),
+            &events,
+            |b, events| {
+                b.iter_batched_ref(
+                    || EventBuffer::with_capacity(events.len()),
+                    |buffer| {
+                        for event in events {
+                            insert_sorted(buffer, event.as_unknown());
+                        }
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Inserts an event after all the events that have a smaller or equal timestamp.
+fn insert_sorted(buffer: &mut EventBuffer, event: &UnknownEvent) {
+    let time = event.header().time();
+    let mut low = 0;
+    let mut high = buffer.len();
+
+    while low < high {
+        let middle = (low + high) / 2;
+        // PANIC: middle is always in bounds.
+        if buffer.get(middle as u32).unwrap().header().time() <= time {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    buffer.insert(event, low);
+}
+
+criterion_group!(benches, iteration, insertion);
+criterion_main!(benches);
@@ -0,0 +1,220 @@
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::ffi::CStr;
+
+/// A plugin that copies its input to its output, to measure the overhead of the wrappers.
+pub struct PassthroughPlugin;
+pub struct PassthroughPluginMainThread;
+pub struct PassthroughPluginAudioProcessor;
+
+impl Plugin for PassthroughPlugin {
+    type AudioProcessor<'a> = PassthroughPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = PassthroughPluginMainThread;
+}
+
+impl PluginMainThread<'_, ()> for PassthroughPluginMainThread {}
+
+impl DefaultPluginFactory for PassthroughPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("org.rust-audio.clack.passthrough", "Passthrough")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(PassthroughPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), PassthroughPluginMainThread>
+    for PassthroughPluginAudioProcessor
+{
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut PassthroughPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        mut audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        for event in events.input {
+            black_box(event);
+        }
+
+        for mut port_pair in &mut audio {
+            let Some(channels) = port_pair.channels()?.into_f32() else {
+                continue;
+            };
+
+            for pair in channels {
+                if let ChannelPair::InputOutput(input, output) = pair {
+                    output.copy_from_slice(input);
+                }
+            }
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static PASSTHROUGH_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<PassthroughPlugin>);
+
+struct BenchHost;
+
+impl HostHandlers for BenchHost {
+    type Shared<'a> = BenchHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct BenchHostShared;
+
+impl SharedHandler<'_> for BenchHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+const FRAME_COUNTS: [u32; 3] = [1, 64, 512];
+const EVENT_COUNTS: [u32; 2] = [0, 64];
+
+fn process_overhead(c: &mut Criterion) {
+    let bundle =
+        unsafe { PluginBundle::load_from_raw(&PASSTHROUGH_ENTRY, "/passthrough") }.unwrap();
+    let host = HostInfo::new(
+        "Clack benchmarks",
+        "Clack",
+        "https://github.com/prokopyl/clack",
+        "1.0",
+    )
+    .unwrap();
+
+    let mut instance = PluginInstance::<BenchHost>::new(
+        |_| BenchHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"org.rust-audio.clack.passthrough\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let max_frames = *FRAME_COUNTS.iter().max().unwrap();
+    let config = PluginAudioConfiguration {
+        sample_rate: 48_000.0,
+        min_frames_count: 1,
+        max_frames_count: max_frames,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut input_ports = AudioPorts::with_capacity(2, 1);
+    let mut output_ports = AudioPorts::with_capacity(2, 1);
+
+    let mut group = c.benchmark_group("process");
+
+    for frames in FRAME_COUNTS {
+        for event_count in EVENT_COUNTS {
+            let mut input_channels = vec![vec![0.5f32; frames as usize]; 2];
+            let mut output_channels = vec![vec![0.0f32; frames as usize]; 2];
+
+            let mut input_events = EventBuffer::with_capacity(event_count as usize);
+            for i in 0..event_count {
+                input_events.push(&NoteOnEvent::new(
+                    i % frames,
+                    Pckn::new(0u16, 0u16, 60u16, i),
+                    1.0,
+                ));
+            }
+            input_events.sort();
+
+            let id = BenchmarkId::new(format!("{event_count}_events"), frames);
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    let inputs = input_ports.with_input_buffers([AudioPortBuffer {
+                        latency: 0,
+                        channels: AudioPortBufferType::f32_input_only(
+                            input_channels.iter_mut().map(InputChannel::variable),
+                        ),
+                    }]);
+
+                    let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+                        latency: 0,
+                        channels: AudioPortBufferType::f32_output_only(
+                            output_channels.iter_mut().map(|c| c.as_mut_slice()),
+                        ),
+                    }]);
+
+                    processor
+                        .process(
+                            &inputs,
+                            &mut outputs,
+                            &input_events.as_input(),
+                            &mut OutputEvents::void(),
+                            None,
+                            None,
+                        )
+                        .unwrap()
+                })
+            });
+
+            let id = BenchmarkId::new(format!("{event_count}_events_session"), frames);
+            group.bench_function(id, |b| {
+                let mut session = processor.session().unwrap();
+
+                b.iter(|| {
+                    let inputs = input_ports.with_input_buffers([AudioPortBuffer {
+                        latency: 0,
+                        channels: AudioPortBufferType::f32_input_only(
+                            input_channels.iter_mut().map(InputChannel::variable),
+                        ),
+                    }]);
+
+                    let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+                        latency: 0,
+                        channels: AudioPortBufferType::f32_output_only(
+                            output_channels.iter_mut().map(|c| c.as_mut_slice()),
+                        ),
+                    }]);
+
+                    session
+                        .process(
+                            &inputs,
+                            &mut outputs,
+                            &input_events.as_input(),
+                            &mut OutputEvents::void(),
+                            None,
+                            None,
+                        )
+                        .unwrap()
+                })
+            });
+        }
+    }
+
+    group.finish();
+
+    instance.deactivate(processor.stop_processing());
+}
+
+criterion_group!(benches, process_overhead);
+criterion_main!(benches);
@@ -0,0 +1 @@
+//! Benchmarks for Clack's hot paths. See the `benches` directory, and run them with `cargo bench`.
@@ -0,0 +1,26 @@
+#![no_main]
+
+use clack_common::events::io::EventBuffer;
+use clack_common::events::UnknownEvent;
+use clack_common_fuzz::aligned;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let storage = aligned(data);
+
+    let mut buffer = EventBuffer::new();
+    buffer.push_all(UnknownEvent::iter_from_bytes(&storage[..data.len()]));
+    buffer.sort();
+
+    let input = buffer.as_input();
+    for event in &input {
+        let _ = event.as_core_event();
+    }
+
+    for batch in input.batch() {
+        let _ = batch.sample_bounds();
+        for event in batch.events() {
+            let _ = format!("{event:?}");
+        }
+    }
+});
@@ -0,0 +1,65 @@
+#![no_main]
+
+use clack_common::stream::InputStream;
+use clap_sys::stream::clap_istream;
+use libfuzzer_sys::fuzz_target;
+use std::ffi::c_void;
+use std::io::Read;
+
+/// A stream that behaves according to the fuzzer's input.
+///
+/// The first byte of each read decides how the stream responds, including with errors or
+/// invalid byte counts. The following bytes are used as the stream's contents.
+struct HostileStream<'a> {
+    data: &'a [u8],
+}
+
+unsafe extern "C" fn read(stream: *const clap_istream, buffer: *mut c_void, size: u64) -> i64 {
+    let stream = &mut *((*stream).ctx as *mut HostileStream);
+
+    let Some((&command, rest)) = stream.data.split_first() else {
+        return 0;
+    };
+    stream.data = rest;
+
+    match command {
+        0xFF => -1,
+        0xFE => size as i64 + 1,
+        0xFD => i64::MAX,
+        max => {
+            let len = (max as usize).min(size as usize).min(stream.data.len());
+            core::ptr::copy_nonoverlapping(stream.data.as_ptr(), buffer as *mut u8, len);
+            stream.data = &stream.data[len..];
+            len as i64
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut hostile = HostileStream { data };
+    let mut raw = clap_istream {
+        ctx: &mut hostile as *mut HostileStream as *mut c_void,
+        read: Some(read),
+    };
+
+    // SAFETY: the stream's context and read function are both valid
+    let stream = unsafe { InputStream::from_raw_mut(&mut raw) };
+
+    let mut contents = Vec::new();
+    if stream.read_to_end(&mut contents).is_ok() {
+        assert!(contents.len() <= data.len());
+    }
+
+    // Also exercise the reader wrapper the other way around, reading a regular buffer through
+    // the C FFI interface.
+    let mut source = data;
+    let mut stream = InputStream::from_reader(&mut source);
+    let raw = stream.as_raw_mut();
+    let mut buffer = [0u8; 64];
+
+    for &size in data.iter().take(16) {
+        // SAFETY: the buffer is valid for at least `size` bytes
+        let ret = unsafe { (raw.read.unwrap())(raw, buffer.as_mut_ptr().cast(), size as u64 % 65) };
+        assert!(ret <= 64);
+    }
+});
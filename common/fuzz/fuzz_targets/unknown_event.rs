@@ -0,0 +1,16 @@
+#![no_main]
+
+use clack_common::events::UnknownEvent;
+use clack_common_fuzz::aligned;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let storage = aligned(data);
+
+    if let Some(event) = UnknownEvent::from_bytes(&storage[..data.len()]) {
+        assert!(event.as_bytes().len() <= data.len());
+
+        let _ = event.as_core_event();
+        let _ = format!("{event:?}");
+    }
+});
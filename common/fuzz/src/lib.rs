@@ -0,0 +1,30 @@
+//! Shared helpers for the `clack-common` fuzz targets.
+
+/// Copies the given fuzzer input in a buffer aligned to
+/// [`EVENT_ALIGNMENT`](clack_common::events::EVENT_ALIGNMENT) bytes.
+///
+/// Fuzzer inputs have no alignment guarantees, which would make all event parsing fail early.
+pub fn aligned(data: &[u8]) -> AlignedBytes {
+    let mut storage = vec![0u64; data.len().div_ceil(8)];
+
+    // SAFETY: the storage is at least as large as the input, and any byte is a valid u64 part.
+    unsafe {
+        core::ptr::copy_nonoverlapping(data.as_ptr(), storage.as_mut_ptr().cast(), data.len())
+    };
+
+    AlignedBytes { storage }
+}
+
+/// An aligned byte buffer, created by [`aligned`].
+pub struct AlignedBytes {
+    storage: Vec<u64>,
+}
+
+impl core::ops::Deref for AlignedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: any u64 is a valid sequence of 8 bytes.
+        unsafe { core::slice::from_raw_parts(self.storage.as_ptr().cast(), self.storage.len() * 8) }
+    }
+}
@@ -200,6 +200,32 @@ impl UnknownEvent {
         self.as_event_space(EventSpaceId::core())
     }
 
+    /// Returns the [core event type](CoreEventType) of this event, or `None` if this event isn't a
+    /// standard CLAP event.
+    ///
+    /// Unlike [`as_core_event`](Self::as_core_event), this only inspects the header of this
+    /// event, and doesn't check its size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::events::spaces::CoreEventType;
+    /// use clack_common::events::UnknownEvent;
+    ///
+    /// fn is_note_event(event: &UnknownEvent) -> bool {
+    ///     event.core_type().is_some_and(CoreEventType::is_note)
+    /// }
+    /// ```
+    #[inline]
+    pub fn core_type(&self) -> Option<CoreEventType> {
+        let header = self.header();
+        if header.space_id() != Some(EventSpaceId::core().into()) {
+            return None;
+        }
+
+        CoreEventType::from_type_id(header.type_id())
+    }
+
     /// Attempts to downcast this event to a specific event type from a given [event space](EventSpace).
     ///
     /// This returns a down-casted reference to the event if the event matches the given type and
@@ -284,6 +310,122 @@ impl UnknownEvent {
     pub const unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &UnknownEvent {
         &*(bytes as *const [u8] as *const _)
     }
+
+    /// Retrieves an event from a byte buffer, checking it is well-formed.
+    ///
+    /// The given buffer must start with the event's [header](EventHeader), and be aligned to
+    /// [`EVENT_ALIGNMENT`] bytes. The event's size (as declared in its header) must be at least as
+    /// large as the header itself, and must fit in the buffer. Any bytes following the event are
+    /// ignored.
+    ///
+    /// If any of these conditions are not met, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::events::event_types::NoteOnEvent;
+    /// use clack_common::events::{Pckn, UnknownEvent};
+    ///
+    /// let event = NoteOnEvent::new(0, Pckn::new(0u16, 0u16, 60u16, 0u32), 1.0);
+    /// let bytes = event.as_ref().as_bytes();
+    ///
+    /// let parsed = UnknownEvent::from_bytes(bytes).unwrap();
+    /// assert_eq!(parsed.as_event(), Some(&event));
+    ///
+    /// // Truncated events are rejected.
+    /// assert!(UnknownEvent::from_bytes(&bytes[..4]).is_none());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Option<&UnknownEvent> {
+        if bytes.len() < MIN_EVENT_SIZE || bytes.as_ptr() as usize % EVENT_ALIGNMENT != 0 {
+            return None;
+        }
+
+        // SAFETY: we just checked the buffer is large and aligned enough to contain a header.
+        let size = unsafe { (*(bytes.as_ptr() as *const clap_event_header)).size } as usize;
+
+        if size < MIN_EVENT_SIZE || size > bytes.len() {
+            return None;
+        }
+
+        // SAFETY: the buffer is aligned, and contains a header whose size field matches its length.
+        Some(unsafe { Self::from_bytes_unchecked(&bytes[..size]) })
+    }
+
+    /// Returns an iterator over all the events packed in the given byte buffer.
+    ///
+    /// Events are expected to follow each other in the buffer, each starting at an offset that is
+    /// aligned to [`EVENT_ALIGNMENT`] bytes. The buffer itself must be aligned as well.
+    ///
+    /// The iterator stops at the first malformed event (see [`from_bytes`](Self::from_bytes)), or
+    /// at the end of the buffer. The bytes that could not be parsed can then be retrieved with
+    /// [`PackedEventsIter::remaining`].
+    #[inline]
+    pub fn iter_from_bytes(bytes: &[u8]) -> PackedEventsIter<'_> {
+        PackedEventsIter { remaining: bytes }
+    }
+
+    /// Gets an unknown event from a raw event header, checking its pointer and header size first.
+    ///
+    /// This returns `None` if the pointer is null or misaligned, or if the size declared in the
+    /// header is smaller than the header itself.
+    ///
+    /// # Safety
+    ///
+    /// If the pointer is non-null, the caller must ensure it points to a readable event header,
+    /// which is followed by as many readable bytes as declared in its size.
+    #[inline]
+    pub(crate) unsafe fn from_raw_checked<'e>(
+        header: *const clap_event_header,
+    ) -> Option<&'e Self> {
+        if header.is_null()
+            || header as usize % core::mem::align_of::<clap_event_header>() != 0
+            || ((*header).size as usize) < MIN_EVENT_SIZE
+        {
+            return None;
+        }
+
+        Some(Self::from_raw(header))
+    }
+}
+
+/// The minimum size of any event, i.e. the size of its header.
+const MIN_EVENT_SIZE: usize = core::mem::size_of::<clap_event_header>();
+
+/// The alignment, in bytes, that event buffers must satisfy for any event type to be safely read
+/// from them.
+pub const EVENT_ALIGNMENT: usize = 8;
+
+/// An iterator over events packed in a byte buffer.
+///
+/// See [`UnknownEvent::iter_from_bytes`].
+#[derive(Clone)]
+pub struct PackedEventsIter<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> PackedEventsIter<'a> {
+    /// Returns the bytes that have not been parsed yet.
+    ///
+    /// Once the iterator returned `None`, this is empty if all events were parsed successfully,
+    /// or starts with the malformed event otherwise.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.remaining
+    }
+}
+
+impl<'a> Iterator for PackedEventsIter<'a> {
+    type Item = &'a UnknownEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = UnknownEvent::from_bytes(self.remaining)?;
+
+        let size = event.as_bytes().len();
+        let padded_size = size + (EVENT_ALIGNMENT - size % EVENT_ALIGNMENT) % EVENT_ALIGNMENT;
+        self.remaining = self.remaining.get(padded_size..).unwrap_or(&[]);
+
+        Some(event)
+    }
 }
 
 impl<E: Event> PartialEq<E> for UnknownEvent
@@ -377,3 +519,87 @@ const fn panic_event_space_mismatch_const() {
 const fn panic_event_type_mismatch_const() {
     panic!("CLAP Event mismatch: got a different event ID from expected")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::event_types::MidiEvent;
+
+    /// Packs the given events in an aligned byte buffer.
+    fn pack(events: &[MidiEvent]) -> Vec<u64> {
+        let mut storage = vec![0u64; events.len() * 3];
+        let size = core::mem::size_of_val(events);
+        assert!(size <= storage.len() * 8);
+
+        // SAFETY: the storage is large enough to hold all the events.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                events.as_ptr() as *const u8,
+                storage.as_mut_ptr() as *mut u8,
+                size,
+            )
+        };
+
+        storage
+    }
+
+    fn as_bytes(storage: &[u64]) -> &[u8] {
+        // SAFETY: any u64 is a valid sequence of 8 bytes.
+        unsafe { core::slice::from_raw_parts(storage.as_ptr() as *const u8, storage.len() * 8) }
+    }
+
+    #[test]
+    fn iterates_packed_events() {
+        let events = [MidiEvent::new(0, 0, [1; 3]), MidiEvent::new(1, 0, [2; 3])];
+        let storage = pack(&events);
+
+        let mut iter = UnknownEvent::iter_from_bytes(as_bytes(&storage));
+        assert_eq!(iter.next().unwrap(), &events[0]);
+        assert_eq!(iter.next().unwrap(), &events[1]);
+        assert!(iter.next().is_none());
+        assert!(iter.remaining().is_empty());
+    }
+
+    #[test]
+    fn stops_at_malformed_events() {
+        let events = [MidiEvent::new(0, 0, [1; 3]), MidiEvent::new(1, 0, [2; 3])];
+        let mut storage = pack(&events);
+
+        // Corrupt the size of the second event, making it larger than the buffer.
+        // SAFETY: any u64 is a valid sequence of 8 bytes, and the event size is stored first.
+        let bytes = unsafe { core::slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut u8, 48) };
+        bytes[24..28].copy_from_slice(&0xFFFFu32.to_ne_bytes());
+
+        let mut iter = UnknownEvent::iter_from_bytes(as_bytes(&storage));
+        assert_eq!(iter.next().unwrap(), &events[0]);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.remaining().len(), 24);
+    }
+
+    #[test]
+    fn rejects_misaligned_buffers() {
+        let storage = pack(&[MidiEvent::new(0, 0, [1; 3])]);
+        let bytes = as_bytes(&storage);
+
+        assert!(UnknownEvent::from_bytes(bytes).is_some());
+        assert!(UnknownEvent::from_bytes(&bytes[1..]).is_none());
+        assert!(UnknownEvent::from_bytes(&bytes[..8]).is_none());
+    }
+
+    #[test]
+    fn core_types_round_trip() {
+        for event_type in CoreEventType::ALL {
+            assert_eq!(
+                CoreEventType::from_type_id(event_type.type_id()),
+                Some(event_type)
+            );
+        }
+
+        let event = MidiEvent::new(0, 0, [1; 3]);
+        assert_eq!(event.as_unknown().core_type(), Some(CoreEventType::Midi));
+        assert_eq!(
+            event.as_unknown().as_core_event().unwrap().event_type(),
+            CoreEventType::Midi
+        );
+    }
+}
@@ -2,7 +2,9 @@ use crate::events::spaces::CoreEventSpace;
 use crate::events::{Event, Match, Pckn, UnknownEvent};
 use clap_sys::events::*;
 
+mod builder;
 mod inner;
+pub use builder::*;
 use inner::*;
 
 /// A note key pressed event.
@@ -52,6 +54,22 @@ impl NoteOnEvent {
         self
     }
 
+    /// Returns the velocity of this event as a MIDI 1.0 velocity, in the `0..=127` range.
+    ///
+    /// See [`clap_velocity_to_midi`] for how the velocity is converted.
+    #[inline]
+    pub fn midi_velocity(&self) -> u8 {
+        clap_velocity_to_midi(self.velocity())
+    }
+
+    /// Sets the velocity of this event from a MIDI 1.0 velocity, in the `0..=127` range.
+    ///
+    /// See [`midi_velocity_to_clap`] for how the velocity is converted.
+    #[inline]
+    pub fn with_midi_velocity(self, velocity: u8) -> Self {
+        self.with_velocity(midi_velocity_to_clap(velocity))
+    }
+
     self::impl_note_helpers!();
 }
 
@@ -79,6 +97,22 @@ impl NoteOffEvent {
         self
     }
 
+    /// Returns the velocity of this event as a MIDI 1.0 velocity, in the `0..=127` range.
+    ///
+    /// See [`clap_velocity_to_midi`] for how the velocity is converted.
+    #[inline]
+    pub fn midi_velocity(&self) -> u8 {
+        clap_velocity_to_midi(self.velocity())
+    }
+
+    /// Sets the velocity of this event from a MIDI 1.0 velocity, in the `0..=127` range.
+    ///
+    /// See [`midi_velocity_to_clap`] for how the velocity is converted.
+    #[inline]
+    pub fn with_midi_velocity(self, velocity: u8) -> Self {
+        self.with_velocity(midi_velocity_to_clap(velocity))
+    }
+
     self::impl_note_helpers!();
 }
 
@@ -0,0 +1,234 @@
+#![deny(missing_docs)]
+
+use super::{NoteChokeEvent, NoteEndEvent, NoteOffEvent, NoteOnEvent};
+use crate::events::{Event, EventFlags, Match, Pckn};
+
+/// Converts a MIDI 1.0 velocity (in the `0..=127` range) to a CLAP velocity (in the `0.0..=1.0`
+/// range).
+///
+/// MIDI velocities above `127` are clamped to `1.0`.
+///
+/// Note that this performs a plain linear conversion: a MIDI Note On with a velocity of `0`, which
+/// MIDI hosts usually interpret as a Note Off, is converted to a velocity of `0.0`.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::midi_velocity_to_clap;
+///
+/// assert_eq!(midi_velocity_to_clap(0), 0.0);
+/// assert_eq!(midi_velocity_to_clap(127), 1.0);
+/// ```
+#[inline]
+pub fn midi_velocity_to_clap(velocity: u8) -> f64 {
+    velocity.min(127) as f64 / 127.0
+}
+
+/// Converts a CLAP velocity (in the `0.0..=1.0` range) to a MIDI 1.0 velocity (in the `0..=127`
+/// range), rounding it to the nearest value.
+///
+/// Out-of-range velocities are clamped, and `NaN` is converted to `0`.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::clap_velocity_to_midi;
+///
+/// assert_eq!(clap_velocity_to_midi(0.5), 64);
+/// assert_eq!(clap_velocity_to_midi(2.0), 127);
+/// ```
+#[inline]
+pub fn clap_velocity_to_midi(velocity: f64) -> u8 {
+    if velocity.is_nan() {
+        return 0;
+    }
+
+    (velocity.clamp(0.0, 1.0) * 127.0).round() as u8
+}
+
+/// A builder for all note event types.
+///
+/// Instead of passing all of a note event's properties positionally (as done by e.g.
+/// [`NoteOnEvent::new`]), this builder allows to set each of them by name, and then to create
+/// any of the note event types from them.
+///
+/// All the [`Pckn`] components default to [`Match::All`], the time defaults to `0`, the velocity
+/// defaults to `1.0`, and the flags default to being empty.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::NoteEventBuilder;
+/// use clack_common::events::{EventFlags, Match};
+///
+/// let note = NoteEventBuilder::new()
+///     .time(42)
+///     .port_index(0)
+///     .channel(3)
+///     .key(60)
+///     .midi_velocity(100)
+///     .flags(EventFlags::IS_LIVE);
+///
+/// let note_on = note.note_on();
+/// let note_off = note.midi_velocity(0).note_off();
+///
+/// assert_eq!(note_on.key(), Match::Specific(60));
+/// assert_eq!(note_on.midi_velocity(), 100);
+/// assert_eq!(note_off.velocity(), 0.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NoteEventBuilder {
+    time: u32,
+    pckn: Pckn,
+    velocity: f64,
+    flags: EventFlags,
+}
+
+impl NoteEventBuilder {
+    /// Creates a new builder, with all of its properties set to their default values.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            time: 0,
+            pckn: Pckn::match_all(),
+            velocity: 1.0,
+            flags: EventFlags::empty(),
+        }
+    }
+
+    /// Sets the time of the note events, in samples.
+    #[inline]
+    pub const fn time(mut self, time: u32) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Sets the full [`Pckn`] tuple of the note events.
+    #[inline]
+    pub const fn pckn(mut self, pckn: Pckn) -> Self {
+        self.pckn = pckn;
+        self
+    }
+
+    /// Sets the index of the note port the note events target.
+    #[inline]
+    pub const fn port_index(mut self, port_index: u16) -> Self {
+        self.pckn.port_index = Match::Specific(port_index);
+        self
+    }
+
+    /// Sets the note channel the note events target. This is usually in the `0..=15` range.
+    #[inline]
+    pub const fn channel(mut self, channel: u16) -> Self {
+        self.pckn.channel = Match::Specific(channel);
+        self
+    }
+
+    /// Sets the key of the note the note events target. This is in the `0..=127` range.
+    #[inline]
+    pub const fn key(mut self, key: u16) -> Self {
+        self.pckn.key = Match::Specific(key);
+        self
+    }
+
+    /// Sets the specific ID of the note the note events target.
+    #[inline]
+    pub const fn note_id(mut self, note_id: u32) -> Self {
+        self.pckn.note_id = Match::Specific(note_id);
+        self
+    }
+
+    /// Sets the velocity of the note events, as a CLAP velocity in the `0.0..=1.0` range.
+    ///
+    /// The velocity is only used by [`note_on`](Self::note_on) and
+    /// [`note_off`](Self::note_off).
+    #[inline]
+    pub fn velocity(mut self, velocity: f64) -> Self {
+        self.velocity = velocity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the velocity of the note events from a MIDI 1.0 velocity in the `0..=127` range.
+    ///
+    /// See [`midi_velocity_to_clap`] for how the velocity is converted.
+    #[inline]
+    pub fn midi_velocity(mut self, velocity: u8) -> Self {
+        self.velocity = midi_velocity_to_clap(velocity);
+        self
+    }
+
+    /// Sets the flags of the note events.
+    #[inline]
+    pub const fn flags(mut self, flags: EventFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Creates a [`NoteOnEvent`] from the properties of this builder.
+    #[inline]
+    pub fn note_on(&self) -> NoteOnEvent {
+        NoteOnEvent::new(self.time, self.pckn, self.velocity).with_flags(self.flags)
+    }
+
+    /// Creates a [`NoteOffEvent`] from the properties of this builder.
+    #[inline]
+    pub fn note_off(&self) -> NoteOffEvent {
+        NoteOffEvent::new(self.time, self.pckn, self.velocity).with_flags(self.flags)
+    }
+
+    /// Creates a [`NoteChokeEvent`] from the properties of this builder.
+    ///
+    /// The velocity of this builder is ignored.
+    #[inline]
+    pub fn note_choke(&self) -> NoteChokeEvent {
+        NoteChokeEvent::new(self.time, self.pckn).with_flags(self.flags)
+    }
+
+    /// Creates a [`NoteEndEvent`] from the properties of this builder.
+    ///
+    /// The velocity of this builder is ignored.
+    #[inline]
+    pub fn note_end(&self) -> NoteEndEvent {
+        NoteEndEvent::new(self.time, self.pckn).with_flags(self.flags)
+    }
+}
+
+impl Default for NoteEventBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn velocities_round_trip() {
+        for velocity in 0..=127 {
+            assert_eq!(
+                clap_velocity_to_midi(midi_velocity_to_clap(velocity)),
+                velocity
+            );
+        }
+
+        assert_eq!(midi_velocity_to_clap(200), 1.0);
+        assert_eq!(clap_velocity_to_midi(-1.0), 0);
+        assert_eq!(clap_velocity_to_midi(f64::NAN), 0);
+    }
+
+    #[test]
+    fn builds_note_events() {
+        let builder = NoteEventBuilder::new().time(5).channel(2).key(64);
+        let note_on = builder.note_on();
+
+        assert_eq!(note_on.time(), 5);
+        assert_eq!(
+            note_on.pckn(),
+            Pckn::new(Match::All, 2u16, 64u16, Match::All)
+        );
+        assert_eq!(note_on.velocity(), 1.0);
+        assert_eq!(builder.note_end().pckn(), note_on.pckn());
+    }
+}
@@ -5,14 +5,20 @@
 
 mod batcher;
 mod buffer;
+mod grouped;
 mod implementation;
 mod input;
 mod merger;
 mod output;
+mod ring;
+mod validation;
 
 pub use batcher::*;
 pub use buffer::*;
+pub use grouped::*;
 pub use implementation::*;
 pub use input::*;
 pub use merger::*;
 pub use output::*;
+pub use ring::*;
+pub use validation::*;
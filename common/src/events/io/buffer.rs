@@ -181,6 +181,18 @@ impl EventBuffer {
         self.indexes.push(index as u32);
     }
 
+    /// Pushes a copy of the given event into the buffer, with its time set to the given `time`.
+    ///
+    /// The event is always added at the end of the buffer. This is useful to re-time events, e.g.
+    /// when splitting or merging processing blocks.
+    pub fn push_with_time<E: AsRef<UnknownEvent> + ?Sized>(&mut self, event: &E, time: u32) {
+        let index = self.append_header_data(event.as_ref());
+
+        // SAFETY: append_header_data just wrote a valid event header at this index.
+        unsafe { self.headers[index].assume_init_mut() }.0.time = time;
+        self.indexes.push(index as u32);
+    }
+
     /// Produces an [`InputEvents`] that wraps this buffer as an [`InputEventBuffer`] implementation.
     ///
     /// This helper method is strictly equivalent to using [`InputEvents::from_buffer`].
@@ -0,0 +1,151 @@
+use crate::events::io::{InputEvents, InputEventsIter};
+
+/// An iterator which groups input events that happen at the same sample time together.
+///
+/// See the [`InputEvents::grouped_by_time`] method documentation for more details and usage
+/// examples.
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EventTimeGroups<'a> {
+    events: &'a InputEvents<'a>,
+    events_len: u32,
+    next_event_index: u32,
+}
+
+impl<'a> EventTimeGroups<'a> {
+    pub(crate) fn new(events: &'a InputEvents<'a>) -> Self {
+        Self {
+            events,
+            events_len: events.len(),
+            next_event_index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for EventTimeGroups<'a> {
+    type Item = (u32, InputEventsIter<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Malformed events are skipped: they have no reliable time to be grouped with.
+        let (start, time) = loop {
+            let index = self.next_event_index;
+            if index >= self.events_len {
+                return None;
+            }
+
+            self.next_event_index += 1;
+            if let Some(event) = self.events.get(index) {
+                break (index, event.header().time());
+            }
+        };
+
+        // Malformed events within a group are skipped when iterating over it.
+        let mut end = start + 1;
+        while end < self.events_len {
+            match self.events.get(end) {
+                Some(event) if event.header().time() != time => break,
+                _ => end += 1,
+            }
+        }
+
+        self.next_event_index = end;
+
+        Some((time, InputEventsIter::new(self.events, start..end)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.events_len - self.next_event_index.min(self.events_len)) as usize;
+        (0, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_types::ParamGestureBeginEvent;
+    use crate::utils::ClapId;
+
+    const PARAM: ClapId = ClapId::new(0);
+
+    #[test]
+    pub fn works_with_empty_events() {
+        let events = InputEvents::empty();
+        assert!(events.grouped_by_time().next().is_none());
+    }
+
+    #[test]
+    pub fn groups_events_by_time() {
+        let buf = [
+            ParamGestureBeginEvent::new(0, PARAM),
+            ParamGestureBeginEvent::new(5, PARAM),
+            ParamGestureBeginEvent::new(5, PARAM),
+            ParamGestureBeginEvent::new(8, PARAM),
+        ];
+
+        let events = InputEvents::from_buffer(&buf);
+        let groups: Vec<_> = events
+            .grouped_by_time()
+            .map(|(time, events)| (time, events.count()))
+            .collect();
+
+        assert_eq!(groups, [(0, 1), (5, 2), (8, 1)]);
+
+        let (_, mut group) = events.grouped_by_time().nth(1).unwrap();
+        assert_eq!(&buf[1], group.next().unwrap().as_event().unwrap());
+        assert_eq!(&buf[2], group.next().unwrap().as_event().unwrap());
+        assert!(group.next().is_none());
+    }
+
+    #[test]
+    pub fn skips_malformed_events() {
+        use clap_sys::events::{clap_event_header, clap_input_events};
+
+        const fn header(size: usize, time: u32) -> clap_event_header {
+            clap_event_header {
+                size: size as u32,
+                time,
+                space_id: 42,
+                type_: 0,
+                flags: 0,
+            }
+        }
+
+        const VALID: usize = core::mem::size_of::<clap_event_header>();
+
+        static HEADERS: [clap_event_header; 6] = [
+            header(0, 0),
+            header(VALID, 2),
+            header(VALID, 2),
+            header(0, 2),
+            header(VALID, 2),
+            header(VALID, 5),
+        ];
+
+        extern "C" fn size(_: *const clap_input_events) -> u32 {
+            HEADERS.len() as u32
+        }
+
+        extern "C" fn get(_: *const clap_input_events, index: u32) -> *const clap_event_header {
+            match HEADERS.get(index as usize) {
+                Some(header) => header,
+                None => core::ptr::null(),
+            }
+        }
+
+        let raw = clap_input_events {
+            ctx: core::ptr::null_mut(),
+            size: Some(size),
+            get: Some(get),
+        };
+
+        // SAFETY: the list only returns valid or undersized event headers
+        let events = unsafe { InputEvents::from_raw(&raw) };
+        let groups: Vec<_> = events
+            .grouped_by_time()
+            .map(|(time, events)| (time, events.count()))
+            .collect();
+
+        assert_eq!(groups, [(2, 3), (5, 1)]);
+    }
+}
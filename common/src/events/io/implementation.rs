@@ -84,11 +84,12 @@ unsafe extern "C" fn try_push<O: OutputEventBuffer>(
     event: *const clap_event_header,
 ) -> bool {
     handle_panic(|| {
-        O::try_push(
-            &mut *((*list).ctx as *const _ as *mut O),
-            UnknownEvent::from_raw(event),
-        )
-        .is_ok()
+        // Malformed events are rejected instead of being pushed.
+        let Some(event) = UnknownEvent::from_raw_checked(event) else {
+            return false;
+        };
+
+        O::try_push(&mut *((*list).ctx as *const _ as *mut O), event).is_ok()
     })
     .unwrap_or(false)
 }
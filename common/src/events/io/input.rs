@@ -1,5 +1,5 @@
 use crate::events::io::implementation::{raw_input_events, InputEventBuffer};
-use crate::events::io::EventBatcher;
+use crate::events::io::{EventBatcher, EventTimeGroups};
 use crate::events::UnknownEvent;
 use clap_sys::events::clap_input_events;
 use std::fmt::{Debug, Formatter};
@@ -136,12 +136,10 @@ impl<'a> InputEvents<'a> {
     pub fn get(&self, index: u32) -> Option<&UnknownEvent> {
         // SAFETY: this function pointer is safely initialized by from_raw or from_buffer
         let event = unsafe { self.inner.get?(&self.inner, index) };
-        if event.is_null() {
-            return None;
-        };
 
-        // SAFETY: the returned event pointer is guaranteed to be valid by from_raw or from_buffer
-        unsafe { Some(UnknownEvent::from_raw(event)) }
+        // SAFETY: the returned event pointer is guaranteed to be valid by from_raw or from_buffer.
+        // Malformed headers (e.g. with a size too small to even contain a header) are skipped.
+        unsafe { UnknownEvent::from_raw_checked(event) }
     }
 
     /// Returns an iterator over all the events in this [`InputEvents`].
@@ -243,6 +241,50 @@ impl<'a> InputEvents<'a> {
     pub fn batch(&self) -> EventBatcher {
         EventBatcher::new(self)
     }
+
+    /// Returns an iterator that groups together all the events happening at the same sample
+    /// time.
+    ///
+    /// Each item is a `(time, events)` pair, where `time` is the sample time shared by all the
+    /// `events` in the group. Groups are yielded in order, and only times that have at least
+    /// one event produce a group.
+    ///
+    /// Malformed events are skipped.
+    ///
+    /// Unlike [`batch`](Self::batch), this doesn't produce the sample ranges between events,
+    /// which makes it a simpler fit for plugins that process their audio one frame at a time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::events::event_types::ParamGestureBeginEvent;
+    /// use clack_common::events::io::InputEvents;
+    /// use clack_common::utils::ClapId;
+    ///
+    /// let buf = [
+    ///     ParamGestureBeginEvent::new(0, ClapId::new(1)),
+    ///     ParamGestureBeginEvent::new(4, ClapId::new(1)),
+    ///     ParamGestureBeginEvent::new(4, ClapId::new(2)),
+    /// ];
+    /// let input_events = InputEvents::from_buffer(&buf);
+    ///
+    /// let mut groups = input_events.grouped_by_time().peekable();
+    /// let mut group_sizes = vec![];
+    ///
+    /// for frame in 0..8 {
+    ///     if let Some((_, events)) = groups.next_if(|(time, _)| *time == frame) {
+    ///         group_sizes.push((frame, events.count()));
+    ///     }
+    ///
+    ///     // (Process the frame...)
+    /// }
+    ///
+    /// assert_eq!(group_sizes, [(0, 1), (4, 2)]);
+    /// ```
+    #[inline]
+    pub fn grouped_by_time(&self) -> EventTimeGroups<'_> {
+        EventTimeGroups::new(self)
+    }
 }
 
 impl<'a> IntoIterator for &'a InputEvents<'a> {
@@ -301,6 +343,10 @@ impl Debug for InputEvents<'_> {
 }
 
 /// Immutable [`InputEvents`] iterator.
+///
+/// Malformed events are skipped. Because of this, the number of events this iterator
+/// yields may be smaller than the length of the range it iterates over, which is only an upper
+/// bound given by [`size_hint`](Iterator::size_hint).
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct InputEventsIter<'a> {
     list: &'a InputEvents<'a>,
@@ -328,26 +374,21 @@ impl<'a> Iterator for InputEventsIter<'a> {
     type Item = &'a UnknownEvent;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.range.next().and_then(|i| self.list.get(i))
+        let list = self.list;
+        self.range.by_ref().find_map(|i| list.get(i))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.range.size_hint()
-    }
-}
-
-impl ExactSizeIterator for InputEventsIter<'_> {
-    #[inline]
-    fn len(&self) -> usize {
-        self.range.len()
+        (0, Some(self.range.len()))
     }
 }
 
 impl DoubleEndedIterator for InputEventsIter<'_> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.range.next_back().and_then(|i| self.list.get(i))
+        let list = self.list;
+        self.range.by_ref().rev().find_map(|i| list.get(i))
     }
 }
 
@@ -357,4 +398,102 @@ mod test {
     use super::*;
 
     sa::assert_not_impl_any!(InputEvents<'static>: Send, Sync);
+
+    #[test]
+    fn malformed_events_are_skipped() {
+        use clap_sys::events::clap_event_header;
+
+        static HEADERS: [clap_event_header; 2] = [
+            clap_event_header {
+                size: 0,
+                time: 0,
+                space_id: 0,
+                type_: 0,
+                flags: 0,
+            },
+            clap_event_header {
+                size: core::mem::size_of::<clap_event_header>() as u32,
+                time: 1,
+                space_id: 42,
+                type_: 0,
+                flags: 0,
+            },
+        ];
+
+        extern "C" fn size(_: *const clap_input_events) -> u32 {
+            3
+        }
+
+        extern "C" fn get(_: *const clap_input_events, index: u32) -> *const clap_event_header {
+            match HEADERS.get(index as usize) {
+                Some(header) => header,
+                None => core::ptr::null(),
+            }
+        }
+
+        let raw = clap_input_events {
+            ctx: core::ptr::null_mut(),
+            size: Some(size),
+            get: Some(get),
+        };
+
+        // SAFETY: the list only returns valid, null or undersized event headers
+        let events = unsafe { InputEvents::from_raw(&raw) };
+
+        assert!(events.get(0).is_none());
+        assert_eq!(events.get(1).unwrap().header().time(), 1);
+        assert!(events.get(2).is_none());
+    }
+
+    #[test]
+    fn iterators_skip_malformed_events() {
+        use clap_sys::events::clap_event_header;
+
+        const fn header(size: usize, time: u32) -> clap_event_header {
+            clap_event_header {
+                size: size as u32,
+                time,
+                space_id: 42,
+                type_: 0,
+                flags: 0,
+            }
+        }
+
+        const VALID: usize = core::mem::size_of::<clap_event_header>();
+
+        static HEADERS: [clap_event_header; 4] = [
+            header(VALID, 0),
+            header(0, 1),
+            header(VALID, 2),
+            header(0, 3),
+        ];
+
+        extern "C" fn size(_: *const clap_input_events) -> u32 {
+            HEADERS.len() as u32
+        }
+
+        extern "C" fn get(_: *const clap_input_events, index: u32) -> *const clap_event_header {
+            match HEADERS.get(index as usize) {
+                Some(header) => header,
+                None => core::ptr::null(),
+            }
+        }
+
+        let raw = clap_input_events {
+            ctx: core::ptr::null_mut(),
+            size: Some(size),
+            get: Some(get),
+        };
+
+        // SAFETY: the list only returns valid or undersized event headers
+        let events = unsafe { InputEvents::from_raw(&raw) };
+
+        let times: Vec<_> = events.iter().map(|e| e.header().time()).collect();
+        assert_eq!(times, [0, 2]);
+
+        let times: Vec<_> = events.iter().rev().map(|e| e.header().time()).collect();
+        assert_eq!(times, [2, 0]);
+
+        assert_eq!(events.iter().size_hint(), (0, Some(4)));
+    }
 }
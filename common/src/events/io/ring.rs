@@ -0,0 +1,524 @@
+use crate::events::event_types::MidiSysExEvent;
+use crate::events::io::buffer::byte_index_to_value_index;
+use crate::events::UnknownEvent;
+use core::cell::UnsafeCell;
+use core::mem::{size_of, MaybeUninit};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+/// A single-producer, single-consumer ring buffer for moving [`UnknownEvent`]s across threads.
+///
+/// Like [`EventBuffer`](super::EventBuffer), this ring buffer can store heterogeneous events of
+/// any size. Its capacity is allocated once at creation, and both pushing and popping events are
+/// wait-free and never allocate, which makes it suitable to send events to and from the audio
+/// thread.
+///
+/// Typical uses include hosts sending parameter changes from the GUI to the audio thread, or
+/// plugins sending meter values or notifications from the audio thread to the GUI.
+///
+/// An [`EventRingBuffer`] is created using [`EventRingBuffer::with_capacity`], which returns both an
+/// [`EventProducer`] and an [`EventConsumer`] handle, that can be sent to different threads.
+///
+/// # MIDI SysEx events
+///
+/// [`MidiSysExEvent`]s only point to their payload, which is usually only valid for the duration
+/// of a plugin or host call. These events have to be pushed using
+/// [`EventProducer::push_sysex`], which copies their payload into the ring buffer alongside them.
+/// Readers may then access the payload until the event is popped.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::ParamValueEvent;
+/// use clack_common::events::io::{EventBuffer, EventRingBuffer};
+/// use clack_common::events::Pckn;
+/// use clack_common::utils::{ClapId, Cookie};
+///
+/// let (mut producer, mut consumer) = EventRingBuffer::with_capacity(4096);
+///
+/// // On the GUI thread:
+/// let event = ParamValueEvent::new(0, ClapId::new(1), Pckn::match_all(), 0.5, Cookie::empty());
+/// producer.push(&event).unwrap();
+///
+/// // On the audio thread:
+/// let mut events = EventBuffer::with_capacity(16);
+/// consumer.pop_all(|event| events.push(event));
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].as_event(), Some(&event));
+/// ```
+pub struct EventRingBuffer {
+    words: Box<[UnsafeCell<MaybeUninit<u64>>]>,
+    /// The total number of words ever written by the producer.
+    head: AtomicUsize,
+    /// The total number of words ever read by the consumer.
+    tail: AtomicUsize,
+}
+
+// SAFETY: the words buffer is only ever written to by the single producer in the free section,
+// and only ever read by the single consumer in the written section. Both sections are
+// synchronized using the head and tail atomics.
+unsafe impl Sync for EventRingBuffer {}
+
+/// Header word value marking the rest of the buffer as padding, as a record didn't fit in it.
+const PADDING: u64 = 0;
+
+impl EventRingBuffer {
+    /// Creates a new ring buffer able to hold `capacity` bytes of events.
+    ///
+    /// The capacity is rounded up to the next multiple of 8 bytes. Each event also uses 8 bytes of
+    /// bookkeeping data, plus the size of its payload for [`MidiSysExEvent`]s.
+    ///
+    /// This returns the producer and consumer handles of the new ring buffer.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method always allocates and is not realtime-safe.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    pub fn with_capacity(capacity: usize) -> (EventProducer, EventConsumer) {
+        assert!(capacity > 0, "Event ring buffer capacity must not be zero");
+
+        let words = (0..byte_index_to_value_index::<u64>(capacity))
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+
+        let ring = Arc::new(Self {
+            words,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        });
+
+        (
+            EventProducer {
+                ring: ring.clone(),
+                head: 0,
+            },
+            EventConsumer { ring, tail: 0 },
+        )
+    }
+
+    /// The capacity of the buffer, in words.
+    #[inline]
+    fn word_capacity(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns a pointer to the word at the given index.
+    ///
+    /// The pointer is valid for reads and writes of all the words after it, until the end of the
+    /// buffer.
+    #[inline]
+    fn word_ptr(&self, index: usize) -> *mut u64 {
+        debug_assert!(index < self.word_capacity());
+        // SAFETY: the index is in bounds. The pointer is derived from the whole slice, and
+        // UnsafeCell allows mutation through it.
+        unsafe { (self.words.as_ptr() as *mut u64).add(index) }
+    }
+}
+
+impl Debug for EventRingBuffer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventRingBuffer")
+            .field("capacity", &(self.word_capacity() * size_of::<u64>()))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The producer handle of an [`EventRingBuffer`], which pushes events into it.
+///
+/// See the [`EventRingBuffer`] documentation for more information.
+#[derive(Debug)]
+pub struct EventProducer {
+    ring: Arc<EventRingBuffer>,
+    head: usize,
+}
+
+impl EventProducer {
+    /// Pushes a copy of the given event into the ring buffer.
+    ///
+    /// # Errors
+    ///
+    /// This returns an [`EventRingPushError`] if there isn't enough free space in the buffer, or if
+    /// the given event is a [`MidiSysExEvent`], which must be pushed using
+    /// [`push_sysex`](Self::push_sysex) instead.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method is realtime-safe.
+    pub fn push<E: AsRef<UnknownEvent> + ?Sized>(
+        &mut self,
+        event: &E,
+    ) -> Result<(), EventRingPushError> {
+        let event = event.as_ref();
+        if event.as_event::<MidiSysExEvent>().is_some() {
+            return Err(EventRingPushError::MissingSysExPayload);
+        }
+
+        let bytes = event.as_bytes();
+        let event_words = byte_index_to_value_index::<u64>(bytes.len());
+        let record_words = 1 + event_words;
+        let position = self.reserve(record_words)?;
+
+        // SAFETY: reserve() ensured the record fits between the given position and the end of
+        // the buffer, and that the consumer isn't reading that section.
+        unsafe {
+            let event_ptr = self.ring.word_ptr(position + 1) as *mut u8;
+            event_ptr.copy_from_nonoverlapping(bytes.as_ptr(), bytes.len());
+        }
+
+        self.commit(position, record_words);
+        Ok(())
+    }
+
+    /// Pushes a copy of the given [`MidiSysExEvent`] into the ring buffer, alongside a copy of its
+    /// payload `data`.
+    ///
+    /// The pushed event's payload will point to the copied data. Consumers may only access it
+    /// until the event is popped.
+    ///
+    /// # Errors
+    ///
+    /// This returns an [`EventRingPushError`] if there isn't enough free space in the buffer.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method is realtime-safe.
+    pub fn push_sysex(
+        &mut self,
+        event: &MidiSysExEvent,
+        data: &[u8],
+    ) -> Result<(), EventRingPushError> {
+        let event_words = byte_index_to_value_index::<u64>(size_of::<MidiSysExEvent>());
+        let data_words = byte_index_to_value_index::<u64>(data.len());
+        let record_words = 1 + event_words + data_words;
+        let position = self.reserve(record_words)?;
+
+        let mut event = *event;
+
+        // SAFETY: reserve() ensured the record fits between the given position and the end of
+        // the buffer, and that the consumer isn't reading that section.
+        unsafe {
+            let data_ptr = self.ring.word_ptr(position + 1).add(event_words) as *mut u8;
+            data_ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+
+            event.as_raw_mut().buffer = data_ptr;
+            event.as_raw_mut().size = data.len() as u32;
+
+            let event_ptr = self.ring.word_ptr(position + 1) as *mut MidiSysExEvent;
+            event_ptr.write(event);
+        }
+
+        self.commit(position, record_words);
+        Ok(())
+    }
+
+    /// Returns the total capacity of the ring buffer, in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.ring.word_capacity() * size_of::<u64>()
+    }
+
+    /// Returns `true` if the [`EventConsumer`] of this ring buffer has been dropped.
+    #[inline]
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) < 2
+    }
+
+    /// Finds a contiguous section of free words for a record, and returns its starting position.
+    ///
+    /// If the record doesn't fit before the end of the buffer, the remaining words are marked as
+    /// padding, and the record starts back at the beginning of the buffer.
+    fn reserve(&mut self, record_words: usize) -> Result<usize, EventRingPushError> {
+        let capacity = self.ring.word_capacity();
+        if record_words > capacity {
+            return Err(EventRingPushError::TooLarge);
+        }
+
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        let free_words = capacity - self.head.wrapping_sub(tail);
+        let position = self.head % capacity;
+        let words_to_end = capacity - position;
+
+        if record_words <= words_to_end {
+            return if record_words <= free_words {
+                Ok(position)
+            } else {
+                Err(EventRingPushError::Full)
+            };
+        }
+
+        if words_to_end + record_words > free_words {
+            return Err(EventRingPushError::Full);
+        }
+
+        // SAFETY: the position is in bounds, and we checked the consumer isn't reading it.
+        unsafe { self.ring.word_ptr(position).write(PADDING) };
+        self.head = self.head.wrapping_add(words_to_end);
+
+        Ok(0)
+    }
+
+    /// Writes the header of a record, and makes it available to the consumer.
+    fn commit(&mut self, position: usize, record_words: usize) {
+        // SAFETY: the position was given by reserve(), and is reserved for this record.
+        unsafe { self.ring.word_ptr(position).write(record_words as u64) };
+
+        self.head = self.head.wrapping_add(record_words);
+        self.ring.head.store(self.head, Ordering::Release);
+    }
+}
+
+/// The consumer handle of an [`EventRingBuffer`], which pops events from it.
+///
+/// See the [`EventRingBuffer`] documentation for more information.
+#[derive(Debug)]
+pub struct EventConsumer {
+    ring: Arc<EventRingBuffer>,
+    tail: usize,
+}
+
+impl EventConsumer {
+    /// Pops the oldest event from the ring buffer, and passes it to the given `reader` closure.
+    ///
+    /// The event is only borrowed during the closure's execution. If it is a [`MidiSysExEvent`],
+    /// its payload is also only valid during that time.
+    ///
+    /// This returns the result of the closure, or `None` if the ring buffer was empty.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method is realtime-safe, as long as `reader` is.
+    pub fn pop<R>(&mut self, reader: impl FnOnce(&UnknownEvent) -> R) -> Option<R> {
+        let head = self.ring.head.load(Ordering::Acquire);
+        let capacity = self.ring.word_capacity();
+
+        let result = loop {
+            if self.tail == head {
+                break None;
+            }
+
+            let position = self.tail % capacity;
+
+            // SAFETY: the position is in bounds, and this header word was written by the producer
+            // before it published the new head.
+            let record_words = unsafe { self.ring.word_ptr(position).read() } as usize;
+
+            if record_words as u64 == PADDING {
+                self.tail = self.tail.wrapping_add(capacity - position);
+                continue;
+            }
+
+            // SAFETY: the producer wrote a whole valid event right after the header word, and
+            // won't overwrite it until the tail is moved past it. Words are 8-byte aligned.
+            let event = unsafe {
+                let event_ptr = self.ring.word_ptr(position + 1) as *const u8;
+                let size = (*(event_ptr as *const clap_sys::events::clap_event_header)).size;
+                UnknownEvent::from_bytes_unchecked(core::slice::from_raw_parts(
+                    event_ptr,
+                    size as usize,
+                ))
+            };
+
+            let result = reader(event);
+            self.tail = self.tail.wrapping_add(record_words);
+            break Some(result);
+        };
+
+        self.ring.tail.store(self.tail, Ordering::Release);
+        result
+    }
+
+    /// Pops all the events currently in the ring buffer, passing each of them to the given
+    /// `reader` closure, in order.
+    ///
+    /// This returns the number of events that were popped.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method is realtime-safe, as long as `reader` is.
+    pub fn pop_all(&mut self, mut reader: impl FnMut(&UnknownEvent)) -> usize {
+        let mut count = 0;
+        while self.pop(&mut reader).is_some() {
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Returns `true` if there are no events left to pop in the ring buffer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ring.head.load(Ordering::Acquire) == self.tail
+    }
+
+    /// Returns the total capacity of the ring buffer, in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.ring.word_capacity() * size_of::<u64>()
+    }
+
+    /// Returns `true` if the [`EventProducer`] of this ring buffer has been dropped.
+    #[inline]
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) < 2
+    }
+}
+
+/// Errors that can occur when pushing an event to an [`EventRingBuffer`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventRingPushError {
+    /// There isn't enough free space left in the buffer to hold the event.
+    ///
+    /// Pushing may succeed again once the consumer popped some events.
+    Full,
+    /// The event is larger than the whole capacity of the buffer.
+    TooLarge,
+    /// The event is a [`MidiSysExEvent`], which must be pushed alongside its payload using
+    /// [`EventProducer::push_sysex`].
+    MissingSysExPayload,
+}
+
+impl Display for EventRingPushError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventRingPushError::Full => f.write_str("Event ring buffer is full"),
+            EventRingPushError::TooLarge => {
+                f.write_str("Event is too large to fit in the event ring buffer")
+            }
+            EventRingPushError::MissingSysExPayload => {
+                f.write_str("MIDI SysEx events must be pushed alongside their payload")
+            }
+        }
+    }
+}
+
+impl Error for EventRingPushError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_types::{NoteOnEvent, ParamGestureBeginEvent, ParamValueEvent};
+    use crate::events::{Event, Pckn};
+    use crate::utils::{ClapId, Cookie};
+
+    fn param_value(time: u32) -> ParamValueEvent {
+        ParamValueEvent::new(
+            time,
+            ClapId::new(1),
+            Pckn::match_all(),
+            0.5,
+            Cookie::empty(),
+        )
+    }
+
+    #[test]
+    fn pushes_and_pops_in_order() {
+        let (mut producer, mut consumer) = EventRingBuffer::with_capacity(1024);
+        assert_eq!(producer.capacity(), 1024);
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.pop(|_| ()), None);
+
+        let note = NoteOnEvent::new(0, Pckn::match_all(), 1.0);
+        let gesture = ParamGestureBeginEvent::new(5, ClapId::new(2));
+        producer.push(&note).unwrap();
+        producer.push(&gesture).unwrap();
+
+        assert_eq!(consumer.pop(|e| e.as_event().copied()), Some(Some(note)));
+        assert_eq!(consumer.pop(|e| e.as_event().copied()), Some(Some(gesture)));
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn wraps_around_with_events_of_various_sizes() {
+        let value = param_value(0);
+        let record_size = 8 + size_of::<ParamValueEvent>();
+
+        let (mut producer, mut consumer) = EventRingBuffer::with_capacity(record_size * 3 + 16);
+
+        for i in 0..100 {
+            let gesture = ParamGestureBeginEvent::new(i, ClapId::new(i));
+            producer.push(&value).unwrap();
+            producer.push(&gesture).unwrap();
+            producer.push(&value).unwrap();
+
+            assert_eq!(consumer.pop(|e| e.as_event().copied()), Some(Some(value)));
+            assert_eq!(consumer.pop(|e| e.as_event().copied()), Some(Some(gesture)));
+            assert_eq!(consumer.pop_all(|_| ()), 1);
+        }
+    }
+
+    #[test]
+    fn reports_full_and_too_large() {
+        let gesture = ParamGestureBeginEvent::new(0, ClapId::new(0));
+        // Room for exactly one gesture event, and its header word.
+        let (mut producer, mut consumer) = EventRingBuffer::with_capacity(40);
+
+        assert_eq!(
+            producer.push(&param_value(0)),
+            Err(EventRingPushError::TooLarge)
+        );
+
+        producer.push(&gesture).unwrap();
+        assert_eq!(producer.push(&gesture), Err(EventRingPushError::Full));
+
+        assert_eq!(consumer.pop_all(|_| ()), 1);
+        producer.push(&gesture).unwrap();
+    }
+
+    #[test]
+    fn copies_sysex_payloads() {
+        let (mut producer, mut consumer) = EventRingBuffer::with_capacity(256);
+
+        let payload = vec![0xF0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0xF7];
+        let event = MidiSysExEvent::new(3, 1, &payload);
+
+        assert_eq!(
+            producer.push(&event),
+            Err(EventRingPushError::MissingSysExPayload)
+        );
+        producer.push_sysex(&event, &payload).unwrap();
+        drop(payload);
+
+        let popped = consumer
+            .pop(|e| {
+                let event = e.as_event::<MidiSysExEvent>().unwrap();
+                assert_eq!(event.header().time(), 3);
+                assert_eq!(event.port_index(), 1);
+                // SAFETY: the payload lives in the ring buffer until the event is popped.
+                unsafe { event.data() }.to_vec()
+            })
+            .unwrap();
+
+        assert_eq!(popped, [0xF0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0xF7]);
+    }
+
+    #[test]
+    fn works_across_threads() {
+        let (mut producer, mut consumer) = EventRingBuffer::with_capacity(128);
+
+        let thread = std::thread::spawn(move || {
+            for i in 0..1000 {
+                let event = ParamGestureBeginEvent::new(i, ClapId::new(i));
+                while producer.push(&event).is_err() {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut received = 0;
+        while received < 1000 {
+            consumer.pop_all(|e| {
+                assert_eq!(e.header().time(), received);
+                received += 1;
+            });
+        }
+
+        thread.join().unwrap();
+        assert!(consumer.is_abandoned());
+    }
+}
@@ -0,0 +1,503 @@
+use crate::events::event_types::*;
+use crate::events::io::{InputEvents, OutputEventBuffer, OutputEvents, TryPushError};
+use crate::events::spaces::EventSpaceId;
+use crate::events::{Event, Pckn, UnknownEvent};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors that can be found when validating a list of events.
+///
+/// See [`InputEvents::validate`] and [`OutputEventValidator`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventValidationError {
+    /// The event at the given index is missing, or malformed (e.g. too small to even hold an
+    /// event header).
+    MissingEvent {
+        /// The index of the event in the list.
+        index: u32,
+    },
+    /// The event at the given index has an invalid event space ID.
+    InvalidSpace {
+        /// The index of the event in the list.
+        index: u32,
+    },
+    /// The event at the given index belongs to the core event space, but either its type is
+    /// unknown, or its size doesn't match its type.
+    InvalidCoreEvent {
+        /// The index of the event in the list.
+        index: u32,
+        /// The raw type ID of the event.
+        type_id: u16,
+        /// The size of the event, as declared in its header.
+        size: u32,
+    },
+    /// The event at the given index happens before the event preceding it.
+    Unordered {
+        /// The index of the event in the list.
+        index: u32,
+        /// The timestamp of the event.
+        time: u32,
+        /// The timestamp of the preceding event.
+        previous_time: u32,
+    },
+    /// The event at the given index happens after the end of the processed block.
+    OutOfBounds {
+        /// The index of the event in the list.
+        index: u32,
+        /// The timestamp of the event.
+        time: u32,
+        /// The number of frames in the processed block.
+        frames_count: u32,
+    },
+    /// The event at the given index is a [`NoteEndEvent`], but it doesn't match any note that
+    /// was started by the host.
+    UnknownNote {
+        /// The index of the event in the list.
+        index: u32,
+        /// The note the event refers to.
+        pckn: Pckn,
+    },
+}
+
+impl Display for EventValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventValidationError::MissingEvent { index } => {
+                write!(f, "Event #{index} is missing or malformed")
+            }
+            EventValidationError::InvalidSpace { index } => {
+                write!(f, "Event #{index} has an invalid event space ID")
+            }
+            EventValidationError::InvalidCoreEvent {
+                index,
+                type_id,
+                size,
+            } => write!(
+                f,
+                "Event #{index} is not a valid core event (type ID: {type_id}, size: {size} bytes)"
+            ),
+            EventValidationError::Unordered {
+                index,
+                time,
+                previous_time,
+            } => write!(
+                f,
+                "Event #{index} (at sample {time}) happens before the previous event (at sample {previous_time})"
+            ),
+            EventValidationError::OutOfBounds {
+                index,
+                time,
+                frames_count,
+            } => write!(
+                f,
+                "Event #{index} (at sample {time}) is outside of the processed block ({frames_count} frames)"
+            ),
+            EventValidationError::UnknownNote { index, pckn } => write!(
+                f,
+                "Event #{index} ends a note that was never started by the host ({pckn:?})"
+            ),
+        }
+    }
+}
+
+impl Error for EventValidationError {}
+
+impl InputEvents<'_> {
+    /// Checks that this event list is well-formed, for a block of the given number of frames.
+    ///
+    /// This checks that:
+    ///
+    /// * All events in `0..len` can be retrieved, and are not malformed;
+    /// * All events have a valid event space ID;
+    /// * All events of the core event space have a known type, and the size matching that type;
+    /// * Events are ordered by time;
+    /// * Events are within the block, i.e. their timestamp is lower than `frames_count`. Events
+    ///   given during a `flush` (i.e. when `frames_count` is `0`) are allowed to have any
+    ///   timestamp.
+    ///
+    /// Hosts can use this to catch bugs before they reach plugins, where they would result in
+    /// undefined behavior. This has to go through all the events in the list, and is therefore
+    /// mostly useful for testing and debugging.
+    ///
+    /// # Errors
+    ///
+    /// This returns the first [`EventValidationError`] that was found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::events::event_types::NoteOnEvent;
+    /// use clack_common::events::io::{EventValidationError, InputEvents};
+    /// use clack_common::events::Pckn;
+    ///
+    /// let events = [
+    ///     NoteOnEvent::new(5, Pckn::match_all(), 1.0),
+    ///     NoteOnEvent::new(2, Pckn::match_all(), 1.0),
+    /// ];
+    /// let input_events = InputEvents::from_buffer(&events);
+    ///
+    /// assert_eq!(
+    ///     input_events.validate(8),
+    ///     Err(EventValidationError::Unordered { index: 1, time: 2, previous_time: 5 })
+    /// );
+    /// ```
+    pub fn validate(&self, frames_count: u32) -> Result<(), EventValidationError> {
+        let mut previous_time = 0;
+
+        for index in 0..self.len() {
+            let event = self
+                .get(index)
+                .ok_or(EventValidationError::MissingEvent { index })?;
+
+            validate_event(event, index)?;
+
+            let time = event.header().time();
+            if time < previous_time {
+                return Err(EventValidationError::Unordered {
+                    index,
+                    time,
+                    previous_time,
+                });
+            }
+
+            if frames_count > 0 && time >= frames_count {
+                return Err(EventValidationError::OutOfBounds {
+                    index,
+                    time,
+                    frames_count,
+                });
+            }
+
+            previous_time = time;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the space and size of a single event.
+pub(crate) fn validate_event(event: &UnknownEvent, index: u32) -> Result<(), EventValidationError> {
+    let header = event.header();
+    let space_id = header
+        .space_id()
+        .ok_or(EventValidationError::InvalidSpace { index })?;
+
+    if space_id.id() != EventSpaceId::core().id() {
+        return Ok(());
+    }
+
+    let type_id = header.type_id();
+    let size = header.size();
+
+    if core_event_size(type_id) != Some(size as usize) {
+        return Err(EventValidationError::InvalidCoreEvent {
+            index,
+            type_id,
+            size,
+        });
+    }
+
+    Ok(())
+}
+
+fn core_event_size(type_id: u16) -> Option<usize> {
+    use core::mem::size_of;
+
+    let size = match type_id {
+        NoteOnEvent::TYPE_ID => size_of::<NoteOnEvent>(),
+        NoteOffEvent::TYPE_ID => size_of::<NoteOffEvent>(),
+        NoteChokeEvent::TYPE_ID => size_of::<NoteChokeEvent>(),
+        NoteEndEvent::TYPE_ID => size_of::<NoteEndEvent>(),
+        NoteExpressionEvent::TYPE_ID => size_of::<NoteExpressionEvent>(),
+        ParamValueEvent::TYPE_ID => size_of::<ParamValueEvent>(),
+        ParamModEvent::TYPE_ID => size_of::<ParamModEvent>(),
+        ParamGestureBeginEvent::TYPE_ID => size_of::<ParamGestureBeginEvent>(),
+        ParamGestureEndEvent::TYPE_ID => size_of::<ParamGestureEndEvent>(),
+        TransportEvent::TYPE_ID => size_of::<TransportEvent>(),
+        MidiEvent::TYPE_ID => size_of::<MidiEvent>(),
+        Midi2Event::TYPE_ID => size_of::<Midi2Event>(),
+        MidiSysExEvent::TYPE_ID => size_of::<MidiSysExEvent>(),
+        _ => return None,
+    };
+
+    Some(size)
+}
+
+/// Tracks the notes a plugin received, in order to validate the events it outputs across many
+/// processing blocks.
+///
+/// Plugins can use this to check the events they push to their [`OutputEvents`] during
+/// development: see [`validate_block`](Self::validate_block). This checks that:
+///
+/// * Output events are well-formed, and have a valid event space ID;
+/// * Output events are ordered by time, and are within the processed block;
+/// * [`NoteEndEvent`]s only reference notes that were previously started by the host, using a
+///   [`NoteOnEvent`].
+///
+/// In order to not allocate on the audio thread, only up to
+/// [`MAX_TRACKED_NOTES`](Self::MAX_TRACKED_NOTES) active notes are tracked. If more notes are
+/// active at once, [`NoteEndEvent`]s are not checked until all tracked notes have ended.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::{NoteEndEvent, NoteOnEvent};
+/// use clack_common::events::io::*;
+/// use clack_common::events::Pckn;
+///
+/// let mut validator = OutputEventValidator::new();
+///
+/// let note = Pckn::new(0u16, 0u16, 60u16, 1u32);
+/// let input = [NoteOnEvent::new(0, note, 1.0)];
+/// let mut output_buffer = EventBuffer::new();
+/// let mut output = OutputEvents::from_buffer(&mut output_buffer);
+///
+/// let mut validated = validator.validate_block(&InputEvents::from_buffer(&input), &mut output, 4);
+///
+/// // The plugin only gets the validated output events.
+/// let mut plugin_output = OutputEvents::from_buffer(&mut validated);
+/// plugin_output.try_push(NoteEndEvent::new(2, note)).unwrap();
+/// assert!(plugin_output.try_push(NoteEndEvent::new(3, note)).is_err());
+///
+/// assert_eq!(
+///     validated.error(),
+///     Some(EventValidationError::UnknownNote { index: 1, pckn: note })
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct OutputEventValidator {
+    active_notes: Vec<Pckn>,
+    overflowed: bool,
+}
+
+impl OutputEventValidator {
+    /// The maximum number of active notes this validator keeps track of.
+    pub const MAX_TRACKED_NOTES: usize = 256;
+
+    /// Creates a new validator, with no active notes.
+    pub fn new() -> Self {
+        Self {
+            active_notes: Vec::with_capacity(Self::MAX_TRACKED_NOTES),
+            overflowed: false,
+        }
+    }
+
+    /// Forgets about all active notes, e.g. when the plugin is reset or deactivated.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.active_notes.clear();
+        self.overflowed = false;
+    }
+
+    /// Returns the number of notes currently being tracked.
+    #[inline]
+    pub fn active_notes_count(&self) -> usize {
+        self.active_notes.len()
+    }
+
+    /// Starts validating the output events of a new processing block of `frames_count` frames.
+    ///
+    /// This first records the notes started by the given `input_events`. It then returns a
+    /// [`ValidatedOutputEvents`] buffer wrapping the given `output_events`, which validates
+    /// every event before forwarding it. The plugin must then push its output events to that
+    /// buffer instead (using [`OutputEvents::from_buffer`]).
+    ///
+    /// If `frames_count` is `0` (i.e. for a `flush`), event timestamps are not bounds-checked.
+    pub fn validate_block<'a, 'o>(
+        &'a mut self,
+        input_events: &InputEvents,
+        output_events: &'a mut OutputEvents<'o>,
+        frames_count: u32,
+    ) -> ValidatedOutputEvents<'a, 'o> {
+        for event in input_events {
+            if let Some(note_on) = event.as_event::<NoteOnEvent>() {
+                self.note_started(note_on.pckn());
+            }
+        }
+
+        ValidatedOutputEvents {
+            validator: self,
+            inner: output_events,
+            frames_count,
+            previous_time: 0,
+            index: 0,
+            error: None,
+        }
+    }
+
+    fn note_started(&mut self, pckn: Pckn) {
+        if self.active_notes.len() < self.active_notes.capacity() {
+            self.active_notes.push(pckn);
+        } else {
+            self.overflowed = true;
+        }
+    }
+
+    fn note_ended(&mut self, pckn: Pckn) -> bool {
+        // Each NoteEndEvent only ends a single voice, even if multiple were started for the
+        // same note.
+        let position = self.active_notes.iter().position(|note| pckn.matches(note));
+
+        let found = match position {
+            Some(index) => {
+                self.active_notes.swap_remove(index);
+                true
+            }
+            None => false,
+        };
+
+        if self.active_notes.is_empty() {
+            self.overflowed = false;
+        }
+
+        found || self.overflowed
+    }
+}
+
+impl Default for OutputEventValidator {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An output event buffer which validates events before forwarding them to another
+/// [`OutputEvents`] list.
+///
+/// This is created by [`OutputEventValidator::validate_block`]. Invalid events are not
+/// forwarded: pushing them returns an error instead, and the first one is available through
+/// [`error`](Self::error).
+pub struct ValidatedOutputEvents<'a, 'o> {
+    validator: &'a mut OutputEventValidator,
+    inner: &'a mut OutputEvents<'o>,
+    frames_count: u32,
+    previous_time: u32,
+    index: u32,
+    error: Option<EventValidationError>,
+}
+
+impl ValidatedOutputEvents<'_, '_> {
+    /// Returns the first validation error that occurred, if any.
+    #[inline]
+    pub fn error(&self) -> Option<EventValidationError> {
+        self.error
+    }
+
+    fn validate(&mut self, event: &UnknownEvent) -> Result<(), EventValidationError> {
+        let index = self.index;
+        validate_event(event, index)?;
+
+        let time = event.header().time();
+        if time < self.previous_time {
+            return Err(EventValidationError::Unordered {
+                index,
+                time,
+                previous_time: self.previous_time,
+            });
+        }
+
+        if self.frames_count > 0 && time >= self.frames_count {
+            return Err(EventValidationError::OutOfBounds {
+                index,
+                time,
+                frames_count: self.frames_count,
+            });
+        }
+
+        if let Some(note_end) = event.as_event::<NoteEndEvent>() {
+            let pckn = note_end.pckn();
+            if !self.validator.note_ended(pckn) {
+                return Err(EventValidationError::UnknownNote { index, pckn });
+            }
+        }
+
+        self.previous_time = time;
+        Ok(())
+    }
+}
+
+impl OutputEventBuffer for ValidatedOutputEvents<'_, '_> {
+    fn try_push(&mut self, event: &UnknownEvent) -> Result<(), TryPushError> {
+        let result = self.validate(event);
+        self.index += 1;
+
+        if let Err(e) = result {
+            self.error.get_or_insert(e);
+            return Err(TryPushError::new());
+        }
+
+        self.inner.try_push(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::io::EventBuffer;
+
+    #[test]
+    fn validates_event_times() {
+        let mut buffer = EventBuffer::new();
+        buffer.push(&NoteOnEvent::new(0, Pckn::match_all(), 1.0));
+        buffer.push(&NoteOffEvent::new(3, Pckn::match_all(), 1.0));
+
+        let events = InputEvents::from_buffer(&buffer);
+        assert_eq!(events.validate(4), Ok(()));
+        assert_eq!(events.validate(0), Ok(()));
+        assert_eq!(
+            events.validate(2),
+            Err(EventValidationError::OutOfBounds {
+                index: 1,
+                time: 3,
+                frames_count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn note_end_events_only_end_a_single_voice() {
+        let note = Pckn::new(0u16, 0u16, 60u16, 1u32);
+        let input = [
+            NoteOnEvent::new(0, note, 1.0),
+            NoteOnEvent::new(1, note, 1.0),
+        ];
+
+        let mut validator = OutputEventValidator::new();
+        let mut output_buffer = EventBuffer::new();
+        let mut output = OutputEvents::from_buffer(&mut output_buffer);
+        let mut validated =
+            validator.validate_block(&InputEvents::from_buffer(&input), &mut output, 4);
+
+        let mut plugin_output = OutputEvents::from_buffer(&mut validated);
+        plugin_output.try_push(NoteEndEvent::new(2, note)).unwrap();
+        plugin_output.try_push(NoteEndEvent::new(3, note)).unwrap();
+        assert!(plugin_output.try_push(NoteEndEvent::new(3, note)).is_err());
+
+        assert_eq!(
+            validated.error(),
+            Some(EventValidationError::UnknownNote {
+                index: 2,
+                pckn: note
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_core_events() {
+        // A note event, claiming to be a parameter change.
+        let mut event = NoteOnEvent::new(0, Pckn::match_all(), 1.0);
+        event.header_mut().as_raw_mut().type_ = ParamValueEvent::TYPE_ID;
+
+        let buffer = [event];
+        let events = InputEvents::from_buffer(&buffer);
+
+        assert_eq!(
+            events.validate(4),
+            Err(EventValidationError::InvalidCoreEvent {
+                index: 0,
+                type_id: ParamValueEvent::TYPE_ID,
+                size: core::mem::size_of::<NoteOnEvent>() as u32,
+            })
+        );
+    }
+}
@@ -3,6 +3,129 @@ use crate::events::{Event, EventSpace, UnknownEvent};
 use std::ffi::CStr;
 use std::fmt::{Debug, Formatter};
 
+/// All the event types of the core event space, i.e. all standard CLAP event types.
+///
+/// See [`CoreEventSpace`] for an event space that also holds a reference to the event itself.
+///
+/// This can be retrieved from an [`UnknownEvent`] using [`UnknownEvent::core_type`], which allows
+/// matching on the type of an event without having to compare raw event type IDs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CoreEventType {
+    /// See [`NoteOnEvent`].
+    NoteOn,
+    /// See [`NoteOffEvent`].
+    NoteOff,
+    /// See [`NoteChokeEvent`].
+    NoteChoke,
+    /// See [`NoteEndEvent`].
+    NoteEnd,
+    /// See [`NoteExpressionEvent`].
+    NoteExpression,
+    /// See [`ParamValueEvent`].
+    ParamValue,
+    /// See [`ParamModEvent`].
+    ParamMod,
+    /// See [`ParamGestureBeginEvent`].
+    ParamGestureBegin,
+    /// See [`ParamGestureEndEvent`].
+    ParamGestureEnd,
+    /// See [`TransportEvent`].
+    Transport,
+    /// See [`MidiEvent`].
+    Midi,
+    /// See [`Midi2Event`].
+    Midi2,
+    /// See [`MidiSysExEvent`].
+    MidiSysEx,
+}
+
+impl CoreEventType {
+    /// All the core event types.
+    pub const ALL: [CoreEventType; 13] = [
+        Self::NoteOn,
+        Self::NoteOff,
+        Self::NoteChoke,
+        Self::NoteEnd,
+        Self::NoteExpression,
+        Self::ParamValue,
+        Self::ParamMod,
+        Self::ParamGestureBegin,
+        Self::ParamGestureEnd,
+        Self::Transport,
+        Self::Midi,
+        Self::Midi2,
+        Self::MidiSysEx,
+    ];
+
+    /// Returns the core event type matching the given raw event type ID, or [`None`] if it
+    /// doesn't match any of the standard event types.
+    #[inline]
+    pub const fn from_type_id(type_id: u16) -> Option<Self> {
+        match type_id {
+            NoteOnEvent::TYPE_ID => Some(Self::NoteOn),
+            NoteOffEvent::TYPE_ID => Some(Self::NoteOff),
+            NoteChokeEvent::TYPE_ID => Some(Self::NoteChoke),
+            NoteEndEvent::TYPE_ID => Some(Self::NoteEnd),
+            NoteExpressionEvent::TYPE_ID => Some(Self::NoteExpression),
+            ParamValueEvent::TYPE_ID => Some(Self::ParamValue),
+            ParamModEvent::TYPE_ID => Some(Self::ParamMod),
+            ParamGestureBeginEvent::TYPE_ID => Some(Self::ParamGestureBegin),
+            ParamGestureEndEvent::TYPE_ID => Some(Self::ParamGestureEnd),
+            TransportEvent::TYPE_ID => Some(Self::Transport),
+            MidiEvent::TYPE_ID => Some(Self::Midi),
+            Midi2Event::TYPE_ID => Some(Self::Midi2),
+            MidiSysExEvent::TYPE_ID => Some(Self::MidiSysEx),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw type ID of this event type.
+    #[inline]
+    pub const fn type_id(self) -> u16 {
+        match self {
+            Self::NoteOn => NoteOnEvent::TYPE_ID,
+            Self::NoteOff => NoteOffEvent::TYPE_ID,
+            Self::NoteChoke => NoteChokeEvent::TYPE_ID,
+            Self::NoteEnd => NoteEndEvent::TYPE_ID,
+            Self::NoteExpression => NoteExpressionEvent::TYPE_ID,
+            Self::ParamValue => ParamValueEvent::TYPE_ID,
+            Self::ParamMod => ParamModEvent::TYPE_ID,
+            Self::ParamGestureBegin => ParamGestureBeginEvent::TYPE_ID,
+            Self::ParamGestureEnd => ParamGestureEndEvent::TYPE_ID,
+            Self::Transport => TransportEvent::TYPE_ID,
+            Self::Midi => MidiEvent::TYPE_ID,
+            Self::Midi2 => Midi2Event::TYPE_ID,
+            Self::MidiSysEx => MidiSysExEvent::TYPE_ID,
+        }
+    }
+
+    /// Returns `true` if this is one of the note event types (Note On, Note Off, Note Choke and
+    /// Note End).
+    #[inline]
+    pub const fn is_note(self) -> bool {
+        matches!(
+            self,
+            Self::NoteOn | Self::NoteOff | Self::NoteChoke | Self::NoteEnd
+        )
+    }
+
+    /// Returns `true` if this is one of the parameter event types (Parameter Value, Parameter
+    /// Modulation, and both Gesture events).
+    #[inline]
+    pub const fn is_param(self) -> bool {
+        matches!(
+            self,
+            Self::ParamValue | Self::ParamMod | Self::ParamGestureBegin | Self::ParamGestureEnd
+        )
+    }
+
+    /// Returns `true` if this is one of the MIDI event types (MIDI, MIDI 2 and MIDI SysEx).
+    #[inline]
+    pub const fn is_midi(self) -> bool {
+        matches!(self, Self::Midi | Self::Midi2 | Self::MidiSysEx)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum CoreEventSpace<'a> {
     NoteOn(&'a NoteOnEvent),
@@ -27,22 +150,23 @@ unsafe impl<'a> EventSpace<'a> for CoreEventSpace<'a> {
     const NAME: &'static CStr = EMPTY;
 
     unsafe fn from_unknown(event: &'a UnknownEvent) -> Option<Self> {
-        use CoreEventSpace::*;
+        let event_type = CoreEventType::from_type_id(event.header().type_id())?;
 
-        match event.header().type_id() {
-            NoteOnEvent::TYPE_ID => Some(NoteOn(event.as_event_unchecked())),
-            NoteOffEvent::TYPE_ID => Some(NoteOff(event.as_event_unchecked())),
-            NoteChokeEvent::TYPE_ID => Some(NoteChoke(event.as_event_unchecked())),
-            NoteEndEvent::TYPE_ID => Some(NoteEnd(event.as_event_unchecked())),
-            NoteExpressionEvent::TYPE_ID => Some(NoteExpression(event.as_event_unchecked())),
-            ParamValueEvent::TYPE_ID => Some(ParamValue(event.as_event_unchecked())),
-            ParamModEvent::TYPE_ID => Some(ParamMod(event.as_event_unchecked())),
-            TransportEvent::TYPE_ID => Some(Transport(event.as_event_unchecked())),
-            MidiEvent::TYPE_ID => Some(Midi(event.as_event_unchecked())),
-            Midi2Event::TYPE_ID => Some(Midi2(event.as_event_unchecked())),
-            MidiSysExEvent::TYPE_ID => Some(MidiSysEx(event.as_event_unchecked())),
-            _ => None,
-        }
+        Some(match event_type {
+            CoreEventType::NoteOn => Self::NoteOn(event.as_event_unchecked()),
+            CoreEventType::NoteOff => Self::NoteOff(event.as_event_unchecked()),
+            CoreEventType::NoteChoke => Self::NoteChoke(event.as_event_unchecked()),
+            CoreEventType::NoteEnd => Self::NoteEnd(event.as_event_unchecked()),
+            CoreEventType::NoteExpression => Self::NoteExpression(event.as_event_unchecked()),
+            CoreEventType::ParamValue => Self::ParamValue(event.as_event_unchecked()),
+            CoreEventType::ParamMod => Self::ParamMod(event.as_event_unchecked()),
+            CoreEventType::ParamGestureBegin => Self::ParamGestureBegin(event.as_event_unchecked()),
+            CoreEventType::ParamGestureEnd => Self::ParamGestureEnd(event.as_event_unchecked()),
+            CoreEventType::Transport => Self::Transport(event.as_event_unchecked()),
+            CoreEventType::Midi => Self::Midi(event.as_event_unchecked()),
+            CoreEventType::Midi2 => Self::Midi2(event.as_event_unchecked()),
+            CoreEventType::MidiSysEx => Self::MidiSysEx(event.as_event_unchecked()),
+        })
     }
 
     #[inline]
@@ -65,6 +189,28 @@ unsafe impl<'a> EventSpace<'a> for CoreEventSpace<'a> {
     }
 }
 
+impl CoreEventSpace<'_> {
+    /// Returns the type of the event contained in this event space.
+    #[inline]
+    pub const fn event_type(&self) -> CoreEventType {
+        match self {
+            CoreEventSpace::NoteOn(_) => CoreEventType::NoteOn,
+            CoreEventSpace::NoteOff(_) => CoreEventType::NoteOff,
+            CoreEventSpace::NoteChoke(_) => CoreEventType::NoteChoke,
+            CoreEventSpace::NoteEnd(_) => CoreEventType::NoteEnd,
+            CoreEventSpace::NoteExpression(_) => CoreEventType::NoteExpression,
+            CoreEventSpace::ParamValue(_) => CoreEventType::ParamValue,
+            CoreEventSpace::ParamMod(_) => CoreEventType::ParamMod,
+            CoreEventSpace::ParamGestureBegin(_) => CoreEventType::ParamGestureBegin,
+            CoreEventSpace::ParamGestureEnd(_) => CoreEventType::ParamGestureEnd,
+            CoreEventSpace::Transport(_) => CoreEventType::Transport,
+            CoreEventSpace::Midi(_) => CoreEventType::Midi,
+            CoreEventSpace::Midi2(_) => CoreEventType::Midi2,
+            CoreEventSpace::MidiSysEx(_) => CoreEventType::MidiSysEx,
+        }
+    }
+}
+
 impl Debug for CoreEventSpace<'_> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
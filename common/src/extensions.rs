@@ -48,9 +48,23 @@ mod private {
 ///
 /// The [`IDENTIFIER`](Extension::IDENTIFIER) **must** match the official identifier for the given
 /// extension, otherwise the extension data could be misinterpreted, leading to Undefined Behavior.
+///
+/// Likewise, all the [`COMPAT_IDENTIFIERS`](Extension::COMPAT_IDENTIFIERS) **must** be identifiers
+/// of extensions that are ABI-compatible with this one.
 pub unsafe trait Extension: Copy + Sized + Send + Sync + 'static {
     /// The standard identifier for this extension.
     const IDENTIFIER: &'static CStr;
+
+    /// Older or alternative identifiers this extension is also known as, if any.
+    ///
+    /// Some extensions were renamed during their lifetime, e.g. when transitioning from a draft
+    /// to a stable extension, while keeping the same ABI. Clack plugins and hosts try these
+    /// identifiers in order, after the main [`IDENTIFIER`](Extension::IDENTIFIER), when querying
+    /// or registering the extension, so that they can interoperate with older counterparts.
+    ///
+    /// This is empty by default.
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[];
+
     /// Whether this is a host extension or a plugin extension
     type ExtensionSide: ExtensionSide;
 
@@ -62,6 +76,15 @@ pub unsafe trait Extension: Copy + Sized + Send + Sync + 'static {
     unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self;
 }
 
+/// Returns all the identifiers of the extension `E`, in the order they should be tried.
+///
+/// This yields [`E::IDENTIFIER`](Extension::IDENTIFIER) first, followed by all of its
+/// [`COMPAT_IDENTIFIERS`](Extension::COMPAT_IDENTIFIERS).
+#[inline]
+pub fn extension_identifiers<E: Extension>() -> impl Iterator<Item = &'static CStr> {
+    core::iter::once(E::IDENTIFIER).chain(E::COMPAT_IDENTIFIERS.iter().copied())
+}
+
 /// Provides an implementation of this extension for a given type `I` (typically either a host or
 /// plugin structure).
 ///
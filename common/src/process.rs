@@ -1,5 +1,7 @@
 use clap_sys::process::*;
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::time::Duration;
 
 mod constant_mask;
 pub use constant_mask::*;
@@ -47,7 +49,18 @@ impl ProcessStatus {
         }
     }
 
-    pub fn combined_with(self, other: ProcessStatus) -> ProcessStatus {
+    /// Combines this status with another one, returning the status that requires the most
+    /// processing of the two.
+    ///
+    /// The precedence is as follows: [`Continue`](Self::Continue) takes precedence over
+    /// [`ContinueIfNotQuiet`](Self::ContinueIfNotQuiet), which takes precedence over
+    /// [`Tail`](Self::Tail), which takes precedence over [`Sleep`](Self::Sleep).
+    ///
+    /// This is useful for hosts that need to know whether a group of plugins (e.g. a chain, or a
+    /// whole graph) can be put to sleep. See also [`combine`](Self::combine) to combine any number
+    /// of statuses at once.
+    #[inline]
+    pub const fn combined_with(self, other: ProcessStatus) -> ProcessStatus {
         use ProcessStatus::*;
 
         match (self, other) {
@@ -57,12 +70,85 @@ impl ProcessStatus {
             (Sleep, Sleep) => Sleep,
         }
     }
+
+    /// Combines all the given statuses into one, returning the status that requires the most
+    /// processing.
+    ///
+    /// See [`combined_with`](Self::combined_with) for the precedence rules. If no statuses are
+    /// given, this returns [`Sleep`](Self::Sleep), as there is nothing left to process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::process::ProcessStatus;
+    ///
+    /// let chain = [ProcessStatus::Sleep, ProcessStatus::Tail, ProcessStatus::ContinueIfNotQuiet];
+    /// assert_eq!(ProcessStatus::combine(chain), ProcessStatus::ContinueIfNotQuiet);
+    /// assert_eq!(ProcessStatus::combine([]), ProcessStatus::Sleep);
+    /// ```
+    pub fn combine<I: IntoIterator<Item = ProcessStatus>>(statuses: I) -> ProcessStatus {
+        let mut combined = ProcessStatus::Sleep;
+
+        for status in statuses {
+            combined = combined.combined_with(status);
+
+            // Nothing can take precedence over Continue.
+            if combined == ProcessStatus::Continue {
+                break;
+            }
+        }
+
+        combined
+    }
+
+    /// Returns whether processing should continue after a plugin returned this status.
+    ///
+    /// * `outputs_quiet` indicates whether all the outputs of the plugin (or group of plugins)
+    ///   were quiet during the last processed block;
+    /// * `tail_ended` indicates whether the plugin's tail, as reported by the `tail` extension,
+    ///   has finished playing.
+    ///
+    /// This only returns `false` when the plugin can be safely put to sleep until the next event
+    /// or variation in audio input.
+    #[inline]
+    pub const fn should_continue(self, outputs_quiet: bool, tail_ended: bool) -> bool {
+        match self {
+            ProcessStatus::Continue => true,
+            ProcessStatus::ContinueIfNotQuiet => !outputs_quiet,
+            ProcessStatus::Tail => !tail_ended,
+            ProcessStatus::Sleep => false,
+        }
+    }
 }
 
 /// The audio configuration passed to a plugin's audio processor upon activation.
 ///
 /// Those settings are constant throughout the audio processor's lifetime,
 /// i.e. from a plugin's activation until its deactivation.
+///
+/// This is the full activation contract between the host and the plugin: every `process()`
+/// call will have a frame count within [`frames_range`](Self::frames_range), at the given sample
+/// rate. Plugins can rely on it to pre-allocate their buffers, delay lines or voice pools
+/// upfront, without having to allocate during processing.
+///
+/// Note that CLAP doesn't give any information about threading upon activation. Plugins that
+/// want to parallelize their processing should use the `thread-pool` extension instead.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::process::PluginAudioConfiguration;
+/// use std::time::Duration;
+///
+/// let config = PluginAudioConfiguration::new(48_000.0, 32, 512);
+///
+/// assert!(config.is_valid());
+/// assert!(config.frames_range().contains(&256));
+/// assert!(!config.is_fixed_block_size());
+///
+/// // A 250ms delay line needs that many frames.
+/// assert_eq!(config.frames_for_duration(Duration::from_millis(250)), 12_000);
+/// ```
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct PluginAudioConfiguration {
     /// The audio's sample rate.
@@ -73,6 +159,64 @@ pub struct PluginAudioConfiguration {
     pub max_frames_count: u32,
 }
 
+impl PluginAudioConfiguration {
+    /// Creates a new audio configuration from its sample rate and frame count bounds.
+    #[inline]
+    pub const fn new(sample_rate: f64, min_frames_count: u32, max_frames_count: u32) -> Self {
+        Self {
+            sample_rate,
+            min_frames_count,
+            max_frames_count,
+        }
+    }
+
+    /// Returns `true` if this configuration is valid according to the CLAP specification.
+    ///
+    /// A valid configuration has a finite, strictly positive sample rate, and frame count bounds
+    /// that are within `1..=i32::MAX`, with the minimum being lower than or equal to the
+    /// maximum.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.sample_rate.is_finite()
+            && self.sample_rate > 0.0
+            && self.min_frames_count >= 1
+            && self.min_frames_count <= self.max_frames_count
+            && self.max_frames_count <= i32::MAX as u32
+    }
+
+    /// Returns the range of frame counts that can be processed at once.
+    #[inline]
+    pub const fn frames_range(&self) -> RangeInclusive<u32> {
+        self.min_frames_count..=self.max_frames_count
+    }
+
+    /// Returns `true` if the host will always process blocks of the same size.
+    #[inline]
+    pub const fn is_fixed_block_size(&self) -> bool {
+        self.min_frames_count == self.max_frames_count
+    }
+
+    /// Returns the number of frames needed to hold the given duration at this configuration's
+    /// sample rate, rounded up.
+    #[inline]
+    pub fn frames_for_duration(&self, duration: Duration) -> usize {
+        (duration.as_secs_f64() * self.sample_rate).ceil() as usize
+    }
+
+    /// Returns the duration of the longest block of frames that can be processed at once.
+    ///
+    /// This returns `None` if the sample rate isn't finite and strictly positive, in which case
+    /// the duration can't be represented.
+    #[inline]
+    pub fn max_block_duration(&self) -> Option<Duration> {
+        if !(self.sample_rate.is_finite() && self.sample_rate > 0.0) {
+            return None;
+        }
+
+        Duration::try_from_secs_f64(self.max_frames_count as f64 / self.sample_rate).ok()
+    }
+}
+
 use clap_sys::audio_buffer::clap_audio_buffer;
 
 /// Processing-related information about an audio port.
@@ -115,3 +259,46 @@ impl AudioPortProcessingInfo {
         self.constant_mask
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ProcessStatus::*;
+
+    #[test]
+    fn combines_statuses() {
+        let all = [Continue, ContinueIfNotQuiet, Tail, Sleep];
+
+        for (i, a) in all.iter().enumerate() {
+            for (j, b) in all.iter().enumerate() {
+                let expected = all[i.min(j)];
+                assert_eq!(a.combined_with(*b), expected);
+                assert_eq!(ProcessStatus::combine([*a, *b]), expected);
+            }
+        }
+
+        assert_eq!(ProcessStatus::combine([Sleep, Sleep]), Sleep);
+        assert_eq!(ProcessStatus::combine([Tail, Continue, Sleep]), Continue);
+    }
+
+    #[test]
+    fn checks_if_processing_should_continue() {
+        assert!(Continue.should_continue(true, true));
+        assert!(ContinueIfNotQuiet.should_continue(false, true));
+        assert!(!ContinueIfNotQuiet.should_continue(true, false));
+        assert!(Tail.should_continue(true, false));
+        assert!(!Tail.should_continue(false, true));
+        assert!(!Sleep.should_continue(false, false));
+    }
+
+    #[test]
+    fn computes_max_block_duration() {
+        let config = PluginAudioConfiguration::new(48_000.0, 32, 480);
+        assert_eq!(config.max_block_duration(), Some(Duration::from_millis(10)));
+
+        for sample_rate in [0.0, -48_000.0, f64::NAN, f64::INFINITY] {
+            let config = PluginAudioConfiguration::new(sample_rate, 32, 480);
+            assert_eq!(config.max_block_duration(), None);
+        }
+    }
+}
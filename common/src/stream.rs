@@ -73,10 +73,7 @@ impl Read for InputStream<'_> {
         } else {
             return Ok(0);
         };
-        match ret {
-            i if i >= 0 => Ok(i as usize),
-            code => Err(std::io::Error::new(ErrorKind::Other, StreamError { code })),
-        }
+        stream_result(ret, buf.len())
     }
 }
 
@@ -124,10 +121,7 @@ impl Write for OutputStream<'_> {
             return Ok(0);
         };
 
-        match ret {
-            i if i >= 0 => Ok(i as usize),
-            code => Err(std::io::Error::new(ErrorKind::Other, StreamError { code })),
-        }
+        stream_result(ret, buf.len())
     }
 
     #[inline]
@@ -136,14 +130,42 @@ impl Write for OutputStream<'_> {
     }
 }
 
+/// Converts the return value of a stream's `read` or `write` function to an I/O result.
+///
+/// Streams reporting they processed more bytes than the buffer holds are treated as errors, as
+/// trusting them would lead the caller out of the buffer's bounds.
+fn stream_result(ret: i64, buffer_len: usize) -> std::io::Result<usize> {
+    match ret {
+        i if i < 0 => Err(std::io::Error::new(
+            ErrorKind::Other,
+            StreamError { code: i },
+        )),
+        i if i as u64 > buffer_len as u64 => Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            "CLAP stream reported more bytes than the buffer's size",
+        )),
+        i => Ok(i as usize),
+    }
+}
+
+/// Clamps a buffer size received through a C FFI stream function to a valid slice length.
+#[inline]
+fn clamp_buffer_size(size: u64) -> usize {
+    size.min(isize::MAX as u64) as usize
+}
+
 #[allow(clippy::missing_safety_doc)]
 unsafe extern "C" fn read<R: Read + Sized>(
     istream: *const clap_istream,
     buffer: *mut c_void,
     size: u64,
 ) -> i64 {
+    if istream.is_null() || (*istream).ctx.is_null() || (buffer.is_null() && size > 0) {
+        return -1;
+    }
+
     let reader = &mut *((*istream).ctx as *mut R);
-    let buffer = slice_from_external_parts_mut(buffer as *mut u8, size as usize);
+    let buffer = slice_from_external_parts_mut(buffer as *mut u8, clamp_buffer_size(size));
 
     match handle_interrupted(|| reader.read(buffer)) {
         Ok(read) => read as i64,
@@ -157,8 +179,12 @@ unsafe extern "C" fn write<W: Write + Sized>(
     buffer: *const c_void,
     size: u64,
 ) -> i64 {
+    if ostream.is_null() || (*ostream).ctx.is_null() || (buffer.is_null() && size > 0) {
+        return -1;
+    }
+
     let writer = &mut *((*ostream).ctx as *mut W);
-    let buffer = slice_from_external_parts(buffer as *const u8, size as usize);
+    let buffer = slice_from_external_parts(buffer as *const u8, clamp_buffer_size(size));
 
     match handle_interrupted(|| writer.write(buffer)) {
         Ok(written) => written as i64,
@@ -203,6 +229,43 @@ mod test {
         assert_eq!(&buf, b"Hello");
     }
 
+    #[test]
+    fn input_streams_reject_oversized_reads() {
+        extern "C" fn read(_: *const clap_istream, _: *mut c_void, size: u64) -> i64 {
+            size as i64 + 1
+        }
+
+        let mut raw = clap_istream {
+            ctx: core::ptr::null_mut(),
+            read: Some(read),
+        };
+
+        // SAFETY: the stream is valid, and its read function doesn't touch the buffer
+        let stream = unsafe { InputStream::from_raw_mut(&mut raw) };
+        let error = stream.read(&mut [0; 4]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn streams_reject_null_buffers() {
+        let mut cursor = Cursor::new(b"Hello");
+        let mut stream = InputStream::from_reader(&mut cursor);
+        let raw = stream.as_raw_mut();
+
+        // SAFETY: the stream is valid, and null buffers must be rejected before being read
+        let ret = unsafe { (raw.read.unwrap())(raw, core::ptr::null_mut(), 5) };
+        assert_eq!(ret, -1);
+
+        let mut buf = vec![];
+        let mut stream = OutputStream::from_writer(&mut buf);
+        let raw = stream.as_raw_mut();
+
+        // SAFETY: the stream is valid, and null buffers must be rejected before being read
+        let ret = unsafe { (raw.write.unwrap())(raw, core::ptr::null(), 5) };
+        assert_eq!(ret, -1);
+        assert!(buf.is_empty());
+    }
+
     #[test]
     fn output_streams_work() {
         let mut buf = vec![];
@@ -11,17 +11,37 @@ pub(crate) fn handle_panic<F: FnOnce() -> R, R>(f: F) -> std::thread::Result<R>
     Ok(f())
 }
 
+#[cfg(feature = "assert-no-alloc")]
+mod alloc;
+mod automation;
 mod fixed_point;
 mod id;
+mod param_queue;
+#[cfg(feature = "serde")]
+pub mod serialization;
+mod triple_buffer;
 mod version;
 
+#[cfg(feature = "assert-no-alloc")]
+pub use alloc::{assert_no_alloc, permit_alloc, AllocDetector};
+pub use automation::AutomationState;
 pub use fixed_point::*;
 pub use id::ClapId;
+pub use param_queue::*;
+pub use triple_buffer::*;
 pub use version::ClapVersion;
 
 use std::ffi::c_void;
 
 /// An opaque pointer for use in e.g. parameter definitions and parameter-related events.
+///
+/// Cookies are set by the plugin, and are given back as-is by the host in parameter events. They
+/// are never dereferenced by either Clack or the host.
+///
+/// Instead of storing actual pointers, plugins can store the index of the parameter in their own
+/// parameter list using [`from_index`](Self::from_index), and retrieve it from incoming events
+/// using [`index`](Self::index). This allows to look parameters up in O(1) without any `unsafe`
+/// code.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Cookie(*mut c_void);
 
@@ -40,6 +60,44 @@ impl Cookie {
     pub const fn as_raw(&self) -> *mut c_void {
         self.0
     }
+
+    /// Returns `true` if this cookie is [empty](Self::empty), i.e. a null pointer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Creates a cookie storing the given index.
+    ///
+    /// The index can be retrieved using [`index`](Self::index). The resulting cookie must not be
+    /// dereferenced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_common::utils::Cookie;
+    ///
+    /// assert_eq!(Cookie::from_index(3).index(), Some(3));
+    /// assert_eq!(Cookie::empty().index(), None);
+    /// ```
+    #[inline]
+    pub fn from_index(index: usize) -> Self {
+        Self(index.wrapping_add(1) as *mut c_void)
+    }
+
+    /// Returns the index stored in this cookie by [`from_index`](Self::from_index), or `None` if
+    /// the cookie is empty.
+    ///
+    /// Note the host may give back any cookie the plugin provided, including stale ones from
+    /// before a parameter rescan. The index must therefore always be bounds-checked, and the
+    /// matching parameter ID verified.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        match self.0 as usize {
+            0 => None,
+            value => Some(value - 1),
+        }
+    }
 }
 
 impl Default for Cookie {
@@ -0,0 +1,148 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    /// How many [`assert_no_alloc`] calls are currently running on this thread.
+    static GUARD_DEPTH: Cell<u32> = const { Cell::new(0) };
+    /// How many allocations were done on this thread while a guard was active.
+    static VIOLATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A global allocator wrapper that detects heap allocations made inside [`assert_no_alloc`].
+///
+/// This wraps another allocator (the [`System`] allocator by default), and must be registered as
+/// the global allocator of the test or debug binary for [`assert_no_alloc`] to detect anything:
+///
+/// ```
+/// use clack_common::utils::AllocDetector;
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: AllocDetector<System> = AllocDetector::new(System);
+/// ```
+///
+/// Allocations, reallocations and deallocations are all counted as violations while a guard is
+/// active on the current thread. Other threads are not affected.
+pub struct AllocDetector<A = System> {
+    inner: A,
+}
+
+impl<A> AllocDetector<A> {
+    /// Wraps the given allocator.
+    #[inline]
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    #[inline]
+    fn check(&self) {
+        // try_with: this may be called when thread-locals are being destroyed.
+        let guarded = GUARD_DEPTH.try_with(|d| d.get() > 0).unwrap_or(false);
+
+        if guarded {
+            let _ = VIOLATIONS.try_with(|v| v.set(v.get() + 1));
+        }
+    }
+}
+
+// SAFETY: all calls are forwarded to the inner allocator, which upholds the contract.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for AllocDetector<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.check();
+        self.inner.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.check();
+        self.inner.dealloc(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.check();
+        self.inner.alloc_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.check();
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Runs the given closure, and panics if it performed any heap allocation on the current thread.
+///
+/// Allocations can only be detected if an [`AllocDetector`] is registered as the global
+/// allocator. Otherwise, this simply runs the closure.
+///
+/// When the `assert-no-alloc` feature is enabled, `clack-host` and `clack-plugin` wrap all the
+/// calls to the plugin's `process` method with this function.
+///
+/// # Panics
+///
+/// This function panics after the closure returns if it allocated or freed memory. The
+/// allocations themselves are not prevented.
+///
+/// # Example
+///
+/// ```should_panic
+/// use clack_common::utils::{assert_no_alloc, AllocDetector};
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: AllocDetector<System> = AllocDetector::new(System);
+///
+/// let sum = assert_no_alloc(|| 1 + 1); // This is fine
+/// assert_eq!(sum, 2);
+///
+/// assert_no_alloc(|| vec![1, 2, 3]); // This panics
+/// ```
+#[track_caller]
+pub fn assert_no_alloc<R>(f: impl FnOnce() -> R) -> R {
+    let violations_before = VIOLATIONS.with(Cell::get);
+
+    GUARD_DEPTH.with(|d| d.set(d.get() + 1));
+    let guard = DepthGuard;
+    let result = f();
+    drop(guard);
+
+    let violations = VIOLATIONS.with(Cell::get) - violations_before;
+    if violations > 0 {
+        panic!("{violations} heap allocations were made inside assert_no_alloc");
+    }
+
+    result
+}
+
+/// Runs the given closure, allowing it to allocate even inside [`assert_no_alloc`].
+///
+/// This is useful for e.g. diagnostic code that is known to allocate, but is not part of the
+/// audio path under test.
+pub fn permit_alloc<R>(f: impl FnOnce() -> R) -> R {
+    let depth = GUARD_DEPTH.with(|d| d.replace(0));
+    let _restore = RestoreDepth(depth);
+
+    f()
+}
+
+/// Decrements the guard depth when dropped, including while unwinding.
+struct DepthGuard;
+
+impl Drop for DepthGuard {
+    #[inline]
+    fn drop(&mut self) {
+        GUARD_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Restores the guard depth when dropped, including while unwinding.
+struct RestoreDepth(u32);
+
+impl Drop for RestoreDepth {
+    #[inline]
+    fn drop(&mut self) {
+        GUARD_DEPTH.with(|d| d.set(self.0));
+    }
+}
@@ -0,0 +1,74 @@
+use clap_sys::ext::draft::param_indication::*;
+
+/// The automation state of a parameter, as indicated by the host.
+///
+/// This is used by the `param-indication` extension, which allows hosts to tell plugins about the
+/// automation state of each parameter, so that it can be displayed e.g. in the plugin's GUI.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum AutomationState {
+    /// The host has no automation for this parameter.
+    #[default]
+    None = CLAP_PARAM_INDICATION_AUTOMATION_NONE,
+    /// The host has automation for this parameter, but it isn't playing.
+    Present = CLAP_PARAM_INDICATION_AUTOMATION_PRESENT,
+    /// The host is playing automation for this parameter.
+    Playing = CLAP_PARAM_INDICATION_AUTOMATION_PLAYING,
+    /// The host is recording automation for this parameter.
+    Recording = CLAP_PARAM_INDICATION_AUTOMATION_RECORDING,
+    /// The host should play automation for this parameter, but the user has started to adjust it
+    /// and is overriding the automation playback.
+    Overriding = CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING,
+}
+
+impl AutomationState {
+    /// Gets an [`AutomationState`] from the raw, C-FFI compatible value.
+    ///
+    /// If the given integer does not match any known automation state, [`None`] is returned.
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Option<Self> {
+        use AutomationState::*;
+
+        match raw {
+            CLAP_PARAM_INDICATION_AUTOMATION_NONE => Some(None),
+            CLAP_PARAM_INDICATION_AUTOMATION_PRESENT => Some(Present),
+            CLAP_PARAM_INDICATION_AUTOMATION_PLAYING => Some(Playing),
+            CLAP_PARAM_INDICATION_AUTOMATION_RECORDING => Some(Recording),
+            CLAP_PARAM_INDICATION_AUTOMATION_OVERRIDING => Some(Overriding),
+            _ => Option::None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this automation state.
+    #[inline]
+    pub const fn to_raw(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns `true` if the host has any automation for this parameter, regardless of whether it
+    /// is being played, recorded or overridden.
+    #[inline]
+    pub const fn has_automation(self) -> bool {
+        !matches!(self, AutomationState::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_values_round_trip() {
+        for state in [
+            AutomationState::None,
+            AutomationState::Present,
+            AutomationState::Playing,
+            AutomationState::Recording,
+            AutomationState::Overriding,
+        ] {
+            assert_eq!(AutomationState::from_raw(state.to_raw()), Some(state));
+        }
+
+        assert_eq!(AutomationState::from_raw(5), None);
+    }
+}
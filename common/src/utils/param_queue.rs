@@ -0,0 +1,392 @@
+//! A bounded, lock-free, single-producer single-consumer queue, to send parameter changes
+//! between threads.
+
+use crate::events::event_types::ParamValueEvent;
+use crate::events::Pckn;
+use crate::utils::{ClapId, Cookie};
+use std::cell::UnsafeCell;
+use std::fmt::{Debug, Formatter};
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A single parameter change, to be sent through a parameter queue.
+///
+/// This holds the same information as a [`ParamValueEvent`], except for its timestamp, which is
+/// usually only known once the change is received by the audio thread. Unlike
+/// [`ParamValueEvent`] however, this type can be sent between threads.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParamChange {
+    /// The ID of the parameter that changed.
+    pub param_id: ClapId,
+    /// The new value of the parameter.
+    pub value: f64,
+    /// The note(s), port(s), channel(s) or key(s) this change applies to.
+    pub pckn: Pckn,
+    /// The cookie of the parameter, as given by the plugin.
+    pub cookie: Cookie,
+}
+
+impl ParamChange {
+    /// Creates a new parameter change, that applies to all notes, ports, channels and keys,
+    /// and with an empty cookie.
+    #[inline]
+    pub const fn new(param_id: ClapId, value: f64) -> Self {
+        Self {
+            param_id,
+            value,
+            pckn: Pckn::match_all(),
+            cookie: Cookie::empty(),
+        }
+    }
+
+    /// Creates a parameter change from the given [`ParamValueEvent`].
+    ///
+    /// This returns `None` if the event doesn't have a valid parameter ID.
+    #[inline]
+    pub fn from_event(event: &ParamValueEvent) -> Option<Self> {
+        Some(Self {
+            param_id: event.param_id()?,
+            value: event.value(),
+            pckn: event.pckn(),
+            cookie: event.cookie(),
+        })
+    }
+
+    /// Creates a [`ParamValueEvent`] from this change, occurring at the given sample time.
+    #[inline]
+    pub const fn to_event(&self, time: u32) -> ParamValueEvent {
+        ParamValueEvent::new(time, self.param_id, self.pckn, self.value, self.cookie)
+    }
+}
+
+/// What to do when pushing to a parameter queue that is already full.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OverflowPolicy {
+    /// [`ParamQueueProducer::push`] fails, and gives the value back to the caller.
+    Reject,
+    /// The value is silently discarded, and [`ParamQueueProducer::push`] succeeds.
+    ///
+    /// The number of discarded values can be retrieved with
+    /// [`ParamQueueConsumer::take_dropped_count`], e.g. to resynchronize all parameters once the
+    /// queue has caught up.
+    DropNewest,
+}
+
+struct Shared<T> {
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+// SAFETY: each slot is only ever accessed by either the producer or the consumer, as
+// synchronized by the head and tail indexes. T being Send is enough to move values between them.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    #[inline]
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        // The buffer's length is always a power of two.
+        self.buffer[index & (self.buffer.len() - 1)].get()
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+
+        for offset in 0..tail.wrapping_sub(head) {
+            // SAFETY: all the slots between head and tail have been initialized by the producer,
+            // and are never read again since we have exclusive access.
+            unsafe { (*self.slot(head.wrapping_add(offset))).assume_init_drop() }
+        }
+    }
+}
+
+/// Creates a new bounded parameter queue, and returns its producer and consumer halves.
+///
+/// The queue can hold at least `capacity` values, which is rounded up to the next power of two.
+/// Its storage is allocated once, when it is created: pushing and popping values never allocates,
+/// never locks, and never blocks, which makes it suitable for communicating with the audio thread.
+///
+/// The `overflow` policy defines what happens when a value is pushed to a queue that is full.
+///
+/// See [`triple_buffer`](super::triple_buffer) to only publish the latest snapshot of a state
+/// instead, e.g. from the audio thread to the GUI.
+///
+/// # Panics
+///
+/// This function panics if `capacity` is zero.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::utils::{param_queue, ClapId, OverflowPolicy, ParamChange};
+///
+/// let (mut producer, mut consumer) = param_queue(64, OverflowPolicy::Reject);
+///
+/// // e.g. on the GUI thread
+/// producer.push(ParamChange::new(ClapId::new(1), 0.5)).unwrap();
+///
+/// // e.g. on the audio thread
+/// let change = consumer.pop().unwrap();
+/// assert_eq!(change.value, 0.5);
+/// ```
+pub fn param_queue<T: Send>(
+    capacity: usize,
+    overflow: OverflowPolicy,
+) -> (ParamQueueProducer<T>, ParamQueueConsumer<T>) {
+    assert!(capacity > 0, "Parameter queue capacity must not be zero");
+
+    let buffer = (0..capacity.next_power_of_two())
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+
+    let shared = Arc::new(Shared {
+        buffer,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        dropped: AtomicUsize::new(0),
+    });
+
+    (
+        ParamQueueProducer {
+            shared: shared.clone(),
+            tail: 0,
+            overflow,
+        },
+        ParamQueueConsumer { shared, head: 0 },
+    )
+}
+
+/// The producing half of a parameter queue, created by [`param_queue`].
+pub struct ParamQueueProducer<T> {
+    shared: Arc<Shared<T>>,
+    tail: usize,
+    overflow: OverflowPolicy,
+}
+
+impl<T> ParamQueueProducer<T> {
+    /// Pushes a new value at the end of the queue.
+    ///
+    /// This never allocates nor blocks.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is full and the queue's [`OverflowPolicy`] is [`OverflowPolicy::Reject`],
+    /// the value is returned back as an error.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let head = self.shared.head.load(Ordering::Acquire);
+
+        if self.tail.wrapping_sub(head) >= self.shared.buffer.len() {
+            return match self.overflow {
+                OverflowPolicy::Reject => Err(value),
+                OverflowPolicy::DropNewest => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                }
+            };
+        }
+
+        // SAFETY: this slot is past the tail, so the consumer won't access it until we publish it.
+        unsafe { (*self.shared.slot(self.tail)).write(value) };
+
+        self.tail = self.tail.wrapping_add(1);
+        self.shared.tail.store(self.tail, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Returns the maximum number of values the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// Returns the number of values that can currently be pushed before the queue is full.
+    #[inline]
+    pub fn available(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Acquire);
+        self.capacity() - self.tail.wrapping_sub(head)
+    }
+}
+
+impl<T> Debug for ParamQueueProducer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParamQueueProducer")
+            .field("capacity", &self.capacity())
+            .field("overflow", &self.overflow)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The consuming half of a parameter queue, created by [`param_queue`].
+pub struct ParamQueueConsumer<T> {
+    shared: Arc<Shared<T>>,
+    head: usize,
+}
+
+impl<T> ParamQueueConsumer<T> {
+    /// Pops the value at the front of the queue, if there is any.
+    ///
+    /// This never allocates nor blocks.
+    pub fn pop(&mut self) -> Option<T> {
+        let tail = self.shared.tail.load(Ordering::Acquire);
+
+        if self.head == tail {
+            return None;
+        }
+
+        // SAFETY: this slot is before the tail, so it has been initialized by the producer, which
+        // won't access it again until we release it.
+        let value = unsafe { (*self.shared.slot(self.head)).assume_init_read() };
+
+        self.head = self.head.wrapping_add(1);
+        self.shared.head.store(self.head, Ordering::Release);
+
+        Some(value)
+    }
+
+    /// Returns an iterator popping all the values currently in the queue.
+    ///
+    /// Values pushed while the iterator is in use may also be returned.
+    #[inline]
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.pop())
+    }
+
+    /// Returns the number of values currently in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shared
+            .tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head)
+    }
+
+    /// Returns `true` if the queue currently holds no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of values the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// Returns the number of values that were discarded since the last call to this method,
+    /// because they were pushed while the queue was full, and resets it to zero.
+    ///
+    /// This is always zero if the queue's [`OverflowPolicy`] is [`OverflowPolicy::Reject`].
+    #[inline]
+    pub fn take_dropped_count(&mut self) -> usize {
+        self.shared.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl<T> Debug for ParamQueueConsumer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParamQueueConsumer")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::Event;
+
+    #[test]
+    fn pushes_and_pops_in_order() {
+        let (mut producer, mut consumer) = param_queue(3, OverflowPolicy::Reject);
+        assert_eq!(producer.capacity(), 4);
+        assert_eq!(consumer.pop(), None);
+
+        for round in 0..10 {
+            for i in 0..4 {
+                producer.push(round * 4 + i).unwrap();
+            }
+
+            assert_eq!(producer.push(42), Err(42));
+            assert_eq!(consumer.len(), 4);
+
+            let values: Vec<_> = consumer.drain().collect();
+            assert_eq!(values, [0, 1, 2, 3].map(|i| round * 4 + i));
+            assert!(consumer.is_empty());
+        }
+    }
+
+    #[test]
+    fn counts_dropped_values() {
+        let (mut producer, mut consumer) = param_queue(2, OverflowPolicy::DropNewest);
+
+        for i in 0..5 {
+            producer
+                .push(ParamChange::new(ClapId::new(i), 1.0))
+                .unwrap();
+        }
+
+        assert_eq!(consumer.take_dropped_count(), 3);
+        assert_eq!(consumer.take_dropped_count(), 0);
+
+        let ids: Vec<_> = consumer.drain().map(|c| c.param_id.get()).collect();
+        assert_eq!(ids, [0, 1]);
+    }
+
+    #[test]
+    fn converts_to_and_from_events() {
+        let change = ParamChange::new(ClapId::new(5), 0.25);
+        let event = change.to_event(12);
+
+        assert_eq!(event.time(), 12);
+        assert_eq!(ParamChange::from_event(&event), Some(change));
+    }
+
+    #[test]
+    fn drops_remaining_values() {
+        let value = Arc::new(());
+        let (mut producer, consumer) = param_queue(4, OverflowPolicy::Reject);
+
+        producer.push(value.clone()).unwrap();
+        producer.push(value.clone()).unwrap();
+        assert_eq!(Arc::strong_count(&value), 3);
+
+        drop(producer);
+        drop(consumer);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn works_across_threads() {
+        let (mut producer, mut consumer) = param_queue(16, OverflowPolicy::Reject);
+
+        let thread = std::thread::spawn(move || {
+            for i in 0..10_000u32 {
+                let mut change = ParamChange::new(ClapId::new(1), i as f64);
+                while let Err(c) = producer.push(change) {
+                    change = c;
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0.0;
+        while expected < 10_000.0 {
+            match consumer.pop() {
+                Some(change) => {
+                    assert_eq!(change.value, expected);
+                    expected += 1.0;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+//! Helpers to implement [`serde`] serialization for CLAP data.
+//!
+//! CLAP strings are not required to be valid UTF-8. The helpers in this module serialize them as
+//! strings whenever possible, so that they stay readable in human-readable formats (e.g. JSON),
+//! and as raw bytes otherwise. Non-human-readable formats (e.g. bincode) always store raw bytes.
+//!
+//! Bit flags are serialized as their raw integer value, preserving unknown bits.
+//!
+//! Each module in here exposes a `serialize` and a `deserialize` function, compatible with serde's
+//! `#[serde(with = "...")]` attribute.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_common::utils::ClapId;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Port {
+//!     id: ClapId,
+//!     #[serde(with = "clack_common::utils::serialization::bytes")]
+//!     name: Vec<u8>,
+//! }
+//! ```
+
+use crate::utils::ClapId;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::CString;
+use std::fmt::Formatter;
+
+/// Serializes byte strings (`Vec<u8>`) as strings when they are valid UTF-8.
+pub mod bytes {
+    use super::*;
+
+    /// Serializes the given byte string.
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            if let Ok(string) = std::str::from_utf8(bytes) {
+                return serializer.serialize_str(string);
+            }
+        }
+
+        serializer.serialize_bytes(bytes)
+    }
+
+    /// Deserializes a byte string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ByteStringVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(ByteStringVisitor)
+        }
+    }
+
+    /// A visitor accepting both strings and bytes.
+    struct ByteStringVisitor;
+
+    impl<'de> Visitor<'de> for ByteStringVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+            formatter.write_str("a string or a byte array")
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(v.as_bytes().to_vec())
+        }
+
+        fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+            Ok(v.into_bytes())
+        }
+
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+
+            Ok(bytes)
+        }
+    }
+}
+
+/// Serializes C strings as strings when they are valid UTF-8.
+pub mod cstring {
+    use super::*;
+
+    /// Serializes the given C string.
+    #[inline]
+    pub fn serialize<S: Serializer>(string: &CString, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes::serialize(string.as_bytes(), serializer)
+    }
+
+    /// Deserializes a C string.
+    ///
+    /// This fails if the string contains a nul byte.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CString, D::Error> {
+        CString::new(bytes::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes optional C strings as strings when they are valid UTF-8.
+pub mod optional_cstring {
+    use super::*;
+
+    /// A wrapper to serialize a C string with the [`cstring`] helpers.
+    struct Wrapper<T>(T);
+
+    impl Serialize for Wrapper<&CString> {
+        #[inline]
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            cstring::serialize(self.0, serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Wrapper<CString> {
+        #[inline]
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            cstring::deserialize(deserializer).map(Wrapper)
+        }
+    }
+
+    /// Serializes the given optional C string.
+    #[inline]
+    pub fn serialize<S: Serializer>(
+        string: &Option<CString>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        string.as_ref().map(Wrapper).serialize(serializer)
+    }
+
+    /// Deserializes an optional C string.
+    #[inline]
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<CString>, D::Error> {
+        Ok(Option::<Wrapper<CString>>::deserialize(deserializer)?.map(|w| w.0))
+    }
+}
+
+/// Serializes lists of C strings, serializing each of them as a string when it is valid UTF-8.
+pub mod cstrings {
+    use super::*;
+    use serde::ser::SerializeSeq;
+
+    /// A wrapper to deserialize a C string with the [`cstring`] helpers.
+    struct Wrapper(CString);
+
+    impl<'de> Deserialize<'de> for Wrapper {
+        #[inline]
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            cstring::deserialize(deserializer).map(Wrapper)
+        }
+    }
+
+    /// A wrapper to serialize a C string with the [`cstring`] helpers.
+    struct WrapperRef<'a>(&'a CString);
+
+    impl Serialize for WrapperRef<'_> {
+        #[inline]
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            cstring::serialize(self.0, serializer)
+        }
+    }
+
+    /// Serializes the given list of C strings.
+    pub fn serialize<S: Serializer>(strings: &[CString], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(strings.len()))?;
+        for string in strings {
+            seq.serialize_element(&WrapperRef(string))?;
+        }
+        seq.end()
+    }
+
+    /// Deserializes a list of C strings.
+    #[inline]
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<CString>, D::Error> {
+        Ok(Vec::<Wrapper>::deserialize(deserializer)?
+            .into_iter()
+            .map(|w| w.0)
+            .collect())
+    }
+}
+
+/// Serializes bit flags as their raw bits.
+pub mod flags {
+    use super::*;
+    use bitflags::Flags;
+
+    /// Serializes the given flags as their raw bits.
+    #[inline]
+    pub fn serialize<F: Flags, S: Serializer>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F::Bits: Serialize,
+    {
+        flags.bits().serialize(serializer)
+    }
+
+    /// Deserializes flags from their raw bits, retaining any unknown bits.
+    #[inline]
+    pub fn deserialize<'de, F: Flags, D: Deserializer<'de>>(deserializer: D) -> Result<F, D::Error>
+    where
+        F::Bits: Deserialize<'de>,
+    {
+        Ok(F::from_bits_retain(F::Bits::deserialize(deserializer)?))
+    }
+}
+
+impl Serialize for ClapId {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.get())
+    }
+}
+
+impl<'de> Deserialize<'de> for ClapId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = u32::deserialize(deserializer)?;
+        ClapId::from_raw(raw).ok_or_else(|| D::Error::custom("invalid CLAP ID: u32::MAX"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitflags::bitflags;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Readable, Token};
+
+    #[test]
+    fn byte_strings_accept_strings_and_bytes() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Bytes(#[serde(with = "bytes")] Vec<u8>);
+
+        assert_tokens(
+            &Bytes(b"Gain".to_vec()).readable(),
+            &[Token::NewtypeStruct { name: "Bytes" }, Token::Str("Gain")],
+        );
+        assert_tokens(
+            &Bytes(vec![0xFF, 0x00]).readable(),
+            &[
+                Token::NewtypeStruct { name: "Bytes" },
+                Token::Bytes(&[0xFF, 0x00]),
+            ],
+        );
+        assert_tokens(
+            &Bytes(b"Gain".to_vec()).compact(),
+            &[
+                Token::NewtypeStruct { name: "Bytes" },
+                Token::Bytes(b"Gain"),
+            ],
+        );
+    }
+
+    #[test]
+    fn cstrings_reject_nul_bytes() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Name(#[serde(with = "cstring")] CString);
+
+        assert_tokens(
+            &Name(CString::new("Gain").unwrap()).readable(),
+            &[Token::NewtypeStruct { name: "Name" }, Token::Str("Gain")],
+        );
+        assert_de_tokens_error::<Readable<Name>>(
+            &[Token::NewtypeStruct { name: "Name" }, Token::Str("Ga\0in")],
+            "nul byte found in provided data at position: 2",
+        );
+    }
+
+    #[test]
+    fn clap_ids_reject_invalid_values() {
+        assert_tokens(&ClapId::new(42), &[Token::U32(42)]);
+        assert_de_tokens_error::<ClapId>(&[Token::U32(u32::MAX)], "invalid CLAP ID: u32::MAX");
+    }
+
+    bitflags! {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        struct TestFlags: u32 {
+            const A = 1;
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestStruct {
+        #[serde(with = "optional_cstring")]
+        title: Option<CString>,
+        #[serde(with = "cstrings")]
+        tags: Vec<CString>,
+        #[serde(with = "flags")]
+        flags: TestFlags,
+    }
+
+    #[test]
+    fn struct_fields_use_helpers() {
+        let value = TestStruct {
+            title: Some(CString::new("Title").unwrap()),
+            tags: vec![CString::new("a").unwrap()],
+            flags: TestFlags::from_bits_retain(0b101),
+        };
+
+        assert_tokens(
+            &value.readable(),
+            &[
+                Token::Struct {
+                    name: "TestStruct",
+                    len: 3,
+                },
+                Token::Str("title"),
+                Token::Some,
+                Token::Str("Title"),
+                Token::Str("tags"),
+                Token::Seq { len: Some(1) },
+                Token::Str("a"),
+                Token::SeqEnd,
+                Token::Str("flags"),
+                Token::U32(0b101),
+                Token::StructEnd,
+            ],
+        );
+    }
+}
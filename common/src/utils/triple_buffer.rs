@@ -0,0 +1,189 @@
+//! A wait-free triple buffer, to publish snapshots of a state from one thread to another.
+
+use std::cell::UnsafeCell;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// Set on the back buffer's index when it holds a value the reader hasn't seen yet.
+const FRESH: u8 = 0b100;
+const INDEX_MASK: u8 = 0b011;
+
+struct Shared<T> {
+    buffers: [UnsafeCell<T>; 3],
+    /// The index of the back buffer, i.e. the one that is currently owned by neither side.
+    back: AtomicU8,
+}
+
+// SAFETY: each buffer is only ever accessed by the side that currently owns it, and ownership is
+// only ever transferred through the atomic swaps of the back buffer index.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// Creates a new triple buffer holding the given initial value, and returns its writer and
+/// reader halves.
+///
+/// A triple buffer allows a writer to continuously publish new snapshots of a value, which the
+/// reader can then access at any time. The reader always sees the latest published snapshot, and
+/// intermediate snapshots it didn't have time to read are skipped.
+///
+/// Neither reading nor writing ever blocks, allocates, or waits on the other side, which makes
+/// this suitable for e.g. publishing meter values or voice counts from the audio thread to the
+/// GUI thread. See [`param_queue`](super::param_queue) to send every single change in the
+/// opposite direction.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::utils::triple_buffer;
+///
+/// let (mut writer, mut reader) = triple_buffer([0.0f32; 2]);
+///
+/// // e.g. on the audio thread
+/// writer.write([0.5, 0.25]);
+///
+/// // e.g. on the GUI thread
+/// assert!(reader.has_update());
+/// assert_eq!(reader.read(), &[0.5, 0.25]);
+/// ```
+pub fn triple_buffer<T: Clone + Send>(
+    initial: T,
+) -> (TripleBufferWriter<T>, TripleBufferReader<T>) {
+    let shared = Arc::new(Shared {
+        buffers: [
+            UnsafeCell::new(initial.clone()),
+            UnsafeCell::new(initial.clone()),
+            UnsafeCell::new(initial),
+        ],
+        back: AtomicU8::new(1),
+    });
+
+    (
+        TripleBufferWriter {
+            shared: shared.clone(),
+            index: 0,
+        },
+        TripleBufferReader { shared, index: 2 },
+    )
+}
+
+/// The writing half of a triple buffer, created by [`triple_buffer`].
+pub struct TripleBufferWriter<T> {
+    shared: Arc<Shared<T>>,
+    index: u8,
+}
+
+impl<T> TripleBufferWriter<T> {
+    /// Publishes a new value, replacing the previously published one.
+    #[inline]
+    pub fn write(&mut self, value: T) {
+        *self.input_buffer() = value;
+        self.publish();
+    }
+
+    /// Returns a mutable reference to the buffer that will be published by the next call to
+    /// [`publish`](Self::publish).
+    ///
+    /// This allows to update a value in place, e.g. to avoid moving large values around.
+    /// Note however that this buffer contains an older snapshot, which is *not* necessarily the
+    /// last published one: it must be fully overwritten before being published.
+    #[inline]
+    pub fn input_buffer(&mut self) -> &mut T {
+        // SAFETY: the writer exclusively owns the buffer at this index.
+        unsafe { &mut *self.shared.buffers[self.index as usize].get() }
+    }
+
+    /// Publishes the contents of the [`input_buffer`](Self::input_buffer).
+    #[inline]
+    pub fn publish(&mut self) {
+        let previous = self.shared.back.swap(self.index | FRESH, Ordering::AcqRel);
+        self.index = previous & INDEX_MASK;
+    }
+}
+
+impl<T> Debug for TripleBufferWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TripleBufferWriter")
+    }
+}
+
+/// The reading half of a triple buffer, created by [`triple_buffer`].
+pub struct TripleBufferReader<T> {
+    shared: Arc<Shared<T>>,
+    index: u8,
+}
+
+impl<T> TripleBufferReader<T> {
+    /// Returns `true` if a new value was published since the last call to [`read`](Self::read).
+    #[inline]
+    pub fn has_update(&self) -> bool {
+        self.shared.back.load(Ordering::Relaxed) & FRESH != 0
+    }
+
+    /// Returns the latest published value.
+    ///
+    /// If nothing was published since the last call to this method, the same value is returned
+    /// again. If nothing was ever published, this returns the initial value.
+    #[inline]
+    pub fn read(&mut self) -> &T {
+        if self.has_update() {
+            let previous = self.shared.back.swap(self.index, Ordering::AcqRel);
+            self.index = previous & INDEX_MASK;
+        }
+
+        // SAFETY: the reader exclusively owns the buffer at this index.
+        unsafe { &*self.shared.buffers[self.index as usize].get() }
+    }
+}
+
+impl<T> Debug for TripleBufferReader<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TripleBufferReader")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_latest_value() {
+        let (mut writer, mut reader) = triple_buffer(0u32);
+        assert!(!reader.has_update());
+        assert_eq!(*reader.read(), 0);
+
+        writer.write(1);
+        writer.write(2);
+        assert!(reader.has_update());
+        assert_eq!(*reader.read(), 2);
+        assert!(!reader.has_update());
+        assert_eq!(*reader.read(), 2);
+
+        *writer.input_buffer() = 3;
+        assert_eq!(*reader.read(), 2);
+        writer.publish();
+        assert_eq!(*reader.read(), 3);
+    }
+
+    #[test]
+    fn works_across_threads() {
+        let (mut writer, mut reader) = triple_buffer([0u64; 8]);
+
+        let thread = std::thread::spawn(move || {
+            for i in 1..=10_000 {
+                writer.write([i; 8]);
+            }
+        });
+
+        let mut last = 0;
+        while last < 10_000 {
+            let value = *reader.read();
+
+            // Snapshots must never be torn, and never go back in time.
+            assert!(value.iter().all(|v| *v == value[0]));
+            assert!(value[0] >= last);
+            last = value[0];
+        }
+
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! The `frame-counter` extension, implemented for both hosts and plugins.
+//!
+//! This module contains everything needed to support this extension on both sides: the raw,
+//! C-compatible ABI definitions, the [`Extension`] types tying them to the extension's identifier,
+//! and the host-side and plugin-side wrappers and implementation traits.
+//!
+//! Published extension crates (such as `clack-extensions`) usually gate the [`host`] and [`plugin`]
+//! submodules behind `clack-host` and `clack-plugin` features respectively, so that plugins don't
+//! have to depend on `clack-host` and vice-versa.
+
+use clack_host::extensions::prelude::{
+    Extension, HostExtensionSide, PluginExtensionSide, RawExtension,
+};
+use clap_sys::host::clap_host;
+use clap_sys::plugin::clap_plugin;
+use std::ffi::CStr;
+
+/// The identifier of the `frame-counter` extension.
+///
+/// Third-party extension identifiers should use a reverse-DNS prefix the extension's author
+/// controls, to avoid collisions with other extensions.
+// SAFETY: the byte string is nul-terminated and has no interior nul bytes.
+pub const EXT_FRAME_COUNTER: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(b"org.rust-audio.clack.example.frame-counter\0") };
+
+/// The raw, C-compatible ABI exposed by the plugin side of the `frame-counter` extension.
+#[repr(C)]
+#[derive(Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct clack_plugin_frame_counter {
+    /// Returns the total number of frames the plugin has processed since it was activated.
+    ///
+    /// \[main-thread]
+    pub processed_frames: Option<unsafe extern "C" fn(plugin: *const clap_plugin) -> u64>,
+}
+
+/// The raw, C-compatible ABI exposed by the host side of the `frame-counter` extension.
+#[repr(C)]
+#[derive(Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct clack_host_frame_counter {
+    /// Informs the host that the plugin has processed a block of `frames_count` frames.
+    ///
+    /// \[thread-safe]
+    pub block_processed: Option<unsafe extern "C" fn(host: *const clap_host, frames_count: u32)>,
+}
+
+/// The plugin side of the `frame-counter` extension.
+///
+/// Hosts receive this type when querying a plugin for this extension.
+#[derive(Copy, Clone)]
+pub struct PluginFrameCounter(RawExtension<PluginExtensionSide, clack_plugin_frame_counter>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginFrameCounter {
+    const IDENTIFIER: &'static CStr = EXT_FRAME_COUNTER;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The host side of the `frame-counter` extension.
+///
+/// Plugins receive this type when querying a host for this extension.
+#[derive(Copy, Clone)]
+pub struct HostFrameCounter(RawExtension<HostExtensionSide, clack_host_frame_counter>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostFrameCounter {
+    const IDENTIFIER: &'static CStr = EXT_FRAME_COUNTER;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The host-side implementation of the extension.
+///
+/// This contains the methods hosts use to call into the plugin, as well as the trait hosts have to
+/// implement in order to expose this extension to plugins.
+pub mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginFrameCounter {
+        /// Returns the total number of frames the plugin has processed since it was activated.
+        ///
+        /// The underlying function is `[main-thread]`, so this requires a
+        /// [`PluginMainThreadHandle`] to be called.
+        #[inline]
+        pub fn processed_frames(&self, plugin: &mut PluginMainThreadHandle) -> u64 {
+            match plugin.use_extension(&self.0).processed_frames {
+                None => 0,
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(processed_frames) => unsafe { processed_frames(plugin.as_raw()) },
+            }
+        }
+    }
+
+    /// Implementation of the host side of the `frame-counter` extension.
+    ///
+    /// Because [`block_processed`](HostFrameCounterImpl::block_processed) is `[thread-safe]` (it is
+    /// called from the plugin's audio thread), this trait must be implemented on the host's
+    /// [`Shared`](HostHandlers::Shared) type.
+    pub trait HostFrameCounterImpl {
+        /// Called by the plugin every time it has processed a block of `frames_count` frames.
+        fn block_processed(&self, frames_count: u32);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostFrameCounter
+    where
+        for<'a> <H as HostHandlers>::Shared<'a>: HostFrameCounterImpl,
+    {
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clack_host_frame_counter {
+                block_processed: Some(block_processed::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn block_processed<H: HostHandlers>(host: *const clap_host, frames_count: u32)
+    where
+        for<'a> <H as HostHandlers>::Shared<'a>: HostFrameCounterImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.shared().block_processed(frames_count);
+            Ok(())
+        });
+    }
+}
+
+/// The plugin-side implementation of the extension.
+///
+/// This contains the methods plugins use to call into the host, as well as the trait plugins have
+/// to implement in order to expose this extension to hosts.
+pub mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    impl HostFrameCounter {
+        /// Informs the host that the plugin has processed a block of `frames_count` frames.
+        ///
+        /// The underlying function is `[thread-safe]`, so this only requires a
+        /// [`HostSharedHandle`], which can be obtained from any other host handle.
+        #[inline]
+        pub fn block_processed(&self, host: &HostSharedHandle, frames_count: u32) {
+            if let Some(block_processed) = host.use_extension(&self.0).block_processed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { block_processed(host.as_raw(), frames_count) }
+            }
+        }
+    }
+
+    /// Implementation of the plugin side of the `frame-counter` extension.
+    ///
+    /// Because [`processed_frames`](PluginFrameCounterImpl::processed_frames) is `[main-thread]`,
+    /// this trait must be implemented on the plugin's [`MainThread`](Plugin::MainThread) type.
+    pub trait PluginFrameCounterImpl {
+        /// Returns the total number of frames processed since the plugin was activated.
+        fn processed_frames(&mut self) -> u64;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginFrameCounter
+    where
+        for<'a> P::MainThread<'a>: PluginFrameCounterImpl,
+    {
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clack_plugin_frame_counter {
+                processed_frames: Some(processed_frames::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn processed_frames<P: Plugin>(plugin: *const clap_plugin) -> u64
+    where
+        for<'a> P::MainThread<'a>: PluginFrameCounterImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |plugin| {
+            Ok(plugin.main_thread().as_mut().processed_frames())
+        })
+        .unwrap_or(0)
+    }
+}
@@ -0,0 +1,6 @@
+#![doc(html_logo_url = "https://raw.githubusercontent.com/prokopyl/clack/main/logo.svg")]
+#![deny(missing_docs, clippy::undocumented_unsafe_blocks)]
+#![doc = include_str!("../README.md")]
+
+pub mod frame_counter;
+pub mod plugin;
@@ -0,0 +1,122 @@
+//! A plugin implementing the plugin side of the `frame-counter` extension, and using its host side.
+
+use crate::frame_counter::plugin::PluginFrameCounterImpl;
+use crate::frame_counter::{HostFrameCounter, PluginFrameCounter};
+use clack_plugin::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The type that represents our plugin in Clack.
+pub struct FrameCounterPlugin;
+
+impl Plugin for FrameCounterPlugin {
+    type AudioProcessor<'a> = FrameCounterPluginAudioProcessor<'a>;
+    type Shared<'a> = FrameCounterPluginShared;
+    type MainThread<'a> = FrameCounterPluginMainThread<'a>;
+
+    fn declare_extensions(
+        builder: &mut PluginExtensions<Self>,
+        _shared: Option<&FrameCounterPluginShared>,
+    ) {
+        // This only compiles because our MainThread type implements PluginFrameCounterImpl.
+        builder.register::<PluginFrameCounter>();
+    }
+}
+
+impl DefaultPluginFactory for FrameCounterPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        use clack_plugin::plugin::features::*;
+
+        PluginDescriptor::new(
+            "org.rust-audio.clack.example.frame-counter",
+            "Clack Frame Counter Example",
+        )
+        .with_features([ANALYZER])
+    }
+
+    fn new_shared(host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(FrameCounterPluginShared {
+            // The host's extensions can be queried as soon as the plugin is initializing.
+            // This is None if the host doesn't support the extension.
+            host_frame_counter: host.get_extension(),
+            processed_frames: AtomicU64::new(0),
+        })
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(FrameCounterPluginMainThread { shared })
+    }
+}
+
+/// The plugin data that gets shared between the Main Thread and the Audio Thread.
+pub struct FrameCounterPluginShared {
+    /// The host side of the extension, if the host supports it.
+    ///
+    /// Extension types are `Copy`, `Send` and `Sync`, so they can be stored and used anywhere.
+    host_frame_counter: Option<HostFrameCounter>,
+    /// The number of frames processed since activation.
+    processed_frames: AtomicU64,
+}
+
+impl PluginShared<'_> for FrameCounterPluginShared {}
+
+/// The data that belongs to the main thread of our plugin.
+pub struct FrameCounterPluginMainThread<'a> {
+    /// A reference to the plugin's shared data.
+    shared: &'a FrameCounterPluginShared,
+}
+
+impl<'a> PluginMainThread<'a, FrameCounterPluginShared> for FrameCounterPluginMainThread<'a> {}
+
+// The plugin side of the extension is [main-thread], so it is implemented on the MainThread type.
+impl PluginFrameCounterImpl for FrameCounterPluginMainThread<'_> {
+    fn processed_frames(&mut self) -> u64 {
+        self.shared.processed_frames.load(Ordering::Relaxed)
+    }
+}
+
+/// Our plugin's audio processor. It lives in the audio thread.
+pub struct FrameCounterPluginAudioProcessor<'a> {
+    /// The host handle, used to call the host side of the extension.
+    host: HostAudioProcessorHandle<'a>,
+    /// A reference to the plugin's shared data.
+    shared: &'a FrameCounterPluginShared,
+}
+
+impl<'a> PluginAudioProcessor<'a, FrameCounterPluginShared, FrameCounterPluginMainThread<'a>>
+    for FrameCounterPluginAudioProcessor<'a>
+{
+    fn activate(
+        host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut FrameCounterPluginMainThread,
+        shared: &'a FrameCounterPluginShared,
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        shared.processed_frames.store(0, Ordering::Relaxed);
+        Ok(Self { host, shared })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        let frames_count = audio.frames_count();
+
+        self.shared
+            .processed_frames
+            .fetch_add(frames_count as u64, Ordering::Relaxed);
+
+        // The host side of the extension is [thread-safe], so the audio thread can call it.
+        if let Some(host_frame_counter) = self.shared.host_frame_counter {
+            host_frame_counter.block_processed(&self.host, frames_count);
+        }
+
+        Ok(ProcessStatus::Sleep)
+    }
+}
+
+clack_export_entry!(SinglePluginEntry<FrameCounterPlugin>);
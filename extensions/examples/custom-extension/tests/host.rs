@@ -0,0 +1,131 @@
+use clack_example_custom_extension::frame_counter::host::HostFrameCounterImpl;
+use clack_example_custom_extension::frame_counter::{HostFrameCounter, PluginFrameCounter};
+use clack_host::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use clack_example_custom_extension::plugin::clap_entry;
+
+struct FrameCounterHostShared {
+    blocks_processed: AtomicU32,
+    frames_processed: AtomicU64,
+}
+
+impl SharedHandler<'_> for FrameCounterHostShared {
+    fn request_restart(&self) {
+        unimplemented!()
+    }
+
+    fn request_process(&self) {
+        unimplemented!()
+    }
+
+    fn request_callback(&self) {
+        unimplemented!()
+    }
+}
+
+// The host side of the extension is [thread-safe], so it is implemented on the Shared type.
+impl HostFrameCounterImpl for FrameCounterHostShared {
+    fn block_processed(&self, frames_count: u32) {
+        self.blocks_processed.fetch_add(1, Ordering::Relaxed);
+        self.frames_processed
+            .fetch_add(frames_count as u64, Ordering::Relaxed);
+    }
+}
+
+struct FrameCounterHost;
+
+impl HostHandlers for FrameCounterHost {
+    type Shared<'a> = FrameCounterHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        // This only compiles because our Shared type implements HostFrameCounterImpl.
+        builder.register::<HostFrameCounter>();
+    }
+}
+
+#[test]
+pub fn custom_extension_works_both_ways() {
+    let info = HostInfo::new("test", "", "", "").unwrap();
+
+    // SAFETY: only called this once here
+    let bundle = unsafe { PluginBundle::load_from_raw(&clap_entry, "") }.unwrap();
+
+    let mut instance = PluginInstance::<FrameCounterHost>::new(
+        |_| FrameCounterHostShared {
+            blocks_processed: AtomicU32::new(0),
+            frames_processed: AtomicU64::new(0),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"org.rust-audio.clack.example.frame-counter\0").unwrap(),
+        &info,
+    )
+    .unwrap();
+
+    // Query the plugin side of the extension.
+    let frame_counter = instance
+        .plugin_handle()
+        .get_extension::<PluginFrameCounter>()
+        .unwrap();
+
+    assert_eq!(
+        0,
+        frame_counter.processed_frames(&mut instance.plugin_handle())
+    );
+
+    let configuration = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 64,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), configuration)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut output_buffers = [vec![0f32; 64], vec![0f32; 64]];
+    let mut outputs_descriptors = AudioPorts::with_capacity(2, 1);
+
+    for frames_count in [64, 32, 16] {
+        let mut output_channels = outputs_descriptors.with_output_buffers([AudioPortBuffer {
+            channels: AudioPortBufferType::f32_output_only(
+                output_buffers.iter_mut().map(|b| &mut b[..frames_count]),
+            ),
+            latency: 0,
+        }]);
+
+        processor
+            .process(
+                &InputAudioBuffers::empty(),
+                &mut output_channels,
+                &InputEvents::empty(),
+                &mut OutputEvents::void(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // The plugin called the host side from its audio thread...
+    let shared = instance.access_shared_handler(|s| {
+        (
+            s.blocks_processed.load(Ordering::Relaxed),
+            s.frames_processed.load(Ordering::Relaxed),
+        )
+    });
+    assert_eq!(shared, (3, 112));
+
+    // ... and the host can call the plugin side from the main thread.
+    assert_eq!(
+        112,
+        frame_counter.processed_frames(&mut instance.plugin_handle())
+    );
+
+    instance.deactivate(processor.stop_processing());
+}
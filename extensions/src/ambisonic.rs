@@ -0,0 +1,17 @@
+//! Allows plugins and hosts to agree on the channel ordering and normalization of ambisonic
+//! audio ports.
+//!
+//! Ambisonic ports (with the [`AMBISONIC_PORT_TYPE`] port type) carry one channel per spherical
+//! harmonic component. Each component is labelled by an [`AmbisonicChannel`], and the
+//! [`AmbisonicRemap`] helper converts buffers between the host's and the plugin's
+//! [configurations](AmbisonicConfig).
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v3`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v3;
+
+pub use v3::*;
@@ -0,0 +1,387 @@
+//! Revision 3 of the Ambisonic draft extension (`clap.ambisonic.draft/3`).
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::ambisonic::*;
+use std::ffi::CStr;
+
+mod channels;
+pub use channels::*;
+
+/// The audio port type of ambisonic ports.
+pub const AMBISONIC_PORT_TYPE: &CStr = CLAP_PORT_AMBISONIC;
+
+/// The Plugin-side of the Ambisonic extension.
+#[derive(Copy, Clone)]
+pub struct PluginAmbisonic(RawExtension<PluginExtensionSide, clap_plugin_ambisonic>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginAmbisonic {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AMBISONIC;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginAmbisonic {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_ambisonic> {
+        self.0
+    }
+}
+
+/// The Host-side of the Ambisonic extension.
+#[derive(Copy, Clone)]
+pub struct HostAmbisonic(RawExtension<HostExtensionSide, clap_host_ambisonic>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostAmbisonic {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AMBISONIC;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl HostAmbisonic {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_ambisonic> {
+        self.0
+    }
+}
+
+/// The order in which the components of an ambisonic signal are laid out in its channels.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AmbisonicOrdering {
+    /// The Furse-Malham ordering (`W X Y Z R S T U V …`), only defined up to the third order.
+    FuMa,
+    /// The Ambisonic Channel Number ordering, where the component of degree `l` and index `m`
+    /// is in channel `l * (l + 1) + m`.
+    Acn,
+}
+
+impl AmbisonicOrdering {
+    /// Gets an [`AmbisonicOrdering`] from its raw, C-FFI compatible value.
+    ///
+    /// This returns `None` if the value is unknown.
+    #[inline]
+    pub const fn from_raw(raw: clap_ambisonic_ordering) -> Option<Self> {
+        match raw {
+            CLAP_AMBISONIC_ORDERING_FUMA => Some(Self::FuMa),
+            CLAP_AMBISONIC_ORDERING_ACN => Some(Self::Acn),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this ordering.
+    #[inline]
+    pub const fn to_raw(self) -> clap_ambisonic_ordering {
+        match self {
+            Self::FuMa => CLAP_AMBISONIC_ORDERING_FUMA,
+            Self::Acn => CLAP_AMBISONIC_ORDERING_ACN,
+        }
+    }
+}
+
+/// The normalization of the components of an ambisonic signal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AmbisonicNormalization {
+    /// Maximum normalization, as used by the Furse-Malham convention.
+    MaxN,
+    /// Schmidt semi-normalization, for 3D signals.
+    Sn3d,
+    /// Full normalization, for 3D signals.
+    N3d,
+    /// Schmidt semi-normalization, for 2D signals.
+    Sn2d,
+    /// Full normalization, for 2D signals.
+    N2d,
+}
+
+impl AmbisonicNormalization {
+    /// Gets an [`AmbisonicNormalization`] from its raw, C-FFI compatible value.
+    ///
+    /// This returns `None` if the value is unknown.
+    #[inline]
+    pub const fn from_raw(raw: clap_ambisonic_normalization) -> Option<Self> {
+        match raw {
+            CLAP_AMBISONIC_NORMALIZATION_MAXN => Some(Self::MaxN),
+            CLAP_AMBISONIC_NORMALIZATION_SN3D => Some(Self::Sn3d),
+            CLAP_AMBISONIC_NORMALIZATION_N3D => Some(Self::N3d),
+            CLAP_AMBISONIC_NORMALIZATION_SN2D => Some(Self::Sn2d),
+            CLAP_AMBISONIC_NORMALIZATION_N2D => Some(Self::N2d),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw, C-FFI compatible value of this normalization.
+    #[inline]
+    pub const fn to_raw(self) -> clap_ambisonic_normalization {
+        match self {
+            Self::MaxN => CLAP_AMBISONIC_NORMALIZATION_MAXN,
+            Self::Sn3d => CLAP_AMBISONIC_NORMALIZATION_SN3D,
+            Self::N3d => CLAP_AMBISONIC_NORMALIZATION_N3D,
+            Self::Sn2d => CLAP_AMBISONIC_NORMALIZATION_SN2D,
+            Self::N2d => CLAP_AMBISONIC_NORMALIZATION_N2D,
+        }
+    }
+}
+
+/// The configuration of an ambisonic port.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AmbisonicConfig {
+    /// The ordering of the components in the port's channels.
+    pub ordering: AmbisonicOrdering,
+    /// The normalization of the components.
+    pub normalization: AmbisonicNormalization,
+}
+
+impl AmbisonicConfig {
+    /// The AmbiX configuration (ACN ordering with SN3D normalization), which is the most common
+    /// one.
+    pub const AMBIX: Self = Self {
+        ordering: AmbisonicOrdering::Acn,
+        normalization: AmbisonicNormalization::Sn3d,
+    };
+
+    /// The Furse-Malham configuration (FuMa ordering with MaxN normalization).
+    pub const FUMA: Self = Self {
+        ordering: AmbisonicOrdering::FuMa,
+        normalization: AmbisonicNormalization::MaxN,
+    };
+
+    /// Gets an [`AmbisonicConfig`] from its raw, C-FFI compatible representation.
+    ///
+    /// This returns `None` if either the ordering or the normalization is unknown.
+    #[inline]
+    pub const fn from_raw(raw: &clap_ambisonic_config) -> Option<Self> {
+        let Some(ordering) = AmbisonicOrdering::from_raw(raw.ordering) else {
+            return None;
+        };
+        let Some(normalization) = AmbisonicNormalization::from_raw(raw.normalization) else {
+            return None;
+        };
+
+        Some(Self {
+            ordering,
+            normalization,
+        })
+    }
+
+    /// Returns the raw, C-FFI compatible representation of this configuration.
+    #[inline]
+    pub const fn to_raw(&self) -> clap_ambisonic_config {
+        clap_ambisonic_config {
+            ordering: self.ordering.to_raw(),
+            normalization: self.normalization.to_raw(),
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    impl PluginAmbisonic {
+        /// Returns `true` if the plugin supports the given ambisonic configuration.
+        pub fn is_config_supported(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            config: AmbisonicConfig,
+        ) -> bool {
+            crate::utils::trace_call!(plugin, "clap_plugin_ambisonic.is_config_supported");
+
+            match plugin.use_extension(&self.0).is_config_supported {
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(supported) => unsafe { supported(plugin.as_raw(), &config.to_raw()) },
+                None => false,
+            }
+        }
+
+        /// Returns the ambisonic configuration of the given port, or `None` if the plugin
+        /// failed to provide a valid one.
+        pub fn get_config(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            port_index: u32,
+        ) -> Option<AmbisonicConfig> {
+            crate::utils::trace_call!(plugin, "clap_plugin_ambisonic.get_config");
+
+            let get_config = plugin.use_extension(&self.0).get_config?;
+            let mut config = MaybeUninit::<clap_ambisonic_config>::zeroed();
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success =
+                unsafe { get_config(plugin.as_raw(), is_input, port_index, config.as_mut_ptr()) };
+
+            if success {
+                // SAFETY: the buffer is zero-initialized, which is a valid config.
+                AmbisonicConfig::from_raw(unsafe { config.assume_init_ref() })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Ambisonic extension.
+    pub trait HostAmbisonicImpl {
+        /// Informs the host that the plugin's ambisonic configurations have changed.
+        ///
+        /// The host must read the configurations again, which can only be done while the plugin
+        /// is deactivated.
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostAmbisonic
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostAmbisonicImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_ambisonic {
+                changed: Some(changed::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostAmbisonicImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    impl HostAmbisonic {
+        /// Informs the host that the plugin's ambisonic configurations have changed.
+        ///
+        /// This can only be called while the plugin is deactivated.
+        #[inline]
+        pub fn changed(&self, host: &mut HostMainThreadHandle) {
+            if let Some(changed) = host.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(host.as_raw()) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Ambisonic extension.
+    pub trait PluginAmbisonicImpl {
+        /// Returns `true` if the plugin supports the given ambisonic configuration.
+        fn is_config_supported(&mut self, config: AmbisonicConfig) -> bool;
+
+        /// Returns the ambisonic configuration of the given port, or `None` if the port isn't
+        /// an ambisonic port.
+        fn get_config(&mut self, is_input: bool, port_index: u32) -> Option<AmbisonicConfig>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginAmbisonic
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_ambisonic {
+                is_config_supported: Some(is_config_supported::<P>),
+                get_config: Some(get_config::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn is_config_supported<P: Plugin>(
+        plugin: *const clap_plugin,
+        config: *const clap_ambisonic_config,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let config = config
+                .as_ref()
+                .ok_or(PluginWrapperError::NulPtr("clap_ambisonic_config"))?;
+
+            // Configurations unknown to this implementation can't be supported.
+            let Some(config) = AmbisonicConfig::from_raw(config) else {
+                return Ok(false);
+            };
+
+            Ok(p.main_thread().as_mut().is_config_supported(config))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_config<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        port_index: u32,
+        info: *mut clap_ambisonic_config,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginAmbisonicImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if info.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_ambisonic_config"));
+            }
+
+            match p.main_thread().as_mut().get_config(is_input, port_index) {
+                Some(config) => {
+                    info.write(config.to_raw());
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_roundtrips() {
+        for config in [AmbisonicConfig::AMBIX, AmbisonicConfig::FUMA] {
+            assert_eq!(AmbisonicConfig::from_raw(&config.to_raw()), Some(config));
+        }
+
+        let unknown = clap_ambisonic_config {
+            ordering: 42,
+            normalization: CLAP_AMBISONIC_NORMALIZATION_N3D,
+        };
+        assert_eq!(AmbisonicConfig::from_raw(&unknown), None);
+    }
+}
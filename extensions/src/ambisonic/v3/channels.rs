@@ -0,0 +1,286 @@
+use super::*;
+
+/// The ACN index of each FuMa channel, up to the third order.
+const FUMA_TO_ACN: [u32; 16] = [0, 3, 1, 2, 6, 7, 5, 8, 4, 12, 13, 11, 14, 10, 15, 9];
+
+/// The highest order the FuMa ordering and normalization are defined for.
+const FUMA_MAX_ORDER: u32 = 3;
+
+/// The component of an ambisonic signal carried by a channel, i.e. a spherical harmonic of a
+/// given degree and index.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AmbisonicChannel {
+    /// The degree `l` of the spherical harmonic, which is its ambisonic order.
+    pub degree: u32,
+    /// The index `m` of the spherical harmonic, in `-l..=l`.
+    pub index: i32,
+}
+
+impl AmbisonicChannel {
+    /// Returns the component with the given Ambisonic Channel Number.
+    pub fn from_acn(acn: u32) -> Self {
+        let degree = integer_sqrt(acn);
+        let index = acn as i64 - (degree as i64 * (degree as i64 + 1));
+
+        Self {
+            degree,
+            index: index as i32,
+        }
+    }
+
+    /// Returns the Ambisonic Channel Number of this component.
+    #[inline]
+    pub fn acn(self) -> u32 {
+        (self.degree * (self.degree + 1)).wrapping_add_signed(self.index)
+    }
+
+    /// Returns the component at the given channel index in a signal using the given ordering,
+    /// or `None` if that ordering doesn't define it.
+    pub fn at(ordering: AmbisonicOrdering, channel_index: usize) -> Option<Self> {
+        match ordering {
+            AmbisonicOrdering::Acn => Some(Self::from_acn(u32::try_from(channel_index).ok()?)),
+            AmbisonicOrdering::FuMa => FUMA_TO_ACN.get(channel_index).map(|&i| Self::from_acn(i)),
+        }
+    }
+
+    /// Returns the channel index of this component in a signal using the given ordering, or
+    /// `None` if that ordering doesn't define it.
+    pub fn channel_index(self, ordering: AmbisonicOrdering) -> Option<usize> {
+        match ordering {
+            AmbisonicOrdering::Acn => Some(self.acn() as usize),
+            AmbisonicOrdering::FuMa => FUMA_TO_ACN.iter().position(|&acn| acn == self.acn()),
+        }
+    }
+
+    /// Returns the gain to apply to this component to convert it from the given normalization
+    /// to SN3D, or `None` if this conversion isn't supported.
+    fn sn3d_gain(self, normalization: AmbisonicNormalization) -> Option<f64> {
+        match normalization {
+            AmbisonicNormalization::Sn3d => Some(1.0),
+            AmbisonicNormalization::N3d => Some(1.0 / ((2 * self.degree + 1) as f64).sqrt()),
+            AmbisonicNormalization::MaxN => Some(match (self.degree, self.index.unsigned_abs()) {
+                (0, _) => std::f64::consts::SQRT_2,
+                (1, _) | (2, 0) | (3, 0) => 1.0,
+                (2, _) => 2.0 / 3f64.sqrt(),
+                (3, 1) => (45.0f64 / 32.0).sqrt(),
+                (3, 2) => 3.0 / 5f64.sqrt(),
+                (3, _) => (8.0f64 / 5.0).sqrt(),
+                _ => return None,
+            }),
+            AmbisonicNormalization::Sn2d | AmbisonicNormalization::N2d => None,
+        }
+    }
+}
+
+/// Returns the ambisonic order of a full-sphere signal with the given number of channels, or
+/// `None` if no order has this number of channels.
+pub fn ambisonic_order(channel_count: usize) -> Option<u32> {
+    let order_plus_one = integer_sqrt(u32::try_from(channel_count).ok()?);
+
+    (order_plus_one > 0 && (order_plus_one * order_plus_one) as usize == channel_count)
+        .then(|| order_plus_one - 1)
+}
+
+fn integer_sqrt(value: u32) -> u32 {
+    let mut root = (value as f64).sqrt() as u32;
+
+    // Correct any floating-point rounding. Squares that overflow are always larger than value.
+    while root.checked_mul(root).map_or(true, |square| square > value) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= value)
+    {
+        root += 1;
+    }
+
+    root
+}
+
+/// A mapping converting the channels of an ambisonic signal from one [`AmbisonicConfig`] to
+/// another, e.g. from the host's configuration to the one a plugin declared for its port.
+///
+/// Each destination channel is fed by the source channel carrying the same component, with the
+/// gain required to convert between both normalizations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmbisonicRemap {
+    sources: Vec<(usize, f32)>,
+}
+
+impl AmbisonicRemap {
+    /// Creates a mapping from a full-sphere signal with the given number of channels in the
+    /// `source` configuration, to the same signal in the `destination` configuration.
+    ///
+    /// This returns `None` if the channel count doesn't match a full-sphere ambisonic order, if
+    /// either configuration uses the FuMa ordering or MaxN normalization above the third order,
+    /// or if the conversion involves a 2D normalization, which is not supported.
+    pub fn new(
+        source: AmbisonicConfig,
+        destination: AmbisonicConfig,
+        channel_count: usize,
+    ) -> Option<Self> {
+        let order = ambisonic_order(channel_count)?;
+
+        let uses_fuma = |config: AmbisonicConfig| {
+            config.ordering == AmbisonicOrdering::FuMa
+                || config.normalization == AmbisonicNormalization::MaxN
+        };
+        if order > FUMA_MAX_ORDER && (uses_fuma(source) || uses_fuma(destination)) {
+            return None;
+        }
+
+        let same_normalization = source.normalization == destination.normalization;
+
+        let sources = (0..channel_count)
+            .map(|index| {
+                let channel = AmbisonicChannel::at(destination.ordering, index)?;
+                let source_index = channel.channel_index(source.ordering)?;
+
+                let gain = if same_normalization {
+                    1.0
+                } else {
+                    channel.sn3d_gain(source.normalization)?
+                        / channel.sn3d_gain(destination.normalization)?
+                };
+
+                Some((source_index, gain as f32))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { sources })
+    }
+
+    /// Returns the index of the source channel feeding each destination channel, alongside the
+    /// gain to apply to it.
+    #[inline]
+    pub fn sources(&self) -> &[(usize, f32)] {
+        &self.sources
+    }
+
+    /// Returns `true` if both configurations are identical, i.e. if this mapping copies every
+    /// channel as-is.
+    pub fn is_identity(&self) -> bool {
+        (self.sources.iter().enumerate())
+            .all(|(index, &(source, gain))| source == index && gain == 1.0)
+    }
+
+    /// Converts the samples of the source channel buffers into the destination channel buffers,
+    /// following this mapping.
+    ///
+    /// Destination channels whose source channel is missing from `source` are silenced. If
+    /// buffers have different lengths, only their common part is converted, and the rest of each
+    /// destination buffer is left untouched.
+    pub fn copy(&self, source: &[impl AsRef<[f32]>], destination: &mut [impl AsMut<[f32]>]) {
+        for (dst, &(index, gain)) in destination.iter_mut().zip(&self.sources) {
+            let dst = dst.as_mut();
+
+            match source.get(index) {
+                Some(src) => {
+                    for (dst, src) in dst.iter_mut().zip(src.as_ref()) {
+                        *dst = *src * gain;
+                    }
+                }
+                None => dst.fill(0.0),
+            }
+        }
+    }
+
+    /// Converts the samples of the source channel buffers into the destination channel buffers,
+    /// following this mapping.
+    ///
+    /// This is the same as [`copy`](Self::copy), but for 64-bit buffers.
+    pub fn copy_f64(&self, source: &[impl AsRef<[f64]>], destination: &mut [impl AsMut<[f64]>]) {
+        for (dst, &(index, gain)) in destination.iter_mut().zip(&self.sources) {
+            let dst = dst.as_mut();
+
+            match source.get(index) {
+                Some(src) => {
+                    for (dst, src) in dst.iter_mut().zip(src.as_ref()) {
+                        *dst = *src * gain as f64;
+                    }
+                }
+                None => dst.fill(0.0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn labels_channels() {
+        let channel = AmbisonicChannel::from_acn(6);
+        assert_eq!(
+            channel,
+            AmbisonicChannel {
+                degree: 2,
+                index: 0
+            }
+        );
+        assert_eq!(channel.acn(), 6);
+        assert_eq!(channel.channel_index(AmbisonicOrdering::FuMa), Some(4));
+
+        for acn in 0..64 {
+            assert_eq!(AmbisonicChannel::from_acn(acn).acn(), acn);
+        }
+
+        // FuMa's X is the front-back component, i.e. ACN 3.
+        let x = AmbisonicChannel::at(AmbisonicOrdering::FuMa, 1).unwrap();
+        assert_eq!(
+            x,
+            AmbisonicChannel {
+                degree: 1,
+                index: 1
+            }
+        );
+        assert_eq!(AmbisonicChannel::at(AmbisonicOrdering::FuMa, 16), None);
+
+        assert_eq!(ambisonic_order(1), Some(0));
+        assert_eq!(ambisonic_order(16), Some(3));
+        assert_eq!(ambisonic_order(5), None);
+        assert_eq!(ambisonic_order(0), None);
+        assert_eq!(integer_sqrt(u32::MAX), 65_535);
+        assert_eq!(ambisonic_order(usize::MAX), None);
+    }
+
+    #[test]
+    fn converts_fuma_to_ambix() {
+        let remap = AmbisonicRemap::new(AmbisonicConfig::FUMA, AmbisonicConfig::AMBIX, 4).unwrap();
+        assert!(!remap.is_identity());
+
+        // AmbiX is W Y Z X, FuMa is W X Y Z, with W attenuated by 3dB.
+        let indices: Vec<_> = remap.sources().iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, [0, 2, 3, 1]);
+
+        let source = [[0.5f32; 2], [1.0; 2], [2.0; 2], [3.0; 2]];
+        let mut destination = [[0.0f32; 2]; 4];
+        remap.copy(&source, &mut destination);
+        assert!((destination[0][0] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(destination[1..], [[2.0; 2], [3.0; 2], [1.0; 2]]);
+
+        let identity =
+            AmbisonicRemap::new(AmbisonicConfig::AMBIX, AmbisonicConfig::AMBIX, 25).unwrap();
+        assert!(identity.is_identity());
+
+        assert!(AmbisonicRemap::new(AmbisonicConfig::AMBIX, AmbisonicConfig::FUMA, 25).is_none());
+        assert!(AmbisonicRemap::new(AmbisonicConfig::AMBIX, AmbisonicConfig::AMBIX, 3).is_none());
+    }
+
+    #[test]
+    fn converts_sn3d_to_n3d() {
+        let n3d = AmbisonicConfig {
+            ordering: AmbisonicOrdering::Acn,
+            normalization: AmbisonicNormalization::N3d,
+        };
+
+        let remap = AmbisonicRemap::new(AmbisonicConfig::AMBIX, n3d, 9).unwrap();
+        let gains: Vec<_> = remap.sources().iter().map(|(_, g)| *g).collect();
+
+        assert_eq!(gains[0], 1.0);
+        assert!((gains[1] - 3f32.sqrt()).abs() < 1e-6);
+        assert!((gains[8] - 5f32.sqrt()).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,18 @@
+//! Allows hosts and plugins supporting [ARA](https://www.celemony.com/en/service1/about-celemony/technologies)
+//! (Audio Random Access) to exchange their ARA entry points.
+//!
+//! ARA itself is a separate API, defined by the ARA SDK: this module only provides the bindings
+//! of its CLAP integration, i.e. the [`PluginAraFactory`] exposed by plugin bundles to give hosts
+//! access to their ARA factories, and the [`PluginAraExtension`] used to bind plugin instances to
+//! an ARA document controller. All ARA objects are passed around as opaque pointers (e.g.
+//! [`AraFactory`]), to be used with the ARA SDK.
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v2`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v2;
+
+pub use v2::*;
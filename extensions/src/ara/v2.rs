@@ -0,0 +1,306 @@
+//! Revision 2 of the ARA integration (`org.ara-audio.ara.factory/2` and
+//! `org.ara-audio.ara.pluginextension/2`).
+//!
+//! The raw C types are defined here, as they are not part of the CLAP headers: they match the
+//! ones from the `ARACLAP.h` header of the ARA SDK.
+
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use clap_sys::plugin::clap_plugin;
+use std::ffi::{c_char, c_void, CStr};
+use std::ptr::NonNull;
+
+#[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+mod factory;
+#[cfg(any(feature = "clack-host", feature = "clack-plugin"))]
+pub use factory::*;
+
+/// The identifier of the ARA factory.
+pub const CLAP_EXT_ARA_FACTORY: &CStr =
+    match CStr::from_bytes_with_nul(b"org.ara-audio.ara.factory/2\0") {
+        Ok(id) => id,
+        Err(_) => panic!(),
+    };
+
+/// The identifier of the ARA plugin extension.
+pub const CLAP_EXT_ARA_PLUGINEXTENSION: &CStr =
+    match CStr::from_bytes_with_nul(b"org.ara-audio.ara.pluginextension/2\0") {
+        Ok(id) => id,
+        Err(_) => panic!(),
+    };
+
+/// The raw ARA factory struct, as defined by the ARA SDK.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_ara_factory {
+    /// Returns the number of ARA factories exposed by the bundle.
+    pub get_factory_count: Option<unsafe extern "C" fn(factory: *const clap_ara_factory) -> u32>,
+    /// Returns the `ARAFactory` at the given index.
+    pub get_ara_factory:
+        Option<unsafe extern "C" fn(factory: *const clap_ara_factory, index: u32) -> *const c_void>,
+    /// Returns the ID of the CLAP plugin matching the `ARAFactory` at the given index.
+    pub get_plugin_id:
+        Option<unsafe extern "C" fn(factory: *const clap_ara_factory, index: u32) -> *const c_char>,
+}
+
+/// The raw ARA plugin extension struct, as defined by the ARA SDK.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct clap_ara_plugin_extension {
+    /// Returns the `ARAFactory` of the plugin.
+    pub get_factory: Option<unsafe extern "C" fn(plugin: *const clap_plugin) -> *const c_void>,
+    /// Binds the plugin to the given `ARADocumentControllerRef`, and returns its
+    /// `ARAPlugInExtensionInstance`.
+    pub bind_to_document_controller: Option<
+        unsafe extern "C" fn(
+            plugin: *const clap_plugin,
+            document_controller_ref: *mut c_void,
+            known_roles: i32,
+            assigned_roles: i32,
+        ) -> *const c_void,
+    >,
+}
+
+/// A pointer to an ARA factory (`ARAFactory`), as defined by the ARA SDK.
+///
+/// ARA factories are static, immutable structs, which remain valid for as long as the bundle
+/// exposing them is loaded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AraFactory(NonNull<c_void>);
+
+// SAFETY: ARA factories are immutable, and can be accessed from any thread.
+unsafe impl Send for AraFactory {}
+// SAFETY: ARA factories are immutable, and can be accessed from any thread.
+unsafe impl Sync for AraFactory {}
+
+impl AraFactory {
+    /// Wraps a raw pointer to an `ARAFactory`, or returns `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point to a valid `ARAFactory`, which must remain valid for as long as
+    /// this type is used.
+    #[inline]
+    pub unsafe fn from_raw(raw: *const c_void) -> Option<Self> {
+        NonNull::new(raw as *mut c_void).map(Self)
+    }
+
+    /// Returns the raw pointer to the `ARAFactory`.
+    #[inline]
+    pub fn as_raw(&self) -> *const c_void {
+        self.0.as_ptr()
+    }
+}
+
+/// A pointer to the ARA plugin extension of a plugin instance (`ARAPlugInExtensionInstance`),
+/// as defined by the ARA SDK.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AraPlugInExtensionInstance(NonNull<c_void>);
+
+impl AraPlugInExtensionInstance {
+    /// Wraps a raw pointer to an `ARAPlugInExtensionInstance`, or returns `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point to a valid `ARAPlugInExtensionInstance`, which must remain valid
+    /// for as long as this type is used.
+    #[inline]
+    pub unsafe fn from_raw(raw: *const c_void) -> Option<Self> {
+        NonNull::new(raw as *mut c_void).map(Self)
+    }
+
+    /// Returns the raw pointer to the `ARAPlugInExtensionInstance`.
+    #[inline]
+    pub fn as_raw(&self) -> *const c_void {
+        self.0.as_ptr()
+    }
+}
+
+/// A reference to an ARA document controller (`ARADocumentControllerRef`), as defined by the
+/// ARA SDK.
+///
+/// This is an opaque pointer created by the plugin's ARA implementation, which is never
+/// dereferenced by the host.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AraDocumentControllerRef(NonNull<c_void>);
+
+impl AraDocumentControllerRef {
+    /// Wraps a raw `ARADocumentControllerRef`, or returns `None` if it is null.
+    #[inline]
+    pub fn from_raw(raw: *mut c_void) -> Option<Self> {
+        NonNull::new(raw).map(Self)
+    }
+
+    /// Returns the raw `ARADocumentControllerRef`.
+    #[inline]
+    pub fn as_raw(&self) -> *mut c_void {
+        self.0.as_ptr()
+    }
+}
+
+bitflags! {
+    /// The roles a plugin instance can take in an ARA document (`ARAPlugInInstanceRoleFlags`).
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct AraPlugInInstanceRoles: i32 {
+        /// The instance renders the playback of the ARA document.
+        const PLAYBACK_RENDERER = 1 << 0;
+        /// The instance renders the previews of edits made to the ARA document.
+        const EDITOR_RENDERER = 1 << 1;
+        /// The instance displays the editor of the ARA document.
+        const EDITOR_VIEW = 1 << 2;
+    }
+}
+
+/// The Plugin-side of the ARA plugin extension.
+#[derive(Copy, Clone)]
+pub struct PluginAraExtension(RawExtension<PluginExtensionSide, clap_ara_plugin_extension>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginAraExtension {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_ARA_PLUGINEXTENSION;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginAraExtension {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_ara_plugin_extension> {
+        self.0
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginAraExtension {
+        /// Returns the ARA factory of the plugin, if it has one.
+        pub fn get_factory(&self, plugin: &mut PluginMainThreadHandle) -> Option<AraFactory> {
+            crate::utils::trace_call!(plugin, "clap_ara_plugin_extension.get_factory");
+
+            let get_factory = plugin.use_extension(&self.0).get_factory?;
+
+            // SAFETY: This type ensures the function pointer is valid. The plugin ensures the
+            // returned factory remains valid while its bundle is loaded.
+            unsafe { AraFactory::from_raw(get_factory(plugin.as_raw())) }
+        }
+
+        /// Binds the plugin to the given ARA document controller, with the given roles, and
+        /// returns the ARA plugin extension of this plugin instance.
+        ///
+        /// `known_roles` are all the roles the host knows about and assigns to its plugin
+        /// instances, and `assigned_roles` are the ones this specific instance takes.
+        ///
+        /// This returns `None` if the plugin failed to bind to the document controller. This can
+        /// only be called once per plugin instance.
+        ///
+        /// # Safety
+        ///
+        /// The given document controller must be valid, and must have been created from the
+        /// plugin's ARA factory, as returned by [`get_factory`](Self::get_factory).
+        pub unsafe fn bind_to_document_controller(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            document_controller: AraDocumentControllerRef,
+            known_roles: AraPlugInInstanceRoles,
+            assigned_roles: AraPlugInInstanceRoles,
+        ) -> Option<AraPlugInExtensionInstance> {
+            let bind = plugin.use_extension(&self.0).bind_to_document_controller?;
+
+            let instance = bind(
+                plugin.as_raw(),
+                document_controller.as_raw(),
+                known_roles.bits(),
+                assigned_roles.bits(),
+            );
+
+            AraPlugInExtensionInstance::from_raw(instance)
+        }
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    /// Implementation of the Plugin-side of the ARA plugin extension.
+    pub trait PluginAraExtensionImpl {
+        /// Returns the ARA factory of the plugin.
+        fn get_factory(&mut self) -> Option<AraFactory>;
+
+        /// Binds the plugin to the given ARA document controller, with the given roles, and
+        /// returns the ARA plugin extension of this plugin instance.
+        ///
+        /// `known_roles` are all the roles the host knows about and assigns to its plugin
+        /// instances, and `assigned_roles` are the ones this specific instance takes.
+        fn bind_to_document_controller(
+            &mut self,
+            document_controller: AraDocumentControllerRef,
+            known_roles: AraPlugInInstanceRoles,
+            assigned_roles: AraPlugInInstanceRoles,
+        ) -> Option<AraPlugInExtensionInstance>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginAraExtension
+    where
+        for<'a> P::MainThread<'a>: PluginAraExtensionImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_ara_plugin_extension {
+                get_factory: Some(get_factory::<P>),
+                bind_to_document_controller: Some(bind_to_document_controller::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_factory<P: Plugin>(plugin: *const clap_plugin) -> *const c_void
+    where
+        for<'a> P::MainThread<'a>: PluginAraExtensionImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().get_factory()))
+            .flatten()
+            .map_or(core::ptr::null(), |factory| factory.as_raw())
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn bind_to_document_controller<P: Plugin>(
+        plugin: *const clap_plugin,
+        document_controller_ref: *mut c_void,
+        known_roles: i32,
+        assigned_roles: i32,
+    ) -> *const c_void
+    where
+        for<'a> P::MainThread<'a>: PluginAraExtensionImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let document_controller = AraDocumentControllerRef::from_raw(document_controller_ref)
+                .ok_or(PluginWrapperError::NulPtr("documentControllerRef"))?;
+
+            Ok(p.main_thread().as_mut().bind_to_document_controller(
+                document_controller,
+                AraPlugInInstanceRoles::from_bits_retain(known_roles),
+                AraPlugInInstanceRoles::from_bits_retain(assigned_roles),
+            ))
+        })
+        .flatten()
+        .map_or(core::ptr::null(), |instance| instance.as_raw())
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
@@ -0,0 +1,268 @@
+use super::*;
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::factory::FactoryPointer;
+
+    /// The host-side of the ARA factory, exposed by plugin bundles supporting ARA.
+    ///
+    /// This lists the [ARA factories](AraFactory) of the bundle, alongside the ID of the CLAP
+    /// plugin each of them belongs to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_extensions::ara::PluginAraFactory;
+    /// use clack_host::prelude::*;
+    ///
+    /// fn list_ara_plugins(bundle: &PluginBundle) {
+    ///     if let Some(ara_factory) = bundle.get_factory::<PluginAraFactory>() {
+    ///         for (plugin_id, factory) in ara_factory.factories() {
+    ///             println!("{plugin_id:?} supports ARA: {:?}", factory.as_raw());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct PluginAraFactory<'a> {
+        inner: &'a clap_ara_factory,
+    }
+
+    // SAFETY: This takes a clap_ara_factory pointer, which matches CLAP_EXT_ARA_FACTORY.
+    unsafe impl<'a> FactoryPointer<'a> for PluginAraFactory<'a> {
+        const IDENTIFIER: &'static CStr = CLAP_EXT_ARA_FACTORY;
+
+        #[inline]
+        unsafe fn from_raw(raw: NonNull<c_void>) -> Self {
+            Self {
+                inner: raw.cast().as_ref(),
+            }
+        }
+    }
+
+    impl<'a> PluginAraFactory<'a> {
+        /// Returns a raw pointer to the C-FFI compatible ARA factory struct.
+        #[inline]
+        pub fn as_raw(&self) -> *const clap_ara_factory {
+            self.inner
+        }
+
+        /// Returns the number of ARA factories exposed by this bundle.
+        #[inline]
+        pub fn factory_count(&self) -> u32 {
+            match self.inner.get_factory_count {
+                None => 0,
+                // SAFETY: this type ensures the function pointer is valid
+                Some(count) => unsafe { count(self.inner) },
+            }
+        }
+
+        /// Returns the ARA factory at the given index, or `None` if there is none.
+        pub fn get_factory(&self, index: u32) -> Option<AraFactory> {
+            let get_ara_factory = self.inner.get_ara_factory?;
+
+            // SAFETY: this type ensures the function pointer is valid, and the returned factory
+            // remains valid while the bundle is loaded.
+            unsafe { AraFactory::from_raw(get_ara_factory(self.inner, index)) }
+        }
+
+        /// Returns the ID of the CLAP plugin the ARA factory at the given index belongs to, or
+        /// `None` if there is none.
+        pub fn plugin_id(&self, index: u32) -> Option<&'a CStr> {
+            let get_plugin_id = self.inner.get_plugin_id?;
+
+            // SAFETY: this type ensures the function pointer is valid.
+            let plugin_id = unsafe { get_plugin_id(self.inner, index) };
+            if plugin_id.is_null() {
+                return None;
+            }
+
+            // SAFETY: the plugin ID is a valid C string, which remains valid while the bundle is
+            // loaded.
+            Some(unsafe { CStr::from_ptr(plugin_id) })
+        }
+
+        /// Returns the ARA factory of the CLAP plugin with the given ID, if it has one.
+        pub fn find(&self, plugin_id: &CStr) -> Option<AraFactory> {
+            (0..self.factory_count())
+                .find(|&index| self.plugin_id(index) == Some(plugin_id))
+                .and_then(|index| self.get_factory(index))
+        }
+
+        /// Returns an iterator of all the ARA factories exposed by this bundle, alongside the ID of
+        /// the CLAP plugin each of them belongs to.
+        ///
+        /// Invalid entries, without a plugin ID or an ARA factory, are skipped.
+        pub fn factories(&self) -> impl Iterator<Item = (&'a CStr, AraFactory)> + 'a {
+            let factory = *self;
+
+            (0..self.factory_count()).filter_map(move |index| {
+                Some((factory.plugin_id(index)?, factory.get_factory(index)?))
+            })
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::factory::Factory;
+    use std::panic::AssertUnwindSafe;
+
+    /// An ARA factory implementation, exposing the ARA factories of the plugins of a bundle.
+    ///
+    /// To be exposed to the host, this must be wrapped in an [`AraFactoryWrapper`], and declared
+    /// by the bundle's [`Entry`](clack_plugin::entry::Entry).
+    pub trait AraFactoryImpl: Send + Sync {
+        /// Returns the number of ARA factories exposed by this factory.
+        fn factory_count(&self) -> u32;
+
+        /// Returns the ARA factory at the given index, or `None` if the index is out of bounds.
+        fn get_factory(&self, index: u32) -> Option<AraFactory>;
+
+        /// Returns the ID of the CLAP plugin the ARA factory at the given index belongs to, or
+        /// `None` if the index is out of bounds.
+        fn plugin_id(&self, index: u32) -> Option<&CStr>;
+    }
+
+    /// A wrapper around a given [`AraFactoryImpl`] implementation.
+    ///
+    /// This wrapper is required in order to expose a C FFI-compatible factory to the host, and is
+    /// what needs to be exposed by an [`Entry`](clack_plugin::entry::Entry), using
+    /// [`EntryFactories::register_factory`](clack_plugin::entry::EntryFactories::register_factory).
+    #[repr(C)]
+    pub struct AraFactoryWrapper<F> {
+        raw: clap_ara_factory,
+        factory: F,
+    }
+
+    impl<F: AraFactoryImpl> AraFactoryWrapper<F> {
+        /// Wraps a given [`AraFactoryImpl`] instance.
+        pub const fn new(factory: F) -> Self {
+            Self {
+                raw: clap_ara_factory {
+                    get_factory_count: Some(Self::get_factory_count),
+                    get_ara_factory: Some(Self::get_ara_factory),
+                    get_plugin_id: Some(Self::get_plugin_id),
+                },
+                factory,
+            }
+        }
+
+        /// Returns a shared reference to the wrapped [`AraFactoryImpl`].
+        #[inline]
+        pub fn factory(&self) -> &F {
+            &self.factory
+        }
+
+        /// Returns a raw CLAP ARA factory pointer, ready to be used by the host.
+        #[inline]
+        pub fn as_raw_ptr(&self) -> *const clap_ara_factory {
+            &self.raw
+        }
+
+        /// # Safety
+        /// The factory pointer must be valid (but it can be null)
+        unsafe fn handle<T>(
+            raw: *const clap_ara_factory,
+            handler: impl FnOnce(&F) -> Option<T>,
+        ) -> Option<T> {
+            let factory = (raw as *const Self).as_ref()?;
+
+            std::panic::catch_unwind(AssertUnwindSafe(|| handler(&factory.factory)))
+                .ok()
+                .flatten()
+        }
+
+        #[allow(clippy::missing_safety_doc)]
+        unsafe extern "C" fn get_factory_count(factory: *const clap_ara_factory) -> u32 {
+            Self::handle(factory, |factory| Some(factory.factory_count())).unwrap_or(0)
+        }
+
+        #[allow(clippy::missing_safety_doc)]
+        unsafe extern "C" fn get_ara_factory(
+            factory: *const clap_ara_factory,
+            index: u32,
+        ) -> *const c_void {
+            Self::handle(factory, |factory| factory.get_factory(index))
+                .map_or(core::ptr::null(), |factory| factory.as_raw())
+        }
+
+        #[allow(clippy::missing_safety_doc)]
+        unsafe extern "C" fn get_plugin_id(
+            factory: *const clap_ara_factory,
+            index: u32,
+        ) -> *const c_char {
+            Self::handle(factory, |factory| {
+                factory.plugin_id(index).map(CStr::as_ptr)
+            })
+            .unwrap_or(core::ptr::null())
+        }
+    }
+
+    // SAFETY: AraFactoryWrapper is #[repr(C)] with clap_ara_factory as its first field, and
+    // matches CLAP_EXT_ARA_FACTORY.
+    unsafe impl<F> Factory for AraFactoryWrapper<F> {
+        const IDENTIFIER: &'static CStr = CLAP_EXT_ARA_FACTORY;
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(all(test, feature = "clack-host", feature = "clack-plugin"))]
+mod test {
+    use super::*;
+    use clack_host::factory::FactoryPointer;
+    use clack_plugin::factory::Factory;
+
+    static ARA_FACTORY: u64 = 42;
+
+    struct MyAraFactory;
+
+    impl AraFactoryImpl for MyAraFactory {
+        fn factory_count(&self) -> u32 {
+            2
+        }
+
+        fn get_factory(&self, index: u32) -> Option<AraFactory> {
+            match index {
+                // SAFETY: this is only used as an opaque pointer.
+                0 => unsafe { AraFactory::from_raw((&ARA_FACTORY as *const u64).cast()) },
+                _ => None,
+            }
+        }
+
+        fn plugin_id(&self, index: u32) -> Option<&CStr> {
+            match index {
+                0 => CStr::from_bytes_with_nul(b"com.example.ara-plugin\0").ok(),
+                1 => CStr::from_bytes_with_nul(b"com.example.broken\0").ok(),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn factory_roundtrip() {
+        let wrapper = AraFactoryWrapper::new(MyAraFactory);
+        // SAFETY: the wrapper is a valid clap_ara_factory.
+        let factory = unsafe { PluginAraFactory::from_raw(wrapper.get_raw_factory_ptr()) };
+
+        assert_eq!(factory.factory_count(), 2);
+        assert!(factory.get_factory(1).is_none());
+        assert!(factory.plugin_id(2).is_none());
+
+        let plugin_id = CStr::from_bytes_with_nul(b"com.example.ara-plugin\0").unwrap();
+        let expected = factory.get_factory(0).unwrap();
+        assert_eq!(expected.as_raw(), (&ARA_FACTORY as *const u64).cast());
+        assert_eq!(factory.find(plugin_id), Some(expected));
+
+        let factories: Vec<_> = factory.factories().collect();
+        assert_eq!(factories, [(plugin_id, expected)]);
+    }
+}
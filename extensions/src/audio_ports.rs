@@ -6,11 +6,9 @@ use std::ffi::CStr;
 use std::fmt::{Debug, Formatter};
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginAudioPorts(RawExtension<PluginExtensionSide, clap_plugin_audio_ports>);
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostAudioPorts(RawExtension<HostExtensionSide, clap_host_audio_ports>);
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -34,11 +32,19 @@ bitflags! {
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct RescanType: u32 {
+        /// The names of the ports changed. This can be requested at any time, and the host only
+        /// needs to read the port names again.
         const NAMES = CLAP_AUDIO_PORTS_RESCAN_NAMES;
+        /// The flags of the ports changed. The plugin must be deactivated.
         const FLAGS = CLAP_AUDIO_PORTS_RESCAN_FLAGS;
+        /// The channel counts of the ports changed. The plugin must be deactivated.
         const CHANNEL_COUNT = CLAP_AUDIO_PORTS_RESCAN_CHANNEL_COUNT;
+        /// The types of the ports changed. The plugin must be deactivated.
         const PORT_TYPE = CLAP_AUDIO_PORTS_RESCAN_PORT_TYPE;
+        /// The in-place pairs of the ports changed. The plugin must be deactivated.
         const IN_PLACE_PAIR = CLAP_AUDIO_PORTS_RESCAN_IN_PLACE_PAIR;
+        /// The list of ports changed: the host must scan all the ports again, and their IDs
+        /// may have changed. The plugin must be deactivated.
         const LIST = CLAP_AUDIO_PORTS_RESCAN_LIST;
     }
 }
@@ -54,12 +60,20 @@ impl RescanType {
         const RESTART_REQUIRED: RescanType = RescanType::FLAGS
             .union(RescanType::CHANNEL_COUNT)
             .union(RescanType::PORT_TYPE)
-            .union(RescanType::PORT_TYPE)
             .union(RescanType::IN_PLACE_PAIR)
             .union(RescanType::LIST);
 
         self.intersects(RESTART_REQUIRED)
     }
+
+    /// Returns `true` if a running plugin instance must be deactivated, and then reactivated
+    /// once the ports are rescanned, to apply these changes.
+    ///
+    /// This is an alias of [`requires_deactivate`](Self::requires_deactivate).
+    #[inline]
+    pub const fn requires_reactivation(&self) -> bool {
+        self.requires_deactivate()
+    }
 }
 
 bitflags! {
@@ -84,6 +98,17 @@ unsafe impl Extension for PluginAudioPorts {
     }
 }
 
+impl PluginAudioPorts {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_audio_ports> {
+        self.0
+    }
+}
+
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
 unsafe impl Extension for HostAudioPorts {
     const IDENTIFIER: &'static CStr = CLAP_EXT_AUDIO_PORTS;
@@ -95,6 +120,17 @@ unsafe impl Extension for HostAudioPorts {
     }
 }
 
+impl HostAudioPorts {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_audio_ports> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct AudioPortInfo<'a> {
     pub id: ClapId,
@@ -142,7 +178,15 @@ impl Debug for AudioPortInfo<'_> {
 #[cfg(feature = "clack-host")]
 mod host;
 #[cfg(feature = "clack-host")]
+mod rescan;
+#[cfg(feature = "clack-host")]
+mod snapshot;
+#[cfg(feature = "clack-host")]
 pub use host::*;
+#[cfg(feature = "clack-host")]
+pub use rescan::*;
+#[cfg(feature = "clack-host")]
+pub use snapshot::*;
 
 #[cfg(feature = "clack-plugin")]
 mod plugin;
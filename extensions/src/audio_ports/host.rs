@@ -4,7 +4,7 @@ use core::mem::MaybeUninit;
 
 #[derive(Clone)]
 pub struct AudioPortInfoBuffer {
-    inner: MaybeUninit<clap_audio_port_info>,
+    pub(crate) inner: MaybeUninit<clap_audio_port_info>,
 }
 
 impl Default for AudioPortInfoBuffer {
@@ -25,6 +25,8 @@ impl AudioPortInfoBuffer {
 
 impl PluginAudioPorts {
     pub fn count(&self, plugin: &mut PluginMainThreadHandle, is_input: bool) -> u32 {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports.count");
+
         match plugin.use_extension(&self.0).count {
             None => 0,
             // SAFETY: This type ensures the function pointer is valid.
@@ -39,6 +41,8 @@ impl PluginAudioPorts {
         is_input: bool,
         buffer: &'b mut AudioPortInfoBuffer,
     ) -> Option<AudioPortInfo<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports.get");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin.use_extension(&self.0).get?(
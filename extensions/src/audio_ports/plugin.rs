@@ -7,7 +7,7 @@ use std::ptr::addr_of_mut;
 
 pub struct AudioPortInfoWriter<'a> {
     buf: &'a mut MaybeUninit<clap_audio_port_info>,
-    is_set: bool,
+    pub(crate) is_set: bool,
 }
 
 impl AudioPortInfoWriter<'_> {
@@ -16,7 +16,7 @@ impl AudioPortInfoWriter<'_> {
     /// The user must ensure the provided pointer is aligned and points to a valid allocation.
     /// However, it doesn't have to be initialized.
     #[inline]
-    unsafe fn from_raw(raw: *mut clap_audio_port_info) -> Self {
+    pub(crate) unsafe fn from_raw(raw: *mut clap_audio_port_info) -> Self {
         Self {
             buf: &mut *raw.cast(),
             is_set: false,
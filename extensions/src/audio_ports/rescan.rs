@@ -0,0 +1,311 @@
+use super::*;
+use clack_host::host::HostHandlers;
+use clack_host::plugin::{PluginInstance, PluginMainThreadHandle};
+use clack_host::process::audio_buffers::{
+    AudioPortBuffer, AudioPortBufferType, AudioPorts, InputAudioBuffers, InputChannel,
+    OutputAudioBuffers,
+};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The action a host must take to apply an audio ports rescan request.
+///
+/// See [`AudioPortsRescanAction::from_flags`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AudioPortsRescanAction {
+    /// Nothing needs to be done.
+    None,
+    /// Only the names of the ports changed. The host only needs to read them again, and can
+    /// keep processing audio in the meantime.
+    UpdateNames,
+    /// The port layout changed. The plugin must be deactivated, its ports scanned again, and
+    /// all audio buffers reallocated to match the new layout, before the plugin is reactivated.
+    Reactivate,
+}
+
+impl AudioPortsRescanAction {
+    /// Returns the action required by the given set of rescan flags.
+    ///
+    /// If multiple flags are set, the most disruptive action is returned.
+    #[inline]
+    pub const fn from_flags(flags: RescanType) -> Self {
+        if flags.requires_deactivate() {
+            Self::Reactivate
+        } else if flags.contains(RescanType::NAMES) {
+            Self::UpdateNames
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Errors that can occur while handling an audio ports rescan.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AudioPortsRescanError {
+    /// The plugin requested a rescan that changes the port layout while it was active.
+    ///
+    /// The host must deactivate the plugin before it can handle this rescan.
+    PluginActive,
+}
+
+impl Display for AudioPortsRescanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioPortsRescanError::PluginActive => f.write_str(
+                "Plugin requested an audio ports rescan while active. It must be deactivated first.",
+            ),
+        }
+    }
+}
+
+impl Error for AudioPortsRescanError {}
+
+/// A pool of audio buffers, allocated to match the audio port layout of a plugin.
+///
+/// Each port gets its own set of channel buffers, which can hold up to
+/// [`max_frames_count`](Self::max_frames_count) samples each. The buffer descriptors the plugin
+/// receives are also kept in the pool, so [`buffers`](Self::buffers) never allocates, and can
+/// be called from the audio thread.
+///
+/// The pool is created using [`PluginAudioPorts::allocate_buffers`], and is reallocated
+/// automatically by [`PluginAudioPorts::handle_rescan`] if the port layout changes.
+#[derive(Clone, Debug)]
+pub struct AudioPortsBufferPool {
+    inputs: PortBuffers,
+    outputs: PortBuffers,
+    max_frames_count: u32,
+}
+
+impl AudioPortsBufferPool {
+    /// Allocates buffers for the given channel counts of each input and output port, each
+    /// channel holding up to `max_frames_count` samples.
+    pub fn new(
+        input_channel_counts: &[u32],
+        output_channel_counts: &[u32],
+        max_frames_count: u32,
+    ) -> Self {
+        Self {
+            inputs: PortBuffers::new(input_channel_counts, max_frames_count),
+            outputs: PortBuffers::new(output_channel_counts, max_frames_count),
+            max_frames_count,
+        }
+    }
+
+    /// Returns the channel count of each input port.
+    #[inline]
+    pub fn input_channel_counts(&self) -> &[u32] {
+        &self.inputs.channel_counts
+    }
+
+    /// Returns the channel count of each output port.
+    #[inline]
+    pub fn output_channel_counts(&self) -> &[u32] {
+        &self.outputs.channel_counts
+    }
+
+    /// Returns the maximum number of samples each channel buffer can hold.
+    #[inline]
+    pub fn max_frames_count(&self) -> u32 {
+        self.max_frames_count
+    }
+
+    /// Reallocates all buffers so they can hold up to `max_frames_count` samples each.
+    ///
+    /// This does nothing if the buffers already have the given size.
+    pub fn resize(&mut self, max_frames_count: u32) {
+        if max_frames_count == self.max_frames_count {
+            return;
+        }
+
+        self.max_frames_count = max_frames_count;
+        self.inputs.resize(max_frames_count);
+        self.outputs.resize(max_frames_count);
+    }
+
+    /// Reallocates all buffers to match the given port layout.
+    ///
+    /// This returns `false` and does nothing if the layout didn't change.
+    pub fn reallocate(
+        &mut self,
+        input_channel_counts: &[u32],
+        output_channel_counts: &[u32],
+    ) -> bool {
+        if self.input_channel_counts() == input_channel_counts
+            && self.output_channel_counts() == output_channel_counts
+        {
+            return false;
+        }
+
+        *self = Self::new(
+            input_channel_counts,
+            output_channel_counts,
+            self.max_frames_count,
+        );
+
+        true
+    }
+
+    /// Returns the input and output buffers to be given to the plugin's `process` call.
+    ///
+    /// The buffers span [`max_frames_count`](Self::max_frames_count) samples. Use
+    /// [`InputAudioBuffers::truncate`] and [`OutputAudioBuffers::truncate`] to process smaller
+    /// blocks.
+    pub fn buffers(&mut self) -> (InputAudioBuffers<'_>, OutputAudioBuffers<'_>) {
+        let inputs = &mut self.inputs;
+        let inputs = inputs
+            .descriptors
+            .with_input_buffers(inputs.channels.iter_mut().map(|channels| AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_input_only(
+                    channels.iter_mut().map(InputChannel::variable),
+                ),
+            }));
+
+        let outputs = &mut self.outputs;
+        let outputs = outputs
+            .descriptors
+            .with_output_buffers(outputs.channels.iter_mut().map(|channels| AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_output_only(
+                    channels.iter_mut().map(|c| c.as_mut_slice()),
+                ),
+            }));
+
+        (inputs, outputs)
+    }
+
+    /// Returns the channel buffers of the given input port, e.g. to fill them before processing.
+    #[inline]
+    pub fn input_channels_mut(&mut self, port_index: usize) -> Option<&mut [Vec<f32>]> {
+        self.inputs
+            .channels
+            .get_mut(port_index)
+            .map(|c| c.as_mut_slice())
+    }
+
+    /// Returns the channel buffers of the given output port, e.g. to read them after processing.
+    #[inline]
+    pub fn output_channels(&self, port_index: usize) -> Option<&[Vec<f32>]> {
+        self.outputs.channels.get(port_index).map(|c| c.as_slice())
+    }
+}
+
+struct PortBuffers {
+    channel_counts: Vec<u32>,
+    channels: Vec<Vec<Vec<f32>>>,
+    descriptors: AudioPorts,
+}
+
+impl PortBuffers {
+    fn new(channel_counts: &[u32], max_frames_count: u32) -> Self {
+        let channels: Vec<Vec<Vec<f32>>> = channel_counts
+            .iter()
+            .map(|&count| vec![vec![0.0; max_frames_count as usize]; count as usize])
+            .collect();
+
+        let total_channel_count = channel_counts.iter().map(|&c| c as usize).sum();
+
+        Self {
+            channel_counts: channel_counts.to_vec(),
+            descriptors: AudioPorts::with_capacity(total_channel_count, channel_counts.len()),
+            channels,
+        }
+    }
+
+    fn resize(&mut self, max_frames_count: u32) {
+        for channel in self.channels.iter_mut().flatten() {
+            channel.resize(max_frames_count as usize, 0.0);
+        }
+    }
+}
+
+impl Clone for PortBuffers {
+    fn clone(&self) -> Self {
+        let total_channel_count = self.channel_counts.iter().map(|&c| c as usize).sum();
+
+        Self {
+            channel_counts: self.channel_counts.clone(),
+            channels: self.channels.clone(),
+            descriptors: AudioPorts::with_capacity(total_channel_count, self.channel_counts.len()),
+        }
+    }
+}
+
+impl Debug for PortBuffers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortBuffers")
+            .field("channel_counts", &self.channel_counts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PluginAudioPorts {
+    /// Returns the channel count of each of the plugin's input or output ports.
+    ///
+    /// Ports the plugin fails to provide information for are counted as having no channels.
+    pub fn channel_counts(&self, plugin: &mut PluginMainThreadHandle, is_input: bool) -> Vec<u32> {
+        let mut buffer = AudioPortInfoBuffer::new();
+
+        (0..self.count(plugin, is_input))
+            .map(|index| {
+                self.get(plugin, index, is_input, &mut buffer)
+                    .map_or(0, |info| info.channel_count)
+            })
+            .collect()
+    }
+
+    /// Scans the plugin's audio ports, and allocates a matching [`AudioPortsBufferPool`].
+    pub fn allocate_buffers(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        max_frames_count: u32,
+    ) -> AudioPortsBufferPool {
+        AudioPortsBufferPool::new(
+            &self.channel_counts(plugin, true),
+            &self.channel_counts(plugin, false),
+            max_frames_count,
+        )
+    }
+
+    /// Applies a rescan request from the plugin to the given [`AudioPortsBufferPool`].
+    ///
+    /// The returned [`AudioPortsRescanAction`] tells what the host has to do on its side:
+    ///
+    /// * [`Reactivate`](AudioPortsRescanAction::Reactivate): the ports are scanned again, and
+    ///   the buffer pool is reallocated if the port layout changed. The host can then reactivate
+    ///   the plugin and resume processing with the new buffers.
+    /// * [`UpdateNames`](AudioPortsRescanAction::UpdateNames): the buffers are left unchanged.
+    ///   The host only needs to read the port names again, e.g. to update its UI.
+    ///
+    /// Because [`HostAudioPortsImpl::rescan`] is called from within the plugin, the host cannot
+    /// deactivate it from there. Instead, the host should store the flags it received, and then
+    /// from its main loop: stop its audio stream, deactivate the plugin, call this method, and
+    /// finally reactivate the plugin and restart the stream.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`AudioPortsRescanError::PluginActive`] if the rescan requires the plugin
+    /// to be deactivated, but it is still active. The pool is left untouched in that case.
+    pub fn handle_rescan<H: HostHandlers>(
+        &self,
+        instance: &mut PluginInstance<H>,
+        flags: RescanType,
+        pool: &mut AudioPortsBufferPool,
+    ) -> Result<AudioPortsRescanAction, AudioPortsRescanError> {
+        let action = AudioPortsRescanAction::from_flags(flags);
+
+        if action == AudioPortsRescanAction::Reactivate {
+            if instance.is_active() {
+                return Err(AudioPortsRescanError::PluginActive);
+            }
+
+            let mut plugin = instance.plugin_handle();
+            pool.reallocate(
+                &self.channel_counts(&mut plugin, true),
+                &self.channel_counts(&mut plugin, false),
+            );
+        }
+
+        Ok(action)
+    }
+}
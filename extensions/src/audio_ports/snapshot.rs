@@ -0,0 +1,331 @@
+use super::*;
+use clack_host::plugin::PluginMainThreadHandle;
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt::{Display, Formatter};
+
+/// An owned copy of an audio port's information, as stored in an [`AudioPortsSnapshot`].
+///
+/// When the `serde` feature is enabled, this type can be serialized and deserialized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioPortSnapshot {
+    /// The stable ID of the port.
+    pub id: ClapId,
+    /// The displayable name of the port.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::bytes")
+    )]
+    pub name: Vec<u8>,
+    /// The number of channels of the port.
+    pub channel_count: u32,
+    /// The flags of the port.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::flags")
+    )]
+    pub flags: AudioPortFlags,
+    /// The type of the port, if the plugin specified one.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub port_type: Option<CString>,
+    /// The ID of the port on the other side this port is an in-place pair with, if any.
+    pub in_place_pair: Option<ClapId>,
+}
+
+impl AudioPortSnapshot {
+    fn from_info(info: &AudioPortInfo) -> Self {
+        Self {
+            id: info.id,
+            name: info.name.to_vec(),
+            channel_count: info.channel_count,
+            flags: info.flags,
+            port_type: info.port_type.map(|t| t.0.to_owned()),
+            in_place_pair: info.in_place_pair,
+        }
+    }
+
+    /// Returns the type of the port, if the plugin specified one.
+    #[inline]
+    pub fn port_type(&self) -> Option<AudioPortType<'_>> {
+        self.port_type.as_deref().map(AudioPortType)
+    }
+
+    fn layout_differs(&self, other: &Self) -> bool {
+        self.channel_count != other.channel_count
+            || self.flags != other.flags
+            || self.port_type != other.port_type
+            || self.in_place_pair != other.in_place_pair
+    }
+}
+
+/// An owned snapshot of all the audio ports of a plugin instance, on both the input and output
+/// sides.
+///
+/// Snapshots are taken using [`scan`](Self::scan). When the plugin requests a rescan, hosts can
+/// take a new snapshot and [`diff`](Self::diff) it against the previous one, to only update what
+/// actually changed instead of tearing everything down.
+///
+/// When the `serde` feature is enabled, this type can be serialized and deserialized, e.g. to
+/// remember the port layout of a plugin across sessions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioPortsSnapshot {
+    inputs: Vec<AudioPortSnapshot>,
+    outputs: Vec<AudioPortSnapshot>,
+}
+
+impl AudioPortsSnapshot {
+    /// Scans all the audio ports of the plugin.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`AudioPortsScanError::MissingPort`] if the plugin fails to provide
+    /// information for any of its ports. Skipping that port instead would shift all the following
+    /// ports, and the buffers [allocated](Self::allocate_buffers) from this snapshot would no
+    /// longer match the plugin's port indices.
+    pub fn scan(
+        plugin: &mut PluginMainThreadHandle,
+        audio_ports: &PluginAudioPorts,
+    ) -> Result<Self, AudioPortsScanError> {
+        Ok(Self {
+            inputs: scan_ports(plugin, audio_ports, true)?,
+            outputs: scan_ports(plugin, audio_ports, false)?,
+        })
+    }
+
+    /// Returns the input ports, in the order the plugin declared them.
+    #[inline]
+    pub fn inputs(&self) -> &[AudioPortSnapshot] {
+        &self.inputs
+    }
+
+    /// Returns the output ports, in the order the plugin declared them.
+    #[inline]
+    pub fn outputs(&self) -> &[AudioPortSnapshot] {
+        &self.outputs
+    }
+
+    /// Returns the input or output port with the given ID, if any.
+    pub fn get(&self, port_id: ClapId, is_input: bool) -> Option<&AudioPortSnapshot> {
+        let ports = if is_input {
+            &self.inputs
+        } else {
+            &self.outputs
+        };
+        ports.iter().find(|p| p.id == port_id)
+    }
+
+    /// Allocates an [`AudioPortsBufferPool`] matching the ports of this snapshot.
+    ///
+    /// Each port gets the buffers matching its index in the plugin's port list.
+    pub fn allocate_buffers(&self, max_frames_count: u32) -> AudioPortsBufferPool {
+        AudioPortsBufferPool::new(
+            &channel_counts(&self.inputs),
+            &channel_counts(&self.outputs),
+            max_frames_count,
+        )
+    }
+
+    /// Computes the differences between two snapshots of the same plugin's ports.
+    pub fn diff(old: &AudioPortsSnapshot, new: &AudioPortsSnapshot) -> AudioPortsDiff {
+        AudioPortsDiff {
+            inputs: AudioPortListDiff::compute(&old.inputs, &new.inputs),
+            outputs: AudioPortListDiff::compute(&old.outputs, &new.outputs),
+        }
+    }
+}
+
+fn channel_counts(ports: &[AudioPortSnapshot]) -> Vec<u32> {
+    ports.iter().map(|p| p.channel_count).collect()
+}
+
+fn scan_ports(
+    plugin: &mut PluginMainThreadHandle,
+    audio_ports: &PluginAudioPorts,
+    is_input: bool,
+) -> Result<Vec<AudioPortSnapshot>, AudioPortsScanError> {
+    let mut buffer = AudioPortInfoBuffer::new();
+
+    (0..audio_ports.count(plugin, is_input))
+        .map(|index| {
+            let info = audio_ports
+                .get(plugin, index, is_input, &mut buffer)
+                .ok_or(AudioPortsScanError::MissingPort { index, is_input })?;
+
+            Ok(AudioPortSnapshot::from_info(&info))
+        })
+        .collect()
+}
+
+/// Errors that can occur while taking an [`AudioPortsSnapshot`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AudioPortsScanError {
+    /// The plugin failed to provide information for one of its ports.
+    MissingPort {
+        /// The index of the port.
+        index: u32,
+        /// Whether the port is an input or an output port.
+        is_input: bool,
+    },
+}
+
+impl Display for AudioPortsScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioPortsScanError::MissingPort { index, is_input } => {
+                let side = if *is_input { "input" } else { "output" };
+                write!(
+                    f,
+                    "Plugin failed to provide information for its {side} audio port #{index}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for AudioPortsScanError {}
+
+/// The differences between two [`AudioPortsSnapshot`]s, as computed by
+/// [`AudioPortsSnapshot::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioPortsDiff {
+    /// The differences between the input ports.
+    pub inputs: AudioPortListDiff,
+    /// The differences between the output ports.
+    pub outputs: AudioPortListDiff,
+}
+
+impl AudioPortsDiff {
+    /// Returns `true` if the ports didn't change at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty() && self.outputs.is_empty()
+    }
+
+    /// Returns `true` if the port layout changed, in which case the plugin must be reactivated
+    /// with newly allocated buffers.
+    ///
+    /// If this returns `false`, at most some ports were renamed.
+    #[inline]
+    pub fn requires_reactivation(&self) -> bool {
+        self.inputs.requires_reactivation() || self.outputs.requires_reactivation()
+    }
+}
+
+/// The differences between two lists of either input or output ports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioPortListDiff {
+    /// The IDs of the ports that were added.
+    pub added: Vec<ClapId>,
+    /// The IDs of the ports that no longer exist.
+    pub removed: Vec<ClapId>,
+    /// The IDs of the ports whose name changed.
+    pub renamed: Vec<ClapId>,
+    /// The IDs of the ports whose channel count, flags, type or in-place pair changed.
+    pub changed: Vec<ClapId>,
+    /// Whether the ports that exist in both lists are now in a different order.
+    pub reordered: bool,
+}
+
+impl AudioPortListDiff {
+    fn compute(old: &[AudioPortSnapshot], new: &[AudioPortSnapshot]) -> Self {
+        let mut diff = Self::default();
+
+        for new_port in new {
+            match old.iter().find(|p| p.id == new_port.id) {
+                None => diff.added.push(new_port.id),
+                Some(old_port) => {
+                    if old_port.name != new_port.name {
+                        diff.renamed.push(new_port.id);
+                    }
+
+                    if old_port.layout_differs(new_port) {
+                        diff.changed.push(new_port.id);
+                    }
+                }
+            }
+        }
+
+        diff.removed = (old.iter())
+            .filter(|p| !new.iter().any(|n| n.id == p.id))
+            .map(|p| p.id)
+            .collect();
+
+        let kept_ids = |a: &[AudioPortSnapshot], b: &[AudioPortSnapshot]| {
+            (a.iter())
+                .filter(|p| b.iter().any(|o| o.id == p.id))
+                .map(|p| p.id)
+                .collect::<Vec<_>>()
+        };
+
+        diff.reordered = kept_ids(old, new) != kept_ids(new, old);
+
+        diff
+    }
+
+    /// Returns `true` if the ports didn't change at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.renamed.is_empty() && !self.requires_reactivation()
+    }
+
+    /// Returns `true` if ports were added, removed, reordered, or if their layout changed.
+    #[inline]
+    pub fn requires_reactivation(&self) -> bool {
+        !self.added.is_empty()
+            || !self.removed.is_empty()
+            || !self.changed.is_empty()
+            || self.reordered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn port(id: u32, name: &[u8], channel_count: u32) -> AudioPortSnapshot {
+        AudioPortSnapshot {
+            id: ClapId::new(id),
+            name: name.to_vec(),
+            channel_count,
+            flags: AudioPortFlags::empty(),
+            port_type: None,
+            in_place_pair: None,
+        }
+    }
+
+    #[test]
+    fn diffs_port_lists() {
+        let old = [
+            port(1, b"Main", 2),
+            port(2, b"Sidechain", 2),
+            port(3, b"Aux", 1),
+        ];
+        let new = [
+            port(1, b"Main", 2),
+            port(3, b"Aux 1", 1),
+            port(4, b"Aux 2", 1),
+        ];
+
+        let diff = AudioPortListDiff::compute(&old, &new);
+        assert_eq!(diff.added, [ClapId::new(4)]);
+        assert_eq!(diff.removed, [ClapId::new(2)]);
+        assert_eq!(diff.renamed, [ClapId::new(3)]);
+        assert!(diff.changed.is_empty());
+        assert!(!diff.reordered);
+        assert!(diff.requires_reactivation());
+
+        let renamed_only = AudioPortListDiff::compute(&old[..1], &[port(1, b"Input", 2)]);
+        assert!(!renamed_only.is_empty());
+        assert!(!renamed_only.requires_reactivation());
+
+        let reordered =
+            AudioPortListDiff::compute(&old, &[old[2].clone(), old[0].clone(), old[1].clone()]);
+        assert!(reordered.reordered);
+        assert!(AudioPortListDiff::compute(&old, &old).is_empty());
+    }
+}
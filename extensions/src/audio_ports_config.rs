@@ -9,13 +9,20 @@
 //! select one that fits the plugin context. The host can also let the user change configurations
 //! at any time, e.g. via a menu.
 //!
-//! The host can only select a configuration if the plugin is deactivated.
+//! The host can only select a configuration if the plugin is deactivated. Once a configuration is
+//! successfully selected, the host must rescan all of the plugin's audio ports.
+//!
+//! Plugins can also implement the complementary Audio Ports Configuration Info extension
+//! ([`PluginAudioPortsConfigInfo`]), which allows the host to know which configuration is
+//! currently selected, and to retrieve full information about the ports of any configuration
+//! without having to select it first.
 //!
 //! Plugins with very complex configuration possibilities that cannot be covered by this extension,
 //! should instead let the user configure the ports from the plugin GUI, and then request a full
 //! port rescan to the host.
 
 use crate::audio_ports::AudioPortType;
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clack_common::utils::ClapId;
 use clap_sys::ext::audio_ports_config::*;
@@ -25,7 +32,6 @@ use std::fmt::{Display, Formatter};
 
 /// The Plugin-side of the Audio Ports Configurations extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginAudioPortsConfig(
     RawExtension<PluginExtensionSide, clap_plugin_audio_ports_config>,
 );
@@ -41,9 +47,19 @@ unsafe impl Extension for PluginAudioPortsConfig {
     }
 }
 
+impl PluginAudioPortsConfig {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_audio_ports_config> {
+        self.0
+    }
+}
+
 /// The Host-side of the Audio Ports Configurations extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostAudioPortsConfig(RawExtension<HostExtensionSide, clap_host_audio_ports_config>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -57,6 +73,54 @@ unsafe impl Extension for HostAudioPortsConfig {
     }
 }
 
+impl HostAudioPortsConfig {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_audio_ports_config> {
+        self.0
+    }
+}
+
+/// The identifier of the stable Audio Ports Configuration Info extension, which is ABI-compatible
+/// with the draft one.
+const CLAP_EXT_AUDIO_PORTS_CONFIG_INFO_STABLE: &CStr =
+    match CStr::from_bytes_with_nul(b"clap.audio-ports-config-info/1\0") {
+        Ok(identifier) => identifier,
+        Err(_) => panic!("Invalid extension identifier"),
+    };
+
+/// The Plugin-side of the Audio Ports Configuration Info extension.
+#[derive(Copy, Clone)]
+pub struct PluginAudioPortsConfigInfo(
+    RawExtension<PluginExtensionSide, clap_plugin_audio_ports_config_info>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginAudioPortsConfigInfo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_AUDIO_PORTS_CONFIG_INFO;
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[CLAP_EXT_AUDIO_PORTS_CONFIG_INFO_STABLE];
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginAudioPortsConfigInfo {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_audio_ports_config_info> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 /// A specific Audio Configuration for the plugin.
 pub struct AudioPortsConfiguration<'a> {
@@ -145,11 +209,31 @@ impl MainPortInfo<'_> {
 
 /// An error that can occur as a plugin selects a new port configuration
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
-pub struct AudioPortConfigSelectError;
+pub struct AudioPortConfigSelectError {
+    failure: CallFailure,
+}
+
+impl AudioPortConfigSelectError {
+    /// Creates a new error with the given [`CallFailure`] reason.
+    #[inline]
+    pub const fn new(failure: CallFailure) -> Self {
+        Self { failure }
+    }
+
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        self.failure
+    }
+}
 
 impl Display for AudioPortConfigSelectError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Failed to change plugin audio ports configuration.")
+        write!(
+            f,
+            "Failed to change plugin audio ports configuration: {}",
+            self.failure
+        )
     }
 }
 
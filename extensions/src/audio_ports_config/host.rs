@@ -1,4 +1,5 @@
 use super::*;
+use crate::audio_ports::{AudioPortInfo, AudioPortInfoBuffer};
 use clack_host::extensions::prelude::*;
 use std::mem::MaybeUninit;
 
@@ -28,6 +29,8 @@ impl AudioPortsConfigBuffer {
 impl PluginAudioPortsConfig {
     /// Returns the number of available [`AudioPortsConfiguration`]s.
     pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> usize {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports_config.count");
+
         // SAFETY: This type ensures the function pointer is valid.
         match plugin.use_extension(&self.0).count {
             None => 0,
@@ -46,6 +49,8 @@ impl PluginAudioPortsConfig {
         index: usize,
         buffer: &'b mut AudioPortsConfigBuffer,
     ) -> Option<AudioPortsConfiguration<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports_config.get");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin.use_extension(&self.0).get?(
@@ -63,10 +68,30 @@ impl PluginAudioPortsConfig {
         }
     }
 
+    /// Retrieves a specific [`AudioPortsConfiguration`] from its ID.
+    ///
+    /// This returns `None` if the plugin has no configuration with the given ID.
+    pub fn find<'b>(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        configuration_id: ClapId,
+        buffer: &'b mut AudioPortsConfigBuffer,
+    ) -> Option<AudioPortsConfiguration<'b>> {
+        let index = (0..self.count(plugin)).find(
+            |index| matches!(self.get(plugin, *index, buffer), Some(c) if c.id == configuration_id),
+        )?;
+
+        self.get(plugin, index, buffer)
+    }
+
     /// Requests the plugin to change its Audio Ports Configuration to the one with the given ID.
     ///
     /// The plugin *must* be deactivated to call this method.
     ///
+    /// Once the configuration is successfully applied, the host must rescan all of the plugin's
+    /// audio ports, as they may have changed. The plugin is not required to request that rescan
+    /// itself.
+    ///
     /// # Error
     ///
     /// This method may return an [`AudioPortConfigSelectError`] if the given ID is out of bounds,
@@ -77,19 +102,105 @@ impl PluginAudioPortsConfig {
         plugin: &mut PluginMainThreadHandle,
         configuration_id: ClapId,
     ) -> Result<(), AudioPortConfigSelectError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports_config.select");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin
                 .use_extension(&self.0)
                 .select
-                .ok_or(AudioPortConfigSelectError)?(
-                plugin.as_raw(), configuration_id.get()
+                .ok_or(AudioPortConfigSelectError::new(CallFailure::Unsupported))?(
+                plugin.as_raw(),
+                configuration_id.get(),
             )
         };
 
         match success {
             true => Ok(()),
-            false => Err(AudioPortConfigSelectError),
+            false => Err(AudioPortConfigSelectError::new(CallFailure::Declined)),
+        }
+    }
+
+    /// Selects the first [`AudioPortsConfiguration`] that matches the given predicate.
+    ///
+    /// This returns the ID of the newly selected configuration, or `None` if no configuration
+    /// matched. Configurations are tried in order, and only the first matching one is selected.
+    ///
+    /// The plugin *must* be deactivated to call this method. As with [`select`](Self::select),
+    /// the host must rescan all of the plugin's audio ports after a configuration is successfully
+    /// selected.
+    ///
+    /// # Error
+    ///
+    /// This method returns an [`AudioPortConfigSelectError`] if the plugin declined or failed to
+    /// change to the matching configuration.
+    pub fn select_matching(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        mut predicate: impl FnMut(&AudioPortsConfiguration) -> bool,
+    ) -> Result<Option<ClapId>, AudioPortConfigSelectError> {
+        let mut buffer = AudioPortsConfigBuffer::new();
+
+        let matching_id = (0..self.count(plugin)).find_map(|index| {
+            let configuration = self.get(plugin, index, &mut buffer)?;
+            predicate(&configuration).then_some(configuration.id)
+        });
+
+        let Some(matching_id) = matching_id else {
+            return Ok(None);
+        };
+
+        self.select(plugin, matching_id)?;
+        Ok(Some(matching_id))
+    }
+}
+
+impl PluginAudioPortsConfigInfo {
+    /// Returns the ID of the currently selected [`AudioPortsConfiguration`].
+    ///
+    /// This returns `None` if the plugin didn't provide a valid configuration ID.
+    pub fn current_config(&self, plugin: &mut PluginMainThreadHandle) -> Option<ClapId> {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports_config_info.current_config");
+
+        let current_config = plugin.use_extension(&self.0).current_config?;
+
+        // SAFETY: This type ensures the function pointer is valid.
+        ClapId::from_raw(unsafe { current_config(plugin.as_raw()) })
+    }
+
+    /// Retrieves full information about an audio port of a given configuration.
+    ///
+    /// This allows to inspect the ports of any configuration before selecting it, including the
+    /// currently selected one.
+    ///
+    /// The plugin gets passed a mutable buffer to write the port information into, to avoid any
+    /// unnecessary allocations.
+    pub fn get<'b>(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        configuration_id: ClapId,
+        port_index: u32,
+        is_input: bool,
+        buffer: &'b mut AudioPortInfoBuffer,
+    ) -> Option<AudioPortInfo<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_audio_ports_config_info.get");
+
+        // SAFETY: This type ensures the function pointer is valid.
+        let success = unsafe {
+            plugin.use_extension(&self.0).get?(
+                plugin.as_raw(),
+                configuration_id.get(),
+                port_index,
+                is_input,
+                buffer.inner.as_mut_ptr(),
+            )
+        };
+
+        if success {
+            // SAFETY: we checked if the buffer was successfully written to
+            Some(unsafe { AudioPortInfo::from_raw(buffer.inner.assume_init_ref())? })
+        } else {
+            None
         }
     }
 }
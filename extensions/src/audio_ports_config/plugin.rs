@@ -1,4 +1,5 @@
 use super::*;
+use crate::audio_ports::AudioPortInfoWriter;
 use crate::utils::write_to_array_buf;
 use clack_plugin::extensions::prelude::*;
 use std::mem::MaybeUninit;
@@ -89,6 +90,77 @@ where
     .unwrap_or(false)
 }
 
+/// Implementation of the Plugin-side of the Audio Ports Configuration Info extension.
+pub trait PluginAudioPortsConfigInfoImpl {
+    /// Returns the ID of the currently selected [`AudioPortsConfiguration`].
+    fn current_config(&mut self) -> ClapId;
+
+    /// Retrieves full information about an audio port of the configuration with the given ID.
+    ///
+    /// The plugin gets passed a host-provided mutable buffer to write the port information into,
+    /// to avoid any unnecessary allocations. If nothing is written to it, the host is informed
+    /// that there is no such port, or no such configuration.
+    fn get(
+        &mut self,
+        config_id: ClapId,
+        port_index: u32,
+        is_input: bool,
+        writer: &mut AudioPortInfoWriter,
+    );
+}
+
+// SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginAudioPortsConfigInfo
+where
+    for<'a> P::MainThread<'a>: PluginAudioPortsConfigInfoImpl,
+{
+    #[doc(hidden)]
+    const IMPLEMENTATION: RawExtensionImplementation =
+        RawExtensionImplementation::new(&clap_plugin_audio_ports_config_info {
+            current_config: Some(current_config::<P>),
+            get: Some(get_info::<P>),
+        });
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn current_config<P: Plugin>(plugin: *const clap_plugin) -> u32
+where
+    for<'a> P::MainThread<'a>: PluginAudioPortsConfigInfoImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        Ok(p.main_thread().as_mut().current_config().get())
+    })
+    .unwrap_or(ClapId::optional_to_raw(None))
+}
+
+#[allow(clippy::missing_safety_doc)]
+unsafe extern "C" fn get_info<P: Plugin>(
+    plugin: *const clap_plugin,
+    config_id: u32,
+    port_index: u32,
+    is_input: bool,
+    info: *mut clap_sys::ext::audio_ports::clap_audio_port_info,
+) -> bool
+where
+    for<'a> P::MainThread<'a>: PluginAudioPortsConfigInfoImpl,
+{
+    PluginWrapper::<P>::handle(plugin, |p| {
+        if info.is_null() {
+            return Err(PluginWrapperError::NulPtr("clap_audio_port_info"));
+        };
+
+        let config_id = ClapId::from_raw(config_id)
+            .ok_or(PluginWrapperError::InvalidParameter("Invalid config_id"))?;
+
+        let mut writer = AudioPortInfoWriter::from_raw(info);
+        p.main_thread()
+            .as_mut()
+            .get(config_id, port_index, is_input, &mut writer);
+        Ok(writer.is_set)
+    })
+    .unwrap_or(false)
+}
+
 /// A helper struct to write an [`AudioPortsConfiguration`] into the host's provided buffer.
 pub struct AudioPortConfigWriter<'a> {
     buf: &'a mut MaybeUninit<clap_audio_ports_config>,
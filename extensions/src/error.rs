@@ -0,0 +1,29 @@
+//! Error types shared between the errors of the different extensions.
+//!
+//! CLAP extension functions usually only report failure through a `false` return value, and may
+//! also be entirely missing. The error types of each extension (e.g. `GuiError`) thus identify
+//! which call failed, and carry a [`CallFailure`] describing why it failed.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The reason why a call to an extension function failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub enum CallFailure {
+    /// The other side does not implement the called function, i.e. its function pointer is null.
+    Unsupported,
+    /// The function was called, but it declined or failed to perform the requested operation.
+    #[default]
+    Declined,
+}
+
+impl Display for CallFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallFailure::Unsupported => f.write_str("function is not implemented"),
+            CallFailure::Declined => f.write_str("operation was declined or failed"),
+        }
+    }
+}
+
+impl Error for CallFailure {}
@@ -3,7 +3,6 @@ use clap_sys::ext::event_registry::{clap_host_event_registry, CLAP_EXT_EVENT_REG
 use std::ffi::CStr;
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostEventRegistry(RawExtension<HostExtensionSide, clap_host_event_registry>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -17,6 +16,17 @@ unsafe impl Extension for HostEventRegistry {
     }
 }
 
+impl HostEventRegistry {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_event_registry> {
+        self.0
+    }
+}
+
 #[cfg(feature = "clack-plugin")]
 const _: () = {
     use clack_common::events::spaces::{EventSpace, EventSpaceId};
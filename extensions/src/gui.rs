@@ -44,6 +44,10 @@
 //! * Call `show`, after which the host can call `hide` and `show` at will.
 //! * Call `destroy` to free the GUI resources when the Host is done with it.
 //!
+//! Hosts using a `GuiSession` can persist the plugin's window size, scale and floating mode
+//! between openings, by using its `destroy_and_save` and `create_restored` methods with a
+//! `GuiWindowStateStore`.
+//!
 //! ### Resize an embedded Plugin window.
 //!
 //! When the users drags to resize an embedded Plugin window, the Host must follow these steps to
@@ -58,6 +62,7 @@
 
 #![deny(missing_docs)]
 
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clap_sys::ext::gui::*;
 use std::cmp::Ordering;
@@ -71,7 +76,15 @@ pub use window::*;
 #[cfg(feature = "clack-host")]
 mod host;
 #[cfg(feature = "clack-host")]
+mod negotiation;
+#[cfg(feature = "clack-host")]
+mod session;
+#[cfg(feature = "clack-host")]
 pub use host::*;
+#[cfg(feature = "clack-host")]
+pub use negotiation::*;
+#[cfg(feature = "clack-host")]
+pub use session::*;
 
 #[cfg(feature = "clack-plugin")]
 mod plugin;
@@ -147,9 +160,40 @@ pub enum AspectRatioStrategy {
     },
 }
 
+/// The reason why a plugin's GUI was closed, as notified by the plugin through the `closed`
+/// callback of the [`HostGui`] extension.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum GuiClosedReason {
+    /// The plugin's floating window was closed (e.g. by the user), but its GUI still exists.
+    ///
+    /// The host may show it again later.
+    Hidden,
+    /// The connection to the plugin's GUI was lost, and the plugin destroyed it.
+    ///
+    /// The host must acknowledge the destruction by calling `destroy`, and must not perform any
+    /// other GUI call until the GUI is created again.
+    Destroyed,
+}
+
+impl GuiClosedReason {
+    /// Returns the reason matching the raw `was_destroyed` value of the CLAP `closed` callback.
+    #[inline]
+    pub const fn from_was_destroyed(was_destroyed: bool) -> Self {
+        match was_destroyed {
+            true => Self::Destroyed,
+            false => Self::Hidden,
+        }
+    }
+
+    /// Returns `true` if the plugin's GUI was destroyed.
+    #[inline]
+    pub const fn was_destroyed(self) -> bool {
+        matches!(self, Self::Destroyed)
+    }
+}
+
 /// The Plugin-side of the GUI extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginGui(RawExtension<PluginExtensionSide, clap_plugin_gui>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -163,9 +207,19 @@ unsafe impl Extension for PluginGui {
     }
 }
 
+impl PluginGui {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_gui> {
+        self.0
+    }
+}
+
 /// The Host-side of the GUI extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostGui(RawExtension<HostExtensionSide, clap_host_gui>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -179,51 +233,84 @@ unsafe impl Extension for HostGui {
     }
 }
 
+impl HostGui {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_gui> {
+        self.0
+    }
+}
+
 /// Errors that can occur related to Plugin GUI handling.
+///
+/// Each variant identifies the call that failed, and carries the [`CallFailure`] reason.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum GuiError {
     /// The plugin failed to create its GUI.
-    CreateError,
+    CreateError(CallFailure),
     /// The plugin failed to set its size.
-    SetSizeError,
+    SetSizeError(CallFailure),
     /// The plugin failed to set its scale.
-    SetScaleError,
+    SetScaleError(CallFailure),
     /// The plugin failed to set a parent window for its GUI.
-    SetParentError,
+    SetParentError(CallFailure),
     /// The plugin failed to set a transient window for its GUI.
-    SetTransientError,
+    SetTransientError(CallFailure),
     /// The plugin's window could not be resized.
-    ResizeError,
+    ResizeError(CallFailure),
     /// The plugin failed to show its GUI.
-    ShowError,
+    ShowError(CallFailure),
     /// The plugin failed to hide its GUI.
-    HideError,
+    HideError(CallFailure),
 
     /// The host denied or failed to process a request to resize its parent window.
-    RequestResizeError,
+    RequestResizeError(CallFailure),
     /// The host denied or failed to process a request to show its parent window.
-    RequestShowError,
+    RequestShowError(CallFailure),
     /// The host denied or failed to process a request to hide its parent window.
-    RequestHideError,
+    RequestHideError(CallFailure),
+}
+
+impl GuiError {
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        match *self {
+            GuiError::CreateError(failure)
+            | GuiError::SetSizeError(failure)
+            | GuiError::SetScaleError(failure)
+            | GuiError::SetParentError(failure)
+            | GuiError::SetTransientError(failure)
+            | GuiError::ResizeError(failure)
+            | GuiError::ShowError(failure)
+            | GuiError::HideError(failure)
+            | GuiError::RequestResizeError(failure)
+            | GuiError::RequestShowError(failure)
+            | GuiError::RequestHideError(failure) => failure,
+        }
+    }
 }
 
 impl Display for GuiError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            GuiError::ResizeError => f.write_str("Failed to resize plugin window"),
-            GuiError::ShowError => f.write_str("Failed to show plugin window"),
-            GuiError::HideError => f.write_str("Failed to hide plugin window"),
-            GuiError::CreateError => f.write_str("Failed to create plugin GUI"),
-            GuiError::SetSizeError => f.write_str("Failed to set plugin window size"),
-            GuiError::SetScaleError => f.write_str("Failed to set plugin window scaling"),
-            GuiError::SetParentError => f.write_str("Failed to set plugin window parent"),
-            GuiError::SetTransientError => f.write_str("Failed to set plugin transient"),
-            GuiError::RequestResizeError => {
-                f.write_str("Request to resize host parent window failed")
-            }
-            GuiError::RequestShowError => f.write_str("Request to show host parent window failed"),
-            GuiError::RequestHideError => f.write_str("Request to hide host parent window failed"),
-        }
+        let message = match self {
+            GuiError::ResizeError(_) => "Failed to resize plugin window",
+            GuiError::ShowError(_) => "Failed to show plugin window",
+            GuiError::HideError(_) => "Failed to hide plugin window",
+            GuiError::CreateError(_) => "Failed to create plugin GUI",
+            GuiError::SetSizeError(_) => "Failed to set plugin window size",
+            GuiError::SetScaleError(_) => "Failed to set plugin window scaling",
+            GuiError::SetParentError(_) => "Failed to set plugin window parent",
+            GuiError::SetTransientError(_) => "Failed to set plugin transient",
+            GuiError::RequestResizeError(_) => "Request to resize host parent window failed",
+            GuiError::RequestShowError(_) => "Request to show host parent window failed",
+            GuiError::RequestHideError(_) => "Request to hide host parent window failed",
+        };
+
+        write!(f, "{message}: {}", self.failure())
     }
 }
 
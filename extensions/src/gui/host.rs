@@ -8,6 +8,8 @@ impl PluginGui {
         plugin: &mut PluginMainThreadHandle,
         configuration: GuiConfiguration,
     ) -> bool {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.is_api_supported");
+
         match plugin.use_extension(&self.0).is_api_supported {
             // SAFETY: This type ensures the function pointer is valid.
             Some(is_api_supported) => unsafe {
@@ -28,6 +30,8 @@ impl PluginGui {
         &self,
         plugin: &mut PluginMainThreadHandle,
     ) -> Option<GuiConfiguration> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.get_preferred_api");
+
         let mut api_type = core::ptr::null();
         let mut is_floating = true;
 
@@ -63,12 +67,14 @@ impl PluginGui {
         plugin: &mut PluginMainThreadHandle,
         configuration: GuiConfiguration,
     ) -> Result<(), GuiError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.create");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin
                 .use_extension(&self.0)
                 .create
-                .ok_or(GuiError::CreateError)?(
+                .ok_or(GuiError::CreateError(CallFailure::Unsupported))?(
                 plugin.as_raw(),
                 configuration.api_type.0.as_ptr(),
                 configuration.is_floating,
@@ -77,12 +83,14 @@ impl PluginGui {
 
         match success {
             true => Ok(()),
-            false => Err(GuiError::CreateError),
+            false => Err(GuiError::CreateError(CallFailure::Declined)),
         }
     }
 
     /// Free all resources associated with the GUI
     pub fn destroy(&self, plugin: &mut PluginMainThreadHandle) {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.destroy");
+
         if let Some(destroy) = plugin.use_extension(&self.0).destroy {
             // SAFETY: This type ensures the function pointer is valid.
             unsafe { destroy(plugin.as_raw()) }
@@ -98,18 +106,22 @@ impl PluginGui {
         plugin: &mut PluginMainThreadHandle,
         scale: f64,
     ) -> Result<(), GuiError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.set_scale");
+
         let success =
             // SAFETY: This type ensures the function pointer is valid.
-            unsafe { plugin.use_extension(&self.0).set_scale.ok_or(GuiError::CreateError)?(plugin.as_raw(), scale) };
+            unsafe { plugin.use_extension(&self.0).set_scale.ok_or(GuiError::SetScaleError(CallFailure::Unsupported))?(plugin.as_raw(), scale) };
 
         match success {
             true => Ok(()),
-            false => Err(GuiError::SetScaleError),
+            false => Err(GuiError::SetScaleError(CallFailure::Declined)),
         }
     }
 
     /// Get current size of GUI
     pub fn get_size(&self, plugin: &mut PluginMainThreadHandle) -> Option<GuiSize> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.get_size");
+
         let mut width = 0;
         let mut height = 0;
 
@@ -129,6 +141,8 @@ impl PluginGui {
     ///
     /// Only applies to embedded windows.
     pub fn can_resize(&self, plugin: &mut PluginMainThreadHandle) -> bool {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.can_resize");
+
         if let Some(can_resize) = plugin.use_extension(&self.0).can_resize {
             // SAFETY: This type ensures the function pointer is valid.
             unsafe { can_resize(plugin.as_raw()) }
@@ -139,6 +153,8 @@ impl PluginGui {
 
     /// Provide hints on the resize-ability of the GUI
     pub fn get_resize_hints(&self, plugin: &mut PluginMainThreadHandle) -> Option<GuiResizeHints> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.get_resize_hints");
+
         let mut hints = clap_gui_resize_hints {
             aspect_ratio_height: u32::MAX,
             aspect_ratio_width: u32::MAX,
@@ -170,6 +186,8 @@ impl PluginGui {
         plugin: &mut PluginMainThreadHandle,
         size: GuiSize,
     ) -> Option<GuiSize> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.adjust_size");
+
         let mut new_size = size;
 
         // SAFETY: This type ensures the function pointer is valid.
@@ -189,17 +207,23 @@ impl PluginGui {
         plugin: &mut PluginMainThreadHandle,
         size: GuiSize,
     ) -> Result<(), GuiError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.set_size");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin
                 .use_extension(&self.0)
                 .set_size
-                .ok_or(GuiError::SetScaleError)?(
-                plugin.as_raw(), size.width, size.height
+                .ok_or(GuiError::SetSizeError(CallFailure::Unsupported))?(
+                plugin.as_raw(),
+                size.width,
+                size.height,
             )
         };
 
-        success.then_some(()).ok_or(GuiError::SetScaleError)
+        success
+            .then_some(())
+            .ok_or(GuiError::SetSizeError(CallFailure::Declined))
     }
 
     /// Embeds the plugin's GUI into the given parent window.
@@ -222,10 +246,15 @@ impl PluginGui {
             plugin
                 .use_extension(&self.0)
                 .set_parent
-                .ok_or(GuiError::SetParentError)?(plugin.as_raw(), window.as_raw())
+                .ok_or(GuiError::SetParentError(CallFailure::Unsupported))?(
+                plugin.as_raw(),
+                window.as_raw(),
+            )
         };
 
-        success.then_some(()).ok_or(GuiError::SetParentError)
+        success
+            .then_some(())
+            .ok_or(GuiError::SetParentError(CallFailure::Declined))
     }
 
     /// Receive instruction to stay above the given window
@@ -250,16 +279,23 @@ impl PluginGui {
             plugin
                 .use_extension(&self.0)
                 .set_transient
-                .ok_or(GuiError::SetParentError)?(plugin.as_raw(), window.as_raw())
+                .ok_or(GuiError::SetTransientError(CallFailure::Unsupported))?(
+                plugin.as_raw(),
+                window.as_raw(),
+            )
         };
 
-        success.then_some(()).ok_or(GuiError::SetParentError)
+        success
+            .then_some(())
+            .ok_or(GuiError::SetTransientError(CallFailure::Declined))
     }
 
     /// Give a suggested window title to the plugin.
     ///
     /// Only applies to floating windows.
     pub fn suggest_title(&self, plugin: &mut PluginMainThreadHandle, title: &CStr) {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.suggest_title");
+
         if let Some(suggest_title) = plugin.use_extension(&self.0).suggest_title {
             // SAFETY: This type ensures the function pointer is valid.
             unsafe { suggest_title(plugin.as_raw(), title.as_ptr()) }
@@ -268,30 +304,34 @@ impl PluginGui {
 
     /// Show the window
     pub fn show(&self, plugin: &mut PluginMainThreadHandle) -> Result<(), GuiError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.show");
+
         // SAFETY: This type ensures the function pointer is valid.
         unsafe {
             plugin
                 .use_extension(&self.0)
                 .show
-                .ok_or(GuiError::ShowError)?(plugin.as_raw())
+                .ok_or(GuiError::ShowError(CallFailure::Unsupported))?(plugin.as_raw())
         }
         .then_some(())
-        .ok_or(GuiError::ShowError)
+        .ok_or(GuiError::ShowError(CallFailure::Declined))
     }
 
     /// Hide the window
     ///
     /// This should not free the resources associated with the GUI, just hide it.
     pub fn hide(&self, plugin: &mut PluginMainThreadHandle) -> Result<(), GuiError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_gui.hide");
+
         // SAFETY: This type ensures the function pointer is valid.
         unsafe {
             plugin
                 .use_extension(&self.0)
                 .hide
-                .ok_or(GuiError::ShowError)?(plugin.as_raw())
+                .ok_or(GuiError::HideError(CallFailure::Unsupported))?(plugin.as_raw())
         }
         .then_some(())
-        .ok_or(GuiError::ShowError)
+        .ok_or(GuiError::HideError(CallFailure::Declined))
     }
 }
 
@@ -335,8 +375,11 @@ pub trait HostGuiImpl {
     /// Notifies the host that either the floating window has been closed, or that the connection to
     /// the GUI was lost.
     ///
-    /// If `is_destroyed` is true, then the host must call `destroy` to acknowledge the GUI destruction.
-    fn closed(&self, was_destroyed: bool);
+    /// If `reason` is [`GuiClosedReason::Destroyed`], then the host must call `destroy` to
+    /// acknowledge the GUI destruction. This may be called from any thread: hosts using a
+    /// [`GuiSession`] can forward it to a [`GuiClosedListener`], so that the session acknowledges
+    /// it automatically.
+    fn closed(&self, reason: GuiClosedReason);
 }
 
 // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
@@ -406,7 +449,8 @@ where
     for<'a> <H as HostHandlers>::Shared<'a>: HostGuiImpl,
 {
     HostWrapper::<H>::handle(host, |host| {
-        host.shared().closed(was_destroyed);
+        host.shared()
+            .closed(GuiClosedReason::from_was_destroyed(was_destroyed));
         Ok(())
     });
 }
@@ -0,0 +1,110 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+
+/// The outcome of a GUI configuration negotiation, as returned by [`PluginGui::negotiate`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GuiNegotiation {
+    /// The plugin's preferred configuration is supported by both sides, and should be used.
+    Preferred(GuiConfiguration<'static>),
+    /// The plugin supports being embedded into a host-provided window using this configuration.
+    Embedded(GuiConfiguration<'static>),
+    /// The plugin only supports creating its own floating window using this configuration.
+    Floating(GuiConfiguration<'static>),
+    /// No configuration is supported by both the plugin and the host.
+    ///
+    /// The plugin must be treated as if it had no GUI at all.
+    Headless,
+}
+
+impl GuiNegotiation {
+    /// Returns the negotiated configuration, or [`None`] if the plugin is [headless](Self::Headless).
+    #[inline]
+    pub fn configuration(&self) -> Option<GuiConfiguration<'static>> {
+        match self {
+            GuiNegotiation::Preferred(c)
+            | GuiNegotiation::Embedded(c)
+            | GuiNegotiation::Floating(c) => Some(*c),
+            GuiNegotiation::Headless => None,
+        }
+    }
+
+    /// Returns `true` if the negotiated configuration uses a floating window, `false` if it is
+    /// embedded, or [`None`] if the plugin is [headless](Self::Headless).
+    #[inline]
+    pub fn is_floating(&self) -> Option<bool> {
+        self.configuration().map(|c| c.is_floating)
+    }
+}
+
+impl PluginGui {
+    /// Negotiates a GUI configuration supported by both the plugin and the host.
+    ///
+    /// `host_apis` lists the GUI APIs the host is able to use, in order of preference. This
+    /// usually only contains [`GuiApiType::default_for_current_platform`].
+    ///
+    /// The configurations are tried in the following order:
+    ///
+    /// 1. The plugin's [preferred configuration](Self::get_preferred_api), if its API is one of
+    ///    `host_apis`, and if the API supports embedding in case the plugin prefers it;
+    /// 2. Each API of `host_apis` that [supports embedding](GuiApiType::supports_embedding), in
+    ///    embedded mode;
+    /// 3. Each API of `host_apis`, in floating mode.
+    ///
+    /// The plugin is queried through [`is_api_supported`](Self::is_api_supported) for every
+    /// candidate, including its own preference, as the latter is only a hint. If none of the
+    /// candidates is accepted, [`GuiNegotiation::Headless`] is returned.
+    pub fn negotiate(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        host_apis: &[GuiApiType<'static>],
+    ) -> GuiNegotiation {
+        if let Some(preferred) = self.preferred_host_configuration(plugin, host_apis) {
+            if self.is_api_supported(plugin, preferred) {
+                return GuiNegotiation::Preferred(preferred);
+            }
+        }
+
+        for &api_type in host_apis.iter().filter(|a| a.supports_embedding()) {
+            let configuration = GuiConfiguration {
+                api_type,
+                is_floating: false,
+            };
+
+            if self.is_api_supported(plugin, configuration) {
+                return GuiNegotiation::Embedded(configuration);
+            }
+        }
+
+        for &api_type in host_apis {
+            let configuration = GuiConfiguration {
+                api_type,
+                is_floating: true,
+            };
+
+            if self.is_api_supported(plugin, configuration) {
+                return GuiNegotiation::Floating(configuration);
+            }
+        }
+
+        GuiNegotiation::Headless
+    }
+
+    /// Returns the plugin's preferred configuration, but only if it can be used by the host.
+    fn preferred_host_configuration(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        host_apis: &[GuiApiType<'static>],
+    ) -> Option<GuiConfiguration<'static>> {
+        let preferred = self.get_preferred_api(plugin)?;
+        let api_type = *host_apis.iter().find(|a| **a == preferred.api_type)?;
+
+        if !preferred.is_floating && !api_type.supports_embedding() {
+            return None;
+        }
+
+        Some(GuiConfiguration {
+            api_type,
+            is_floating: preferred.is_floating,
+        })
+    }
+}
@@ -19,7 +19,7 @@ impl HostGui {
     ///
     /// # Errors
     ///
-    /// This may return a [`GuiError::ResizeError`] if the host denied or was unable to fulfill the
+    /// This may return a [`GuiError::RequestResizeError`] if the host denied or was unable to fulfill the
     /// request.
     ///
     /// Note: as this may not be called from the main thread, a successful return value may only
@@ -36,11 +36,15 @@ impl HostGui {
         if unsafe {
             host.use_extension(&self.0)
                 .request_resize
-                .ok_or(GuiError::RequestResizeError)?(host.as_raw(), width, height)
+                .ok_or(GuiError::RequestResizeError(CallFailure::Unsupported))?(
+                host.as_raw(),
+                width,
+                height,
+            )
         } {
             Ok(())
         } else {
-            Err(GuiError::RequestResizeError)
+            Err(GuiError::RequestResizeError(CallFailure::Declined))
         }
     }
 
@@ -55,11 +59,13 @@ impl HostGui {
         if unsafe {
             host.use_extension(&self.0)
                 .request_show
-                .ok_or(GuiError::RequestShowError)?(host.as_raw())
+                .ok_or(GuiError::RequestShowError(CallFailure::Unsupported))?(
+                host.as_raw()
+            )
         } {
             Ok(())
         } else {
-            Err(GuiError::RequestShowError)
+            Err(GuiError::RequestShowError(CallFailure::Declined))
         }
     }
 
@@ -74,22 +80,25 @@ impl HostGui {
         if unsafe {
             host.use_extension(&self.0)
                 .request_hide
-                .ok_or(GuiError::RequestHideError)?(host.as_raw())
+                .ok_or(GuiError::RequestHideError(CallFailure::Unsupported))?(
+                host.as_raw()
+            )
         } {
             Ok(())
         } else {
-            Err(GuiError::RequestHideError)
+            Err(GuiError::RequestHideError(CallFailure::Declined))
         }
     }
 
     /// Notifies the host that either the floating window has been closed, or that the connection to
     /// the GUI was lost.
     ///
-    /// If `is_destroyed` is true, then the host must call `destroy` to acknowledge the GUI destruction.
-    pub fn closed(&self, host: &HostSharedHandle, was_destroyed: bool) {
+    /// If `reason` is [`GuiClosedReason::Destroyed`], then the host must call `destroy` to
+    /// acknowledge the GUI destruction.
+    pub fn closed(&self, host: &HostSharedHandle, reason: GuiClosedReason) {
         if let Some(closed) = host.use_extension(&self.0).closed {
             // SAFETY: This type ensures the function pointer is valid.
-            unsafe { closed(host.as_raw(), was_destroyed) }
+            unsafe { closed(host.as_raw(), reason.was_destroyed()) }
         }
     }
 }
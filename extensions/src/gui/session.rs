@@ -0,0 +1,669 @@
+use super::*;
+use clack_host::extensions::prelude::*;
+use std::sync::atomic::{AtomicU8, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+/// The lifecycle state of a plugin's GUI, as tracked by a [`GuiSession`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum GuiSessionState {
+    /// The GUI hasn't been created yet, or has been destroyed.
+    Destroyed,
+    /// The GUI has been created, but isn't attached to a parent window yet.
+    Created,
+    /// The GUI has been attached to a parent (for embedded windows) or transient (for floating
+    /// windows) window, but hasn't been shown yet.
+    Attached,
+    /// The GUI is currently shown.
+    Visible,
+    /// The GUI has been shown, and then hidden.
+    Hidden,
+}
+
+impl GuiSessionState {
+    /// Returns `true` if the GUI currently exists, i.e. it was created and not destroyed yet.
+    #[inline]
+    pub fn is_created(&self) -> bool {
+        !matches!(self, GuiSessionState::Destroyed)
+    }
+}
+
+impl Display for GuiSessionState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuiSessionState::Destroyed => f.write_str("destroyed"),
+            GuiSessionState::Created => f.write_str("created"),
+            GuiSessionState::Attached => f.write_str("attached"),
+            GuiSessionState::Visible => f.write_str("visible"),
+            GuiSessionState::Hidden => f.write_str("hidden"),
+        }
+    }
+}
+
+/// Errors that can occur when using a [`GuiSession`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GuiSessionError {
+    /// The operation cannot be performed in the GUI's current state.
+    InvalidState {
+        /// The name of the operation that was attempted.
+        operation: &'static str,
+        /// The state the GUI was in.
+        state: GuiSessionState,
+    },
+    /// The operation only applies to floating windows, but the GUI is embedded.
+    NotFloating {
+        /// The name of the operation that was attempted.
+        operation: &'static str,
+    },
+    /// The operation only applies to embedded windows, but the GUI is floating.
+    NotEmbedded {
+        /// The name of the operation that was attempted.
+        operation: &'static str,
+    },
+    /// The plugin failed to perform the operation.
+    Gui(GuiError),
+}
+
+impl Display for GuiSessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuiSessionError::InvalidState { operation, state } => {
+                write!(f, "Cannot call {operation} while plugin GUI is {state}")
+            }
+            GuiSessionError::NotFloating { operation } => {
+                write!(f, "Cannot call {operation} on an embedded plugin GUI")
+            }
+            GuiSessionError::NotEmbedded { operation } => {
+                write!(f, "Cannot call {operation} on a floating plugin GUI")
+            }
+            GuiSessionError::Gui(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for GuiSessionError {}
+
+impl From<GuiError> for GuiSessionError {
+    #[inline]
+    fn from(e: GuiError) -> Self {
+        Self::Gui(e)
+    }
+}
+
+const NOT_CLOSED: u8 = 0;
+const CLOSED_HIDDEN: u8 = 1;
+const CLOSED_DESTROYED: u8 = 2;
+
+/// A thread-safe handle that forwards the plugin's [`closed`](HostGuiImpl::closed) notifications
+/// to a [`GuiSession`].
+///
+/// The plugin may notify the host that its GUI was closed from any thread, while the
+/// [`GuiSession`] lives on the main thread. Hosts can store a clone of this listener in their
+/// shared handler, call [`notify`](Self::notify) from their [`HostGuiImpl::closed`]
+/// implementation, and give it to the session using
+/// [`GuiSession::with_closed_listener`].
+///
+/// The session then picks up the notification before any other GUI operation: a destroyed GUI is
+/// acknowledged with a call to `destroy`, and is never called into again until it is re-created.
+#[derive(Clone, Debug, Default)]
+pub struct GuiClosedListener {
+    pending: Arc<AtomicU8>,
+}
+
+impl GuiClosedListener {
+    /// Creates a new listener, with no pending notification.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the plugin notified its GUI was closed for the given `reason`.
+    ///
+    /// If multiple notifications are received before the session picks them up, a
+    /// [`Destroyed`](GuiClosedReason::Destroyed) notification takes precedence.
+    #[inline]
+    pub fn notify(&self, reason: GuiClosedReason) {
+        let value = match reason {
+            GuiClosedReason::Hidden => CLOSED_HIDDEN,
+            GuiClosedReason::Destroyed => CLOSED_DESTROYED,
+        };
+
+        self.pending.fetch_max(value, AtomicOrdering::AcqRel);
+    }
+
+    /// Returns the pending notification, if any, without clearing it.
+    #[inline]
+    pub fn pending(&self) -> Option<GuiClosedReason> {
+        Self::decode(self.pending.load(AtomicOrdering::Acquire))
+    }
+
+    #[inline]
+    fn take(&self) -> Option<GuiClosedReason> {
+        Self::decode(self.pending.swap(NOT_CLOSED, AtomicOrdering::AcqRel))
+    }
+
+    #[inline]
+    fn decode(value: u8) -> Option<GuiClosedReason> {
+        match value {
+            CLOSED_HIDDEN => Some(GuiClosedReason::Hidden),
+            CLOSED_DESTROYED => Some(GuiClosedReason::Destroyed),
+            _ => None,
+        }
+    }
+}
+
+/// The window state of a plugin's GUI, which hosts can persist between the times it is opened.
+///
+/// This is captured by [`GuiSession::destroy_and_save`] or
+/// [`GuiSession::capture_window_state`], and re-applied by [`GuiSession::create_restored`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GuiWindowState {
+    /// The size of the GUI, if the plugin reported it.
+    pub size: Option<GuiSize>,
+    /// The scaling factor that was last applied to the GUI, if any.
+    pub scale: Option<f64>,
+    /// Whether the GUI used a floating window, or was embedded.
+    pub is_floating: bool,
+}
+
+/// A user-provided storage for the [`GuiWindowState`] of a single plugin instance.
+///
+/// Hosts typically implement this on their per-instance data, to persist the GUI's window state
+/// alongside e.g. the rest of the project. An in-memory implementation is provided for
+/// `Option<GuiWindowState>`.
+pub trait GuiWindowStateStore {
+    /// Returns the previously saved window state, if any.
+    fn load_window_state(&mut self) -> Option<GuiWindowState>;
+
+    /// Saves the given window state, replacing any previously saved one.
+    fn save_window_state(&mut self, state: GuiWindowState);
+}
+
+impl GuiWindowStateStore for Option<GuiWindowState> {
+    #[inline]
+    fn load_window_state(&mut self) -> Option<GuiWindowState> {
+        *self
+    }
+
+    #[inline]
+    fn save_window_state(&mut self, state: GuiWindowState) {
+        *self = Some(state);
+    }
+}
+
+/// A wrapper around a plugin's [`PluginGui`] extension, which enforces the call ordering required
+/// by the CLAP specification.
+///
+/// Plugin GUIs must be created before any other operation is performed, then attached to a
+/// parent window (for embedded windows) and shown. They can then be hidden and shown again at
+/// will, until they are destroyed. See the [module docs](super) for the full list of steps.
+///
+/// This type tracks the current state of the plugin's GUI, and returns a
+/// [`GuiSessionError::InvalidState`] error instead of calling into the plugin whenever an
+/// operation is attempted in the wrong state. Operations that only apply to either floating or
+/// embedded windows are also checked against the configuration the GUI was created with.
+///
+/// The plugin may also close its GUI on its own, which must be reported to the session using
+/// either [`handle_closed`](Self::handle_closed), or a [`GuiClosedListener`].
+///
+/// Note that a session does not destroy the plugin's GUI when dropped:
+/// [`destroy`](Self::destroy) must be called before the plugin instance is destroyed.
+pub struct GuiSession {
+    gui: PluginGui,
+    state: GuiSessionState,
+    is_floating: bool,
+    scale: Option<f64>,
+    closed_listener: Option<GuiClosedListener>,
+}
+
+impl Debug for GuiSession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuiSession")
+            .field("state", &self.state)
+            .field("is_floating", &self.is_floating)
+            .field("scale", &self.scale)
+            .finish()
+    }
+}
+
+impl GuiSession {
+    /// Creates a new GUI session for the given plugin GUI extension.
+    ///
+    /// The plugin's GUI is not created yet: [`create`](Self::create) must be called first.
+    #[inline]
+    pub fn new(gui: PluginGui) -> Self {
+        Self {
+            gui,
+            state: GuiSessionState::Destroyed,
+            is_floating: false,
+            scale: None,
+            closed_listener: None,
+        }
+    }
+
+    /// Makes this session pick up the [`closed`](HostGuiImpl::closed) notifications received by
+    /// the given listener.
+    ///
+    /// See [`GuiClosedListener`] for more information.
+    #[inline]
+    pub fn with_closed_listener(mut self, listener: GuiClosedListener) -> Self {
+        self.closed_listener = Some(listener);
+        self
+    }
+
+    /// Returns the plugin GUI extension this session wraps.
+    #[inline]
+    pub fn plugin_gui(&self) -> PluginGui {
+        self.gui
+    }
+
+    /// Returns the current state of the plugin's GUI.
+    ///
+    /// This takes into account the notifications received by the session's
+    /// [`GuiClosedListener`], if any, even if they haven't been acknowledged yet.
+    #[inline]
+    pub fn state(&self) -> GuiSessionState {
+        let pending = self.closed_listener.as_ref().and_then(|l| l.pending());
+
+        match pending {
+            Some(GuiClosedReason::Destroyed) => GuiSessionState::Destroyed,
+            Some(GuiClosedReason::Hidden) if self.state.is_created() => GuiSessionState::Hidden,
+            _ => self.state,
+        }
+    }
+
+    /// Returns `true` if the plugin's GUI is currently created, and uses a floating window.
+    #[inline]
+    pub fn is_floating(&self) -> bool {
+        self.state().is_created() && self.is_floating
+    }
+
+    /// Returns the scaling factor that was last successfully applied to the plugin's GUI, if any.
+    #[inline]
+    pub fn scale(&self) -> Option<f64> {
+        self.scale
+    }
+
+    /// Returns `true` if the plugin's GUI is currently shown.
+    #[inline]
+    pub fn is_visible(&self) -> bool {
+        self.state() == GuiSessionState::Visible
+    }
+
+    /// Acknowledges the pending closed notification of the session's listener, if any.
+    fn sync_closed(&mut self, plugin: &mut PluginMainThreadHandle) {
+        if let Some(reason) = self.closed_listener.as_ref().and_then(|l| l.take()) {
+            self.apply_closed(plugin, reason);
+        }
+    }
+
+    fn apply_closed(&mut self, plugin: &mut PluginMainThreadHandle, reason: GuiClosedReason) {
+        match reason {
+            GuiClosedReason::Destroyed => self.destroy_created(plugin),
+            GuiClosedReason::Hidden if self.state.is_created() => {
+                self.state = GuiSessionState::Hidden
+            }
+            GuiClosedReason::Hidden => {}
+        }
+    }
+
+    fn destroy_created(&mut self, plugin: &mut PluginMainThreadHandle) {
+        if self.state.is_created() {
+            self.gui.destroy(plugin);
+            self.state = GuiSessionState::Destroyed;
+        }
+    }
+
+    fn expect_state(
+        &self,
+        operation: &'static str,
+        allowed: &[GuiSessionState],
+    ) -> Result<(), GuiSessionError> {
+        let state = self.state();
+        if allowed.contains(&state) {
+            Ok(())
+        } else {
+            Err(GuiSessionError::InvalidState { operation, state })
+        }
+    }
+
+    fn expect_created(&self, operation: &'static str) -> Result<(), GuiSessionError> {
+        let state = self.state();
+        if state.is_created() {
+            Ok(())
+        } else {
+            Err(GuiSessionError::InvalidState { operation, state })
+        }
+    }
+
+    fn expect_floating(&self, operation: &'static str) -> Result<(), GuiSessionError> {
+        self.expect_created(operation)?;
+
+        match self.is_floating {
+            true => Ok(()),
+            false => Err(GuiSessionError::NotFloating { operation }),
+        }
+    }
+
+    fn expect_embedded(&self, operation: &'static str) -> Result<(), GuiSessionError> {
+        self.expect_created(operation)?;
+
+        match self.is_floating {
+            true => Err(GuiSessionError::NotEmbedded { operation }),
+            false => Ok(()),
+        }
+    }
+
+    /// Creates the plugin's GUI with the given configuration.
+    ///
+    /// This can only be called while the GUI is [`Destroyed`](GuiSessionState::Destroyed).
+    ///
+    /// See [`PluginGui::create`].
+    pub fn create(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        configuration: GuiConfiguration,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_state("create", &[GuiSessionState::Destroyed])?;
+
+        self.gui.create(plugin, configuration)?;
+        self.state = GuiSessionState::Created;
+        self.is_floating = configuration.is_floating;
+        self.scale = None;
+
+        Ok(())
+    }
+
+    /// Sets the scaling factor of the plugin's GUI.
+    ///
+    /// This can be called at any time while the GUI is created.
+    ///
+    /// See [`PluginGui::set_scale`].
+    pub fn set_scale(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        scale: f64,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_created("set_scale")?;
+
+        self.gui.set_scale(plugin, scale)?;
+        self.scale = Some(scale);
+
+        Ok(())
+    }
+
+    /// Returns the current size of the plugin's GUI.
+    ///
+    /// This returns `None` if the plugin's GUI isn't created, or if the plugin failed to provide
+    /// its size.
+    ///
+    /// See [`PluginGui::get_size`].
+    pub fn get_size(&self, plugin: &mut PluginMainThreadHandle) -> Option<GuiSize> {
+        self.state().is_created().then_some(())?;
+        self.gui.get_size(plugin)
+    }
+
+    /// Returns `true` if the plugin's GUI is embedded and can be resized.
+    ///
+    /// See [`PluginGui::can_resize`].
+    pub fn can_resize(&self, plugin: &mut PluginMainThreadHandle) -> bool {
+        self.expect_embedded("can_resize").is_ok() && self.gui.can_resize(plugin)
+    }
+
+    /// Returns the resize hints of the plugin's embedded GUI.
+    ///
+    /// See [`PluginGui::get_resize_hints`].
+    pub fn get_resize_hints(&self, plugin: &mut PluginMainThreadHandle) -> Option<GuiResizeHints> {
+        self.expect_embedded("get_resize_hints").ok()?;
+        self.gui.get_resize_hints(plugin)
+    }
+
+    /// Computes the closest size the plugin's embedded GUI can be resized to.
+    ///
+    /// See [`PluginGui::adjust_size`].
+    pub fn adjust_size(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        size: GuiSize,
+    ) -> Option<GuiSize> {
+        self.expect_embedded("adjust_size").ok()?;
+        self.gui.adjust_size(plugin, size)
+    }
+
+    /// Sets the size of the plugin's embedded GUI.
+    ///
+    /// See [`PluginGui::set_size`].
+    pub fn set_size(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        size: GuiSize,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_embedded("set_size")?;
+        Ok(self.gui.set_size(plugin, size)?)
+    }
+
+    /// Embeds the plugin's GUI into the given parent window.
+    ///
+    /// This can only be called once, on an embedded GUI that was just
+    /// [`Created`](GuiSessionState::Created).
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as [`PluginGui::set_parent`] apply: the window must remain
+    /// valid until [`destroy`](Self::destroy) is called.
+    pub unsafe fn set_parent(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        window: Window,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_embedded("set_parent")?;
+        self.expect_state("set_parent", &[GuiSessionState::Created])?;
+
+        self.gui.set_parent(plugin, window)?;
+        self.state = GuiSessionState::Attached;
+
+        Ok(())
+    }
+
+    /// Makes the plugin's floating GUI stay above the given window.
+    ///
+    /// This can only be called once, on a floating GUI that was just
+    /// [`Created`](GuiSessionState::Created).
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as [`PluginGui::set_transient`] apply: the window must remain
+    /// valid until [`destroy`](Self::destroy) is called.
+    pub unsafe fn set_transient(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        window: Window,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_floating("set_transient")?;
+        self.expect_state("set_transient", &[GuiSessionState::Created])?;
+
+        self.gui.set_transient(plugin, window)?;
+        self.state = GuiSessionState::Attached;
+
+        Ok(())
+    }
+
+    /// Suggests a window title to the plugin's floating GUI.
+    ///
+    /// See [`PluginGui::suggest_title`].
+    pub fn suggest_title(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        title: &CStr,
+    ) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_floating("suggest_title")?;
+        self.gui.suggest_title(plugin, title);
+        Ok(())
+    }
+
+    /// Shows the plugin's GUI.
+    ///
+    /// Embedded GUIs must have been attached to a parent window using
+    /// [`set_parent`](Self::set_parent) first. Floating GUIs can be shown as soon as they are
+    /// created.
+    ///
+    /// See [`PluginGui::show`].
+    pub fn show(&mut self, plugin: &mut PluginMainThreadHandle) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        if self.is_floating {
+            self.expect_state(
+                "show",
+                &[
+                    GuiSessionState::Created,
+                    GuiSessionState::Attached,
+                    GuiSessionState::Hidden,
+                ],
+            )?;
+        } else {
+            self.expect_state(
+                "show",
+                &[GuiSessionState::Attached, GuiSessionState::Hidden],
+            )?;
+        }
+
+        self.gui.show(plugin)?;
+        self.state = GuiSessionState::Visible;
+
+        Ok(())
+    }
+
+    /// Hides the plugin's GUI, without destroying it.
+    ///
+    /// This can only be called while the GUI is [`Visible`](GuiSessionState::Visible).
+    ///
+    /// See [`PluginGui::hide`].
+    pub fn hide(&mut self, plugin: &mut PluginMainThreadHandle) -> Result<(), GuiSessionError> {
+        self.sync_closed(plugin);
+        self.expect_state("hide", &[GuiSessionState::Visible])?;
+
+        self.gui.hide(plugin)?;
+        self.state = GuiSessionState::Hidden;
+
+        Ok(())
+    }
+
+    /// Destroys the plugin's GUI, if it is created. Otherwise, this does nothing.
+    ///
+    /// See [`PluginGui::destroy`].
+    pub fn destroy(&mut self, plugin: &mut PluginMainThreadHandle) {
+        self.sync_closed(plugin);
+        self.destroy_created(plugin);
+    }
+
+    /// Captures the current window state of the plugin's GUI, which can later be re-applied
+    /// using [`create_restored`](Self::create_restored).
+    ///
+    /// This returns `None` if the plugin's GUI isn't created.
+    pub fn capture_window_state(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+    ) -> Option<GuiWindowState> {
+        self.state().is_created().then_some(())?;
+
+        Some(GuiWindowState {
+            size: self.gui.get_size(plugin),
+            scale: self.scale,
+            is_floating: self.is_floating,
+        })
+    }
+
+    /// Creates the plugin's GUI, re-applying the window state previously saved in the given
+    /// store, if any.
+    ///
+    /// The saved floating or embedded mode is used instead of the one in the given
+    /// `configuration`, if the plugin supports it. Then, the saved scale and size (for resizable
+    /// embedded GUIs only) are applied. Failing to re-apply those is not considered an error, as
+    /// they are only hints: hosts that know the current scale of their windows should still call
+    /// [`set_scale`](Self::set_scale) afterward.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the GUI could not be created. See [`create`](Self::create).
+    pub fn create_restored(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        mut configuration: GuiConfiguration,
+        store: &mut impl GuiWindowStateStore,
+    ) -> Result<(), GuiSessionError> {
+        let saved = store.load_window_state();
+
+        if let Some(saved) = saved {
+            let saved_configuration = GuiConfiguration {
+                is_floating: saved.is_floating,
+                ..configuration
+            };
+
+            if saved.is_floating != configuration.is_floating
+                && self.gui.is_api_supported(plugin, saved_configuration)
+            {
+                configuration = saved_configuration;
+            }
+        }
+
+        self.create(plugin, configuration)?;
+
+        let Some(saved) = saved else {
+            return Ok(());
+        };
+
+        if let Some(scale) = saved.scale {
+            let _ = self.set_scale(plugin, scale);
+        }
+
+        if let Some(size) = saved.size {
+            if self.can_resize(plugin) {
+                let size = self.gui.adjust_size(plugin, size).unwrap_or(size);
+                let _ = self.set_size(plugin, size);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the current window state of the plugin's GUI into the given store, then destroys
+    /// it. If the GUI isn't created, this does nothing.
+    ///
+    /// Note that if the plugin already destroyed its GUI on its own (see
+    /// [`GuiClosedReason::Destroyed`]), its window state cannot be captured anymore, and the
+    /// store is left untouched. Hosts can also call
+    /// [`capture_window_state`](Self::capture_window_state) at any other time (e.g. after a
+    /// resize) to keep their store up to date.
+    pub fn destroy_and_save(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+        store: &mut impl GuiWindowStateStore,
+    ) {
+        self.sync_closed(plugin);
+
+        if let Some(state) = self.capture_window_state(plugin) {
+            store.save_window_state(state);
+        }
+
+        self.destroy_created(plugin);
+    }
+
+    /// Updates the session after the plugin notified the host its GUI was closed, through
+    /// [`HostGuiImpl::closed`].
+    ///
+    /// If the GUI was [`Hidden`](GuiClosedReason::Hidden), it is considered
+    /// [`Hidden`](GuiSessionState::Hidden), and can be shown again. Otherwise, the host must
+    /// acknowledge its destruction, which this method does by calling [`destroy`](Self::destroy).
+    ///
+    /// Hosts using a [`GuiClosedListener`] do not need to call this method, as the session
+    /// already does so automatically.
+    pub fn handle_closed(&mut self, plugin: &mut PluginMainThreadHandle, reason: GuiClosedReason) {
+        // Also consume the listener's notification, which may be the same as this one.
+        self.sync_closed(plugin);
+        self.apply_closed(plugin, reason);
+    }
+}
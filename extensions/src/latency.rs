@@ -3,7 +3,6 @@ use clap_sys::ext::latency::{clap_host_latency, clap_plugin_latency, CLAP_EXT_LA
 use std::ffi::CStr;
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginLatency(RawExtension<PluginExtensionSide, clap_plugin_latency>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -17,8 +16,18 @@ unsafe impl Extension for PluginLatency {
     }
 }
 
+impl PluginLatency {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_latency> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostLatency(RawExtension<HostExtensionSide, clap_host_latency>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -32,6 +41,17 @@ unsafe impl Extension for HostLatency {
     }
 }
 
+impl HostLatency {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_latency> {
+        self.0
+    }
+}
+
 #[cfg(feature = "clack-host")]
 mod host {
     use super::*;
@@ -40,6 +60,8 @@ mod host {
     impl PluginLatency {
         #[inline]
         pub fn get(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            crate::utils::trace_call!(plugin, "clap_plugin_latency.get");
+
             match plugin.use_extension(&self.0).get {
                 None => 0,
                 // SAFETY: This type ensures the function pointer is valid.
@@ -1,3 +1,39 @@
+//! Implementations of the standard CLAP extensions, for both Clack plugins and hosts.
+//!
+//! # Features
+//!
+//! Every extension is behind its own Cargo feature, named after the extension (e.g. `gui`,
+//! `params`, `audio-ports`, …), so that only the extensions that are actually needed get compiled.
+//! The `all-extensions` feature enables all of them at once.
+//!
+//! Plugin-side and host-side implementations of each enabled extension are respectively
+//! enabled by the `clack-plugin` and `clack-host` features.
+//!
+//! The `raw-window-handle_05` and `raw-window-handle_06` features enable integrations with the
+//! corresponding versions of the `raw-window-handle` crate for the `gui` extension, which they
+//! also enable.
+//!
+//! The `tracing` feature makes the host-side implementations emit `tracing` spans around the
+//! host's main-thread calls to plugin extensions.
+//!
+//! Finally, the `full` feature enables all of the above, including draft extensions.
+//!
+//! # Draft extensions
+//!
+//! Draft CLAP extensions are still being designed, and may change in incompatible ways between
+//! CLAP releases. They are only available when the `draft` feature is enabled, which is implied
+//! by each draft extension's own feature (e.g. `track-info`). The `all-draft-extensions` feature
+//! enables all of them.
+//!
+//! Each revision of a draft extension lives in its own versioned module, named after the revision
+//! number found in its identifier (e.g. `track_info::v1` for `clap.track-info.draft/1`).
+//! The extension's module itself re-exports its latest revision.
+//!
+//! When a new revision of a draft extension is published, it is added in a new module, and the
+//! re-export is updated to point to it. Older revisions are kept around, so that code that needs a
+//! specific ABI revision can keep using it through its versioned path. Once an extension becomes
+//! stable, it moves to its own feature and module, and its draft revisions are eventually removed.
+
 #![doc(html_logo_url = "https://raw.githubusercontent.com/prokopyl/clack/main/logo.svg")]
 #![deny(clippy::undocumented_unsafe_blocks)]
 
@@ -36,8 +72,26 @@ pub mod timer;
 #[cfg(feature = "voice-info")]
 pub mod voice_info;
 
+#[cfg(feature = "ambisonic")]
+pub mod ambisonic;
+#[cfg(feature = "ara")]
+pub mod ara;
+#[cfg(feature = "remote-controls")]
+pub mod remote_controls;
+#[cfg(feature = "resource-directory")]
+pub mod resource_directory;
+#[cfg(feature = "surround")]
+pub mod surround;
+#[cfg(feature = "track-info")]
+pub mod track_info;
+
+pub mod error;
+
 pub(crate) mod utils;
 
 #[cfg(test)]
 #[doc(hidden)]
 pub mod __doc_utils;
+
+#[cfg(all(test, feature = "clack-host", feature = "clack-plugin"))]
+mod smoke_tests;
@@ -12,6 +12,11 @@ mod host;
 #[cfg(feature = "clack-host")]
 pub use host::*;
 
+#[cfg(feature = "clack-host")]
+mod rate_limit;
+#[cfg(feature = "clack-host")]
+pub use rate_limit::RateLimitedLog;
+
 #[repr(i32)]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum LogSeverity {
@@ -65,7 +70,6 @@ impl Display for LogSeverity {
 }
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostLog(RawExtension<HostExtensionSide, clap_host_log>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -79,6 +83,17 @@ unsafe impl Extension for HostLog {
     }
 }
 
+impl HostLog {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_log> {
+        self.0
+    }
+}
+
 #[cfg(feature = "clack-plugin")]
 mod plugin {
     use super::*;
@@ -0,0 +1,240 @@
+use super::{HostLogImpl, LogSeverity};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A [`HostLogImpl`] wrapper, which limits the rate at which messages are forwarded to an inner
+/// logger.
+///
+/// Plugins may log from any thread, including the audio thread. A plugin logging on every
+/// `process` call can then flood the host's logger with messages, which may take locks of its
+/// own (e.g. to write to the standard output or to a file), and cause priority inversion on the
+/// audio thread.
+///
+/// This wrapper uses a lock-free token bucket: up to a [`burst`](Self::with_burst) of messages
+/// can be logged at once, after which messages are only forwarded at the given
+/// [`rate`](Self::with_rate). Messages above that rate are dropped without ever reaching the inner
+/// logger. Dropping a message never locks nor allocates.
+///
+/// Once messages can be forwarded again, a single [`LogSeverity::Warning`] message is logged
+/// before the next one, reporting how many messages were dropped. That message is formatted into
+/// a fixed-size buffer on the stack, and doesn't allocate either.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::log::{HostLogImpl, LogSeverity, RateLimitedLog};
+///
+/// struct StdErrLogger;
+///
+/// impl HostLogImpl for StdErrLogger {
+///     fn log(&self, severity: LogSeverity, message: &str) {
+///         eprintln!("[{severity}] {message}");
+///     }
+/// }
+///
+/// // Allow bursts of up to 10 messages, then at most 2 messages per second.
+/// let logger = RateLimitedLog::new(StdErrLogger).with_rate(2).with_burst(10);
+///
+/// for _ in 0..100 {
+///     logger.log(LogSeverity::Info, "Processing...");
+/// }
+///
+/// assert_eq!(logger.dropped_count(), 90);
+/// ```
+pub struct RateLimitedLog<L> {
+    inner: L,
+    start: Instant,
+    /// The minimum interval between two messages, in nanoseconds.
+    interval: u64,
+    /// How far ahead of the current time the next message slot can go, in nanoseconds.
+    tolerance: u64,
+    /// The theoretical time of the next allowed message, in nanoseconds since `start`.
+    next_slot: AtomicU64,
+    dropped: AtomicU32,
+}
+
+impl<L> RateLimitedLog<L> {
+    /// The default maximum number of messages per second.
+    pub const DEFAULT_RATE: u32 = 10;
+    /// The default maximum number of messages that can be logged at once.
+    pub const DEFAULT_BURST: u32 = 50;
+
+    /// Wraps the given logger, forwarding messages with the default rate and burst limits.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            start: Instant::now(),
+            interval: 0,
+            tolerance: 0,
+            next_slot: AtomicU64::new(0),
+            dropped: AtomicU32::new(0),
+        }
+        .with_rate(Self::DEFAULT_RATE)
+        .with_burst(Self::DEFAULT_BURST)
+    }
+
+    /// Sets the maximum number of messages forwarded per second, once the burst is exhausted.
+    ///
+    /// A rate of zero is treated as one message per second.
+    #[inline]
+    pub fn with_rate(mut self, messages_per_second: u32) -> Self {
+        let burst = self.burst();
+        self.interval =
+            Duration::from_secs(1).as_nanos() as u64 / messages_per_second.max(1) as u64;
+        self.tolerance = self.interval * burst.saturating_sub(1) as u64;
+        self
+    }
+
+    /// Sets the maximum number of messages that can be forwarded at once.
+    ///
+    /// A burst of zero is treated as a burst of one message.
+    #[inline]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.tolerance = self.interval * burst.saturating_sub(1) as u64;
+        self
+    }
+
+    /// Returns a shared reference to the wrapped logger.
+    #[inline]
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    /// Returns the number of messages that were dropped, and haven't been reported yet.
+    #[inline]
+    pub fn dropped_count(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn burst(&self) -> u32 {
+        self.tolerance
+            .checked_div(self.interval)
+            .map_or(1, |burst| burst as u32 + 1)
+    }
+
+    /// Tries to reserve a slot for a new message. Returns `false` if the rate limit is reached.
+    fn try_acquire(&self) -> bool {
+        let now = self.start.elapsed().as_nanos() as u64;
+        let mut next_slot = self.next_slot.load(Ordering::Relaxed);
+
+        loop {
+            let slot = next_slot.max(now);
+            if slot - now > self.tolerance {
+                return false;
+            }
+
+            match self.next_slot.compare_exchange_weak(
+                next_slot,
+                slot + self.interval,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(current) => next_slot = current,
+            }
+        }
+    }
+}
+
+impl<L: HostLogImpl> HostLogImpl for RateLimitedLog<L> {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if !self.try_acquire() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            let mut buffer = StackBuffer::new();
+            let _ = write!(
+                buffer,
+                "{dropped} log messages were dropped, as they exceeded the rate limit."
+            );
+
+            self.inner.log(LogSeverity::Warning, buffer.as_str());
+        }
+
+        self.inner.log(severity, message);
+    }
+}
+
+/// A small, fixed-size string buffer, to format messages without allocating.
+struct StackBuffer {
+    bytes: [u8; 96],
+    len: usize,
+}
+
+impl StackBuffer {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            bytes: [0; 96],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        // Only whole strs are ever written to the buffer.
+        std::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for StackBuffer {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let destination = self
+            .bytes
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(std::fmt::Error)?;
+
+        destination.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingLogger(Mutex<Vec<(LogSeverity, String)>>);
+
+    impl HostLogImpl for RecordingLogger {
+        fn log(&self, severity: LogSeverity, message: &str) {
+            self.0.lock().unwrap().push((severity, message.to_owned()));
+        }
+    }
+
+    #[test]
+    fn limits_and_reports_dropped_messages() {
+        let logger = RateLimitedLog::new(RecordingLogger::default())
+            .with_rate(100)
+            .with_burst(3);
+
+        for _ in 0..10 {
+            logger.log(LogSeverity::Info, "Hello");
+        }
+
+        assert_eq!(logger.inner().0.lock().unwrap().len(), 3);
+        assert_eq!(logger.dropped_count(), 7);
+
+        // Wait for the bucket to refill.
+        std::thread::sleep(Duration::from_millis(50));
+        logger.log(LogSeverity::Error, "World");
+        assert_eq!(logger.dropped_count(), 0);
+
+        let messages = logger.inner().0.lock().unwrap();
+        assert_eq!(messages.len(), 5);
+        assert_eq!(
+            messages[3],
+            (
+                LogSeverity::Warning,
+                "7 log messages were dropped, as they exceeded the rate limit.".into()
+            )
+        );
+        assert_eq!(messages[4], (LogSeverity::Error, "World".into()));
+    }
+}
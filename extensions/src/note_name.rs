@@ -10,7 +10,6 @@ use std::ffi::CStr;
 
 /// The Plugin-side of the Note Name extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginNoteName(RawExtension<PluginExtensionSide, clap_plugin_note_name>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -24,9 +23,19 @@ unsafe impl Extension for PluginNoteName {
     }
 }
 
+impl PluginNoteName {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_note_name> {
+        self.0
+    }
+}
+
 /// The Host-side of the Note Name extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostNoteName(RawExtension<HostExtensionSide, clap_host_note_name>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -40,6 +49,17 @@ unsafe impl Extension for HostNoteName {
     }
 }
 
+impl HostNoteName {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_note_name> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 /// A Note's name.
 pub struct NoteName<'a> {
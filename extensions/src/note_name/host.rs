@@ -28,6 +28,8 @@ impl NoteNameBuffer {
 impl PluginNoteName {
     /// Returns the number of available [`NoteName`]s.
     pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> usize {
+        crate::utils::trace_call!(plugin, "clap_plugin_note_name.count");
+
         match plugin.use_extension(&self.0).count {
             None => 0,
             // SAFETY: This type ensures the function pointer is valid.
@@ -45,6 +47,8 @@ impl PluginNoteName {
         index: usize,
         buffer: &'b mut NoteNameBuffer,
     ) -> Option<NoteName<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_note_name.get");
+
         let success =
             // SAFETY: This type ensures the function pointer is valid.
             unsafe { plugin.use_extension(&self.0).get?(plugin.as_raw(), index as u32, buffer.inner.as_mut_ptr()) };
@@ -5,22 +5,36 @@ use clap_sys::ext::note_ports::*;
 use std::ffi::CStr;
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginNotePorts(RawExtension<PluginExtensionSide, clap_plugin_note_ports>);
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostNotePorts(RawExtension<HostExtensionSide, clap_host_note_ports>);
 
 bitflags! {
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct NotePortRescanFlags: u32 {
+        /// The list of ports, or any of their properties (except names) changed: the host must
+        /// scan all the ports again. The plugin must be deactivated.
         const ALL = CLAP_NOTE_PORTS_RESCAN_ALL;
+        /// The names of the ports changed. This can be requested at any time, and the host only
+        /// needs to read the port names again.
         const NAMES = CLAP_NOTE_PORTS_RESCAN_NAMES;
     }
 }
 
+impl NotePortRescanFlags {
+    /// Returns `true` if any of the set flag values requires the plugin to be deactivated
+    /// before re-scanning.
+    /// Otherwise, this returns false.
+    ///
+    /// As of now, this is true if the [`ALL`](Self::ALL) flag is set.
+    #[inline]
+    pub const fn requires_deactivate(&self) -> bool {
+        self.contains(NotePortRescanFlags::ALL)
+    }
+}
+
 bitflags! {
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -78,6 +92,17 @@ unsafe impl Extension for PluginNotePorts {
     }
 }
 
+impl PluginNotePorts {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_note_ports> {
+        self.0
+    }
+}
+
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
 unsafe impl Extension for HostNotePorts {
     const IDENTIFIER: &'static CStr = CLAP_EXT_NOTE_PORTS;
@@ -89,6 +114,17 @@ unsafe impl Extension for HostNotePorts {
     }
 }
 
+impl HostNotePorts {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_note_ports> {
+        self.0
+    }
+}
+
 pub struct NotePortInfo<'a> {
     pub id: ClapId,
     pub name: &'a [u8],
@@ -105,6 +141,31 @@ impl<'a> NotePortInfo<'a> {
             preferred_dialect: NoteDialect::from_raw(raw.preferred_dialect),
         })
     }
+
+    /// Picks the dialect a host supporting the given dialects should use to communicate with
+    /// this port.
+    ///
+    /// The port's preferred dialect is used if the host supports it. Otherwise, the first
+    /// dialect supported by both sides is picked, in the following order: CLAP, MIDI 2.0,
+    /// MIDI MPE, and MIDI.
+    ///
+    /// This returns `None` if the host and the port have no dialect in common.
+    pub fn negotiate_dialect(&self, host_dialects: NoteDialects) -> Option<NoteDialect> {
+        let common = self.supported_dialects & host_dialects;
+
+        if let Some(preferred) = self.preferred_dialect.filter(|d| common.supports(*d)) {
+            return Some(preferred);
+        }
+
+        [
+            NoteDialect::Clap,
+            NoteDialect::Midi2,
+            NoteDialect::MidiMpe,
+            NoteDialect::Midi,
+        ]
+        .into_iter()
+        .find(|d| common.supports(*d))
+    }
 }
 
 #[cfg(feature = "clack-host")]
@@ -116,3 +177,40 @@ pub use host::*;
 mod plugin;
 #[cfg(feature = "clack-plugin")]
 pub use plugin::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn negotiates_dialects() {
+        let port = NotePortInfo {
+            id: ClapId::new(0),
+            name: b"Notes",
+            supported_dialects: NoteDialects::MIDI | NoteDialects::MIDI2 | NoteDialects::CLAP,
+            preferred_dialect: Some(NoteDialect::Midi),
+        };
+
+        assert_eq!(
+            port.negotiate_dialect(NoteDialects::all()),
+            Some(NoteDialect::Midi)
+        );
+        assert_eq!(
+            port.negotiate_dialect(NoteDialects::CLAP | NoteDialects::MIDI2),
+            Some(NoteDialect::Clap)
+        );
+        assert_eq!(
+            port.negotiate_dialect(NoteDialects::MIDI2 | NoteDialects::MIDI_MPE),
+            Some(NoteDialect::Midi2)
+        );
+        assert_eq!(port.negotiate_dialect(NoteDialects::MIDI_MPE), None);
+    }
+
+    #[test]
+    fn rescan_flags_require_deactivation() {
+        assert!(!NotePortRescanFlags::empty().requires_deactivate());
+        assert!(!NotePortRescanFlags::NAMES.requires_deactivate());
+        assert!(NotePortRescanFlags::ALL.requires_deactivate());
+        assert!(NotePortRescanFlags::all().requires_deactivate());
+    }
+}
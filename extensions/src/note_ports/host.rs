@@ -1,5 +1,8 @@
 use super::*;
 use clack_host::extensions::prelude::*;
+use clack_host::plugin::PluginInstance;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::mem::MaybeUninit;
 
 #[derive(Clone)]
@@ -25,6 +28,8 @@ impl NotePortInfoBuffer {
 
 impl PluginNotePorts {
     pub fn count(&self, plugin: &mut PluginMainThreadHandle, is_input: bool) -> u32 {
+        crate::utils::trace_call!(plugin, "clap_plugin_note_ports.count");
+
         match plugin.use_extension(&self.0).count {
             None => 0,
             // SAFETY: This type ensures the function pointer is valid.
@@ -39,6 +44,8 @@ impl PluginNotePorts {
         is_input: bool,
         buffer: &'b mut NotePortInfoBuffer,
     ) -> Option<NotePortInfo<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_note_ports.get");
+
         let success =
             // SAFETY: This type ensures the function pointer is valid.
             unsafe { plugin.use_extension(&self.0).get?(plugin.as_raw(), index, is_input, buffer.inner.as_mut_ptr()) };
@@ -52,8 +59,18 @@ impl PluginNotePorts {
     }
 }
 
+/// Implementation of the Host-side of the Note Ports extension.
 pub trait HostNotePortsImpl {
+    /// Returns all the note dialects the host supports.
     fn supported_dialects(&self) -> NoteDialects;
+
+    /// Informs the host that the plugin's note ports changed, and must be scanned again.
+    ///
+    /// Plugins may only request a rescan that
+    /// [requires deactivation](NotePortRescanFlags::requires_deactivate) while they are
+    /// deactivated. Since this is called from within the plugin, hosts cannot deactivate it from
+    /// here: they should instead store the flags, and handle them later from their main loop,
+    /// e.g. using [`NotePortsScan::handle_rescan`].
     fn rescan(&mut self, flags: NotePortRescanFlags);
 }
 
@@ -63,7 +80,7 @@ where
     for<'h> <H as HostHandlers>::MainThread<'h>: HostNotePortsImpl,
 {
     const IMPLEMENTATION: RawExtensionImplementation =
-        RawExtensionImplementation::new(&&clap_host_note_ports {
+        RawExtensionImplementation::new(&clap_host_note_ports {
             supported_dialects: Some(supported_dialects::<H>),
             rescan: Some(rescan::<H>),
         });
@@ -93,3 +110,240 @@ where
         Ok(())
     });
 }
+
+/// The action a host must take to apply a note ports rescan request.
+///
+/// See [`NotePortsRescanAction::from_flags`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NotePortsRescanAction {
+    /// Nothing needs to be done.
+    None,
+    /// Only the names of the ports changed. The host only needs to read them again, and can
+    /// keep processing in the meantime.
+    UpdateNames,
+    /// The port layout changed. The plugin must be deactivated, and its ports scanned again
+    /// (including their dialects), before the plugin is reactivated.
+    Reactivate,
+}
+
+impl NotePortsRescanAction {
+    /// Returns the action required by the given set of rescan flags.
+    ///
+    /// If multiple flags are set, the most disruptive action is returned.
+    #[inline]
+    pub const fn from_flags(flags: NotePortRescanFlags) -> Self {
+        if flags.requires_deactivate() {
+            Self::Reactivate
+        } else if flags.contains(NotePortRescanFlags::NAMES) {
+            Self::UpdateNames
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Errors that can occur while handling a note ports rescan.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NotePortsRescanError {
+    /// The plugin requested a rescan that changes the port layout while it was active.
+    ///
+    /// The host must deactivate the plugin before it can handle this rescan.
+    PluginActive,
+}
+
+impl Display for NotePortsRescanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotePortsRescanError::PluginActive => f.write_str(
+                "Plugin requested a note ports rescan while active. It must be deactivated first.",
+            ),
+        }
+    }
+}
+
+impl Error for NotePortsRescanError {}
+
+/// Information about a single note port, as retrieved by a [`NotePortsScan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScannedNotePort {
+    /// The index of the port in the plugin's list of input or output note ports.
+    ///
+    /// This may differ from the position of this port in the scan, if the plugin failed to
+    /// provide information for some of the ports before it.
+    pub index: u32,
+    /// The stable ID of the port.
+    pub id: ClapId,
+    /// The displayable name of the port.
+    pub name: Vec<u8>,
+    /// All the dialects supported by the port.
+    pub supported_dialects: NoteDialects,
+    /// The dialect the port prefers, if any.
+    pub preferred_dialect: Option<NoteDialect>,
+    /// The dialect the host should use with this port, or `None` if there is none the host
+    /// and the port have in common.
+    pub dialect: Option<NoteDialect>,
+}
+
+impl ScannedNotePort {
+    fn from_info(index: u32, info: &NotePortInfo, host_dialects: NoteDialects) -> Self {
+        Self {
+            index,
+            id: info.id,
+            name: info.name.to_vec(),
+            supported_dialects: info.supported_dialects,
+            preferred_dialect: info.preferred_dialect,
+            dialect: info.negotiate_dialect(host_dialects),
+        }
+    }
+}
+
+/// The result of scanning all the note ports of a plugin, with the dialect a host should use for
+/// each one.
+///
+/// A scan is created with [`PluginNotePorts::scan`], and should be kept up to date by calling
+/// [`rescan`](Self::rescan) every time the plugin requests it through
+/// [`HostNotePortsImpl::rescan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotePortsScan {
+    host_dialects: NoteDialects,
+    inputs: Vec<ScannedNotePort>,
+    outputs: Vec<ScannedNotePort>,
+}
+
+impl NotePortsScan {
+    /// Returns the dialects supported by the host, as given to [`PluginNotePorts::scan`].
+    #[inline]
+    pub fn host_dialects(&self) -> NoteDialects {
+        self.host_dialects
+    }
+
+    /// Returns all the scanned input ports, in order.
+    #[inline]
+    pub fn inputs(&self) -> &[ScannedNotePort] {
+        &self.inputs
+    }
+
+    /// Returns all the scanned output ports, in order.
+    #[inline]
+    pub fn outputs(&self) -> &[ScannedNotePort] {
+        &self.outputs
+    }
+
+    /// Returns the scanned port at the given index in the plugin's port list, if any.
+    ///
+    /// This returns `None` if the plugin has no such port, or if it failed to provide information
+    /// for it during the scan.
+    #[inline]
+    pub fn port(&self, index: usize, is_input: bool) -> Option<&ScannedNotePort> {
+        let ports = if is_input {
+            &self.inputs
+        } else {
+            &self.outputs
+        };
+
+        ports.iter().find(|port| port.index as usize == index)
+    }
+
+    /// Returns the dialect the host should use with the port at the given index in the plugin's
+    /// port list.
+    ///
+    /// This returns `None` if there is no such scanned port, or if the host and the port have no
+    /// dialect in common.
+    #[inline]
+    pub fn dialect(&self, index: usize, is_input: bool) -> Option<NoteDialect> {
+        self.port(index, is_input)?.dialect
+    }
+
+    /// Updates this scan following a rescan request from the plugin.
+    ///
+    /// If the [`ALL`](NotePortRescanFlags::ALL) flag is set, all the ports are scanned again.
+    /// Otherwise, if only the [`NAMES`](NotePortRescanFlags::NAMES) flag is set, only the names of
+    /// the existing ports are updated.
+    ///
+    /// Note that the plugin is only allowed to request a full rescan while it is deactivated. See
+    /// [`handle_rescan`](Self::handle_rescan) for a version of this method that checks it.
+    pub fn rescan(
+        &mut self,
+        note_ports: &PluginNotePorts,
+        plugin: &mut PluginMainThreadHandle,
+        flags: NotePortRescanFlags,
+    ) {
+        if flags.contains(NotePortRescanFlags::ALL) {
+            *self = note_ports.scan(plugin, self.host_dialects);
+            return;
+        }
+
+        if flags.contains(NotePortRescanFlags::NAMES) {
+            let mut buffer = NotePortInfoBuffer::new();
+
+            for (is_input, ports) in [(true, &mut self.inputs), (false, &mut self.outputs)] {
+                for port in ports.iter_mut() {
+                    if let Some(info) = note_ports.get(plugin, port.index, is_input, &mut buffer) {
+                        port.name = info.name.to_vec();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies a rescan request from the plugin to this scan.
+    ///
+    /// The returned [`NotePortsRescanAction`] tells what the host has to do on its side:
+    ///
+    /// * [`Reactivate`](NotePortsRescanAction::Reactivate): all the ports are scanned again. The
+    ///   host should update the note routing and dialects it uses from the new scan, and can then
+    ///   reactivate the plugin.
+    /// * [`UpdateNames`](NotePortsRescanAction::UpdateNames): only the port names are updated,
+    ///   e.g. for the host to refresh its UI.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`NotePortsRescanError::PluginActive`] if the rescan requires the plugin to
+    /// be deactivated, but it is still active. The scan is left untouched in that case.
+    pub fn handle_rescan<H: HostHandlers>(
+        &mut self,
+        note_ports: &PluginNotePorts,
+        instance: &mut PluginInstance<H>,
+        flags: NotePortRescanFlags,
+    ) -> Result<NotePortsRescanAction, NotePortsRescanError> {
+        let action = NotePortsRescanAction::from_flags(flags);
+
+        if action == NotePortsRescanAction::Reactivate && instance.is_active() {
+            return Err(NotePortsRescanError::PluginActive);
+        }
+
+        self.rescan(note_ports, &mut instance.plugin_handle(), flags);
+        Ok(action)
+    }
+}
+
+impl PluginNotePorts {
+    /// Scans all the note ports of the plugin, and picks the dialect the host should use for each
+    /// one, given the dialects the host supports.
+    ///
+    /// Ports the plugin fails to provide information for are skipped. The other ports keep
+    /// their index in the plugin's port list, see [`ScannedNotePort::index`].
+    ///
+    /// See [`NotePortInfo::negotiate_dialect`] for how dialects are picked.
+    pub fn scan(
+        &self,
+        plugin: &mut PluginMainThreadHandle,
+        host_dialects: NoteDialects,
+    ) -> NotePortsScan {
+        let mut buffer = NotePortInfoBuffer::new();
+        let mut scan_ports = |is_input| {
+            (0..self.count(plugin, is_input))
+                .filter_map(|index| {
+                    let info = self.get(plugin, index, is_input, &mut buffer)?;
+                    Some(ScannedNotePort::from_info(index, &info, host_dialects))
+                })
+                .collect()
+        };
+
+        NotePortsScan {
+            host_dialects,
+            inputs: scan_ports(true),
+            outputs: scan_ports(false),
+        }
+    }
+}
@@ -8,9 +8,28 @@ bitflags! {
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct ParamRescanFlags: u32 {
+        /// The values of the parameters changed, e.g. after loading a preset.
+        ///
+        /// The host must read all the parameter values again. These changes must not be recorded
+        /// as automation points. This can be requested at any time.
         const VALUES = CLAP_PARAM_RESCAN_VALUES;
+        /// The information of the parameters changed, but only their name, their module, and
+        /// their [`IS_PERIODIC`](ParamInfoFlags::IS_PERIODIC) and
+        /// [`IS_HIDDEN`](ParamInfoFlags::IS_HIDDEN) flags.
+        ///
+        /// The host must read the information of all parameters again. This can be requested at
+        /// any time.
         const INFO = CLAP_PARAM_RESCAN_INFO;
+        /// The value-to-text conversion of the parameters changed.
+        ///
+        /// The host must render the text of all displayed values again. This can be requested at
+        /// any time.
         const TEXT = CLAP_PARAM_RESCAN_TEXT;
+        /// The whole list of parameters changed.
+        ///
+        /// The host must scan all the parameters again, and delete the automation and modulation
+        /// of any parameters that no longer exist. This can only be requested while the plugin is
+        /// deactivated.
         const ALL = CLAP_PARAM_RESCAN_ALL;
     }
 }
@@ -22,6 +41,33 @@ impl ParamRescanFlags {
     pub fn requires_restart(&self) -> bool {
         self.contains(Self::ALL)
     }
+
+    /// Returns `true` if the host must read the values of the parameters again.
+    ///
+    /// This is the case if either the [`VALUES`](Self::VALUES) or the [`ALL`](Self::ALL) flags
+    /// are set.
+    #[inline]
+    pub fn requires_values_rescan(&self) -> bool {
+        self.intersects(Self::VALUES | Self::ALL)
+    }
+
+    /// Returns `true` if the host must read the information of the parameters again.
+    ///
+    /// This is the case if either the [`INFO`](Self::INFO) or the [`ALL`](Self::ALL) flags are
+    /// set.
+    #[inline]
+    pub fn requires_info_rescan(&self) -> bool {
+        self.intersects(Self::INFO | Self::ALL)
+    }
+
+    /// Returns `true` if the host must render the text of displayed values again.
+    ///
+    /// This is the case if any flag is set except for [`INFO`](Self::INFO), as any value change
+    /// also changes the displayed text.
+    #[inline]
+    pub fn requires_text_refresh(&self) -> bool {
+        self.intersects(Self::TEXT | Self::VALUES | Self::ALL)
+    }
 }
 
 bitflags! {
@@ -38,22 +84,46 @@ bitflags! {
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct ParamInfoFlags: u32 {
+        /// The parameter can be automated by the host.
         const IS_AUTOMATABLE = CLAP_PARAM_IS_AUTOMATABLE;
+        /// The parameter's automation can target a specific MIDI channel.
         const IS_AUTOMATABLE_PER_CHANNEL = CLAP_PARAM_IS_AUTOMATABLE_PER_CHANNEL;
+        /// The parameter's automation can target a specific key.
         const IS_AUTOMATABLE_PER_KEY = CLAP_PARAM_IS_AUTOMATABLE_PER_KEY;
+        /// The parameter's automation can target a specific note ID.
         const IS_AUTOMATABLE_PER_NOTE_ID = CLAP_PARAM_IS_AUTOMATABLE_PER_NOTE_ID;
+        /// The parameter's automation can target a specific note port.
         const IS_AUTOMATABLE_PER_PORT = CLAP_PARAM_IS_AUTOMATABLE_PER_PORT;
+        /// The parameter is the plugin's bypass parameter.
         const IS_BYPASS = CLAP_PARAM_IS_BYPASS;
+        /// The parameter must not be shown to the user.
         const IS_HIDDEN = CLAP_PARAM_IS_HIDDEN;
+        /// The parameter can be modulated by the host.
         const IS_MODULATABLE = CLAP_PARAM_IS_MODULATABLE;
+        /// The parameter's modulation can target a specific MIDI channel.
         const IS_MODULATABLE_PER_CHANNEL = CLAP_PARAM_IS_MODULATABLE_PER_CHANNEL;
+        /// The parameter's modulation can target a specific key.
         const IS_MODULATABLE_PER_KEY = CLAP_PARAM_IS_MODULATABLE_PER_KEY;
+        /// The parameter's modulation can target a specific note ID.
         const IS_MODULATABLE_PER_NOTE_ID = CLAP_PARAM_IS_MODULATABLE_PER_NOTE_ID;
+        /// The parameter's modulation can target a specific note port.
         const IS_MODULATABLE_PER_PORT = CLAP_PARAM_IS_MODULATABLE_PER_PORT;
+        /// The parameter's values wrap around, e.g. for a phase or an angle.
         const IS_PERIODIC = CLAP_PARAM_IS_PERIODIC;
+        /// The parameter can only be changed by the plugin, e.g. for a meter.
         const IS_READONLY = CLAP_PARAM_IS_READONLY;
+        /// The parameter only takes integer values.
         const IS_STEPPED = CLAP_PARAM_IS_STEPPED;
+        /// Changes to the parameter's value must be processed by the plugin's audio processor,
+        /// even if they don't affect the audio.
         const REQUIRES_PROCESS = CLAP_PARAM_REQUIRES_PROCESS;
+        /// The parameter's values are a list of named options, such as a filter type.
+        ///
+        /// This is only valid in combination with [`IS_STEPPED`](Self::IS_STEPPED). Hosts can use
+        /// it to display the parameter as a drop-down menu rather than a knob.
+        ///
+        /// This flag was introduced in CLAP 1.2.0.
+        const IS_ENUM = 1 << 16;
     }
 }
 
@@ -73,12 +143,104 @@ impl ParamInfoFlags {
             | Self::IS_MODULATABLE_PER_PORT.bits()
             | Self::IS_READONLY.bits()
             | Self::IS_BYPASS.bits()
-            | Self::IS_STEPPED.bits(),
+            | Self::IS_STEPPED.bits()
+            | Self::IS_ENUM.bits(),
+    );
+
+    /// The flags allowing automation to target individual notes, channels, keys or ports.
+    pub const PER_NOTE_AUTOMATION_FLAGS: Self = Self::from_bits_truncate(
+        Self::IS_AUTOMATABLE_PER_NOTE_ID.bits()
+            | Self::IS_AUTOMATABLE_PER_KEY.bits()
+            | Self::IS_AUTOMATABLE_PER_CHANNEL.bits()
+            | Self::IS_AUTOMATABLE_PER_PORT.bits(),
     );
+
+    /// The flags allowing modulation to target individual notes, channels, keys or ports.
+    pub const PER_NOTE_MODULATION_FLAGS: Self = Self::from_bits_truncate(
+        Self::IS_MODULATABLE_PER_NOTE_ID.bits()
+            | Self::IS_MODULATABLE_PER_KEY.bits()
+            | Self::IS_MODULATABLE_PER_CHANNEL.bits()
+            | Self::IS_MODULATABLE_PER_PORT.bits(),
+    );
+
+    /// Returns `true` if the parameter only takes integer values.
+    #[inline]
+    pub fn is_stepped(&self) -> bool {
+        self.contains(Self::IS_STEPPED)
+    }
+
+    /// Returns `true` if the parameter's values are a list of named options.
+    ///
+    /// This requires both the [`IS_ENUM`](Self::IS_ENUM) and [`IS_STEPPED`](Self::IS_STEPPED)
+    /// flags to be set, as the former is meaningless without the latter.
+    #[inline]
+    pub fn is_enum(&self) -> bool {
+        self.contains(Self::IS_ENUM | Self::IS_STEPPED)
+    }
+
+    /// Returns `true` if the parameter's values wrap around.
+    #[inline]
+    pub fn is_periodic(&self) -> bool {
+        self.contains(Self::IS_PERIODIC)
+    }
+
+    /// Returns `true` if the parameter must not be shown to the user.
+    #[inline]
+    pub fn is_hidden(&self) -> bool {
+        self.contains(Self::IS_HIDDEN)
+    }
+
+    /// Returns `true` if the parameter can only be changed by the plugin.
+    #[inline]
+    pub fn is_readonly(&self) -> bool {
+        self.contains(Self::IS_READONLY)
+    }
+
+    /// Returns `true` if the parameter is the plugin's bypass parameter.
+    #[inline]
+    pub fn is_bypass(&self) -> bool {
+        self.contains(Self::IS_BYPASS)
+    }
+
+    /// Returns `true` if the parameter can be automated by the host.
+    #[inline]
+    pub fn is_automatable(&self) -> bool {
+        self.contains(Self::IS_AUTOMATABLE)
+    }
+
+    /// Returns `true` if the parameter's automation can target individual notes, channels, keys
+    /// or ports.
+    ///
+    /// Use [`PER_NOTE_AUTOMATION_FLAGS`](Self::PER_NOTE_AUTOMATION_FLAGS) to find out which ones.
+    #[inline]
+    pub fn is_per_note_automatable(&self) -> bool {
+        self.is_automatable() && self.intersects(Self::PER_NOTE_AUTOMATION_FLAGS)
+    }
+
+    /// Returns `true` if the parameter can be modulated by the host.
+    #[inline]
+    pub fn is_modulatable(&self) -> bool {
+        self.contains(Self::IS_MODULATABLE)
+    }
+
+    /// Returns `true` if the parameter's modulation can target individual notes, channels, keys
+    /// or ports.
+    ///
+    /// Use [`PER_NOTE_MODULATION_FLAGS`](Self::PER_NOTE_MODULATION_FLAGS) to find out which ones.
+    #[inline]
+    pub fn is_per_note_modulatable(&self) -> bool {
+        self.is_modulatable() && self.intersects(Self::PER_NOTE_MODULATION_FLAGS)
+    }
+
+    /// Returns `true` if changes to the parameter's value must be sent to the plugin's audio
+    /// processor, even if they don't affect the audio.
+    #[inline]
+    pub fn requires_process(&self) -> bool {
+        self.contains(Self::REQUIRES_PROCESS)
+    }
 }
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginParams(RawExtension<PluginExtensionSide, clap_plugin_params>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -92,8 +254,18 @@ unsafe impl Extension for PluginParams {
     }
 }
 
+impl PluginParams {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_params> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostParams(RawExtension<HostExtensionSide, clap_host_params>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -106,6 +278,22 @@ unsafe impl Extension for HostParams {
         Self(raw.cast())
     }
 }
+
+impl HostParams {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_params> {
+        self.0
+    }
+}
+
+/// The information of a parameter, as borrowed from the plugin.
+///
+/// Use [`into_owned`](Self::into_owned) to keep a copy of this information around.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ParamInfo<'a> {
     pub id: ClapId,
     pub flags: ParamInfoFlags,
@@ -131,6 +319,12 @@ impl<'a> ParamInfo<'a> {
         })
     }
 
+    /// Returns an owned copy of this information.
+    #[inline]
+    pub fn into_owned(self) -> OwnedParamInfo {
+        OwnedParamInfo::from(&self)
+    }
+
     pub fn diff_for_rescan(&self, other: &ParamInfo) -> ParamRescanFlags {
         #[inline]
         fn flags_differ(
@@ -170,12 +364,137 @@ impl<'a> ParamInfo<'a> {
     }
 }
 
+/// An owned copy of a parameter's [`ParamInfo`].
+///
+/// The [`cookie`](Self::cookie) is kept as an opaque value, which is never dereferenced on the
+/// host side. It is only valid until the plugin's parameter list changes: after a
+/// [`ParamRescanFlags::ALL`] rescan, this information must be discarded and read again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedParamInfo {
+    /// The stable ID of the parameter.
+    pub id: ClapId,
+    /// The flags of the parameter.
+    pub flags: ParamInfoFlags,
+    /// The cookie the plugin associated to this parameter.
+    pub cookie: Cookie,
+    /// The displayable name of the parameter.
+    pub name: Vec<u8>,
+    /// The module the parameter belongs to, as a `/`-separated path.
+    pub module: Vec<u8>,
+    /// The minimum plain value of the parameter.
+    pub min_value: f64,
+    /// The maximum plain value of the parameter.
+    pub max_value: f64,
+    /// The default plain value of the parameter.
+    pub default_value: f64,
+}
+
+impl OwnedParamInfo {
+    /// Borrows this information as a [`ParamInfo`].
+    #[inline]
+    pub fn as_info(&self) -> ParamInfo<'_> {
+        ParamInfo {
+            id: self.id,
+            flags: self.flags,
+            cookie: self.cookie,
+            name: &self.name,
+            module: &self.module,
+            min_value: self.min_value,
+            max_value: self.max_value,
+            default_value: self.default_value,
+        }
+    }
+}
+
+impl From<&ParamInfo<'_>> for OwnedParamInfo {
+    fn from(info: &ParamInfo<'_>) -> Self {
+        Self {
+            id: info.id,
+            flags: info.flags,
+            cookie: info.cookie,
+            name: info.name.to_vec(),
+            module: info.module.to_vec(),
+            min_value: info.min_value,
+            max_value: info.max_value,
+            default_value: info.default_value,
+        }
+    }
+}
+
+mod lookup;
+mod model;
+mod modulation;
+mod output;
+pub use lookup::*;
+pub use model::*;
+pub use modulation::*;
+pub use output::*;
+
+#[cfg(feature = "clack-host")]
+mod cache;
 #[cfg(feature = "clack-host")]
 mod host;
 #[cfg(feature = "clack-host")]
+mod morph;
+#[cfg(feature = "clack-host")]
+pub use cache::*;
+#[cfg(feature = "clack-host")]
 pub use host::*;
+#[cfg(feature = "clack-host")]
+pub use morph::*;
 
+#[cfg(feature = "clack-plugin")]
+mod editor;
 #[cfg(feature = "clack-plugin")]
 mod plugin;
 #[cfg(feature = "clack-plugin")]
+mod validation;
+#[cfg(feature = "clack-plugin")]
+pub use editor::*;
+#[cfg(feature = "clack-plugin")]
 pub use plugin::*;
+#[cfg(feature = "clack-plugin")]
+pub use validation::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flag_helpers() {
+        let flags = ParamInfoFlags::IS_AUTOMATABLE | ParamInfoFlags::IS_AUTOMATABLE_PER_KEY;
+        assert!(flags.is_automatable());
+        assert!(flags.is_per_note_automatable());
+        assert!(!flags.is_modulatable());
+        assert!(!flags.is_per_note_modulatable());
+
+        // Per-note flags are meaningless without the base flag.
+        assert!(!ParamInfoFlags::IS_MODULATABLE_PER_NOTE_ID.is_per_note_modulatable());
+
+        assert!(!ParamInfoFlags::IS_ENUM.is_enum());
+        assert!((ParamInfoFlags::IS_ENUM | ParamInfoFlags::IS_STEPPED).is_enum());
+        assert_eq!(
+            ParamInfoFlags::from_bits_truncate(1 << 16),
+            ParamInfoFlags::IS_ENUM
+        );
+    }
+
+    #[test]
+    fn owned_info_round_trips() {
+        let info = ParamInfo {
+            id: ClapId::new(4),
+            flags: ParamInfoFlags::IS_STEPPED | ParamInfoFlags::REQUIRES_PROCESS,
+            cookie: Cookie::empty(),
+            name: b"Mode",
+            module: b"Filter",
+            min_value: 0.0,
+            max_value: 3.0,
+            default_value: 1.0,
+        };
+
+        let owned = info.into_owned();
+        assert_eq!(owned.name, b"Mode");
+        assert!(owned.flags.requires_process());
+        assert_eq!(owned.clone().as_info(), info);
+    }
+}
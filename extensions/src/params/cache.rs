@@ -0,0 +1,321 @@
+use super::*;
+use clack_common::events::event_types::{ParamModEvent, ParamValueEvent};
+use clack_common::events::Pckn;
+use clack_host::host::HostHandlers;
+use clack_host::plugin::{PluginInstance, PluginMainThreadHandle};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// A parameter's information and value, as stored in a [`ParamCache`].
+///
+/// When the `serde` feature is enabled, this type can be serialized and deserialized. The
+/// [`cookie`](Self::cookie) is only valid for the plugin instance that provided it, and is
+/// therefore never serialized: it is always deserialized as an empty cookie.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedParam {
+    /// The stable ID of the parameter.
+    pub id: ClapId,
+    /// The flags of the parameter.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::flags")
+    )]
+    pub flags: ParamInfoFlags,
+    /// The cookie the plugin associated to this parameter.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cookie: Cookie,
+    /// The displayable name of the parameter.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::bytes")
+    )]
+    pub name: Vec<u8>,
+    /// The module the parameter belongs to, as a `/`-separated path.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::bytes")
+    )]
+    pub module: Vec<u8>,
+    /// The minimum plain value of the parameter.
+    pub min_value: f64,
+    /// The maximum plain value of the parameter.
+    pub max_value: f64,
+    /// The default plain value of the parameter.
+    pub default_value: f64,
+    /// The last known value of the parameter, or `None` if the plugin failed to provide it.
+    pub value: Option<f64>,
+}
+
+impl CachedParam {
+    fn from_info(info: &ParamInfo, value: Option<f64>) -> Self {
+        Self {
+            id: info.id,
+            flags: info.flags,
+            cookie: info.cookie,
+            name: info.name.to_vec(),
+            module: info.module.to_vec(),
+            min_value: info.min_value,
+            max_value: info.max_value,
+            default_value: info.default_value,
+            value,
+        }
+    }
+
+    /// Creates a [`ParamValueEvent`] setting this parameter to the given value.
+    ///
+    /// The event carries this parameter's cookie, allowing the plugin to look it up quickly.
+    #[inline]
+    pub fn value_event(&self, time: u32, pckn: Pckn, value: f64) -> ParamValueEvent {
+        ParamValueEvent::new(time, self.id, pckn, value, self.cookie)
+    }
+
+    /// Creates a [`ParamModEvent`] modulating this parameter by the given amount.
+    ///
+    /// The event carries this parameter's cookie, allowing the plugin to look it up quickly.
+    #[inline]
+    pub fn mod_event(&self, time: u32, pckn: Pckn, amount: f64) -> ParamModEvent {
+        ParamModEvent::new(time, self.id, pckn, amount, self.cookie)
+    }
+
+    fn update_info(&mut self, info: &ParamInfo) {
+        const INFO_FLAGS: ParamInfoFlags = ParamInfoFlags::FLAGS_REQUIRING_INFO_RESCAN;
+
+        self.name = info.name.to_vec();
+        self.module = info.module.to_vec();
+        self.flags = (self.flags - INFO_FLAGS) | (info.flags & INFO_FLAGS);
+    }
+}
+
+/// A host-side cache of all the parameters of a plugin instance, their information and values.
+///
+/// The cache is filled using [`PluginParams::scan`], and should be kept up-to-date by calling
+/// [`PluginParams::handle_rescan`] every time the plugin requests it through
+/// [`HostParamsImplMainThread::rescan`], which performs the steps required by the CLAP
+/// specification for each rescan flag.
+#[derive(Clone, Debug, Default)]
+pub struct ParamCache {
+    params: Vec<CachedParam>,
+    indexes: HashMap<ClapId, usize>,
+}
+
+impl ParamCache {
+    /// Creates a new, empty parameter cache.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached parameter with the given ID, if any.
+    #[inline]
+    pub fn get(&self, param_id: ClapId) -> Option<&CachedParam> {
+        self.params.get(*self.indexes.get(&param_id)?)
+    }
+
+    /// Returns the number of cached parameters.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns `true` if no parameters are cached.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Returns an iterator over all the cached parameters, in the order the plugin declared them.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &CachedParam> {
+        self.params.iter()
+    }
+
+    /// Returns the plugin's [bypass](ParamInfoFlags::IS_BYPASS) parameter, if it declared one.
+    ///
+    /// Hosts can use it to let the plugin handle bypassing itself, instead of crossfading its
+    /// output (see `clack_host::process::bypass`).
+    #[inline]
+    pub fn bypass_param(&self) -> Option<&CachedParam> {
+        self.params.iter().find(|param| param.flags.is_bypass())
+    }
+
+    /// Creates a [`ParamValueEvent`] setting the parameter with the given ID to the given value,
+    /// or `None` if there is no cached parameter with that ID.
+    ///
+    /// The event carries the parameter's cookie. See [`CachedParam::value_event`].
+    #[inline]
+    pub fn value_event(
+        &self,
+        time: u32,
+        param_id: ClapId,
+        pckn: Pckn,
+        value: f64,
+    ) -> Option<ParamValueEvent> {
+        Some(self.get(param_id)?.value_event(time, pckn, value))
+    }
+
+    /// Creates a [`ParamModEvent`] modulating the parameter with the given ID by the given
+    /// amount, or `None` if there is no cached parameter with that ID.
+    ///
+    /// The event carries the parameter's cookie. See [`CachedParam::mod_event`].
+    #[inline]
+    pub fn mod_event(
+        &self,
+        time: u32,
+        param_id: ClapId,
+        pckn: Pckn,
+        amount: f64,
+    ) -> Option<ParamModEvent> {
+        Some(self.get(param_id)?.mod_event(time, pckn, amount))
+    }
+
+    /// Updates the cached value of a parameter, e.g. from a [`ParamValueEvent`] the plugin
+    /// output.
+    ///
+    /// This returns `false` if there is no cached parameter with the given ID.
+    pub fn set_value(&mut self, param_id: ClapId, value: f64) -> bool {
+        match self.indexes.get(&param_id) {
+            Some(index) => {
+                self.params[*index].value = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scan_values(&mut self, params: &PluginParams, plugin: &mut PluginMainThreadHandle) {
+        for param in &mut self.params {
+            param.value = params.get_value(plugin, param.id);
+        }
+    }
+
+    fn scan_info(&mut self, params: &PluginParams, plugin: &mut PluginMainThreadHandle) {
+        let mut buffer = ParamInfoBuffer::new();
+
+        for index in 0..params.count(plugin) {
+            let Some(info) = params.get_info(plugin, index, &mut buffer) else {
+                continue;
+            };
+
+            if let Some(index) = self.indexes.get(&info.id) {
+                self.params[*index].update_info(&info);
+            }
+        }
+    }
+}
+
+/// The changes made to a [`ParamCache`] by [`PluginParams::handle_rescan`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParamRescanChanges {
+    /// The IDs of the parameters that were added.
+    pub added: Vec<ClapId>,
+    /// The IDs of the parameters that no longer exist.
+    ///
+    /// The host must delete all automation and modulation of these parameters.
+    pub removed: Vec<ClapId>,
+}
+
+/// Errors that can occur while handling a parameter rescan.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParamRescanError {
+    /// The plugin requested a full rescan ([`ParamRescanFlags::ALL`]) while it was active.
+    ///
+    /// The host must deactivate the plugin before it can handle this rescan.
+    PluginActive,
+}
+
+impl Display for ParamRescanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamRescanError::PluginActive => f.write_str(
+                "Plugin requested a full parameter rescan while active. It must be deactivated first.",
+            ),
+        }
+    }
+}
+
+impl Error for ParamRescanError {}
+
+impl PluginParams {
+    /// Scans all the parameters of the plugin, with their current values.
+    ///
+    /// Parameters the plugin fails to provide information for are skipped.
+    pub fn scan(&self, plugin: &mut PluginMainThreadHandle) -> ParamCache {
+        let mut buffer = ParamInfoBuffer::new();
+        let mut cache = ParamCache::new();
+
+        for index in 0..self.count(plugin) {
+            let Some(info) = self.get_info(plugin, index, &mut buffer) else {
+                continue;
+            };
+
+            let param = CachedParam::from_info(&info, None);
+            cache.indexes.insert(param.id, cache.params.len());
+            cache.params.push(param);
+        }
+
+        cache.scan_values(self, plugin);
+        cache
+    }
+
+    /// Updates the given [`ParamCache`] following a rescan request from the plugin, as
+    /// mandated by the CLAP specification:
+    ///
+    /// * [`ALL`](ParamRescanFlags::ALL): all parameters are scanned again. The added and removed
+    ///   parameters are returned, as the host must then delete the automation and modulation of
+    ///   the removed ones.
+    /// * [`INFO`](ParamRescanFlags::INFO): the names, modules, and the
+    ///   [`IS_PERIODIC`](ParamInfoFlags::IS_PERIODIC) and [`IS_HIDDEN`](ParamInfoFlags::IS_HIDDEN)
+    ///   flags of all parameters are read again.
+    /// * [`VALUES`](ParamRescanFlags::VALUES): all parameter values are read again. These changes
+    ///   must not be recorded as automation.
+    ///
+    /// The [`TEXT`](ParamRescanFlags::TEXT) flag doesn't affect the cache: the host must only
+    /// render displayed values again, see [`ParamRescanFlags::requires_text_refresh`].
+    ///
+    /// # Errors
+    ///
+    /// This returns [`ParamRescanError::PluginActive`] if a full rescan was requested while the
+    /// plugin is active. The cache is left untouched in that case.
+    pub fn handle_rescan<H: HostHandlers>(
+        &self,
+        instance: &mut PluginInstance<H>,
+        flags: ParamRescanFlags,
+        cache: &mut ParamCache,
+    ) -> Result<ParamRescanChanges, ParamRescanError> {
+        if flags.requires_restart() && instance.is_active() {
+            return Err(ParamRescanError::PluginActive);
+        }
+
+        let mut plugin = instance.plugin_handle();
+        let mut changes = ParamRescanChanges::default();
+
+        if flags.contains(ParamRescanFlags::ALL) {
+            let new_cache = self.scan(&mut plugin);
+
+            changes.removed = (cache.iter())
+                .filter(|p| new_cache.get(p.id).is_none())
+                .map(|p| p.id)
+                .collect();
+
+            changes.added = (new_cache.iter())
+                .filter(|p| cache.get(p.id).is_none())
+                .map(|p| p.id)
+                .collect();
+
+            *cache = new_cache;
+            return Ok(changes);
+        }
+
+        if flags.contains(ParamRescanFlags::INFO) {
+            cache.scan_info(self, &mut plugin);
+        }
+
+        if flags.contains(ParamRescanFlags::VALUES) {
+            cache.scan_values(self, &mut plugin);
+        }
+
+        Ok(changes)
+    }
+}
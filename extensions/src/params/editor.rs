@@ -0,0 +1,217 @@
+use super::HostParams;
+use clack_common::events::event_types::{ParamGestureBeginEvent, ParamGestureEndEvent};
+use clack_common::events::io::OutputEvents;
+use clack_common::utils::{
+    param_queue, ClapId, OverflowPolicy, ParamChange, ParamQueueConsumer, ParamQueueProducer,
+};
+use clack_plugin::host::HostSharedHandle;
+use std::fmt::{Debug, Formatter};
+
+/// A single parameter edit, sent from a [`ParamEditor`] to its [`ParamEditReceiver`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParamEdit {
+    /// The user started adjusting the given parameter, e.g. by clicking on a knob.
+    GestureBegin(ClapId),
+    /// The user changed the value of a parameter.
+    Value(ParamChange),
+    /// The user stopped adjusting the given parameter, e.g. by releasing a knob.
+    GestureEnd(ClapId),
+}
+
+/// A handle that plugin GUIs can use to edit the plugin's parameters from any thread.
+///
+/// In CLAP, parameter changes made by the plugin itself (e.g. from its GUI) must be reported to
+/// the host as output events, which can only be sent during `process`, or during a parameter
+/// `flush`. Both of those are called by the host, on either the audio thread or the main thread
+/// depending on the plugin's activation state.
+///
+/// This editor handles all of that thread handoff: each edit is pushed to a lock-free queue,
+/// and the host is then asked to [`request_flush`](HostParams::request_flush), so that
+/// the edits get picked up as soon as possible. The plugin then calls
+/// [`ParamEditReceiver::receive`] at the start of both its `process` and its `flush`
+/// implementations, which applies the edits to the plugin's state, and reports them to the host.
+///
+/// Value changes should be surrounded by [`begin_gesture`](Self::begin_gesture) and
+/// [`end_gesture`](Self::end_gesture) calls while the user is adjusting a parameter, so that the
+/// host can e.g. group them into a single undo step, or record automation properly. For single
+/// edits (e.g. typing a value in a text field), [`edit`](Self::edit) does both at once.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::params::{ParamEdit, ParamEditReceiver, ParamEditor};
+/// use clack_plugin::prelude::*;
+///
+/// fn create_gui(host: HostSharedHandle) -> ParamEditor {
+///     let (editor, receiver) = ParamEditor::new(host, 256);
+///     // Give the receiver to the audio processor, and the editor to the GUI.
+///     # drop(receiver);
+///     editor
+/// }
+///
+/// // On the GUI thread, while the user drags a knob:
+/// fn on_knob_dragged(editor: &mut ParamEditor, value: f64) {
+///     let _ = editor.set_value(ClapId::new(1), value);
+/// }
+///
+/// // In both process() and flush():
+/// fn apply_edits(receiver: &mut ParamEditReceiver, output_events: &mut OutputEvents) {
+///     receiver.receive(output_events, |change| {
+///         // Update the plugin's state with change.param_id and change.value
+///     });
+/// }
+/// ```
+pub struct ParamEditor<'a> {
+    producer: ParamQueueProducer<ParamEdit>,
+    host: HostSharedHandle<'a>,
+    host_params: Option<HostParams>,
+}
+
+impl<'a> ParamEditor<'a> {
+    /// Creates a new editor, and its matching receiver.
+    ///
+    /// The queue between the two can hold at least `capacity` pending edits.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `capacity` is zero.
+    pub fn new(host: HostSharedHandle<'a>, capacity: usize) -> (Self, ParamEditReceiver) {
+        let (producer, consumer) = param_queue(capacity, OverflowPolicy::Reject);
+
+        let editor = Self {
+            producer,
+            host,
+            host_params: host.get_extension(),
+        };
+
+        (editor, ParamEditReceiver { consumer })
+    }
+
+    /// Notifies the host that the user started adjusting the given parameter.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is full, the edit is given back as an error.
+    #[inline]
+    pub fn begin_gesture(&mut self, param_id: ClapId) -> Result<(), ParamEdit> {
+        self.push(ParamEdit::GestureBegin(param_id))
+    }
+
+    /// Sets a new value for the given parameter.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is full, the edit is given back as an error.
+    #[inline]
+    pub fn set_value(&mut self, param_id: ClapId, value: f64) -> Result<(), ParamEdit> {
+        self.push(ParamEdit::Value(ParamChange::new(param_id, value)))
+    }
+
+    /// Notifies the host that the user stopped adjusting the given parameter.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is full, the edit is given back as an error.
+    #[inline]
+    pub fn end_gesture(&mut self, param_id: ClapId) -> Result<(), ParamEdit> {
+        self.push(ParamEdit::GestureEnd(param_id))
+    }
+
+    /// Sets a new value for the given parameter, as a single, complete gesture.
+    ///
+    /// # Errors
+    ///
+    /// If the queue doesn't have enough room for the whole gesture, nothing is sent, and the
+    /// value change is given back as an error.
+    pub fn edit(&mut self, param_id: ClapId, value: f64) -> Result<(), ParamEdit> {
+        let change = ParamEdit::Value(ParamChange::new(param_id, value));
+
+        if self.producer.available() < 3 {
+            return Err(change);
+        }
+
+        self.producer.push(ParamEdit::GestureBegin(param_id))?;
+        self.producer.push(change)?;
+        self.push(ParamEdit::GestureEnd(param_id))
+    }
+
+    /// Pushes a raw edit, and requests a parameter flush from the host.
+    ///
+    /// # Errors
+    ///
+    /// If the queue is full, the edit is given back as an error.
+    pub fn push(&mut self, edit: ParamEdit) -> Result<(), ParamEdit> {
+        self.producer.push(edit)?;
+
+        if let Some(host_params) = &self.host_params {
+            host_params.request_flush(&self.host);
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for ParamEditor<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParamEditor")
+            .field("available", &self.producer.available())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a [`ParamEditor`].
+///
+/// See the [`ParamEditor`] documentation for more information.
+///
+/// Because parameters can be flushed from either the audio thread or the main thread depending
+/// on the plugin's activation state, this receiver must be usable from both. A simple way to do
+/// so is to move it into the audio processor when the plugin is activated, and to move it back
+/// to the main thread when the plugin is deactivated.
+pub struct ParamEditReceiver {
+    consumer: ParamQueueConsumer<ParamEdit>,
+}
+
+impl ParamEditReceiver {
+    /// Receives all pending edits.
+    ///
+    /// Each value change is given to the `apply` callback, so that the plugin can update its
+    /// state. All edits are also reported to the host by pushing the matching events to the
+    /// given `output_events`, at the start of the block.
+    ///
+    /// This never allocates nor blocks, and is suitable for calling on the audio thread.
+    pub fn receive(
+        &mut self,
+        output_events: &mut OutputEvents,
+        mut apply: impl FnMut(&ParamChange),
+    ) {
+        while let Some(edit) = self.consumer.pop() {
+            // Parameter changes are still applied even if the host cannot be notified.
+            let _ = match edit {
+                ParamEdit::GestureBegin(param_id) => {
+                    output_events.try_push(ParamGestureBeginEvent::new(0, param_id))
+                }
+                ParamEdit::Value(change) => {
+                    apply(&change);
+                    output_events.try_push(change.to_event(0))
+                }
+                ParamEdit::GestureEnd(param_id) => {
+                    output_events.try_push(ParamGestureEndEvent::new(0, param_id))
+                }
+            };
+        }
+    }
+
+    /// Returns `true` if there are no pending edits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.consumer.is_empty()
+    }
+}
+
+impl Debug for ParamEditReceiver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParamEditReceiver")
+            .field("pending", &self.consumer.len())
+            .finish()
+    }
+}
@@ -26,6 +26,8 @@ impl ParamInfoBuffer {
 
 impl PluginParams {
     pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.count");
+
         match plugin.use_extension(&self.0).count {
             None => 0,
             // SAFETY: This type ensures the function pointer is valid.
@@ -39,6 +41,8 @@ impl PluginParams {
         index: u32,
         buffer: &'b mut ParamInfoBuffer,
     ) -> Option<ParamInfo<'b>> {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.get_info");
+
         // SAFETY: This type ensures the function pointer is valid.
         let success = unsafe {
             plugin.use_extension(&self.0).get_info?(
@@ -57,6 +61,8 @@ impl PluginParams {
     }
 
     pub fn get_value(&self, plugin: &mut PluginMainThreadHandle, param_id: ClapId) -> Option<f64> {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.get_value");
+
         let mut value = 0.0;
         // SAFETY: This type ensures the function pointer is valid.
         let valid = unsafe {
@@ -77,6 +83,8 @@ impl PluginParams {
         value: f64,
         buffer: &'b mut [MaybeUninit<u8>],
     ) -> Result<&'b mut [u8], core::fmt::Error> {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.value_to_text");
+
         let Some(value_to_text) = plugin.use_extension(&self.0).value_to_text else {
             return Err(core::fmt::Error);
         };
@@ -109,6 +117,8 @@ impl PluginParams {
         param_id: ClapId,
         display: &CStr,
     ) -> Option<f64> {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.text_to_value");
+
         let mut value = 0.0;
 
         // SAFETY: This type ensures the function pointer is valid.
@@ -134,6 +144,8 @@ impl PluginParams {
         input_parameter_changes: &InputEvents,
         output_parameter_changes: &mut OutputEvents,
     ) {
+        crate::utils::trace_call!(plugin, "clap_plugin_params.flush");
+
         if let Some(flush) = plugin.use_extension(&self.0).flush {
             // SAFETY: This type ensures the function pointer is valid.
             unsafe {
@@ -153,6 +165,8 @@ impl PluginParams {
         output_parameter_changes: &mut OutputEvents,
     ) {
         if let Some(flush) = plugin.use_extension(&self.0).flush {
+            let _audio_thread = AudioThreadGuard::enter();
+
             // SAFETY: This type ensures the function pointer is valid.
             unsafe {
                 flush(
@@ -0,0 +1,191 @@
+use clack_common::events::event_types::{ParamModEvent, ParamValueEvent};
+use clack_common::utils::{ClapId, Cookie};
+
+/// A plugin's list of parameters, which can be looked up in O(1) from the cookie of incoming
+/// parameter events.
+///
+/// Each parameter is associated with a value of type `T`, such as its state or its smoothed
+/// value. The cookie to give to the host in [`ParamInfo`](super::ParamInfo) is obtained from
+/// [`cookie`](Self::cookie), and stores the parameter's index in this list.
+///
+/// When looking a parameter up from an event, the cookie is used if it is valid for the event's
+/// parameter ID. Otherwise (e.g. if the host didn't send the cookie back), this falls back to
+/// searching the parameter by ID.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::params::ParamLookup;
+/// use clack_common::events::event_types::ParamValueEvent;
+/// use clack_common::events::Pckn;
+/// use clack_common::utils::{ClapId, Cookie};
+///
+/// let mut params = ParamLookup::new();
+/// params.push(ClapId::new(10), 0.5f64);
+/// params.push(ClapId::new(20), 1.0f64);
+///
+/// // Plugins give this cookie to the host when it requests the parameter's info.
+/// let cookie = params.cookie(1).unwrap();
+///
+/// let event = ParamValueEvent::new(0, ClapId::new(20), Pckn::match_all(), 0.25, cookie);
+/// *params.for_value_event_mut(&event).unwrap() = event.value();
+/// assert_eq!(params.get(ClapId::new(20)), Some(&0.25));
+///
+/// // Events without cookies still work, but are slower to look up.
+/// let event = ParamValueEvent::new(0, ClapId::new(10), Pckn::match_all(), 0.0, Cookie::empty());
+/// assert_eq!(params.for_value_event(&event), Some(&0.5));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamLookup<T> {
+    params: Vec<(ClapId, T)>,
+}
+
+impl<T> ParamLookup<T> {
+    /// Creates a new, empty list of parameters.
+    #[inline]
+    pub fn new() -> Self {
+        Self { params: Vec::new() }
+    }
+
+    /// Adds a parameter at the end of the list.
+    ///
+    /// Parameter IDs are expected to be unique. If the ID is already in the list, the first
+    /// parameter with that ID shadows this one when looking it up by ID.
+    #[inline]
+    pub fn push(&mut self, param_id: ClapId, value: T) {
+        self.params.push((param_id, value))
+    }
+
+    /// Returns the number of parameters in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns `true` if the list has no parameters.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Returns the cookie to give to the host for the parameter at the given index, or `None`
+    /// if there is no parameter at that index.
+    #[inline]
+    pub fn cookie(&self, index: usize) -> Option<Cookie> {
+        (index < self.params.len()).then(|| Cookie::from_index(index))
+    }
+
+    /// Returns the ID and value of the parameter at the given index.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(ClapId, &T)> {
+        self.params.get(index).map(|(id, value)| (*id, value))
+    }
+
+    /// Returns the value of the parameter with the given ID.
+    #[inline]
+    pub fn get(&self, param_id: ClapId) -> Option<&T> {
+        let index = self.index_of(param_id)?;
+        Some(&self.params[index].1)
+    }
+
+    /// Returns the value of the parameter with the given ID, mutably.
+    #[inline]
+    pub fn get_mut(&mut self, param_id: ClapId) -> Option<&mut T> {
+        let index = self.index_of(param_id)?;
+        Some(&mut self.params[index].1)
+    }
+
+    /// Returns the value of the parameter with the given ID, using the given cookie to find it
+    /// in O(1) if possible.
+    #[inline]
+    pub fn resolve(&self, param_id: ClapId, cookie: Cookie) -> Option<&T> {
+        let index = self.resolve_index(param_id, cookie)?;
+        Some(&self.params[index].1)
+    }
+
+    /// Returns the value of the parameter with the given ID mutably, using the given cookie to
+    /// find it in O(1) if possible.
+    #[inline]
+    pub fn resolve_mut(&mut self, param_id: ClapId, cookie: Cookie) -> Option<&mut T> {
+        let index = self.resolve_index(param_id, cookie)?;
+        Some(&mut self.params[index].1)
+    }
+
+    /// Returns the value of the parameter targeted by the given value event.
+    #[inline]
+    pub fn for_value_event(&self, event: &ParamValueEvent) -> Option<&T> {
+        self.resolve(event.param_id()?, event.cookie())
+    }
+
+    /// Returns the value of the parameter targeted by the given value event, mutably.
+    #[inline]
+    pub fn for_value_event_mut(&mut self, event: &ParamValueEvent) -> Option<&mut T> {
+        self.resolve_mut(event.param_id()?, event.cookie())
+    }
+
+    /// Returns the value of the parameter targeted by the given modulation event.
+    #[inline]
+    pub fn for_mod_event(&self, event: &ParamModEvent) -> Option<&T> {
+        self.resolve(event.param_id()?, event.cookie())
+    }
+
+    /// Returns the value of the parameter targeted by the given modulation event, mutably.
+    #[inline]
+    pub fn for_mod_event_mut(&mut self, event: &ParamModEvent) -> Option<&mut T> {
+        self.resolve_mut(event.param_id()?, event.cookie())
+    }
+
+    /// Returns an iterator over the IDs and values of all the parameters, in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ClapId, &T)> {
+        self.params.iter().map(|(id, value)| (*id, value))
+    }
+
+    fn index_of(&self, param_id: ClapId) -> Option<usize> {
+        self.params.iter().position(|(id, _)| *id == param_id)
+    }
+
+    fn resolve_index(&self, param_id: ClapId, cookie: Cookie) -> Option<usize> {
+        match cookie.index() {
+            // Cookies coming from the host can be stale, so they are always checked.
+            Some(index)
+                if self
+                    .params
+                    .get(index)
+                    .is_some_and(|(id, _)| *id == param_id) =>
+            {
+                Some(index)
+            }
+            _ => self.index_of(param_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ignores_stale_cookies() {
+        let mut params = ParamLookup::new();
+        params.push(ClapId::new(1), "first");
+        params.push(ClapId::new(2), "second");
+
+        assert_eq!(params.cookie(2), None);
+        assert_eq!(
+            params.resolve(ClapId::new(2), params.cookie(1).unwrap()),
+            Some(&"second")
+        );
+
+        // The cookie points to another parameter, or out of bounds.
+        assert_eq!(
+            params.resolve(ClapId::new(2), params.cookie(0).unwrap()),
+            Some(&"second")
+        );
+        assert_eq!(
+            params.resolve(ClapId::new(1), Cookie::from_index(5)),
+            Some(&"first")
+        );
+        assert_eq!(params.resolve(ClapId::new(3), Cookie::from_index(0)), None);
+    }
+}
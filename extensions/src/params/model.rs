@@ -0,0 +1,418 @@
+use super::{ParamInfo, ParamInfoFlags};
+use clack_common::utils::ClapId;
+
+/// The maximum number of steps a stepped parameter can have for its values to be listed as
+/// enumeration entries.
+///
+/// Past this, a stepped parameter is better displayed as a regular, notched control.
+pub const MAX_ENUM_ENTRIES: u32 = 64;
+
+/// The kind of control a [`ParamModel`] is best represented with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ParamControlKind {
+    /// A continuous control, e.g. a knob or a slider.
+    Continuous,
+    /// A control that snaps to a number of discrete steps, e.g. a notched knob.
+    Stepped {
+        /// The number of discrete values the parameter can take.
+        step_count: u32,
+    },
+    /// A stepped parameter with only two values, e.g. a checkbox or a toggle button.
+    Toggle,
+    /// A stepped parameter with a labelled entry for every one of its values, e.g. a drop-down
+    /// list.
+    Enumeration,
+}
+
+/// A labelled value of a stepped parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamEnumEntry {
+    /// The plain value of this entry.
+    pub value: f64,
+    /// The text to display for this entry.
+    pub label: String,
+}
+
+/// A toolkit-agnostic model of a single parameter, for GUIs to bind their controls to.
+///
+/// The model is built from a parameter's [`ParamInfo`], and holds its current plain value,
+/// along with the text to display for it. It handles the conversions between the plain values
+/// plugins deal with and the normalized `0..=1` values most GUI controls work with, including
+/// snapping to the nearest step for [stepped](ParamInfoFlags::IS_STEPPED) parameters.
+///
+/// This type doesn't depend on either the host or the plugin side, so that it can be used both
+/// by generic host UIs and by plugin GUIs. Hosts can use `PluginParams::model` to build a model
+/// with its display text and enumeration entries filled in by the plugin, which requires the
+/// `clack-host` feature.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::params::{ParamControlKind, ParamInfo, ParamInfoFlags, ParamModel};
+/// use clack_common::utils::{ClapId, Cookie};
+///
+/// let mut model = ParamModel::from_info(&ParamInfo {
+///     id: ClapId::new(1),
+///     flags: ParamInfoFlags::IS_STEPPED,
+///     cookie: Cookie::empty(),
+///     name: b"Voices",
+///     module: b"",
+///     min_value: 1.0,
+///     max_value: 16.0,
+///     default_value: 8.0,
+/// });
+///
+/// assert_eq!(model.control_kind(), ParamControlKind::Stepped { step_count: 16 });
+/// assert_eq!(model.value(), 8.0);
+///
+/// // Stepped values are snapped to the nearest step.
+/// model.set_normalized(0.5);
+/// assert_eq!(model.value(), 9.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamModel {
+    id: ClapId,
+    flags: ParamInfoFlags,
+    name: String,
+    module: String,
+    min_value: f64,
+    max_value: f64,
+    default_value: f64,
+    value: f64,
+    display: String,
+    entries: Vec<ParamEnumEntry>,
+}
+
+impl ParamModel {
+    /// Creates a new model from the given parameter information.
+    ///
+    /// The model starts at the parameter's default value, with no display text.
+    pub fn from_info(info: &ParamInfo) -> Self {
+        Self {
+            id: info.id,
+            flags: info.flags,
+            name: String::from_utf8_lossy(info.name).into_owned(),
+            module: String::from_utf8_lossy(info.module).into_owned(),
+            min_value: info.min_value,
+            max_value: info.max_value,
+            default_value: info.default_value,
+            value: info.default_value,
+            display: String::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the ID of the parameter.
+    #[inline]
+    pub fn id(&self) -> ClapId {
+        self.id
+    }
+
+    /// Returns the flags of the parameter.
+    #[inline]
+    pub fn flags(&self) -> ParamInfoFlags {
+        self.flags
+    }
+
+    /// Returns the displayable name of the parameter.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the module the parameter belongs to, as a `/`-separated path.
+    #[inline]
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Returns the minimum plain value of the parameter.
+    #[inline]
+    pub fn min_value(&self) -> f64 {
+        self.min_value
+    }
+
+    /// Returns the maximum plain value of the parameter.
+    #[inline]
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// Returns the default plain value of the parameter.
+    #[inline]
+    pub fn default_value(&self) -> f64 {
+        self.default_value
+    }
+
+    /// Returns `true` if the parameter's values are snapped to integer steps.
+    #[inline]
+    pub fn is_stepped(&self) -> bool {
+        self.flags.is_stepped()
+    }
+
+    /// Returns the number of discrete values a stepped parameter can take, or `None` if it is
+    /// continuous.
+    pub fn step_count(&self) -> Option<u32> {
+        if !self.is_stepped() {
+            return None;
+        }
+
+        let range = (self.max_value - self.min_value).round();
+        // Huge ranges saturate to u32::MAX, instead of overflowing.
+        Some(if range > 0.0 {
+            (range as u32).saturating_add(1)
+        } else {
+            1
+        })
+    }
+
+    /// Returns the kind of control this parameter is best represented with.
+    pub fn control_kind(&self) -> ParamControlKind {
+        match self.step_count() {
+            None => ParamControlKind::Continuous,
+            Some(_) if !self.entries.is_empty() => ParamControlKind::Enumeration,
+            Some(2) => ParamControlKind::Toggle,
+            Some(step_count) => ParamControlKind::Stepped { step_count },
+        }
+    }
+
+    /// Returns the current plain value of the parameter.
+    #[inline]
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Sets the current plain value of the parameter.
+    ///
+    /// The value is clamped to the range of the parameter, and snapped to the nearest step if
+    /// the parameter is stepped. The new value is returned.
+    ///
+    /// This doesn't update the display text, which the host or plugin must provide again.
+    pub fn set_value(&mut self, value: f64) -> f64 {
+        self.value = self.constrain(value);
+        self.value
+    }
+
+    /// Returns the current value of the parameter, normalized to the `0..=1` range.
+    #[inline]
+    pub fn normalized(&self) -> f64 {
+        self.normalize(self.value)
+    }
+
+    /// Sets the current value of the parameter from a normalized value in the `0..=1` range.
+    ///
+    /// See [`set_value`](Self::set_value). The new plain value is returned.
+    #[inline]
+    pub fn set_normalized(&mut self, normalized: f64) -> f64 {
+        self.set_value(self.denormalize(normalized))
+    }
+
+    /// Converts the given plain value to the `0..=1` range.
+    ///
+    /// If the range of the parameter is empty, this always returns `0`.
+    pub fn normalize(&self, value: f64) -> f64 {
+        let range = self.max_value - self.min_value;
+
+        if range > 0.0 {
+            ((value - self.min_value) / range).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Converts the given normalized value to a plain value.
+    ///
+    /// The result is clamped to the range of the parameter, and snapped to the nearest step if
+    /// the parameter is stepped.
+    #[inline]
+    pub fn denormalize(&self, normalized: f64) -> f64 {
+        let normalized = normalized.clamp(0.0, 1.0);
+        self.constrain(self.min_value + normalized * (self.max_value - self.min_value))
+    }
+
+    /// Returns the text to display for the current value.
+    ///
+    /// This is empty until the host or plugin provides it using
+    /// [`set_display`](Self::set_display).
+    #[inline]
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    /// Sets the text to display for the current value.
+    #[inline]
+    pub fn set_display(&mut self, display: impl Into<String>) {
+        self.display = display.into();
+    }
+
+    /// Returns the labelled entries of this parameter, if it is an enumeration.
+    #[inline]
+    pub fn entries(&self) -> &[ParamEnumEntry] {
+        &self.entries
+    }
+
+    /// Sets the labelled entries of this parameter.
+    ///
+    /// Giving a non-empty list of entries to a stepped parameter makes it an
+    /// [`Enumeration`](ParamControlKind::Enumeration).
+    #[inline]
+    pub fn set_entries(&mut self, entries: Vec<ParamEnumEntry>) {
+        self.entries = entries;
+    }
+
+    /// Returns the entry matching the current value, if this parameter is an enumeration.
+    pub fn current_entry(&self) -> Option<&ParamEnumEntry> {
+        self.entries.iter().find(|e| e.value == self.value)
+    }
+
+    fn constrain(&self, value: f64) -> f64 {
+        if value.is_nan() {
+            return self.value;
+        }
+
+        // Not using clamp(), which panics if a misbehaving plugin declared min > max.
+        let value = value.max(self.min_value).min(self.max_value);
+
+        if self.is_stepped() {
+            value.round()
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use crate::params::PluginParams;
+    use clack_host::plugin::PluginMainThreadHandle;
+    use std::mem::MaybeUninit;
+
+    impl ParamModel {
+        /// Updates the current value of this model from the plugin, along with its display text.
+        ///
+        /// The value is left unchanged if the plugin fails to provide it.
+        pub fn refresh(&mut self, params: &PluginParams, plugin: &mut PluginMainThreadHandle) {
+            if let Some(value) = params.get_value(plugin, self.id) {
+                self.value = value;
+            }
+
+            self.refresh_display(params, plugin);
+        }
+
+        /// Updates the display text of the current value from the plugin.
+        ///
+        /// The display text is cleared if the plugin fails to provide it.
+        pub fn refresh_display(
+            &mut self,
+            params: &PluginParams,
+            plugin: &mut PluginMainThreadHandle,
+        ) {
+            self.display = value_to_text(params, plugin, self.id, self.value).unwrap_or_default();
+        }
+    }
+
+    impl PluginParams {
+        /// Builds a [`ParamModel`] for the given parameter, with its current value and display
+        /// text provided by the plugin.
+        ///
+        /// If the parameter is stepped with no more than [`MAX_ENUM_ENTRIES`] steps, and the
+        /// plugin provides display text for each of them, they are listed as enumeration entries.
+        pub fn model(&self, plugin: &mut PluginMainThreadHandle, info: &ParamInfo) -> ParamModel {
+            let mut model = ParamModel::from_info(info);
+            model.refresh(self, plugin);
+
+            if let Some(step_count) = model.step_count().filter(|&c| c <= MAX_ENUM_ENTRIES) {
+                let entries: Option<Vec<_>> = (0..step_count)
+                    .map(|step| {
+                        let value = model.min_value + step as f64;
+                        let label = value_to_text(self, plugin, model.id, value)?;
+                        Some(ParamEnumEntry { value, label })
+                    })
+                    .collect();
+
+                model.entries = entries.unwrap_or_default();
+            }
+
+            model
+        }
+    }
+
+    fn value_to_text(
+        params: &PluginParams,
+        plugin: &mut PluginMainThreadHandle,
+        param_id: ClapId,
+        value: f64,
+    ) -> Option<String> {
+        let mut buffer = [MaybeUninit::uninit(); 256];
+        let text = params
+            .value_to_text(plugin, param_id, value, &mut buffer)
+            .ok()?;
+
+        Some(String::from_utf8_lossy(text).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clack_common::utils::Cookie;
+
+    fn info(flags: ParamInfoFlags, min_value: f64, max_value: f64) -> ParamInfo<'static> {
+        ParamInfo {
+            id: ClapId::new(1),
+            flags,
+            cookie: Cookie::empty(),
+            name: b"Param",
+            module: b"Module/Sub",
+            min_value,
+            max_value,
+            default_value: min_value,
+        }
+    }
+
+    #[test]
+    fn normalizes_continuous_values() {
+        let mut model = ParamModel::from_info(&info(ParamInfoFlags::empty(), -10.0, 10.0));
+
+        assert_eq!(model.control_kind(), ParamControlKind::Continuous);
+        assert_eq!(model.step_count(), None);
+        assert_eq!(model.module(), "Module/Sub");
+        assert_eq!(model.normalized(), 0.0);
+
+        assert_eq!(model.set_normalized(0.75), 5.0);
+        assert_eq!(model.set_value(42.0), 10.0);
+        assert_eq!(model.normalized(), 1.0);
+        assert_eq!(model.set_value(f64::NAN), 10.0);
+    }
+
+    #[test]
+    fn detects_control_kinds() {
+        let toggle = ParamModel::from_info(&info(ParamInfoFlags::IS_STEPPED, 0.0, 1.0));
+        assert_eq!(toggle.control_kind(), ParamControlKind::Toggle);
+
+        let mut mode = ParamModel::from_info(&info(ParamInfoFlags::IS_STEPPED, 0.0, 2.0));
+        assert_eq!(
+            mode.control_kind(),
+            ParamControlKind::Stepped { step_count: 3 }
+        );
+
+        mode.set_entries(
+            ["Low", "Mid", "High"]
+                .iter()
+                .enumerate()
+                .map(|(i, label)| ParamEnumEntry {
+                    value: i as f64,
+                    label: label.to_string(),
+                })
+                .collect(),
+        );
+
+        assert_eq!(mode.control_kind(), ParamControlKind::Enumeration);
+        mode.set_normalized(0.6);
+        assert_eq!(mode.current_entry().unwrap().label, "Mid");
+
+        let huge = ParamModel::from_info(&info(ParamInfoFlags::IS_STEPPED, 0.0, f64::MAX));
+        assert_eq!(huge.step_count(), Some(u32::MAX));
+    }
+}
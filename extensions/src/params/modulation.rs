@@ -0,0 +1,333 @@
+use super::ParamInfo;
+use clack_common::events::event_types::{ParamModEvent, ParamValueEvent};
+use clack_common::events::io::{EventBuffer, InputEvents};
+use clack_common::events::spaces::CoreEventSpace;
+use clack_common::events::{Pckn, UnknownEvent};
+use clack_common::utils::{ClapId, Cookie};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+struct ModulatedParam {
+    base_value: f64,
+    range: Option<(f64, f64)>,
+    cookie: Cookie,
+    /// The current modulation amounts, for each PCKN they were sent to.
+    modulations: Vec<(Pckn, f64)>,
+}
+
+impl ModulatedParam {
+    fn new(base_value: f64) -> Self {
+        Self {
+            base_value,
+            range: None,
+            cookie: Cookie::empty(),
+            modulations: Vec::new(),
+        }
+    }
+
+    fn set_modulation(&mut self, pckn: Pckn, amount: f64) {
+        self.modulations.retain(|(p, _)| *p != pckn);
+
+        if amount != 0.0 {
+            self.modulations.push((pckn, amount));
+        }
+    }
+}
+
+/// A host-side helper that keeps track of the base values and modulation amounts of a plugin's
+/// parameters, following the CLAP modulation model.
+///
+/// In CLAP, modulation is kept separate from a parameter's base value: the host sends
+/// [`ParamValueEvent`]s to change a parameter's base value (e.g. from automation or user input),
+/// and [`ParamModEvent`]s to change its modulation amount. Modulation amounts are offsets
+/// expressed in the parameter's plain value units, which are added on top of the base value
+/// without ever changing it. They can either be global, or target specific voices using a
+/// [`Pckn`] tuple, in which case they are added on top of the global modulation for the matching
+/// voices.
+///
+/// This helper stores both values separately for each parameter, and queues the matching
+/// events to be sent to the plugin during the next `process()` call, which can be retrieved using
+/// [`pending_events`](Self::pending_events). It can also be fed the events output by the plugin
+/// using [`handle_event`](Self::handle_event), to update base values when the plugin changes them
+/// (e.g. from its own GUI), while keeping modulation untouched.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::{Match, Pckn};
+/// use clack_common::utils::ClapId;
+/// use clack_extensions::params::ParamModulation;
+///
+/// let param_id = ClapId::new(0);
+/// let mut modulation = ParamModulation::new();
+/// modulation.set_range(param_id, 0.0, 1.0);
+///
+/// // Set the base value, then modulate it globally and for a single voice.
+/// modulation.set_value(0, param_id, 0.5);
+/// modulation.set_modulation(0, param_id, Pckn::match_all(), 0.25);
+///
+/// let voice = Pckn::new(0u16, 0u16, 60u16, Match::All);
+/// modulation.set_modulation(10, param_id, voice, 0.5);
+///
+/// assert_eq!(modulation.base_value(param_id), Some(0.5));
+/// assert_eq!(modulation.modulated_value(param_id, &Pckn::match_all()), Some(0.75));
+/// // Values are clamped to the parameter's range.
+/// assert_eq!(modulation.modulated_value(param_id, &voice), Some(1.0));
+///
+/// // One ParamValueEvent and two ParamModEvents are waiting to be sent to the plugin.
+/// assert_eq!(modulation.pending_events().len(), 3);
+/// modulation.clear_events();
+/// ```
+#[derive(Default)]
+pub struct ParamModulation {
+    params: HashMap<ClapId, ModulatedParam>,
+    events: EventBuffer,
+}
+
+impl ParamModulation {
+    /// Creates a new, empty modulation state.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a parameter from its [`ParamInfo`].
+    ///
+    /// This sets the parameter's range and cookie, and resets its base value to its default value.
+    pub fn register(&mut self, info: &ParamInfo) {
+        let param = self
+            .params
+            .entry(info.id)
+            .or_insert_with(|| ModulatedParam::new(info.default_value));
+
+        param.base_value = info.default_value;
+        param.range = Some((info.min_value, info.max_value));
+        param.cookie = info.cookie;
+    }
+
+    /// Sets the range of the given parameter.
+    ///
+    /// Modulated values are always clamped to this range. By default, parameters don't have a
+    /// range, and modulated values are not clamped.
+    pub fn set_range(&mut self, param_id: ClapId, min: f64, max: f64) {
+        self.param_mut(param_id).range = Some((min, max));
+    }
+
+    /// Sets the base value of the given parameter, and queues the matching [`ParamValueEvent`]
+    /// at the given sample time.
+    ///
+    /// The parameter's modulation is left untouched.
+    pub fn set_value(&mut self, time: u32, param_id: ClapId, value: f64) {
+        let param = self.param_mut(param_id);
+        param.base_value = value;
+        let cookie = param.cookie;
+
+        self.events.push(&ParamValueEvent::new(
+            time,
+            param_id,
+            Pckn::match_all(),
+            value,
+            cookie,
+        ));
+    }
+
+    /// Sets the modulation amount of the given parameter for the given [`Pckn`] tuple, and queues
+    /// the matching [`ParamModEvent`] at the given sample time.
+    ///
+    /// A [`Pckn::match_all`] tuple sets the global modulation amount. Any other tuple sets the
+    /// modulation amount of the matching voices, which is added on top of the global one.
+    ///
+    /// This replaces any previous modulation amount that was set for the exact same tuple. An
+    /// amount of `0.0` removes the modulation.
+    pub fn set_modulation(&mut self, time: u32, param_id: ClapId, pckn: Pckn, amount: f64) {
+        let param = self.param_mut(param_id);
+        param.set_modulation(pckn, amount);
+        let cookie = param.cookie;
+
+        self.events
+            .push(&ParamModEvent::new(time, param_id, pckn, amount, cookie));
+    }
+
+    /// Removes all modulation from the given parameter, and queues the [`ParamModEvent`]s needed
+    /// to reset it at the given sample time.
+    pub fn clear_modulation(&mut self, time: u32, param_id: ClapId) {
+        let Some(param) = self.params.get_mut(&param_id) else {
+            return;
+        };
+
+        for (pckn, _) in param.modulations.drain(..) {
+            self.events
+                .push(&ParamModEvent::new(time, param_id, pckn, 0.0, param.cookie));
+        }
+    }
+
+    /// Returns the base value of the given parameter, without any modulation applied.
+    ///
+    /// This returns `None` if this parameter is unknown, i.e. if it was never registered, and
+    /// none of its value, range or modulation were ever set. Parameters that only had their range
+    /// or modulation set have a base value of `0.0`.
+    #[inline]
+    pub fn base_value(&self, param_id: ClapId) -> Option<f64> {
+        Some(self.params.get(&param_id)?.base_value)
+    }
+
+    /// Returns the total modulation amount applied to the given voice of the given parameter.
+    ///
+    /// This is the sum of all the modulation amounts whose [`Pckn`] tuple matches the given one,
+    /// including the global modulation. Use [`Pckn::match_all`] to only get the global modulation.
+    pub fn modulation_amount(&self, param_id: ClapId, voice: &Pckn) -> f64 {
+        let Some(param) = self.params.get(&param_id) else {
+            return 0.0;
+        };
+
+        param
+            .modulations
+            .iter()
+            .filter(|(pckn, _)| pckn.matches_all() || (!voice.matches_all() && pckn.matches(voice)))
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// Returns the value of the given parameter with modulation applied for the given voice,
+    /// clamped to the parameter's range if it has one.
+    ///
+    /// This returns `None` if this parameter is unknown, like [`base_value`](Self::base_value).
+    pub fn modulated_value(&self, param_id: ClapId, voice: &Pckn) -> Option<f64> {
+        let param = self.params.get(&param_id)?;
+        let value = param.base_value + self.modulation_amount(param_id, voice);
+
+        Some(match param.range {
+            // Unlike clamp, this doesn't panic if the range is inverted or NaN.
+            Some((min, max)) => value.max(min).min(max),
+            None => value,
+        })
+    }
+
+    /// Updates this state from an event, e.g. one that was output by the plugin.
+    ///
+    /// Global [`ParamValueEvent`]s update the base value of their parameter, and
+    /// [`ParamModEvent`]s update its modulation amounts. Other events are ignored. Unlike the
+    /// setter methods, this does not queue any event to be sent back to the plugin.
+    pub fn handle_event(&mut self, event: &UnknownEvent) {
+        match event.as_core_event() {
+            Some(CoreEventSpace::ParamValue(event)) if event.pckn().matches_all() => {
+                if let Some(param_id) = event.param_id() {
+                    self.param_mut(param_id).base_value = event.value();
+                }
+            }
+            Some(CoreEventSpace::ParamMod(event)) => {
+                if let Some(param_id) = event.param_id() {
+                    self.param_mut(param_id)
+                        .set_modulation(event.pckn(), event.amount());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns all the events that were queued since the last call to
+    /// [`clear_events`](Self::clear_events), sorted by time, and ready to be sent to the plugin.
+    pub fn pending_events(&mut self) -> InputEvents<'_> {
+        self.events.sort();
+        self.events.as_input()
+    }
+
+    /// Clears all the queued events, e.g. after they have been sent to the plugin.
+    #[inline]
+    pub fn clear_events(&mut self) {
+        self.events.clear()
+    }
+
+    /// Forgets all parameters and queued events.
+    pub fn clear(&mut self) {
+        self.params.clear();
+        self.events.clear();
+    }
+
+    fn param_mut(&mut self, param_id: ClapId) -> &mut ModulatedParam {
+        self.params
+            .entry(param_id)
+            .or_insert_with(|| ModulatedParam::new(0.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clack_common::events::Match;
+
+    #[test]
+    fn keeps_modulation_separate_from_base_values() {
+        let id = ClapId::new(4);
+        let voice = Pckn::new(0u16, 0u16, 60u16, Match::All);
+        let other_voice = Pckn::new(0u16, 0u16, 64u16, Match::All);
+
+        let mut modulation = ParamModulation::new();
+        assert_eq!(modulation.base_value(id), None);
+        assert_eq!(modulation.modulated_value(id, &voice), None);
+
+        modulation.set_value(0, id, 1.0);
+        modulation.set_modulation(0, id, Pckn::match_all(), 0.5);
+        modulation.set_modulation(0, id, voice, 0.25);
+
+        assert_eq!(modulation.modulated_value(id, &voice), Some(1.75));
+        assert_eq!(modulation.modulated_value(id, &other_voice), Some(1.5));
+
+        // The plugin changing the base value doesn't affect modulation.
+        let plugin_event = ParamValueEvent::new(0, id, Pckn::match_all(), 2.0, Cookie::empty());
+        modulation.handle_event(plugin_event.as_ref());
+        assert_eq!(modulation.base_value(id), Some(2.0));
+        assert_eq!(modulation.modulated_value(id, &voice), Some(2.75));
+
+        // Replacing and clearing modulation amounts.
+        modulation.set_modulation(5, id, voice, -0.5);
+        assert_eq!(modulation.modulation_amount(id, &voice), 0.0);
+        modulation.clear_events();
+
+        modulation.clear_modulation(10, id);
+        assert_eq!(modulation.modulated_value(id, &voice), Some(2.0));
+
+        let events: Vec<_> = modulation
+            .pending_events()
+            .iter()
+            .map(|e| match e.as_core_event() {
+                Some(CoreEventSpace::ParamMod(e)) => (e.pckn(), e.amount()),
+                _ => panic!("Unexpected event"),
+            })
+            .collect();
+
+        assert_eq!(events, [(Pckn::match_all(), 0.0), (voice, 0.0)]);
+    }
+
+    #[test]
+    fn handles_modulation_of_unset_and_invalid_params() {
+        let id = ClapId::new(4);
+        let voice = Pckn::match_all();
+
+        let mut modulation = ParamModulation::new();
+        modulation.set_modulation(0, id, voice, 0.5);
+        assert_eq!(modulation.base_value(id), Some(0.0));
+        assert_eq!(modulation.modulated_value(id, &voice), Some(0.5));
+
+        // Inverted ranges don't panic.
+        modulation.set_range(id, 1.0, 0.0);
+        assert_eq!(modulation.modulated_value(id, &voice), Some(0.0));
+    }
+
+    #[test]
+    fn sorts_pending_events() {
+        let id = ClapId::new(0);
+        let mut modulation = ParamModulation::new();
+
+        modulation.set_modulation(20, id, Pckn::match_all(), 1.0);
+        modulation.set_value(10, id, 0.5);
+
+        let times: Vec<_> = modulation
+            .pending_events()
+            .iter()
+            .map(|e| e.header().time())
+            .collect();
+
+        assert_eq!(times, [10, 20]);
+    }
+}
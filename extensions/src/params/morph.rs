@@ -0,0 +1,296 @@
+use super::*;
+use clack_common::events::io::{OutputEvents, TryPushError};
+use clack_common::events::Pckn;
+
+/// The shape of the interpolation performed by a [`ParamMorph`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MorphCurve {
+    /// The values move at a constant rate from one snapshot to the other.
+    #[default]
+    Linear,
+    /// The values ease in and out of each snapshot, following a smoothstep curve.
+    Smooth,
+}
+
+impl MorphCurve {
+    /// Maps the given linear progress (between `0.0` and `1.0`) onto this curve.
+    #[inline]
+    pub fn apply(self, progress: f64) -> f64 {
+        let progress = progress.clamp(0.0, 1.0);
+
+        match self {
+            MorphCurve::Linear => progress,
+            MorphCurve::Smooth => progress * progress * (3.0 - 2.0 * progress),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct MorphTarget {
+    param: CachedParam,
+    from: f64,
+    to: f64,
+    last_sent: Option<f64>,
+}
+
+impl MorphTarget {
+    fn value_at(&self, position: f64) -> f64 {
+        let value = self.from + (self.to - self.from) * position;
+        // Not using clamp(), which panics if a misbehaving plugin declared min > max.
+        let value = value.max(self.param.min_value).min(self.param.max_value);
+
+        if self.param.flags.contains(ParamInfoFlags::IS_STEPPED) {
+            value.round()
+        } else {
+            value
+        }
+    }
+}
+
+/// A host-side utility interpolating the values of a plugin's parameters between two captured
+/// snapshots over time.
+///
+/// The snapshots are two [`ParamCache`]s, e.g. captured using [`PluginParams::scan`] before and
+/// after loading a preset. On each `process()` call, [`fill_events`](Self::fill_events) outputs
+/// the [`ParamValueEvent`](clack_common::events::event_types::ParamValueEvent)s moving the
+/// parameters a block further through the morph, which can then be sent to the plugin as input
+/// events.
+///
+/// Only the parameters present in both snapshots, with a known value in both and that are not
+/// [read-only](ParamInfoFlags::IS_READONLY), are morphed. [Stepped](ParamInfoFlags::IS_STEPPED)
+/// parameters are rounded to the nearest step, and events are only output when a parameter's
+/// value actually changes.
+///
+/// The [resolution](Self::with_resolution) sets how often, in frames, new values are output.
+/// Setting it to `1` outputs an event for every frame and every parameter, which is also useful
+/// to stress-test a plugin's parameter handling.
+///
+/// # Example
+///
+/// ```no_run
+/// use clack_extensions::params::{ParamCache, ParamMorph};
+/// use clack_host::events::io::EventBuffer;
+///
+/// # fn morph(from: &ParamCache, to: &ParamCache) {
+/// // Morph from one snapshot to the other over a second at 48kHz.
+/// let mut morph = ParamMorph::new(from, to, 48_000);
+/// let mut input_events = EventBuffer::new();
+///
+/// while !morph.is_finished() {
+///     input_events.clear();
+///     morph.fill_events(256, &mut input_events.as_output()).unwrap();
+///
+///     // Process 256 frames using input_events as the plugin's input events...
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParamMorph {
+    targets: Vec<MorphTarget>,
+    duration: u32,
+    position: u32,
+    resolution: u32,
+    curve: MorphCurve,
+    needs_refresh: bool,
+    is_finished: bool,
+}
+
+impl ParamMorph {
+    /// The default [resolution](Self::with_resolution) of a morph, in frames.
+    pub const DEFAULT_RESOLUTION: u32 = 64;
+
+    /// Creates a new morph from one parameter snapshot to the other, lasting the given number of
+    /// frames.
+    ///
+    /// The information (cookie, range and flags) of the parameters is taken from the `to`
+    /// snapshot.
+    pub fn new(from: &ParamCache, to: &ParamCache, duration: u32) -> Self {
+        let targets = to
+            .iter()
+            .filter(|param| !param.flags.contains(ParamInfoFlags::IS_READONLY))
+            .filter_map(|param| {
+                Some(MorphTarget {
+                    from: from.get(param.id)?.value?,
+                    to: param.value?,
+                    param: param.clone(),
+                    last_sent: None,
+                })
+            })
+            .collect();
+
+        Self {
+            targets,
+            duration,
+            position: 0,
+            resolution: Self::DEFAULT_RESOLUTION,
+            curve: MorphCurve::Linear,
+            needs_refresh: true,
+            is_finished: false,
+        }
+    }
+
+    /// Sets how often, in frames, new parameter values are output. This is clamped to at least
+    /// one frame.
+    ///
+    /// The default is [`DEFAULT_RESOLUTION`](Self::DEFAULT_RESOLUTION).
+    #[inline]
+    pub fn with_resolution(mut self, frames: u32) -> Self {
+        self.resolution = frames.max(1);
+        self
+    }
+
+    /// Sets the shape of the interpolation. The default is [`MorphCurve::Linear`].
+    #[inline]
+    pub fn with_curve(mut self, curve: MorphCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Returns the number of parameters this morph interpolates.
+    #[inline]
+    pub fn param_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns the total duration of this morph, in frames.
+    #[inline]
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+
+    /// Returns the current position of this morph, in frames.
+    #[inline]
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Returns the current linear progress of this morph, between `0.0` and `1.0`.
+    #[inline]
+    pub fn progress(&self) -> f64 {
+        if self.duration == 0 {
+            1.0
+        } else {
+            self.position as f64 / self.duration as f64
+        }
+    }
+
+    /// Returns `true` if the final values of this morph have been output.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    /// Moves this morph to the given position, in frames.
+    ///
+    /// The values at the new position are output on the next call to
+    /// [`fill_events`](Self::fill_events).
+    pub fn seek(&mut self, position: u32) {
+        self.position = position.min(self.duration);
+        self.needs_refresh = true;
+        self.is_finished = false;
+    }
+
+    /// Returns the value the given parameter has at the current position of this morph, or
+    /// `None` if this morph doesn't interpolate this parameter.
+    pub fn current_value(&self, param_id: ClapId) -> Option<f64> {
+        let target = self.targets.iter().find(|t| t.param.id == param_id)?;
+        Some(target.value_at(self.curve.apply(self.progress())))
+    }
+
+    /// Outputs the events moving the parameters through the next `frames_count` frames of this
+    /// morph, and advances it by that many frames.
+    ///
+    /// Events are timestamped relative to the start of the block. Once the morph is
+    /// [finished](Self::is_finished), this does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TryPushError`] if the given output event buffer is full. The morph is then
+    /// left at the frame of the event that could not be pushed.
+    pub fn fill_events(
+        &mut self,
+        frames_count: u32,
+        events: &mut OutputEvents,
+    ) -> Result<(), TryPushError> {
+        if self.is_finished {
+            return Ok(());
+        }
+
+        let start = self.position;
+
+        for time in 0..frames_count {
+            let position = start + time;
+            if position > self.duration {
+                break;
+            }
+
+            let is_last = position == self.duration;
+            if position % self.resolution != 0 && !is_last && !self.needs_refresh {
+                continue;
+            }
+
+            self.position = position;
+            let progress = self.curve.apply(self.progress());
+
+            for target in &mut self.targets {
+                let value = target.value_at(progress);
+                if target.last_sent == Some(value) {
+                    continue;
+                }
+
+                events.try_push(target.param.value_event(time, Pckn::match_all(), value))?;
+                target.last_sent = Some(value);
+            }
+
+            self.needs_refresh = false;
+
+            if is_last {
+                self.is_finished = true;
+                return Ok(());
+            }
+        }
+
+        self.position = (start + frames_count).min(self.duration);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn curves_start_and_end_at_bounds() {
+        for curve in [MorphCurve::Linear, MorphCurve::Smooth] {
+            assert_eq!(curve.apply(-1.0), 0.0);
+            assert_eq!(curve.apply(0.0), 0.0);
+            assert_eq!(curve.apply(0.5), 0.5);
+            assert_eq!(curve.apply(1.0), 1.0);
+            assert_eq!(curve.apply(2.0), 1.0);
+        }
+
+        assert!(MorphCurve::Smooth.apply(0.25) < 0.25);
+    }
+
+    #[test]
+    fn inverted_ranges_do_not_panic() {
+        let target = MorphTarget {
+            param: CachedParam {
+                id: ClapId::new(1),
+                flags: ParamInfoFlags::empty(),
+                cookie: Cookie::empty(),
+                name: Vec::new(),
+                module: Vec::new(),
+                min_value: 1.0,
+                max_value: 0.0,
+                default_value: 0.0,
+                value: None,
+            },
+            from: 0.0,
+            to: 1.0,
+            last_sent: None,
+        };
+
+        assert_eq!(target.value_at(0.5), 0.0);
+    }
+}
@@ -0,0 +1,280 @@
+use clack_common::events::spaces::CoreEventSpace;
+use clack_common::events::{Event, Pckn, UnknownEvent};
+use clack_common::utils::ClapId;
+use std::collections::{HashMap, HashSet};
+
+/// A parameter change notification, parsed from an event output by a plugin.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParamOutputEvent {
+    /// The plugin changed the value of a parameter, e.g. from its GUI.
+    ValueChanged {
+        /// The sample time of the change.
+        time: u32,
+        /// The ID of the parameter that changed.
+        param_id: ClapId,
+        /// The voices this change applies to.
+        pckn: Pckn,
+        /// The new value of the parameter.
+        value: f64,
+    },
+    /// The user started adjusting a parameter, e.g. by clicking on a knob.
+    GestureBegin {
+        /// The sample time of the start of the gesture.
+        time: u32,
+        /// The ID of the adjusted parameter.
+        param_id: ClapId,
+    },
+    /// The user stopped adjusting a parameter.
+    GestureEnd {
+        /// The sample time of the end of the gesture.
+        time: u32,
+        /// The ID of the adjusted parameter.
+        param_id: ClapId,
+    },
+}
+
+impl ParamOutputEvent {
+    /// Parses a parameter notification from the given event.
+    ///
+    /// This returns `None` if the event is not a parameter value or gesture event, or if it has
+    /// no valid parameter ID. Modulation events are also ignored, as plugins are not allowed to
+    /// output them.
+    pub fn from_event(event: &UnknownEvent) -> Option<Self> {
+        match event.as_core_event()? {
+            CoreEventSpace::ParamValue(event) => Some(Self::ValueChanged {
+                time: event.header().time(),
+                param_id: event.param_id()?,
+                pckn: event.pckn(),
+                value: event.value(),
+            }),
+            CoreEventSpace::ParamGestureBegin(event) => Some(Self::GestureBegin {
+                time: event.header().time(),
+                param_id: event.param_id()?,
+            }),
+            CoreEventSpace::ParamGestureEnd(event) => Some(Self::GestureEnd {
+                time: event.header().time(),
+                param_id: event.param_id()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the sample time of this notification.
+    #[inline]
+    pub fn time(&self) -> u32 {
+        match *self {
+            Self::ValueChanged { time, .. }
+            | Self::GestureBegin { time, .. }
+            | Self::GestureEnd { time, .. } => time,
+        }
+    }
+
+    /// Returns the ID of the parameter this notification is about.
+    #[inline]
+    pub fn param_id(&self) -> ClapId {
+        match *self {
+            Self::ValueChanged { param_id, .. }
+            | Self::GestureBegin { param_id, .. }
+            | Self::GestureEnd { param_id, .. } => param_id,
+        }
+    }
+}
+
+type Callback<'a> = Box<dyn FnMut(&ParamOutputEvent) + 'a>;
+
+/// A host-side router for the parameter events output by a plugin.
+///
+/// Plugins notify the host of parameter changes they initiate themselves (e.g. from their GUI)
+/// by outputting [`ParamValueEvent`](clack_common::events::event_types::ParamValueEvent)s,
+/// optionally surrounded by gesture begin and end events, either during `process()` or
+/// `flush()`.
+///
+/// After each of those calls, the resulting output events can be given to
+/// [`route`](Self::route), which parses them into [`ParamOutputEvent`]s, and calls the callbacks
+/// registered for each parameter with [`on_param`](Self::on_param), or the fallback one
+/// registered with [`on_any`](Self::on_any). Other events are ignored.
+///
+/// This router also keeps track of which parameters are currently being adjusted by the user,
+/// which hosts can use to e.g. record automation only while a gesture is in progress.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::events::event_types::{ParamGestureBeginEvent, ParamValueEvent};
+/// use clack_common::events::io::EventBuffer;
+/// use clack_common::events::Pckn;
+/// use clack_common::utils::{ClapId, Cookie};
+/// use clack_extensions::params::{ParamOutputEvent, ParamOutputRouter};
+///
+/// let gain_id = ClapId::new(1);
+/// let mut recorded = Vec::new();
+///
+/// // e.g. the buffer given to the plugin as its output events
+/// let mut output_events = EventBuffer::new();
+/// output_events.push(&ParamGestureBeginEvent::new(0, gain_id));
+/// output_events.push(&ParamValueEvent::new(0, gain_id, Pckn::match_all(), 0.5, Cookie::empty()));
+///
+/// let mut router = ParamOutputRouter::new();
+/// router.on_param(gain_id, |event| {
+///     if let ParamOutputEvent::ValueChanged { value, .. } = event {
+///         recorded.push(*value);
+///     }
+/// });
+///
+/// router.route(&output_events);
+/// assert!(router.is_in_gesture(gain_id));
+///
+/// drop(router);
+/// assert_eq!(recorded, [0.5]);
+/// ```
+#[derive(Default)]
+pub struct ParamOutputRouter<'a> {
+    routes: HashMap<ClapId, Callback<'a>>,
+    fallback: Option<Callback<'a>>,
+    gestures: HashSet<ClapId>,
+}
+
+impl<'a> ParamOutputRouter<'a> {
+    /// Creates a new router, with no callbacks registered.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the callback to be called for all notifications about the given parameter.
+    ///
+    /// This replaces any callback previously registered for the same parameter.
+    pub fn on_param(&mut self, param_id: ClapId, callback: impl FnMut(&ParamOutputEvent) + 'a) {
+        self.routes.insert(param_id, Box::new(callback));
+    }
+
+    /// Registers the callback to be called for all notifications about parameters that don't have
+    /// a callback of their own.
+    ///
+    /// This replaces any previously registered fallback callback.
+    pub fn on_any(&mut self, callback: impl FnMut(&ParamOutputEvent) + 'a) {
+        self.fallback = Some(Box::new(callback));
+    }
+
+    /// Removes the callback registered for the given parameter, if any.
+    pub fn remove(&mut self, param_id: ClapId) {
+        self.routes.remove(&param_id);
+    }
+
+    /// Routes all the parameter events in the given output events to their callbacks.
+    ///
+    /// Events that are not parameter events are ignored.
+    pub fn route<'e>(&mut self, events: impl IntoIterator<Item = &'e UnknownEvent>) {
+        for event in events {
+            if let Some(event) = ParamOutputEvent::from_event(event) {
+                self.dispatch(&event);
+            }
+        }
+    }
+
+    /// Routes a single parameter notification to its callback.
+    pub fn dispatch(&mut self, event: &ParamOutputEvent) {
+        match *event {
+            ParamOutputEvent::GestureBegin { param_id, .. } => {
+                self.gestures.insert(param_id);
+            }
+            ParamOutputEvent::GestureEnd { param_id, .. } => {
+                self.gestures.remove(&param_id);
+            }
+            ParamOutputEvent::ValueChanged { .. } => {}
+        }
+
+        if let Some(callback) = self.routes.get_mut(&event.param_id()) {
+            callback(event)
+        } else if let Some(fallback) = &mut self.fallback {
+            fallback(event)
+        }
+    }
+
+    /// Returns `true` if the user is currently adjusting the given parameter, i.e. if the plugin
+    /// sent a gesture begin event for it, and no matching gesture end event yet.
+    #[inline]
+    pub fn is_in_gesture(&self, param_id: ClapId) -> bool {
+        self.gestures.contains(&param_id)
+    }
+
+    /// Forgets about all gestures currently in progress, e.g. after the plugin was deactivated.
+    #[inline]
+    pub fn reset_gestures(&mut self) {
+        self.gestures.clear()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clack_common::events::event_types::*;
+    use clack_common::events::io::EventBuffer;
+    use clack_common::utils::Cookie;
+
+    #[test]
+    fn routes_events_to_callbacks() {
+        let first = ClapId::new(1);
+        let second = ClapId::new(2);
+
+        let mut events = EventBuffer::new();
+        events.push(&ParamGestureBeginEvent::new(0, first));
+        events.push(&ParamValueEvent::new(
+            1,
+            first,
+            Pckn::match_all(),
+            0.5,
+            Cookie::empty(),
+        ));
+        events.push(&NoteOnEvent::new(2, Pckn::match_all(), 1.0));
+        events.push(&ParamValueEvent::new(
+            3,
+            second,
+            Pckn::match_all(),
+            0.25,
+            Cookie::empty(),
+        ));
+        events.push(&ParamGestureEndEvent::new(4, first));
+
+        let mut first_events = Vec::new();
+        let mut other_events = Vec::new();
+
+        let mut router = ParamOutputRouter::new();
+        router.on_param(first, |e| first_events.push(*e));
+        router.on_any(|e| other_events.push(*e));
+
+        router.route(&events);
+        assert!(!router.is_in_gesture(first));
+        drop(router);
+
+        assert_eq!(
+            first_events,
+            [
+                ParamOutputEvent::GestureBegin {
+                    time: 0,
+                    param_id: first
+                },
+                ParamOutputEvent::ValueChanged {
+                    time: 1,
+                    param_id: first,
+                    pckn: Pckn::match_all(),
+                    value: 0.5
+                },
+                ParamOutputEvent::GestureEnd {
+                    time: 4,
+                    param_id: first
+                },
+            ]
+        );
+
+        assert_eq!(
+            other_events,
+            [ParamOutputEvent::ValueChanged {
+                time: 3,
+                param_id: second,
+                pckn: Pckn::match_all(),
+                value: 0.25
+            }]
+        );
+    }
+}
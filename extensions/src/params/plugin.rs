@@ -1,6 +1,7 @@
 use super::*;
 use crate::utils::{slice_from_external_parts_mut, write_to_array_buf};
 use clack_common::events::io::{InputEvents, OutputEvents};
+use clack_common::events::UnknownEvent;
 use clack_plugin::extensions::prelude::*;
 use clap_sys::events::{clap_input_events, clap_output_events};
 use clap_sys::ext::log::CLAP_LOG_ERROR;
@@ -93,6 +94,11 @@ impl core::fmt::Write for ParamDisplayWriter<'_> {
     }
 }
 
+/// The main-thread side of the Params extension implementation.
+///
+/// Besides describing the plugin's parameters, this handles parameter [`flush`](Self::flush)es
+/// that the host requests while the plugin is *inactive*. Flushes requested while the plugin is
+/// active are handled by [`PluginAudioProcessorParams`] instead, on the audio thread.
 pub trait PluginMainThreadParams {
     fn count(&mut self) -> u32;
     fn get_info(&mut self, param_index: u32, info: &mut ParamInfoWriter);
@@ -104,6 +110,14 @@ pub trait PluginMainThreadParams {
         writer: &mut ParamDisplayWriter,
     ) -> core::fmt::Result;
     fn text_to_value(&mut self, param_id: ClapId, text: &CStr) -> Option<f64>;
+
+    /// Synchronizes parameter values while the plugin is inactive.
+    ///
+    /// The plugin must handle all the given input events, and may push output events (e.g.
+    /// parameter changes coming from its GUI) to the host.
+    ///
+    /// Plugins that don't send any output event can use [`forward_param_events`] to implement
+    /// this method.
     fn flush(
         &mut self,
         input_parameter_changes: &InputEvents,
@@ -111,7 +125,18 @@ pub trait PluginMainThreadParams {
     );
 }
 
+/// The audio-thread side of the Params extension implementation.
+///
+/// This handles parameter [`flush`](Self::flush)es that the host requests while the plugin is
+/// *active*. Per the CLAP specification, those are never called concurrently to `process`.
 pub trait PluginAudioProcessorParams {
+    /// Synchronizes parameter values while the plugin is active, without processing any audio.
+    ///
+    /// The plugin must handle all the given input events, and may push output events (e.g.
+    /// parameter changes coming from its GUI) to the host.
+    ///
+    /// Plugins that don't send any output event can use [`forward_param_events`] to implement
+    /// this method.
     fn flush(
         &mut self,
         input_parameter_changes: &InputEvents,
@@ -119,6 +144,43 @@ pub trait PluginAudioProcessorParams {
     );
 }
 
+/// Forwards every parameter event received during a `flush` to the given handler.
+///
+/// This is a helper to implement either [`PluginMainThreadParams::flush`] or
+/// [`PluginAudioProcessorParams::flush`], for plugins that don't send any output event. Plugins
+/// can pass the same handler they use for the parameter events they receive in `process`, so
+/// that both paths behave identically.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::params::{forward_param_events, PluginAudioProcessorParams};
+/// use clack_plugin::prelude::*;
+///
+/// struct MyAudioProcessor;
+///
+/// impl MyAudioProcessor {
+///     fn handle_event(&mut self, event: &UnknownEvent) {
+///         /* ... */
+///     }
+/// }
+///
+/// impl PluginAudioProcessorParams for MyAudioProcessor {
+///     fn flush(&mut self, input: &InputEvents, _output: &mut OutputEvents) {
+///         forward_param_events(input, |event| self.handle_event(event))
+///     }
+/// }
+/// ```
+#[inline]
+pub fn forward_param_events(
+    input_parameter_changes: &InputEvents,
+    mut handler: impl FnMut(&UnknownEvent),
+) {
+    for event in input_parameter_changes {
+        handler(event);
+    }
+}
+
 #[allow(clippy::missing_safety_doc)]
 unsafe extern "C" fn count<P: Plugin>(plugin: *const clap_plugin) -> u32
 where
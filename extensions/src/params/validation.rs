@@ -0,0 +1,296 @@
+use super::ParamInfo;
+use clack_common::events::event_types::ParamValueEvent;
+use clack_common::events::spaces::CoreEventSpace;
+use clack_common::events::UnknownEvent;
+use clack_common::utils::ClapId;
+use clack_plugin::host::HostSharedHandle;
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+
+#[cfg(feature = "log")]
+use crate::log::{HostLog, LogSeverity};
+
+/// Errors that can occur when validating an incoming parameter value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParamValidationError {
+    /// The parameter ID doesn't match any declared parameter.
+    ///
+    /// This is `None` if the event didn't contain a valid parameter ID at all.
+    UnknownParam(Option<ClapId>),
+    /// The value for the given parameter is NaN.
+    NotANumber(ClapId),
+}
+
+impl Display for ParamValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamValidationError::UnknownParam(Some(param_id)) => {
+                write!(f, "Unknown parameter ID: {param_id}")
+            }
+            ParamValidationError::UnknownParam(None) => f.write_str("Invalid parameter ID"),
+            ParamValidationError::NotANumber(param_id) => {
+                write!(f, "Received a NaN value for parameter {param_id}")
+            }
+        }
+    }
+}
+
+impl Error for ParamValidationError {}
+
+/// A plugin-side helper, which validates incoming parameter values against the parameters the
+/// plugin declared.
+///
+/// Hosts are expected to only send values within the declared range of each parameter, but a
+/// misbehaving host may send values for unknown parameters, values out of range, or even NaNs.
+/// Feeding those directly into DSP code can produce anything from audible glitches to filters
+/// blowing up.
+///
+/// This validator is opt-in: plugins [`register`](Self::register) the same parameters they
+/// declare through the Params extension, and run incoming [`ParamValueEvent`]s through
+/// [`validate_event`](Self::validate_event) or [`filter_event`](Self::filter_event), both in
+/// `process` and in `flush`. Values out of range are clamped to the declared minimum and maximum
+/// values, while values for unknown parameters and NaNs are rejected.
+///
+/// In debug builds, if a host logger was given using `with_host_log`, every violation is also
+/// reported to the host's log with the `HostMisbehaving` severity.
+/// This requires the `log` feature.
+///
+/// Validating values never allocates.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::params::{ParamInfo, ParamInfoFlags, ParamValidationError, ParamValidator};
+/// use clack_plugin::utils::{ClapId, Cookie};
+///
+/// let gain = ClapId::new(1);
+///
+/// let mut validator = ParamValidator::new();
+/// validator.register(&ParamInfo {
+///     id: gain,
+///     flags: ParamInfoFlags::IS_AUTOMATABLE,
+///     cookie: Cookie::empty(),
+///     name: b"Gain",
+///     module: b"",
+///     min_value: 0.0,
+///     max_value: 2.0,
+///     default_value: 1.0,
+/// });
+///
+/// assert_eq!(validator.validate(gain, 1.5), Ok(1.5));
+/// assert_eq!(validator.validate(gain, 42.0), Ok(2.0));
+/// assert_eq!(validator.validate(gain, f64::NAN), Err(ParamValidationError::NotANumber(gain)));
+/// assert_eq!(
+///     validator.validate(ClapId::new(2), 0.5),
+///     Err(ParamValidationError::UnknownParam(Some(ClapId::new(2))))
+/// );
+/// ```
+pub struct ParamValidator<'a> {
+    ranges: HashMap<ClapId, (f64, f64)>,
+    #[cfg(feature = "log")]
+    log: Option<(HostSharedHandle<'a>, HostLog)>,
+    _host: PhantomData<HostSharedHandle<'a>>,
+}
+
+impl<'a> ParamValidator<'a> {
+    /// Creates a new, empty validator.
+    ///
+    /// Until parameters are registered, all values are rejected.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            ranges: HashMap::new(),
+            #[cfg(feature = "log")]
+            log: None,
+            _host: PhantomData,
+        }
+    }
+
+    /// Reports violations to the host's log, in debug builds only.
+    ///
+    /// This does nothing in release builds, or if the host doesn't support the Log extension.
+    #[cfg(feature = "log")]
+    pub fn with_host_log(mut self, host: HostSharedHandle<'a>) -> Self {
+        if cfg!(debug_assertions) {
+            self.log = host.get_extension::<HostLog>().map(|log| (host, log));
+        }
+
+        self
+    }
+
+    /// Registers a parameter from its information, replacing any previously registered
+    /// parameter with the same ID.
+    #[inline]
+    pub fn register(&mut self, info: &ParamInfo) {
+        self.register_range(info.id, info.min_value, info.max_value)
+    }
+
+    /// Registers a parameter from its ID and value range, replacing any previously registered
+    /// parameter with the same ID.
+    #[inline]
+    pub fn register_range(&mut self, param_id: ClapId, min_value: f64, max_value: f64) {
+        self.ranges.insert(param_id, (min_value, max_value));
+    }
+
+    /// Removes a previously registered parameter, e.g. after the plugin's parameters changed.
+    #[inline]
+    pub fn unregister(&mut self, param_id: ClapId) {
+        self.ranges.remove(&param_id);
+    }
+
+    /// Removes all registered parameters.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// Returns `true` if a parameter with the given ID was registered.
+    #[inline]
+    pub fn contains(&self, param_id: ClapId) -> bool {
+        self.ranges.contains_key(&param_id)
+    }
+
+    /// Validates the given value for the given parameter.
+    ///
+    /// This returns the value clamped to the range of the parameter, or an error if the
+    /// parameter is unknown, or if the value is NaN. If the parameter's range is inverted (i.e.
+    /// its minimum is greater than its maximum), values are clamped to its maximum.
+    pub fn validate(&self, param_id: ClapId, value: f64) -> Result<f64, ParamValidationError> {
+        let Some(&(min, max)) = self.ranges.get(&param_id) else {
+            self.report(MESSAGE_UNKNOWN_PARAM);
+            return Err(ParamValidationError::UnknownParam(Some(param_id)));
+        };
+
+        if value.is_nan() {
+            self.report(MESSAGE_NAN);
+            return Err(ParamValidationError::NotANumber(param_id));
+        }
+
+        if value < min || value > max {
+            self.report(MESSAGE_OUT_OF_RANGE);
+        }
+
+        // Not using clamp(), which panics if min > max.
+        Ok(value.max(min).min(max))
+    }
+
+    /// Validates the given parameter value event.
+    ///
+    /// This returns a copy of the event with its value clamped to the range of the parameter,
+    /// or an error if the event targets an unknown parameter, or if its value is NaN.
+    pub fn validate_event(
+        &self,
+        event: &ParamValueEvent,
+    ) -> Result<ParamValueEvent, ParamValidationError> {
+        let Some(param_id) = event.param_id() else {
+            self.report(MESSAGE_UNKNOWN_PARAM);
+            return Err(ParamValidationError::UnknownParam(None));
+        };
+
+        let value = self.validate(param_id, event.value())?;
+        Ok(event.with_value(value))
+    }
+
+    /// Returns the validated parameter value event, if the given event is a valid
+    /// [`ParamValueEvent`].
+    ///
+    /// This returns `None` for any other event type, as well as for rejected values. See
+    /// [`validate_event`](Self::validate_event).
+    #[inline]
+    pub fn filter_event(&self, event: &UnknownEvent) -> Option<ParamValueEvent> {
+        match event.as_core_event()? {
+            CoreEventSpace::ParamValue(event) => self.validate_event(event).ok(),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "log")]
+    fn report(&self, message: &CStr) {
+        if let Some((host, log)) = &self.log {
+            log.log(host, LogSeverity::HostMisbehaving, message);
+        }
+    }
+
+    #[cfg(not(feature = "log"))]
+    #[inline]
+    fn report(&self, _message: &CStr) {}
+}
+
+impl Default for ParamValidator<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ParamValidator<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParamValidator")
+            .field("ranges", &self.ranges)
+            .finish_non_exhaustive()
+    }
+}
+
+const fn message(bytes: &'static [u8]) -> &'static CStr {
+    match CStr::from_bytes_with_nul(bytes) {
+        Ok(message) => message,
+        Err(_) => panic!("Invalid log message"),
+    }
+}
+
+const MESSAGE_UNKNOWN_PARAM: &CStr =
+    message(b"Host sent a parameter value for an unknown parameter ID.\0");
+const MESSAGE_NAN: &CStr = message(b"Host sent a NaN parameter value.\0");
+const MESSAGE_OUT_OF_RANGE: &CStr =
+    message(b"Host sent a parameter value out of the parameter's range. It was clamped.\0");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clack_common::events::{Event, Pckn};
+    use clack_common::utils::Cookie;
+
+    #[test]
+    fn clamps_and_rejects_event_values() {
+        let mut validator = ParamValidator::new();
+        validator.register_range(ClapId::new(1), -1.0, 1.0);
+
+        let event = |param_id, value| {
+            ParamValueEvent::new(
+                5,
+                ClapId::new(param_id),
+                Pckn::match_all(),
+                value,
+                Cookie::empty(),
+            )
+        };
+
+        let clamped = validator.validate_event(&event(1, -3.0)).unwrap();
+        assert_eq!(clamped.value(), -1.0);
+        assert_eq!(clamped.header().time(), 5);
+
+        assert_eq!(
+            validator
+                .filter_event(event(1, f64::INFINITY).as_ref())
+                .map(|e| e.value()),
+            Some(1.0)
+        );
+        assert!(validator
+            .filter_event(event(1, f64::NAN).as_ref())
+            .is_none());
+        assert!(validator.filter_event(event(2, 0.0).as_ref()).is_none());
+
+        // Inverted ranges don't panic.
+        validator.register_range(ClapId::new(3), 1.0, -1.0);
+        assert_eq!(validator.validate(ClapId::new(3), 0.0), Ok(-1.0));
+
+        validator.unregister(ClapId::new(1));
+        assert_eq!(
+            validator.validate(ClapId::new(1), 0.0),
+            Err(ParamValidationError::UnknownParam(Some(ClapId::new(1))))
+        );
+    }
+}
@@ -27,12 +27,10 @@ bitflags! {
 
 /// Plugin-side of the POSIX File Descriptors extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginPosixFd(RawExtension<PluginExtensionSide, clap_plugin_posix_fd_support>);
 
 /// Plugin-side of the POSIX File Descriptors extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostPosixFd(RawExtension<HostExtensionSide, clap_host_posix_fd_support>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -46,6 +44,17 @@ unsafe impl Extension for PluginPosixFd {
     }
 }
 
+impl PluginPosixFd {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_posix_fd_support> {
+        self.0
+    }
+}
+
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
 unsafe impl Extension for HostPosixFd {
     const IDENTIFIER: &'static CStr = CLAP_EXT_POSIX_FD_SUPPORT;
@@ -57,6 +66,17 @@ unsafe impl Extension for HostPosixFd {
     }
 }
 
+impl HostPosixFd {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_posix_fd_support> {
+        self.0
+    }
+}
+
 /// Errors that can occur with the POSIX File Descriptors extension.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum FdError {
@@ -96,6 +116,8 @@ mod host {
         /// Descriptor will continuously produce "on_fd()" events.
         #[inline]
         pub fn on_fd(&self, plugin: &mut PluginMainThreadHandle, fd: RawFd, flags: FdFlags) {
+            crate::utils::trace_call!(plugin, "clap_plugin_posix_fd_support.on_fd");
+
             if let Some(on_fd) = plugin.use_extension(&self.0).on_fd {
                 // SAFETY: This type ensures the function pointer is valid.
                 unsafe { on_fd(plugin.as_raw(), fd, flags.bits()) }
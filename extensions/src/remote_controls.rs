@@ -0,0 +1,15 @@
+//! Allows plugins to expose pages of parameters to be mapped on hardware controllers.
+//!
+//! Each page holds up to eight parameters, which hosts map to the knobs of the user's controller.
+//! Plugins can build their pages declaratively using a [`RemoteControlsPagesBuilder`], which
+//! validates the parameter IDs of each page and splits them into pages of eight.
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v2`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v2;
+
+pub use v2::*;
@@ -0,0 +1,387 @@
+//! Revision 2 of the Remote Controls draft extension (`clap.remote-controls.draft/2`).
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clack_common::utils::ClapId;
+use clap_sys::ext::draft::remote_controls::*;
+use clap_sys::string_sizes::CLAP_NAME_SIZE;
+use std::ffi::CStr;
+
+mod pages;
+pub use pages::*;
+
+/// The number of parameters in a single remote controls page.
+pub const REMOTE_CONTROLS_COUNT: usize = CLAP_REMOTE_CONTROLS_COUNT;
+
+/// The Plugin-side of the Remote Controls extension.
+#[derive(Copy, Clone)]
+pub struct PluginRemoteControls(RawExtension<PluginExtensionSide, clap_plugin_remote_controls>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginRemoteControls {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_REMOTE_CONTROLS;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginRemoteControls {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_remote_controls> {
+        self.0
+    }
+}
+
+/// The Host-side of the Remote Controls extension.
+#[derive(Copy, Clone)]
+pub struct HostRemoteControls(RawExtension<HostExtensionSide, clap_host_remote_controls>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostRemoteControls {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_REMOTE_CONTROLS;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl HostRemoteControls {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_remote_controls> {
+        self.0
+    }
+}
+
+/// A page of up to eight parameters, to be mapped on a hardware controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RemoteControlsPage<'a> {
+    /// The name of the section this page belongs to, used by hosts to group pages together.
+    pub section_name: &'a [u8],
+    /// The unique, stable ID of this page.
+    pub page_id: ClapId,
+    /// The user-facing name of this page.
+    pub page_name: &'a [u8],
+    /// The IDs of the parameters of each slot of this page, or `None` for empty slots.
+    pub param_ids: [Option<ClapId>; REMOTE_CONTROLS_COUNT],
+    /// Whether this page is specific to the currently loaded preset.
+    pub is_for_preset: bool,
+}
+
+impl<'a> RemoteControlsPage<'a> {
+    /// Creates a new [`RemoteControlsPage`] from a reference to the given raw, C-FFI compatible
+    /// page.
+    ///
+    /// This returns `None` if the page has an invalid ID.
+    pub fn from_raw(raw: &'a clap_remote_controls_page) -> Option<Self> {
+        Some(Self {
+            section_name: crate::utils::data_from_array_buf(&raw.section_name),
+            page_id: ClapId::from_raw(raw.page_id)?,
+            page_name: crate::utils::data_from_array_buf(&raw.page_name),
+            param_ids: raw.param_ids.map(ClapId::from_raw),
+            is_for_preset: raw.is_for_preset,
+        })
+    }
+
+    /// Creates a new raw, C-FFI compatible page from this [`RemoteControlsPage`].
+    ///
+    /// Names that are too long to fit are truncated.
+    pub fn to_raw(&self) -> clap_remote_controls_page {
+        let mut section_name = [0; CLAP_NAME_SIZE];
+        let mut page_name = [0; CLAP_NAME_SIZE];
+
+        // SAFETY: both names are valid pointers, as they come from &mut references.
+        unsafe {
+            crate::utils::write_to_array_buf(&mut section_name, self.section_name);
+            crate::utils::write_to_array_buf(&mut page_name, self.page_name);
+        }
+
+        clap_remote_controls_page {
+            section_name,
+            page_id: self.page_id.get(),
+            page_name,
+            param_ids: self.param_ids.map(ClapId::optional_to_raw),
+            is_for_preset: self.is_for_preset,
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    /// A host-provided buffer for the plugin to write a remote controls page into.
+    #[derive(Clone)]
+    pub struct RemoteControlsPageBuffer {
+        inner: MaybeUninit<clap_remote_controls_page>,
+    }
+
+    impl Default for RemoteControlsPageBuffer {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl RemoteControlsPageBuffer {
+        /// Creates an uninitialized page buffer.
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                inner: MaybeUninit::zeroed(),
+            }
+        }
+    }
+
+    impl PluginRemoteControls {
+        /// Returns the number of remote controls pages the plugin exposes.
+        pub fn count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            crate::utils::trace_call!(plugin, "clap_plugin_remote_controls.count");
+
+            match plugin.use_extension(&self.0).count {
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(count) => unsafe { count(plugin.as_raw()) },
+                None => 0,
+            }
+        }
+
+        /// Retrieves the remote controls page at the given index.
+        ///
+        /// The plugin gets passed a mutable buffer to write the page into, to avoid any
+        /// unnecessary allocations.
+        pub fn get<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            page_index: u32,
+            buffer: &'b mut RemoteControlsPageBuffer,
+        ) -> Option<RemoteControlsPage<'b>> {
+            crate::utils::trace_call!(plugin, "clap_plugin_remote_controls.get");
+
+            let get = plugin.use_extension(&self.0).get?;
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe { get(plugin.as_raw(), page_index, buffer.inner.as_mut_ptr()) };
+
+            if success {
+                // SAFETY: we just checked the buffer was successfully written to.
+                RemoteControlsPage::from_raw(unsafe { buffer.inner.assume_init_ref() })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Remote Controls extension.
+    pub trait HostRemoteControlsImpl {
+        /// Informs the host that the plugin's remote controls pages have changed, and must be
+        /// read again.
+        fn changed(&mut self);
+
+        /// Suggests the host to display the page with the given ID, e.g. because the user
+        /// interacted with the matching section of the plugin's GUI.
+        fn suggest_page(&mut self, page_id: ClapId);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostRemoteControls
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostRemoteControlsImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_remote_controls {
+                changed: Some(changed::<H>),
+                suggest_page: Some(suggest_page::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostRemoteControlsImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn suggest_page<H: HostHandlers>(host: *const clap_host, page_id: u32)
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostRemoteControlsImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            let page_id = ClapId::from_raw(page_id)
+                .ok_or(HostWrapperError::InvalidParameter("Invalid page ID"))?;
+
+            host.main_thread().as_mut().suggest_page(page_id);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+    use clap_sys::id::CLAP_INVALID_ID;
+
+    const EMPTY_PAGE: clap_remote_controls_page = clap_remote_controls_page {
+        section_name: [0; CLAP_NAME_SIZE],
+        page_id: CLAP_INVALID_ID,
+        page_name: [0; CLAP_NAME_SIZE],
+        param_ids: [CLAP_INVALID_ID; REMOTE_CONTROLS_COUNT],
+        is_for_preset: false,
+    };
+
+    /// A writer for the plugin to write a remote controls page into the host-provided buffer.
+    pub struct RemoteControlsPageWriter<'a> {
+        buf: &'a mut clap_remote_controls_page,
+        is_set: bool,
+    }
+
+    impl RemoteControlsPageWriter<'_> {
+        /// # Safety
+        ///
+        /// The user must ensure the provided pointer is aligned and points to a valid allocation.
+        /// The page it points to doesn't need to hold any meaningful data, but must be
+        /// initialized.
+        #[inline]
+        unsafe fn from_raw(raw: *mut clap_remote_controls_page) -> Self {
+            Self {
+                buf: &mut *raw,
+                is_set: false,
+            }
+        }
+
+        /// Writes the given page.
+        #[inline]
+        pub fn set(&mut self, page: &RemoteControlsPage) {
+            *self.buf = page.to_raw();
+            self.is_set = true;
+        }
+    }
+
+    impl HostRemoteControls {
+        /// Informs the host that the plugin's remote controls pages have changed, and must be
+        /// read again.
+        #[inline]
+        pub fn changed(&self, host: &mut HostMainThreadHandle) {
+            if let Some(changed) = host.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(host.as_raw()) }
+            }
+        }
+
+        /// Suggests the host to display the page with the given ID.
+        #[inline]
+        pub fn suggest_page(&self, host: &mut HostMainThreadHandle, page_id: ClapId) {
+            if let Some(suggest_page) = host.use_extension(&self.0).suggest_page {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { suggest_page(host.as_raw(), page_id.get()) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Remote Controls extension.
+    ///
+    /// Plugins can use a [`RemoteControlsPages`] collection to implement this trait.
+    pub trait PluginRemoteControlsImpl {
+        /// Returns the number of remote controls pages the plugin exposes.
+        fn count(&mut self) -> u32;
+
+        /// Writes the page at the given index into the given writer.
+        ///
+        /// If nothing is written, the host is informed that the page could not be retrieved.
+        fn get(&mut self, page_index: u32, writer: &mut RemoteControlsPageWriter);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginRemoteControls
+    where
+        for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_remote_controls {
+                count: Some(count::<P>),
+                get: Some(get::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn count<P: Plugin>(plugin: *const clap_plugin) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().count())).unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get<P: Plugin>(
+        plugin: *const clap_plugin,
+        page_index: u32,
+        page: *mut clap_remote_controls_page,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginRemoteControlsImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if page.is_null() {
+                return Err(PluginWrapperError::NulPtr("clap_remote_controls_page"));
+            }
+
+            // The host-provided buffer may be uninitialized.
+            page.write(EMPTY_PAGE);
+            let mut writer = RemoteControlsPageWriter::from_raw(page);
+            p.main_thread().as_mut().get(page_index, &mut writer);
+
+            Ok(writer.is_set)
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn page_roundtrips() {
+        let mut param_ids = [None; REMOTE_CONTROLS_COUNT];
+        param_ids[0] = Some(ClapId::new(4));
+        param_ids[3] = Some(ClapId::new(2));
+
+        let page = RemoteControlsPage {
+            section_name: b"Filter",
+            page_id: ClapId::new(1),
+            page_name: b"Cutoff",
+            param_ids,
+            is_for_preset: true,
+        };
+
+        let raw = page.to_raw();
+        assert_eq!(raw.param_ids[1], clap_sys::id::CLAP_INVALID_ID);
+        assert_eq!(RemoteControlsPage::from_raw(&raw), Some(page));
+    }
+}
@@ -0,0 +1,349 @@
+use super::*;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// An owned collection of remote controls pages, as built by a [`RemoteControlsPagesBuilder`].
+///
+/// Plugins can use this collection to implement the plugin-side of the Remote Controls extension,
+/// using [`len`](Self::len) and [`get`](Self::get).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RemoteControlsPages {
+    pages: Vec<OwnedPage>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct OwnedPage {
+    section_name: String,
+    page_id: ClapId,
+    page_name: String,
+    param_ids: [Option<ClapId>; REMOTE_CONTROLS_COUNT],
+    is_for_preset: bool,
+}
+
+impl RemoteControlsPages {
+    /// Returns the number of pages in this collection.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns `true` if this collection has no pages.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Returns the page at the given index, or `None` if it is out of bounds.
+    pub fn get(&self, page_index: usize) -> Option<RemoteControlsPage<'_>> {
+        let page = self.pages.get(page_index)?;
+
+        Some(RemoteControlsPage {
+            section_name: page.section_name.as_bytes(),
+            page_id: page.page_id,
+            page_name: page.page_name.as_bytes(),
+            param_ids: page.param_ids,
+            is_for_preset: page.is_for_preset,
+        })
+    }
+
+    /// Returns an iterator over all the pages of this collection.
+    pub fn iter(&self) -> impl Iterator<Item = RemoteControlsPage<'_>> {
+        (0..self.pages.len()).filter_map(|i| self.get(i))
+    }
+}
+
+/// Errors that can occur when building remote controls pages.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RemoteControlsPagesError {
+    /// A page refers to a parameter that was not declared to the builder.
+    UnknownParam {
+        /// The name of the page.
+        page_name: String,
+        /// The ID of the unknown parameter.
+        param_id: ClapId,
+    },
+    /// A page refers to the same parameter more than once.
+    DuplicateParam {
+        /// The name of the page.
+        page_name: String,
+        /// The ID of the duplicated parameter.
+        param_id: ClapId,
+    },
+}
+
+impl Display for RemoteControlsPagesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteControlsPagesError::UnknownParam {
+                page_name,
+                param_id,
+            } => write!(
+                f,
+                "Remote controls page '{page_name}' refers to unknown parameter {param_id}"
+            ),
+            RemoteControlsPagesError::DuplicateParam {
+                page_name,
+                param_id,
+            } => write!(
+                f,
+                "Remote controls page '{page_name}' refers to parameter {param_id} more than once"
+            ),
+        }
+    }
+}
+
+impl Error for RemoteControlsPagesError {}
+
+#[derive(Clone, Debug)]
+struct PendingPage {
+    section_name: String,
+    page_name: String,
+    param_ids: Vec<ClapId>,
+    is_for_preset: bool,
+}
+
+/// A builder for [`RemoteControlsPages`], which maps a plugin's declared parameters into pages of
+/// eight.
+///
+/// Each page added to the builder belongs to the current [section](Self::section), and can hold
+/// any number of parameters: pages with more than [`REMOTE_CONTROLS_COUNT`] parameters are split
+/// into as many consecutive pages as needed, with a number appended to their name.
+///
+/// The parameter IDs of every page are validated against the declared parameters when
+/// [building](Self::build) the pages. Page IDs are assigned sequentially, in the order the pages
+/// were added: plugins should therefore always build their pages in the same order, to keep page
+/// IDs stable.
+///
+/// # Example
+///
+/// ```
+/// use clack_common::utils::ClapId;
+/// use clack_extensions::remote_controls::RemoteControlsPagesBuilder;
+///
+/// let ids: Vec<ClapId> = (0..12).map(ClapId::new).collect();
+///
+/// let pages = RemoteControlsPagesBuilder::new(ids.iter().copied())
+///     .section("Oscillators")
+///     .page("Oscillators", &ids[..10])
+///     .section("Filter")
+///     .page("Filter", &ids[10..])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(pages.len(), 3);
+/// assert_eq!(pages.get(1).unwrap().page_name, b"Oscillators 2");
+/// assert_eq!(pages.get(2).unwrap().section_name, b"Filter");
+/// ```
+#[derive(Clone, Debug)]
+pub struct RemoteControlsPagesBuilder {
+    declared_params: Vec<ClapId>,
+    section_name: String,
+    pages: Vec<PendingPage>,
+}
+
+impl RemoteControlsPagesBuilder {
+    /// Creates a new builder, which accepts the given declared parameter IDs.
+    pub fn new(declared_params: impl IntoIterator<Item = ClapId>) -> Self {
+        let mut declared_params: Vec<ClapId> = declared_params.into_iter().collect();
+        declared_params.sort_unstable_by_key(|id| id.get());
+        declared_params.dedup();
+
+        Self {
+            declared_params,
+            section_name: String::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Creates a new builder from the given declared parameters, with one section per parameter
+    /// module, each section holding all of the module's parameters.
+    ///
+    /// Parameters are grouped in the order their module first appears, and
+    /// [hidden](crate::params::ParamInfoFlags::IS_HIDDEN) parameters are skipped. More pages can
+    /// still be added to the builder afterward.
+    #[cfg(feature = "params")]
+    pub fn from_param_modules<'a, 'i: 'a>(
+        params: impl IntoIterator<Item = &'a crate::params::ParamInfo<'i>>,
+    ) -> Self {
+        let mut modules: Vec<(&[u8], Vec<ClapId>)> = Vec::new();
+        let mut declared_params = Vec::new();
+
+        for param in params {
+            declared_params.push(param.id);
+
+            if param.flags.is_hidden() {
+                continue;
+            }
+
+            match modules
+                .iter_mut()
+                .find(|(module, _)| *module == param.module)
+            {
+                Some((_, ids)) => ids.push(param.id),
+                None => modules.push((param.module, vec![param.id])),
+            }
+        }
+
+        let mut builder = Self::new(declared_params);
+
+        for (module, ids) in modules {
+            let name = String::from_utf8_lossy(module);
+            builder = builder.section(&name).page(&name, &ids);
+        }
+
+        builder
+    }
+
+    /// Sets the name of the section the following pages belong to.
+    #[inline]
+    pub fn section(mut self, section_name: &str) -> Self {
+        self.section_name = section_name.to_owned();
+        self
+    }
+
+    /// Adds a page with the given name and parameters, which are mapped to the page's slots in
+    /// order.
+    ///
+    /// If there are more than [`REMOTE_CONTROLS_COUNT`] parameters, the page is split.
+    #[inline]
+    pub fn page(self, page_name: &str, param_ids: &[ClapId]) -> Self {
+        self.push_page(page_name, param_ids, false)
+    }
+
+    /// Adds a page with the given name and parameters, which is specific to the currently loaded
+    /// preset.
+    ///
+    /// See [`page`](Self::page).
+    #[inline]
+    pub fn preset_page(self, page_name: &str, param_ids: &[ClapId]) -> Self {
+        self.push_page(page_name, param_ids, true)
+    }
+
+    fn push_page(mut self, page_name: &str, param_ids: &[ClapId], is_for_preset: bool) -> Self {
+        self.pages.push(PendingPage {
+            section_name: self.section_name.clone(),
+            page_name: page_name.to_owned(),
+            param_ids: param_ids.to_vec(),
+            is_for_preset,
+        });
+
+        self
+    }
+
+    /// Validates the parameters of every page, and builds the pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RemoteControlsPagesError::UnknownParam`] if a page refers to an undeclared
+    /// parameter, or [`RemoteControlsPagesError::DuplicateParam`] if a page refers to the same
+    /// parameter more than once.
+    pub fn build(self) -> Result<RemoteControlsPages, RemoteControlsPagesError> {
+        let mut pages = Vec::new();
+
+        for page in self.pages {
+            for (index, param_id) in page.param_ids.iter().enumerate() {
+                if self
+                    .declared_params
+                    .binary_search_by_key(&param_id.get(), |id| id.get())
+                    .is_err()
+                {
+                    return Err(RemoteControlsPagesError::UnknownParam {
+                        page_name: page.page_name,
+                        param_id: *param_id,
+                    });
+                }
+
+                if page.param_ids[..index].contains(param_id) {
+                    return Err(RemoteControlsPagesError::DuplicateParam {
+                        page_name: page.page_name,
+                        param_id: *param_id,
+                    });
+                }
+            }
+
+            // Pages without any parameters are kept, as a single empty page.
+            let page_count =
+                ((page.param_ids.len() + REMOTE_CONTROLS_COUNT - 1) / REMOTE_CONTROLS_COUNT).max(1);
+
+            for chunk_index in 0..page_count {
+                let start = chunk_index * REMOTE_CONTROLS_COUNT;
+                let end = (start + REMOTE_CONTROLS_COUNT).min(page.param_ids.len());
+                let chunk = &page.param_ids[start..end];
+
+                let mut param_ids = [None; REMOTE_CONTROLS_COUNT];
+                for (slot, id) in param_ids.iter_mut().zip(chunk) {
+                    *slot = Some(*id);
+                }
+
+                let page_name = if chunk_index > 0 {
+                    format!("{} {}", page.page_name, chunk_index + 1)
+                } else {
+                    page.page_name.clone()
+                };
+
+                pages.push(OwnedPage {
+                    section_name: page.section_name.clone(),
+                    page_id: ClapId::new(pages.len() as u32),
+                    page_name,
+                    param_ids,
+                    is_for_preset: page.is_for_preset,
+                });
+            }
+        }
+
+        Ok(RemoteControlsPages { pages })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validates_param_ids() {
+        let builder = RemoteControlsPagesBuilder::new([ClapId::new(1), ClapId::new(2)]);
+
+        assert_eq!(
+            builder
+                .clone()
+                .page("Main", &[ClapId::new(1), ClapId::new(3)])
+                .build(),
+            Err(RemoteControlsPagesError::UnknownParam {
+                page_name: "Main".into(),
+                param_id: ClapId::new(3)
+            })
+        );
+
+        assert_eq!(
+            builder
+                .clone()
+                .page("Main", &[ClapId::new(1), ClapId::new(1)])
+                .build(),
+            Err(RemoteControlsPagesError::DuplicateParam {
+                page_name: "Main".into(),
+                param_id: ClapId::new(1)
+            })
+        );
+
+        let pages = builder
+            .section("Macros")
+            .preset_page("Main", &[ClapId::new(2)])
+            .page("Empty", &[])
+            .build()
+            .unwrap();
+
+        let page = pages.get(0).unwrap();
+        assert_eq!(page.page_id, ClapId::new(0));
+        assert_eq!(page.section_name, b"Macros");
+        assert!(page.is_for_preset);
+        assert_eq!(page.param_ids[0], Some(ClapId::new(2)));
+        assert_eq!(page.param_ids[1], None);
+
+        // Empty pages are kept as-is.
+        assert_eq!(pages.len(), 2);
+        assert_eq!(
+            pages.get(1).unwrap().param_ids,
+            [None; REMOTE_CONTROLS_COUNT]
+        );
+    }
+}
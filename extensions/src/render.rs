@@ -8,14 +8,15 @@
 //! If this information does not influence your rendering code, your plugin should **NOT**
 //! implement this extension.
 
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
 use clap_sys::ext::render::*;
+use std::error::Error;
 use std::ffi::CStr;
 use std::fmt::{Display, Formatter};
 
 /// The Plugin-side of the Render extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginRender(RawExtension<PluginExtensionSide, clap_plugin_render>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -29,6 +30,17 @@ unsafe impl Extension for PluginRender {
     }
 }
 
+impl PluginRender {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_render> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
 #[repr(i32)]
 /// The different modes of rendering a plugin may be subjected to.
@@ -65,14 +77,32 @@ impl RenderMode {
 
 /// An errors that occurs when the plugin either declined or failed to switch to a new render mode.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
-pub struct PluginRenderError;
+pub struct PluginRenderError {
+    failure: CallFailure,
+}
+
+impl PluginRenderError {
+    /// Creates a new error with the given [`CallFailure`] reason.
+    #[inline]
+    pub const fn new(failure: CallFailure) -> Self {
+        Self { failure }
+    }
+
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        self.failure
+    }
+}
 
 impl Display for PluginRenderError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Failed to set plugin's render mode.")
+        write!(f, "Failed to set plugin's render mode: {}", self.failure)
     }
 }
 
+impl Error for PluginRenderError {}
+
 #[cfg(feature = "clack-plugin")]
 mod plugin {
     use super::*;
@@ -157,6 +187,8 @@ mod host {
         /// other real-time events.
         #[inline]
         pub fn has_realtime_requirement(&self, plugin: &mut PluginMainThreadHandle) -> bool {
+            crate::utils::trace_call!(plugin, "clap_plugin_render.has_hard_realtime_requirement");
+
             if let Some(has_hard_realtime_requirement) =
                 plugin.use_extension(&self.0).has_hard_realtime_requirement
             {
@@ -178,9 +210,14 @@ mod host {
             plugin: &mut PluginMainThreadHandle,
             render_mode: RenderMode,
         ) -> Result<(), PluginRenderError> {
+            crate::utils::trace_call!(plugin, "clap_plugin_render.set");
+
             // SAFETY: This type ensures the function pointer is valid.
             let success = unsafe {
-                plugin.use_extension(&self.0).set.ok_or(PluginRenderError)?(
+                plugin
+                    .use_extension(&self.0)
+                    .set
+                    .ok_or(PluginRenderError::new(CallFailure::Unsupported))?(
                     plugin.as_raw(),
                     render_mode.as_raw(),
                 )
@@ -188,7 +225,7 @@ mod host {
 
             match success {
                 true => Ok(()),
-                false => Err(PluginRenderError),
+                false => Err(PluginRenderError::new(CallFailure::Declined)),
             }
         }
     }
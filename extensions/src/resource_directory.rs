@@ -0,0 +1,18 @@
+//! Allows plugins to store the files they depend on (e.g. samples or impulse responses) in a
+//! directory provided by the host, so that they travel along with the project.
+//!
+//! Plugins ask the host for a directory using [`HostResourceDirectory::request_directory`], and
+//! the host then provides it with [`PluginResourceDirectory::set_directory`]. Hosts can use the
+//! [`ResourceDirectories`] helper to provision one private directory per plugin instance under
+//! the project's folder, keep them apart when instances are duplicated, and clean them up when
+//! instances are removed.
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v0`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v0;
+
+pub use v0::*;
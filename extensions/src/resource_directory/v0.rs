@@ -0,0 +1,390 @@
+//! Revision 0 of the Resource Directory draft extension (`clap.resource-directory.draft/0`).
+
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::resource_directory::*;
+use std::ffi::CStr;
+
+mod directories;
+pub use directories::*;
+
+/// The Plugin-side of the Resource Directory extension.
+#[derive(Copy, Clone)]
+pub struct PluginResourceDirectory(
+    RawExtension<PluginExtensionSide, clap_plugin_resource_directory>,
+);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginResourceDirectory {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_RESOURCE_DIRECTORY;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginResourceDirectory {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_resource_directory> {
+        self.0
+    }
+}
+
+/// The Host-side of the Resource Directory extension.
+#[derive(Copy, Clone)]
+pub struct HostResourceDirectory(RawExtension<HostExtensionSide, clap_host_resource_directory>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostResourceDirectory {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_RESOURCE_DIRECTORY;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl HostResourceDirectory {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_resource_directory> {
+        self.0
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginResourceDirectory {
+        /// Sets the directory in which the plugin can store its resources, or clears it if
+        /// `directory` is `None`.
+        ///
+        /// If `is_shared` is `true`, the directory is shared with all the other instances of the
+        /// plugin's bundle in the project. Otherwise, it is private to this plugin instance.
+        ///
+        /// The directory must remain valid until it is overridden or cleared, or until the plugin
+        /// is destroyed. See [`c_path`] to convert a [`Path`](std::path::Path) into a C string.
+        pub fn set_directory(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            directory: Option<&CStr>,
+            is_shared: bool,
+        ) {
+            crate::utils::trace_call!(plugin, "clap_plugin_resource_directory.set_directory");
+
+            if let Some(set_directory) = plugin.use_extension(&self.0).set_directory {
+                let directory = directory.map_or(core::ptr::null(), CStr::as_ptr);
+
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { set_directory(plugin.as_raw(), directory, is_shared) }
+            }
+        }
+
+        /// Asks the plugin to copy all the files it depends on into its resource directories.
+        ///
+        /// If `all` is `false`, the plugin may skip the files that are part of its factory
+        /// content, i.e. those shipped with the plugin itself.
+        pub fn collect(&self, plugin: &mut PluginMainThreadHandle, all: bool) {
+            crate::utils::trace_call!(plugin, "clap_plugin_resource_directory.collect");
+
+            if let Some(collect) = plugin.use_extension(&self.0).collect {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { collect(plugin.as_raw(), all) }
+            }
+        }
+
+        /// Returns the number of files the plugin currently uses.
+        pub fn files_count(&self, plugin: &mut PluginMainThreadHandle) -> u32 {
+            crate::utils::trace_call!(plugin, "clap_plugin_resource_directory.get_files_count");
+
+            match plugin.use_extension(&self.0).get_files_count {
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(count) => unsafe { count(plugin.as_raw()) },
+                None => 0,
+            }
+        }
+
+        /// Retrieves the path of the file at the given index, writing it into the given buffer.
+        ///
+        /// This returns `None` if the plugin failed to provide the path, or if the given buffer
+        /// was too small to hold it.
+        pub fn get_file_path<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            index: u32,
+            buffer: &'b mut [u8],
+        ) -> Option<&'b CStr> {
+            crate::utils::trace_call!(plugin, "clap_plugin_resource_directory.get_file_path");
+
+            let get_file_path = plugin.use_extension(&self.0).get_file_path?;
+
+            let capacity = buffer.len().min(u32::MAX as usize);
+            if capacity == 0 {
+                return None;
+            }
+
+            // SAFETY: This type ensures the function pointer is valid. The buffer is valid for
+            // `capacity` writes.
+            let len = unsafe {
+                get_file_path(
+                    plugin.as_raw(),
+                    index,
+                    buffer.as_mut_ptr().cast(),
+                    capacity as u32,
+                )
+            };
+
+            let len = usize::try_from(len).ok().filter(|len| *len < capacity)?;
+            CStr::from_bytes_until_nul(&buffer[..=len]).ok()
+        }
+    }
+
+    /// Implementation of the Host-side of the Resource Directory extension.
+    pub trait HostResourceDirectoryImpl {
+        /// Requests the host to set up a resource directory for the plugin, which is then given
+        /// to it using [`PluginResourceDirectory::set_directory`].
+        ///
+        /// If `is_shared` is `true`, the requested directory is shared with all the other
+        /// instances of the plugin's bundle. Otherwise, it is private to this plugin instance.
+        ///
+        /// Returns `true` if the host will set up the requested directory.
+        fn request_directory(&mut self, is_shared: bool) -> bool;
+
+        /// Informs the host that the plugin doesn't need the given resource directory anymore.
+        ///
+        /// If the directory is private to the plugin instance (i.e. `is_shared` is `false`), the
+        /// host may delete its contents.
+        fn release_directory(&mut self, is_shared: bool);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostResourceDirectory
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostResourceDirectoryImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_resource_directory {
+                request_directory: Some(request_directory::<H>),
+                release_directory: Some(release_directory::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn request_directory<H: HostHandlers>(
+        host: *const clap_host,
+        is_shared: bool,
+    ) -> bool
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostResourceDirectoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            Ok(host.main_thread().as_mut().request_directory(is_shared))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn release_directory<H: HostHandlers>(host: *const clap_host, is_shared: bool)
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostResourceDirectoryImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().release_directory(is_shared);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    /// A writer for the plugin to write a file path into the host-provided buffer.
+    pub struct ResourcePathWriter<'a> {
+        buf: &'a mut [u8],
+        len: Option<usize>,
+    }
+
+    impl ResourcePathWriter<'_> {
+        /// Returns how many bytes the host-provided buffer can hold, including the nul
+        /// terminator.
+        #[inline]
+        pub fn capacity(&self) -> usize {
+            self.buf.len()
+        }
+
+        /// Writes the given file path.
+        ///
+        /// If the path doesn't fit in the host-provided buffer, it is truncated, and the host
+        /// is informed of its full length so that it can retry with a larger buffer.
+        pub fn set(&mut self, path: &CStr) {
+            let bytes = path.to_bytes();
+
+            if let Some(max_len) = self.buf.len().checked_sub(1) {
+                let len = bytes.len().min(max_len);
+                self.buf[..len].copy_from_slice(&bytes[..len]);
+                self.buf[len] = 0;
+            }
+
+            self.len = Some(bytes.len());
+        }
+    }
+
+    impl HostResourceDirectory {
+        /// Requests the host to set up a resource directory for the plugin.
+        ///
+        /// If `is_shared` is `true`, the requested directory is shared with all the other
+        /// instances of the plugin's bundle. Otherwise, it is private to this plugin instance.
+        ///
+        /// Returns `true` if the host will provide the requested directory, through
+        /// [`PluginResourceDirectoryImpl::set_directory`].
+        pub fn request_directory(&self, host: &mut HostMainThreadHandle, is_shared: bool) -> bool {
+            match host.use_extension(&self.0).request_directory {
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(request_directory) => unsafe { request_directory(host.as_raw(), is_shared) },
+                None => false,
+            }
+        }
+
+        /// Informs the host that the plugin doesn't need the given resource directory anymore.
+        ///
+        /// If the directory is private to the plugin instance (i.e. `is_shared` is `false`), the
+        /// host may delete its contents.
+        pub fn release_directory(&self, host: &mut HostMainThreadHandle, is_shared: bool) {
+            if let Some(release_directory) = host.use_extension(&self.0).release_directory {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { release_directory(host.as_raw(), is_shared) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Resource Directory extension.
+    pub trait PluginResourceDirectoryImpl {
+        /// Sets the directory in which the plugin can store its resources, or clears it if
+        /// `directory` is `None`.
+        ///
+        /// If `is_shared` is `true`, the directory is shared with all the other instances of the
+        /// plugin's bundle. Otherwise, it is private to this plugin instance.
+        ///
+        /// The directory remains valid until it is overridden or cleared, or until the plugin is
+        /// destroyed.
+        fn set_directory(&mut self, directory: Option<&CStr>, is_shared: bool);
+
+        /// Copies all the files the plugin depends on into its resource directories.
+        ///
+        /// If `all` is `false`, the files that are part of the plugin's factory content may be
+        /// skipped.
+        fn collect(&mut self, all: bool);
+
+        /// Returns the number of files the plugin currently uses.
+        fn files_count(&mut self) -> u32;
+
+        /// Writes the path of the file at the given index into the given writer.
+        ///
+        /// If nothing is written, the host is informed that the path could not be retrieved.
+        fn get_file_path(&mut self, index: u32, writer: &mut ResourcePathWriter);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginResourceDirectory
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_resource_directory {
+                set_directory: Some(set_directory::<P>),
+                collect: Some(collect::<P>),
+                get_files_count: Some(get_files_count::<P>),
+                get_file_path: Some(get_file_path::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn set_directory<P: Plugin>(
+        plugin: *const clap_plugin,
+        path: *const core::ffi::c_char,
+        is_shared: bool,
+    ) where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            // A blank path also clears the directory.
+            let directory = Some(path)
+                .filter(|path| !path.is_null())
+                .map(|path| CStr::from_ptr(path))
+                .filter(|path| !path.is_empty());
+
+            p.main_thread().as_mut().set_directory(directory, is_shared);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn collect<P: Plugin>(plugin: *const clap_plugin, all: bool)
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            p.main_thread().as_mut().collect(all);
+            Ok(())
+        });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_files_count<P: Plugin>(plugin: *const clap_plugin) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| Ok(p.main_thread().as_mut().files_count()))
+            .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_file_path<P: Plugin>(
+        plugin: *const clap_plugin,
+        index: u32,
+        path: *mut core::ffi::c_char,
+        path_size: u32,
+    ) -> i32
+    where
+        for<'a> P::MainThread<'a>: PluginResourceDirectoryImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if path.is_null() && path_size > 0 {
+                return Err(PluginWrapperError::NulPtr("path"));
+            }
+
+            let buf =
+                crate::utils::slice_from_external_parts_mut(path.cast::<u8>(), path_size as usize);
+
+            let mut writer = ResourcePathWriter { buf, len: None };
+            p.main_thread().as_mut().get_file_path(index, &mut writer);
+
+            Ok(match writer.len {
+                Some(len) => i32::try_from(len).unwrap_or(i32::MAX),
+                None => -1,
+            })
+        })
+        .unwrap_or(-1)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
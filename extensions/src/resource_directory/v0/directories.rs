@@ -0,0 +1,459 @@
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The name of the directory holding the private directories of each plugin instance.
+const INSTANCES_DIR: &str = "instances";
+/// The name of the directory holding the directories shared by all instances of a plugin.
+const SHARED_DIR: &str = "shared";
+
+/// A host-side helper provisioning the resource directories of plugin instances under a
+/// project's folder.
+///
+/// Each plugin instance gets its own private directory, identified by an instance ID chosen by
+/// the host (e.g. the ID of the track slot it is in), and all instances of the same plugin also
+/// share a directory identified by the plugin's ID:
+///
+/// ```text
+/// <root>/instances/<instance ID>/
+/// <root>/shared/<plugin ID>/
+/// ```
+///
+/// Instance and plugin IDs are used as directory names as-is: they must not be empty, must not
+/// be `.` or `..`, and must not contain path separators or nul bytes. Otherwise, the methods of
+/// this type return an [`InvalidInput`](io::ErrorKind::InvalidInput) error.
+///
+/// When an instance is duplicated, [`duplicate`](Self::duplicate) gives the copy its own
+/// directory holding a copy of the original's resources, picking a new instance ID if needed so
+/// that both instances never share the same private directory. IDs picked this way (e.g.
+/// `track-1-2`) may collide with the IDs the host chooses itself: hosts that use
+/// [`provision_unique`](Self::provision_unique) or [`duplicate`](Self::duplicate) must therefore
+/// only give [`provision`](Self::provision) IDs that were previously returned by those methods. When an instance is removed,
+/// [`remove`](Self::remove) deletes its directory, and [`retain`](Self::retain) cleans up
+/// the directories of instances that no longer exist.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::resource_directory::{c_path, ResourceDirectories};
+///
+/// # let root = std::env::temp_dir().join(format!("clack-doc-resources-{}", std::process::id()));
+/// let directories = ResourceDirectories::new(root.join("resources"));
+///
+/// // When the plugin requests its private directory.
+/// let directory = directories.provision("track-1")?;
+/// let directory = c_path(&directory).unwrap();
+/// // resource_directory.set_directory(&mut plugin, Some(&directory), false);
+///
+/// // When the user duplicates the track, the copy gets its own directory.
+/// let (copy_id, copy_directory) = directories.duplicate("track-1", "track-1")?;
+/// assert_eq!(copy_id, "track-1-2");
+/// # assert!(copy_directory.is_dir());
+///
+/// // When the original track is deleted.
+/// directories.remove("track-1")?;
+/// # std::fs::remove_dir_all(&root)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ResourceDirectories {
+    root: PathBuf,
+}
+
+impl ResourceDirectories {
+    /// Creates a helper provisioning resource directories under the given root directory,
+    /// typically a subdirectory of the project's folder.
+    ///
+    /// Nothing is created on disk until a directory is actually provisioned.
+    #[inline]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the root directory all resource directories are provisioned under.
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path of the private directory of the given plugin instance, without creating
+    /// it.
+    pub fn instance_directory(&self, instance_id: &str) -> io::Result<PathBuf> {
+        Ok(self.root.join(INSTANCES_DIR).join(validate(instance_id)?))
+    }
+
+    /// Returns the path of the directory shared by all instances of the given plugin, without
+    /// creating it.
+    pub fn shared_directory(&self, plugin_id: &str) -> io::Result<PathBuf> {
+        Ok(self.root.join(SHARED_DIR).join(validate(plugin_id)?))
+    }
+
+    /// Creates the private directory of the given plugin instance if it doesn't exist yet, and
+    /// returns its path.
+    ///
+    /// If the directory already exists, it is returned as-is, even if it belongs to another
+    /// instance. When mixing this method with [`provision_unique`](Self::provision_unique) or
+    /// [`duplicate`](Self::duplicate), only pass it IDs those methods returned.
+    pub fn provision(&self, instance_id: &str) -> io::Result<PathBuf> {
+        let directory = self.instance_directory(instance_id)?;
+        fs::create_dir_all(&directory)?;
+        Ok(directory)
+    }
+
+    /// Creates the directory shared by all instances of the given plugin if it doesn't exist
+    /// yet, and returns its path.
+    pub fn provision_shared(&self, plugin_id: &str) -> io::Result<PathBuf> {
+        let directory = self.shared_directory(plugin_id)?;
+        fs::create_dir_all(&directory)?;
+        Ok(directory)
+    }
+
+    /// Creates a new, empty private directory for a plugin instance, and returns its instance
+    /// ID alongside its path.
+    ///
+    /// The given ID is used if no directory exists for it yet. Otherwise, a numbered suffix is
+    /// appended to it (e.g. `track-1-2`), until an unused ID is found.
+    pub fn provision_unique(&self, base_id: &str) -> io::Result<(String, PathBuf)> {
+        validate(base_id)?;
+
+        let instances = self.root.join(INSTANCES_DIR);
+        fs::create_dir_all(&instances)?;
+
+        for suffix in 1u32.. {
+            let instance_id = match suffix {
+                1 => base_id.to_owned(),
+                suffix => format!("{base_id}-{suffix}"),
+            };
+
+            let directory = instances.join(&instance_id);
+
+            // Creating the directory itself (rather than checking for its existence first)
+            // ensures two instances can never claim the same ID.
+            match fs::create_dir(&directory) {
+                Ok(()) => return Ok((instance_id, directory)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "No unused plugin instance ID is left",
+        ))
+    }
+
+    /// Provisions the private directory of a copy of the given plugin instance, holding a copy
+    /// of all of the original's resources, and returns the copy's instance ID alongside its path.
+    ///
+    /// The copy's ID is picked from the given `base_id`, in the same way as
+    /// [`provision_unique`](Self::provision_unique). If the original instance doesn't have a
+    /// directory, the copy's directory is left empty.
+    ///
+    /// Symbolic links are copied as links rather than followed, so that the copy never holds
+    /// files from outside of the original's directory. On non-Unix platforms, where creating links
+    /// usually requires elevated privileges, they are skipped instead.
+    ///
+    /// If copying fails, the copy's directory is removed.
+    pub fn duplicate(&self, source_id: &str, base_id: &str) -> io::Result<(String, PathBuf)> {
+        let source = self.instance_directory(source_id)?;
+        let (instance_id, directory) = self.provision_unique(base_id)?;
+
+        if source.is_dir() {
+            if let Err(e) = copy_contents(&source, &directory) {
+                let _ = fs::remove_dir_all(&directory);
+                return Err(e);
+            }
+        }
+
+        Ok((instance_id, directory))
+    }
+
+    /// Moves the private directory of the given plugin instance into another set of resource
+    /// directories (e.g. when the project is saved to a new folder), keeping the same instance
+    /// ID, and returns its new path.
+    ///
+    /// If a non-empty directory already exists for this instance at the destination, this
+    /// returns an [`AlreadyExists`](io::ErrorKind::AlreadyExists) error. If the instance doesn't
+    /// have a directory, an empty one is provisioned at the destination.
+    pub fn migrate(&self, instance_id: &str, destination: &Self) -> io::Result<PathBuf> {
+        let source = self.instance_directory(instance_id)?;
+        let target = destination.instance_directory(instance_id)?;
+
+        if source == target {
+            return destination.provision(instance_id);
+        }
+
+        if fs::read_dir(&target).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "Destination resource directory is not empty",
+            ));
+        }
+
+        if !source.is_dir() {
+            return destination.provision(instance_id);
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _ = fs::remove_dir(&target);
+
+        // Renaming fails across file systems, in which case the contents are copied instead.
+        if fs::rename(&source, &target).is_err() {
+            fs::create_dir_all(&target)?;
+            copy_contents(&source, &target)?;
+            fs::remove_dir_all(&source)?;
+        }
+
+        Ok(target)
+    }
+
+    /// Deletes the contents of the private directory of the given plugin instance, but keeps
+    /// the directory itself.
+    ///
+    /// This is typically done when the plugin releases its private directory.
+    pub fn clear(&self, instance_id: &str) -> io::Result<()> {
+        let directory = self.instance_directory(instance_id)?;
+
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the private directory of the given plugin instance, and all of its contents.
+    ///
+    /// This does nothing if the instance doesn't have a directory.
+    pub fn remove(&self, instance_id: &str) -> io::Result<()> {
+        match fs::remove_dir_all(self.instance_directory(instance_id)?) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the IDs of all the plugin instances that have a private directory, sorted.
+    pub fn instances(&self) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(self.root.join(INSTANCES_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut instances = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+
+            if let (true, Ok(instance_id)) =
+                (entry.file_type()?.is_dir(), entry.file_name().into_string())
+            {
+                instances.push(instance_id);
+            }
+        }
+
+        instances.sort();
+        Ok(instances)
+    }
+
+    /// Deletes the private directories of all the plugin instances for which the given
+    /// predicate returns `false`, e.g. instances that were removed from the project, and returns
+    /// their IDs.
+    pub fn retain(&self, mut keep: impl FnMut(&str) -> bool) -> io::Result<Vec<String>> {
+        let mut removed = self.instances()?;
+        removed.retain(|instance_id| !keep(instance_id));
+
+        for instance_id in &removed {
+            self.remove(instance_id)?;
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Converts a path into a C string, e.g. to pass it to
+/// [`PluginResourceDirectory::set_directory`](super::PluginResourceDirectory::set_directory).
+///
+/// This returns `None` if the path contains a nul byte. On non-Unix platforms, this also returns
+/// `None` if the path is not valid Unicode.
+pub fn c_path(path: &Path) -> Option<CString> {
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()).to_vec();
+    #[cfg(not(unix))]
+    let bytes = path.to_str()?.as_bytes().to_vec();
+
+    CString::new(bytes).ok()
+}
+
+fn validate(id: &str) -> io::Result<&str> {
+    let is_valid = !matches!(id, "" | "." | "..")
+        && !id.contains(['/', '\\', '\0'])
+        && Path::new(id).components().count() == 1;
+
+    if is_valid {
+        Ok(id)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Invalid resource directory ID",
+        ))
+    }
+}
+
+fn copy_contents(source: &Path, destination: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            fs::create_dir(&target)?;
+            copy_contents(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(_source: &Path, _destination: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("clack-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn duplicates_and_removes_instances() {
+        let root = temp_dir("resource-directories");
+        let directories = ResourceDirectories::new(&root);
+
+        let original = directories.provision("track-1").unwrap();
+        fs::create_dir(original.join("samples")).unwrap();
+        fs::write(original.join("samples/kick.wav"), b"kick").unwrap();
+
+        let (copy_id, copy) = directories.duplicate("track-1", "track-1").unwrap();
+        assert_eq!(copy_id, "track-1-2");
+        assert_eq!(fs::read(copy.join("samples/kick.wav")).unwrap(), b"kick");
+
+        // The copy's resources are its own.
+        fs::write(copy.join("samples/kick.wav"), b"snare").unwrap();
+        assert_eq!(
+            fs::read(original.join("samples/kick.wav")).unwrap(),
+            b"kick"
+        );
+
+        let (other_id, _) = directories.provision_unique("track-1").unwrap();
+        assert_eq!(other_id, "track-1-3");
+        assert_eq!(
+            directories.instances().unwrap(),
+            ["track-1", "track-1-2", "track-1-3"]
+        );
+
+        directories.clear("track-1-2").unwrap();
+        assert!(copy.is_dir());
+        assert!(!copy.join("samples").exists());
+
+        directories.remove("track-1").unwrap();
+        directories.remove("track-1").unwrap();
+        assert!(!original.exists());
+
+        let removed = directories.retain(|id| id == "track-1-3").unwrap();
+        assert_eq!(removed, ["track-1-2"]);
+        assert_eq!(directories.instances().unwrap(), ["track-1-3"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn copies_symlinks_without_following_them() {
+        let root = temp_dir("resource-symlinks");
+        let directories = ResourceDirectories::new(&root);
+
+        let outside = root.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret"), b"secret").unwrap();
+
+        let original = directories.provision("track-1").unwrap();
+        std::os::unix::fs::symlink(&outside, original.join("link")).unwrap();
+
+        let (_, copy) = directories.duplicate("track-1", "track-1").unwrap();
+        let link = copy.join("link");
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), outside);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn migrates_instances() {
+        let root = temp_dir("resource-migration");
+        let old = ResourceDirectories::new(root.join("old"));
+        let new = ResourceDirectories::new(root.join("new"));
+
+        fs::write(old.provision("synth").unwrap().join("patch"), b"patch").unwrap();
+
+        let migrated = old.migrate("synth", &new).unwrap();
+        assert_eq!(migrated, new.instance_directory("synth").unwrap());
+        assert_eq!(fs::read(migrated.join("patch")).unwrap(), b"patch");
+        assert!(old.instances().unwrap().is_empty());
+
+        fs::write(old.provision("synth").unwrap().join("patch"), b"other").unwrap();
+        let error = old.migrate("synth", &new).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn rejects_invalid_ids() {
+        let directories = ResourceDirectories::new("/project/resources");
+
+        for id in ["", ".", "..", "a/b", "a\\b", "a\0b"] {
+            let error = directories.instance_directory(id).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        assert_eq!(
+            directories.shared_directory("com.example.synth").unwrap(),
+            Path::new("/project/resources/shared/com.example.synth")
+        );
+        assert_eq!(
+            c_path(Path::new("/project")).unwrap().to_bytes(),
+            b"/project"
+        );
+    }
+}
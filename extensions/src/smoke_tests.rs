@@ -0,0 +1,704 @@
+//! Roundtrip smoke tests for the extension wrappers.
+//!
+//! These tests statically link a host ([`SmokeHost`]) and a plugin ([`SmokePlugin`]) which both
+//! implement every extension enabled in the current build, and call each extension across the FFI
+//! boundary in both directions, checking that the data and notifications make it to the other
+//! side intact.
+//!
+//! Calls are recorded into a per-thread log using [`record`], and checked using [`take_calls`].
+//! Plugin-to-host calls are run from the plugin's main thread callback or from its audio
+//! processor, using [`on_plugin_main_thread`] and [`on_plugin_audio_thread`] respectively.
+//!
+//! The `gui`, `params` and `audio-ports-config` extensions need more elaborate setups, and are
+//! covered by the integration tests of the `clack-host` crate instead.
+
+mod host;
+mod plugin;
+
+use clack_host::prelude::*;
+use clack_host::process::StartedPluginAudioProcessor;
+use host::*;
+use plugin::*;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::rc::Rc;
+
+thread_local! {
+    static CALLS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records that an extension method was called, on either side.
+fn record(call: impl Into<String>) {
+    CALLS.with(|c| c.borrow_mut().push(call.into()));
+}
+
+/// Returns all the calls recorded since the last call to this function, and clears the log.
+fn take_calls() -> Vec<String> {
+    CALLS.with(|c| std::mem::take(&mut *c.borrow_mut()))
+}
+
+fn instantiate() -> PluginInstance<SmokeHost> {
+    take_calls();
+
+    let host_info = HostInfo::new("Smoke host", "Clack", "https://example.com", "1.0").unwrap();
+    // SAFETY: we're loading our own, statically linked bundle.
+    let bundle =
+        unsafe { PluginBundle::load_from_raw(&SMOKE_PLUGIN_ENTRY, "/smoke-test") }.unwrap();
+
+    PluginInstance::<SmokeHost>::new(
+        |_| SmokeHostShared,
+        |_| SmokeHostMainThread,
+        &bundle,
+        CStr::from_bytes_with_nul(b"org.rust-audio.clack.smoke-test\0").unwrap(),
+        &host_info,
+    )
+    .unwrap()
+}
+
+#[allow(dead_code)] // Only used by audio-thread extensions
+fn start_processing(
+    instance: &mut PluginInstance<SmokeHost>,
+) -> StartedPluginAudioProcessor<SmokeHost> {
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    instance
+        .activate(|_, _| SmokeHostAudioProcessor, config)
+        .unwrap()
+        .start_processing()
+        .unwrap()
+}
+
+/// Runs the given task on the plugin's main thread, with its host handle, and returns its result.
+fn on_plugin_main_thread<R: 'static>(
+    instance: &mut PluginInstance<SmokeHost>,
+    task: impl FnOnce(&mut clack_plugin::host::HostMainThreadHandle) -> R + 'static,
+) -> R {
+    let result = Rc::new(RefCell::new(None));
+    let task_result = result.clone();
+    schedule_main_thread_task(move |host| *task_result.borrow_mut() = Some(task(host)));
+
+    instance.call_on_main_thread_callback();
+
+    let result = result.borrow_mut().take();
+    result.expect("Plugin did not run the main thread task")
+}
+
+/// Runs the given task in the plugin's audio processor, with its host handle, and returns its
+/// result.
+#[allow(dead_code)] // Only used by audio-thread extensions
+fn on_plugin_audio_thread<R: 'static>(
+    processor: &mut StartedPluginAudioProcessor<SmokeHost>,
+    task: impl FnOnce(&mut clack_plugin::host::HostAudioProcessorHandle) -> R + 'static,
+) -> R {
+    let result = Rc::new(RefCell::new(None));
+    let task_result = result.clone();
+    schedule_audio_thread_task(move |host| *task_result.borrow_mut() = Some(task(host)));
+
+    processor
+        .process(
+            &InputAudioBuffers::empty_with_frames(4),
+            &mut OutputAudioBuffers::empty_with_frames(4),
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    let result = result.borrow_mut().take();
+    result.expect("Plugin did not run the audio thread task")
+}
+
+#[cfg(feature = "ambisonic")]
+mod ambisonic {
+    use super::*;
+    use crate::ambisonic::*;
+
+    #[test]
+    fn ambisonic_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let ambisonic = plugin.get_extension::<PluginAmbisonic>().unwrap();
+
+        assert!(ambisonic.is_config_supported(&mut plugin, AmbisonicConfig::AMBIX));
+        assert!(!ambisonic.is_config_supported(&mut plugin, AmbisonicConfig::FUMA));
+        assert_eq!(
+            ambisonic.get_config(&mut plugin, true, 0),
+            Some(AmbisonicConfig::AMBIX)
+        );
+        assert_eq!(ambisonic.get_config(&mut plugin, false, 0), None);
+
+        on_plugin_main_thread(&mut instance, |host| {
+            host.get_extension::<HostAmbisonic>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.ambisonic.changed"]);
+    }
+}
+
+#[cfg(feature = "ara")]
+mod ara {
+    use super::*;
+    use crate::ara::*;
+    use std::ffi::c_void;
+
+    #[test]
+    fn ara_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let ara = plugin.get_extension::<PluginAraExtension>().unwrap();
+
+        let factory = ara.get_factory(&mut plugin).unwrap();
+        assert_eq!(factory.as_raw(), ARA_FACTORY.as_ptr().cast());
+
+        let mut controller = 0u8;
+        let controller =
+            AraDocumentControllerRef::from_raw((&mut controller as *mut u8).cast::<c_void>())
+                .unwrap();
+        let roles = AraPlugInInstanceRoles::PLAYBACK_RENDERER | AraPlugInInstanceRoles::EDITOR_VIEW;
+
+        // SAFETY: the smoke test plugin never dereferences the document controller.
+        let extension_instance = unsafe {
+            ara.bind_to_document_controller(
+                &mut plugin,
+                controller,
+                AraPlugInInstanceRoles::all(),
+                roles,
+            )
+        };
+        assert_eq!(
+            extension_instance.unwrap().as_raw(),
+            controller.as_raw().cast_const()
+        );
+
+        assert_eq!(
+            take_calls(),
+            [format!(
+                "plugin.ara.bind_to_document_controller({:?}, {:?})",
+                AraPlugInInstanceRoles::all(),
+                roles
+            )]
+        );
+    }
+}
+
+#[cfg(feature = "audio-ports")]
+mod audio_ports {
+    use super::*;
+    use crate::audio_ports::*;
+
+    #[test]
+    fn audio_ports_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let ports = plugin.get_extension::<PluginAudioPorts>().unwrap();
+
+        assert_eq!(ports.count(&mut plugin, true), 1);
+        assert_eq!(ports.count(&mut plugin, false), 1);
+
+        let mut buffer = AudioPortInfoBuffer::new();
+        let info = ports.get(&mut plugin, 0, false, &mut buffer).unwrap();
+        assert!(
+            info == AudioPortInfo {
+                id: ClapId::new(0),
+                name: b"main",
+                channel_count: 2,
+                flags: AudioPortFlags::IS_MAIN,
+                port_type: Some(AudioPortType::STEREO),
+                in_place_pair: Some(ClapId::new(0)),
+            }
+        );
+        assert!(ports.get(&mut plugin, 1, false, &mut buffer).is_none());
+
+        let supported = on_plugin_main_thread(&mut instance, |host| {
+            let ports = host.get_extension::<HostAudioPorts>().unwrap();
+            let supported = (
+                ports.is_rescan_flag_supported(host, RescanType::NAMES),
+                ports.is_rescan_flag_supported(host, RescanType::LIST),
+            );
+            ports.rescan(host, RescanType::NAMES);
+            supported
+        });
+
+        assert_eq!(supported, (true, false));
+        assert_eq!(take_calls(), ["host.audio_ports.rescan(RescanType(NAMES))"]);
+    }
+}
+
+#[cfg(feature = "event-registry")]
+mod event_registry {
+    use super::*;
+    use crate::event_registry::*;
+    use clack_common::events::spaces::EventSpace;
+    use clack_common::events::UnknownEvent;
+
+    struct SmokeEventSpace<'a>(&'a UnknownEvent);
+
+    // SAFETY: this event space is only used for its name.
+    unsafe impl<'a> EventSpace<'a> for SmokeEventSpace<'a> {
+        const NAME: &'static CStr =
+            match CStr::from_bytes_with_nul(b"org.rust-audio.clack.smoke-test\0") {
+                Ok(name) => name,
+                Err(_) => panic!(),
+            };
+
+        unsafe fn from_unknown(event: &'a UnknownEvent) -> Option<Self> {
+            Some(Self(event))
+        }
+
+        fn as_unknown(&self) -> &'a UnknownEvent {
+            self.0
+        }
+    }
+
+    #[test]
+    fn event_registry_roundtrip() {
+        let mut instance = instantiate();
+
+        let space_id = on_plugin_main_thread(&mut instance, |host| {
+            let registry = host.get_extension::<HostEventRegistry>().unwrap();
+            registry.query::<SmokeEventSpace>(host).map(|id| id.id())
+        });
+
+        assert_eq!(space_id, Some(42));
+    }
+}
+
+#[cfg(feature = "latency")]
+mod latency {
+    use super::*;
+    use crate::latency::*;
+
+    #[test]
+    fn latency_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let latency = plugin.get_extension::<PluginLatency>().unwrap();
+        assert_eq!(latency.get(&mut plugin), 42);
+
+        on_plugin_main_thread(&mut instance, |host| {
+            host.get_extension::<HostLatency>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.latency.changed"]);
+    }
+}
+
+#[cfg(feature = "log")]
+mod log {
+    use super::*;
+    use crate::log::*;
+
+    #[test]
+    fn log_roundtrip() {
+        let mut instance = instantiate();
+
+        on_plugin_main_thread(&mut instance, |host| {
+            let message = CStr::from_bytes_with_nul(b"Hello, world!\0").unwrap();
+            let log = host.get_extension::<HostLog>().unwrap();
+            log.log(&host.shared(), LogSeverity::Warning, message)
+        });
+
+        assert_eq!(take_calls(), ["host.log(Warning, Hello, world!)"]);
+    }
+}
+
+#[cfg(feature = "note-name")]
+mod note_name {
+    use super::*;
+    use crate::note_name::*;
+    use clack_common::events::Match;
+
+    #[test]
+    fn note_name_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let note_name = plugin.get_extension::<PluginNoteName>().unwrap();
+
+        assert_eq!(note_name.count(&mut plugin), 1);
+
+        let mut buffer = NoteNameBuffer::new();
+        let name = note_name.get(&mut plugin, 0, &mut buffer).unwrap();
+        assert_eq!(name.name, b"Kick");
+        assert_eq!(name.port, Match::All);
+        assert_eq!(name.channel, Match::Specific(9));
+        assert_eq!(name.key, Match::Specific(36));
+        assert!(note_name.get(&mut plugin, 1, &mut buffer).is_none());
+
+        on_plugin_main_thread(&mut instance, |host| {
+            host.get_extension::<HostNoteName>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.note_name.changed"]);
+    }
+}
+
+#[cfg(feature = "note-ports")]
+mod note_ports {
+    use super::*;
+    use crate::note_ports::*;
+
+    #[test]
+    fn note_ports_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let ports = plugin.get_extension::<PluginNotePorts>().unwrap();
+
+        assert_eq!(ports.count(&mut plugin, true), 1);
+        assert_eq!(ports.count(&mut plugin, false), 0);
+
+        let mut buffer = NotePortInfoBuffer::new();
+        let info = ports.get(&mut plugin, 0, true, &mut buffer).unwrap();
+        assert_eq!(info.id, ClapId::new(1));
+        assert_eq!(info.name, b"notes");
+        assert_eq!(
+            info.supported_dialects,
+            NoteDialects::CLAP | NoteDialects::MIDI
+        );
+        assert_eq!(info.preferred_dialect, Some(NoteDialect::Clap));
+        assert!(ports.get(&mut plugin, 0, false, &mut buffer).is_none());
+
+        let dialects = on_plugin_main_thread(&mut instance, |host| {
+            let ports = host.get_extension::<HostNotePorts>().unwrap();
+            ports.rescan(host, NotePortRescanFlags::NAMES);
+            ports.supported_dialects(host)
+        });
+
+        assert_eq!(dialects, NoteDialects::CLAP | NoteDialects::MIDI);
+        assert_eq!(
+            take_calls(),
+            ["host.note_ports.rescan(NotePortRescanFlags(NAMES))"]
+        );
+    }
+}
+
+#[cfg(all(unix, feature = "posix-fd"))]
+mod posix_fd {
+    use super::*;
+    use crate::posix_fd::*;
+
+    #[test]
+    fn posix_fd_roundtrip() {
+        let mut instance = instantiate();
+
+        on_plugin_main_thread(&mut instance, |host| {
+            let fds = host.get_extension::<HostPosixFd>().unwrap();
+            fds.register_fd(host, 3, FdFlags::READ).unwrap();
+            fds.modify_fd(host, 3, FdFlags::READ | FdFlags::WRITE)
+                .unwrap();
+            fds.unregister_fd(host, 3).unwrap();
+        });
+
+        let mut plugin = instance.plugin_handle();
+        let fds = plugin.get_extension::<PluginPosixFd>().unwrap();
+        fds.on_fd(&mut plugin, 3, FdFlags::WRITE);
+
+        assert_eq!(
+            take_calls(),
+            [
+                "host.posix_fd.register_fd(3, FdFlags(READ))",
+                "host.posix_fd.modify_fd(3, FdFlags(READ | WRITE))",
+                "host.posix_fd.unregister_fd(3)",
+                "plugin.posix_fd.on_fd(3, FdFlags(WRITE))",
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "render")]
+mod render {
+    use super::*;
+    use crate::render::*;
+
+    #[test]
+    fn render_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let render = plugin.get_extension::<PluginRender>().unwrap();
+
+        assert!(render.has_realtime_requirement(&mut plugin));
+        render.set(&mut plugin, RenderMode::Offline).unwrap();
+
+        assert_eq!(take_calls(), ["plugin.render.set(Offline)"]);
+    }
+}
+
+#[cfg(feature = "resource-directory")]
+mod resource_directory {
+    use super::*;
+    use crate::resource_directory::*;
+
+    #[test]
+    fn resource_directory_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let resource_directory = plugin.get_extension::<PluginResourceDirectory>().unwrap();
+
+        let directory = CStr::from_bytes_with_nul(b"/project/resources\0").unwrap();
+        resource_directory.set_directory(&mut plugin, Some(directory), false);
+        resource_directory.set_directory(&mut plugin, None, true);
+        resource_directory.collect(&mut plugin, true);
+
+        assert_eq!(resource_directory.files_count(&mut plugin), 1);
+
+        let mut buffer = [0; 64];
+        let path = resource_directory.get_file_path(&mut plugin, 0, &mut buffer);
+        assert_eq!(path.unwrap().to_bytes(), b"/project/resources/kick.wav");
+
+        // The buffer is too small to hold the path.
+        let mut buffer = [0; 8];
+        assert!(resource_directory
+            .get_file_path(&mut plugin, 0, &mut buffer)
+            .is_none());
+        assert!(resource_directory
+            .get_file_path(&mut plugin, 1, &mut [0; 64])
+            .is_none());
+
+        on_plugin_main_thread(&mut instance, |host| {
+            let resource_directory = host.get_extension::<HostResourceDirectory>().unwrap();
+            assert!(resource_directory.request_directory(host, false));
+            assert!(!resource_directory.request_directory(host, true));
+            resource_directory.release_directory(host, false);
+        });
+
+        assert_eq!(
+            take_calls(),
+            [
+                "plugin.resource_directory.set_directory(Some(\"/project/resources\"), false)",
+                "plugin.resource_directory.set_directory(None, true)",
+                "plugin.resource_directory.collect(true)",
+                "host.resource_directory.request_directory(false)",
+                "host.resource_directory.request_directory(true)",
+                "host.resource_directory.release_directory(false)",
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "state")]
+mod state {
+    use super::*;
+    use crate::state::*;
+
+    #[test]
+    fn state_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let state = plugin.get_extension::<PluginState>().unwrap();
+
+        let mut saved = Vec::new();
+        state.save(&mut plugin, &mut saved).unwrap();
+        assert_eq!(saved, b"saved state");
+
+        state.load(&mut plugin, &mut &b"loaded state"[..]).unwrap();
+
+        on_plugin_main_thread(&mut instance, |host| {
+            let mut state = host.get_extension::<HostState>().unwrap();
+            state.mark_dirty(host)
+        });
+
+        assert_eq!(
+            take_calls(),
+            ["plugin.state.load(loaded state)", "host.state.mark_dirty"]
+        );
+    }
+}
+
+#[cfg(feature = "surround")]
+mod surround {
+    use super::*;
+    use crate::surround::*;
+
+    #[test]
+    fn surround_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let surround = plugin.get_extension::<PluginSurround>().unwrap();
+
+        let layout = SpeakerLayout::Surround5_1;
+        assert!(surround.is_channel_mask_supported(&mut plugin, layout.mask()));
+        assert!(!surround.is_channel_mask_supported(&mut plugin, SpeakerLayout::Quad.mask()));
+
+        let mut buffer = [SurroundChannel::from_raw(0); 8];
+        let channel_map = surround.get_channel_map(&mut plugin, true, 0, &mut buffer);
+        assert_eq!(channel_map, layout.channels(ChannelOrder::Film));
+
+        // Routing the host's SMPTE channels into the plugin's Film-ordered port.
+        let remap = ChannelRemap::new(layout.channels(ChannelOrder::Smpte), channel_map);
+        assert_eq!(remap.source_of(5), Some(3));
+
+        on_plugin_main_thread(&mut instance, |host| {
+            host.get_extension::<HostSurround>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.surround.changed"]);
+    }
+}
+
+#[cfg(feature = "tail")]
+mod tail {
+    use super::*;
+    use crate::tail::*;
+
+    #[test]
+    fn tail_roundtrip() {
+        let mut instance = instantiate();
+        let mut processor = start_processing(&mut instance);
+
+        let plugin = processor.plugin_handle();
+        let tail = plugin.get_extension::<PluginTail>().unwrap();
+        assert_eq!(tail.get(&plugin), TailLength::Finite(128));
+        assert_eq!(
+            tail.get_on_main_thread(&mut instance.plugin_handle()),
+            TailLength::Finite(128)
+        );
+
+        on_plugin_audio_thread(&mut processor, |host| {
+            host.get_extension::<HostTail>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.tail.changed"]);
+    }
+}
+
+#[cfg(feature = "thread-check")]
+mod thread_check {
+    use super::*;
+    use crate::thread_check::*;
+
+    #[test]
+    fn thread_check_roundtrip() {
+        let mut instance = instantiate();
+
+        let checks = on_plugin_main_thread(&mut instance, |host| {
+            let check = host.get_extension::<HostThreadCheck>().unwrap();
+            (
+                check.is_main_thread(&host.shared()),
+                check.is_audio_thread(&host.shared()),
+            )
+        });
+
+        assert_eq!(checks, (Some(true), Some(true)));
+    }
+}
+
+#[cfg(feature = "thread-pool")]
+mod thread_pool {
+    use super::*;
+    use crate::thread_pool::*;
+
+    #[test]
+    fn thread_pool_roundtrip() {
+        let mut instance = instantiate();
+        let mut processor = start_processing(&mut instance);
+
+        on_plugin_audio_thread(&mut processor, |host| {
+            let pool = host.get_extension::<HostThreadPool>().unwrap();
+            pool.request_exec(host, 2).unwrap()
+        });
+
+        let plugin = instance.plugin_shared_handle();
+        let pool = plugin.get_extension::<PluginThreadPool>().unwrap();
+        pool.exec(&plugin, 0);
+        pool.exec(&plugin, 1);
+
+        assert_eq!(
+            take_calls(),
+            [
+                "host.thread_pool.request_exec(2)",
+                "plugin.thread_pool.exec(0)",
+                "plugin.thread_pool.exec(1)",
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "timer")]
+mod timer {
+    use super::*;
+    use crate::timer::*;
+
+    #[test]
+    fn timer_roundtrip() {
+        let mut instance = instantiate();
+
+        let timer_id = on_plugin_main_thread(&mut instance, |host| {
+            let timer = host.get_extension::<HostTimer>().unwrap();
+            timer.register_timer(host, 30).unwrap()
+        });
+        assert_eq!(timer_id, TimerId(5));
+
+        let mut plugin = instance.plugin_handle();
+        let timer = plugin.get_extension::<PluginTimer>().unwrap();
+        timer.on_timer(&mut plugin, timer_id);
+
+        on_plugin_main_thread(&mut instance, move |host| {
+            let timer = host.get_extension::<HostTimer>().unwrap();
+            timer.unregister_timer(host, timer_id).unwrap()
+        });
+
+        assert_eq!(
+            take_calls(),
+            [
+                "host.timer.register_timer(30)",
+                "plugin.timer.on_timer(TimerId(5))",
+                "host.timer.unregister_timer(TimerId(5))",
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "track-info")]
+mod track_info {
+    use super::*;
+    use crate::track_info::*;
+
+    #[test]
+    fn track_info_roundtrip() {
+        let mut instance = instantiate();
+
+        let (flags, name) = on_plugin_main_thread(&mut instance, |host| {
+            let track_info = host.get_extension::<HostTrackInfo>().unwrap();
+            let mut buffer = TrackInfoBuffer::new();
+            let info = track_info.get(host, &mut buffer).unwrap();
+            (info.flags, info.name.map(|n| n.to_vec()))
+        });
+
+        assert_eq!(flags, TrackInfoFlags::IS_FOR_BUS);
+        assert_eq!(name.as_deref(), Some(&b"Drums"[..]));
+
+        let mut plugin = instance.plugin_handle();
+        let track_info = plugin.get_extension::<PluginTrackInfo>().unwrap();
+        track_info.changed(&mut plugin);
+
+        assert_eq!(take_calls(), ["plugin.track_info.changed"]);
+    }
+}
+
+#[cfg(feature = "voice-info")]
+mod voice_info {
+    use super::*;
+    use crate::voice_info::*;
+
+    #[test]
+    fn voice_info_roundtrip() {
+        let mut instance = instantiate();
+        let mut plugin = instance.plugin_handle();
+        let voice_info = plugin.get_extension::<PluginVoiceInfo>().unwrap();
+
+        let info = voice_info.get(&mut plugin).unwrap();
+        assert_eq!(info.voice_count, 8);
+        assert_eq!(info.voice_capacity, 16);
+        assert_eq!(info.flags, VoiceInfoFlags::SUPPORTS_OVERLAPPING_NOTES);
+
+        on_plugin_main_thread(&mut instance, |host| {
+            host.get_extension::<HostVoiceInfo>().unwrap().changed(host)
+        });
+
+        assert_eq!(take_calls(), ["host.voice_info.changed"]);
+    }
+}
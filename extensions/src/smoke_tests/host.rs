@@ -0,0 +1,320 @@
+use super::record;
+use clack_host::prelude::*;
+
+pub struct SmokeHost;
+
+pub struct SmokeHostShared;
+
+impl SharedHandler<'_> for SmokeHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+pub struct SmokeHostMainThread;
+
+impl MainThreadHandler<'_> for SmokeHostMainThread {}
+
+pub struct SmokeHostAudioProcessor;
+
+impl AudioProcessorHandler<'_> for SmokeHostAudioProcessor {}
+
+impl HostHandlers for SmokeHost {
+    type Shared<'a> = SmokeHostShared;
+    type MainThread<'a> = SmokeHostMainThread;
+    type AudioProcessor<'a> = SmokeHostAudioProcessor;
+
+    #[allow(unused)]
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        #[cfg(feature = "ambisonic")]
+        builder.register::<crate::ambisonic::HostAmbisonic>();
+        #[cfg(feature = "audio-ports")]
+        builder.register::<crate::audio_ports::HostAudioPorts>();
+        #[cfg(feature = "event-registry")]
+        builder.register::<crate::event_registry::HostEventRegistry>();
+        #[cfg(feature = "latency")]
+        builder.register::<crate::latency::HostLatency>();
+        #[cfg(feature = "log")]
+        builder.register::<crate::log::HostLog>();
+        #[cfg(feature = "note-name")]
+        builder.register::<crate::note_name::HostNoteName>();
+        #[cfg(feature = "note-ports")]
+        builder.register::<crate::note_ports::HostNotePorts>();
+        #[cfg(all(unix, feature = "posix-fd"))]
+        builder.register::<crate::posix_fd::HostPosixFd>();
+        #[cfg(feature = "resource-directory")]
+        builder.register::<crate::resource_directory::HostResourceDirectory>();
+        #[cfg(feature = "state")]
+        builder.register::<crate::state::HostState>();
+        #[cfg(feature = "surround")]
+        builder.register::<crate::surround::HostSurround>();
+        #[cfg(feature = "tail")]
+        builder.register::<crate::tail::HostTail>();
+        #[cfg(feature = "thread-check")]
+        builder.register::<crate::thread_check::HostThreadCheck>();
+        #[cfg(feature = "thread-pool")]
+        builder.register::<crate::thread_pool::HostThreadPool>();
+        #[cfg(feature = "timer")]
+        builder.register::<crate::timer::HostTimer>();
+        #[cfg(feature = "track-info")]
+        builder.register::<crate::track_info::HostTrackInfo>();
+        #[cfg(feature = "voice-info")]
+        builder.register::<crate::voice_info::HostVoiceInfo>();
+    }
+}
+
+#[cfg(feature = "ambisonic")]
+mod ambisonic {
+    use super::*;
+    use crate::ambisonic::*;
+
+    impl HostAmbisonicImpl for SmokeHostMainThread {
+        fn changed(&mut self) {
+            record("host.ambisonic.changed");
+        }
+    }
+}
+
+#[cfg(feature = "audio-ports")]
+mod audio_ports {
+    use super::*;
+    use crate::audio_ports::*;
+
+    impl HostAudioPortsImpl for SmokeHostMainThread {
+        fn is_rescan_flag_supported(&self, flag: RescanType) -> bool {
+            flag == RescanType::NAMES
+        }
+
+        fn rescan(&mut self, flag: RescanType) {
+            record(format!("host.audio_ports.rescan({flag:?})"));
+        }
+    }
+}
+
+#[cfg(feature = "event-registry")]
+mod event_registry {
+    use super::*;
+    use crate::event_registry::*;
+    use clack_common::events::spaces::EventSpaceId;
+    use std::ffi::CStr;
+
+    // SAFETY: the only event space ID this returns is unique and stable.
+    unsafe impl HostEventRegistryImpl for SmokeHostMainThread {
+        fn query(&self, space_name: &CStr) -> Option<EventSpaceId> {
+            match space_name.to_bytes() {
+                b"org.rust-audio.clack.smoke-test" => EventSpaceId::new(42),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "latency")]
+mod latency {
+    use super::*;
+    use crate::latency::*;
+
+    impl HostLatencyImpl for SmokeHostMainThread {
+        fn changed(&mut self) {
+            record("host.latency.changed");
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+mod log {
+    use super::*;
+    use crate::log::*;
+
+    impl HostLogImpl for SmokeHostShared {
+        fn log(&self, severity: LogSeverity, message: &str) {
+            record(format!("host.log({severity:?}, {message})"));
+        }
+    }
+}
+
+#[cfg(feature = "note-name")]
+mod note_name {
+    use super::*;
+    use crate::note_name::*;
+
+    impl HostNoteNameImpl for SmokeHostMainThread {
+        fn changed(&mut self) {
+            record("host.note_name.changed");
+        }
+    }
+}
+
+#[cfg(feature = "note-ports")]
+mod note_ports {
+    use super::*;
+    use crate::note_ports::*;
+
+    impl HostNotePortsImpl for SmokeHostMainThread {
+        fn supported_dialects(&self) -> NoteDialects {
+            NoteDialects::CLAP | NoteDialects::MIDI
+        }
+
+        fn rescan(&mut self, flags: NotePortRescanFlags) {
+            record(format!("host.note_ports.rescan({flags:?})"));
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "posix-fd"))]
+mod posix_fd {
+    use super::*;
+    use crate::posix_fd::*;
+    use std::os::unix::io::RawFd;
+
+    impl HostPosixFdImpl for SmokeHostMainThread {
+        fn register_fd(&mut self, fd: RawFd, flags: FdFlags) -> Result<(), HostError> {
+            record(format!("host.posix_fd.register_fd({fd}, {flags:?})"));
+            Ok(())
+        }
+
+        fn modify_fd(&mut self, fd: RawFd, flags: FdFlags) -> Result<(), HostError> {
+            record(format!("host.posix_fd.modify_fd({fd}, {flags:?})"));
+            Ok(())
+        }
+
+        fn unregister_fd(&mut self, fd: RawFd) -> Result<(), HostError> {
+            record(format!("host.posix_fd.unregister_fd({fd})"));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "resource-directory")]
+mod resource_directory {
+    use super::*;
+    use crate::resource_directory::*;
+
+    impl HostResourceDirectoryImpl for SmokeHostMainThread {
+        fn request_directory(&mut self, is_shared: bool) -> bool {
+            record(format!(
+                "host.resource_directory.request_directory({is_shared})"
+            ));
+            !is_shared
+        }
+
+        fn release_directory(&mut self, is_shared: bool) {
+            record(format!(
+                "host.resource_directory.release_directory({is_shared})"
+            ));
+        }
+    }
+}
+
+#[cfg(feature = "state")]
+mod state {
+    use super::*;
+    use crate::state::*;
+
+    impl HostStateImpl for SmokeHostMainThread {
+        fn mark_dirty(&mut self) {
+            record("host.state.mark_dirty");
+        }
+    }
+}
+
+#[cfg(feature = "surround")]
+mod surround {
+    use super::*;
+    use crate::surround::*;
+
+    impl HostSurroundImpl for SmokeHostMainThread {
+        fn changed(&mut self) {
+            record("host.surround.changed");
+        }
+    }
+}
+
+#[cfg(feature = "tail")]
+mod tail {
+    use super::*;
+    use crate::tail::*;
+
+    impl HostTailImpl for SmokeHostAudioProcessor {
+        fn changed(&mut self) {
+            record("host.tail.changed");
+        }
+    }
+}
+
+#[cfg(feature = "thread-check")]
+mod thread_check {
+    use super::*;
+    use crate::thread_check::*;
+
+    // All the smoke tests run on a single thread, which acts as both the main and audio thread.
+    impl HostThreadCheckImpl for SmokeHostShared {
+        fn is_main_thread(&self) -> bool {
+            true
+        }
+
+        fn is_audio_thread(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(feature = "thread-pool")]
+mod thread_pool {
+    use super::*;
+    use crate::thread_pool::*;
+
+    impl HostThreadPoolImpl for SmokeHostAudioProcessor {
+        fn request_exec(&mut self, task_count: u32) -> Result<(), HostError> {
+            record(format!("host.thread_pool.request_exec({task_count})"));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "timer")]
+mod timer {
+    use super::*;
+    use crate::timer::*;
+
+    impl HostTimerImpl for SmokeHostMainThread {
+        fn register_timer(&mut self, period_ms: u32) -> Result<TimerId, HostError> {
+            record(format!("host.timer.register_timer({period_ms})"));
+            Ok(TimerId(5))
+        }
+
+        fn unregister_timer(&mut self, timer_id: TimerId) -> Result<(), HostError> {
+            record(format!("host.timer.unregister_timer({timer_id:?})"));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "track-info")]
+mod track_info {
+    use super::*;
+    use crate::track_info::*;
+
+    impl HostTrackInfoImpl for SmokeHostMainThread {
+        fn get(&mut self) -> Option<TrackInfo<'_>> {
+            Some(TrackInfo {
+                flags: TrackInfoFlags::IS_FOR_BUS,
+                name: Some(b"Drums"),
+                color: None,
+                audio_channel: None,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "voice-info")]
+mod voice_info {
+    use super::*;
+    use crate::voice_info::*;
+
+    impl HostVoiceInfoImpl for SmokeHostMainThread {
+        fn changed(&mut self) {
+            record("host.voice_info.changed");
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use super::record;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::cell::RefCell;
+
+type MainThreadTask = Box<dyn FnOnce(&mut HostMainThreadHandle)>;
+type AudioThreadTask = Box<dyn FnOnce(&mut HostAudioProcessorHandle)>;
+
+thread_local! {
+    static MAIN_THREAD_TASK: RefCell<Option<MainThreadTask>> = const { RefCell::new(None) };
+    static AUDIO_THREAD_TASK: RefCell<Option<AudioThreadTask>> = const { RefCell::new(None) };
+}
+
+/// Schedules a task to be run by the plugin the next time its `on_main_thread` callback is called.
+pub fn schedule_main_thread_task(task: impl FnOnce(&mut HostMainThreadHandle) + 'static) {
+    MAIN_THREAD_TASK.with(|t| *t.borrow_mut() = Some(Box::new(task)));
+}
+
+/// Schedules a task to be run by the plugin the next time it processes audio.
+#[allow(dead_code)] // Only used by audio-thread extensions
+pub fn schedule_audio_thread_task(task: impl FnOnce(&mut HostAudioProcessorHandle) + 'static) {
+    AUDIO_THREAD_TASK.with(|t| *t.borrow_mut() = Some(Box::new(task)));
+}
+
+pub struct SmokePlugin;
+
+pub struct SmokePluginShared;
+
+impl PluginShared<'_> for SmokePluginShared {}
+
+pub struct SmokePluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+}
+
+impl<'a> PluginMainThread<'a, SmokePluginShared> for SmokePluginMainThread<'a> {
+    fn on_main_thread(&mut self) {
+        if let Some(task) = MAIN_THREAD_TASK.with(|t| t.borrow_mut().take()) {
+            task(&mut self.host)
+        }
+    }
+}
+
+pub struct SmokePluginAudioProcessor<'a> {
+    host: HostAudioProcessorHandle<'a>,
+}
+
+impl<'a> PluginAudioProcessor<'a, SmokePluginShared, SmokePluginMainThread<'a>>
+    for SmokePluginAudioProcessor<'a>
+{
+    fn activate(
+        host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut SmokePluginMainThread<'a>,
+        _shared: &'a SmokePluginShared,
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self { host })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        if let Some(task) = AUDIO_THREAD_TASK.with(|t| t.borrow_mut().take()) {
+            task(&mut self.host)
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl Plugin for SmokePlugin {
+    type AudioProcessor<'a> = SmokePluginAudioProcessor<'a>;
+    type Shared<'a> = SmokePluginShared;
+    type MainThread<'a> = SmokePluginMainThread<'a>;
+
+    #[allow(unused)]
+    fn declare_extensions(
+        builder: &mut PluginExtensions<Self>,
+        _shared: Option<&Self::Shared<'_>>,
+    ) {
+        #[cfg(feature = "ambisonic")]
+        builder.register::<crate::ambisonic::PluginAmbisonic>();
+        #[cfg(feature = "ara")]
+        builder.register::<crate::ara::PluginAraExtension>();
+        #[cfg(feature = "audio-ports")]
+        builder.register::<crate::audio_ports::PluginAudioPorts>();
+        #[cfg(feature = "latency")]
+        builder.register::<crate::latency::PluginLatency>();
+        #[cfg(feature = "note-name")]
+        builder.register::<crate::note_name::PluginNoteName>();
+        #[cfg(feature = "note-ports")]
+        builder.register::<crate::note_ports::PluginNotePorts>();
+        #[cfg(all(unix, feature = "posix-fd"))]
+        builder.register::<crate::posix_fd::PluginPosixFd>();
+        #[cfg(feature = "render")]
+        builder.register::<crate::render::PluginRender>();
+        #[cfg(feature = "resource-directory")]
+        builder.register::<crate::resource_directory::PluginResourceDirectory>();
+        #[cfg(feature = "state")]
+        builder.register::<crate::state::PluginState>();
+        #[cfg(feature = "surround")]
+        builder.register::<crate::surround::PluginSurround>();
+        #[cfg(feature = "tail")]
+        builder.register::<crate::tail::PluginTail>();
+        #[cfg(feature = "thread-pool")]
+        builder.register::<crate::thread_pool::PluginThreadPool>();
+        #[cfg(feature = "timer")]
+        builder.register::<crate::timer::PluginTimer>();
+        #[cfg(feature = "track-info")]
+        builder.register::<crate::track_info::PluginTrackInfo>();
+        #[cfg(feature = "voice-info")]
+        builder.register::<crate::voice_info::PluginVoiceInfo>();
+    }
+}
+
+impl DefaultPluginFactory for SmokePlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("org.rust-audio.clack.smoke-test", "Smoke test plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(SmokePluginShared)
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(SmokePluginMainThread { host })
+    }
+}
+
+pub static SMOKE_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<SmokePlugin>);
+
+#[cfg(feature = "ambisonic")]
+mod ambisonic {
+    use super::*;
+    use crate::ambisonic::*;
+
+    impl PluginAmbisonicImpl for SmokePluginMainThread<'_> {
+        fn is_config_supported(&mut self, config: AmbisonicConfig) -> bool {
+            config == AmbisonicConfig::AMBIX
+        }
+
+        fn get_config(&mut self, is_input: bool, port_index: u32) -> Option<AmbisonicConfig> {
+            (is_input && port_index == 0).then_some(AmbisonicConfig::AMBIX)
+        }
+    }
+}
+
+#[cfg(feature = "ara")]
+mod ara {
+    use super::*;
+    use crate::ara::*;
+
+    /// A stand-in for the ARA factory, which is only ever used as an opaque pointer.
+    pub static ARA_FACTORY: [u8; 8] = *b"ARAFACTO";
+
+    impl PluginAraExtensionImpl for SmokePluginMainThread<'_> {
+        fn get_factory(&mut self) -> Option<AraFactory> {
+            // SAFETY: the host never dereferences this pointer.
+            unsafe { AraFactory::from_raw(ARA_FACTORY.as_ptr().cast()) }
+        }
+
+        fn bind_to_document_controller(
+            &mut self,
+            document_controller: AraDocumentControllerRef,
+            known_roles: AraPlugInInstanceRoles,
+            assigned_roles: AraPlugInInstanceRoles,
+        ) -> Option<AraPlugInExtensionInstance> {
+            record(format!(
+                "plugin.ara.bind_to_document_controller({known_roles:?}, {assigned_roles:?})"
+            ));
+
+            // SAFETY: the host never dereferences this pointer.
+            unsafe { AraPlugInExtensionInstance::from_raw(document_controller.as_raw()) }
+        }
+    }
+}
+
+#[cfg(feature = "ara")]
+pub use ara::ARA_FACTORY;
+
+#[cfg(feature = "audio-ports")]
+mod audio_ports {
+    use super::*;
+    use crate::audio_ports::*;
+
+    impl PluginAudioPortsImpl for SmokePluginMainThread<'_> {
+        fn count(&mut self, _is_input: bool) -> u32 {
+            1
+        }
+
+        fn get(&mut self, index: u32, _is_input: bool, writer: &mut AudioPortInfoWriter) {
+            if index == 0 {
+                writer.set(&AudioPortInfo {
+                    id: ClapId::new(0),
+                    name: b"main",
+                    channel_count: 2,
+                    flags: AudioPortFlags::IS_MAIN,
+                    port_type: Some(AudioPortType::STEREO),
+                    in_place_pair: Some(ClapId::new(0)),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "latency")]
+mod latency {
+    use super::*;
+    use crate::latency::*;
+
+    impl PluginLatencyImpl for SmokePluginMainThread<'_> {
+        fn get(&mut self) -> u32 {
+            42
+        }
+    }
+}
+
+#[cfg(feature = "note-name")]
+mod note_name {
+    use super::*;
+    use crate::note_name::*;
+    use clack_common::events::Match;
+
+    impl PluginNoteNameImpl for SmokePluginMainThread<'_> {
+        fn count(&mut self) -> usize {
+            1
+        }
+
+        fn get(&mut self, index: usize, writer: &mut NoteNameWriter) {
+            if index == 0 {
+                writer.write(&NoteName {
+                    name: b"Kick",
+                    port: Match::All,
+                    channel: Match::Specific(9),
+                    key: Match::Specific(36),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "note-ports")]
+mod note_ports {
+    use super::*;
+    use crate::note_ports::*;
+
+    impl PluginNotePortsImpl for SmokePluginMainThread<'_> {
+        fn count(&mut self, is_input: bool) -> u32 {
+            if is_input {
+                1
+            } else {
+                0
+            }
+        }
+
+        fn get(&mut self, index: u32, is_input: bool, writer: &mut NotePortInfoWriter) {
+            if index == 0 && is_input {
+                writer.set(&NotePortInfo {
+                    id: ClapId::new(1),
+                    name: b"notes",
+                    supported_dialects: NoteDialects::CLAP | NoteDialects::MIDI,
+                    preferred_dialect: Some(NoteDialect::Clap),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "posix-fd"))]
+mod posix_fd {
+    use super::*;
+    use crate::posix_fd::*;
+    use std::os::unix::io::RawFd;
+
+    impl PluginPosixFdImpl for SmokePluginMainThread<'_> {
+        fn on_fd(&mut self, fd: RawFd, flags: FdFlags) {
+            record(format!("plugin.posix_fd.on_fd({fd}, {flags:?})"));
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+mod render {
+    use super::*;
+    use crate::render::*;
+
+    impl PluginRenderImpl for SmokePluginMainThread<'_> {
+        fn has_hard_realtime_requirement(&self) -> bool {
+            true
+        }
+
+        fn set(&mut self, mode: RenderMode) -> Result<(), PluginError> {
+            record(format!("plugin.render.set({mode:?})"));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "resource-directory")]
+mod resource_directory {
+    use super::*;
+    use crate::resource_directory::*;
+    use std::ffi::CStr;
+
+    impl PluginResourceDirectoryImpl for SmokePluginMainThread<'_> {
+        fn set_directory(&mut self, directory: Option<&CStr>, is_shared: bool) {
+            record(format!(
+                "plugin.resource_directory.set_directory({directory:?}, {is_shared})"
+            ));
+        }
+
+        fn collect(&mut self, all: bool) {
+            record(format!("plugin.resource_directory.collect({all})"));
+        }
+
+        fn files_count(&mut self) -> u32 {
+            1
+        }
+
+        fn get_file_path(&mut self, index: u32, writer: &mut ResourcePathWriter) {
+            if index == 0 {
+                writer.set(CStr::from_bytes_with_nul(b"/project/resources/kick.wav\0").unwrap());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "state")]
+mod state {
+    use super::*;
+    use crate::state::*;
+    use clack_common::stream::{InputStream, OutputStream};
+    use std::io::{Read, Write};
+
+    impl PluginStateImpl for SmokePluginMainThread<'_> {
+        fn save(&mut self, output: &mut OutputStream) -> Result<(), PluginError> {
+            output.write_all(b"saved state")?;
+            Ok(())
+        }
+
+        fn load(&mut self, input: &mut InputStream) -> Result<(), PluginError> {
+            let mut state = String::new();
+            input.read_to_string(&mut state)?;
+            record(format!("plugin.state.load({state})"));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "surround")]
+mod surround {
+    use super::*;
+    use crate::surround::*;
+
+    impl PluginSurroundImpl for SmokePluginMainThread<'_> {
+        fn is_channel_mask_supported(&mut self, channel_mask: SurroundChannelMask) -> bool {
+            channel_mask == SpeakerLayout::Surround5_1.mask()
+        }
+
+        fn get_channel_map(
+            &mut self,
+            _is_input: bool,
+            _port_index: u32,
+            writer: &mut SurroundChannelMapWriter,
+        ) {
+            writer.set(SpeakerLayout::Surround5_1.channels(ChannelOrder::Film));
+        }
+    }
+}
+
+#[cfg(feature = "tail")]
+mod tail {
+    use super::*;
+    use crate::tail::*;
+
+    impl PluginTailImpl for SmokePluginAudioProcessor<'_> {
+        fn get(&self) -> TailLength {
+            TailLength::Finite(128)
+        }
+    }
+}
+
+#[cfg(feature = "thread-pool")]
+mod thread_pool {
+    use super::*;
+    use crate::thread_pool::*;
+
+    impl PluginThreadPoolImpl for SmokePluginShared {
+        fn exec(&self, task_index: u32) {
+            record(format!("plugin.thread_pool.exec({task_index})"));
+        }
+    }
+}
+
+#[cfg(feature = "timer")]
+mod timer {
+    use super::*;
+    use crate::timer::*;
+
+    impl PluginTimerImpl for SmokePluginMainThread<'_> {
+        fn on_timer(&mut self, timer_id: TimerId) {
+            record(format!("plugin.timer.on_timer({timer_id:?})"));
+        }
+    }
+}
+
+#[cfg(feature = "track-info")]
+mod track_info {
+    use super::*;
+    use crate::track_info::*;
+
+    impl PluginTrackInfoImpl for SmokePluginMainThread<'_> {
+        fn changed(&mut self) {
+            record("plugin.track_info.changed");
+        }
+    }
+}
+
+#[cfg(feature = "voice-info")]
+mod voice_info {
+    use super::*;
+    use crate::voice_info::*;
+
+    impl PluginVoiceInfoImpl for SmokePluginMainThread<'_> {
+        fn get(&self) -> Option<VoiceInfo> {
+            Some(VoiceInfo {
+                voice_count: 8,
+                voice_capacity: 16,
+                flags: VoiceInfoFlags::SUPPORTS_OVERLAPPING_NOTES,
+            })
+        }
+    }
+}
@@ -7,7 +7,12 @@
 //! [`OutputStream`](clack_common::stream::OutputStream)
 //!
 //! Plugins can also notify the host that their state has changed compared to the last time it was
-//! saved or loaded, using the `mark_dirty` call.
+//! saved or loaded, using the `mark_dirty` call. Hosts can use a `DirtyTracker` to keep track of
+//! those changes, and to autosave the plugin's state.
+//!
+//! Plugins with large states (e.g. embedded sample libraries) can use a `ChunkedStateReader` or a
+//! `ChunkedStateWriter` to transfer their state in chunks, reporting progress to their GUI and
+//! allowing long transfers to be cancelled.
 //!
 //! # Host-Side Example
 //!
@@ -83,6 +88,7 @@
 //! # Ok(()) }
 //! ```
 
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clap_sys::ext::state::{clap_host_state, clap_plugin_state, CLAP_EXT_STATE};
 use std::error::Error;
@@ -90,7 +96,6 @@ use std::ffi::CStr;
 use std::fmt::{Display, Formatter};
 
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginState(RawExtension<PluginExtensionSide, clap_plugin_state>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -104,8 +109,18 @@ unsafe impl Extension for PluginState {
     }
 }
 
+impl PluginState {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_state> {
+        self.0
+    }
+}
+
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostState(RawExtension<HostExtensionSide, clap_host_state>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -119,9 +134,26 @@ unsafe impl Extension for HostState {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct StateError {
-    saving: bool,
+impl HostState {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_state> {
+        self.0
+    }
+}
+
+/// Errors that can occur while loading or saving a plugin's state.
+///
+/// Each variant identifies the call that failed, and carries the [`CallFailure`] reason.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StateError {
+    /// The plugin failed to load its state.
+    LoadError(CallFailure),
+    /// The plugin failed to save its state.
+    SaveError(CallFailure),
 }
 
 impl StateError {
@@ -129,35 +161,57 @@ impl StateError {
     ///
     /// This information is used in the error's message.
     pub const fn loading() -> Self {
-        Self { saving: false }
+        Self::LoadError(CallFailure::Declined)
     }
 
     /// Returns a [`StateError`] that was triggered while saving state.
     ///
     /// This information is used in the error's message.
     pub const fn saving() -> Self {
-        Self { saving: true }
+        Self::SaveError(CallFailure::Declined)
+    }
+
+    /// Returns `true` if this error was triggered while saving state, `false` if it was
+    /// triggered while loading state.
+    #[inline]
+    pub const fn is_saving(&self) -> bool {
+        matches!(self, Self::SaveError(_))
+    }
+
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        match *self {
+            Self::LoadError(failure) | Self::SaveError(failure) => failure,
+        }
     }
 }
 
 impl Display for StateError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.saving {
-            f.write_str("Failed to save plugin state")
-        } else {
-            f.write_str("Failed to load plugin state")
+        match self {
+            Self::SaveError(failure) => write!(f, "Failed to save plugin state: {failure}"),
+            Self::LoadError(failure) => write!(f, "Failed to load plugin state: {failure}"),
         }
     }
 }
 
 impl Error for StateError {}
 
+#[cfg(feature = "clack-plugin")]
+mod chunked;
 #[cfg(feature = "clack-plugin")]
 mod plugin;
 #[cfg(feature = "clack-plugin")]
+pub use chunked::*;
+#[cfg(feature = "clack-plugin")]
 pub use plugin::*;
 
+#[cfg(feature = "clack-host")]
+mod dirty;
 #[cfg(feature = "clack-host")]
 mod host;
 #[cfg(feature = "clack-host")]
+pub use dirty::*;
+#[cfg(feature = "clack-host")]
 pub use host::*;
@@ -0,0 +1,393 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+use std::ops::ControlFlow;
+
+/// The default size of the chunks transferred by [`ChunkedStateReader`] and
+/// [`ChunkedStateWriter`], in bytes.
+pub const DEFAULT_STATE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The progress of a state transfer, given to the progress callback of a [`ChunkedStateReader`]
+/// or a [`ChunkedStateWriter`] after each chunk.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StateProgress {
+    /// The number of bytes transferred so far.
+    pub transferred: u64,
+    /// The total number of bytes to transfer, if known.
+    pub total: Option<u64>,
+}
+
+impl StateProgress {
+    /// Returns the completed fraction of the transfer, between `0.0` and `1.0`, or `None` if the
+    /// total size of the transfer is unknown.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.transferred as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+/// Errors that can occur during a chunked state transfer.
+#[derive(Debug)]
+pub enum ChunkedStateError {
+    /// The underlying stream failed.
+    Io(std::io::Error),
+    /// The stream ended before the expected amount of data could be read.
+    UnexpectedEnd,
+    /// The transfer was cancelled by the progress callback.
+    Cancelled,
+}
+
+impl Display for ChunkedStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkedStateError::Io(e) => write!(f, "State stream error: {e}"),
+            ChunkedStateError::UnexpectedEnd => f.write_str("State stream ended unexpectedly"),
+            ChunkedStateError::Cancelled => f.write_str("State transfer was cancelled"),
+        }
+    }
+}
+
+impl Error for ChunkedStateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ChunkedStateError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ChunkedStateError {
+    #[inline]
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::UnexpectedEof => Self::UnexpectedEnd,
+            _ => Self::Io(e),
+        }
+    }
+}
+
+/// Reads a plugin's state from an [`InputStream`](clack_common::stream::InputStream) (or any
+/// other [`Read`] implementation) in chunks, reporting progress after each chunk.
+///
+/// After each chunk, the progress callback is given the current [`StateProgress`]. It can
+/// return [`ControlFlow::Break`] to cooperatively cancel the transfer, in which case
+/// [`ChunkedStateError::Cancelled`] is returned.
+///
+/// This is useful for plugins with large states (e.g. embedded sample libraries), so that they can
+/// report loading progress to their GUI, and allow the user to cancel long loads.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::state::ChunkedStateReader;
+/// use std::ops::ControlFlow;
+///
+/// let data = vec![42u8; 1000];
+/// let mut input = &data[..]; // Usually an InputStream
+///
+/// let mut reader = ChunkedStateReader::new(&mut input, |progress| {
+///     println!("Loaded {:?}", progress.fraction());
+///     ControlFlow::Continue(())
+/// })
+/// .with_chunk_size(100)
+/// .with_total_size(1000);
+///
+/// let loaded = reader.read_to_end().unwrap();
+/// assert_eq!(loaded, data);
+/// ```
+pub struct ChunkedStateReader<R, F> {
+    input: R,
+    on_progress: F,
+    chunk_size: usize,
+    progress: StateProgress,
+}
+
+impl<R: Read, F: FnMut(&StateProgress) -> ControlFlow<()>> ChunkedStateReader<R, F> {
+    /// Creates a new chunked reader over the given input, reporting progress to the given callback.
+    pub fn new(input: R, on_progress: F) -> Self {
+        Self {
+            input,
+            on_progress,
+            chunk_size: DEFAULT_STATE_CHUNK_SIZE,
+            progress: StateProgress {
+                transferred: 0,
+                total: None,
+            },
+        }
+    }
+
+    /// Sets the size of the chunks to read, in bytes.
+    ///
+    /// Defaults to [`DEFAULT_STATE_CHUNK_SIZE`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    #[inline]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be non-zero");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the total number of bytes expected to be read, which is used to compute the
+    /// [progress fraction](StateProgress::fraction).
+    ///
+    /// Plugins usually know this value by writing it in a header at the start of their state.
+    #[inline]
+    pub fn with_total_size(mut self, total: u64) -> Self {
+        self.progress.total = Some(total);
+        self
+    }
+
+    /// Returns the current progress of the transfer.
+    #[inline]
+    pub fn progress(&self) -> StateProgress {
+        self.progress
+    }
+
+    /// Fills the given buffer with data from the input, one chunk at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChunkedStateError::UnexpectedEnd`] if the input ended before the buffer could be
+    /// filled, or [`ChunkedStateError::Cancelled`] if the progress callback cancelled the transfer.
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), ChunkedStateError> {
+        for chunk in buf.chunks_mut(self.chunk_size) {
+            self.input.read_exact(chunk)?;
+            self.advance(chunk.len())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads all the remaining data from the input, one chunk at a time.
+    ///
+    /// If a [total size](Self::with_total_size) was set, only up to that many bytes (minus the
+    /// bytes that were already read) are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChunkedStateError::Cancelled`] if the progress callback cancelled the transfer.
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, ChunkedStateError> {
+        let mut data = Vec::new();
+
+        loop {
+            let limit = match self.progress.total {
+                Some(total) => {
+                    let remaining = total.saturating_sub(self.progress.transferred);
+                    usize::try_from(remaining).map_or(self.chunk_size, |r| r.min(self.chunk_size))
+                }
+                None => self.chunk_size,
+            };
+
+            if limit == 0 {
+                return Ok(data);
+            }
+
+            let start = data.len();
+            data.resize(start + limit, 0);
+
+            let read = match self.input.read(&mut data[start..]) {
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {
+                    data.truncate(start);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            data.truncate(start + read);
+
+            if read == 0 {
+                return Ok(data);
+            }
+
+            self.advance(read)?;
+        }
+    }
+
+    /// Consumes this reader, returning the underlying input.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+
+    fn advance(&mut self, len: usize) -> Result<(), ChunkedStateError> {
+        self.progress.transferred += len as u64;
+
+        match (self.on_progress)(&self.progress) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(ChunkedStateError::Cancelled),
+        }
+    }
+}
+
+/// Writes a plugin's state to an [`OutputStream`](clack_common::stream::OutputStream) (or any
+/// other [`Write`] implementation) in chunks, reporting progress after each chunk.
+///
+/// After each chunk, the progress callback is given the current [`StateProgress`]. It can
+/// return [`ControlFlow::Break`] to cooperatively cancel the transfer, in which case
+/// [`ChunkedStateError::Cancelled`] is returned.
+///
+/// See [`ChunkedStateReader`] for the reading counterpart.
+pub struct ChunkedStateWriter<W, F> {
+    output: W,
+    on_progress: F,
+    chunk_size: usize,
+    progress: StateProgress,
+}
+
+impl<W: Write, F: FnMut(&StateProgress) -> ControlFlow<()>> ChunkedStateWriter<W, F> {
+    /// Creates a new chunked writer over the given output, reporting progress to the given
+    /// callback.
+    pub fn new(output: W, on_progress: F) -> Self {
+        Self {
+            output,
+            on_progress,
+            chunk_size: DEFAULT_STATE_CHUNK_SIZE,
+            progress: StateProgress {
+                transferred: 0,
+                total: None,
+            },
+        }
+    }
+
+    /// Sets the size of the chunks to write, in bytes.
+    ///
+    /// Defaults to [`DEFAULT_STATE_CHUNK_SIZE`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    #[inline]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be non-zero");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the total number of bytes that are going to be written, which is used to compute the
+    /// [progress fraction](StateProgress::fraction).
+    #[inline]
+    pub fn with_total_size(mut self, total: u64) -> Self {
+        self.progress.total = Some(total);
+        self
+    }
+
+    /// Returns the current progress of the transfer.
+    #[inline]
+    pub fn progress(&self) -> StateProgress {
+        self.progress
+    }
+
+    /// Writes all of the given data to the output, one chunk at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChunkedStateError::Io`] if the output failed, or
+    /// [`ChunkedStateError::Cancelled`] if the progress callback cancelled the transfer.
+    pub fn write_all(&mut self, data: &[u8]) -> Result<(), ChunkedStateError> {
+        for chunk in data.chunks(self.chunk_size) {
+            self.output.write_all(chunk)?;
+            self.progress.transferred += chunk.len() as u64;
+
+            if let ControlFlow::Break(()) = (self.on_progress)(&self.progress) {
+                return Err(ChunkedStateError::Cancelled);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the underlying output, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChunkedStateError::Io`] if flushing the output failed.
+    pub fn finish(mut self) -> Result<W, ChunkedStateError> {
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transfers_in_chunks() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+
+        let mut reports = Vec::new();
+        let mut writer = ChunkedStateWriter::new(Vec::new(), |p: &StateProgress| {
+            reports.push(*p);
+            ControlFlow::Continue(())
+        })
+        .with_chunk_size(300)
+        .with_total_size(1000);
+
+        writer.write_all(&data).unwrap();
+        let written = writer.finish().unwrap();
+        assert_eq!(written, data);
+
+        let transferred: Vec<u64> = reports.iter().map(|p| p.transferred).collect();
+        assert_eq!(transferred, [300, 600, 900, 1000]);
+        assert_eq!(reports[3].fraction(), Some(1.0));
+
+        let mut input = &written[..];
+        let mut header = [0; 10];
+        let mut reader =
+            ChunkedStateReader::new(&mut input, |_: &StateProgress| ControlFlow::Continue(()))
+                .with_chunk_size(300)
+                .with_total_size(1000);
+
+        reader.read_exact(&mut header).unwrap();
+        let rest = reader.read_to_end().unwrap();
+        assert_eq!(reader.progress().transferred, 1000);
+        assert_eq!(header, data[..10]);
+        assert_eq!(rest, data[10..]);
+
+        let mut input = &written[..500];
+        let mut buf = vec![0; 1000];
+        let mut reader =
+            ChunkedStateReader::new(&mut input, |_: &StateProgress| ControlFlow::Continue(()));
+        assert!(matches!(
+            reader.read_exact(&mut buf),
+            Err(ChunkedStateError::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn cancels_transfers() {
+        let data = vec![0; 1000];
+
+        let mut writer = ChunkedStateWriter::new(Vec::new(), |p: &StateProgress| {
+            if p.transferred >= 200 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .with_chunk_size(100);
+
+        assert!(matches!(
+            writer.write_all(&data),
+            Err(ChunkedStateError::Cancelled)
+        ));
+        assert_eq!(writer.progress().transferred, 200);
+
+        let mut input = &data[..];
+        let mut reader =
+            ChunkedStateReader::new(&mut input, |_: &StateProgress| ControlFlow::Break(()))
+                .with_chunk_size(100);
+        assert!(matches!(
+            reader.read_to_end(),
+            Err(ChunkedStateError::Cancelled)
+        ));
+        assert_eq!(reader.progress().fraction(), None);
+    }
+}
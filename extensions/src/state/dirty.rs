@@ -0,0 +1,216 @@
+use super::HostStateImpl;
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
+
+type AutosaveHook = Box<dyn FnMut() -> bool>;
+
+/// A host-side helper tracking whether a plugin instance's state changed since it was last saved
+/// or loaded.
+///
+/// Plugins notify the host that their state changed through the [`HostStateImpl::mark_dirty`]
+/// callback, which this type implements. Hosts can use one tracker per plugin instance, and
+/// forward that callback to it:
+///
+/// ```
+/// use clack_extensions::state::{DirtyTracker, HostStateImpl};
+///
+/// struct MyHostMainThread {
+///     dirty: DirtyTracker,
+/// }
+///
+/// impl HostStateImpl for MyHostMainThread {
+///     fn mark_dirty(&mut self) {
+///         self.dirty.mark_dirty();
+///     }
+/// }
+/// ```
+///
+/// The time of the first and last changes since the last save are recorded, and the host can
+/// then query them to e.g. prompt the user to save their project before quitting.
+///
+/// # Autosave
+///
+/// An autosave hook can be set using [`with_autosave`](Self::with_autosave). It is called from
+/// [`poll`](Self::poll) once the plugin hasn't marked its state dirty for the given delay, which
+/// avoids saving the state repeatedly while e.g. the user is still turning a knob. The hook
+/// returns whether saving succeeded, in which case the tracker is cleared.
+pub struct DirtyTracker {
+    dirty_since: Option<Instant>,
+    last_marked: Option<Instant>,
+    mark_count: u32,
+    autosave: Option<(Duration, AutosaveHook)>,
+}
+
+impl DirtyTracker {
+    /// Creates a new tracker, with a clean state and no autosave hook.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            dirty_since: None,
+            last_marked: None,
+            mark_count: 0,
+            autosave: None,
+        }
+    }
+
+    /// Sets the hook to be called by [`poll`](Self::poll) once the state has been dirty, and
+    /// left untouched for the given delay.
+    ///
+    /// The hook must return `true` if the state was successfully saved, which clears the tracker.
+    /// If it returns `false`, it will be called again on the next poll.
+    pub fn with_autosave(mut self, delay: Duration, hook: impl FnMut() -> bool + 'static) -> Self {
+        self.autosave = Some((delay, Box::new(hook)));
+        self
+    }
+
+    /// Marks the state as dirty, at the current time.
+    #[inline]
+    pub fn mark_dirty(&mut self) {
+        self.mark_dirty_at(Instant::now())
+    }
+
+    /// Marks the state as dirty, at the given time.
+    pub fn mark_dirty_at(&mut self, now: Instant) {
+        self.dirty_since.get_or_insert(now);
+        self.last_marked = Some(now);
+        self.mark_count = self.mark_count.saturating_add(1);
+    }
+
+    /// Returns `true` if the state changed since it was last saved or loaded.
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_since.is_some()
+    }
+
+    /// Returns when the state was first marked dirty since it was last saved or loaded, or `None`
+    /// if it is clean.
+    #[inline]
+    pub fn dirty_since(&self) -> Option<Instant> {
+        self.dirty_since
+    }
+
+    /// Returns when the state was last marked dirty, or `None` if it is clean.
+    #[inline]
+    pub fn last_marked(&self) -> Option<Instant> {
+        self.last_marked
+    }
+
+    /// Returns how many times the state was marked dirty since it was last saved or loaded.
+    #[inline]
+    pub fn mark_count(&self) -> u32 {
+        self.mark_count
+    }
+
+    /// Marks the state as clean, e.g. after it was saved or loaded.
+    #[inline]
+    pub fn clear_dirty(&mut self) {
+        self.dirty_since = None;
+        self.last_marked = None;
+        self.mark_count = 0;
+    }
+
+    /// Calls the autosave hook if the state is dirty and an autosave is due.
+    ///
+    /// This should be called periodically from the main thread. This returns `true` if the hook
+    /// was called, and successfully saved the state.
+    #[inline]
+    pub fn poll(&mut self) -> bool {
+        self.poll_at(Instant::now())
+    }
+
+    /// Calls the autosave hook if the state is dirty and an autosave is due at the given time.
+    ///
+    /// See [`poll`](Self::poll).
+    pub fn poll_at(&mut self, now: Instant) -> bool {
+        let Some(last_marked) = self.last_marked else {
+            return false;
+        };
+
+        let Some((delay, hook)) = &mut self.autosave else {
+            return false;
+        };
+
+        if now.saturating_duration_since(last_marked) < *delay {
+            return false;
+        }
+
+        let saved = hook();
+        if saved {
+            self.clear_dirty();
+        }
+
+        saved
+    }
+}
+
+impl Default for DirtyTracker {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostStateImpl for DirtyTracker {
+    #[inline]
+    fn mark_dirty(&mut self) {
+        DirtyTracker::mark_dirty(self)
+    }
+}
+
+impl Debug for DirtyTracker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirtyTracker")
+            .field("dirty_since", &self.dirty_since)
+            .field("last_marked", &self.last_marked)
+            .field("mark_count", &self.mark_count)
+            .field("autosave_delay", &self.autosave.as_ref().map(|(d, _)| d))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn tracks_dirty_state_and_autosaves() {
+        let saves = Rc::new(Cell::new(0));
+        let succeed = Rc::new(Cell::new(false));
+
+        let mut tracker = DirtyTracker::new().with_autosave(Duration::from_secs(2), {
+            let saves = saves.clone();
+            let succeed = succeed.clone();
+            move || {
+                saves.set(saves.get() + 1);
+                succeed.get()
+            }
+        });
+
+        let start = Instant::now();
+        assert!(!tracker.is_dirty());
+        assert!(!tracker.poll_at(start + Duration::from_secs(10)));
+
+        tracker.mark_dirty_at(start);
+        tracker.mark_dirty_at(start + Duration::from_secs(1));
+        assert!(tracker.is_dirty());
+        assert_eq!(tracker.mark_count(), 2);
+        assert_eq!(tracker.dirty_since(), Some(start));
+
+        // Autosaves are debounced from the last change.
+        assert!(!tracker.poll_at(start + Duration::from_secs(2)));
+        assert_eq!(saves.get(), 0);
+
+        // Failed saves keep the state dirty.
+        assert!(!tracker.poll_at(start + Duration::from_secs(3)));
+        assert_eq!(saves.get(), 1);
+        assert!(tracker.is_dirty());
+
+        succeed.set(true);
+        assert!(tracker.poll_at(start + Duration::from_secs(3)));
+        assert_eq!(saves.get(), 2);
+        assert!(!tracker.is_dirty());
+        assert_eq!(tracker.mark_count(), 0);
+    }
+}
@@ -9,6 +9,8 @@ impl PluginState {
         plugin: &mut PluginMainThreadHandle,
         reader: &mut R,
     ) -> Result<(), StateError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_state.load");
+
         let mut stream = InputStream::from_reader(reader);
 
         // SAFETY: This type ensures the function pointer is valid.
@@ -16,13 +18,14 @@ impl PluginState {
             (plugin
                 .use_extension(&self.0)
                 .load
-                .ok_or(StateError { saving: false })?)(
-                plugin.as_raw(), stream.as_raw_mut()
+                .ok_or(StateError::LoadError(CallFailure::Unsupported))?)(
+                plugin.as_raw(),
+                stream.as_raw_mut(),
             )
         } {
             Ok(())
         } else {
-            Err(StateError { saving: false })
+            Err(StateError::LoadError(CallFailure::Declined))
         }
     }
 
@@ -31,6 +34,8 @@ impl PluginState {
         plugin: &mut PluginMainThreadHandle,
         writer: &mut W,
     ) -> Result<(), StateError> {
+        crate::utils::trace_call!(plugin, "clap_plugin_state.save");
+
         let mut stream = OutputStream::from_writer(writer);
 
         // SAFETY: This type ensures the function pointer is valid.
@@ -38,13 +43,14 @@ impl PluginState {
             (plugin
                 .use_extension(&self.0)
                 .save
-                .ok_or(StateError { saving: true })?)(
-                plugin.as_raw(), stream.as_raw_mut()
+                .ok_or(StateError::SaveError(CallFailure::Unsupported))?)(
+                plugin.as_raw(),
+                stream.as_raw_mut(),
             )
         } {
             Ok(())
         } else {
-            Err(StateError { saving: true })
+            Err(StateError::SaveError(CallFailure::Declined))
         }
     }
 }
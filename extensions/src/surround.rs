@@ -0,0 +1,16 @@
+//! Allows plugins and hosts to agree on the speaker layout of surround audio ports.
+//!
+//! Surround ports (with the [`SURROUND_PORT_TYPE`] port type) carry one channel per speaker.
+//! Plugins declare which speaker each channel of their ports maps to through a channel map, and
+//! hosts use the [`ChannelRemap`] helper to route their own channel order (e.g.
+//! [SMPTE or Film](ChannelOrder)) into it, so that multichannel routing is not manual index math.
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v4`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v4;
+
+pub use v4::*;
@@ -0,0 +1,446 @@
+//! Revision 4 of the Surround draft extension (`clap.surround.draft/4`).
+
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::ext::draft::surround::*;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+
+mod layout;
+pub use layout::*;
+
+/// The audio port type of surround ports.
+pub const SURROUND_PORT_TYPE: &CStr = CLAP_PORT_SURROUND;
+
+/// The Plugin-side of the Surround extension.
+#[derive(Copy, Clone)]
+pub struct PluginSurround(RawExtension<PluginExtensionSide, clap_plugin_surround>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginSurround {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_SURROUND;
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginSurround {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_surround> {
+        self.0
+    }
+}
+
+/// The Host-side of the Surround extension.
+#[derive(Copy, Clone)]
+pub struct HostSurround(RawExtension<HostExtensionSide, clap_host_surround>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostSurround {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_SURROUND;
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl HostSurround {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_surround> {
+        self.0
+    }
+}
+
+/// The speaker a channel of a surround port is mapped to.
+///
+/// This wraps the raw speaker position used by CLAP, so that positions unknown to this
+/// implementation are preserved as-is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct SurroundChannel(u8);
+
+impl SurroundChannel {
+    /// Front left.
+    pub const FRONT_LEFT: Self = Self(CLAP_SURROUND_FL as u8);
+    /// Front right.
+    pub const FRONT_RIGHT: Self = Self(CLAP_SURROUND_FR as u8);
+    /// Front center.
+    pub const FRONT_CENTER: Self = Self(CLAP_SURROUND_FC as u8);
+    /// Low-frequency effects.
+    pub const LOW_FREQUENCY: Self = Self(CLAP_SURROUND_LFE as u8);
+    /// Back left.
+    pub const BACK_LEFT: Self = Self(CLAP_SURROUND_BL as u8);
+    /// Back right.
+    pub const BACK_RIGHT: Self = Self(CLAP_SURROUND_BR as u8);
+    /// Front left of center.
+    pub const FRONT_LEFT_OF_CENTER: Self = Self(CLAP_SURROUND_FLC as u8);
+    /// Front right of center.
+    pub const FRONT_RIGHT_OF_CENTER: Self = Self(CLAP_SURROUND_FRC as u8);
+    /// Back center.
+    pub const BACK_CENTER: Self = Self(CLAP_SURROUND_BC as u8);
+    /// Side left.
+    pub const SIDE_LEFT: Self = Self(CLAP_SURROUND_SL as u8);
+    /// Side right.
+    pub const SIDE_RIGHT: Self = Self(CLAP_SURROUND_SR as u8);
+    /// Top center.
+    pub const TOP_CENTER: Self = Self(CLAP_SURROUND_TC as u8);
+    /// Top front left.
+    pub const TOP_FRONT_LEFT: Self = Self(CLAP_SURROUND_TFL as u8);
+    /// Top front center.
+    pub const TOP_FRONT_CENTER: Self = Self(CLAP_SURROUND_TFC as u8);
+    /// Top front right.
+    pub const TOP_FRONT_RIGHT: Self = Self(CLAP_SURROUND_TFR as u8);
+    /// Top back left.
+    pub const TOP_BACK_LEFT: Self = Self(CLAP_SURROUND_TBL as u8);
+    /// Top back center.
+    pub const TOP_BACK_CENTER: Self = Self(CLAP_SURROUND_TBC as u8);
+    /// Top back right.
+    pub const TOP_BACK_RIGHT: Self = Self(CLAP_SURROUND_TBR as u8);
+
+    const LABELS: [&'static str; 18] = [
+        "FL", "FR", "FC", "LFE", "BL", "BR", "FLC", "FRC", "BC", "SL", "SR", "TC", "TFL", "TFC",
+        "TFR", "TBL", "TBC", "TBR",
+    ];
+
+    /// Creates a surround channel from its raw CLAP speaker position.
+    #[inline]
+    pub const fn from_raw(raw: u8) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw CLAP speaker position of this channel.
+    #[inline]
+    pub const fn to_raw(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the short, conventional label of this speaker (e.g. `FL` or `LFE`), or `None`
+    /// if its position is unknown to this implementation.
+    #[inline]
+    pub fn label(self) -> Option<&'static str> {
+        Self::LABELS.get(self.0 as usize).copied()
+    }
+
+    /// Returns the bit of this speaker in a [`SurroundChannelMask`], or an empty mask if its
+    /// position doesn't fit into one.
+    #[inline]
+    pub fn mask(self) -> SurroundChannelMask {
+        match 1u64.checked_shl(self.0 as u32) {
+            Some(bit) => SurroundChannelMask::from_bits_retain(bit),
+            None => SurroundChannelMask::empty(),
+        }
+    }
+}
+
+impl Display for SurroundChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.label() {
+            Some(label) => f.write_str(label),
+            None => write!(f, "Unknown({})", self.0),
+        }
+    }
+}
+
+bitflags! {
+    /// A set of speakers, as used by hosts to ask plugins whether they support a given layout.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct SurroundChannelMask: u64 {
+        /// Front left.
+        const FRONT_LEFT = 1 << CLAP_SURROUND_FL;
+        /// Front right.
+        const FRONT_RIGHT = 1 << CLAP_SURROUND_FR;
+        /// Front center.
+        const FRONT_CENTER = 1 << CLAP_SURROUND_FC;
+        /// Low-frequency effects.
+        const LOW_FREQUENCY = 1 << CLAP_SURROUND_LFE;
+        /// Back left.
+        const BACK_LEFT = 1 << CLAP_SURROUND_BL;
+        /// Back right.
+        const BACK_RIGHT = 1 << CLAP_SURROUND_BR;
+        /// Front left of center.
+        const FRONT_LEFT_OF_CENTER = 1 << CLAP_SURROUND_FLC;
+        /// Front right of center.
+        const FRONT_RIGHT_OF_CENTER = 1 << CLAP_SURROUND_FRC;
+        /// Back center.
+        const BACK_CENTER = 1 << CLAP_SURROUND_BC;
+        /// Side left.
+        const SIDE_LEFT = 1 << CLAP_SURROUND_SL;
+        /// Side right.
+        const SIDE_RIGHT = 1 << CLAP_SURROUND_SR;
+        /// Top center.
+        const TOP_CENTER = 1 << CLAP_SURROUND_TC;
+        /// Top front left.
+        const TOP_FRONT_LEFT = 1 << CLAP_SURROUND_TFL;
+        /// Top front center.
+        const TOP_FRONT_CENTER = 1 << CLAP_SURROUND_TFC;
+        /// Top front right.
+        const TOP_FRONT_RIGHT = 1 << CLAP_SURROUND_TFR;
+        /// Top back left.
+        const TOP_BACK_LEFT = 1 << CLAP_SURROUND_TBL;
+        /// Top back center.
+        const TOP_BACK_CENTER = 1 << CLAP_SURROUND_TBC;
+        /// Top back right.
+        const TOP_BACK_RIGHT = 1 << CLAP_SURROUND_TBR;
+    }
+}
+
+impl SurroundChannelMask {
+    /// Returns the mask of all the speakers in the given channel map.
+    pub fn from_channels(channels: &[SurroundChannel]) -> Self {
+        channels
+            .iter()
+            .fold(Self::empty(), |mask, channel| mask | channel.mask())
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginSurround {
+        /// Returns `true` if the plugin supports the speaker layout described by the given mask.
+        pub fn is_channel_mask_supported(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            channel_mask: SurroundChannelMask,
+        ) -> bool {
+            crate::utils::trace_call!(plugin, "clap_plugin_surround.is_channel_mask_supported");
+
+            match plugin.use_extension(&self.0).is_channel_mask_supported {
+                // SAFETY: This type ensures the function pointer is valid.
+                Some(supported) => unsafe { supported(plugin.as_raw(), channel_mask.bits()) },
+                None => false,
+            }
+        }
+
+        /// Retrieves the channel map of the given port.
+        ///
+        /// The plugin writes the speaker of each of the port's channels into the given buffer,
+        /// which must be large enough to hold all of the port's channels. The filled part of the
+        /// buffer is returned.
+        pub fn get_channel_map<'b>(
+            &self,
+            plugin: &mut PluginMainThreadHandle,
+            is_input: bool,
+            port_index: u32,
+            buffer: &'b mut [SurroundChannel],
+        ) -> &'b [SurroundChannel] {
+            crate::utils::trace_call!(plugin, "clap_plugin_surround.get_channel_map");
+
+            let Some(get_channel_map) = plugin.use_extension(&self.0).get_channel_map else {
+                return &[];
+            };
+
+            let capacity = buffer.len().min(u32::MAX as usize);
+
+            // SAFETY: This type ensures the function pointer is valid. SurroundChannel is
+            // repr(transparent) over u8, and the buffer is valid for `capacity` writes.
+            let written = unsafe {
+                get_channel_map(
+                    plugin.as_raw(),
+                    is_input,
+                    port_index,
+                    buffer.as_mut_ptr().cast(),
+                    capacity as u32,
+                )
+            };
+
+            &buffer[..(written as usize).min(capacity)]
+        }
+    }
+
+    /// Implementation of the Host-side of the Surround extension.
+    pub trait HostSurroundImpl {
+        /// Informs the host that the plugin's channel maps have changed.
+        ///
+        /// The host must read the channel maps again, which can only be done while the plugin is
+        /// deactivated.
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostSurround
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostSurroundImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_surround {
+                changed: Some(changed::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<H: HostHandlers>(host: *const clap_host)
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostSurroundImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            host.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+
+    /// A writer for the plugin to write a port's channel map into the host-provided buffer.
+    pub struct SurroundChannelMapWriter<'a> {
+        buf: &'a mut [u8],
+        len: usize,
+    }
+
+    impl SurroundChannelMapWriter<'_> {
+        /// Returns how many channels the host-provided buffer can hold.
+        #[inline]
+        pub fn capacity(&self) -> usize {
+            self.buf.len()
+        }
+
+        /// Writes the given channel map.
+        ///
+        /// Channels that don't fit in the host-provided buffer are ignored.
+        pub fn set(&mut self, channel_map: &[SurroundChannel]) {
+            let len = channel_map.len().min(self.buf.len());
+
+            for (dst, channel) in self.buf.iter_mut().zip(&channel_map[..len]) {
+                *dst = channel.to_raw();
+            }
+
+            self.len = len;
+        }
+    }
+
+    impl HostSurround {
+        /// Informs the host that the plugin's channel maps have changed.
+        ///
+        /// This can only be called while the plugin is deactivated.
+        #[inline]
+        pub fn changed(&self, host: &mut HostMainThreadHandle) {
+            if let Some(changed) = host.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(host.as_raw()) }
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Surround extension.
+    pub trait PluginSurroundImpl {
+        /// Returns `true` if the plugin supports the speaker layout described by the given mask.
+        fn is_channel_mask_supported(&mut self, channel_mask: SurroundChannelMask) -> bool;
+
+        /// Writes the channel map of the given port into the given writer.
+        fn get_channel_map(
+            &mut self,
+            is_input: bool,
+            port_index: u32,
+            writer: &mut SurroundChannelMapWriter,
+        );
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginSurround
+    where
+        for<'a> P::MainThread<'a>: PluginSurroundImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_surround {
+                is_channel_mask_supported: Some(is_channel_mask_supported::<P>),
+                get_channel_map: Some(get_channel_map::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn is_channel_mask_supported<P: Plugin>(
+        plugin: *const clap_plugin,
+        channel_mask: u64,
+    ) -> bool
+    where
+        for<'a> P::MainThread<'a>: PluginSurroundImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            let channel_mask = SurroundChannelMask::from_bits_retain(channel_mask);
+            Ok(p.main_thread()
+                .as_mut()
+                .is_channel_mask_supported(channel_mask))
+        })
+        .unwrap_or(false)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_channel_map<P: Plugin>(
+        plugin: *const clap_plugin,
+        is_input: bool,
+        port_index: u32,
+        channel_map: *mut u8,
+        channel_map_capacity: u32,
+    ) -> u32
+    where
+        for<'a> P::MainThread<'a>: PluginSurroundImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            if channel_map.is_null() && channel_map_capacity > 0 {
+                return Err(PluginWrapperError::NulPtr("channel_map"));
+            }
+
+            let buf = crate::utils::slice_from_external_parts_mut(
+                channel_map,
+                channel_map_capacity as usize,
+            );
+            // The host-provided buffer may hold anything.
+            buf.fill(0);
+
+            let mut writer = SurroundChannelMapWriter { buf, len: 0 };
+            p.main_thread()
+                .as_mut()
+                .get_channel_map(is_input, port_index, &mut writer);
+
+            Ok(writer.len as u32)
+        })
+        .unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn labels_and_masks_match() {
+        let channels = [SurroundChannel::FRONT_LEFT, SurroundChannel::LOW_FREQUENCY];
+        let mask = SurroundChannelMask::from_channels(&channels);
+
+        assert_eq!(
+            mask,
+            SurroundChannelMask::FRONT_LEFT | SurroundChannelMask::LOW_FREQUENCY
+        );
+        assert_eq!(SurroundChannel::TOP_BACK_RIGHT.label(), Some("TBR"));
+        assert_eq!(SurroundChannel::from_raw(200).to_string(), "Unknown(200)");
+        assert!(SurroundChannel::from_raw(200).mask().is_empty());
+    }
+}
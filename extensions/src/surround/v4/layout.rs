@@ -0,0 +1,250 @@
+use super::*;
+
+/// A conventional channel order for speaker layouts, as used by hosts and file formats.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ChannelOrder {
+    /// The SMPTE / ITU order (e.g. `L R C LFE Ls Rs` for 5.1), also used by WAV files.
+    #[default]
+    Smpte,
+    /// The Film order (e.g. `L C R Ls Rs LFE` for 5.1), used by some film post-production
+    /// tools.
+    Film,
+}
+
+/// A common speaker layout.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SpeakerLayout {
+    /// A single, front center speaker.
+    Mono,
+    /// Front left and right speakers.
+    Stereo,
+    /// Front left, center and right speakers.
+    Lcr,
+    /// Front and back left and right speakers.
+    Quad,
+    /// 5.0: front left, center and right, and back left and right speakers.
+    Surround5_0,
+    /// 5.1: 5.0, with a low-frequency effects channel.
+    Surround5_1,
+    /// 7.1: 5.1, with side left and right speakers.
+    Surround7_1,
+}
+
+impl SpeakerLayout {
+    /// Returns the channel map of this layout, in the given channel order.
+    pub fn channels(self, order: ChannelOrder) -> &'static [SurroundChannel] {
+        use ChannelOrder::*;
+        use SurroundChannel as C;
+
+        match (self, order) {
+            (SpeakerLayout::Mono, _) => &[C::FRONT_CENTER],
+            (SpeakerLayout::Stereo, _) => &[C::FRONT_LEFT, C::FRONT_RIGHT],
+            (SpeakerLayout::Lcr, Smpte) => &[C::FRONT_LEFT, C::FRONT_RIGHT, C::FRONT_CENTER],
+            (SpeakerLayout::Lcr, Film) => &[C::FRONT_LEFT, C::FRONT_CENTER, C::FRONT_RIGHT],
+            (SpeakerLayout::Quad, _) => {
+                &[C::FRONT_LEFT, C::FRONT_RIGHT, C::BACK_LEFT, C::BACK_RIGHT]
+            }
+            (SpeakerLayout::Surround5_0, Smpte) => &[
+                C::FRONT_LEFT,
+                C::FRONT_RIGHT,
+                C::FRONT_CENTER,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+            ],
+            (SpeakerLayout::Surround5_0, Film) => &[
+                C::FRONT_LEFT,
+                C::FRONT_CENTER,
+                C::FRONT_RIGHT,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+            ],
+            (SpeakerLayout::Surround5_1, Smpte) => &[
+                C::FRONT_LEFT,
+                C::FRONT_RIGHT,
+                C::FRONT_CENTER,
+                C::LOW_FREQUENCY,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+            ],
+            (SpeakerLayout::Surround5_1, Film) => &[
+                C::FRONT_LEFT,
+                C::FRONT_CENTER,
+                C::FRONT_RIGHT,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+                C::LOW_FREQUENCY,
+            ],
+            (SpeakerLayout::Surround7_1, Smpte) => &[
+                C::FRONT_LEFT,
+                C::FRONT_RIGHT,
+                C::FRONT_CENTER,
+                C::LOW_FREQUENCY,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+                C::SIDE_LEFT,
+                C::SIDE_RIGHT,
+            ],
+            (SpeakerLayout::Surround7_1, Film) => &[
+                C::FRONT_LEFT,
+                C::FRONT_CENTER,
+                C::FRONT_RIGHT,
+                C::SIDE_LEFT,
+                C::SIDE_RIGHT,
+                C::BACK_LEFT,
+                C::BACK_RIGHT,
+                C::LOW_FREQUENCY,
+            ],
+        }
+    }
+
+    /// Returns the number of channels of this layout.
+    #[inline]
+    pub fn channel_count(self) -> usize {
+        self.channels(ChannelOrder::Smpte).len()
+    }
+
+    /// Returns the mask of all the speakers of this layout.
+    #[inline]
+    pub fn mask(self) -> SurroundChannelMask {
+        SurroundChannelMask::from_channels(self.channels(ChannelOrder::Smpte))
+    }
+}
+
+/// A mapping from the channels of one channel map to the channels of another, e.g. from the
+/// host's channel order to a plugin's declared channel map.
+///
+/// Each destination channel is fed by the source channel of the same speaker, if there is one.
+/// Destination channels without a matching source channel are silenced.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChannelRemap {
+    sources: Vec<Option<usize>>,
+    source_count: usize,
+}
+
+impl ChannelRemap {
+    /// Creates a mapping from the `source` channel map to the `destination` channel map.
+    ///
+    /// If a speaker appears more than once in the source channel map, its first channel is used.
+    pub fn new(source: &[SurroundChannel], destination: &[SurroundChannel]) -> Self {
+        Self {
+            sources: destination
+                .iter()
+                .map(|channel| source.iter().position(|c| c == channel))
+                .collect(),
+            source_count: source.len(),
+        }
+    }
+
+    /// Returns the index of the source channel feeding each destination channel, or `None` if
+    /// there is no matching source channel.
+    #[inline]
+    pub fn sources(&self) -> &[Option<usize>] {
+        &self.sources
+    }
+
+    /// Returns the index of the source channel feeding the given destination channel, if any.
+    #[inline]
+    pub fn source_of(&self, destination_index: usize) -> Option<usize> {
+        self.sources.get(destination_index).copied().flatten()
+    }
+
+    /// Returns `true` if both channel maps have the same number of channels, and every
+    /// destination channel is fed by the source channel at the same index, i.e. if both channel
+    /// maps are identical.
+    pub fn is_identity(&self) -> bool {
+        self.sources.len() == self.source_count
+            && (self.sources.iter().enumerate()).all(|(index, source)| *source == Some(index))
+    }
+
+    /// Returns `true` if every destination channel is fed by a source channel.
+    pub fn is_complete(&self) -> bool {
+        self.sources.iter().all(Option::is_some)
+    }
+
+    /// Reorders a slice of per-channel items (e.g. channel buffer pointers) from the source
+    /// order to the destination order.
+    ///
+    /// Destination items without a matching source item, or whose source item is missing from
+    /// `source`, are set to `missing`. Extra destination items are left untouched.
+    pub fn reorder<T: Copy>(&self, source: &[T], destination: &mut [T], missing: T) {
+        for (dst, index) in destination.iter_mut().zip(&self.sources) {
+            *dst = index
+                .and_then(|i| source.get(i))
+                .copied()
+                .unwrap_or(missing);
+        }
+    }
+
+    /// Copies the samples of the source channel buffers into the destination channel buffers,
+    /// following this mapping.
+    ///
+    /// Destination channels without a matching source channel are silenced. If buffers have
+    /// different lengths, only their common part is copied, and the rest of each destination
+    /// buffer is left untouched.
+    pub fn copy<T: Copy + Default>(
+        &self,
+        source: &[impl AsRef<[T]>],
+        destination: &mut [impl AsMut<[T]>],
+    ) {
+        for (dst, index) in destination.iter_mut().zip(&self.sources) {
+            let dst = dst.as_mut();
+
+            match index.and_then(|i| source.get(i)) {
+                Some(src) => {
+                    let src = src.as_ref();
+                    let len = src.len().min(dst.len());
+                    dst[..len].copy_from_slice(&src[..len]);
+                }
+                None => dst.fill(T::default()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn remaps_smpte_to_film() {
+        let smpte = SpeakerLayout::Surround5_1.channels(ChannelOrder::Smpte);
+        let film = SpeakerLayout::Surround5_1.channels(ChannelOrder::Film);
+
+        let remap = ChannelRemap::new(smpte, film);
+        assert!(remap.is_complete());
+        assert!(!remap.is_identity());
+        assert_eq!(
+            remap.sources(),
+            [Some(0), Some(2), Some(1), Some(4), Some(5), Some(3)]
+        );
+
+        let source: Vec<Vec<f32>> = (0..6).map(|i| vec![i as f32; 2]).collect();
+        let mut destination = vec![vec![-1.0f32; 2]; 6];
+        remap.copy(&source, &mut destination);
+        assert_eq!(destination[1], [2.0, 2.0]);
+        assert_eq!(destination[5], [3.0, 3.0]);
+
+        assert!(ChannelRemap::new(film, film).is_identity());
+
+        // Dropping source channels isn't an identity mapping.
+        assert!(!ChannelRemap::new(film, &film[..2]).is_identity());
+    }
+
+    #[test]
+    fn silences_missing_channels() {
+        let stereo = SpeakerLayout::Stereo.channels(ChannelOrder::Smpte);
+        let lcr = SpeakerLayout::Lcr.channels(ChannelOrder::Film);
+
+        let remap = ChannelRemap::new(stereo, lcr);
+        assert!(!remap.is_complete());
+        assert_eq!(remap.source_of(1), None);
+
+        let mut destination = [0; 3];
+        remap.reorder(&[10, 20], &mut destination, -1);
+        assert_eq!(destination, [10, -1, 20]);
+
+        let mut buffers = [[1.0f64; 4]; 3];
+        remap.copy(&[[5.0; 4], [6.0; 4]], &mut buffers);
+        assert_eq!(buffers, [[5.0; 4], [0.0; 4], [6.0; 4]]);
+    }
+}
@@ -8,7 +8,6 @@ use std::ffi::CStr;
 
 /// The Plugin-side of the Tail extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginTail(RawExtension<PluginExtensionSide, clap_plugin_tail>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -22,9 +21,19 @@ unsafe impl Extension for PluginTail {
     }
 }
 
+impl PluginTail {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_tail> {
+        self.0
+    }
+}
+
 /// The Host-side of the Tail extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostTail(RawExtension<HostExtensionSide, clap_host_tail>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -38,6 +47,17 @@ unsafe impl Extension for HostTail {
     }
 }
 
+impl HostTail {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_tail> {
+        self.0
+    }
+}
+
 /// The length of a plugin's tail, which can potentially be infinite.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum TailLength {
@@ -107,9 +127,30 @@ mod host {
     use clack_host::extensions::prelude::*;
 
     impl PluginTail {
-        /// Returns the plugin's [`TailLength`].
+        /// Returns the plugin's [`TailLength`], from the audio thread.
+        ///
+        /// See [`get_on_main_thread`](Self::get_on_main_thread) to query it from the main thread
+        /// instead.
         #[inline]
         pub fn get(&self, plugin: &PluginAudioProcessorHandle) -> TailLength {
+            match plugin.use_extension(&self.0).get {
+                Some(get) => {
+                    let _audio_thread = AudioThreadGuard::enter();
+
+                    // SAFETY: This type ensures the function pointer is valid.
+                    TailLength::from_raw(unsafe { get(plugin.as_raw()) })
+                }
+                None => TailLength::default(),
+            }
+        }
+
+        /// Returns the plugin's [`TailLength`], from the main thread.
+        ///
+        /// The plugin must be active, otherwise [`TailLength::default`] is returned.
+        #[inline]
+        pub fn get_on_main_thread(&self, plugin: &mut PluginMainThreadHandle) -> TailLength {
+            crate::utils::trace_call!(plugin, "clap_plugin_tail.get");
+
             match plugin.use_extension(&self.0).get {
                 // SAFETY: This type ensures the function pointer is valid.
                 Some(get) => TailLength::from_raw(unsafe { get(plugin.as_raw()) }),
@@ -191,7 +232,12 @@ mod plugin {
         for<'a> P::AudioProcessor<'a>: PluginTailImpl,
     {
         PluginWrapper::<P>::handle(plugin, |plugin| {
-            Ok(plugin.audio_processor()?.as_ref().get().to_raw())
+            // The host may query the tail length from the main thread as well.
+            Ok(plugin
+                .audio_processor_on_main_or_audio_thread()?
+                .as_ref()
+                .get()
+                .to_raw())
         })
         .unwrap_or_else(|| TailLength::default().to_raw())
     }
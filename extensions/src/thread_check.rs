@@ -2,8 +2,30 @@ use clack_common::extensions::{Extension, HostExtensionSide, RawExtension};
 use clap_sys::ext::thread_check::{clap_host_thread_check, CLAP_EXT_THREAD_CHECK};
 use std::ffi::CStr;
 
+/// The Host-side of the Thread Check extension.
+///
+/// This extension allows plugins to ask the host whether the current thread is the host's main
+/// thread or one of its audio threads.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "clack-plugin")]
+/// # mod example {
+/// use clack_extensions::thread_check::HostThreadCheck;
+/// use clack_plugin::host::HostSharedHandle;
+///
+/// fn assert_on_main_thread(host: &HostSharedHandle) {
+///     let is_main_thread = host
+///         .get_extension::<HostThreadCheck>()
+///         .and_then(|thread_check| thread_check.is_main_thread(host));
+///
+///     // `None` means the host cannot tell, which is not a violation.
+///     debug_assert_ne!(is_main_thread, Some(false));
+/// }
+/// # }
+/// ```
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostThreadCheck(RawExtension<HostExtensionSide, clap_host_thread_check>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -17,18 +39,35 @@ unsafe impl Extension for HostThreadCheck {
     }
 }
 
+impl HostThreadCheck {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_thread_check> {
+        self.0
+    }
+}
+
 #[cfg(feature = "clack-plugin")]
 mod plugin {
     use super::*;
     use clack_plugin::host::HostSharedHandle;
 
     impl HostThreadCheck {
+        /// Returns whether the current thread is the host's main thread.
+        ///
+        /// This returns `None` if the host doesn't implement this check.
         #[inline]
         pub fn is_main_thread(&self, host: &HostSharedHandle) -> Option<bool> {
             // SAFETY: This type ensures the function pointer is valid.
             Some(unsafe { host.use_extension(&self.0).is_main_thread?(host.as_raw()) })
         }
 
+        /// Returns whether the current thread is one of the host's audio threads.
+        ///
+        /// This returns `None` if the host doesn't implement this check.
         #[inline]
         pub fn is_audio_thread(&self, host: &HostSharedHandle) -> Option<bool> {
             // SAFETY: This type ensures the function pointer is valid.
@@ -42,12 +81,94 @@ mod host {
     use crate::thread_check::HostThreadCheck;
     use clack_host::extensions::prelude::*;
     use clap_sys::ext::thread_check::clap_host_thread_check;
+    use std::thread::ThreadId;
 
     pub trait HostThreadCheckImpl {
         fn is_main_thread(&self) -> bool;
         fn is_audio_thread(&self) -> bool;
     }
 
+    /// A ready-made implementation of the Thread Check extension.
+    ///
+    /// The main thread is the thread this type was created on. Hosts should create it from their
+    /// [`SharedHandler`](clack_host::host::SharedHandler) constructor, which runs on the main thread when the plugin instance is
+    /// created.
+    ///
+    /// Audio threads are any thread that is currently calling one of the plugin's audio-thread
+    /// functions through Clack, as registered by the audio processor wrapper. See
+    /// [`is_audio_thread`](clack_host::host::is_audio_thread) for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_extensions::thread_check::{DefaultThreadCheck, HostThreadCheckImpl};
+    ///
+    /// struct MyHostShared {
+    ///     thread_check: DefaultThreadCheck,
+    /// }
+    ///
+    /// impl HostThreadCheckImpl for MyHostShared {
+    ///     fn is_main_thread(&self) -> bool {
+    ///         self.thread_check.is_main_thread()
+    ///     }
+    ///
+    ///     fn is_audio_thread(&self) -> bool {
+    ///         self.thread_check.is_audio_thread()
+    ///     }
+    /// }
+    ///
+    /// let shared = MyHostShared {
+    ///     thread_check: DefaultThreadCheck::new(),
+    /// };
+    ///
+    /// assert!(shared.is_main_thread());
+    /// assert!(!shared.is_audio_thread());
+    /// ```
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    pub struct DefaultThreadCheck {
+        main_thread: ThreadId,
+    }
+
+    impl DefaultThreadCheck {
+        /// Creates a new thread check implementation, using the current thread as the main
+        /// thread.
+        #[inline]
+        pub fn new() -> Self {
+            Self::with_main_thread(std::thread::current().id())
+        }
+
+        /// Creates a new thread check implementation, using the given thread as the main thread.
+        #[inline]
+        pub fn with_main_thread(main_thread: ThreadId) -> Self {
+            Self { main_thread }
+        }
+
+        /// Returns the identifier of the main thread.
+        #[inline]
+        pub fn main_thread(&self) -> ThreadId {
+            self.main_thread
+        }
+    }
+
+    impl Default for DefaultThreadCheck {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl HostThreadCheckImpl for DefaultThreadCheck {
+        #[inline]
+        fn is_main_thread(&self) -> bool {
+            std::thread::current().id() == self.main_thread
+        }
+
+        #[inline]
+        fn is_audio_thread(&self) -> bool {
+            clack_host::host::is_audio_thread()
+        }
+    }
+
     // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
     unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostThreadCheck
     where
@@ -1,6 +1,7 @@
 //! Allows plugins to use a host's thread pool for multithreaded audio processing.
 #![deny(missing_docs)]
 
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clap_sys::ext::thread_pool::*;
 use std::error::Error;
@@ -9,7 +10,6 @@ use std::fmt::{Display, Formatter};
 
 /// Plugin-side of the ThreadPool extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginThreadPool(RawExtension<PluginExtensionSide, clap_plugin_thread_pool>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -23,9 +23,19 @@ unsafe impl Extension for PluginThreadPool {
     }
 }
 
+impl PluginThreadPool {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_thread_pool> {
+        self.0
+    }
+}
+
 /// Host-side of the ThreadPool extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostThreadPool(RawExtension<HostExtensionSide, clap_host_thread_pool>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -39,14 +49,45 @@ unsafe impl Extension for HostThreadPool {
     }
 }
 
+impl HostThreadPool {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_thread_pool> {
+        self.0
+    }
+}
+
 /// An error that occurred as a plugin requested access to the host's thread pool.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
-pub struct ThreadPoolRequestError;
+pub struct ThreadPoolRequestError {
+    failure: CallFailure,
+}
+
+impl ThreadPoolRequestError {
+    /// Creates a new error with the given [`CallFailure`] reason.
+    #[inline]
+    pub const fn new(failure: CallFailure) -> Self {
+        Self { failure }
+    }
+
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        self.failure
+    }
+}
 
 impl Display for ThreadPoolRequestError {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Failed to send execution request to the host's thread pool")
+        write!(
+            f,
+            "Failed to send execution request to the host's thread pool: {}",
+            self.failure
+        )
     }
 }
 
@@ -110,13 +151,13 @@ mod plugin {
             let request_exec = host
                 .use_extension(&self.0)
                 .request_exec
-                .ok_or(ThreadPoolRequestError)?;
+                .ok_or(ThreadPoolRequestError::new(CallFailure::Unsupported))?;
             // SAFETY: This type ensures the function pointer is valid.
             let success = unsafe { request_exec(host.as_raw(), task_count) };
 
             match success {
                 true => Ok(()),
-                false => Err(ThreadPoolRequestError),
+                false => Err(ThreadPoolRequestError::new(CallFailure::Declined)),
             }
         }
     }
@@ -183,6 +224,9 @@ mod host {
         /// The index of the requested task to execute is given, and must be in the `0..task_count` range.
         pub fn exec(&self, plugin: &PluginSharedHandle, task_index: u32) {
             if let Some(exec) = plugin.use_extension(&self.0).exec {
+                // Thread pool tasks are part of the plugin's audio processing.
+                let _audio_thread = AudioThreadGuard::enter();
+
                 // SAFETY: This type ensures the function pointer is valid.
                 unsafe { exec(plugin.as_raw_ptr(), task_index) }
             }
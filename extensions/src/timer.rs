@@ -5,6 +5,7 @@
 //! This extension allows plugins to register timers to the host, which will then proceed to call
 //! a plugin's callback at a given regular interval.
 
+use crate::error::CallFailure;
 use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
 use clap_sys::ext::timer_support::*;
 use std::error::Error;
@@ -13,7 +14,6 @@ use std::fmt::{Display, Formatter};
 
 /// Host-side of the Timer extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostTimer(RawExtension<HostExtensionSide, clap_host_timer_support>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -27,9 +27,19 @@ unsafe impl Extension for HostTimer {
     }
 }
 
+impl HostTimer {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_timer_support> {
+        self.0
+    }
+}
+
 /// Plugin-side of the Timer extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginTimer(RawExtension<PluginExtensionSide, clap_plugin_timer_support>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -43,6 +53,17 @@ unsafe impl Extension for PluginTimer {
     }
 }
 
+impl PluginTimer {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_timer_support> {
+        self.0
+    }
+}
+
 /// An identifier representing a timer given to a plugin.
 ///
 /// Each identifier must be unique for a specific plugin instance.
@@ -57,19 +78,35 @@ impl Display for TimerId {
 }
 
 /// Errors that can occur while setting up Timers.
+///
+/// Each variant identifies the call that failed, and carries the [`CallFailure`] reason.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum TimerError {
     /// The host failed or declined to register a timer.
-    RegisterError,
+    RegisterError(CallFailure),
     /// The host failed to unregister a timer.
-    UnregisterError,
+    UnregisterError(CallFailure),
+}
+
+impl TimerError {
+    /// Returns the reason why the call failed.
+    #[inline]
+    pub const fn failure(&self) -> CallFailure {
+        match *self {
+            TimerError::RegisterError(failure) | TimerError::UnregisterError(failure) => failure,
+        }
+    }
 }
 
 impl Display for TimerError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            TimerError::RegisterError => f.write_str("Failed to register CLAP Timer"),
-            TimerError::UnregisterError => f.write_str("Failed to unregister CLAP Timer"),
+            TimerError::RegisterError(failure) => {
+                write!(f, "Failed to register CLAP Timer: {failure}")
+            }
+            TimerError::UnregisterError(failure) => {
+                write!(f, "Failed to unregister CLAP Timer: {failure}")
+            }
         }
     }
 }
@@ -103,12 +140,12 @@ mod plugin {
             let register_timer = host
                 .use_extension(&self.0)
                 .register_timer
-                .ok_or(TimerError::RegisterError)?;
+                .ok_or(TimerError::RegisterError(CallFailure::Unsupported))?;
 
             // SAFETY: This type ensures the function pointer is valid.
             match unsafe { register_timer(host.as_raw(), period_ms, &mut id) } {
                 true => Ok(TimerId(id)),
-                false => Err(TimerError::RegisterError),
+                false => Err(TimerError::RegisterError(CallFailure::Declined)),
             }
         }
 
@@ -129,12 +166,12 @@ mod plugin {
             let unregister_timer = host
                 .use_extension(&self.0)
                 .unregister_timer
-                .ok_or(TimerError::UnregisterError)?;
+                .ok_or(TimerError::UnregisterError(CallFailure::Unsupported))?;
 
             // SAFETY: This type ensures the function pointer is valid.
             match unsafe { unregister_timer(host.as_raw(), timer_id.0) } {
                 true => Ok(()),
-                false => Err(TimerError::RegisterError),
+                false => Err(TimerError::UnregisterError(CallFailure::Declined)),
             }
         }
     }
@@ -174,6 +211,11 @@ mod plugin {
 #[cfg(feature = "clack-plugin")]
 pub use plugin::*;
 
+#[cfg(feature = "clack-plugin")]
+mod repaint;
+#[cfg(feature = "clack-plugin")]
+pub use repaint::*;
+
 #[cfg(feature = "clack-host")]
 mod host {
     use super::*;
@@ -267,6 +309,8 @@ mod host {
         /// it.
         #[inline]
         pub fn on_timer(&self, plugin: &mut PluginMainThreadHandle, timer_id: TimerId) {
+            crate::utils::trace_call!(plugin, "clap_plugin_timer_support.on_timer");
+
             if let Some(on_timer) = plugin.use_extension(&self.0).on_timer {
                 // SAFETY: This type ensures the function pointer is valid.
                 unsafe { on_timer(plugin.as_raw(), timer_id.0) }
@@ -0,0 +1,227 @@
+use super::*;
+use clack_plugin::host::{HostMainThreadHandle, HostSharedHandle};
+use clap_sys::host::clap_host;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A helper to drive a plugin GUI's repaints at a regular frame rate, using host-provided timers.
+///
+/// When started, this registers a timer to the host through the [`HostTimer`] extension. If the
+/// host doesn't support that extension, or refuses to register the timer, this falls back to
+/// spawning a background thread which periodically requests the host to call the plugin's
+/// [`on_main_thread`](clack_plugin::plugin::PluginMainThread::on_main_thread) callback instead.
+///
+/// In both cases, the plugin must forward both its [`PluginTimerImpl::on_timer`] and
+/// [`on_main_thread`](clack_plugin::plugin::PluginMainThread::on_main_thread) callbacks to this
+/// timer (using [`on_timer`](Self::on_timer) and [`on_main_thread`](Self::on_main_thread)
+/// respectively), which will call the given repaint closure whenever a new frame is due.
+///
+/// The timer is meant to be started when the GUI is shown, and [stopped](Self::stop) when it is
+/// hidden or destroyed. If this is dropped without being stopped, the background thread (if any)
+/// is stopped and joined, but the host timer (if any) is left registered.
+///
+/// The timer borrows the host for its lifetime `'a`. However, as its background thread (if any) is
+/// only stopped when the timer is stopped or dropped, the timer must never be leaked (e.g. using
+/// [`core::mem::forget`]). See [`start`](Self::start).
+pub struct RepaintTimer<'a> {
+    period_ms: u32,
+    strategy: RepaintStrategy<'a>,
+}
+
+enum RepaintStrategy<'a> {
+    HostTimer { timer: HostTimer, timer_id: TimerId },
+    Thread(RepaintThread<'a>),
+    Stopped,
+}
+
+impl<'a> RepaintTimer<'a> {
+    /// Starts a new repaint timer, targeting the given number of frames per second.
+    ///
+    /// Note the host may adjust the timer's period if it is too short.
+    ///
+    /// # Safety
+    ///
+    /// The returned timer must be either [stopped](Self::stop) or dropped before the plugin
+    /// instance is destroyed. In particular, it must not be leaked (e.g. using
+    /// [`core::mem::forget`] or a reference cycle): the fallback background thread would then keep
+    /// calling into the host after it has been destroyed.
+    pub unsafe fn start(host: &mut HostMainThreadHandle<'a>, fps: u32) -> Self {
+        let period_ms = (1000 / fps.max(1)).max(1);
+
+        let host_timer = host.get_extension::<HostTimer>().and_then(|timer| {
+            let timer_id = timer.register_timer(host, period_ms).ok()?;
+            Some(RepaintStrategy::HostTimer { timer, timer_id })
+        });
+
+        let strategy = match host_timer {
+            Some(strategy) => strategy,
+            None => RepaintStrategy::Thread(RepaintThread::spawn(host.shared(), period_ms)),
+        };
+
+        Self {
+            period_ms,
+            strategy,
+        }
+    }
+
+    /// Returns the target period between two frames, in milliseconds.
+    #[inline]
+    pub fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Returns `true` if this timer is driven by a host-provided timer, or `false` if it fell back
+    /// to a background thread, or if it was stopped.
+    #[inline]
+    pub fn uses_host_timer(&self) -> bool {
+        matches!(self.strategy, RepaintStrategy::HostTimer { .. })
+    }
+
+    /// Returns `true` if this timer is still running, i.e. it wasn't [stopped](Self::stop) yet.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        !matches!(self.strategy, RepaintStrategy::Stopped)
+    }
+
+    /// Handles a timer tick, calling the `repaint` closure if the tick belongs to this timer.
+    ///
+    /// This must be called from the plugin's [`PluginTimerImpl::on_timer`] implementation.
+    /// This returns `true` if `repaint` was called, `false` otherwise. Ticks from other timers
+    /// are ignored, and can be processed by the plugin as usual.
+    pub fn on_timer(&mut self, timer_id: TimerId, repaint: impl FnOnce()) -> bool {
+        match &self.strategy {
+            RepaintStrategy::HostTimer { timer_id: id, .. } if *id == timer_id => {
+                repaint();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles a main-thread callback, calling the `repaint` closure if a new frame was requested
+    /// by the background thread.
+    ///
+    /// This must be called from the plugin's
+    /// [`on_main_thread`](clack_plugin::plugin::PluginMainThread::on_main_thread) implementation.
+    /// This returns `true` if `repaint` was called, `false` otherwise, e.g. if the callback was
+    /// requested by the plugin for another reason.
+    pub fn on_main_thread(&mut self, repaint: impl FnOnce()) -> bool {
+        match &self.strategy {
+            RepaintStrategy::Thread(thread) if thread.take_frame_request() => {
+                repaint();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stops this timer, unregistering the host timer or stopping the background thread.
+    ///
+    /// The `repaint` closure will not be called anymore afterward. This does nothing if the
+    /// timer was already stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimerError::UnregisterError`] if the host failed to unregister the timer.
+    /// The timer is considered stopped regardless.
+    pub fn stop(&mut self, host: &mut HostMainThreadHandle) -> Result<(), TimerError> {
+        match core::mem::replace(&mut self.strategy, RepaintStrategy::Stopped) {
+            RepaintStrategy::HostTimer { timer, timer_id } => {
+                timer.unregister_timer(host, timer_id)
+            }
+            RepaintStrategy::Thread(thread) => {
+                drop(thread);
+                Ok(())
+            }
+            RepaintStrategy::Stopped => Ok(()),
+        }
+    }
+}
+
+/// A raw host pointer, sent to the background thread.
+struct SendHost(NonNull<clap_host>);
+
+// SAFETY: the background thread only uses the host's thread-safe functions.
+unsafe impl Send for SendHost {}
+
+impl SendHost {
+    /// # Safety
+    ///
+    /// The host pointer must still be valid for the returned lifetime.
+    #[inline]
+    unsafe fn into_handle<'a>(self) -> HostSharedHandle<'a> {
+        HostSharedHandle::from_raw(self.0)
+    }
+}
+
+/// A background thread periodically requesting main-thread callbacks from the host.
+///
+/// The thread is joined when this is dropped. Soundness relies on this actually being dropped,
+/// which is guaranteed by the caller of [`RepaintTimer::start`].
+struct RepaintThread<'a> {
+    frame_requested: Arc<AtomicBool>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    _host: PhantomData<HostSharedHandle<'a>>,
+}
+
+impl<'a> RepaintThread<'a> {
+    fn spawn(host: HostSharedHandle<'a>, period_ms: u32) -> Self {
+        let host = SendHost(NonNull::from(host.as_raw()));
+
+        let frame_requested = Arc::new(AtomicBool::new(false));
+        let (stop, stop_receiver) = channel::<()>();
+        let period = Duration::from_millis(period_ms as u64);
+
+        let thread = {
+            let frame_requested = frame_requested.clone();
+
+            std::thread::spawn(move || {
+                // SAFETY: the host is valid for 'a, and this thread is joined when the
+                // RepaintThread is dropped, which the caller of RepaintTimer::start guarantees
+                // happens before the plugin instance is destroyed.
+                let host = unsafe { host.into_handle() };
+
+                loop {
+                    match stop_receiver.recv_timeout(period) {
+                        Err(RecvTimeoutError::Timeout) => {
+                            // Don't flood the host with requests if the main thread is lagging behind.
+                            if !frame_requested.swap(true, Ordering::AcqRel) {
+                                host.request_callback();
+                            }
+                        }
+                        _ => return,
+                    }
+                }
+            })
+        };
+
+        Self {
+            frame_requested,
+            stop: Some(stop),
+            thread: Some(thread),
+            _host: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn take_frame_request(&self) -> bool {
+        self.frame_requested.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl Drop for RepaintThread<'_> {
+    fn drop(&mut self) {
+        // Dropping the sender wakes up and stops the thread.
+        drop(self.stop.take());
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Allows plugins to query information about the track they are placed on.
+//!
+//! This is a **draft** extension. Its latest revision is re-exported from this module, and older
+//! revisions are kept in their own versioned submodule (e.g. [`v1`]). See the
+//! [crate-level documentation](crate#draft-extensions) for more information.
+
+#![deny(missing_docs)]
+
+pub mod v1;
+
+pub use v1::*;
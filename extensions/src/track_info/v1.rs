@@ -0,0 +1,407 @@
+//! Revision 1 of the Track Info draft extension (`clap.track-info.draft/1`).
+//!
+//! This revision is ABI-compatible with the stable `clap.track-info/1` extension, which is also
+//! tried when querying or registering it.
+
+use bitflags::bitflags;
+use clack_common::extensions::{Extension, HostExtensionSide, PluginExtensionSide, RawExtension};
+use clap_sys::color::clap_color;
+use clap_sys::ext::draft::track_info::*;
+use clap_sys::string_sizes::CLAP_NAME_SIZE;
+use std::ffi::CStr;
+
+/// The identifier of the stable Track Info extension, which is ABI-compatible with this revision.
+const CLAP_EXT_TRACK_INFO_STABLE: &CStr = match CStr::from_bytes_with_nul(b"clap.track-info/1\0") {
+    Ok(identifier) => identifier,
+    Err(_) => panic!("Invalid extension identifier"),
+};
+
+/// The Plugin-side of the Track Info extension.
+#[derive(Copy, Clone)]
+pub struct PluginTrackInfo(RawExtension<PluginExtensionSide, clap_plugin_track_info>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for PluginTrackInfo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRACK_INFO;
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[CLAP_EXT_TRACK_INFO_STABLE];
+    type ExtensionSide = PluginExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl PluginTrackInfo {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_track_info> {
+        self.0
+    }
+}
+
+/// The Host-side of the Track Info extension.
+#[derive(Copy, Clone)]
+pub struct HostTrackInfo(RawExtension<HostExtensionSide, clap_host_track_info>);
+
+// SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
+unsafe impl Extension for HostTrackInfo {
+    const IDENTIFIER: &'static CStr = CLAP_EXT_TRACK_INFO;
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[CLAP_EXT_TRACK_INFO_STABLE];
+    type ExtensionSide = HostExtensionSide;
+
+    #[inline]
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+impl HostTrackInfo {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_track_info> {
+        self.0
+    }
+}
+
+bitflags! {
+    /// Flags describing the kind of track a plugin is placed on.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct TrackInfoFlags: u64 {
+        /// The track is a return track.
+        const IS_FOR_RETURN_TRACK = CLAP_TRACK_INFO_IS_FOR_RETURN_TRACK;
+        /// The track is a bus track.
+        const IS_FOR_BUS = CLAP_TRACK_INFO_IS_FOR_BUS;
+        /// The track is the master track.
+        const IS_FOR_MASTER = CLAP_TRACK_INFO_IS_FOR_MASTER;
+    }
+}
+
+/// A color, as displayed by the host for a track.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Color {
+    /// The alpha component.
+    pub alpha: u8,
+    /// The red component.
+    pub red: u8,
+    /// The green component.
+    pub green: u8,
+    /// The blue component.
+    pub blue: u8,
+}
+
+impl Color {
+    /// Creates a new color from the given raw, C-FFI compatible color.
+    #[inline]
+    pub const fn from_raw(raw: clap_color) -> Self {
+        Self {
+            alpha: raw.alpha,
+            red: raw.red,
+            green: raw.green,
+            blue: raw.blue,
+        }
+    }
+
+    /// Returns this color as a raw, C-FFI compatible color.
+    #[inline]
+    pub const fn to_raw(&self) -> clap_color {
+        clap_color {
+            alpha: self.alpha,
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+        }
+    }
+}
+
+/// Information about the track a plugin is placed on.
+///
+/// All fields but [`flags`](Self::flags) are optional, and are `None` if the host didn't
+/// provide them.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TrackInfo<'a> {
+    /// Flags describing the kind of track.
+    pub flags: TrackInfoFlags,
+    /// The user-facing name of the track.
+    pub name: Option<&'a [u8]>,
+    /// The color of the track.
+    pub color: Option<Color>,
+    /// The number of audio channels of the track, alongside the type of its audio port, if any.
+    ///
+    /// See the Audio Ports extension for the standard port types.
+    pub audio_channel: Option<(u32, Option<&'a CStr>)>,
+}
+
+impl<'a> TrackInfo<'a> {
+    /// Creates a new [`TrackInfo`] from a reference to the given raw, C-FFI compatible buffer.
+    ///
+    /// # Safety
+    ///
+    /// If it is non-null, the `audio_port_type` pointer of the buffer must be valid for reads
+    /// and point to a NULL-terminated string, valid for the lifetime `'a`.
+    pub unsafe fn from_raw(raw: &'a clap_track_info) -> Self {
+        let audio_channel = if raw.flags & CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL != 0 {
+            let port_type = if raw.audio_port_type.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(raw.audio_port_type))
+            };
+
+            Some((raw.audio_channel_count.max(0) as u32, port_type))
+        } else {
+            None
+        };
+
+        Self {
+            flags: TrackInfoFlags::from_bits_truncate(raw.flags),
+            name: (raw.flags & CLAP_TRACK_INFO_HAS_TRACK_NAME != 0)
+                .then(|| crate::utils::data_from_array_buf(&raw.name)),
+            color: (raw.flags & CLAP_TRACK_INFO_HAS_TRACK_COLOR != 0)
+                .then(|| Color::from_raw(raw.color)),
+            audio_channel,
+        }
+    }
+
+    /// Creates a new raw, C-FFI compatible track info buffer from this [`TrackInfo`].
+    ///
+    /// The returned buffer borrows this track info's audio port type, if any.
+    pub fn to_raw(&self) -> clap_track_info {
+        let mut flags = self.flags.bits();
+        let mut name = [0; CLAP_NAME_SIZE];
+
+        if let Some(track_name) = self.name {
+            flags |= CLAP_TRACK_INFO_HAS_TRACK_NAME;
+            // SAFETY: name is a valid pointer, as it comes from a &mut reference.
+            unsafe { crate::utils::write_to_array_buf(&mut name, track_name) }
+        }
+
+        if self.color.is_some() {
+            flags |= CLAP_TRACK_INFO_HAS_TRACK_COLOR;
+        }
+
+        if self.audio_channel.is_some() {
+            flags |= CLAP_TRACK_INFO_HAS_AUDIO_CHANNEL;
+        }
+
+        let (audio_channel_count, audio_port_type) = match self.audio_channel {
+            Some((count, port_type)) => (
+                count.min(i32::MAX as u32) as i32,
+                port_type.map_or(core::ptr::null(), CStr::as_ptr),
+            ),
+            None => (0, core::ptr::null()),
+        };
+
+        clap_track_info {
+            flags,
+            name,
+            color: self
+                .color
+                .unwrap_or(Color {
+                    alpha: 0,
+                    red: 0,
+                    green: 0,
+                    blue: 0,
+                })
+                .to_raw(),
+            audio_channel_count,
+            audio_port_type,
+        }
+    }
+}
+
+#[cfg(feature = "clack-host")]
+mod host {
+    use super::*;
+    use clack_host::extensions::prelude::*;
+
+    impl PluginTrackInfo {
+        /// Informs the plugin that the track information has changed.
+        #[inline]
+        pub fn changed(&self, plugin: &mut PluginMainThreadHandle) {
+            crate::utils::trace_call!(plugin, "clap_plugin_track_info.changed");
+
+            if let Some(changed) = plugin.use_extension(&self.0).changed {
+                // SAFETY: This type ensures the function pointer is valid.
+                unsafe { changed(plugin.as_raw()) }
+            }
+        }
+    }
+
+    /// Implementation of the Host-side of the Track Info extension.
+    pub trait HostTrackInfoImpl {
+        /// Returns information about the track the plugin is placed on, or `None` if it is
+        /// unavailable.
+        fn get(&mut self) -> Option<TrackInfo<'_>>;
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostTrackInfo
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostTrackInfoImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_host_track_info {
+                get: Some(get::<H>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get<H: HostHandlers>(
+        host: *const clap_host,
+        info: *mut clap_track_info,
+    ) -> bool
+    where
+        for<'h> <H as HostHandlers>::MainThread<'h>: HostTrackInfoImpl,
+    {
+        HostWrapper::<H>::handle(host, |host| {
+            if info.is_null() {
+                return Err(HostWrapperError::InvalidParameter(
+                    "Null clap_track_info output pointer",
+                ));
+            }
+
+            match host.main_thread().as_mut().get() {
+                Some(track_info) => {
+                    info.write(track_info.to_raw());
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "clack-host")]
+pub use host::*;
+
+#[cfg(feature = "clack-plugin")]
+mod plugin {
+    use super::*;
+    use clack_plugin::extensions::prelude::*;
+    use std::mem::MaybeUninit;
+
+    /// A plugin-provided buffer for the host to write the track information into.
+    #[derive(Clone)]
+    pub struct TrackInfoBuffer {
+        inner: MaybeUninit<clap_track_info>,
+    }
+
+    impl Default for TrackInfoBuffer {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl TrackInfoBuffer {
+        /// Creates an uninitialized track info buffer.
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                inner: MaybeUninit::zeroed(),
+            }
+        }
+    }
+
+    impl HostTrackInfo {
+        /// Retrieves information about the track the plugin is placed on.
+        ///
+        /// The host gets passed a mutable buffer to write the information into, to avoid any
+        /// unnecessary allocations.
+        pub fn get<'b>(
+            &self,
+            host: &mut HostMainThreadHandle,
+            buffer: &'b mut TrackInfoBuffer,
+        ) -> Option<TrackInfo<'b>> {
+            let get = host.use_extension(&self.0).get?;
+
+            // SAFETY: This type ensures the function pointer is valid.
+            let success = unsafe { get(host.as_raw(), buffer.inner.as_mut_ptr()) };
+
+            if success {
+                // SAFETY: we just checked the buffer was successfully written to, and the host
+                // guarantees the audio port type pointer is valid.
+                Some(unsafe { TrackInfo::from_raw(buffer.inner.assume_init_ref()) })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Implementation of the Plugin-side of the Track Info extension.
+    pub trait PluginTrackInfoImpl {
+        /// Informs the plugin that the track information has changed.
+        fn changed(&mut self);
+    }
+
+    // SAFETY: The given struct is the CLAP extension struct for the matching side of this extension.
+    unsafe impl<P: Plugin> ExtensionImplementation<P> for PluginTrackInfo
+    where
+        for<'a> P::MainThread<'a>: PluginTrackInfoImpl,
+    {
+        #[doc(hidden)]
+        const IMPLEMENTATION: RawExtensionImplementation =
+            RawExtensionImplementation::new(&clap_plugin_track_info {
+                changed: Some(changed::<P>),
+            });
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn changed<P: Plugin>(plugin: *const clap_plugin)
+    where
+        for<'a> P::MainThread<'a>: PluginTrackInfoImpl,
+    {
+        PluginWrapper::<P>::handle(plugin, |p| {
+            p.main_thread().as_mut().changed();
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "clack-plugin")]
+pub use plugin::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn track_info_roundtrips() {
+        let info = TrackInfo {
+            flags: TrackInfoFlags::IS_FOR_BUS,
+            name: Some(b"Drums"),
+            color: Some(Color {
+                alpha: 255,
+                red: 10,
+                green: 20,
+                blue: 30,
+            }),
+            audio_channel: Some((2, Some(CStr::from_bytes_with_nul(b"stereo\0").unwrap()))),
+        };
+
+        let raw = info.to_raw();
+        // SAFETY: the port type pointer comes from a static string.
+        assert_eq!(unsafe { TrackInfo::from_raw(&raw) }, info);
+
+        let empty = TrackInfo {
+            flags: TrackInfoFlags::empty(),
+            name: None,
+            color: None,
+            audio_channel: None,
+        };
+
+        let raw = empty.to_raw();
+        assert_eq!(raw.flags, 0);
+        // SAFETY: the port type pointer is null.
+        assert_eq!(unsafe { TrackInfo::from_raw(&raw) }, empty);
+    }
+}
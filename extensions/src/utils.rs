@@ -46,3 +46,23 @@ pub(crate) unsafe fn slice_from_external_parts_mut<'a, T>(data: *mut T, len: usi
 
     core::slice::from_raw_parts_mut(data, len)
 }
+
+/// Enters a `tracing` span around a host-side call to the given plugin extension function, until
+/// the end of the current scope, if the `tracing` feature is enabled. Otherwise, this does nothing.
+///
+/// This is only used for main-thread calls, as creating spans isn't realtime-safe.
+#[cfg(feature = "clack-host")]
+macro_rules! trace_call {
+    ($plugin:expr, $function:literal) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "extension_call",
+            plugin_id = ?$plugin.descriptor().and_then(|d| d.id()),
+            function = $function,
+        )
+        .entered();
+    };
+}
+
+#[cfg(feature = "clack-host")]
+pub(crate) use trace_call;
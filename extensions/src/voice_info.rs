@@ -16,7 +16,6 @@ use std::ffi::CStr;
 
 /// Plugin-side of the Voice Info extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct PluginVoiceInfo(RawExtension<PluginExtensionSide, clap_plugin_voice_info>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -30,9 +29,19 @@ unsafe impl Extension for PluginVoiceInfo {
     }
 }
 
+impl PluginVoiceInfo {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<PluginExtensionSide, clap_plugin_voice_info> {
+        self.0
+    }
+}
+
 /// Host-side of the Voice Info extension.
 #[derive(Copy, Clone)]
-#[allow(dead_code)]
 pub struct HostVoiceInfo(RawExtension<HostExtensionSide, clap_host_voice_info>);
 
 // SAFETY: This type is repr(C) and ABI-compatible with the matching extension type.
@@ -46,6 +55,17 @@ unsafe impl Extension for HostVoiceInfo {
     }
 }
 
+impl HostVoiceInfo {
+    /// Returns the raw extension pointer wrapped by this type.
+    ///
+    /// See the [`RawExtension`] documentation on how to safely access the extension struct
+    /// through this pointer.
+    #[inline]
+    pub fn as_raw(&self) -> RawExtension<HostExtensionSide, clap_host_voice_info> {
+        self.0
+    }
+}
+
 bitflags! {
     /// Option flags for [`VoiceInfo`].
     #[repr(C)]
@@ -101,6 +121,8 @@ mod host {
         ///
         /// If the plugin failed to provide any Voice Information, this returns [`None`].
         pub fn get(&self, plugin: &mut PluginMainThreadHandle) -> Option<VoiceInfo> {
+            crate::utils::trace_call!(plugin, "clap_plugin_voice_info.get");
+
             let info = MaybeUninit::zeroed();
 
             // SAFETY: This type ensures the function pointer is valid.
@@ -6,9 +6,13 @@ use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
 use clack_extensions::params::{
     HostParams, HostParamsImplMainThread, HostParamsImplShared, ParamClearFlags, ParamRescanFlags,
 };
+#[cfg(unix)]
+use clack_extensions::posix_fd::{HostPosixFd, PluginPosixFd};
 use clack_extensions::timer::{HostTimer, PluginTimer};
 use clack_host::prelude::*;
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use clack_host::process::recovery::ProcessFailure;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use rtrb::Consumer;
 use std::error::Error;
 use std::ffi::CString;
 use std::rc::Rc;
@@ -20,6 +24,9 @@ use winit::event_loop::{ControlFlow, EventLoop};
 
 /// Audio related routines and utilities.
 mod audio;
+/// A POSIX file descriptors reactor implementation.
+#[cfg(unix)]
+mod fd;
 /// GUI handling.
 mod gui;
 /// A Timer implementation.
@@ -27,6 +34,8 @@ mod timer;
 
 use audio::*;
 use clack_extensions::note_ports::{HostNotePortsImpl, NoteDialects, NotePortRescanFlags};
+#[cfg(unix)]
+use fd::*;
 use gui::*;
 use timer::*;
 
@@ -57,6 +66,9 @@ impl HostHandlers for CpalHost {
             .register::<HostGui>()
             .register::<HostTimer>()
             .register::<HostParams>();
+
+        #[cfg(unix)]
+        builder.register::<HostPosixFd>();
     }
 }
 
@@ -123,8 +135,17 @@ pub struct CpalHostMainThread<'a> {
     timer_support: Option<PluginTimer>,
     /// The timer implementation.
     timers: Rc<Timers>,
+    /// A handle to the plugin's POSIX FD extension, if it supports it.
+    /// This is placed here, since only the main thread will ever use that extension.
+    #[cfg(unix)]
+    posix_fd_support: Option<PluginPosixFd>,
+    /// The file descriptors reactor implementation.
+    #[cfg(unix)]
+    fds: Rc<FdWatcher>,
     /// A handle to the plugin's GUI extension, if it supports it.
     gui: Option<PluginGui>,
+    /// The processing failures sent by the audio thread, once the plugin is activated.
+    process_failures: Option<Consumer<ProcessFailure>>,
 }
 
 impl<'a> CpalHostMainThread<'a> {
@@ -135,7 +156,12 @@ impl<'a> CpalHostMainThread<'a> {
             plugin: None,
             timer_support: None,
             gui: None,
+            process_failures: None,
             timers: Rc::new(Timers::new()),
+            #[cfg(unix)]
+            posix_fd_support: None,
+            #[cfg(unix)]
+            fds: Rc::new(FdWatcher::new()),
         }
     }
 }
@@ -144,6 +170,10 @@ impl<'a> MainThreadHandler<'a> for CpalHostMainThread<'a> {
     fn initialized(&mut self, instance: InitializedPluginHandle<'a>) {
         self.gui = instance.get_extension();
         self.timer_support = instance.get_extension();
+        #[cfg(unix)]
+        {
+            self.posix_fd_support = instance.get_extension();
+        }
 
         self.plugin = Some(instance);
     }
@@ -202,7 +232,15 @@ fn run_gui_floating(
     println!("Opening GUI in floating mode");
     gui.open_floating(&mut instance.plugin_handle())?;
 
-    for message in receiver {
+    loop {
+        let wait_duration = run_main_thread_reactors(&mut instance);
+
+        let message = match receiver.recv_timeout(wait_duration) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
         match message {
             MainThreadMessage::RunOnMainThread => instance.call_on_main_thread_callback(),
             MainThreadMessage::GuiClosed { .. } => {
@@ -234,8 +272,6 @@ fn run_gui_embedded(
 
     let uses_logical_pixels = gui.configuration.unwrap().api_type.uses_logical_size();
 
-    let timers = instance.access_handler(|h| h.timer_support.map(|ext| (h.timers.clone(), ext)));
-
     #[allow(deprecated)]
     event_loop.run(move |event, target| {
         while let Ok(message) = receiver.try_recv() {
@@ -292,16 +328,7 @@ fn run_gui_embedded(
             _ => {}
         }
 
-        let wait_duration = if let Some((timers, timer_ext)) = &timers {
-            timers.tick_timers(timer_ext, &mut instance.plugin_handle());
-
-            timers
-                .smallest_duration()
-                .unwrap_or(Duration::from_millis(60))
-        } else {
-            Duration::from_millis(60)
-        };
-
+        let wait_duration = run_main_thread_reactors(&mut instance);
         target.set_control_flow(ControlFlow::WaitUntil(Instant::now() + wait_duration));
     })?;
 
@@ -320,24 +347,73 @@ fn run_cli(
 ) -> Result<(), Box<dyn Error>> {
     println!("Running headless. Press Ctrl+C to stop processing.");
 
-    for message in receiver {
-        if let MainThreadMessage::RunOnMainThread = message {
-            instance.call_on_main_thread_callback()
+    loop {
+        let wait_duration = run_main_thread_reactors(&mut instance);
+
+        match receiver.recv_timeout(wait_duration) {
+            Ok(MainThreadMessage::RunOnMainThread) => instance.call_on_main_thread_callback(),
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
         }
     }
 
     Ok(())
 }
 
+/// Runs the plugin's main-thread callbacks for all the timers and file descriptors that were
+/// triggered since the last call.
+///
+/// This returns how long the main thread can wait before this needs to be called again.
+fn run_main_thread_reactors(instance: &mut PluginInstance<CpalHost>) -> Duration {
+    /// The default duration to wait for, if the plugin didn't register anything.
+    const DEFAULT_WAIT: Duration = Duration::from_millis(60);
+    /// How often to poll file descriptors, if the plugin registered any.
+    #[cfg(unix)]
+    const FD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    let mut wait_duration = DEFAULT_WAIT;
+
+    instance.access_handler_mut(|h| {
+        if let Some(failures) = &mut h.process_failures {
+            report_failures(failures);
+        }
+    });
+
+    let timers = instance.access_handler(|h| h.timer_support.map(|ext| (h.timers.clone(), ext)));
+
+    if let Some((timers, timer_ext)) = timers {
+        timers.tick_timers(&timer_ext, &mut instance.plugin_handle());
+
+        if let Some(smallest) = timers.smallest_duration() {
+            wait_duration = smallest;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let fds = instance.access_handler(|h| h.posix_fd_support.map(|ext| (h.fds.clone(), ext)));
+
+        if let Some((fds, fd_ext)) = fds {
+            fds.poll_fds(&fd_ext, &mut instance.plugin_handle());
+
+            if !fds.is_empty() {
+                wait_duration = wait_duration.min(FD_POLL_INTERVAL);
+            }
+        }
+    }
+
+    wait_duration
+}
+
 /// Information about this host.
 fn host_info() -> HostInfo {
-    HostInfo::new(
-        "Clack example CPAL host",
-        "Clack",
-        "https://github.com/prokopyl/clack",
-        "0.0.0",
-    )
-    .unwrap()
+    HostInfo::builder()
+        .name("Clack example CPAL host")
+        .vendor("Clack")
+        .url("https://github.com/prokopyl/clack")
+        .version(env!("CARGO_PKG_VERSION"))
+        .build()
+        .unwrap()
 }
 
 impl HostLogImpl for CpalHostShared {
@@ -1,10 +1,12 @@
 use crate::host::CpalHost;
 use clack_host::prelude::*;
-use clack_host::process::StartedPluginAudioProcessor;
+use clack_host::process::recovery::{ProcessFailure, ProcessOutcome, ProcessRecovery};
+use clack_host::process::{PluginAudioProcessor, StartedPluginAudioProcessor};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{
     BuildStreamError, Device, FromSample, OutputCallbackInfo, SampleFormat, Stream, StreamConfig,
 };
+use rtrb::{Consumer, Producer, RingBuffer};
 use std::error::Error;
 
 /// Handling of audio buffers.
@@ -32,13 +34,18 @@ pub fn activate_to_stream(
 
     let midi = MidiReceiver::new(44_100, instance)?;
 
+    // Printing isn't realtime-safe: failures are sent to the main thread, which reports them.
+    let (failures_producer, failures_consumer) = RingBuffer::new(MAX_PENDING_FAILURES);
+    instance.access_handler_mut(|h| h.process_failures = Some(failures_consumer));
+
     let plugin_audio_processor = instance
         .activate(|_, _| (), config.as_clack_plugin_config())?
         .start_processing()?;
 
     let sample_format = config.sample_format;
     let cpal_config = config.as_cpal_stream_config();
-    let audio_processor = StreamAudioProcessor::new(plugin_audio_processor, midi, config);
+    let audio_processor =
+        StreamAudioProcessor::new(plugin_audio_processor, midi, config, failures_producer);
 
     let stream = build_output_stream_for_sample_format(
         &output_device,
@@ -102,10 +109,19 @@ fn make_stream_runner<S: FromSample<f32>>(
     move |data, _info| audio_processor.process(data)
 }
 
+/// How many processing failures can be waiting to be reported by the main thread. Further failures
+/// are dropped until the main thread catches up.
+const MAX_PENDING_FAILURES: usize = 16;
+
+/// The failure callback of the [`ProcessRecovery`] helper.
+type FailureCallback = Box<dyn FnMut(&ProcessFailure) + Send>;
+
 /// Holds all of the data, buffers and state that are going to live and get used on the audio thread.
 struct StreamAudioProcessor {
     /// The plugin's audio processor.
-    audio_processor: StartedPluginAudioProcessor<CpalHost>,
+    audio_processor: PluginAudioProcessor<CpalHost>,
+    /// Tracks processing failures, to bypass the plugin once it failed.
+    recovery: ProcessRecovery<FailureCallback>,
     /// The audio buffers.
     buffers: HostAudioBuffers,
     /// The MIDI event receiver.
@@ -120,9 +136,17 @@ impl StreamAudioProcessor {
         plugin_instance: StartedPluginAudioProcessor<CpalHost>,
         midi_receiver: Option<MidiReceiver>,
         config: FullAudioConfig,
+        mut failures: Producer<ProcessFailure>,
     ) -> Self {
+        let on_failure: FailureCallback = Box::new(move |failure: &ProcessFailure| {
+            // This never blocks nor allocates, as processing errors never hold an extension
+            // identifier to clone. If the ring buffer is full, the failure is dropped.
+            let _ = failures.push(failure.clone());
+        });
+
         Self {
-            audio_processor: plugin_instance,
+            audio_processor: plugin_instance.into(),
+            recovery: ProcessRecovery::new(on_failure).with_max_restarts(1),
             buffers: HostAudioBuffers::from_config(config),
             midi_receiver,
             steady_counter: 0,
@@ -149,18 +173,38 @@ impl StreamAudioProcessor {
             InputEvents::empty()
         };
 
-        match self.audio_processor.process(
-            &ins,
-            &mut outs,
-            &events,
-            &mut OutputEvents::void(),
-            Some(self.steady_counter),
-            None,
-        ) {
-            Ok(_) => self.buffers.write_to_cpal_buffer(data),
-            Err(e) => eprintln!("{e}"),
+        let steady_counter = self.steady_counter;
+        let outcome = self
+            .recovery
+            .process(&mut self.audio_processor, |processor| {
+                processor.process(
+                    &ins,
+                    &mut outs,
+                    &events,
+                    &mut OutputEvents::void(),
+                    Some(steady_counter),
+                    None,
+                )
+            });
+
+        match outcome {
+            ProcessOutcome::Processed(_) => self.buffers.write_to_cpal_buffer(data),
+            // Output silence while the plugin is errored.
+            ProcessOutcome::Bypassed => data.fill_with(|| S::from_sample_(0.0)),
         }
 
         self.steady_counter += sample_count as u64;
     }
 }
+
+/// Reports all the processing failures the audio thread sent since the last call.
+///
+/// This must be called on the main thread.
+pub fn report_failures(failures: &mut Consumer<ProcessFailure>) {
+    while let Ok(failure) = failures.pop() {
+        eprintln!(
+            "Plugin failed to process ({:?}): {}",
+            failure.action, failure.error
+        );
+    }
+}
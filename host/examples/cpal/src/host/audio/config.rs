@@ -2,9 +2,11 @@ use crate::host::CpalHost;
 use clack_extensions::audio_ports::{
     AudioPortFlags, AudioPortInfoBuffer, AudioPortType, PluginAudioPorts,
 };
+use clack_extensions::audio_ports_config;
 use clack_host::prelude::{
     ClapId, PluginAudioConfiguration, PluginInstance, PluginMainThreadHandle,
 };
+use clack_host::process::frames_budget::FramesCountBudget;
 use cpal::traits::DeviceTrait;
 use cpal::{
     BufferSize, Device, SampleFormat, SampleRate, StreamConfig, SupportedBufferSize,
@@ -45,6 +47,10 @@ impl FullAudioConfig {
     ) -> Result<Self, Box<dyn Error>> {
         let best_cpal_configs = list_device_configs_ordered(device)?;
 
+        // The plugin's ports may change once a new configuration is selected, so this needs to
+        // happen before scanning them.
+        select_stereo_ports_config(&mut instance.plugin_handle());
+
         let input_ports = get_config_from_ports(&mut instance.plugin_handle(), true);
         let output_ports = get_config_from_ports(&mut instance.plugin_handle(), false);
 
@@ -276,6 +282,26 @@ fn sample_type_preference(sample_type: SampleFormat) -> u8 {
     }
 }
 
+/// Selects a stereo port configuration using the Audio Ports Configuration extension, if the
+/// plugin supports it.
+///
+/// Plugins that don't support this extension are left untouched.
+pub fn select_stereo_ports_config(plugin: &mut PluginMainThreadHandle) {
+    let Some(ports_config) = plugin.get_extension::<audio_ports_config::PluginAudioPortsConfig>()
+    else {
+        return;
+    };
+
+    let is_stereo =
+        |port: Option<audio_ports_config::MainPortInfo>| port.is_some_and(|p| p.channel_count == 2);
+
+    match ports_config.select_matching(plugin, |c| is_stereo(c.main_output)) {
+        Ok(Some(id)) => println!("Selected plugin's stereo ports configuration (ID: {id})"),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: {e}"),
+    }
+}
+
 /// Retrieves a given plugin's port configuration using the Audio Ports extension.
 ///
 /// This can query either the input ports or the output ports.
@@ -375,11 +401,15 @@ fn find_matching_output_config(
         .or_else(|| ordered_stream_configs.first())
         .expect("No config supported by output device");
 
-    let (min_buffer_size, max_buffer_size) = match best_stream_config.buffer_size() {
-        SupportedBufferSize::Range { min, max } => (*min, 1024.clamp(*min, *max)),
-        SupportedBufferSize::Unknown => (1, 1024),
+    let budget = match best_stream_config.buffer_size() {
+        SupportedBufferSize::Range { min, max } => FramesCountBudget::new(*min, *max),
+        SupportedBufferSize::Unknown => FramesCountBudget::unknown(),
     };
 
+    // CPAL may give us smaller buffers than requested, so blocks can always be split.
+    let frames_range = budget.with_likely_frames_count(1024).frames_count_range();
+    let (min_buffer_size, max_buffer_size) = (*frames_range.start(), *frames_range.end());
+
     FullAudioConfig {
         output_channel_count: best_stream_config.channels() as usize,
         min_buffer_size,
@@ -0,0 +1,173 @@
+use crate::host::CpalHostMainThread;
+use clack_extensions::posix_fd::{FdFlags, HostPosixFdImpl, PluginPosixFd};
+use clack_host::prelude::{HostError, PluginMainThreadHandle};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::unix::io::RawFd;
+
+impl HostPosixFdImpl for CpalHostMainThread<'_> {
+    fn register_fd(&mut self, fd: RawFd, flags: FdFlags) -> Result<(), HostError> {
+        if self.fds.register(fd, flags) {
+            Ok(())
+        } else {
+            Err(HostError::Message("File descriptor is already registered"))
+        }
+    }
+
+    fn modify_fd(&mut self, fd: RawFd, flags: FdFlags) -> Result<(), HostError> {
+        if self.fds.modify(fd, flags) {
+            Ok(())
+        } else {
+            Err(HostError::Message("Unknown file descriptor"))
+        }
+    }
+
+    fn unregister_fd(&mut self, fd: RawFd) -> Result<(), HostError> {
+        if self.fds.unregister(fd) {
+            Ok(())
+        } else {
+            Err(HostError::Message("Unknown file descriptor"))
+        }
+    }
+}
+
+/// Handles all the file descriptors registered by the plugin.
+///
+/// Winit does not allow to hook arbitrary file descriptors into its own event loop, so this
+/// implementation polls the registered file descriptors without blocking every time the event loop
+/// wakes up instead.
+pub struct FdWatcher {
+    /// All the registered file descriptors, with the events they are watched for.
+    fds: RefCell<HashMap<RawFd, FdFlags>>,
+}
+
+impl FdWatcher {
+    /// Initializes file descriptor handling.
+    pub fn new() -> Self {
+        Self {
+            fds: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a new file descriptor, to be watched for the given events.
+    ///
+    /// Returns `false` if that file descriptor was already registered, `true` otherwise.
+    pub fn register(&self, fd: RawFd, flags: FdFlags) -> bool {
+        // PANIC: This method is not reentrant with any that may also borrow fds
+        let mut fds = self.fds.borrow_mut();
+        if fds.contains_key(&fd) {
+            return false;
+        }
+
+        println!("Plugin registered file descriptor ({fd}) with flags {flags:?}.");
+        fds.insert(fd, flags);
+        true
+    }
+
+    /// Changes the events a given file descriptor is watched for.
+    ///
+    /// Returns `false` if that file descriptor was not registered, `true` otherwise.
+    pub fn modify(&self, fd: RawFd, flags: FdFlags) -> bool {
+        // PANIC: This method is not reentrant with any that may also borrow fds
+        match self.fds.borrow_mut().get_mut(&fd) {
+            Some(current) => {
+                *current = flags;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops watching a given file descriptor.
+    ///
+    /// Returns `false` if that file descriptor was not registered, `true` otherwise.
+    pub fn unregister(&self, fd: RawFd) -> bool {
+        // PANIC: This method is not reentrant with any that may also borrow fds
+        if self.fds.borrow_mut().remove(&fd).is_some() {
+            println!("Plugin unregistered file descriptor ({fd}).");
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if no file descriptors are currently registered.
+    pub fn is_empty(&self) -> bool {
+        self.fds.borrow().is_empty()
+    }
+
+    /// Polls all the registered file descriptors without blocking, returning those that have
+    /// pending events, alongside the events in question.
+    #[allow(unsafe_code)]
+    fn poll_all(&self) -> Vec<(RawFd, FdFlags)> {
+        // PANIC: This method is not reentrant with any that may also borrow fds
+        let mut poll_fds: Vec<libc::pollfd> = self
+            .fds
+            .borrow()
+            .iter()
+            .map(|(&fd, &flags)| libc::pollfd {
+                fd,
+                events: flags_to_poll_events(flags),
+                revents: 0,
+            })
+            .collect();
+
+        if poll_fds.is_empty() {
+            return Vec::new();
+        }
+
+        // SAFETY: the pointer and length both come from the same valid, exclusively borrowed Vec.
+        let result = unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as _, 0) };
+        if result <= 0 {
+            return Vec::new();
+        }
+
+        poll_fds
+            .into_iter()
+            .filter(|p| p.revents != 0)
+            .map(|p| (p.fd, poll_events_to_flags(p.revents)))
+            .collect()
+    }
+
+    /// Polls all the registered file descriptors, and runs the plugin's callback for all of those
+    /// that have pending events.
+    pub fn poll_fds(&self, fd_ext: &PluginPosixFd, plugin: &mut PluginMainThreadHandle) {
+        for (fd, flags) in self.poll_all() {
+            fd_ext.on_fd(plugin, fd, flags);
+        }
+    }
+}
+
+/// Converts the CLAP fd flags into the matching `poll` events.
+fn flags_to_poll_events(flags: FdFlags) -> libc::c_short {
+    let mut events = 0;
+
+    if flags.contains(FdFlags::READ) {
+        events |= libc::POLLIN;
+    }
+    if flags.contains(FdFlags::WRITE) {
+        events |= libc::POLLOUT;
+    }
+    if flags.contains(FdFlags::ERROR) {
+        events |= libc::POLLERR;
+    }
+
+    events
+}
+
+/// Converts the events returned by `poll` into the matching CLAP fd flags.
+fn poll_events_to_flags(events: libc::c_short) -> FdFlags {
+    let mut flags = FdFlags::empty();
+
+    if events & libc::POLLIN != 0 {
+        flags |= FdFlags::READ;
+    }
+    if events & libc::POLLOUT != 0 {
+        flags |= FdFlags::WRITE;
+    }
+    if events & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+        flags |= FdFlags::ERROR;
+    }
+
+    flags
+}
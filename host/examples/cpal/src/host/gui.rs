@@ -1,6 +1,7 @@
 use crate::host::{CpalHostMainThread, CpalHostShared, MainThreadMessage};
 use clack_extensions::gui::{
-    GuiApiType, GuiConfiguration, GuiError, GuiSize, HostGuiImpl, PluginGui, Window as ClapWindow,
+    GuiApiType, GuiClosedReason, GuiConfiguration, GuiSession, GuiSessionError, GuiSize,
+    HostGuiImpl, PluginGui, Window as ClapWindow,
 };
 use clack_host::prelude::*;
 use std::error::Error;
@@ -29,7 +30,7 @@ impl HostGuiImpl for CpalHostShared {
         Ok(())
     }
 
-    fn closed(&self, _was_destroyed: bool) {
+    fn closed(&self, _reason: GuiClosedReason) {
         self.sender.send(MainThreadMessage::GuiClosed).unwrap();
     }
 }
@@ -38,12 +39,10 @@ impl CpalHostMainThread<'_> {}
 
 /// Tracks a plugin's GUI state and configuration.
 pub struct Gui {
-    /// The plugin's GUI extension.
-    plugin_gui: PluginGui,
+    /// The plugin's GUI session, which tracks whether the GUI is currently open.
+    session: GuiSession,
     /// The negociated GUI configuration, or None if no compatible setup could be found.
     pub configuration: Option<GuiConfiguration<'static>>,
-    /// Whether the GUI is currently open.
-    is_open: bool,
     /// Whether the GUI accepts to be resized.
     is_resizeable: bool,
 }
@@ -52,9 +51,8 @@ impl Gui {
     /// Initializes the GUI state for a given instance
     pub fn new(plugin_gui: PluginGui, instance: &mut PluginMainThreadHandle) -> Self {
         Self {
-            plugin_gui,
+            session: GuiSession::new(plugin_gui),
             configuration: Self::negotiate_configuration(&plugin_gui, instance),
-            is_open: false,
             is_resizeable: false,
         }
     }
@@ -68,23 +66,8 @@ impl Gui {
         plugin: &mut PluginMainThreadHandle,
     ) -> Option<GuiConfiguration<'static>> {
         // This implementation only supports the default: Win32 on Windows, Cocoa on macOS, X11 on Unix
-        // We completely ignore the plugin's preference here: it's platform-default or nothing.
         let api_type = GuiApiType::default_for_current_platform()?;
-        let mut config = GuiConfiguration {
-            api_type,
-            is_floating: false,
-        };
-
-        if gui.is_api_supported(plugin, config) {
-            Some(config)
-        } else {
-            config.is_floating = true;
-            if gui.is_api_supported(plugin, config) {
-                Some(config)
-            } else {
-                None
-            }
-        }
+        gui.negotiate(plugin, &[api_type]).configuration()
     }
 
     /// Gets a Winit-compatible GUI size from a given plugin-GUI size.
@@ -119,7 +102,10 @@ impl Gui {
     }
 
     /// Opens the plugin's GUI in floating mode.
-    pub fn open_floating(&mut self, plugin: &mut PluginMainThreadHandle) -> Result<(), GuiError> {
+    pub fn open_floating(
+        &mut self,
+        plugin: &mut PluginMainThreadHandle,
+    ) -> Result<(), GuiSessionError> {
         let Some(configuration) = self.configuration else {
             panic!("Called open_floating on incompatible plugin")
         };
@@ -127,12 +113,12 @@ impl Gui {
             panic!("Called open_floating on incompatible plugin")
         };
 
-        self.plugin_gui.create(plugin, configuration)?;
-        self.plugin_gui.suggest_title(
+        self.session.create(plugin, configuration)?;
+        self.session.suggest_title(
             plugin,
             CStr::from_bytes_with_nul(b"Clack CPAL plugin!\0").unwrap(),
-        );
-        self.plugin_gui.show(plugin)?;
+        )?;
+        self.session.show(plugin)?;
 
         Ok(())
     }
@@ -144,7 +130,7 @@ impl Gui {
         plugin: &mut PluginMainThreadHandle,
         event_loop: &EventLoop<()>,
     ) -> Result<Window, Box<dyn Error>> {
-        let gui = self.plugin_gui;
+        let gui = &mut self.session;
         let Some(configuration) = self.configuration else {
             panic!("Called open_embedded on incompatible plugin")
         };
@@ -176,7 +162,6 @@ impl Gui {
         unsafe { gui.set_parent(plugin, ClapWindow::from_window(&window).unwrap())? };
         // Some plugins don't show anything until this is called, others return an error.
         let _ = gui.show(plugin);
-        self.is_open = true;
 
         Ok(window)
     }
@@ -206,22 +191,19 @@ impl Gui {
         };
 
         if !self.is_resizeable {
-            let forced_size = self.plugin_gui.get_size(plugin).unwrap_or(size);
+            let forced_size = self.session.get_size(plugin).unwrap_or(size);
 
             return self.gui_size_to_winit_size(forced_size);
         }
 
-        let working_size = self.plugin_gui.adjust_size(plugin, size).unwrap_or(size);
-        self.plugin_gui.set_size(plugin, working_size).unwrap();
+        let working_size = self.session.adjust_size(plugin, size).unwrap_or(size);
+        self.session.set_size(plugin, working_size).unwrap();
 
         self.gui_size_to_winit_size(working_size)
     }
 
     /// Destroys the plugin's GUI resources, if its GUI is still open.
     pub fn destroy(&mut self, plugin: &mut PluginMainThreadHandle) {
-        if self.is_open {
-            self.plugin_gui.destroy(plugin);
-            self.is_open = false;
-        }
+        self.session.destroy(plugin);
     }
 }
@@ -0,0 +1,154 @@
+use clack_extensions::audio_ports::{HostAudioPorts, HostAudioPortsImpl, RescanType};
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_extensions::note_ports::{
+    HostNotePorts, HostNotePortsImpl, NoteDialects, NotePortRescanFlags,
+};
+use clack_host::prelude::*;
+use jack::{Client, ClientOptions};
+use std::error::Error;
+use std::ffi::CStr;
+use std::sync::mpsc::{channel, Sender};
+
+/// The JACK process handler, and the handling of audio buffers.
+mod audio;
+/// Conversions between JACK MIDI messages and CLAP events.
+mod midi;
+/// Discovery of the plugin's audio and note ports.
+mod ports;
+
+use audio::*;
+use ports::*;
+
+/// Messages that can be sent to the main thread from any of the plugin's threads.
+enum MainThreadMessage {
+    /// Request to run the "on_main_thread" callback.
+    RunOnMainThread,
+    /// The user requested the host to stop.
+    Quit,
+}
+
+/// Our host implementation.
+pub struct JackHost;
+
+impl HostHandlers for JackHost {
+    type Shared<'a> = JackHostShared;
+    type MainThread<'a> = JackHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder
+            .register::<HostLog>()
+            .register::<HostAudioPorts>()
+            .register::<HostNotePorts>();
+    }
+}
+
+/// Data, accessible by all the plugin's threads.
+pub struct JackHostShared {
+    /// The sender side of the channel to the main thread.
+    sender: Sender<MainThreadMessage>,
+}
+
+impl SharedHandler<'_> for JackHostShared {
+    fn request_restart(&self) {
+        // We don't support restarting plugins
+    }
+
+    fn request_process(&self) {
+        // We never pause, and JACK is in full control anyway
+    }
+
+    fn request_callback(&self) {
+        let _ = self.sender.send(MainThreadMessage::RunOnMainThread);
+    }
+}
+
+/// Data only accessible by the main thread.
+pub struct JackHostMainThread;
+
+impl MainThreadHandler<'_> for JackHostMainThread {}
+
+/// Runs the plugin with the given ID from the given bundle, until the user presses Enter.
+pub fn run(bundle: &PluginBundle, plugin_id: &CStr) -> Result<(), Box<dyn Error>> {
+    let (client, _status) = Client::new("Clack host", ClientOptions::NO_START_SERVER)?;
+    let (sender, receiver) = channel();
+
+    let mut instance = PluginInstance::<JackHost>::new(
+        |_| JackHostShared {
+            sender: sender.clone(),
+        },
+        |_| JackHostMainThread,
+        bundle,
+        plugin_id,
+        &host_info(),
+    )?;
+
+    let plugin_ports = PluginPorts::discover(&mut instance);
+    let process_handler = JackProcessHandler::activate(&client, &mut instance, &plugin_ports)?;
+    let active_client = client.activate_async((), process_handler)?;
+
+    println!(
+        "Plugin is running as JACK client '{}'. Connect its ports, and press Enter to stop.",
+        active_client.as_client().name()
+    );
+
+    std::thread::spawn(move || {
+        let _ = std::io::stdin().read_line(&mut String::new());
+        let _ = sender.send(MainThreadMessage::Quit);
+    });
+
+    while let Ok(message) = receiver.recv() {
+        match message {
+            MainThreadMessage::RunOnMainThread => instance.call_on_main_thread_callback(),
+            MainThreadMessage::Quit => break,
+        }
+    }
+
+    println!("Stopping...");
+    let (_client, (), process_handler) = active_client.deactivate()?;
+    instance.deactivate(process_handler.into_audio_processor().stop_processing());
+
+    Ok(())
+}
+
+/// Returns the information about this host.
+fn host_info() -> HostInfo {
+    HostInfo::new(
+        "Clack example JACK host",
+        "Clack",
+        "https://github.com/prokopyl/clack",
+        env!("CARGO_PKG_VERSION"),
+    )
+    .unwrap()
+}
+
+impl HostLogImpl for JackHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if severity <= LogSeverity::Debug {
+            return;
+        };
+        // Note: writing to stdout isn't realtime-safe, and should ideally be avoided.
+        // This is only "good enough™" for an example.
+        eprintln!("[{severity}] {message}")
+    }
+}
+
+impl HostAudioPortsImpl for JackHostMainThread {
+    fn is_rescan_flag_supported(&self, _flag: RescanType) -> bool {
+        false
+    }
+
+    fn rescan(&mut self, _flag: RescanType) {
+        // We don't support audio ports changing on the fly
+    }
+}
+
+impl HostNotePortsImpl for JackHostMainThread {
+    fn supported_dialects(&self) -> NoteDialects {
+        NoteDialects::CLAP | NoteDialects::MIDI
+    }
+
+    fn rescan(&mut self, _flags: NotePortRescanFlags) {
+        // We don't support note ports changing on the fly
+    }
+}
@@ -0,0 +1,234 @@
+use crate::host::midi::{event_to_midi, push_midi_to_buffer};
+use crate::host::ports::{NotePortInfo, PluginPorts};
+use crate::host::JackHost;
+use clack_host::prelude::*;
+use clack_host::process::StartedPluginAudioProcessor;
+use jack::{
+    AudioIn, AudioOut, Client, Control, Frames, MidiIn, MidiOut, Port, ProcessHandler,
+    ProcessScope, RawMidi,
+};
+use std::error::Error;
+
+/// The JACK ports and host-side buffers mapped to one of the plugin's audio input ports.
+struct InputPort {
+    /// One JACK port for each of the plugin port's channels.
+    jack_ports: Vec<Port<AudioIn>>,
+    /// The buffers JACK's input is copied to, one for each channel.
+    ///
+    /// This is needed because plugins may process input buffers in-place, while JACK input
+    /// buffers cannot be modified.
+    buffers: Vec<Vec<f32>>,
+}
+
+/// Holds all of the data, buffers and state that are going to live and get used on the audio thread.
+pub struct JackProcessHandler {
+    /// The plugin's audio processor.
+    audio_processor: StartedPluginAudioProcessor<JackHost>,
+
+    /// The JACK ports and buffers mapped to each of the plugin's audio input ports.
+    audio_inputs: Vec<InputPort>,
+    /// The JACK ports mapped to each channel of each of the plugin's audio output ports.
+    ///
+    /// The plugin directly writes to the buffers of those ports.
+    audio_outputs: Vec<Vec<Port<AudioOut>>>,
+    /// Buffers for the plugin's input ports information.
+    input_ports: AudioPorts,
+    /// Buffers for the plugin's output ports information.
+    output_ports: AudioPorts,
+
+    /// The JACK MIDI input port, and the plugin note port it is mapped to.
+    midi_input: Option<(Port<MidiIn>, NotePortInfo)>,
+    /// The JACK MIDI output port, and the plugin note port it is mapped to.
+    midi_output: Option<(Port<MidiOut>, NotePortInfo)>,
+    /// The buffer holding CLAP events to be fed to the plugin.
+    input_events: EventBuffer,
+    /// The buffer receiving the CLAP events sent by the plugin.
+    output_events: EventBuffer,
+
+    /// The buffer size the plugin was activated with.
+    max_frames_count: u32,
+    /// A steady frame counter, used by the plugin's process() method.
+    steady_counter: u64,
+}
+
+impl JackProcessHandler {
+    /// Registers all the JACK ports matching the plugin's ports, then activates the plugin using
+    /// the JACK server's sample rate and buffer size.
+    pub fn activate(
+        client: &Client,
+        instance: &mut PluginInstance<JackHost>,
+        plugin_ports: &PluginPorts,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut audio_inputs = Vec::with_capacity(plugin_ports.audio_inputs.len());
+        for (port_index, port) in plugin_ports.audio_inputs.iter().enumerate() {
+            let jack_ports = (0..port.channel_count)
+                .map(|channel| {
+                    client.register_port(&format!("in{port_index}_{channel}"), AudioIn::default())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            audio_inputs.push(InputPort {
+                buffers: vec![vec![0.0; client.buffer_size() as usize]; jack_ports.len()],
+                jack_ports,
+            });
+        }
+
+        let mut audio_outputs = Vec::with_capacity(plugin_ports.audio_outputs.len());
+        for (port_index, port) in plugin_ports.audio_outputs.iter().enumerate() {
+            let jack_ports = (0..port.channel_count)
+                .map(|channel| {
+                    client.register_port(&format!("out{port_index}_{channel}"), AudioOut::default())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            audio_outputs.push(jack_ports);
+        }
+
+        let midi_input = match plugin_ports.note_input {
+            Some(port) => Some((client.register_port("midi_in", MidiIn::default())?, port)),
+            None => None,
+        };
+
+        let midi_output = match plugin_ports.note_output {
+            Some(port) => Some((client.register_port("midi_out", MidiOut::default())?, port)),
+            None => None,
+        };
+
+        // JACK always processes cycles of the same size, which can only change through the
+        // buffer_size callback.
+        let config = PluginAudioConfiguration {
+            sample_rate: client.sample_rate() as f64,
+            min_frames_count: client.buffer_size(),
+            max_frames_count: client.buffer_size(),
+        };
+
+        println!(
+            "Activating plugin with a sample rate of {} Hz, and a buffer size of {} frames",
+            config.sample_rate, config.max_frames_count
+        );
+
+        let audio_processor = instance.activate(|_, _| (), config)?.start_processing()?;
+
+        Ok(Self {
+            audio_processor,
+            input_ports: AudioPorts::with_capacity(
+                audio_inputs.iter().map(|p| p.jack_ports.len()).sum(),
+                audio_inputs.len(),
+            ),
+            output_ports: AudioPorts::with_capacity(
+                audio_outputs.iter().map(|p| p.len()).sum(),
+                audio_outputs.len(),
+            ),
+            audio_inputs,
+            audio_outputs,
+            midi_input,
+            midi_output,
+            input_events: EventBuffer::with_capacity(256),
+            output_events: EventBuffer::with_capacity(256),
+            max_frames_count: config.max_frames_count,
+            steady_counter: 0,
+        })
+    }
+
+    /// Returns the plugin's audio processor, so that it can be stopped and deactivated.
+    pub fn into_audio_processor(self) -> StartedPluginAudioProcessor<JackHost> {
+        self.audio_processor
+    }
+}
+
+impl ProcessHandler for JackProcessHandler {
+    fn process(&mut self, _client: &Client, scope: &ProcessScope) -> Control {
+        let frames_count = scope.n_frames();
+        if frames_count > self.max_frames_count {
+            eprintln!("JACK cycle is larger than the buffer size the plugin was activated with");
+            return Control::Quit;
+        }
+
+        // JACK MIDI events are already timed relative to the start of the cycle.
+        self.input_events.clear();
+        if let Some((jack_port, note_port)) = &self.midi_input {
+            for RawMidi { time, bytes } in jack_port.iter(scope) {
+                push_midi_to_buffer(time, bytes, *note_port, &mut self.input_events);
+            }
+        }
+
+        for port in &mut self.audio_inputs {
+            for (jack_port, buffer) in port.jack_ports.iter().zip(&mut port.buffers) {
+                buffer[..frames_count as usize].copy_from_slice(jack_port.as_slice(scope));
+            }
+        }
+
+        let inputs = if self.audio_inputs.is_empty() {
+            InputAudioBuffers::empty_with_frames(frames_count)
+        } else {
+            self.input_ports
+                .with_input_buffers(self.audio_inputs.iter_mut().map(|port| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_input_only(port.buffers.iter_mut().map(
+                        |buffer| InputChannel {
+                            buffer: &mut buffer[..frames_count as usize],
+                            is_constant: false,
+                        },
+                    )),
+                }))
+        };
+
+        let mut outputs = if self.audio_outputs.is_empty() {
+            OutputAudioBuffers::empty_with_frames(frames_count)
+        } else {
+            self.output_ports
+                .with_output_buffers(self.audio_outputs.iter_mut().map(|jack_ports| {
+                    AudioPortBuffer {
+                        latency: 0,
+                        channels: AudioPortBufferType::f32_output_only(
+                            jack_ports.iter_mut().map(|p| p.as_mut_slice(scope)),
+                        ),
+                    }
+                }))
+        };
+
+        self.output_events.clear();
+        let result = self.audio_processor.process(
+            &inputs,
+            &mut outputs,
+            &self.input_events.as_input(),
+            &mut OutputEvents::from_buffer(&mut self.output_events),
+            Some(self.steady_counter),
+            None,
+        );
+
+        self.steady_counter += frames_count as u64;
+
+        if let Err(e) = result {
+            eprintln!("{e}");
+            return Control::Continue;
+        }
+
+        // Events sent by the plugin keep the frame offset they were produced at.
+        if let Some((jack_port, note_port)) = &mut self.midi_output {
+            let mut writer = jack_port.writer(scope);
+            for event in self.output_events.iter() {
+                let Some(bytes) = event_to_midi(event, note_port.index) else {
+                    continue;
+                };
+
+                let time = event.header().time().min(frames_count.saturating_sub(1));
+                let _ = writer.write(&RawMidi {
+                    time,
+                    bytes: &bytes,
+                });
+            }
+        }
+
+        Control::Continue
+    }
+
+    fn buffer_size(&mut self, _client: &Client, size: Frames) -> Control {
+        if size > self.max_frames_count {
+            eprintln!("JACK buffer size changed to {size} frames, which is unsupported. Stopping.");
+            return Control::Quit;
+        }
+
+        Control::Continue
+    }
+}
@@ -0,0 +1,111 @@
+use crate::host::ports::NotePortInfo;
+use clack_host::events::event_types::{
+    clap_velocity_to_midi, midi_velocity_to_clap, MidiEvent, NoteOffEvent, NoteOnEvent,
+};
+use clack_host::events::spaces::CoreEventSpace;
+use clack_host::events::{EventFlags, Match, UnknownEvent};
+use clack_host::prelude::*;
+
+/// The MIDI status nibble of Note Off messages.
+const NOTE_OFF: u8 = 0x80;
+/// The MIDI status nibble of Note On messages.
+const NOTE_ON: u8 = 0x90;
+
+/// Converts a raw MIDI message received from JACK to a CLAP event, and pushes it to the given
+/// buffer.
+///
+/// The given `time` is the frame offset of the message in the current JACK cycle, and is used as
+/// the event's sample time as-is.
+///
+/// Note On and Note Off messages are converted to CLAP note events, unless the plugin prefers
+/// receiving MIDI events. All other messages are forwarded as MIDI events, except SysEx messages,
+/// which are ignored.
+pub fn push_midi_to_buffer(time: u32, bytes: &[u8], port: NotePortInfo, buffer: &mut EventBuffer) {
+    if bytes.is_empty() || bytes.len() > 3 {
+        return;
+    }
+
+    let mut data = [0; 3];
+    data[..bytes.len()].copy_from_slice(bytes);
+    let [status, key, velocity] = data;
+
+    let pckn = Pckn::new(port.index, (status & 0x0F) as u16, key as u16, Match::All);
+    let velocity = midi_velocity_to_clap(velocity);
+
+    match status & 0xF0 {
+        NOTE_ON if !port.prefers_midi && velocity > 0.0 => {
+            buffer.push(&NoteOnEvent::new(time, pckn, velocity).with_flags(EventFlags::IS_LIVE))
+        }
+        // A Note On with a velocity of 0 is a Note Off
+        NOTE_OFF | NOTE_ON if !port.prefers_midi => {
+            buffer.push(&NoteOffEvent::new(time, pckn, velocity).with_flags(EventFlags::IS_LIVE))
+        }
+        _ => buffer.push(&MidiEvent::new(time, port.index, data).with_flags(EventFlags::IS_LIVE)),
+    }
+}
+
+/// Converts an event sent by the plugin on the given note port to a raw MIDI message.
+///
+/// This returns `None` if the event isn't a note or MIDI event for the given port, or if it
+/// cannot be represented as a MIDI message (e.g. a note event targeting all keys).
+pub fn event_to_midi(event: &UnknownEvent, port_index: u16) -> Option<[u8; 3]> {
+    match event.as_core_event()? {
+        CoreEventSpace::NoteOn(e) if e.port_index().matches(port_index) => {
+            // A Note On with a velocity of 0 would be a Note Off
+            note_to_midi(NOTE_ON, e.pckn(), e.velocity()).map(|[s, k, v]| [s, k, v.max(1)])
+        }
+        CoreEventSpace::NoteOff(e) if e.port_index().matches(port_index) => {
+            note_to_midi(NOTE_OFF, e.pckn(), e.velocity())
+        }
+        CoreEventSpace::Midi(e) if e.port_index() == port_index => Some(e.data()),
+        _ => None,
+    }
+}
+
+/// Builds a MIDI note message, if the given note targets a single channel and key.
+fn note_to_midi(status: u8, pckn: Pckn, velocity: f64) -> Option<[u8; 3]> {
+    let channel = *pckn.channel.as_specific()?;
+    let key = *pckn.key.as_specific()?;
+
+    if channel > 15 || key > 127 {
+        return None;
+    }
+
+    Some([
+        status | channel as u8,
+        key as u8,
+        clap_velocity_to_midi(velocity),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn midi_notes_round_trip() {
+        let port = NotePortInfo {
+            index: 0,
+            prefers_midi: false,
+        };
+
+        let mut buffer = EventBuffer::new();
+        push_midi_to_buffer(12, &[0x91, 60, 127], port, &mut buffer);
+        push_midi_to_buffer(34, &[0x91, 60, 0], port, &mut buffer);
+        push_midi_to_buffer(56, &[0xB1, 7, 100], port, &mut buffer);
+
+        let events: Vec<_> = buffer
+            .iter()
+            .map(|e| (e.header().time(), event_to_midi(e, 0)))
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                (12, Some([0x91, 60, 127])),
+                (34, Some([0x81, 60, 0])),
+                (56, Some([0xB1, 7, 100])),
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::host::JackHost;
+use clack_extensions::audio_ports::{AudioPortInfoBuffer, PluginAudioPorts};
+use clack_extensions::note_ports::{NoteDialects, NotePortInfoBuffer, PluginNotePorts};
+use clack_host::prelude::*;
+
+/// Information about one of the plugin's audio ports.
+pub struct AudioPortInfo {
+    /// The name of the port, as given by the plugin.
+    pub name: String,
+    /// The number of channels of the port, each mapped to a single JACK port.
+    pub channel_count: u32,
+}
+
+/// Information about the plugin's note port that gets connected to a JACK MIDI port.
+#[derive(Copy, Clone)]
+pub struct NotePortInfo {
+    /// The index of the port, as used in the events' port index.
+    pub index: u16,
+    /// If the plugin prefers to receive (or sends) note events as MIDI events instead of CLAP
+    /// Note events.
+    pub prefers_midi: bool,
+}
+
+/// All the ports of the plugin this host maps to JACK ports.
+pub struct PluginPorts {
+    /// The plugin's audio input ports.
+    pub audio_inputs: Vec<AudioPortInfo>,
+    /// The plugin's audio output ports.
+    pub audio_outputs: Vec<AudioPortInfo>,
+    /// The plugin's main note input port, if it has any.
+    pub note_input: Option<NotePortInfo>,
+    /// The plugin's main note output port, if it has any.
+    pub note_output: Option<NotePortInfo>,
+}
+
+impl PluginPorts {
+    /// Queries all the audio and note ports of the given plugin instance.
+    ///
+    /// If the plugin doesn't implement the Audio Ports extension, it is considered to have no
+    /// audio ports at all.
+    pub fn discover(instance: &mut PluginInstance<JackHost>) -> Self {
+        let ports = Self {
+            audio_inputs: discover_audio_ports(instance, true),
+            audio_outputs: discover_audio_ports(instance, false),
+            note_input: find_main_note_port(instance, true),
+            note_output: find_main_note_port(instance, false),
+        };
+
+        for (port, direction) in ports
+            .audio_inputs
+            .iter()
+            .map(|p| (p, "input"))
+            .chain(ports.audio_outputs.iter().map(|p| (p, "output")))
+        {
+            println!(
+                "Found audio {direction} port '{}' ({} channels)",
+                port.name, port.channel_count
+            );
+        }
+
+        ports
+    }
+}
+
+/// Lists all the audio ports of a plugin, in a given direction.
+fn discover_audio_ports(
+    instance: &mut PluginInstance<JackHost>,
+    is_input: bool,
+) -> Vec<AudioPortInfo> {
+    let mut handle = instance.plugin_handle();
+    let Some(plugin_audio_ports) = handle.get_extension::<PluginAudioPorts>() else {
+        return Vec::new();
+    };
+
+    let mut buffer = AudioPortInfoBuffer::new();
+    let count = plugin_audio_ports.count(&mut handle, is_input);
+
+    (0..count)
+        .filter_map(|i| {
+            let info = plugin_audio_ports.get(&mut handle, i, is_input, &mut buffer)?;
+
+            Some(AudioPortInfo {
+                name: String::from_utf8_lossy(info.name).into_owned(),
+                channel_count: info.channel_count,
+            })
+        })
+        .collect()
+}
+
+/// Tries to find the index of the main note port of a plugin in a given direction, and whether it
+/// supports CLAP note events or not.
+///
+/// This returns `None` if it couldn't find one.
+fn find_main_note_port(
+    instance: &mut PluginInstance<JackHost>,
+    is_input: bool,
+) -> Option<NotePortInfo> {
+    let mut handle = instance.plugin_handle();
+    let plugin_note_ports = handle.get_extension::<PluginNotePorts>()?;
+
+    let mut buffer = NotePortInfoBuffer::new();
+
+    // Only count up to u16::MAX, since port indexes in events only support u16
+    let ports_count = plugin_note_ports
+        .count(&mut handle, is_input)
+        .min(u16::MAX as u32);
+
+    for i in 0..ports_count {
+        let Some(port_info) = plugin_note_ports.get(&mut handle, i, is_input, &mut buffer) else {
+            continue;
+        };
+
+        if !port_info
+            .supported_dialects
+            .intersects(NoteDialects::CLAP | NoteDialects::MIDI)
+        {
+            continue;
+        }
+
+        let prefers_midi = !port_info.supported_dialects.intersects(NoteDialects::CLAP);
+        println!(
+            "Found note {} port '{}' (ID {}, Supports CLAP events: {})",
+            if is_input { "input" } else { "output" },
+            String::from_utf8_lossy(port_info.name),
+            port_info.id,
+            !prefers_midi
+        );
+
+        return Some(NotePortInfo {
+            index: i as u16,
+            prefers_midi,
+        });
+    }
+
+    None
+}
@@ -0,0 +1,103 @@
+#![doc = include_str!("../README.md")]
+#![deny(missing_docs, clippy::missing_docs_in_private_items, unsafe_code)]
+
+/// The host implementation in itself, for actually running a plugin.
+mod host;
+
+use clack_host::prelude::*;
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::process::exit;
+
+fn main() {
+    let mut args = std::env::args_os().skip(1);
+
+    let Some(bundle_path) = args.next().map(PathBuf::from) else {
+        eprintln!("Usage: clack-host-jack <path/to/bundle.clap> [plugin.id]");
+        exit(1);
+    };
+
+    let plugin_id = args.next().map(|id| id.to_string_lossy().into_owned());
+
+    if let Err(e) = run(bundle_path, plugin_id) {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
+
+/// Loads the bundle at the given path, selects the plugin to load from it, and runs it.
+// Loading a bundle means running its code, which is unsafe
+#[allow(unsafe_code)]
+fn run(bundle_path: PathBuf, plugin_id: Option<String>) -> Result<(), Box<dyn Error>> {
+    let bundle = unsafe { PluginBundle::load(&bundle_path)? };
+    let plugin_id = select_plugin(&bundle, plugin_id.as_deref())?;
+
+    println!(
+        "Loading plugin '{}' from bundle: {}",
+        plugin_id.to_string_lossy(),
+        bundle_path.display()
+    );
+
+    host::run(&bundle, &plugin_id)
+}
+
+/// Finds the ID of the plugin to load in the given bundle.
+///
+/// If no ID was given by the user, the bundle must contain exactly one plugin.
+fn select_plugin(bundle: &PluginBundle, plugin_id: Option<&str>) -> Result<CString, MainError> {
+    let factory = bundle
+        .get_plugin_factory()
+        .ok_or(MainError::MissingPluginFactory)?;
+
+    let ids: Vec<CString> = factory
+        .plugin_descriptors()
+        .filter_map(|d| d.id().map(|id| id.to_owned()))
+        .collect();
+
+    match plugin_id {
+        Some(plugin_id) => ids
+            .into_iter()
+            .find(|id| id.to_bytes() == plugin_id.as_bytes())
+            .ok_or_else(|| MainError::PluginNotFound(plugin_id.to_string())),
+        None if ids.len() == 1 => Ok(ids.into_iter().next().unwrap()),
+        None if ids.is_empty() => Err(MainError::NoPlugin),
+        None => {
+            println!("Found {} plugins in CLAP bundle:", ids.len());
+            for id in &ids {
+                println!("\t > {}", id.to_string_lossy());
+            }
+
+            Err(MainError::MultiplePlugins)
+        }
+    }
+}
+
+/// Errors happening when selecting the plugin to load.
+#[derive(Clone, Debug)]
+enum MainError {
+    /// The CLAP bundle has no plugin factory.
+    MissingPluginFactory,
+    /// The CLAP bundle does not contain any plugin.
+    NoPlugin,
+    /// The CLAP bundle contains multiple plugins, but the user didn't specify which one to load.
+    MultiplePlugins,
+    /// The CLAP bundle does not contain the plugin with the given ID.
+    PluginNotFound(String),
+}
+
+impl Display for MainError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MainError::MissingPluginFactory => f.write_str("Bundle has no plugin factory"),
+            MainError::NoPlugin => f.write_str("No plugin found in bundle"),
+            MainError::MultiplePlugins => f.write_str(
+                "Multiple plugins found in bundle: please specify which plugin ID to load",
+            ),
+            MainError::PluginNotFound(id) => write!(f, "No plugin with ID '{id}' found in bundle"),
+        }
+    }
+}
+
+impl Error for MainError {}
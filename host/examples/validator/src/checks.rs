@@ -0,0 +1,809 @@
+use crate::host::{
+    ValidatorHost, ValidatorHostAudioProcessor, ValidatorHostMainThread, ValidatorHostShared,
+};
+use crate::report::CheckOutcome;
+use clack_extensions::audio_ports::{AudioPortInfoBuffer, PluginAudioPorts};
+use clack_extensions::params::{ParamInfoBuffer, ParamInfoFlags, PluginParams};
+use clack_extensions::state::PluginState;
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::prelude::*;
+use clack_host::process::StartedPluginAudioProcessor;
+use clack_host::utils::Cookie;
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::mem::MaybeUninit;
+use std::time::Duration;
+
+/// A single conformance check, which is run against a fresh instance of the plugin.
+#[derive(Copy, Clone)]
+pub struct Check {
+    /// The short, unique name of the check.
+    pub name: &'static str,
+    /// A user-friendly description of what the check verifies.
+    pub description: &'static str,
+    /// The function performing the check.
+    run: fn(&PluginBundle, &CStr) -> CheckOutcome,
+}
+
+impl Check {
+    /// Runs this check against the plugin matching the given ID in the given bundle.
+    #[inline]
+    pub fn run(&self, bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+        (self.run)(bundle, plugin_id)
+    }
+}
+
+/// All the checks the validator runs, in order.
+pub static CHECKS: &[Check] = &[
+    Check {
+        name: "lifecycle",
+        description: "Activates, processes, resets and deactivates the plugin multiple times, with different configurations.",
+        run: check_lifecycle,
+    },
+    Check {
+        name: "threading",
+        description: "Processes audio on a separate thread, while querying the plugin from the main thread, and checks all host calls happen on the correct thread.",
+        run: check_threading,
+    },
+    Check {
+        name: "params",
+        description: "Checks parameter infos and values are consistent, and that values survive text conversions and flushes.",
+        run: check_params,
+    },
+    Check {
+        name: "state",
+        description: "Saves the plugin's state, loads it in a new instance, and checks the two instances are identical.",
+        run: check_state,
+    },
+    Check {
+        name: "event-ordering",
+        description: "Sends events throughout processing blocks, and checks the output events are well-ordered and in bounds.",
+        run: check_event_ordering,
+    },
+];
+
+/// The number of frames processed in every block, unless specified otherwise.
+const BLOCK_SIZE: u32 = 512;
+
+/// The number of blocks processed at a time, unless specified otherwise.
+const BLOCK_COUNT: usize = 8;
+
+/// The sample rate used for processing, unless specified otherwise.
+const SAMPLE_RATE: f64 = 44_100.0;
+
+/// Returns the audio configuration for the given sample rate and block size.
+fn configuration(sample_rate: f64, max_frames_count: u32) -> PluginAudioConfiguration {
+    PluginAudioConfiguration {
+        sample_rate,
+        min_frames_count: 1,
+        max_frames_count,
+    }
+}
+
+/// Turns the result of a check's body into its outcome.
+fn outcome(result: Result<CheckOutcome, String>) -> CheckOutcome {
+    result.unwrap_or_else(|e| CheckOutcome::Failed(vec![e]))
+}
+
+/// A plugin instance being validated, alongside all the violations found so far.
+struct Session {
+    /// The plugin instance.
+    instance: PluginInstance<ValidatorHost>,
+    /// The violations found so far by the checks themselves.
+    violations: Vec<String>,
+}
+
+impl Session {
+    /// Instantiates the plugin.
+    fn new(bundle: &PluginBundle, plugin_id: &CStr) -> Result<Self, String> {
+        let host_info = HostInfo::new(
+            "Clack Validator",
+            "Clack",
+            "https://github.com/prokopyl/clack",
+            env!("CARGO_PKG_VERSION"),
+        )
+        .unwrap();
+
+        let instance = PluginInstance::<ValidatorHost>::new(
+            |_| ValidatorHostShared::new(),
+            |shared| ValidatorHostMainThread::new(shared),
+            bundle,
+            plugin_id,
+            &host_info,
+        )
+        .map_err(|e| format!("Failed to instantiate the plugin: {e}"))?;
+
+        Ok(Self {
+            instance,
+            violations: Vec::new(),
+        })
+    }
+
+    /// Activates the plugin and starts its processing.
+    ///
+    /// The current thread is marked as being the audio thread until [`stop`](Self::stop) is
+    /// called, as processing happens on the main thread unless a check moves the processor to
+    /// another thread.
+    fn start(
+        &mut self,
+        configuration: PluginAudioConfiguration,
+    ) -> Result<StartedPluginAudioProcessor<ValidatorHost>, String> {
+        let processor = self
+            .instance
+            .activate(
+                |shared, _| ValidatorHostAudioProcessor::new(shared),
+                configuration,
+            )
+            .map_err(|e| format!("Failed to activate the plugin: {e}"))?;
+
+        self.host()
+            .set_audio_thread(Some(std::thread::current().id()));
+
+        processor.start_processing().map_err(|e| {
+            self.host().set_audio_thread(None);
+            format!("Failed to start processing: {e}")
+        })
+    }
+
+    /// Stops the plugin's processing, and deactivates it.
+    fn stop(&mut self, processor: StartedPluginAudioProcessor<ValidatorHost>) {
+        self.host()
+            .set_audio_thread(Some(std::thread::current().id()));
+        let processor = processor.stop_processing();
+        self.host().set_audio_thread(None);
+
+        self.instance.deactivate(processor);
+    }
+
+    /// Runs the plugin's main-thread callback, if the plugin requested it.
+    fn run_callback(&mut self) {
+        if self.host().take_callback_request() {
+            self.instance.call_on_main_thread_callback();
+        }
+    }
+
+    /// Returns the shared part of the host.
+    fn host(&self) -> &ValidatorHostShared {
+        self.instance.access_shared_handler(|s| s)
+    }
+
+    /// Returns the buffers matching the plugin's audio port configuration.
+    fn buffers(&mut self, frames_count: u32) -> ProcessBuffers {
+        let (inputs, outputs) = audio_port_layout(&mut self.instance);
+        ProcessBuffers::new(&inputs, &outputs, frames_count as usize)
+    }
+
+    /// Ends this session, returning the violations found by the check and the host.
+    fn finish(mut self) -> CheckOutcome {
+        self.violations.extend(self.host().take_violations());
+        CheckOutcome::from_violations(self.violations)
+    }
+}
+
+/// Returns the channel counts of all the input ports and output ports of the plugin.
+fn audio_port_layout(instance: &mut PluginInstance<ValidatorHost>) -> (Vec<usize>, Vec<usize>) {
+    let mut handle = instance.plugin_handle();
+    let Some(ports) = handle.get_extension::<PluginAudioPorts>() else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut buffer = AudioPortInfoBuffer::new();
+    let mut channel_counts = |is_input| {
+        (0..ports.count(&mut handle, is_input))
+            .map(|index| {
+                ports
+                    .get(&mut handle, index, is_input, &mut buffer)
+                    .map(|info| info.channel_count as usize)
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+
+    let inputs = channel_counts(true);
+    let outputs = channel_counts(false);
+    (inputs, outputs)
+}
+
+/// All the buffers needed to process blocks of audio with a plugin.
+struct ProcessBuffers {
+    /// The input buffers, indexed by port, then by channel.
+    inputs: Vec<Vec<Vec<f32>>>,
+    /// The output buffers, indexed by port, then by channel.
+    outputs: Vec<Vec<Vec<f32>>>,
+    /// The input audio port descriptors.
+    input_ports: AudioPorts,
+    /// The output audio port descriptors.
+    output_ports: AudioPorts,
+    /// The events sent to the plugin for the current block.
+    input_events: EventBuffer,
+    /// The events the plugin produced in the current block.
+    output_events: EventBuffer,
+    /// The number of frames in every block.
+    frames_count: usize,
+    /// The steady time counter.
+    steady_time: u64,
+}
+
+impl ProcessBuffers {
+    /// Creates new buffers for the given port layouts. Inputs are filled with a test signal.
+    fn new(inputs: &[usize], outputs: &[usize], frames_count: usize) -> Self {
+        let signal: Vec<f32> = (0..frames_count)
+            .map(|i| (i as f32 * 0.05).sin() * 0.5)
+            .collect();
+
+        Self {
+            inputs: inputs
+                .iter()
+                .map(|&channels| vec![signal.clone(); channels])
+                .collect(),
+            outputs: outputs
+                .iter()
+                .map(|&channels| vec![vec![0.0; frames_count]; channels])
+                .collect(),
+            input_ports: AudioPorts::with_capacity(inputs.iter().sum(), inputs.len()),
+            output_ports: AudioPorts::with_capacity(outputs.iter().sum(), outputs.len()),
+            input_events: EventBuffer::new(),
+            output_events: EventBuffer::new(),
+            frames_count,
+            steady_time: 0,
+        }
+    }
+
+    /// Processes a number of blocks.
+    ///
+    /// The `events` callback is called before every block, to fill the events sent to the plugin.
+    /// The `outputs` callback is called after every block, with the events the plugin produced.
+    ///
+    /// Any violation found in the plugin's output is added to `violations`.
+    fn process_blocks(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<ValidatorHost>,
+        block_count: usize,
+        violations: &mut Vec<String>,
+        mut events: impl FnMut(usize, &mut EventBuffer),
+        mut outputs: impl FnMut(&EventBuffer),
+    ) {
+        for block in 0..block_count {
+            self.input_events.clear();
+            self.output_events.clear();
+            events(block, &mut self.input_events);
+            self.input_events.sort();
+
+            if let Err(e) = self.process(processor) {
+                violations.push(format!("Processing failed in block {block}: {e}"));
+                return;
+            }
+
+            self.check_outputs(block, violations);
+            outputs(&self.output_events);
+        }
+    }
+
+    /// Processes a single block.
+    fn process(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<ValidatorHost>,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let frames_count = self.frames_count as u32;
+
+        let input_buffers = if self.inputs.is_empty() {
+            // SAFETY: there are no ports, so there are no buffers for frames_count to overflow.
+            unsafe { InputAudioBuffers::from_raw_buffers(&[], frames_count) }
+        } else {
+            self.input_ports
+                .with_input_buffers(self.inputs.iter_mut().map(|port| AudioPortBuffer {
+                    channels: AudioPortBufferType::f32_input_only(
+                        port.iter_mut().map(InputChannel::variable),
+                    ),
+                    latency: 0,
+                }))
+        };
+
+        let mut output_buffers = if self.outputs.is_empty() {
+            // SAFETY: there are no ports, so there are no buffers for frames_count to overflow.
+            unsafe { OutputAudioBuffers::from_raw_buffers(&mut [], frames_count) }
+        } else {
+            self.output_ports
+                .with_output_buffers(self.outputs.iter_mut().map(|port| AudioPortBuffer {
+                    channels: AudioPortBufferType::f32_output_only(
+                        port.iter_mut().map(|c| c.as_mut_slice()),
+                    ),
+                    latency: 0,
+                }))
+        };
+
+        let status = processor.process(
+            &input_buffers,
+            &mut output_buffers,
+            &self.input_events.as_input(),
+            &mut self.output_events.as_output(),
+            Some(self.steady_time),
+            None,
+        )?;
+
+        self.steady_time += self.frames_count as u64;
+        Ok(status)
+    }
+
+    /// Checks the audio and events the plugin output in the last block.
+    fn check_outputs(&self, block: usize, violations: &mut Vec<String>) {
+        for (port_index, port) in self.outputs.iter().enumerate() {
+            for (channel_index, channel) in port.iter().enumerate() {
+                if let Some(frame) = channel.iter().position(|s| !s.is_finite()) {
+                    violations.push(format!(
+                        "Output port {port_index}, channel {channel_index} contains a non-finite sample at frame {frame} of block {block}"
+                    ));
+                }
+            }
+        }
+
+        let mut previous_time = 0;
+        for event in &self.output_events {
+            let time = event.header().time();
+
+            if time < previous_time {
+                violations.push(format!(
+                    "Output events are out of order in block {block}: event at frame {time} came after an event at frame {previous_time}"
+                ));
+            }
+
+            if time as usize >= self.frames_count {
+                violations.push(format!(
+                    "Output event at frame {time} is out of the bounds of block {block} ({} frames)",
+                    self.frames_count
+                ));
+            }
+
+            previous_time = time;
+        }
+    }
+}
+
+/// Activates, processes and deactivates the plugin multiple times.
+fn check_lifecycle(bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+    outcome((|| {
+        let mut session = Session::new(bundle, plugin_id)?;
+
+        for (sample_rate, block_size) in [(SAMPLE_RATE, BLOCK_SIZE), (96_000.0, 64), (22_050.0, 1)]
+        {
+            let mut buffers = session.buffers(block_size);
+            let mut processor = session.start(configuration(sample_rate, block_size))?;
+
+            let mut violations = Vec::new();
+            buffers.process_blocks(
+                &mut processor,
+                BLOCK_COUNT,
+                &mut violations,
+                |_, _| {},
+                |_| {},
+            );
+
+            processor.reset();
+
+            // Restart the processing once before deactivating.
+            let processor = processor
+                .stop_processing()
+                .start_processing()
+                .map_err(|e| format!("Failed to restart processing: {e}"))?;
+
+            session.stop(processor);
+            session.run_callback();
+            session.violations.extend(violations);
+        }
+
+        Ok(session.finish())
+    })())
+}
+
+/// Processes the plugin on a separate audio thread, while querying it on the main thread.
+fn check_threading(bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+    outcome((|| {
+        let mut session = Session::new(bundle, plugin_id)?;
+        let mut buffers = session.buffers(BLOCK_SIZE);
+        let mut processor = session.start(configuration(SAMPLE_RATE, BLOCK_SIZE))?;
+
+        let params = session
+            .instance
+            .plugin_handle()
+            .get_extension::<PluginParams>();
+
+        let (processor, violations) = std::thread::scope(|s| {
+            let audio_thread = s.spawn(move || {
+                let thread_id = std::thread::current().id();
+                processor.access_shared_handler(|h| h.set_audio_thread(Some(thread_id)));
+
+                let mut violations = Vec::new();
+                buffers.process_blocks(
+                    &mut processor,
+                    BLOCK_COUNT * 4,
+                    &mut violations,
+                    |_, _| {},
+                    |_| {},
+                );
+
+                processor.access_shared_handler(|h| h.set_audio_thread(None));
+                (processor, violations)
+            });
+
+            // Keep the main thread busy while the plugin processes.
+            while !audio_thread.is_finished() {
+                session.run_callback();
+
+                if let Some(params) = params {
+                    let mut handle = session.instance.plugin_handle();
+                    let mut buffer = ParamInfoBuffer::new();
+
+                    for index in 0..params.count(&mut handle) {
+                        if let Some(id) = params
+                            .get_info(&mut handle, index, &mut buffer)
+                            .map(|i| i.id)
+                        {
+                            params.get_value(&mut handle, id);
+                        }
+                    }
+                }
+
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            audio_thread.join()
+        })
+        .map_err(|_| "The audio thread panicked".to_string())?;
+
+        session.stop(processor);
+        session.violations.extend(violations);
+
+        Ok(session.finish())
+    })())
+}
+
+/// Information about a single parameter.
+struct ParamSummary {
+    /// The ID of the parameter.
+    id: ClapId,
+    /// The flags of the parameter.
+    flags: ParamInfoFlags,
+    /// The minimum value of the parameter.
+    min_value: f64,
+    /// The maximum value of the parameter.
+    max_value: f64,
+}
+
+/// Returns the information about all of the plugin's parameters, reporting any inconsistencies.
+///
+/// This returns `None` if the plugin doesn't implement the params extension.
+fn read_params(session: &mut Session) -> Option<(PluginParams, Vec<ParamSummary>)> {
+    let mut handle = session.instance.plugin_handle();
+    let params = handle.get_extension::<PluginParams>()?;
+
+    let mut ids = HashSet::new();
+    let mut summaries = Vec::new();
+    let mut buffer = ParamInfoBuffer::new();
+
+    for index in 0..params.count(&mut handle) {
+        let Some(info) = params.get_info(&mut handle, index, &mut buffer) else {
+            session.violations.push(format!(
+                "Failed to get the info of the parameter at index {index}"
+            ));
+            continue;
+        };
+
+        let id = info.id;
+        let name = String::from_utf8_lossy(info.name).into_owned();
+
+        if !ids.insert(id) {
+            session.violations.push(format!(
+                "Parameter ID {id} is used by more than one parameter"
+            ));
+        }
+
+        if name.is_empty() {
+            session
+                .violations
+                .push(format!("Parameter {id} has an empty name"));
+        }
+
+        let (min, max, default) = (info.min_value, info.max_value, info.default_value);
+
+        if !(min.is_finite() && max.is_finite() && default.is_finite()) {
+            session.violations.push(format!(
+                "Parameter {id} ({name}) has non-finite bounds or default value"
+            ));
+        } else if min > max {
+            session.violations.push(format!(
+                "Parameter {id} ({name}) has a minimum value ({min}) greater than its maximum value ({max})"
+            ));
+        } else if !(min..=max).contains(&default) {
+            session.violations.push(format!(
+                "Parameter {id} ({name}) has a default value ({default}) outside of its range ({min} to {max})"
+            ));
+        }
+
+        summaries.push(ParamSummary {
+            id,
+            flags: info.flags,
+            min_value: min,
+            max_value: max,
+        });
+    }
+
+    Some((params, summaries))
+}
+
+/// Sets the given parameter values through the params extension's `flush`.
+fn flush_values(session: &mut Session, params: PluginParams, values: &[(ClapId, f64)]) {
+    let mut input_events = EventBuffer::new();
+    for &(id, value) in values {
+        input_events.push(&ParamValueEvent::new(
+            0,
+            id,
+            Pckn::match_all(),
+            value,
+            Cookie::empty(),
+        ));
+    }
+
+    let mut output_events = EventBuffer::new();
+    params.flush(
+        &mut session.instance.plugin_handle(),
+        &input_events.as_input(),
+        &mut output_events.as_output(),
+    );
+}
+
+/// Returns the text representation of the given parameter value, if the plugin supports it.
+fn value_to_text(
+    session: &mut Session,
+    params: PluginParams,
+    id: ClapId,
+    value: f64,
+) -> Option<CString> {
+    let mut buffer = [MaybeUninit::uninit(); 256];
+    let text = params
+        .value_to_text(
+            &mut session.instance.plugin_handle(),
+            id,
+            value,
+            &mut buffer,
+        )
+        .ok()?;
+
+    CString::new(text.to_vec()).ok()
+}
+
+/// Checks the consistency of the plugin's parameters.
+fn check_params(bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+    outcome((|| {
+        let mut session = Session::new(bundle, plugin_id)?;
+        let Some((params, summaries)) = read_params(&mut session) else {
+            return Ok(CheckOutcome::Skipped(
+                "The plugin does not implement the params extension".into(),
+            ));
+        };
+
+        for param in &summaries {
+            let id = param.id;
+            let mut handle = session.instance.plugin_handle();
+
+            let Some(value) = params.get_value(&mut handle, id) else {
+                session
+                    .violations
+                    .push(format!("Failed to get the value of parameter {id}"));
+                continue;
+            };
+
+            if !(param.min_value..=param.max_value).contains(&value) {
+                session.violations.push(format!(
+                    "Parameter {id} has a value ({value}) outside of its range ({} to {})",
+                    param.min_value, param.max_value
+                ));
+            }
+
+            // Converting a value to text and back must be stable.
+            if let Some(text) = value_to_text(&mut session, params, id, value) {
+                let mut handle = session.instance.plugin_handle();
+                if let Some(parsed) = params.text_to_value(&mut handle, id, &text) {
+                    let text_again = value_to_text(&mut session, params, id, parsed);
+
+                    if text_again.as_ref() != Some(&text) {
+                        session.violations.push(format!(
+                            "Parameter {id}: converting {text:?} to a value and back to text yields {text_again:?}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Values set through flush must be reflected by get_value.
+        let writable: Vec<_> = summaries
+            .iter()
+            .filter(|p| !p.flags.is_readonly())
+            .map(|p| (p.id, p.max_value))
+            .collect();
+
+        flush_values(&mut session, params, &writable);
+
+        for &(id, expected) in &writable {
+            let value = params.get_value(&mut session.instance.plugin_handle(), id);
+
+            if value.map_or(true, |v| {
+                (v - expected).abs() > 1e-6 * expected.abs().max(1.0)
+            }) {
+                session.violations.push(format!(
+                    "Parameter {id} was set to {expected} through flush, but its value is now {value:?}"
+                ));
+            }
+        }
+
+        Ok(session.finish())
+    })())
+}
+
+/// Returns the values of all the given parameters.
+fn param_values(
+    session: &mut Session,
+    params: PluginParams,
+    summaries: &[ParamSummary],
+) -> Vec<Option<f64>> {
+    let mut handle = session.instance.plugin_handle();
+    summaries
+        .iter()
+        .map(|p| params.get_value(&mut handle, p.id))
+        .collect()
+}
+
+/// Checks the plugin's state can be saved and restored.
+fn check_state(bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+    outcome((|| {
+        let mut source = Session::new(bundle, plugin_id)?;
+        let Some(state) = source
+            .instance
+            .plugin_handle()
+            .get_extension::<PluginState>()
+        else {
+            return Ok(CheckOutcome::Skipped(
+                "The plugin does not implement the state extension".into(),
+            ));
+        };
+
+        // Move all writable parameters away from their defaults, so that they are part of the state.
+        let params = read_params(&mut source);
+        if let Some((params, summaries)) = &params {
+            let values: Vec<_> = summaries
+                .iter()
+                .filter(|p| !p.flags.is_readonly())
+                .map(|p| (p.id, p.min_value + (p.max_value - p.min_value) * 0.25))
+                .collect();
+
+            flush_values(&mut source, *params, &values);
+        }
+
+        let mut saved = Vec::new();
+        state
+            .save(&mut source.instance.plugin_handle(), &mut saved)
+            .map_err(|e| format!("Failed to save the plugin's state: {e}"))?;
+
+        // Extensions are bound to the instance they were retrieved from.
+        let mut destination = Session::new(bundle, plugin_id)?;
+        let handle = destination.instance.plugin_handle();
+        let Some(state) = handle.get_extension::<PluginState>() else {
+            return Err(
+                "A second instance of the plugin does not implement the state extension".into(),
+            );
+        };
+        let destination_params = handle.get_extension::<PluginParams>();
+
+        state
+            .load(
+                &mut destination.instance.plugin_handle(),
+                &mut saved.as_slice(),
+            )
+            .map_err(|e| format!("Failed to load the plugin's state: {e}"))?;
+
+        let mut saved_again = Vec::new();
+        state
+            .save(&mut destination.instance.plugin_handle(), &mut saved_again)
+            .map_err(|e| format!("Failed to save the plugin's reloaded state: {e}"))?;
+
+        if saved != saved_again {
+            source.violations.push(format!(
+                "The state saved after loading differs from the original ({} bytes, original was {} bytes)",
+                saved_again.len(),
+                saved.len()
+            ));
+        }
+
+        if let (Some((params, summaries)), Some(destination_params)) = (&params, destination_params)
+        {
+            let expected = param_values(&mut source, *params, summaries);
+            let actual = param_values(&mut destination, destination_params, summaries);
+
+            for ((param, expected), actual) in summaries.iter().zip(expected).zip(actual) {
+                if expected != actual {
+                    source.violations.push(format!(
+                        "Parameter {} has value {actual:?} after loading the state, but {expected:?} before saving it",
+                        param.id
+                    ));
+                }
+            }
+        }
+
+        source
+            .violations
+            .extend(destination.host().take_violations());
+        Ok(source.finish())
+    })())
+}
+
+/// Sends events throughout processing blocks, and checks the plugin's output events.
+fn check_event_ordering(bundle: &PluginBundle, plugin_id: &CStr) -> CheckOutcome {
+    outcome((|| {
+        let mut session = Session::new(bundle, plugin_id)?;
+
+        let targets: Vec<_> = read_params(&mut session)
+            .map(|(_, summaries)| {
+                summaries
+                    .iter()
+                    .filter(|p| !p.flags.is_readonly())
+                    .map(|p| (p.id, p.min_value, p.max_value))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let known_ids: HashSet<_> = targets.iter().map(|(id, _, _)| *id).collect();
+
+        let mut buffers = session.buffers(BLOCK_SIZE);
+        let mut processor = session.start(configuration(SAMPLE_RATE, BLOCK_SIZE))?;
+
+        let mut violations = Vec::new();
+        let mut unknown_ids = Vec::new();
+
+        buffers.process_blocks(
+            &mut processor,
+            BLOCK_COUNT,
+            &mut violations,
+            |block, events| {
+                // Spread changes to every parameter over the block, including on its last frame.
+                let steps = [0, BLOCK_SIZE / 3, BLOCK_SIZE / 2, BLOCK_SIZE - 1];
+
+                for (i, &time) in steps.iter().enumerate() {
+                    for &(id, min, max) in &targets {
+                        let position = ((block + i) % 4) as f64 / 3.0;
+                        events.push(&ParamValueEvent::new(
+                            time,
+                            id,
+                            Pckn::match_all(),
+                            min + (max - min) * position,
+                            Cookie::empty(),
+                        ));
+                    }
+                }
+            },
+            |outputs| {
+                for event in outputs {
+                    let Some(id) = event
+                        .as_event::<ParamValueEvent>()
+                        .and_then(|e| e.param_id())
+                    else {
+                        continue;
+                    };
+
+                    if !known_ids.contains(&id) {
+                        unknown_ids.push(id);
+                    }
+                }
+            },
+        );
+
+        session.stop(processor);
+
+        for id in unknown_ids {
+            violations.push(format!(
+                "The plugin output a parameter value event for an unknown parameter ID ({id})"
+            ));
+        }
+
+        session.violations.extend(violations);
+        Ok(session.finish())
+    })())
+}
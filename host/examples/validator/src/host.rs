@@ -0,0 +1,242 @@
+use clack_extensions::audio_ports::{HostAudioPorts, HostAudioPortsImpl, RescanType};
+use clack_extensions::latency::{HostLatency, HostLatencyImpl};
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_extensions::note_ports::{
+    HostNotePorts, HostNotePortsImpl, NoteDialects, NotePortRescanFlags,
+};
+use clack_extensions::params::{
+    HostParams, HostParamsImplMainThread, HostParamsImplShared, ParamClearFlags, ParamRescanFlags,
+};
+use clack_extensions::state::{HostState, HostStateImpl};
+use clack_extensions::tail::{HostTail, HostTailImpl};
+use clack_extensions::thread_check::{HostThreadCheck, HostThreadCheckImpl};
+use clack_host::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::ThreadId;
+
+/// The host used to validate plugins.
+///
+/// It exposes most of the extensions a plugin may rely on, and checks that every call the plugin
+/// makes to them happens on the thread mandated by the CLAP specification.
+pub struct ValidatorHost;
+
+impl HostHandlers for ValidatorHost {
+    type Shared<'a> = ValidatorHostShared;
+    type MainThread<'a> = ValidatorHostMainThread<'a>;
+    type AudioProcessor<'a> = ValidatorHostAudioProcessor<'a>;
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder
+            .register::<HostAudioPorts>()
+            .register::<HostLatency>()
+            .register::<HostLog>()
+            .register::<HostNotePorts>()
+            .register::<HostParams>()
+            .register::<HostState>()
+            .register::<HostTail>()
+            .register::<HostThreadCheck>();
+    }
+}
+
+/// The thread-safe part of the validator host, which records all violations the plugin commits.
+pub struct ValidatorHostShared {
+    /// The ID of the main thread, i.e. the thread that created the plugin instance.
+    main_thread: ThreadId,
+    /// The ID of the thread currently processing audio, if any.
+    audio_thread: Mutex<Option<ThreadId>>,
+    /// Whether the plugin requested a main-thread callback that has not been run yet.
+    callback_requested: AtomicBool,
+    /// All the violations that were found so far.
+    violations: Mutex<Vec<String>>,
+}
+
+impl ValidatorHostShared {
+    /// Creates the shared state of the host. This must be called on the main thread.
+    pub fn new() -> Self {
+        Self {
+            main_thread: std::thread::current().id(),
+            audio_thread: Mutex::new(None),
+            callback_requested: AtomicBool::new(false),
+            violations: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Marks the given thread as being the audio thread, or clears it if `None` is given.
+    pub fn set_audio_thread(&self, thread: Option<ThreadId>) {
+        *self.audio_thread.lock().unwrap() = thread;
+    }
+
+    /// Returns and clears all the violations that were recorded so far.
+    pub fn take_violations(&self) -> Vec<String> {
+        std::mem::take(&mut *self.violations.lock().unwrap())
+    }
+
+    /// Returns whether the plugin requested a callback, and clears the request.
+    pub fn take_callback_request(&self) -> bool {
+        self.callback_requested.swap(false, Ordering::Relaxed)
+    }
+
+    /// Records a new violation.
+    pub fn violation(&self, message: impl Into<String>) {
+        self.violations.lock().unwrap().push(message.into());
+    }
+
+    /// Records a violation if the current thread isn't the main thread.
+    fn expect_main_thread(&self, function: &str) {
+        if !self.is_main_thread() {
+            self.violation(format!("{function} was called outside of the main thread"));
+        }
+    }
+
+    /// Records a violation if the current thread isn't the audio thread.
+    fn expect_audio_thread(&self, function: &str) {
+        if !self.is_audio_thread() {
+            self.violation(format!("{function} was called outside of the audio thread"));
+        }
+    }
+
+    /// Records a violation if the current thread is the audio thread.
+    fn expect_not_audio_thread(&self, function: &str) {
+        if self.is_audio_thread() {
+            self.violation(format!("{function} was called from the audio thread"));
+        }
+    }
+}
+
+impl Default for ValidatorHostShared {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SharedHandler<'a> for ValidatorHostShared {
+    fn request_restart(&self) {
+        // We don't restart plugins during validation.
+    }
+
+    fn request_process(&self) {
+        // We always process plugins during validation anyway.
+    }
+
+    fn request_callback(&self) {
+        self.callback_requested.store(true, Ordering::Relaxed);
+    }
+}
+
+impl HostLogImpl for ValidatorHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        match severity {
+            LogSeverity::PluginMisbehaving => {
+                self.violation(format!("The plugin reported misbehaving: {message}"))
+            }
+            LogSeverity::HostMisbehaving => self.violation(format!(
+                "The plugin reported the host misbehaving: {message}"
+            )),
+            _ => {}
+        }
+    }
+}
+
+impl HostParamsImplShared for ValidatorHostShared {
+    fn request_flush(&self) {
+        self.expect_not_audio_thread("clap_host_params.request_flush");
+    }
+}
+
+impl HostThreadCheckImpl for ValidatorHostShared {
+    fn is_main_thread(&self) -> bool {
+        std::thread::current().id() == self.main_thread
+    }
+
+    fn is_audio_thread(&self) -> bool {
+        *self.audio_thread.lock().unwrap() == Some(std::thread::current().id())
+    }
+}
+
+/// The main-thread part of the validator host.
+///
+/// This only holds a shared reference, so that thread violations performed by the plugin can be
+/// detected and reported without corrupting the host's state.
+pub struct ValidatorHostMainThread<'a> {
+    /// A reference to the shared part of the host.
+    shared: &'a ValidatorHostShared,
+}
+
+impl<'a> ValidatorHostMainThread<'a> {
+    /// Creates the main-thread part of the host.
+    pub fn new(shared: &'a ValidatorHostShared) -> Self {
+        Self { shared }
+    }
+}
+
+impl<'a> MainThreadHandler<'a> for ValidatorHostMainThread<'a> {}
+
+impl HostAudioPortsImpl for ValidatorHostMainThread<'_> {
+    fn is_rescan_flag_supported(&self, _flag: RescanType) -> bool {
+        self.shared
+            .expect_main_thread("clap_host_audio_ports.is_rescan_flag_supported");
+        true
+    }
+
+    fn rescan(&mut self, _flag: RescanType) {
+        self.shared
+            .expect_main_thread("clap_host_audio_ports.rescan");
+    }
+}
+
+impl HostLatencyImpl for ValidatorHostMainThread<'_> {
+    fn changed(&mut self) {
+        self.shared.expect_main_thread("clap_host_latency.changed");
+    }
+}
+
+impl HostNotePortsImpl for ValidatorHostMainThread<'_> {
+    fn supported_dialects(&self) -> NoteDialects {
+        self.shared
+            .expect_main_thread("clap_host_note_ports.supported_dialects");
+        NoteDialects::all()
+    }
+
+    fn rescan(&mut self, _flags: NotePortRescanFlags) {
+        self.shared
+            .expect_main_thread("clap_host_note_ports.rescan");
+    }
+}
+
+impl HostParamsImplMainThread for ValidatorHostMainThread<'_> {
+    fn rescan(&mut self, _flags: ParamRescanFlags) {
+        self.shared.expect_main_thread("clap_host_params.rescan");
+    }
+
+    fn clear(&mut self, _param_id: ClapId, _flags: ParamClearFlags) {
+        self.shared.expect_main_thread("clap_host_params.clear");
+    }
+}
+
+impl HostStateImpl for ValidatorHostMainThread<'_> {
+    fn mark_dirty(&mut self) {
+        self.shared.expect_main_thread("clap_host_state.mark_dirty");
+    }
+}
+
+/// The audio-thread part of the validator host.
+pub struct ValidatorHostAudioProcessor<'a> {
+    /// A reference to the shared part of the host.
+    shared: &'a ValidatorHostShared,
+}
+
+impl<'a> ValidatorHostAudioProcessor<'a> {
+    /// Creates the audio-thread part of the host.
+    pub fn new(shared: &'a ValidatorHostShared) -> Self {
+        Self { shared }
+    }
+}
+
+impl<'a> AudioProcessorHandler<'a> for ValidatorHostAudioProcessor<'a> {}
+
+impl HostTailImpl for ValidatorHostAudioProcessor<'_> {
+    fn changed(&mut self) {
+        self.shared.expect_audio_thread("clap_host_tail.changed");
+    }
+}
@@ -0,0 +1,45 @@
+#![doc = include_str!("../README.md")]
+#![deny(
+    missing_docs,
+    clippy::missing_docs_in_private_items,
+    clippy::undocumented_unsafe_blocks
+)]
+
+/// The individual conformance checks.
+mod checks;
+/// The host implementation used to validate plugins.
+mod host;
+/// The validation results.
+mod report;
+
+pub use checks::{Check, CHECKS};
+pub use host::{
+    ValidatorHost, ValidatorHostAudioProcessor, ValidatorHostMainThread, ValidatorHostShared,
+};
+pub use report::{CheckOutcome, CheckResult, Report};
+
+use clack_host::prelude::PluginBundle;
+use std::ffi::CStr;
+
+/// Runs all the [`CHECKS`] against the plugin matching the given ID in the given bundle.
+///
+/// Every check is run against a fresh instance of the plugin.
+pub fn validate(bundle: &PluginBundle, plugin_id: &CStr) -> Report {
+    validate_with(bundle, plugin_id, CHECKS)
+}
+
+/// Runs the given checks against the plugin matching the given ID in the given bundle.
+///
+/// Every check is run against a fresh instance of the plugin.
+pub fn validate_with(bundle: &PluginBundle, plugin_id: &CStr, checks: &[Check]) -> Report {
+    Report {
+        plugin_id: plugin_id.to_string_lossy().into_owned(),
+        results: checks
+            .iter()
+            .map(|check| CheckResult {
+                name: check.name,
+                outcome: check.run(bundle, plugin_id),
+            })
+            .collect(),
+    }
+}
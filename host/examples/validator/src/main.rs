@@ -0,0 +1,95 @@
+#![deny(missing_docs, clippy::missing_docs_in_private_items)]
+
+//! The command-line interface of the Clack validator.
+
+use clack_host::prelude::PluginBundle;
+use clack_validator::{validate_with, Check, CHECKS};
+use clap::Parser;
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::path::PathBuf;
+use std::process::exit;
+
+/// Checks CLAP plugins for conformance to the CLAP specification.
+///
+/// All the plugins contained in the given bundle are validated, unless the `--plugin-id` (`-p`)
+/// parameter is used to specify which one to validate.
+#[derive(Parser)]
+#[command(about, long_about)]
+struct Cli {
+    /// The path of the CLAP bundle to validate.
+    bundle_path: PathBuf,
+    /// Only validates the plugin with the given unique ID.
+    #[arg(short = 'p', long = "plugin-id")]
+    plugin_id: Option<String>,
+    /// Only runs the checks with the given names. Can be repeated.
+    #[arg(short = 'c', long = "check")]
+    checks: Vec<String>,
+    /// Lists all the available checks, then exits.
+    #[arg(long = "list-checks")]
+    list_checks: bool,
+}
+
+fn main() {
+    let args = Cli::parse();
+
+    if args.list_checks {
+        for check in CHECKS {
+            println!("{}: {}", check.name, check.description);
+        }
+        return;
+    }
+
+    match run(&args) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(e) => {
+            eprintln!("{e}");
+            exit(2);
+        }
+    }
+}
+
+/// Validates the requested plugins, returning whether they all passed.
+fn run(args: &Cli) -> Result<bool, Box<dyn Error>> {
+    let checks: Vec<Check> = if args.checks.is_empty() {
+        CHECKS.to_vec()
+    } else {
+        args.checks
+            .iter()
+            .map(|name| {
+                CHECKS
+                    .iter()
+                    .find(|c| c.name == name)
+                    .copied()
+                    .ok_or_else(|| format!("Unknown check: {name}"))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    // SAFETY: Loading an external library object file is inherently unsafe.
+    let bundle = unsafe { PluginBundle::load(&args.bundle_path)? };
+
+    let plugin_ids: Vec<CString> = match &args.plugin_id {
+        Some(id) => vec![CString::new(id.as_str())?],
+        None => bundle
+            .get_plugin_factory()
+            .ok_or("The bundle does not expose a plugin factory")?
+            .plugin_descriptors()
+            .filter_map(|d| d.id().map(CStr::to_owned))
+            .collect(),
+    };
+
+    if plugin_ids.is_empty() {
+        return Err("No plugins found in the bundle".into());
+    }
+
+    let mut success = true;
+    for plugin_id in &plugin_ids {
+        let report = validate_with(&bundle, plugin_id, &checks);
+        print!("{report}");
+        success &= report.is_success();
+    }
+
+    Ok(success)
+}
@@ -0,0 +1,82 @@
+use std::fmt::{Display, Formatter};
+
+/// The outcome of a single check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The plugin passed the check.
+    Passed,
+    /// The check could not be run, e.g. because the plugin doesn't implement the extension it
+    /// tests. This contains the reason why the check was skipped.
+    Skipped(String),
+    /// The plugin failed the check. This contains all the violations that were found.
+    Failed(Vec<String>),
+}
+
+impl CheckOutcome {
+    /// Returns a passing outcome if the given list of violations is empty, or a failing one
+    /// otherwise.
+    pub fn from_violations(violations: Vec<String>) -> Self {
+        if violations.is_empty() {
+            Self::Passed
+        } else {
+            Self::Failed(violations)
+        }
+    }
+
+    /// Returns `true` if this outcome is a failure.
+    #[inline]
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+/// The result of a single check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckResult {
+    /// The name of the check.
+    pub name: &'static str,
+    /// The outcome of the check.
+    pub outcome: CheckOutcome,
+}
+
+/// The results of validating a plugin against all the checks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    /// The ID of the plugin that was validated.
+    pub plugin_id: String,
+    /// The results of all the checks, in the order they were run.
+    pub results: Vec<CheckResult>,
+}
+
+impl Report {
+    /// Returns `true` if the plugin didn't fail any check.
+    pub fn is_success(&self) -> bool {
+        !self.results.iter().any(|r| r.outcome.is_failure())
+    }
+
+    /// Returns the result of the check with the given name, if it was run.
+    pub fn get(&self, name: &str) -> Option<&CheckResult> {
+        self.results.iter().find(|r| r.name == name)
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Validation results for {}:", self.plugin_id)?;
+
+        for result in &self.results {
+            match &result.outcome {
+                CheckOutcome::Passed => writeln!(f, "\t[PASS] {}", result.name)?,
+                CheckOutcome::Skipped(reason) => writeln!(f, "\t[SKIP] {}: {reason}", result.name)?,
+                CheckOutcome::Failed(violations) => {
+                    writeln!(f, "\t[FAIL] {}", result.name)?;
+                    for violation in violations {
+                        writeln!(f, "\t\t - {violation}")?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use clack_host::prelude::*;
+use clack_plugin_gain::clap_entry;
+use clack_validator::{validate, CheckOutcome, CHECKS};
+use std::ffi::CStr;
+
+#[test]
+pub fn gain_example_passes_validation() {
+    // SAFETY: this entry is a valid, compliant CLAP entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&clap_entry, "").unwrap() };
+    let plugin_id = CStr::from_bytes_with_nul(b"org.rust-audio.clack.gain\0").unwrap();
+
+    let report = validate(&bundle, plugin_id);
+
+    assert!(report.is_success(), "{report}");
+    assert_eq!(report.results.len(), CHECKS.len());
+
+    // The gain example implements all the extensions the checks rely on.
+    for result in &report.results {
+        assert_eq!(result.outcome, CheckOutcome::Passed, "{report}");
+    }
+}
@@ -0,0 +1,63 @@
+#ifndef CLACK_HOST_H
+#define CLACK_HOST_H
+
+/*
+ * C API for clack-host, available when the `c-api` feature is enabled.
+ * See the documentation of the `clack_host::c_api` module for more information.
+ */
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#include <clap/process.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct clack_bundle clack_bundle_t;
+typedef struct clack_instance clack_instance_t;
+typedef struct clack_audio_processor clack_audio_processor_t;
+
+typedef struct clack_host_callbacks {
+    void *user_data;
+    void (*request_restart)(void *user_data);
+    void (*request_process)(void *user_data);
+    void (*request_callback)(void *user_data);
+} clack_host_callbacks_t;
+
+clack_bundle_t *clack_host_bundle_load(const char *path);
+void clack_host_bundle_destroy(clack_bundle_t *bundle);
+
+clack_instance_t *clack_host_instance_new(const clack_bundle_t *bundle,
+                                          const char *plugin_id,
+                                          const char *host_name,
+                                          const char *host_vendor,
+                                          const char *host_url,
+                                          const char *host_version,
+                                          const clack_host_callbacks_t *callbacks);
+
+/* Main thread. */
+clack_audio_processor_t *clack_host_instance_activate(clack_instance_t *instance,
+                                                      double sample_rate,
+                                                      uint32_t min_frames_count,
+                                                      uint32_t max_frames_count);
+void clack_host_instance_deactivate(clack_instance_t *instance,
+                                    clack_audio_processor_t *processor);
+
+/* Audio thread. */
+bool clack_host_processor_start_processing(clack_audio_processor_t *processor);
+void clack_host_processor_stop_processing(clack_audio_processor_t *processor);
+clap_process_status clack_host_processor_process(clack_audio_processor_t *processor,
+                                                 const clap_process_t *process);
+
+/* Main thread. */
+
+void clack_host_instance_on_main_thread(clack_instance_t *instance);
+void clack_host_instance_destroy(clack_instance_t *instance);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CLACK_HOST_H */
@@ -51,8 +51,10 @@
 //!
 //! # Plugin bundle discovery
 //!
-//! As of now, Clack does not implement any utilities to aid host implementations with discovering
-//! which CLAP bundle files are available to be loaded on the filesystem.
+//! The [`scanner`] module provides a [`Scanner`](scanner::Scanner), which discovers the CLAP
+//! bundle files available in the standard search paths, and collects the descriptors of the
+//! plugins they contain. Scan results can be cached between runs, to avoid loading all bundles on
+//! every startup.
 //!
 //! Refer to the
 //! [CLAP specification](https://github.com/free-audio/clap/blob/main/include/clap/entry.h) for more
@@ -66,9 +68,12 @@ use std::ptr::NonNull;
 
 mod cache;
 mod entry;
+mod version;
 
 #[cfg(feature = "libloading")]
 mod library;
+#[cfg(feature = "libloading")]
+pub mod scanner;
 
 #[cfg(test)]
 #[allow(missing_docs)]
@@ -78,6 +83,7 @@ use crate::bundle::cache::CachedEntry;
 use crate::factory::{FactoryPointer, PluginFactory};
 pub use clack_common::entry::*;
 use clack_common::utils::ClapVersion;
+pub use version::*;
 
 /// A handle to a loaded CLAP plugin bundle file.
 ///
@@ -145,6 +151,8 @@ impl PluginBundle {
         use crate::bundle::library::PluginEntryLibrary;
 
         let path = path.as_ref();
+        crate::util::trace_span!(INFO, "load_bundle", path = ?path);
+
         let path_str = path.to_str().ok_or(PluginBundleError::InvalidUtf8Path)?;
 
         let library = PluginEntryLibrary::load(path)?;
@@ -200,6 +208,8 @@ impl PluginBundle {
         use crate::bundle::library::PluginEntryLibrary;
 
         let path = path.as_ref();
+        crate::util::trace_span!(INFO, "load_bundle", path = ?path);
+
         let path_str = path.to_str().ok_or(PluginBundleError::InvalidUtf8Path)?;
 
         let library = PluginEntryLibrary::load_from_symbol_in_library(library, symbol_name)?;
@@ -245,6 +255,8 @@ impl PluginBundle {
         inner: &'static EntryDescriptor,
         plugin_path: &str,
     ) -> Result<Self, PluginBundleError> {
+        crate::util::trace_span!(INFO, "load_bundle", path = plugin_path);
+
         Ok(Self {
             inner: cache::load_from_raw(inner, plugin_path)?,
         })
@@ -313,6 +325,57 @@ impl PluginBundle {
     pub fn version(&self) -> ClapVersion {
         ClapVersion::from_raw(self.raw_entry().clap_version)
     }
+
+    /// Returns `true` if this bundle's CLAP version is ABI-compatible with the CLAP version
+    /// implemented by Clack.
+    ///
+    /// Bundles with incompatible versions are already rejected when loading them, so this always
+    /// returns `true` for bundles loaded through Clack. Use [`check_version`](Self::check_version)
+    /// to apply a host's own version policy.
+    #[inline]
+    pub fn is_compatible(&self) -> bool {
+        self.version().is_compatible()
+    }
+
+    /// Checks this bundle's CLAP version against the given host [`ClapVersionPolicy`].
+    ///
+    /// This returns the bundle version's [`VersionCompatibility`] if it is accepted by the policy.
+    /// If it is [`VersionCompatibility::Newer`], hosts may want to warn their users that the bundle
+    /// may rely on features this host isn't aware of.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`PluginBundleError::UnsupportedClapVersion`] if the bundle's version is
+    /// rejected by the policy. See [`ClapVersionPolicy::ensure`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use clack_host::bundle::{ClapVersionPolicy, VersionCompatibility};
+    /// use clack_host::prelude::PluginBundle;
+    /// use clack_host::utils::ClapVersion;
+    ///
+    /// let policy = ClapVersionPolicy::new().with_minimum_version(ClapVersion {
+    ///     major: 1,
+    ///     minor: 1,
+    ///     revision: 0,
+    /// });
+    ///
+    /// let bundle = unsafe { PluginBundle::load("/home/user/.clap/u-he/libdiva.so")? };
+    ///
+    /// if bundle.check_version(&policy)? == VersionCompatibility::Newer {
+    ///     println!("Warning: this plugin uses CLAP {}, which is newer than this host.", bundle.version());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn check_version(
+        &self,
+        policy: &ClapVersionPolicy,
+    ) -> Result<VersionCompatibility, PluginBundleError> {
+        policy.ensure(self.version())
+    }
 }
 
 /// Errors that can occur while loading a [`PluginBundle`].
@@ -337,6 +400,14 @@ pub enum PluginBundleError {
         /// See [`ClapVersion::CURRENT`] to get the current clap version.
         plugin_version: ClapVersion,
     },
+    /// The exposed entry uses a CLAP version that is compatible, but not supported by the host's
+    /// [`ClapVersionPolicy`].
+    ///
+    /// See [`PluginBundle::check_version`].
+    UnsupportedClapVersion {
+        /// The CLAP version that the entry uses.
+        plugin_version: ClapVersion,
+    },
     /// The given path is not a valid C string.
     InvalidNulPath(NulError),
     /// The entry's `init` method failed.
@@ -369,6 +440,10 @@ impl Display for PluginBundleError {
                 f.write_str("Plugin descriptor path contains invalid UTF-8")
             }
             PluginBundleError::NullEntryPointer => f.write_str("Plugin entry pointer is null"),
+            PluginBundleError::UnsupportedClapVersion { plugin_version } => write!(
+                f,
+                "Unsupported CLAP version: plugin is v{plugin_version}, which this host does not support"
+            ),
             PluginBundleError::IncompatibleClapVersion { plugin_version } => write!(
                 f,
                 "Incompatible CLAP version: plugin is v{}, host is v{}",
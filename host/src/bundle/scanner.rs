@@ -0,0 +1,736 @@
+//! Discovery and scanning of the CLAP bundle files installed on the system.
+//!
+//! A [`Scanner`] looks for `.clap` files in a list of search paths (by default, the standard
+//! paths defined by the CLAP specification), loads each bundle, and collects the descriptors of
+//! all the plugins they contain into a [`ScanReport`].
+//!
+//! Loading hundreds of plugin bundles can take a long time. Scanners can therefore keep the
+//! results of previous scans in a cache file (see [`Scanner::with_cache`]): bundles whose files
+//! haven't changed since they were last scanned are not loaded again, and their cached
+//! [`ScannedBundle`] is returned instead.
+//!
+//! Some bundles crash or hang while being loaded. Scanners can record those in a quarantine file
+//! (see [`Scanner::with_quarantine`]), so that they are skipped by subsequent scans instead of
+//! crashing the host every time. Quarantined bundles are scanned again once their file changes,
+//! or once the host explicitly [retries](Scanner::retry_quarantined) them.
+//!
+//! Scanners may also load bundles in short-lived child processes instead (see
+//! [`Scanner::with_worker`] and [`ScanWorker`]), so that a bundle that crashes doesn't take the
+//! host down with it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use clack_host::bundle::scanner::Scanner;
+//!
+//! let scanner = Scanner::with_standard_paths()
+//!     .with_cache("/home/user/.cache/my-host/clap.cache")
+//!     .with_quarantine("/home/user/.cache/my-host/clap.quarantine");
+//! let report = unsafe { scanner.scan() };
+//!
+//! for bundle in &report.bundles {
+//!     for plugin in &bundle.plugins {
+//!         println!("{:?}: {:?}", bundle.path, plugin.name);
+//!     }
+//! }
+//!
+//! for failure in &report.failures {
+//!     eprintln!("Failed to scan {:?}: {}", failure.path, failure.error);
+//! }
+//!
+//! for path in &report.quarantined {
+//!     eprintln!("Skipped quarantined bundle {path:?}");
+//! }
+//! ```
+
+use crate::bundle::{PluginBundle, PluginBundleError};
+use crate::factory::OwnedPluginDescriptor;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Duration, Instant, SystemTime};
+
+mod cache;
+mod encoding;
+mod quarantine;
+mod worker;
+
+pub use cache::ScanCache;
+pub use quarantine::{Quarantine, QuarantineEntry, QuarantineReason};
+pub use worker::ScanWorker;
+
+use quarantine::ScanMarker;
+
+/// How a [`Scanner`] checks whether a cached bundle is still up to date.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum CacheValidation {
+    /// The bundle file's size and modification time are compared with the cached ones.
+    ///
+    /// This is very fast, as it doesn't need to read the bundle file.
+    #[default]
+    Metadata,
+    /// In addition to its metadata, a hash of the bundle file's contents is compared with the
+    /// cached one.
+    ///
+    /// This catches bundle files that were replaced while keeping their modification time, at
+    /// the cost of reading every bundle file on each scan.
+    ContentHash,
+}
+
+/// Information about a bundle file, used to detect when it changed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BundleFileInfo {
+    /// The size of the bundle file, in bytes.
+    pub size: u64,
+    /// The last modification time of the bundle file, if the platform supports it.
+    pub modified: Option<SystemTime>,
+    /// A hash of the contents of the bundle file, if it was computed.
+    ///
+    /// This is only computed with [`CacheValidation::ContentHash`], and only for bundles that
+    /// are single files.
+    pub content_hash: Option<u64>,
+}
+
+impl BundleFileInfo {
+    /// Reads the information of the bundle file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the file's metadata (or its contents, when computing its hash)
+    /// failed.
+    pub fn read(path: &Path, validation: CacheValidation) -> io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+
+        let content_hash = if validation == CacheValidation::ContentHash && metadata.is_file() {
+            Some(hash_file(path)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            content_hash,
+        })
+    }
+
+    /// Returns `true` if this information matches the given cached information, i.e. if the
+    /// bundle file didn't change since it was cached.
+    ///
+    /// Content hashes are compared only if both sides have one.
+    pub fn matches(&self, cached: &BundleFileInfo) -> bool {
+        let hash_matches = match (self.content_hash, cached.content_hash) {
+            (Some(current), Some(cached)) => current == cached,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        self.size == cached.size && self.modified == cached.modified && hash_matches
+    }
+}
+
+/// Hashes the contents of a file, using the 64-bit FNV-1a hash function.
+///
+/// This isn't a cryptographic hash: it only aims to detect changes, and must stay stable across
+/// Rust versions and platforms, as it is stored in cache files.
+fn hash_file(path: &Path) -> io::Result<u64> {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut hash = OFFSET_BASIS;
+
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => return Ok(hash),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for byte in &buffer[..read] {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+}
+
+/// The results of scanning a single bundle, as stored in a [`ScanCache`].
+///
+/// When the `serde` feature is enabled, this type can be serialized and deserialized.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScannedBundle {
+    /// The path of the bundle file.
+    pub path: PathBuf,
+    /// Information about the bundle file, as of when it was scanned.
+    pub file: BundleFileInfo,
+    /// The descriptors of all the plugins the bundle contains.
+    pub plugins: Vec<OwnedPluginDescriptor>,
+}
+
+/// Errors that can occur while scanning a bundle.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ScanError {
+    /// The bundle file's information could not be read.
+    Io(io::Error),
+    /// The bundle could not be loaded.
+    Load(PluginBundleError),
+    /// The bundle doesn't expose a plugin factory.
+    NoPluginFactory,
+    /// Loading the bundle took longer than the scanner's
+    /// [timeout](Scanner::with_scan_timeout). The bundle was quarantined.
+    TimedOut {
+        /// How long loading the bundle took.
+        duration: Duration,
+    },
+    /// The bundle could not be loaded by the [scan worker](ScanWorker) process.
+    ///
+    /// This contains the message of the underlying [`PluginBundleError`], which cannot be sent
+    /// across processes.
+    WorkerLoad(String),
+    /// The [scan worker](ScanWorker) process crashed, or exited with an error, while scanning
+    /// the bundle.
+    WorkerCrashed(ExitStatus),
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Io(e) => write!(f, "Failed to read bundle file: {e}"),
+            ScanError::Load(e) => write!(f, "Failed to load bundle: {e}"),
+            ScanError::NoPluginFactory => f.write_str("Bundle doesn't expose a plugin factory"),
+            ScanError::TimedOut { duration } => {
+                write!(f, "Bundle took too long to load ({duration:?})")
+            }
+            ScanError::WorkerLoad(message) => {
+                write!(f, "Failed to load bundle in scan worker: {message}")
+            }
+            ScanError::WorkerCrashed(status) => write!(f, "Scan worker crashed ({status})"),
+        }
+    }
+}
+
+impl Error for ScanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScanError::Io(e) => Some(e),
+            ScanError::Load(e) => Some(e),
+            ScanError::NoPluginFactory
+            | ScanError::TimedOut { .. }
+            | ScanError::WorkerLoad(_)
+            | ScanError::WorkerCrashed(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScanError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<PluginBundleError> for ScanError {
+    #[inline]
+    fn from(e: PluginBundleError) -> Self {
+        Self::Load(e)
+    }
+}
+
+/// A bundle that failed to be scanned, as reported in a [`ScanReport`].
+#[derive(Debug)]
+pub struct ScanFailure {
+    /// The path of the bundle file.
+    pub path: PathBuf,
+    /// The error that occurred.
+    pub error: ScanError,
+}
+
+/// The results of a [`Scanner::scan`].
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// All the bundles that were scanned successfully, including the ones taken from the cache.
+    pub bundles: Vec<ScannedBundle>,
+    /// All the bundles that failed to be scanned.
+    pub failures: Vec<ScanFailure>,
+    /// How many of the scanned bundles were taken from the cache, without being loaded.
+    pub cached_count: usize,
+    /// All the bundles that were skipped because they are quarantined.
+    ///
+    /// This includes the bundle that crashed the previous scan, if any.
+    pub quarantined: Vec<PathBuf>,
+    /// The error that occurred while saving the cache file, if any.
+    ///
+    /// Failing to save the cache doesn't affect the scan results, but the next scan will have to
+    /// load all the bundles again.
+    pub cache_error: Option<io::Error>,
+    /// The last error that occurred while saving the quarantine file, if any.
+    pub quarantine_error: Option<io::Error>,
+}
+
+/// Discovers and scans the CLAP bundles located in a list of search paths.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Clone, Debug, Default)]
+pub struct Scanner {
+    search_paths: Vec<PathBuf>,
+    cache_path: Option<PathBuf>,
+    validation: CacheValidation,
+    quarantine_path: Option<PathBuf>,
+    scan_timeout: Option<Duration>,
+    worker: Option<ScanWorker>,
+}
+
+impl Scanner {
+    /// Creates a new scanner, without any search paths.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new scanner, using the standard search paths defined by the CLAP specification.
+    ///
+    /// These are all the paths listed in the `CLAP_PATH` environment variable, followed by the
+    /// platform's standard paths:
+    ///
+    /// * On Linux and other Unix platforms: `~/.clap` and `/usr/lib/clap`;
+    /// * On Windows: `%COMMONPROGRAMFILES%\CLAP` and `%LOCALAPPDATA%\Programs\Common\CLAP`;
+    /// * On macOS: `/Library/Audio/Plug-Ins/CLAP` and `~/Library/Audio/Plug-Ins/CLAP`.
+    pub fn with_standard_paths() -> Self {
+        let mut search_paths: Vec<PathBuf> = std::env::var_os("CLAP_PATH")
+            .map(|paths| std::env::split_paths(&paths).collect())
+            .unwrap_or_default();
+
+        search_paths.extend(standard_paths());
+
+        Self {
+            search_paths,
+            ..Self::default()
+        }
+    }
+
+    /// Adds a search path to this scanner.
+    #[inline]
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.search_paths.push(path.into());
+        self
+    }
+
+    /// Keeps the results of the scans in the cache file at the given path.
+    ///
+    /// When scanning, bundles that didn't change since the previous scan are taken from the cache
+    /// instead of being loaded. The cache file is then updated with the results of the new scan.
+    ///
+    /// Bundles that failed to be scanned are never cached, so that they are retried on every scan.
+    #[inline]
+    pub fn with_cache(mut self, cache_path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(cache_path.into());
+        self
+    }
+
+    /// Sets how cached bundles are checked for changes. The default is
+    /// [`CacheValidation::Metadata`].
+    #[inline]
+    pub fn with_cache_validation(mut self, validation: CacheValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Keeps a list of the bundles that crashed or timed out while being scanned in the quarantine
+    /// file at the given path.
+    ///
+    /// Before loading a bundle, the scanner records it in a marker file next to the quarantine
+    /// file (with a `.scanning` extension appended), and removes the marker once loading returned.
+    /// If loading crashes the process, the marker is left behind, and the next scan quarantines
+    /// the bundle it names.
+    ///
+    /// Quarantined bundles are skipped by subsequent scans, and reported in
+    /// [`ScanReport::quarantined`], until their file changes or until they are
+    /// [retried](Self::retry_quarantined).
+    #[inline]
+    pub fn with_quarantine(mut self, quarantine_path: impl Into<PathBuf>) -> Self {
+        self.quarantine_path = Some(quarantine_path.into());
+        self
+    }
+
+    /// Sets how long loading a single bundle may take before it is quarantined.
+    ///
+    /// Unless this scanner [uses a worker](Self::with_worker), bundles are loaded in the scanning
+    /// thread, so this cannot interrupt a bundle that hangs: it only catches bundles that are
+    /// unreasonably slow to load. Worker processes, however, are killed once they time out.
+    ///
+    /// Bundles that took too long are reported as failed with [`ScanError::TimedOut`], and are
+    /// quarantined if this scanner [has a quarantine](Self::with_quarantine).
+    ///
+    /// By default, there is no timeout.
+    #[inline]
+    pub fn with_scan_timeout(mut self, timeout: Duration) -> Self {
+        self.scan_timeout = Some(timeout);
+        self
+    }
+
+    /// Loads bundles in worker processes spawned by the given [`ScanWorker`], instead of in the
+    /// current process.
+    ///
+    /// Crashing worker processes are reported as [`ScanError::WorkerCrashed`], and their bundle is
+    /// quarantined if this scanner [has a quarantine](Self::with_quarantine).
+    #[inline]
+    pub fn with_worker(mut self, worker: ScanWorker) -> Self {
+        self.worker = Some(worker);
+        self
+    }
+
+    /// Returns the search paths of this scanner.
+    #[inline]
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// Returns the path of the cache file of this scanner, if it has one.
+    #[inline]
+    pub fn cache_path(&self) -> Option<&Path> {
+        self.cache_path.as_deref()
+    }
+
+    /// Returns the path of the quarantine file of this scanner, if it has one.
+    #[inline]
+    pub fn quarantine_path(&self) -> Option<&Path> {
+        self.quarantine_path.as_deref()
+    }
+
+    /// Returns the current quarantine list of this scanner.
+    ///
+    /// This is empty if this scanner doesn't have a quarantine, or if its quarantine file doesn't
+    /// exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the quarantine file could not be read.
+    pub fn quarantine(&self) -> io::Result<Quarantine> {
+        match &self.quarantine_path {
+            Some(path) => Quarantine::load_or_default(path),
+            None => Ok(Quarantine::new()),
+        }
+    }
+
+    /// Removes the bundle at the given path from quarantine, so that it is scanned again by the
+    /// next scan.
+    ///
+    /// This returns `false` if the bundle wasn't quarantined.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the quarantine file could not be read or written.
+    pub fn retry_quarantined(&self, bundle_path: &Path) -> io::Result<bool> {
+        let Some(quarantine_path) = &self.quarantine_path else {
+            return Ok(false);
+        };
+
+        let mut quarantine = Quarantine::load_or_default(quarantine_path)?;
+        if quarantine.remove(bundle_path).is_none() {
+            return Ok(false);
+        }
+
+        quarantine.save(quarantine_path)?;
+        Ok(true)
+    }
+
+    /// Removes all bundles from quarantine, so that they are all scanned again by the next scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the quarantine file could not be written.
+    pub fn clear_quarantine(&self) -> io::Result<()> {
+        match &self.quarantine_path {
+            Some(path) => Quarantine::new().save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the paths of all the `.clap` bundles in the search paths, without loading them.
+    ///
+    /// Search paths are searched recursively, and missing or unreadable directories are skipped.
+    /// Paths are sorted in each directory, and bundles found in more than one search path are only
+    /// returned once.
+    pub fn discover(&self) -> Vec<PathBuf> {
+        let mut bundles = Vec::new();
+        let mut visited = HashSet::new();
+
+        for path in &self.search_paths {
+            discover_in(path, &mut visited, &mut bundles);
+        }
+
+        let mut seen = HashSet::new();
+        bundles.retain(|path| seen.insert(path.clone()));
+        bundles
+    }
+
+    /// Discovers and scans all the bundles in the search paths, using the cache if this scanner
+    /// has one.
+    ///
+    /// An unreadable or corrupted cache file is ignored, and replaced by the results of this scan.
+    ///
+    /// # Safety
+    ///
+    /// This loads all the bundles that are not cached, which is inherently unsafe. See
+    /// [`PluginBundle::load`].
+    pub unsafe fn scan(&self) -> ScanReport {
+        self.scan_paths(self.discover())
+    }
+
+    /// Scans the given bundles, using the cache if this scanner has one.
+    ///
+    /// This is the same as [`scan`](Self::scan), but skips discovery.
+    ///
+    /// # Safety
+    ///
+    /// This loads all the bundles that are not cached, which is inherently unsafe. See
+    /// [`PluginBundle::load`].
+    pub unsafe fn scan_paths(&self, paths: impl IntoIterator<Item = PathBuf>) -> ScanReport {
+        let previous_cache = match &self.cache_path {
+            Some(cache_path) => ScanCache::load(cache_path).unwrap_or_default(),
+            None => ScanCache::new(),
+        };
+
+        let mut cache = ScanCache::new();
+        let mut report = ScanReport::default();
+        let mut quarantine = QuarantineState::open(self, &mut report);
+
+        for path in paths {
+            let file = match BundleFileInfo::read(&path, self.validation) {
+                Ok(file) => file,
+                Err(e) => {
+                    report.failures.push(ScanFailure {
+                        path,
+                        error: e.into(),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(quarantine) = &mut quarantine {
+                if quarantine.list.is_quarantined(&path, &file) {
+                    report.quarantined.push(path);
+                    continue;
+                }
+            }
+
+            let scanned = match previous_cache.get(&path, &file) {
+                Some(cached) => {
+                    report.cached_count += 1;
+                    Ok(cached.clone())
+                }
+                None => self.load_bundle(&path, file, quarantine.as_mut(), &mut report),
+            };
+
+            match scanned {
+                Ok(bundle) => {
+                    cache.insert(bundle.clone());
+                    report.bundles.push(bundle);
+                }
+                Err(error) => report.failures.push(ScanFailure { path, error }),
+            }
+        }
+
+        if let Some(cache_path) = &self.cache_path {
+            report.cache_error = cache.save(cache_path).err();
+        }
+
+        report
+    }
+
+    /// Loads a bundle that isn't cached, either in a worker process or guarded by the
+    /// quarantine's marker, and quarantines it if it crashed or timed out.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginBundle::load`].
+    unsafe fn load_bundle(
+        &self,
+        path: &Path,
+        file: BundleFileInfo,
+        quarantine: Option<&mut QuarantineState>,
+        report: &mut ScanReport,
+    ) -> Result<ScannedBundle, ScanError> {
+        let scanned = match &self.worker {
+            Some(worker) => worker.scan_with_timeout(path, self.scan_timeout.or(worker.timeout())),
+            None => self.load_in_process(path, quarantine.as_deref()),
+        };
+
+        let reason = match &scanned {
+            Err(ScanError::WorkerCrashed(_)) => Some(QuarantineReason::Crashed),
+            Err(ScanError::TimedOut { duration }) => Some(QuarantineReason::TimedOut {
+                duration: *duration,
+            }),
+            _ => None,
+        };
+
+        if let Some(quarantine) = quarantine {
+            match reason {
+                Some(reason) => quarantine.insert(path.to_path_buf(), reason, file, report),
+                // The bundle changed since it was quarantined, and now loads without crashing.
+                None if quarantine.list.remove(path).is_some() => quarantine.save(report),
+                None => {}
+            }
+        }
+
+        Ok(ScannedBundle {
+            path: path.to_path_buf(),
+            file,
+            plugins: scanned?,
+        })
+    }
+
+    /// Loads a bundle in the current process, and collects its plugin descriptors.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginBundle::load`].
+    unsafe fn load_in_process(
+        &self,
+        path: &Path,
+        quarantine: Option<&QuarantineState>,
+    ) -> Result<Vec<OwnedPluginDescriptor>, ScanError> {
+        if let Some(quarantine) = quarantine {
+            // If the marker can't be written, crashes just won't be detected.
+            let _ = quarantine.marker.begin(path);
+        }
+
+        let start = Instant::now();
+        let scanned = PluginBundle::load(path)
+            .map_err(ScanError::from)
+            .and_then(|bundle| descriptors(&bundle));
+        let duration = start.elapsed();
+
+        if let Some(quarantine) = quarantine {
+            quarantine.marker.end();
+        }
+
+        match self.scan_timeout {
+            Some(timeout) if duration > timeout => Err(ScanError::TimedOut { duration }),
+            _ => scanned,
+        }
+    }
+}
+
+/// The quarantine of a [`Scanner`], as used during a single scan.
+struct QuarantineState<'a> {
+    path: &'a Path,
+    list: Quarantine,
+    marker: ScanMarker,
+}
+
+impl<'a> QuarantineState<'a> {
+    /// Loads the scanner's quarantine, and quarantines the bundle that crashed the previous scan,
+    /// if any.
+    fn open(scanner: &'a Scanner, report: &mut ScanReport) -> Option<Self> {
+        let path = scanner.quarantine_path.as_deref()?;
+
+        let mut state = Self {
+            path,
+            // A corrupted quarantine file is discarded, like a corrupted cache.
+            list: Quarantine::load_or_default(path).unwrap_or_default(),
+            marker: ScanMarker::for_quarantine(path),
+        };
+
+        if let Some(crashed) = state.marker.take_crashed() {
+            if let Ok(file) = BundleFileInfo::read(&crashed, scanner.validation) {
+                state.insert(crashed, QuarantineReason::Crashed, file, report);
+            }
+        }
+
+        Some(state)
+    }
+
+    fn insert(
+        &mut self,
+        path: PathBuf,
+        reason: QuarantineReason,
+        file: BundleFileInfo,
+        report: &mut ScanReport,
+    ) {
+        self.list.insert(QuarantineEntry { path, reason, file });
+        self.save(report);
+    }
+
+    /// Saves the quarantine right away, so that it isn't lost if the scan crashes later on.
+    fn save(&self, report: &mut ScanReport) {
+        if let Err(e) = self.list.save(self.path) {
+            report.quarantine_error = Some(e);
+        }
+    }
+}
+
+/// Collects the descriptors of all the plugins in the given bundle.
+fn descriptors(bundle: &PluginBundle) -> Result<Vec<OwnedPluginDescriptor>, ScanError> {
+    let factory = bundle
+        .get_plugin_factory()
+        .ok_or(ScanError::NoPluginFactory)?;
+
+    Ok(factory
+        .plugin_descriptors()
+        .map(OwnedPluginDescriptor::from)
+        .collect())
+}
+
+fn discover_in(directory: &Path, visited: &mut HashSet<PathBuf>, bundles: &mut Vec<PathBuf>) {
+    // Symlinks to directories are followed, so directories are tracked by their canonical path
+    // to avoid walking symlink cycles forever.
+    let Ok(canonical) = directory.canonicalize() else {
+        return;
+    };
+
+    if !visited.insert(canonical) {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| Some(e.ok()?.path())).collect();
+    paths.sort();
+
+    for path in paths {
+        // On macOS, bundles are directories: they must not be searched into.
+        if path.extension().is_some_and(|ext| ext == "clap") {
+            bundles.push(path);
+        } else if path.is_dir() {
+            discover_in(&path, visited, bundles);
+        }
+    }
+}
+
+fn standard_paths() -> Vec<PathBuf> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let mut paths = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        if let Some(common) = std::env::var_os("COMMONPROGRAMFILES") {
+            paths.push(PathBuf::from(common).join("CLAP"));
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            paths.push(PathBuf::from(local).join("Programs/Common/CLAP"));
+        }
+    } else if cfg!(target_os = "macos") {
+        paths.push(PathBuf::from("/Library/Audio/Plug-Ins/CLAP"));
+        if let Some(home) = home() {
+            paths.push(home.join("Library/Audio/Plug-Ins/CLAP"));
+        }
+    } else {
+        if let Some(home) = home() {
+            paths.push(home.join(".clap"));
+        }
+        paths.push(PathBuf::from("/usr/lib/clap"));
+    }
+
+    paths
+}
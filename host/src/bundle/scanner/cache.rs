@@ -0,0 +1,209 @@
+use super::encoding::{Reader, Writer};
+use super::*;
+use std::collections::HashMap;
+
+/// The magic bytes at the start of every cache file.
+const MAGIC: &[u8; 8] = b"CLACKSCN";
+/// The version of the cache file format. Cache files with any other version are discarded.
+const FORMAT_VERSION: u32 = 2;
+
+/// A cache of [`ScannedBundle`]s, stored in a file between scans.
+///
+/// This is normally handled by [`Scanner::with_cache`], but can also be used directly by hosts
+/// that implement their own scanning strategy.
+///
+/// The cache file uses a compact, versioned binary format that is specific to this
+/// implementation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanCache {
+    bundles: HashMap<PathBuf, ScannedBundle>,
+}
+
+impl ScanCache {
+    /// Creates a new, empty cache.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a cache from the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read, or if it is not a valid cache file. Errors
+    /// of the [`InvalidData`](io::ErrorKind::InvalidData) kind indicate a corrupted or outdated
+    /// cache file, which can safely be discarded.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// Saves this cache into the file at the given path, replacing it if it exists.
+    ///
+    /// Parent directories are created if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, self.encode())
+    }
+
+    /// Returns the number of cached bundles.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    /// Returns `true` if no bundles are cached.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    /// Returns the cached bundle at the given path, if its cached file information matches the
+    /// given one.
+    ///
+    /// See [`BundleFileInfo::matches`].
+    pub fn get(&self, path: &Path, file: &BundleFileInfo) -> Option<&ScannedBundle> {
+        self.bundles
+            .get(path)
+            .filter(|bundle| file.matches(&bundle.file))
+    }
+
+    /// Inserts a scanned bundle into this cache, replacing any bundle with the same path.
+    #[inline]
+    pub fn insert(&mut self, bundle: ScannedBundle) {
+        self.bundles.insert(bundle.path.clone(), bundle);
+    }
+
+    /// Removes the cached bundle at the given path, and returns it.
+    #[inline]
+    pub fn remove(&mut self, path: &Path) -> Option<ScannedBundle> {
+        self.bundles.remove(path)
+    }
+
+    /// Returns an iterator over all the cached bundles, in no particular order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &ScannedBundle> {
+        self.bundles.values()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::new(MAGIC, FORMAT_VERSION);
+
+        let mut bundles: Vec<_> = self.bundles.values().collect();
+        // Keep the output deterministic.
+        bundles.sort_by(|a, b| a.path.cmp(&b.path));
+
+        writer.u32(bundles.len() as u32);
+        for bundle in bundles {
+            writer.path(&bundle.path);
+            writer.file_info(&bundle.file);
+
+            writer.u32(bundle.plugins.len() as u32);
+            for plugin in &bundle.plugins {
+                writer.descriptor(plugin);
+            }
+        }
+
+        writer.finish()
+    }
+
+    fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader::new(data, MAGIC, FORMAT_VERSION)?;
+
+        let mut cache = Self::new();
+
+        for _ in 0..reader.u32()? {
+            let path = reader.path()?;
+            let file = reader.file_info()?;
+
+            let mut plugins = Vec::new();
+            for _ in 0..reader.u32()? {
+                plugins.push(reader.descriptor()?);
+            }
+
+            cache.insert(ScannedBundle {
+                path,
+                file,
+                plugins,
+            });
+        }
+
+        reader.finish()?;
+        Ok(cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn cached_bundle(path: &str) -> ScannedBundle {
+        ScannedBundle {
+            path: path.into(),
+            file: BundleFileInfo {
+                size: 1024,
+                modified: Some(UNIX_EPOCH + Duration::new(1_700_000_000, 42)),
+                content_hash: Some(0xDEAD_BEEF),
+            },
+            plugins: vec![OwnedPluginDescriptor {
+                id: Some(CString::new("org.rust-audio.clack.gain").unwrap()),
+                name: Some(CString::new("Gain").unwrap()),
+                features: vec![CString::new("audio-effect").unwrap()],
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn roundtrips() {
+        let mut cache = ScanCache::new();
+        cache.insert(cached_bundle("/b.clap"));
+        cache.insert(cached_bundle("/a.clap"));
+
+        let encoded = cache.encode();
+        assert_eq!(ScanCache::decode(&encoded).unwrap(), cache);
+
+        // Truncated or otherwise corrupted files are rejected.
+        for len in 0..encoded.len() {
+            let error = ScanCache::decode(&encoded[..len]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn only_returns_unchanged_bundles() {
+        let bundle = cached_bundle("/a.clap");
+        let mut cache = ScanCache::new();
+        cache.insert(bundle.clone());
+
+        let path = Path::new("/a.clap");
+        assert_eq!(cache.get(path, &bundle.file), Some(&bundle));
+
+        let without_hash = BundleFileInfo {
+            content_hash: None,
+            ..bundle.file
+        };
+        assert_eq!(cache.get(path, &without_hash), Some(&bundle));
+
+        let resized = BundleFileInfo {
+            size: 2048,
+            ..bundle.file
+        };
+        assert_eq!(cache.get(path, &resized), None);
+
+        let rehashed = BundleFileInfo {
+            content_hash: Some(0),
+            ..bundle.file
+        };
+        assert_eq!(cache.get(path, &rehashed), None);
+        assert_eq!(cache.get(Path::new("/b.clap"), &bundle.file), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! The binary encoding shared by the files of the scanner.
+
+use super::*;
+use std::borrow::Cow;
+use std::ffi::CString;
+use std::time::{Duration, UNIX_EPOCH};
+
+pub(super) fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "Invalid scanner file")
+}
+
+/// Returns the OS-specific bytes of the given path, which can be turned back into the same path
+/// using [`path_from_bytes`], even if it isn't valid Unicode.
+#[cfg(unix)]
+pub(super) fn path_to_bytes(path: &Path) -> Cow<'_, [u8]> {
+    Cow::Borrowed(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()))
+}
+
+/// Turns bytes returned by [`path_to_bytes`] back into a path.
+///
+/// This returns `None` if the bytes aren't a valid path for the current platform.
+#[cfg(unix)]
+pub(super) fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    Some(<std::ffi::OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(bytes).into())
+}
+
+/// On Windows, these are the little-endian bytes of the path's UTF-16 code units.
+#[cfg(windows)]
+pub(super) fn path_to_bytes(path: &Path) -> Cow<'_, [u8]> {
+    let wide = std::os::windows::ffi::OsStrExt::encode_wide(path.as_os_str());
+    Cow::Owned(wide.flat_map(u16::to_le_bytes).collect())
+}
+
+#[cfg(windows)]
+pub(super) fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+
+    Some(<std::ffi::OsString as std::os::windows::ffi::OsStringExt>::from_wide(&wide).into())
+}
+
+/// Other platforms only support Unicode paths.
+#[cfg(not(any(unix, windows)))]
+pub(super) fn path_to_bytes(path: &Path) -> Cow<'_, [u8]> {
+    Cow::Borrowed(path.to_str().unwrap_or_default().as_bytes())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    std::str::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// A writer for the binary files of the scanner.
+pub(super) struct Writer(Vec<u8>);
+
+impl Writer {
+    pub(super) fn new(magic: &[u8; 8], version: u32) -> Self {
+        let mut writer = Self(magic.to_vec());
+        writer.u32(version);
+        writer
+    }
+
+    /// Creates a writer without any header, e.g. for data nested in another file.
+    pub(super) fn headerless() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(super) fn finish(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub(super) fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub(super) fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(super) fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(super) fn bytes(&mut self, bytes: &[u8]) {
+        self.u32(bytes.len() as u32);
+        self.0.extend_from_slice(bytes);
+    }
+
+    /// Writes a path, using its OS-specific bytes. See [`path_to_bytes`].
+    pub(super) fn path(&mut self, path: &Path) {
+        self.bytes(&path_to_bytes(path));
+    }
+
+    pub(super) fn duration(&mut self, duration: Duration) {
+        self.u64(duration.as_secs());
+        self.u32(duration.subsec_nanos());
+    }
+
+    pub(super) fn optional_string(&mut self, string: &Option<CString>) {
+        match string {
+            Some(string) => {
+                self.u8(1);
+                self.bytes(string.as_bytes());
+            }
+            None => self.u8(0),
+        }
+    }
+
+    pub(super) fn file_info(&mut self, file: &BundleFileInfo) {
+        self.u64(file.size);
+
+        match file
+            .modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        {
+            Some(modified) => {
+                self.u8(1);
+                self.duration(modified);
+            }
+            None => self.u8(0),
+        }
+
+        match file.content_hash {
+            Some(hash) => {
+                self.u8(1);
+                self.u64(hash);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    pub(super) fn descriptor(&mut self, descriptor: &OwnedPluginDescriptor) {
+        self.optional_string(&descriptor.id);
+        self.optional_string(&descriptor.name);
+        self.optional_string(&descriptor.vendor);
+        self.optional_string(&descriptor.url);
+        self.optional_string(&descriptor.manual_url);
+        self.optional_string(&descriptor.support_url);
+        self.optional_string(&descriptor.version);
+        self.optional_string(&descriptor.description);
+
+        self.u32(descriptor.features.len() as u32);
+        for feature in &descriptor.features {
+            self.bytes(feature.as_bytes());
+        }
+    }
+}
+
+/// A reader for the binary files of the scanner, which fails on any malformed or unexpected
+/// data.
+pub(super) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(data: &'a [u8], magic: &[u8; 8], version: u32) -> io::Result<Self> {
+        let mut reader = Self(data);
+
+        if reader.take(magic.len())? != magic || reader.u32()? != version {
+            return Err(invalid_data());
+        }
+
+        Ok(reader)
+    }
+
+    /// Creates a reader for data without any header, e.g. nested in another file.
+    pub(super) fn headerless(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    /// Checks that all the data was read.
+    pub(super) fn finish(self) -> io::Result<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_data())
+        }
+    }
+
+    pub(super) fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data());
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    pub(super) fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(super) fn u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub(super) fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(super) fn flag(&mut self) -> io::Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data()),
+        }
+    }
+
+    pub(super) fn bytes(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(super) fn path(&mut self) -> io::Result<PathBuf> {
+        path_from_bytes(self.bytes()?).ok_or_else(invalid_data)
+    }
+
+    /// Reads a duration, which fails if its nanoseconds don't fit in a second.
+    pub(super) fn duration(&mut self) -> io::Result<Duration> {
+        let secs = self.u64()?;
+        let nanos = self.u32()?;
+
+        if nanos >= 1_000_000_000 {
+            return Err(invalid_data());
+        }
+
+        Ok(Duration::new(secs, nanos))
+    }
+
+    pub(super) fn string(&mut self) -> io::Result<CString> {
+        CString::new(self.bytes()?).map_err(|_| invalid_data())
+    }
+
+    pub(super) fn optional_string(&mut self) -> io::Result<Option<CString>> {
+        if self.flag()? {
+            Ok(Some(self.string()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(super) fn file_info(&mut self) -> io::Result<BundleFileInfo> {
+        let size = self.u64()?;
+
+        let modified = if self.flag()? {
+            let since_epoch = self.duration()?;
+            Some(
+                UNIX_EPOCH
+                    .checked_add(since_epoch)
+                    .ok_or_else(invalid_data)?,
+            )
+        } else {
+            None
+        };
+
+        let content_hash = if self.flag()? {
+            Some(self.u64()?)
+        } else {
+            None
+        };
+
+        Ok(BundleFileInfo {
+            size,
+            modified,
+            content_hash,
+        })
+    }
+
+    pub(super) fn descriptor(&mut self) -> io::Result<OwnedPluginDescriptor> {
+        let mut descriptor = OwnedPluginDescriptor {
+            id: self.optional_string()?,
+            name: self.optional_string()?,
+            vendor: self.optional_string()?,
+            url: self.optional_string()?,
+            manual_url: self.optional_string()?,
+            support_url: self.optional_string()?,
+            version: self.optional_string()?,
+            description: self.optional_string()?,
+            features: Vec::new(),
+        };
+
+        for _ in 0..self.u32()? {
+            descriptor.features.push(self.string()?);
+        }
+
+        Ok(descriptor)
+    }
+}
@@ -0,0 +1,308 @@
+use super::encoding::{path_from_bytes, path_to_bytes, Reader, Writer};
+use super::*;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::time::Duration;
+
+/// The magic bytes at the start of every quarantine file.
+const MAGIC: &[u8; 8] = b"CLACKQRN";
+/// The version of the quarantine file format. Files with any other version are discarded.
+const FORMAT_VERSION: u32 = 2;
+
+/// Why a bundle was put in [`Quarantine`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum QuarantineReason {
+    /// Scanning the bundle crashed the scanning process.
+    Crashed,
+    /// Scanning the bundle took longer than the scanner's [timeout](Scanner::with_scan_timeout).
+    TimedOut {
+        /// How long scanning the bundle took, or how long it was waited for.
+        duration: Duration,
+    },
+}
+
+impl Display for QuarantineReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuarantineReason::Crashed => f.write_str("Bundle crashed while being scanned"),
+            QuarantineReason::TimedOut { duration } => {
+                write!(f, "Bundle timed out while being scanned ({duration:?})")
+            }
+        }
+    }
+}
+
+/// A bundle in [`Quarantine`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QuarantineEntry {
+    /// The path of the bundle file.
+    pub path: PathBuf,
+    /// Why the bundle was quarantined.
+    pub reason: QuarantineReason,
+    /// Information about the bundle file, as of when it was quarantined.
+    ///
+    /// If the bundle file changes (e.g. when the plugin is updated), it is scanned again.
+    pub file: BundleFileInfo,
+}
+
+/// A list of bundles that crashed or timed out while being scanned, and that are skipped by
+/// subsequent scans.
+///
+/// This is normally handled by [`Scanner::with_quarantine`], which records the bundles that
+/// misbehave, and skips them until they are [retried](Scanner::retry_quarantined) or until their
+/// file changes.
+///
+/// Like the [`ScanCache`], the quarantine file uses a compact, versioned binary format that is
+/// specific to this implementation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Quarantine {
+    entries: HashMap<PathBuf, QuarantineEntry>,
+}
+
+impl Quarantine {
+    /// Creates a new, empty quarantine list.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a quarantine list from the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read, or if it is not a valid quarantine file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// Loads a quarantine list from the file at the given path, or returns an empty list if the
+    /// file doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but could not be read, or if it is not a valid
+    /// quarantine file.
+    pub fn load_or_default(path: &Path) -> io::Result<Self> {
+        match Self::load(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            result => result,
+        }
+    }
+
+    /// Saves this quarantine list into the file at the given path, replacing it if it exists.
+    ///
+    /// Parent directories are created if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, self.encode())
+    }
+
+    /// Returns the number of quarantined bundles.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no bundles are quarantined.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the quarantine entry of the bundle at the given path, if it is quarantined.
+    #[inline]
+    pub fn get(&self, path: &Path) -> Option<&QuarantineEntry> {
+        self.entries.get(path)
+    }
+
+    /// Returns `true` if the bundle at the given path is quarantined, and its file didn't change
+    /// since it was.
+    pub fn is_quarantined(&self, path: &Path, file: &BundleFileInfo) -> bool {
+        self.entries
+            .get(path)
+            .is_some_and(|entry| file.matches(&entry.file))
+    }
+
+    /// Quarantines a bundle, replacing any entry with the same path.
+    #[inline]
+    pub fn insert(&mut self, entry: QuarantineEntry) {
+        self.entries.insert(entry.path.clone(), entry);
+    }
+
+    /// Removes the bundle at the given path from quarantine, and returns its entry.
+    #[inline]
+    pub fn remove(&mut self, path: &Path) -> Option<QuarantineEntry> {
+        self.entries.remove(path)
+    }
+
+    /// Removes all bundles from quarantine.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns an iterator over all the quarantined bundles, in no particular order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &QuarantineEntry> {
+        self.entries.values()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::new(MAGIC, FORMAT_VERSION);
+
+        let mut entries: Vec<_> = self.entries.values().collect();
+        // Keep the output deterministic.
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        writer.u32(entries.len() as u32);
+        for entry in entries {
+            writer.path(&entry.path);
+            writer.file_info(&entry.file);
+
+            match entry.reason {
+                QuarantineReason::Crashed => writer.u8(0),
+                QuarantineReason::TimedOut { duration } => {
+                    writer.u8(1);
+                    writer.duration(duration);
+                }
+            }
+        }
+
+        writer.finish()
+    }
+
+    fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader::new(data, MAGIC, FORMAT_VERSION)?;
+        let mut quarantine = Self::new();
+
+        for _ in 0..reader.u32()? {
+            let path = reader.path()?;
+            let file = reader.file_info()?;
+
+            let reason = match reader.u8()? {
+                0 => QuarantineReason::Crashed,
+                1 => QuarantineReason::TimedOut {
+                    duration: reader.duration()?,
+                },
+                _ => return Err(super::encoding::invalid_data()),
+            };
+
+            quarantine.insert(QuarantineEntry { path, reason, file });
+        }
+
+        reader.finish()?;
+        Ok(quarantine)
+    }
+}
+
+/// A marker file recording which bundle is currently being scanned.
+///
+/// The marker is written before loading a bundle, and removed once loading returned. If the
+/// scanning process crashes, the marker is left behind, and the next scan can quarantine the
+/// bundle that caused the crash.
+pub(super) struct ScanMarker {
+    path: PathBuf,
+}
+
+impl ScanMarker {
+    pub(super) fn for_quarantine(quarantine_path: &Path) -> Self {
+        let mut path = OsString::from(quarantine_path.as_os_str());
+        path.push(".scanning");
+
+        Self { path: path.into() }
+    }
+
+    /// Returns the bundle that was being scanned when the previous scan crashed, if any, and
+    /// removes the marker.
+    pub(super) fn take_crashed(&self) -> Option<PathBuf> {
+        let contents = std::fs::read(&self.path).ok()?;
+        let _ = std::fs::remove_file(&self.path);
+
+        path_from_bytes(&contents)
+    }
+
+    pub(super) fn begin(&self, bundle_path: &Path) -> io::Result<()> {
+        std::fs::write(&self.path, path_to_bytes(bundle_path))
+    }
+
+    pub(super) fn end(&self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips() {
+        let file = BundleFileInfo {
+            size: 1024,
+            modified: None,
+            content_hash: None,
+        };
+
+        let mut quarantine = Quarantine::new();
+        quarantine.insert(QuarantineEntry {
+            path: "/a.clap".into(),
+            reason: QuarantineReason::Crashed,
+            file,
+        });
+        quarantine.insert(QuarantineEntry {
+            path: "/b.clap".into(),
+            reason: QuarantineReason::TimedOut {
+                duration: Duration::from_millis(12_500),
+            },
+            file,
+        });
+
+        let encoded = quarantine.encode();
+        assert_eq!(Quarantine::decode(&encoded).unwrap(), quarantine);
+        assert!(Quarantine::decode(&encoded[..encoded.len() - 1]).is_err());
+
+        // Durations with more than a second worth of nanoseconds are rejected.
+        let mut invalid = encoded.clone();
+        let nanos = invalid.len() - 4;
+        invalid[nanos..].copy_from_slice(&1_000_000_000u32.to_le_bytes());
+        assert!(Quarantine::decode(&invalid).is_err());
+
+        assert!(quarantine.is_quarantined(Path::new("/a.clap"), &file));
+        let updated = BundleFileInfo { size: 2048, ..file };
+        assert!(!quarantine.is_quarantined(Path::new("/a.clap"), &updated));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn roundtrips_non_unicode_paths() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/\xff.clap"));
+        let file = BundleFileInfo {
+            size: 1024,
+            modified: None,
+            content_hash: None,
+        };
+
+        let mut quarantine = Quarantine::new();
+        quarantine.insert(QuarantineEntry {
+            path: path.into(),
+            reason: QuarantineReason::Crashed,
+            file,
+        });
+
+        let decoded = Quarantine::decode(&quarantine.encode()).unwrap();
+        assert!(decoded.is_quarantined(path, &file));
+
+        let marker_path = std::env::temp_dir().join(format!("clack-marker-{}", std::process::id()));
+        let marker = ScanMarker::for_quarantine(&marker_path);
+        marker.begin(path).unwrap();
+        assert_eq!(marker.take_crashed().as_deref(), Some(path));
+    }
+}
@@ -0,0 +1,346 @@
+use super::encoding::{invalid_data, Reader, Writer};
+use super::*;
+use std::ffi::OsString;
+use std::io::Write;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+
+/// The magic bytes at the start of every result sent by a worker.
+const MAGIC: &[u8; 8] = b"CLACKWRK";
+/// The version of the worker protocol. Results with any other version are ignored.
+const FORMAT_VERSION: u32 = 1;
+
+/// The environment variable holding the path of the bundle a worker process must scan.
+const BUNDLE_PATH_VAR: &str = "CLACK_SCAN_WORKER_BUNDLE";
+
+/// How often a running worker process is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A helper loading bundles and enumerating their plugin descriptors in short-lived child
+/// processes, so that a misbehaving bundle cannot crash the host while it is being scanned.
+///
+/// For every bundle to scan, a worker process is spawned from the given program (by default,
+/// the host's own executable), with the path of the bundle in its environment. That process must
+/// call [`ScanWorker::run_if_requested`] as early as possible: it then loads the bundle, sends
+/// its plugin descriptors back to the host through its standard output, and exits.
+///
+/// If the worker process crashes, this is reported as [`ScanError::WorkerCrashed`], and if it
+/// takes longer than the [timeout](Self::with_timeout), it is killed and reported as
+/// [`ScanError::TimedOut`]. Either way, the host keeps running.
+///
+/// This can be used on its own, or through [`Scanner::with_worker`], which also quarantines the
+/// bundles that crashed or timed out.
+///
+/// # Example
+///
+/// ```no_run
+/// use clack_host::bundle::scanner::{ScanWorker, Scanner};
+/// use std::time::Duration;
+///
+/// fn main() {
+///     // This must be done before anything else: if this process is a scan worker, this scans
+///     // the requested bundle and exits.
+///     unsafe { ScanWorker::run_if_requested() };
+///
+///     let worker = ScanWorker::current_exe().unwrap();
+///     let scanner = Scanner::with_standard_paths()
+///         .with_worker(worker)
+///         .with_scan_timeout(Duration::from_secs(10));
+///
+///     // Bundles are only loaded in worker processes.
+///     let report = unsafe { scanner.scan() };
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScanWorker {
+    program: PathBuf,
+    args: Vec<OsString>,
+    timeout: Option<Duration>,
+}
+
+impl ScanWorker {
+    /// Creates a scan worker spawning worker processes from the given program.
+    ///
+    /// The program must call [`ScanWorker::run_if_requested`] on startup.
+    #[inline]
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Creates a scan worker spawning worker processes from the current executable.
+    ///
+    /// The current executable must call [`ScanWorker::run_if_requested`] on startup, or the host
+    /// will spawn copies of itself instead of workers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path of the current executable could not be retrieved. See
+    /// [`std::env::current_exe`].
+    pub fn current_exe() -> io::Result<Self> {
+        Ok(Self::new(std::env::current_exe()?))
+    }
+
+    /// Adds a command-line argument given to every worker process.
+    #[inline]
+    pub fn with_arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Sets how long a worker process may run before it is killed. By default, there is no
+    /// timeout.
+    ///
+    /// When used through a [`Scanner`], the scanner's own
+    /// [timeout](Scanner::with_scan_timeout) takes precedence over this one.
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the timeout of this worker, if it has one.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the program worker processes are spawned from.
+    #[inline]
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+
+    /// Scans the bundle at the given path in a new worker process, and returns the descriptors
+    /// of all the plugins it contains.
+    ///
+    /// This blocks until the worker process exits and its output is closed, or until the timeout
+    /// elapses. Worker processes still running at that point are killed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the worker process could not be spawned or didn't return any results,
+    /// if it crashed or timed out, or if the bundle failed to be scanned.
+    #[inline]
+    pub fn scan(&self, bundle_path: &Path) -> Result<Vec<OwnedPluginDescriptor>, ScanError> {
+        self.scan_with_timeout(bundle_path, self.timeout)
+    }
+
+    pub(super) fn scan_with_timeout(
+        &self,
+        bundle_path: &Path,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<OwnedPluginDescriptor>, ScanError> {
+        let start = Instant::now();
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .env(BUNDLE_PATH_VAR, bundle_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        // The output must be read while waiting, or the worker could block on a full pipe.
+        let mut stdout = child.stdout.take().ok_or_else(no_result)?;
+        let (sender, output) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = sender.send(stdout.read_to_end(&mut output).map(|_| output));
+        });
+
+        let status = wait(&mut child, start, timeout)?;
+        if !status.success() {
+            return Err(ScanError::WorkerCrashed(status));
+        }
+
+        // Processes spawned by the bundle may inherit the worker's output, and keep it open after
+        // the worker exited. Reading it is therefore bound by the same timeout.
+        let output = match timeout {
+            None => output.recv().map_err(|_| no_result())?,
+            Some(timeout) => match output.recv_timeout(timeout.saturating_sub(start.elapsed())) {
+                Ok(output) => output,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(ScanError::TimedOut {
+                        duration: start.elapsed(),
+                    })
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(ScanError::Io(no_result())),
+            },
+        }?;
+
+        decode_result(&output).ok_or_else(|| ScanError::Io(no_result()))?
+    }
+
+    /// Runs the scan requested by the host, if the current process was spawned as a worker by a
+    /// [`ScanWorker`], and exits. Otherwise, this does nothing.
+    ///
+    /// This must be called by the worker program as early as possible, before it outputs anything
+    /// or starts any threads. Output from the scanned bundle is tolerated.
+    ///
+    /// # Safety
+    ///
+    /// If the current process is a worker, this loads the requested bundle, which is inherently
+    /// unsafe. See [`PluginBundle::load`].
+    pub unsafe fn run_if_requested() {
+        let Some(bundle_path) = std::env::var_os(BUNDLE_PATH_VAR) else {
+            return;
+        };
+
+        let bundle_path = PathBuf::from(bundle_path);
+
+        let code = match PluginBundle::load(&bundle_path) {
+            Ok(bundle) => {
+                let result = descriptors(&bundle);
+                let sent = send_result(&result);
+
+                // The bundle is unloaded after sending the results, but a crash while unloading
+                // is still reported to the host.
+                drop(bundle);
+                i32::from(sent.is_err())
+            }
+            Err(e) => i32::from(send_result(&Err(e.into())).is_err()),
+        };
+
+        std::process::exit(code);
+    }
+}
+
+fn no_result() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Scan worker didn't return any results",
+    )
+}
+
+/// Waits for the worker process to exit, killing it once the given timeout has elapsed since
+/// `start`.
+fn wait(
+    child: &mut Child,
+    start: Instant,
+    timeout: Option<Duration>,
+) -> Result<ExitStatus, ScanError> {
+    let Some(timeout) = timeout else {
+        return Ok(child.wait()?);
+    };
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        let duration = start.elapsed();
+        if duration > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(ScanError::TimedOut { duration });
+        }
+
+        std::thread::sleep(POLL_INTERVAL.min(timeout - duration));
+    }
+}
+
+fn send_result(result: &Result<Vec<OwnedPluginDescriptor>, ScanError>) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&encode_result(result))?;
+    stdout.flush()
+}
+
+fn encode_result(result: &Result<Vec<OwnedPluginDescriptor>, ScanError>) -> Vec<u8> {
+    let mut payload = Writer::headerless();
+
+    match result {
+        Ok(plugins) => {
+            payload.u8(0);
+            payload.u32(plugins.len() as u32);
+            for plugin in plugins {
+                payload.descriptor(plugin);
+            }
+        }
+        Err(ScanError::NoPluginFactory) => payload.u8(1),
+        Err(e) => {
+            payload.u8(2);
+            // Load errors are sent without their "Failed to load bundle" prefix, as the host
+            // reports them as worker load errors, which have their own.
+            let message = match e {
+                ScanError::Load(load_error) => load_error.to_string(),
+                other => other.to_string(),
+            };
+            payload.bytes(message.as_bytes());
+        }
+    }
+
+    // The payload is length-prefixed, so that it can be found among the bundle's own output.
+    let mut writer = Writer::new(MAGIC, FORMAT_VERSION);
+    writer.bytes(&payload.finish());
+    writer.finish()
+}
+
+/// Finds and decodes the result in a worker's output, which may also contain anything the
+/// scanned bundle printed.
+fn decode_result(output: &[u8]) -> Option<Result<Vec<OwnedPluginDescriptor>, ScanError>> {
+    (0..output.len())
+        .filter(|&start| output[start..].starts_with(MAGIC))
+        .find_map(|start| decode_frame(&output[start..]).ok())
+}
+
+fn decode_frame(frame: &[u8]) -> io::Result<Result<Vec<OwnedPluginDescriptor>, ScanError>> {
+    let mut reader = Reader::new(frame, MAGIC, FORMAT_VERSION)?;
+    let mut payload = Reader::headerless(reader.bytes()?);
+
+    let result = match payload.u8()? {
+        0 => {
+            let mut plugins = Vec::new();
+            for _ in 0..payload.u32()? {
+                plugins.push(payload.descriptor()?);
+            }
+            Ok(plugins)
+        }
+        1 => Err(ScanError::NoPluginFactory),
+        2 => {
+            let message = String::from_utf8_lossy(payload.bytes()?).into_owned();
+            Err(ScanError::WorkerLoad(message))
+        }
+        _ => return Err(invalid_data()),
+    };
+
+    payload.finish()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn finds_results_among_bundle_output() {
+        let plugins = vec![OwnedPluginDescriptor {
+            id: Some(CString::new("org.rust-audio.clack.gain").unwrap()),
+            ..Default::default()
+        }];
+
+        let mut output = b"Loading... CLACKWRK but not really\n".to_vec();
+        output.extend(encode_result(&Ok(plugins.clone())));
+        output.extend(b"Unloading...\n");
+
+        assert_eq!(decode_result(&output).unwrap().unwrap(), plugins);
+
+        let output = encode_result(&Err(ScanError::NoPluginFactory));
+        assert!(matches!(
+            decode_result(&output),
+            Some(Err(ScanError::NoPluginFactory))
+        ));
+
+        // Load errors aren't prefixed twice.
+        let output = encode_result(&Err(ScanError::Load(PluginBundleError::NullEntryPointer)));
+        let Some(Err(ScanError::WorkerLoad(message))) = decode_result(&output) else {
+            panic!("Expected a worker load error");
+        };
+        assert_eq!(message, PluginBundleError::NullEntryPointer.to_string());
+
+        assert!(decode_result(b"CLACKWRK").is_none());
+        assert!(decode_result(b"").is_none());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::bundle::PluginBundleError;
+use clack_common::utils::ClapVersion;
+
+/// How a bundle's CLAP version relates to the versions supported by a host.
+///
+/// See [`ClapVersionPolicy::check`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VersionCompatibility {
+    /// The bundle's CLAP version is fully supported.
+    Supported,
+    /// The bundle's CLAP version is ABI-compatible, but newer than the CLAP version implemented by
+    /// Clack (see [`ClapVersion::CURRENT`]).
+    ///
+    /// The bundle can be loaded and used, but may rely on features or semantics this host isn't
+    /// aware of. Hosts may want to warn their users about it.
+    Newer,
+    /// The bundle's CLAP version is ABI-compatible, but older than the minimum version the host
+    /// supports.
+    TooOld,
+    /// The bundle's CLAP version is not ABI-compatible with the CLAP version implemented by
+    /// Clack (e.g. a pre-1.0 version).
+    ///
+    /// Such bundles are always rejected when loading them.
+    Incompatible,
+}
+
+impl VersionCompatibility {
+    /// Returns `true` if a bundle with this compatibility can be used, i.e. if it is either
+    /// [`Supported`](Self::Supported) or [`Newer`](Self::Newer).
+    #[inline]
+    pub const fn is_usable(&self) -> bool {
+        matches!(self, Self::Supported | Self::Newer)
+    }
+}
+
+/// A host's policy regarding which CLAP versions it supports.
+///
+/// By default, this accepts any version that is ABI-compatible with the CLAP version implemented
+/// by Clack, including newer ones.
+///
+/// See [`PluginBundle::check_version`](crate::bundle::PluginBundle::check_version).
+///
+/// # Example
+///
+/// ```
+/// use clack_host::bundle::{ClapVersionPolicy, VersionCompatibility};
+/// use clack_host::utils::ClapVersion;
+///
+/// let policy = ClapVersionPolicy::new().with_minimum_version(ClapVersion {
+///     major: 1,
+///     minor: 1,
+///     revision: 0,
+/// });
+///
+/// let old = ClapVersion { major: 1, minor: 0, revision: 3 };
+/// assert_eq!(policy.check(old), VersionCompatibility::TooOld);
+/// assert_eq!(policy.check(ClapVersion::CURRENT), VersionCompatibility::Supported);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ClapVersionPolicy {
+    minimum_version: ClapVersion,
+    allow_newer: bool,
+}
+
+impl ClapVersionPolicy {
+    /// The oldest CLAP version Clack is ABI-compatible with.
+    pub const MINIMUM_COMPATIBLE_VERSION: ClapVersion = ClapVersion {
+        major: 1,
+        minor: 0,
+        revision: 0,
+    };
+
+    /// Creates a new policy, accepting all ABI-compatible CLAP versions.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            minimum_version: Self::MINIMUM_COMPATIBLE_VERSION,
+            allow_newer: true,
+        }
+    }
+
+    /// Sets the minimum CLAP version supported by the host.
+    ///
+    /// Versions older than [`MINIMUM_COMPATIBLE_VERSION`](Self::MINIMUM_COMPATIBLE_VERSION) are
+    /// never supported, even if a lower minimum version is set.
+    #[inline]
+    pub const fn with_minimum_version(mut self, minimum_version: ClapVersion) -> Self {
+        self.minimum_version = minimum_version;
+        self
+    }
+
+    /// Sets whether CLAP versions newer than [`ClapVersion::CURRENT`] should be accepted.
+    ///
+    /// This is `true` by default, as CLAP guarantees newer minor versions are ABI-compatible.
+    #[inline]
+    pub const fn allow_newer_versions(mut self, allow_newer: bool) -> Self {
+        self.allow_newer = allow_newer;
+        self
+    }
+
+    /// Returns the minimum CLAP version supported by the host.
+    #[inline]
+    pub const fn minimum_version(&self) -> ClapVersion {
+        self.minimum_version
+    }
+
+    /// Returns how the given CLAP version relates to this policy.
+    pub fn check(&self, version: ClapVersion) -> VersionCompatibility {
+        if !version.is_compatible() {
+            VersionCompatibility::Incompatible
+        } else if version < self.minimum_version {
+            VersionCompatibility::TooOld
+        } else if version > ClapVersion::CURRENT {
+            VersionCompatibility::Newer
+        } else {
+            VersionCompatibility::Supported
+        }
+    }
+
+    /// Checks the given CLAP version against this policy.
+    ///
+    /// This returns the version's [`VersionCompatibility`] if it is accepted by this policy.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`PluginBundleError::IncompatibleClapVersion`] if the version is not
+    /// ABI-compatible, and [`PluginBundleError::UnsupportedClapVersion`] if it is rejected by this
+    /// policy.
+    pub fn ensure(&self, version: ClapVersion) -> Result<VersionCompatibility, PluginBundleError> {
+        match self.check(version) {
+            VersionCompatibility::Incompatible => Err(PluginBundleError::IncompatibleClapVersion {
+                plugin_version: version,
+            }),
+            VersionCompatibility::Newer if !self.allow_newer => {
+                Err(PluginBundleError::UnsupportedClapVersion {
+                    plugin_version: version,
+                })
+            }
+            VersionCompatibility::TooOld => Err(PluginBundleError::UnsupportedClapVersion {
+                plugin_version: version,
+            }),
+            compatibility => Ok(compatibility),
+        }
+    }
+}
+
+impl Default for ClapVersionPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const fn version(major: u32, minor: u32, revision: u32) -> ClapVersion {
+        ClapVersion {
+            major,
+            minor,
+            revision,
+        }
+    }
+
+    #[test]
+    fn default_policy_accepts_all_compatible_versions() {
+        let policy = ClapVersionPolicy::default();
+
+        assert_eq!(
+            policy.check(version(0, 9, 0)),
+            VersionCompatibility::Incompatible
+        );
+        assert_eq!(
+            policy.check(version(1, 0, 0)),
+            VersionCompatibility::Supported
+        );
+        assert_eq!(
+            policy.check(ClapVersion::CURRENT),
+            VersionCompatibility::Supported
+        );
+
+        let newer = version(
+            ClapVersion::CURRENT.major,
+            ClapVersion::CURRENT.minor + 1,
+            0,
+        );
+        assert_eq!(policy.check(newer), VersionCompatibility::Newer);
+        assert_eq!(policy.ensure(newer).unwrap(), VersionCompatibility::Newer);
+    }
+
+    #[test]
+    fn policy_rejects_unsupported_versions() {
+        let policy = ClapVersionPolicy::new()
+            .with_minimum_version(version(1, 1, 0))
+            .allow_newer_versions(false);
+
+        assert!(matches!(
+            policy.ensure(version(1, 0, 5)),
+            Err(PluginBundleError::UnsupportedClapVersion { .. })
+        ));
+        assert!(matches!(
+            policy.ensure(version(0, 1, 0)),
+            Err(PluginBundleError::IncompatibleClapVersion { .. })
+        ));
+
+        let newer = version(
+            ClapVersion::CURRENT.major,
+            ClapVersion::CURRENT.minor + 1,
+            0,
+        );
+        assert!(matches!(
+            policy.ensure(newer),
+            Err(PluginBundleError::UnsupportedClapVersion { .. })
+        ));
+        assert!(policy.ensure(ClapVersion::CURRENT).is_ok());
+    }
+}
@@ -0,0 +1,419 @@
+#![deny(missing_docs)]
+
+//! A small, C-compatible façade over the core host operations.
+//!
+//! This module allows non-Rust applications to use Clack as their CLAP hosting layer, by linking
+//! to a `staticlib` or `cdylib` crate depending on `clack-host`, and calling the `clack_host_*`
+//! functions it exports. The matching C declarations are available in the
+//! `include/clack_host.h` header of this crate.
+//!
+//! Only the core lifecycle of a plugin instance is exposed: loading a bundle, instantiating a
+//! plugin, activating it, processing audio and events, and destroying it. Audio buffers and
+//! events are given using the standard CLAP types (`clap_process`, `clap_input_events`, etc.),
+//! which C applications hosting CLAP plugins are already familiar with.
+//!
+//! Activating an instance returns a separate [`ClackAudioProcessor`] handle, which is meant to be
+//! handed over to the audio thread. The CLAP threading rules apply: all `clack_host_processor_*`
+//! functions must only be called on the audio thread, and all other functions must be called on
+//! the main thread. Because the instance and its audio processor are separate handles, both
+//! threads never share any mutable state.
+//!
+//! This module is only available when the `c-api` feature is enabled.
+
+use crate::bundle::PluginBundle;
+use crate::host::{HostHandlers, HostInfo, SharedHandler};
+use crate::plugin::PluginInstance;
+use crate::process::audio_buffers::{InputAudioBuffers, OutputAudioBuffers};
+use crate::process::{PluginAudioConfiguration, PluginAudioProcessor};
+use clack_common::events::event_types::TransportEvent;
+use clack_common::events::io::{InputEvents, OutputEvents};
+use clap_sys::process::{clap_process, clap_process_status, CLAP_PROCESS_ERROR};
+use std::ffi::{c_char, c_void, CStr};
+
+/// The callbacks a C application provides to handle the requests of a plugin instance.
+///
+/// All callbacks are optional, and receive the given `user_data` pointer. They may be called
+/// from any thread, and must therefore be thread-safe.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ClackHostCallbacks {
+    /// An opaque pointer, given to all the callbacks.
+    pub user_data: *mut c_void,
+    /// Called when the plugin requests to be deactivated and then reactivated.
+    pub request_restart: Option<unsafe extern "C" fn(user_data: *mut c_void)>,
+    /// Called when the plugin requests to be processed, e.g. to wake up from sleep.
+    pub request_process: Option<unsafe extern "C" fn(user_data: *mut c_void)>,
+    /// Called when the plugin requests [`clack_host_instance_on_main_thread`] to be called.
+    pub request_callback: Option<unsafe extern "C" fn(user_data: *mut c_void)>,
+}
+
+impl ClackHostCallbacks {
+    const NONE: Self = Self {
+        user_data: core::ptr::null_mut(),
+        request_restart: None,
+        request_process: None,
+        request_callback: None,
+    };
+
+    fn call(&self, callback: Option<unsafe extern "C" fn(*mut c_void)>) {
+        if let Some(callback) = callback {
+            // SAFETY: the C application guarantees the callback and user data are valid for the
+            // lifetime of the instance.
+            unsafe { callback(self.user_data) }
+        }
+    }
+}
+
+struct CHostShared {
+    callbacks: ClackHostCallbacks,
+}
+
+// SAFETY: the C application guarantees its callbacks and user data can be used from any thread.
+unsafe impl Send for CHostShared {}
+// SAFETY: same as above.
+unsafe impl Sync for CHostShared {}
+
+impl SharedHandler<'_> for CHostShared {
+    fn request_restart(&self) {
+        self.callbacks.call(self.callbacks.request_restart)
+    }
+
+    fn request_process(&self) {
+        self.callbacks.call(self.callbacks.request_process)
+    }
+
+    fn request_callback(&self) {
+        self.callbacks.call(self.callbacks.request_callback)
+    }
+}
+
+struct CHost;
+
+impl HostHandlers for CHost {
+    type Shared<'a> = CHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+/// An opaque handle to a loaded plugin bundle.
+pub struct ClackBundle {
+    bundle: PluginBundle,
+}
+
+impl ClackBundle {
+    /// Wraps an already loaded bundle into a handle that can be given to C code.
+    ///
+    /// The handle must be released using [`clack_host_bundle_destroy`].
+    pub fn raw_handle(bundle: PluginBundle) -> *mut ClackBundle {
+        Box::into_raw(Box::new(ClackBundle { bundle }))
+    }
+}
+
+/// An opaque handle to a plugin instance.
+///
+/// This handle must only be used on the main thread.
+pub struct ClackInstance {
+    instance: PluginInstance<CHost>,
+}
+
+/// An opaque handle to the audio processor of an active plugin instance.
+///
+/// This handle is returned by [`clack_host_instance_activate`], and must only be used on the
+/// audio thread, until it is given back to [`clack_host_instance_deactivate`].
+pub struct ClackAudioProcessor {
+    processor: PluginAudioProcessor<CHost>,
+}
+
+/// # Safety
+///
+/// `ptr` must be null, or a valid, null-terminated C string that lives for `'a`.
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+/// Loads the CLAP bundle at the given path.
+///
+/// Returns a null pointer if the bundle failed to load.
+///
+/// # Safety
+///
+/// `path` must be a valid, null-terminated, UTF-8 C string. Loading a bundle runs arbitrary code
+/// from it, see [`PluginBundle::load`].
+#[cfg(feature = "libloading")]
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_bundle_load(path: *const c_char) -> *mut ClackBundle {
+    let Some(path) = str_from_ptr(path) else {
+        return core::ptr::null_mut();
+    };
+
+    match PluginBundle::load(path) {
+        Ok(bundle) => ClackBundle::raw_handle(bundle),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Releases a bundle. Instances created from it remain valid.
+///
+/// # Safety
+///
+/// `bundle` must be null, or a pointer returned by [`clack_host_bundle_load`] that wasn't
+/// destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_bundle_destroy(bundle: *mut ClackBundle) {
+    if !bundle.is_null() {
+        drop(Box::from_raw(bundle));
+    }
+}
+
+/// Instantiates the plugin with the given ID from a bundle.
+///
+/// The host name, vendor, URL and version are given to the plugin. `callbacks` may be null if the
+/// application doesn't need to handle the plugin's requests.
+///
+/// Returns a null pointer if instantiation failed.
+///
+/// # Safety
+///
+/// `bundle` must be a valid bundle pointer, and all strings must be valid, null-terminated, UTF-8
+/// C strings. If non-null, `callbacks` must point to valid callbacks, which must stay valid until
+/// the instance is destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_instance_new(
+    bundle: *const ClackBundle,
+    plugin_id: *const c_char,
+    host_name: *const c_char,
+    host_vendor: *const c_char,
+    host_url: *const c_char,
+    host_version: *const c_char,
+    callbacks: *const ClackHostCallbacks,
+) -> *mut ClackInstance {
+    let (Some(bundle), false) = (bundle.as_ref(), plugin_id.is_null()) else {
+        return core::ptr::null_mut();
+    };
+
+    let (Some(name), Some(vendor), Some(url), Some(version)) = (
+        str_from_ptr(host_name),
+        str_from_ptr(host_vendor),
+        str_from_ptr(host_url),
+        str_from_ptr(host_version),
+    ) else {
+        return core::ptr::null_mut();
+    };
+
+    let Ok(host_info) = HostInfo::new(name, vendor, url, version) else {
+        return core::ptr::null_mut();
+    };
+
+    let callbacks = callbacks
+        .as_ref()
+        .copied()
+        .unwrap_or(ClackHostCallbacks::NONE);
+
+    let instance = PluginInstance::<CHost>::new(
+        |_| CHostShared { callbacks },
+        |_| (),
+        &bundle.bundle,
+        CStr::from_ptr(plugin_id),
+        &host_info,
+    );
+
+    match instance {
+        Ok(instance) => Box::into_raw(Box::new(ClackInstance { instance })),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Activates the plugin instance with the given audio configuration.
+///
+/// On success, this returns the handle to the plugin's audio processor, which can then be sent to
+/// the audio thread. Returns a null pointer if activation failed, or if the instance is already
+/// active.
+///
+/// # Safety
+///
+/// `instance` must be a valid instance pointer.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_instance_activate(
+    instance: *mut ClackInstance,
+    sample_rate: f64,
+    min_frames_count: u32,
+    max_frames_count: u32,
+) -> *mut ClackAudioProcessor {
+    let Some(instance) = instance.as_mut() else {
+        return core::ptr::null_mut();
+    };
+
+    if instance.instance.is_active() {
+        return core::ptr::null_mut();
+    }
+
+    let configuration =
+        PluginAudioConfiguration::new(sample_rate, min_frames_count, max_frames_count);
+
+    match instance.instance.activate(|_, _| (), configuration) {
+        Ok(processor) => Box::into_raw(Box::new(ClackAudioProcessor {
+            processor: processor.into(),
+        })),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Deactivates the plugin instance, consuming its audio processor handle.
+///
+/// The audio processor should be stopped first using [`clack_host_processor_stop_processing`]
+/// on the audio thread. If it wasn't, it is stopped here on the main thread instead.
+///
+/// # Safety
+///
+/// `instance` must be a valid instance pointer, and `processor` must be null, or the audio
+/// processor pointer that was returned when activating this instance. The audio thread must not
+/// use `processor` anymore.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_instance_deactivate(
+    instance: *mut ClackInstance,
+    processor: *mut ClackAudioProcessor,
+) {
+    let (Some(instance), false) = (instance.as_mut(), processor.is_null()) else {
+        return;
+    };
+
+    let mut processor = Box::from_raw(processor).processor;
+    processor.ensure_processing_stopped();
+    instance.instance.deactivate(processor.into_stopped());
+}
+
+/// Starts processing on the audio processor.
+///
+/// Returns `true` on success, or if processing was already started.
+///
+/// # Safety
+///
+/// `processor` must be a valid audio processor pointer, only used on the audio thread.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_processor_start_processing(
+    processor: *mut ClackAudioProcessor,
+) -> bool {
+    match processor.as_mut() {
+        Some(processor) => processor.processor.ensure_processing_started().is_ok(),
+        None => false,
+    }
+}
+
+/// Stops processing on the audio processor. This does nothing if processing isn't started.
+///
+/// # Safety
+///
+/// `processor` must be a valid audio processor pointer, only used on the audio thread.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_processor_stop_processing(processor: *mut ClackAudioProcessor) {
+    if let Some(processor) = processor.as_mut() {
+        processor.processor.ensure_processing_stopped();
+    }
+}
+
+/// Processes a block of audio and events, and returns the plugin's process status.
+///
+/// The audio processor is started first if needed. The frame count is taken from `process`, even
+/// if there is no audio port.
+///
+/// Returns `CLAP_PROCESS_ERROR` if processing failed.
+///
+/// # Safety
+///
+/// `processor` must be a valid audio processor pointer, only used on the audio thread. `process`
+/// must be a valid CLAP process structure, whose buffers hold at least `frames_count` frames.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_processor_process(
+    processor: *mut ClackAudioProcessor,
+    process: *const clap_process,
+) -> clap_process_status {
+    let (Some(processor), Some(process)) = (processor.as_mut(), process.as_ref()) else {
+        return CLAP_PROCESS_ERROR;
+    };
+
+    let Ok(processor) = processor.processor.ensure_processing_started() else {
+        return CLAP_PROCESS_ERROR;
+    };
+
+    let frames_count = process.frames_count;
+
+    let audio_inputs = if process.audio_inputs.is_null() || process.audio_inputs_count == 0 {
+        InputAudioBuffers::empty_with_frames(frames_count)
+    } else {
+        InputAudioBuffers::from_raw_buffers(
+            core::slice::from_raw_parts(process.audio_inputs, process.audio_inputs_count as usize),
+            frames_count,
+        )
+    };
+
+    let mut audio_outputs = if process.audio_outputs.is_null() || process.audio_outputs_count == 0 {
+        OutputAudioBuffers::empty_with_frames(frames_count)
+    } else {
+        OutputAudioBuffers::from_raw_buffers(
+            core::slice::from_raw_parts_mut(
+                process.audio_outputs,
+                process.audio_outputs_count as usize,
+            ),
+            frames_count,
+        )
+    };
+
+    let empty_input_events = InputEvents::empty();
+    let input_events = match process.in_events.as_ref() {
+        Some(in_events) => InputEvents::from_raw(in_events),
+        None => &empty_input_events,
+    };
+
+    let mut void_output_events = OutputEvents::void();
+    let output_events =
+        match (process.out_events as *mut clap_sys::events::clap_output_events).as_mut() {
+            Some(out_events) => OutputEvents::from_raw_mut(out_events),
+            None => &mut void_output_events,
+        };
+
+    let steady_time = u64::try_from(process.steady_time).ok();
+    let transport = process.transport.as_ref().map(TransportEvent::from_raw_ref);
+
+    match processor.process(
+        &audio_inputs,
+        &mut audio_outputs,
+        input_events,
+        output_events,
+        steady_time,
+        transport,
+    ) {
+        Ok(status) => status as clap_process_status,
+        Err(_) => CLAP_PROCESS_ERROR,
+    }
+}
+
+/// Runs the plugin's main-thread callback, as requested through the `request_callback`
+/// callback.
+///
+/// # Safety
+///
+/// `instance` must be a valid instance pointer.
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_instance_on_main_thread(instance: *mut ClackInstance) {
+    if let Some(instance) = instance.as_mut() {
+        instance.instance.call_on_main_thread_callback();
+    }
+}
+
+/// Destroys the plugin instance.
+///
+/// # Safety
+///
+/// `instance` must be null, or a pointer returned by [`clack_host_instance_new`] that wasn't
+/// destroyed yet. If the instance is active, it must be deactivated first using
+/// [`clack_host_instance_deactivate`].
+#[no_mangle]
+pub unsafe extern "C" fn clack_host_instance_destroy(instance: *mut ClackInstance) {
+    if !instance.is_null() {
+        drop(Box::from_raw(instance));
+    }
+}
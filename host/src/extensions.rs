@@ -136,7 +136,25 @@
 //!
 //! # Creating custom extensions
 //!
-//! TODO: document custom extensions.
+//! Clack does not make any distinction between official CLAP extensions and third-party ones:
+//! any extension can be implemented outside the `clack-extensions` crate, using the exact same
+//! API. Implementing an extension consists of the following steps:
+//!
+//! * Defining the C-compatible structs of the extension's ABI for both sides (or re-using the ones
+//!   from `clap-sys`), as well as the extension's unique identifier.
+//! * Defining a type for each side of the ABI that wraps a [`RawExtension`], and implementing the
+//!   [`Extension`] trait on it, which ties it to its identifier.
+//! * Implementing methods on the plugin-side type, which call the plugin's function pointers.
+//!   These methods must take the plugin handle type that matches the function's thread
+//!   specification (e.g. [`PluginMainThreadHandle`](crate::plugin::PluginMainThreadHandle) for
+//!   `[main-thread]` functions).
+//! * Defining a trait for the host to implement, and implementing [`ExtensionImplementation`] on
+//!   the host-side type for any [`HostHandlers`](crate::host::HostHandlers) whose matching
+//!   associated type implements it. The C functions exposed to the plugin can then use
+//!   [`HostWrapper::handle`](wrapper::HostWrapper::handle) to safely access the host's types.
+//!
+//! The `clack-example-custom-extension` crate in the Clack repository contains a complete working
+//! example of a third-party extension, which implements both the host and the plugin sides.
 //!
 //! ## Example
 //!
@@ -226,7 +244,7 @@ pub mod prelude {
             Extension, ExtensionImplementation, HostExtensionSide, PluginExtensionSide,
             RawExtension, RawExtensionImplementation,
         },
-        host::{HostError, HostHandlers},
+        host::{AudioThreadGuard, HostError, HostHandlers},
         plugin::{PluginAudioProcessorHandle, PluginMainThreadHandle, PluginSharedHandle},
         utils::ClapId,
     };
@@ -40,6 +40,9 @@ pub struct HostWrapper<H: HostHandlers> {
     init_started: AtomicBool,
     plugin_ptr: OnceLock<NonNull<clap_plugin>>,
 
+    // Set when the plugin requests a restart, cleared when it is (re-)activated
+    restart_requested: AtomicBool,
+
     // Drop stuff
     destroy_lock: Arc<DestroyLock>,
 }
@@ -137,6 +140,7 @@ impl<H: HostHandlers> HostWrapper<H> {
             init_guard: Once::new(),
             init_started: AtomicBool::new(false),
             plugin_ptr: OnceLock::new(),
+            restart_requested: AtomicBool::new(false),
             destroy_lock: Arc::new(DestroyLock::new()),
         });
 
@@ -234,6 +238,17 @@ impl<H: HostHandlers> HostWrapper<H> {
         }
     }
 
+    /// Returns `true` if the plugin requested to be restarted, and hasn't been re-activated since.
+    #[inline]
+    pub fn is_restart_requested(&self) -> bool {
+        self.restart_requested.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn set_restart_requested(&self, requested: bool) {
+        self.restart_requested.store(requested, Ordering::Release)
+    }
+
     /// # Safety
     /// the user must ensure this is not called concurrently
     /// to [`Self::setup_audio_processor`] or [`Self::teardown_audio_processor`]
@@ -282,7 +297,7 @@ impl<H: HostHandlers> HostWrapper<H> {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum HostWrapperError {
     /// An invalid parameter value was encountered.
     ///
@@ -53,6 +53,7 @@ unsafe extern "C" fn get_extension<H: HostHandlers>(
 
     HostWrapper::<H>::handle(host, |h| {
         H::declare_extensions(&mut builder, h.shared());
+        builder.report_unsupported(h.shared());
         Ok(())
     });
     builder.found()
@@ -61,6 +62,7 @@ unsafe extern "C" fn get_extension<H: HostHandlers>(
 #[allow(clippy::missing_safety_doc)]
 unsafe extern "C" fn request_restart<H: HostHandlers>(host: *const clap_host) {
     HostWrapper::<H>::handle(host, |h| {
+        h.set_restart_requested(true);
         h.shared().request_restart();
         Ok(())
     });
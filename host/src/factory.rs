@@ -29,7 +29,9 @@ use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 mod plugin_descriptor;
+mod plugin_features;
 pub use plugin_descriptor::*;
+pub use plugin_features::*;
 
 /// A custom factory pointer type.
 ///
@@ -95,6 +97,17 @@ unsafe impl<'a> FactoryPointer<'a> for PluginFactory<'a> {
 }
 
 impl<'a> PluginFactory<'a> {
+    /// Returns a raw pointer to the C-FFI compatible plugin factory struct.
+    ///
+    /// This pointer is guaranteed to be valid as long as the [`PluginBundle`](crate::bundle::PluginBundle)
+    /// it was obtained from is still alive.
+    ///
+    /// To create a [`PluginFactory`] from a raw pointer, see [`FactoryPointer::from_raw`].
+    #[inline]
+    pub fn as_raw(&self) -> *const clap_plugin_factory {
+        self.inner
+    }
+
     /// Returns the number of plugin descriptors exposed by this plugin factory.
     #[inline]
     pub fn plugin_count(&self) -> u32 {
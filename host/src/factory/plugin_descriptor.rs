@@ -1,5 +1,5 @@
 use clap_sys::plugin::clap_plugin_descriptor;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
 
 /// Various textual information about a plugin.
@@ -40,13 +40,26 @@ unsafe fn cstr_to_str<'a>(ptr: *const std::os::raw::c_char) -> Option<&'a CStr>
 }
 
 impl<'a> PluginDescriptor<'a> {
+    /// Creates a new plugin descriptor wrapper from a reference to the raw, C-FFI compatible
+    /// descriptor struct.
+    ///
     /// # Safety
     /// The user must ensure the provided descriptor is valid, including all of its pointers.
+    /// All of its non-null string pointers must point to valid, null-terminated C strings, and its
+    /// `features` pointer must be either null or point to a null-terminated array of such strings.
+    ///
+    /// All of these must also remain valid for the lifetime `'a`.
     #[inline]
-    pub(crate) unsafe fn from_raw(descriptor: &'a clap_plugin_descriptor) -> Self {
+    pub unsafe fn from_raw(descriptor: &'a clap_plugin_descriptor) -> Self {
         Self { descriptor }
     }
 
+    /// Returns a shared reference to the raw, C-FFI compatible descriptor struct.
+    #[inline]
+    pub fn as_raw(&self) -> &'a clap_plugin_descriptor {
+        self.descriptor
+    }
+
     /// An arbitrary string identifier that is unique to this plugin.
     ///
     /// Plugins are encouraged to use a reverse-URI for this, e.g. `com.u-he.diva`, but this is not
@@ -223,6 +236,100 @@ impl<'a> PluginDescriptor<'a> {
     }
 }
 
+/// An owned copy of all the information contained in a [`PluginDescriptor`].
+///
+/// Unlike [`PluginDescriptor`], this type isn't tied to the lifetime of the plugin bundle it was
+/// read from. This makes it suitable for hosts to store in their plugin database, e.g. to display
+/// plugins without loading their bundles again.
+///
+/// When the `serde` feature is enabled, this type can be serialized and deserialized.
+///
+/// # Example
+/// ```
+/// use clack_host::factory::{OwnedPluginDescriptor, PluginDescriptor};
+///
+/// # fn x(descriptor: PluginDescriptor) {
+/// let descriptor: PluginDescriptor = /* ... */
+/// # unreachable!();
+/// let owned = OwnedPluginDescriptor::from(descriptor);
+/// assert_eq!(b"com.u-he.diva", owned.id.unwrap().as_bytes());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedPluginDescriptor {
+    /// See [`PluginDescriptor::id`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub id: Option<CString>,
+    /// See [`PluginDescriptor::name`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub name: Option<CString>,
+    /// See [`PluginDescriptor::vendor`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub vendor: Option<CString>,
+    /// See [`PluginDescriptor::url`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub url: Option<CString>,
+    /// See [`PluginDescriptor::manual_url`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub manual_url: Option<CString>,
+    /// See [`PluginDescriptor::support_url`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub support_url: Option<CString>,
+    /// See [`PluginDescriptor::version`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub version: Option<CString>,
+    /// See [`PluginDescriptor::description`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::optional_cstring")
+    )]
+    pub description: Option<CString>,
+    /// See [`PluginDescriptor::features`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "clack_common::utils::serialization::cstrings")
+    )]
+    pub features: Vec<CString>,
+}
+
+impl From<PluginDescriptor<'_>> for OwnedPluginDescriptor {
+    fn from(descriptor: PluginDescriptor<'_>) -> Self {
+        Self {
+            id: descriptor.id().map(CStr::to_owned),
+            name: descriptor.name().map(CStr::to_owned),
+            vendor: descriptor.vendor().map(CStr::to_owned),
+            url: descriptor.url().map(CStr::to_owned),
+            manual_url: descriptor.manual_url().map(CStr::to_owned),
+            support_url: descriptor.support_url().map(CStr::to_owned),
+            version: descriptor.version().map(CStr::to_owned),
+            description: descriptor.description().map(CStr::to_owned),
+            features: descriptor.features().map(CStr::to_owned).collect(),
+        }
+    }
+}
+
 struct FeaturesIter<'a> {
     current: *const *const std::os::raw::c_char,
     _lifetime: PhantomData<&'a CStr>,
@@ -0,0 +1,492 @@
+use super::{OwnedPluginDescriptor, PluginDescriptor};
+use clack_common::plugin::features;
+use std::ffi::{CStr, CString};
+
+macro_rules! plugin_features {
+    ($($(#[$meta:meta])* $variant:ident => $constant:ident,)*) => {
+        /// One of the standard features a plugin can declare in its [`PluginDescriptor`].
+        ///
+        /// See the [`features`](crate::plugin::features) module for a description of each of them.
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+        #[repr(u8)]
+        pub enum PluginFeature {
+            $($(#[$meta])* $variant,)*
+        }
+
+        impl PluginFeature {
+            /// All of the standard plugin features, in declaration order.
+            pub const ALL: &'static [PluginFeature] = &[$(PluginFeature::$variant,)*];
+
+            /// Returns the standard feature matching the given feature string, or [`None`] if it
+            /// is not a known standard feature.
+            ///
+            /// # Example
+            /// ```
+            /// use clack_host::factory::PluginFeature;
+            /// use clack_host::plugin::features::SYNTHESIZER;
+            ///
+            /// assert_eq!(PluginFeature::from_cstr(SYNTHESIZER), Some(PluginFeature::Synthesizer));
+            /// ```
+            pub fn from_cstr(feature: &CStr) -> Option<Self> {
+                $(if feature == features::$constant {
+                    return Some(PluginFeature::$variant);
+                })*
+
+                None
+            }
+
+            /// Returns the standard feature string of this feature.
+            pub fn as_cstr(self) -> &'static CStr {
+                match self {
+                    $(PluginFeature::$variant => features::$constant,)*
+                }
+            }
+        }
+    };
+}
+
+plugin_features! {
+    /// See [`INSTRUMENT`](features::INSTRUMENT).
+    Instrument => INSTRUMENT,
+    /// See [`AUDIO_EFFECT`](features::AUDIO_EFFECT).
+    AudioEffect => AUDIO_EFFECT,
+    /// See [`NOTE_EFFECT`](features::NOTE_EFFECT).
+    NoteEffect => NOTE_EFFECT,
+    /// See [`ANALYZER`](features::ANALYZER).
+    Analyzer => ANALYZER,
+    /// See [`SYNTHESIZER`](features::SYNTHESIZER).
+    Synthesizer => SYNTHESIZER,
+    /// See [`SAMPLER`](features::SAMPLER).
+    Sampler => SAMPLER,
+    /// See [`DRUM`](features::DRUM).
+    Drum => DRUM,
+    /// See [`DRUM_MACHINE`](features::DRUM_MACHINE).
+    DrumMachine => DRUM_MACHINE,
+    /// See [`FILTER`](features::FILTER).
+    Filter => FILTER,
+    /// See [`PHASER`](features::PHASER).
+    Phaser => PHASER,
+    /// See [`EQUALIZER`](features::EQUALIZER).
+    Equalizer => EQUALIZER,
+    /// See [`DEESSER`](features::DEESSER).
+    DeEsser => DEESSER,
+    /// See [`PHASE_VOCODER`](features::PHASE_VOCODER).
+    PhaseVocoder => PHASE_VOCODER,
+    /// See [`GRANULAR`](features::GRANULAR).
+    Granular => GRANULAR,
+    /// See [`FREQUENCY_SHIFTER`](features::FREQUENCY_SHIFTER).
+    FrequencyShifter => FREQUENCY_SHIFTER,
+    /// See [`PITCH_SHIFTER`](features::PITCH_SHIFTER).
+    PitchShifter => PITCH_SHIFTER,
+    /// See [`DISTORTION`](features::DISTORTION).
+    Distortion => DISTORTION,
+    /// See [`TRANSIENT_SHAPER`](features::TRANSIENT_SHAPER).
+    TransientShaper => TRANSIENT_SHAPER,
+    /// See [`COMPRESSOR`](features::COMPRESSOR).
+    Compressor => COMPRESSOR,
+    /// See [`LIMITER`](features::LIMITER).
+    Limiter => LIMITER,
+    /// See [`FLANGER`](features::FLANGER).
+    Flanger => FLANGER,
+    /// See [`CHORUS`](features::CHORUS).
+    Chorus => CHORUS,
+    /// See [`DELAY`](features::DELAY).
+    Delay => DELAY,
+    /// See [`REVERB`](features::REVERB).
+    Reverb => REVERB,
+    /// See [`TREMOLO`](features::TREMOLO).
+    Tremolo => TREMOLO,
+    /// See [`GLITCH`](features::GLITCH).
+    Glitch => GLITCH,
+    /// See [`UTILITY`](features::UTILITY).
+    Utility => UTILITY,
+    /// See [`PITCH_CORRECTION`](features::PITCH_CORRECTION).
+    PitchCorrection => PITCH_CORRECTION,
+    /// See [`RESTORATION`](features::RESTORATION).
+    Restoration => RESTORATION,
+    /// See [`MULTI_EFFECTS`](features::MULTI_EFFECTS).
+    MultiEffects => MULTI_EFFECTS,
+    /// See [`MIXING`](features::MIXING).
+    Mixing => MIXING,
+    /// See [`MASTERING`](features::MASTERING).
+    Mastering => MASTERING,
+    /// See [`MONO`](features::MONO).
+    Mono => MONO,
+    /// See [`STEREO`](features::STEREO).
+    Stereo => STEREO,
+    /// See [`SURROUND`](features::SURROUND).
+    Surround => SURROUND,
+    /// See [`AMBISONIC`](features::AMBISONIC).
+    Ambisonic => AMBISONIC,
+}
+
+impl PluginFeature {
+    /// Returns `true` if this feature is one of the main plugin categories, i.e.
+    /// [`Instrument`](PluginFeature::Instrument), [`AudioEffect`](PluginFeature::AudioEffect),
+    /// [`NoteEffect`](PluginFeature::NoteEffect) or [`Analyzer`](PluginFeature::Analyzer).
+    #[inline]
+    pub fn is_main_category(self) -> bool {
+        matches!(
+            self,
+            Self::Instrument | Self::AudioEffect | Self::NoteEffect | Self::Analyzer
+        )
+    }
+
+    /// Returns `true` if this feature describes the plugin's audio channel capabilities, i.e.
+    /// [`Mono`](PluginFeature::Mono), [`Stereo`](PluginFeature::Stereo),
+    /// [`Surround`](PluginFeature::Surround) or [`Ambisonic`](PluginFeature::Ambisonic).
+    #[inline]
+    pub fn is_channel_layout(self) -> bool {
+        matches!(
+            self,
+            Self::Mono | Self::Stereo | Self::Surround | Self::Ambisonic
+        )
+    }
+
+    #[inline]
+    const fn bit(self) -> u64 {
+        1 << self as u8
+    }
+}
+
+/// A set of [`PluginFeature`]s.
+///
+/// This is used both to store the standard features a plugin declares in [`PluginFeatures`], and
+/// to express the requirements of a [`PluginFeatureFilter`].
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct PluginFeatureSet(u64);
+
+impl PluginFeatureSet {
+    /// An empty feature set.
+    pub const EMPTY: Self = Self(0);
+
+    /// Creates a new, empty feature set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Returns this set with the given feature added to it.
+    #[inline]
+    pub const fn with(self, feature: PluginFeature) -> Self {
+        Self(self.0 | feature.bit())
+    }
+
+    /// Adds the given feature to this set.
+    #[inline]
+    pub fn insert(&mut self, feature: PluginFeature) {
+        self.0 |= feature.bit()
+    }
+
+    /// Removes the given feature from this set.
+    #[inline]
+    pub fn remove(&mut self, feature: PluginFeature) {
+        self.0 &= !feature.bit()
+    }
+
+    /// Returns `true` if this set contains the given feature.
+    #[inline]
+    pub const fn contains(&self, feature: PluginFeature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// Returns `true` if this set contains all the features of the `other` set.
+    #[inline]
+    pub const fn contains_all(&self, other: &Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if this set contains any of the features of the `other` set.
+    #[inline]
+    pub const fn intersects(&self, other: &Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns `true` if this set contains no features.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of features in this set.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns an iterator over all the features in this set, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = PluginFeature> + '_ {
+        PluginFeature::ALL
+            .iter()
+            .copied()
+            .filter(|f| self.contains(*f))
+    }
+}
+
+impl core::fmt::Debug for PluginFeatureSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<PluginFeature> for PluginFeatureSet {
+    fn from_iter<T: IntoIterator<Item = PluginFeature>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<PluginFeature> for PluginFeatureSet {
+    fn extend<T: IntoIterator<Item = PluginFeature>>(&mut self, iter: T) {
+        for feature in iter {
+            self.insert(feature)
+        }
+    }
+}
+
+impl<const N: usize> From<[PluginFeature; N]> for PluginFeatureSet {
+    #[inline]
+    fn from(features: [PluginFeature; N]) -> Self {
+        features.into_iter().collect()
+    }
+}
+
+/// The features declared by a plugin, parsed into typed standard [`PluginFeature`]s.
+///
+/// Feature strings that do not match any standard feature (e.g. vendor-specific
+/// `"namespace:feature"` tags) are kept as-is, and can be retrieved using
+/// [`custom_features`](PluginFeatures::custom_features).
+///
+/// This allows hosts to e.g. sort plugins by category in a plugin browser, or to filter them using
+/// a [`PluginFeatureFilter`].
+///
+/// # Example
+///
+/// ```
+/// use clack_host::factory::{PluginFeature, PluginFeatures};
+/// use clack_host::plugin::features::*;
+///
+/// let features = PluginFeatures::from_features([INSTRUMENT, SYNTHESIZER, STEREO]);
+///
+/// assert_eq!(features.main_category(), Some(PluginFeature::Instrument));
+/// assert!(features.contains(PluginFeature::Synthesizer));
+/// assert!(!features.contains(PluginFeature::Mono));
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct PluginFeatures {
+    standard: PluginFeatureSet,
+    custom: Vec<CString>,
+}
+
+impl PluginFeatures {
+    /// Parses the given list of feature strings.
+    pub fn from_features<'a>(features: impl IntoIterator<Item = &'a CStr>) -> Self {
+        let mut parsed = Self::default();
+
+        for feature in features {
+            match PluginFeature::from_cstr(feature) {
+                Some(feature) => parsed.standard.insert(feature),
+                None if !parsed.custom.iter().any(|f| f.as_c_str() == feature) => {
+                    parsed.custom.push(feature.to_owned())
+                }
+                None => {}
+            }
+        }
+
+        parsed
+    }
+
+    /// Returns the set of all standard features declared by the plugin.
+    #[inline]
+    pub fn standard_features(&self) -> PluginFeatureSet {
+        self.standard
+    }
+
+    /// Returns all the feature strings declared by the plugin that do not match any standard
+    /// feature, in the order they were declared.
+    #[inline]
+    pub fn custom_features(&self) -> &[CString] {
+        &self.custom
+    }
+
+    /// Returns `true` if the plugin declared the given standard feature.
+    #[inline]
+    pub fn contains(&self, feature: PluginFeature) -> bool {
+        self.standard.contains(feature)
+    }
+
+    /// Returns `true` if the plugin declared the given custom feature string.
+    pub fn contains_custom(&self, feature: &CStr) -> bool {
+        self.custom.iter().any(|f| f.as_c_str() == feature)
+    }
+
+    /// Returns the main category of the plugin, i.e. the first of
+    /// [`Instrument`](PluginFeature::Instrument), [`AudioEffect`](PluginFeature::AudioEffect),
+    /// [`NoteEffect`](PluginFeature::NoteEffect) and [`Analyzer`](PluginFeature::Analyzer) it
+    /// declared, in that order.
+    ///
+    /// This returns [`None`] if the plugin didn't declare any main category.
+    pub fn main_category(&self) -> Option<PluginFeature> {
+        self.standard.iter().find(|f| f.is_main_category())
+    }
+
+    /// Returns the set of channel layouts (e.g. [`Mono`](PluginFeature::Mono) or
+    /// [`Stereo`](PluginFeature::Stereo)) declared by the plugin.
+    pub fn channel_layouts(&self) -> PluginFeatureSet {
+        self.standard
+            .iter()
+            .filter(|f| f.is_channel_layout())
+            .collect()
+    }
+
+    /// Returns `true` if these features match the given filter.
+    ///
+    /// See [`PluginFeatureFilter`] for more information.
+    #[inline]
+    pub fn matches(&self, filter: &PluginFeatureFilter) -> bool {
+        filter.matches(self)
+    }
+}
+
+impl From<PluginDescriptor<'_>> for PluginFeatures {
+    #[inline]
+    fn from(descriptor: PluginDescriptor<'_>) -> Self {
+        Self::from_features(descriptor.features())
+    }
+}
+
+impl From<&OwnedPluginDescriptor> for PluginFeatures {
+    #[inline]
+    fn from(descriptor: &OwnedPluginDescriptor) -> Self {
+        Self::from_features(descriptor.features.iter().map(CString::as_c_str))
+    }
+}
+
+/// A filter over [`PluginFeatures`], e.g. to select the plugins listed in a category of a plugin
+/// browser.
+///
+/// A plugin matches this filter if it declares all the [required](PluginFeatureFilter::require)
+/// features, at least one of the [accepted](PluginFeatureFilter::any_of) features (if any), and
+/// none of the [excluded](PluginFeatureFilter::exclude) features.
+///
+/// An empty filter matches all plugins.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::factory::{PluginFeature, PluginFeatureFilter, PluginFeatures};
+/// use clack_host::plugin::features::*;
+///
+/// // All stereo or surround reverbs
+/// let filter = PluginFeatureFilter::new()
+///     .require(PluginFeature::Reverb)
+///     .any_of([PluginFeature::Stereo, PluginFeature::Surround]);
+///
+/// assert!(PluginFeatures::from_features([AUDIO_EFFECT, REVERB, STEREO]).matches(&filter));
+/// assert!(!PluginFeatures::from_features([AUDIO_EFFECT, REVERB, MONO]).matches(&filter));
+/// assert!(!PluginFeatures::from_features([AUDIO_EFFECT, DELAY, STEREO]).matches(&filter));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct PluginFeatureFilter {
+    required: PluginFeatureSet,
+    any_of: PluginFeatureSet,
+    excluded: PluginFeatureSet,
+}
+
+impl PluginFeatureFilter {
+    /// Creates a new, empty filter, which matches all plugins.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            required: PluginFeatureSet::EMPTY,
+            any_of: PluginFeatureSet::EMPTY,
+            excluded: PluginFeatureSet::EMPTY,
+        }
+    }
+
+    /// Creates a filter that matches all plugins of the given category.
+    #[inline]
+    pub const fn category(feature: PluginFeature) -> Self {
+        Self::new().require(feature)
+    }
+
+    /// Requires matching plugins to declare the given feature.
+    #[inline]
+    pub const fn require(mut self, feature: PluginFeature) -> Self {
+        self.required = self.required.with(feature);
+        self
+    }
+
+    /// Requires matching plugins to declare at least one of the given features.
+    ///
+    /// Calling this multiple times extends the set of accepted features.
+    #[inline]
+    pub fn any_of(mut self, features: impl IntoIterator<Item = PluginFeature>) -> Self {
+        self.any_of.extend(features);
+        self
+    }
+
+    /// Requires matching plugins not to declare the given feature.
+    #[inline]
+    pub const fn exclude(mut self, feature: PluginFeature) -> Self {
+        self.excluded = self.excluded.with(feature);
+        self
+    }
+
+    /// Returns `true` if the given features match this filter.
+    pub fn matches(&self, features: &PluginFeatures) -> bool {
+        let declared = features.standard_features();
+
+        declared.contains_all(&self.required)
+            && (self.any_of.is_empty() || declared.intersects(&self.any_of))
+            && !declared.intersects(&self.excluded)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn all_features_round_trip() {
+        for feature in PluginFeature::ALL {
+            assert_eq!(PluginFeature::from_cstr(feature.as_cstr()), Some(*feature));
+        }
+
+        assert!(PluginFeature::ALL.len() <= u64::BITS as usize);
+    }
+
+    #[test]
+    fn keeps_custom_features() {
+        let custom = CStr::from_bytes_with_nul(b"com.example:granular-cloud\0").unwrap();
+        let features = PluginFeatures::from_features([
+            features::AUDIO_EFFECT,
+            custom,
+            features::GRANULAR,
+            custom,
+        ]);
+
+        assert_eq!(features.main_category(), Some(PluginFeature::AudioEffect));
+        assert_eq!(features.custom_features(), [custom.to_owned()]);
+        assert!(features.contains_custom(custom));
+        assert_eq!(
+            features.standard_features(),
+            [PluginFeature::AudioEffect, PluginFeature::Granular].into()
+        );
+    }
+
+    #[test]
+    fn filters_features() {
+        let features =
+            PluginFeatures::from_features([features::INSTRUMENT, features::DRUM, features::MONO]);
+
+        assert!(features.matches(&PluginFeatureFilter::new()));
+        assert!(features.matches(&PluginFeatureFilter::category(PluginFeature::Instrument)));
+        assert!(!features.matches(&PluginFeatureFilter::category(PluginFeature::AudioEffect)));
+        assert!(!features.matches(
+            &PluginFeatureFilter::category(PluginFeature::Instrument).exclude(PluginFeature::Mono)
+        ));
+        assert_eq!(features.channel_layouts(), [PluginFeature::Mono].into());
+    }
+}
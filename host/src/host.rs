@@ -193,10 +193,14 @@
 mod error;
 mod extensions;
 mod info;
+mod thread;
 
 pub use error::HostError;
-pub use extensions::HostExtensions;
-pub use info::HostInfo;
+pub use extensions::{HostExtensions, UnsupportedExtensions, UnsupportedHandler};
+#[doc(hidden)]
+pub use info::__host_info_from_cargo;
+pub use info::{HostInfo, HostInfoBuilder, HostInfoError, HostInfoField};
+pub use thread::{is_audio_thread, AudioThreadGuard, MainThreadToken};
 
 use crate::plugin::{InitializedPluginHandle, InitializingPluginHandle};
 
@@ -311,6 +315,14 @@ pub trait HostHandlers: 'static {
     ///
     /// A temporary reference to this host's [`Shared`](HostHandlers::Shared) type is also given, in case
     /// extensions need to be dynamically declared.
+    ///
+    /// Extensions can be registered conditionally, e.g. only exposing a GUI extension when a
+    /// display is available, using a regular `if` or [`HostExtensions::register_if`].
+    /// Extensions that don't have an associated extension type can be registered from their raw
+    /// identifier and implementation using [`HostExtensions::register_raw`].
+    ///
+    /// Each extension identifier must only be registered once, otherwise plugin instantiation
+    /// fails with [`PluginInstanceError::DuplicateExtension`](crate::plugin::PluginInstanceError::DuplicateExtension).
     #[inline]
     #[allow(unused)]
     fn declare_extensions(builder: &mut HostExtensions<Self>, shared: &Self::Shared<'_>) {}
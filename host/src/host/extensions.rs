@@ -1,49 +1,288 @@
 use crate::host::HostHandlers;
 use clack_common::extensions::*;
-use std::ffi::{c_void, CStr};
+use std::collections::BTreeMap;
+use std::ffi::{c_void, CStr, CString};
 use std::marker::PhantomData;
 use std::ptr::NonNull;
+use std::sync::Mutex;
 
 /// A collection of all extensions supported for a given [`HostHandlers`] type.
 ///
 /// Host can declare the different extensions they support by using the
 /// [`register`](HostExtensions::register) method on this struct, during a call to
 /// [`declare_extensions`](HostHandlers::declare_extensions).
-pub struct HostExtensions<'a, H: ?Sized> {
-    found: Option<NonNull<c_void>>,
-    requested: &'a CStr,
+///
+/// Each extension identifier can only be registered once, including compatibility identifiers.
+/// Registering the same extension twice, or two extensions sharing an identifier, is detected when
+/// the plugin is instantiated, which then fails with
+/// [`PluginInstanceError::DuplicateExtension`](crate::plugin::PluginInstanceError::DuplicateExtension).
+///
+/// As a diagnostic, hosts can also be notified of every extension a plugin queries but the host
+/// doesn't implement, using the [`on_unsupported`](HostExtensions::on_unsupported) method.
+pub struct HostExtensions<'a, H: HostHandlers + ?Sized> {
+    mode: Mode<'a>,
+    on_unsupported: Option<UnsupportedHandler<H>>,
     plugin_type: PhantomData<H>,
 }
 
+/// A handler given to [`HostExtensions::on_unsupported`].
+pub type UnsupportedHandler<H> = for<'s> fn(&<H as HostHandlers>::Shared<'s>, &CStr);
+
+enum Mode<'a> {
+    /// Looking for the implementation of a single extension, requested by the plugin.
+    Query {
+        requested: &'a CStr,
+        found: Option<NonNull<c_void>>,
+    },
+    /// Collecting all registered identifiers, to check for duplicates.
+    Validate {
+        registered: Vec<CString>,
+        duplicate: Option<CString>,
+    },
+}
+
 impl<'a, H: HostHandlers> HostExtensions<'a, H> {
     #[inline]
     pub(crate) fn new(requested: &'a CStr) -> Self {
         Self {
-            found: None,
-            requested,
+            mode: Mode::Query {
+                requested,
+                found: None,
+            },
+            on_unsupported: None,
             plugin_type: PhantomData,
         }
     }
 
     #[inline]
     pub(crate) fn found(&self) -> *const c_void {
-        self.found
-            .map(|p| p.as_ptr())
-            .unwrap_or(core::ptr::null_mut())
+        match self.mode {
+            Mode::Query {
+                found: Some(found), ..
+            } => found.as_ptr(),
+            _ => core::ptr::null(),
+        }
+    }
+
+    /// Calls the handler given to [`on_unsupported`](Self::on_unsupported), if the requested
+    /// extension wasn't registered.
+    ///
+    /// This must only be called after [`declare_extensions`](HostHandlers::declare_extensions)
+    /// returned, so that all extensions had the chance to be registered.
+    pub(crate) fn report_unsupported(&self, shared: &H::Shared<'_>) {
+        if let (
+            Mode::Query {
+                requested,
+                found: None,
+            },
+            Some(handler),
+        ) = (&self.mode, self.on_unsupported)
+        {
+            handler(shared, requested);
+        }
+    }
+
+    /// Checks that no extension identifier is registered more than once by the given host
+    /// [`Shared`](HostHandlers::Shared) type.
+    ///
+    /// If one is, the first duplicated identifier is returned.
+    pub(crate) fn find_duplicate(shared: &H::Shared<'_>) -> Option<CString> {
+        let mut builder = HostExtensions::<H> {
+            mode: Mode::Validate {
+                registered: Vec::new(),
+                duplicate: None,
+            },
+            on_unsupported: None,
+            plugin_type: PhantomData,
+        };
+
+        H::declare_extensions(&mut builder, shared);
+
+        match builder.mode {
+            Mode::Validate { duplicate, .. } => duplicate,
+            Mode::Query { .. } => None,
+        }
+    }
+
+    fn add(&mut self, identifier: &CStr, implementation: NonNull<c_void>) {
+        match &mut self.mode {
+            Mode::Query { requested, found } => {
+                if found.is_none() && identifier == *requested {
+                    *found = Some(implementation)
+                }
+            }
+            Mode::Validate {
+                registered,
+                duplicate,
+            } => {
+                if registered.iter().any(|r| r.as_c_str() == identifier) {
+                    duplicate.get_or_insert_with(|| identifier.to_owned());
+                } else {
+                    registered.push(identifier.to_owned());
+                }
+            }
+        }
     }
 
     /// Adds a given extension implementation to the list of extensions this plugin supports.
     pub fn register<E: ExtensionImplementation<H, ExtensionSide = HostExtensionSide>>(
         &mut self,
     ) -> &mut Self {
-        if self.found.is_some() {
-            return self;
+        for identifier in extension_identifiers::<E>() {
+            self.add(identifier, E::IMPLEMENTATION.as_ptr());
         }
 
-        if E::IDENTIFIER == self.requested {
-            self.found = Some(E::IMPLEMENTATION.as_ptr())
+        self
+    }
+
+    /// Adds a given extension implementation to the list of supported extensions, but only if
+    /// `condition` is `true`.
+    ///
+    /// This is a convenience method to conditionally register extensions from a chain of calls,
+    /// e.g. depending on a runtime configuration.
+    #[inline]
+    pub fn register_if<E: ExtensionImplementation<H, ExtensionSide = HostExtensionSide>>(
+        &mut self,
+        condition: bool,
+    ) -> &mut Self {
+        if condition {
+            self.register::<E>();
         }
 
         self
     }
+
+    /// Adds a raw extension implementation to the list of supported extensions, for the given
+    /// extension identifier.
+    ///
+    /// This allows to expose extensions that don't have an associated [`Extension`] type, such as
+    /// third-party extensions.
+    ///
+    /// # Safety
+    ///
+    /// The given implementation must point to a valid, C-FFI compatible extension struct that
+    /// matches the ABI of the extension designated by `identifier`.
+    pub unsafe fn register_raw(
+        &mut self,
+        identifier: &CStr,
+        implementation: RawExtensionImplementation,
+    ) -> &mut Self {
+        self.add(identifier, implementation.as_ptr());
+        self
+    }
+
+    /// Sets a handler to be called if the extension requested by the plugin wasn't registered.
+    ///
+    /// This allows hosts to log or collect the identifiers of all the extensions plugins query but
+    /// the host doesn't implement, e.g. using [`UnsupportedExtensions`], to find out which ones to
+    /// implement first.
+    ///
+    /// The handler is called with the host's [`Shared`](HostHandlers::Shared) type once
+    /// [`declare_extensions`](HostHandlers::declare_extensions) returned, so this can be called
+    /// before or after registering extensions. If this is called multiple times, only the last
+    /// handler is kept. It is never called while the registered extensions are being checked for
+    /// duplicates.
+    ///
+    /// Note that plugins may query extensions from any thread, including the audio thread, and the
+    /// handler is called on the thread the query was made from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_host::prelude::*;
+    /// use clack_host::host::UnsupportedExtensions;
+    ///
+    /// struct MyHost;
+    /// struct MyHostShared {
+    ///     unsupported: UnsupportedExtensions,
+    /// }
+    ///
+    /// # impl<'a> SharedHandler<'a> for MyHostShared {
+    /// #     fn request_restart(&self) {}
+    /// #     fn request_process(&self) {}
+    /// #     fn request_callback(&self) {}
+    /// # }
+    /// impl HostHandlers for MyHost {
+    ///     type Shared<'a> = MyHostShared;
+    ///     type MainThread<'a> = ();
+    ///     type AudioProcessor<'a> = ();
+    ///
+    ///     fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &MyHostShared) {
+    ///         builder.on_unsupported(|shared, identifier| shared.unsupported.record(identifier));
+    ///         // builder.register::<...>();
+    ///     }
+    /// }
+    /// ```
+    pub fn on_unsupported(&mut self, handler: UnsupportedHandler<H>) -> &mut Self {
+        self.on_unsupported = Some(handler);
+        self
+    }
+}
+
+/// A thread-safe collection of the identifiers of all the extensions plugins queried but the host
+/// didn't implement, alongside how many times each was queried.
+///
+/// This is meant to be filled from [`HostExtensions::on_unsupported`], and is typically kept in
+/// the host's [`Shared`](HostHandlers::Shared) type, or shared between all plugin instances.
+///
+/// When the `tracing` feature is enabled, every newly recorded identifier is also logged.
+#[derive(Debug, Default)]
+pub struct UnsupportedExtensions {
+    queries: Mutex<BTreeMap<CString, u64>>,
+}
+
+impl UnsupportedExtensions {
+    /// Creates a new, empty collection.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a query of the given extension identifier.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method locks a mutex, and allocates the first time a given identifier is recorded. It
+    /// is therefore *not* realtime-safe: if a plugin queries extensions from the audio thread,
+    /// recording them may cause audio glitches. This is meant as a diagnostic tool only.
+    pub fn record(&self, identifier: &CStr) {
+        let mut queries = self.lock();
+
+        match queries.get_mut(identifier) {
+            Some(count) => *count += 1,
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?identifier, "Plugin queried an unsupported host extension");
+
+                queries.insert(identifier.to_owned(), 1);
+            }
+        }
+    }
+
+    /// Returns how many times the given extension identifier was queried.
+    pub fn count(&self, identifier: &CStr) -> u64 {
+        self.lock().get(identifier).copied().unwrap_or(0)
+    }
+
+    /// Returns all the recorded identifiers, sorted, alongside how many times each was queried.
+    pub fn snapshot(&self) -> Vec<(CString, u64)> {
+        (self.lock().iter())
+            .map(|(identifier, count)| (identifier.clone(), *count))
+            .collect()
+    }
+
+    /// Returns `true` if no unsupported extension was queried.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Clears all the recorded identifiers.
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<CString, u64>> {
+        // The map is always left in a consistent state, even if a thread panicked.
+        self.queries.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
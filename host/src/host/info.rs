@@ -1,5 +1,7 @@
 use clap_sys::host::clap_host;
+use std::error::Error;
 use std::ffi::{CStr, CString, NulError};
+use std::fmt::{Display, Formatter};
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -74,6 +76,32 @@ impl HostInfo {
         }
     }
 
+    /// Returns a [`HostInfoBuilder`], to build a host information container field by field.
+    ///
+    /// Unlike [`new`](HostInfo::new), the builder also validates that the required fields (the
+    /// name and version) are not empty.
+    ///
+    /// See also the [`host_info_from_cargo`](crate::host_info_from_cargo) macro, which fills
+    /// all the fields from the current crate's metadata.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_host::host::HostInfo;
+    ///
+    /// let info = HostInfo::builder()
+    ///     .name("Bitwig Studio")
+    ///     .vendor("Bitwig GmbH")
+    ///     .url("https://bitwig.com")
+    ///     .version("4.3.2")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    pub fn builder() -> HostInfoBuilder {
+        HostInfoBuilder::new()
+    }
+
     /// Creates a new host information container using the data originally provided to a plugin.
     #[cfg(feature = "clack-plugin")]
     pub fn from_plugin(info: &clack_plugin::host::HostInfo) -> Self {
@@ -99,3 +127,249 @@ impl HostInfo {
         host.version = self.inner.version.as_ptr();
     }
 }
+
+/// A builder for [`HostInfo`], created by [`HostInfo::builder`].
+///
+/// All fields are empty by default. The [`name`](Self::name) and [`version`](Self::version) must
+/// be set before calling [`build`](Self::build), while the others are optional.
+#[derive(Debug, Clone, Default)]
+pub struct HostInfoBuilder {
+    name: String,
+    vendor: String,
+    url: String,
+    version: String,
+}
+
+impl HostInfoBuilder {
+    /// Creates a new builder, with all fields empty.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the host. Example: `"Bitwig Studio"`
+    #[inline]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the software vendor of the host. Example: `"Bitwig GmbH"`
+    #[inline]
+    pub fn vendor(mut self, vendor: impl Into<String>) -> Self {
+        self.vendor = vendor.into();
+        self
+    }
+
+    /// Sets the URL of the host. Example: `"https://bitwig.com"`
+    #[inline]
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Sets the version string of the host. Example: `"4.3.2"`
+    #[inline]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Validates the fields and builds the [`HostInfo`].
+    ///
+    /// # Errors
+    ///
+    /// This returns a [`HostInfoError`] if the name or version are empty, or if any of the fields
+    /// contain a null (`\0`) character.
+    pub fn build(self) -> Result<HostInfo, HostInfoError> {
+        if self.name.is_empty() {
+            return Err(HostInfoError::MissingField(HostInfoField::Name));
+        }
+
+        if self.version.is_empty() {
+            return Err(HostInfoError::MissingField(HostInfoField::Version));
+        }
+
+        fn to_cstring(value: String, field: HostInfoField) -> Result<CString, HostInfoError> {
+            CString::new(value).map_err(|_| HostInfoError::InteriorNul(field))
+        }
+
+        Ok(HostInfo::new_from_cstring(
+            to_cstring(self.name, HostInfoField::Name)?,
+            to_cstring(self.vendor, HostInfoField::Vendor)?,
+            to_cstring(self.url, HostInfoField::Url)?,
+            to_cstring(self.version, HostInfoField::Version)?,
+        ))
+    }
+}
+
+/// One of the fields of a [`HostInfo`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HostInfoField {
+    /// The name of the host.
+    Name,
+    /// The software vendor of the host.
+    Vendor,
+    /// The URL of the host.
+    Url,
+    /// The version string of the host.
+    Version,
+}
+
+impl Display for HostInfoField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HostInfoField::Name => "name",
+            HostInfoField::Vendor => "vendor",
+            HostInfoField::Url => "URL",
+            HostInfoField::Version => "version",
+        })
+    }
+}
+
+/// Errors that can occur when building a [`HostInfo`] using a [`HostInfoBuilder`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HostInfoError {
+    /// A required field was left empty.
+    MissingField(HostInfoField),
+    /// A field contained a null (`\0`) character.
+    InteriorNul(HostInfoField),
+}
+
+impl Display for HostInfoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostInfoError::MissingField(field) => write!(f, "Host {field} must not be empty"),
+            HostInfoError::InteriorNul(field) => {
+                write!(f, "Host {field} must not contain a null character")
+            }
+        }
+    }
+}
+
+impl Error for HostInfoError {}
+
+#[doc(hidden)]
+pub fn __host_info_from_cargo(
+    name: &CStr,
+    authors: &CStr,
+    homepage: &CStr,
+    repository: &CStr,
+    version: &CStr,
+) -> HostInfo {
+    let vendor = authors.to_string_lossy().replace(':', ", ");
+    let url = if homepage.to_bytes().is_empty() {
+        repository
+    } else {
+        homepage
+    };
+
+    HostInfo::new_from_cstring(
+        name.into(),
+        // The authors come from a CStr, which cannot contain null characters.
+        CString::new(vendor).unwrap_or_default(),
+        url.into(),
+        version.into(),
+    )
+}
+
+/// Creates a [`HostInfo`] from the metadata of the crate this macro is invoked in.
+///
+/// The fields are filled as follows:
+///
+/// * name: the package's `name`;
+/// * vendor: the package's `authors`, separated by commas;
+/// * url: the package's `homepage`, or its `repository` if it has no homepage;
+/// * version: the package's `version`.
+///
+/// All this information is validated at compile-time, and will fail to compile if it contains a
+/// null (`\0`) character.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::host::HostInfo;
+/// use clack_host::host_info_from_cargo;
+///
+/// let info: HostInfo = host_info_from_cargo!();
+/// ```
+#[macro_export]
+macro_rules! host_info_from_cargo {
+    () => {{
+        macro_rules! __cargo_cstr {
+            ($var:literal) => {{
+                const VALUE: &::std::ffi::CStr = match ::std::ffi::CStr::from_bytes_with_nul(
+                    concat!(env!($var), "\0").as_bytes(),
+                ) {
+                    Ok(value) => value,
+                    Err(_) => panic!(concat!($var, " must not contain null characters")),
+                };
+                VALUE
+            }};
+        }
+
+        $crate::host::__host_info_from_cargo(
+            __cargo_cstr!("CARGO_PKG_NAME"),
+            __cargo_cstr!("CARGO_PKG_AUTHORS"),
+            __cargo_cstr!("CARGO_PKG_HOMEPAGE"),
+            __cargo_cstr!("CARGO_PKG_REPOSITORY"),
+            __cargo_cstr!("CARGO_PKG_VERSION"),
+        )
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builder_validates_fields() {
+        assert_eq!(
+            HostInfo::builder().version("1.0").build().unwrap_err(),
+            HostInfoError::MissingField(HostInfoField::Name)
+        );
+
+        assert_eq!(
+            HostInfo::builder().name("host").build().unwrap_err(),
+            HostInfoError::MissingField(HostInfoField::Version)
+        );
+
+        assert_eq!(
+            HostInfo::builder()
+                .name("host")
+                .url("https://example\0.com")
+                .version("1.0")
+                .build()
+                .unwrap_err(),
+            HostInfoError::InteriorNul(HostInfoField::Url)
+        );
+
+        let info = HostInfo::builder()
+            .name("host")
+            .vendor("vendor")
+            .version("1.0")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            &*info.inner.name,
+            CStr::from_bytes_with_nul(b"host\0").unwrap()
+        );
+        assert_eq!(
+            &*info.inner.vendor,
+            CStr::from_bytes_with_nul(b"vendor\0").unwrap()
+        );
+        assert!(info.inner.url.to_bytes().is_empty());
+    }
+
+    #[test]
+    fn reads_info_from_cargo() {
+        let info = crate::host_info_from_cargo!();
+
+        assert_eq!(info.inner.name.to_str().unwrap(), env!("CARGO_PKG_NAME"));
+        assert_eq!(
+            info.inner.version.to_str().unwrap(),
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+}
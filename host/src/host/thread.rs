@@ -0,0 +1,147 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+/// A zero-sized token, proving that the code holding it runs on the host's main thread.
+///
+/// This token is neither [`Send`] nor [`Sync`], and can only be obtained from types that
+/// themselves can only exist on the main thread, such as
+/// [`PluginInstance`](crate::plugin::PluginInstance) or
+/// [`PluginMainThreadHandle`](crate::plugin::PluginMainThreadHandle). Requiring it in an API
+/// therefore makes calling that API from another thread a compile-time error.
+///
+/// A [`PluginMainThreadHandle`](crate::plugin::PluginMainThreadHandle) can only be built from
+/// this token, and is itself required by all main-thread-only plugin APIs, such as GUI calls,
+/// state saving and loading, or parameter rescans.
+///
+/// It can be stored and copied around freely, for host authors to gate their own main-thread-only
+/// APIs, or to recover a [`PluginMainThreadHandle`](crate::plugin::PluginMainThreadHandle)
+/// from a [`PluginSharedHandle`](crate::plugin::PluginSharedHandle) using
+/// [`PluginSharedHandle::to_main_thread`](crate::plugin::PluginSharedHandle::to_main_thread).
+///
+/// ```compile_fail
+/// use clack_host::host::MainThreadToken;
+///
+/// fn is_send<T: Send>(_: T) {}
+///
+/// // SAFETY: we are on the main thread.
+/// let token = unsafe { MainThreadToken::new_unchecked() };
+/// is_send(token); // This fails to compile
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MainThreadToken {
+    _no_send: PhantomData<*const ()>,
+}
+
+impl MainThreadToken {
+    /// Creates a new main thread token.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called on the host's main thread, i.e. the thread the plugin instances
+    /// are created and managed from.
+    #[inline]
+    pub const unsafe fn new_unchecked() -> Self {
+        Self {
+            _no_send: PhantomData,
+        }
+    }
+}
+
+thread_local! {
+    /// How many audio-thread plugin functions the current thread is currently calling.
+    ///
+    /// This is a counter rather than a flag, since a host may call into multiple plugins (e.g.
+    /// a plugin hosting other plugins) in a nested fashion.
+    static AUDIO_THREAD_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns `true` if the current thread is currently calling one of a plugin's audio-thread
+/// functions through Clack (e.g. [`process`](crate::process::StartedPluginAudioProcessor::process),
+/// [`start_processing`](crate::process::StoppedPluginAudioProcessor::start_processing) or
+/// [`reset`](crate::process::StartedPluginAudioProcessor::reset)), or while an
+/// [`AudioThreadGuard`] is alive on it, and `false` otherwise.
+///
+/// This is mainly useful for hosts to implement the `thread-check` extension, as a plugin asking
+/// whether it is on the audio thread always does so from within one of those functions (or
+/// from a thread it spawned itself, which is never an audio thread).
+#[inline]
+pub fn is_audio_thread() -> bool {
+    AUDIO_THREAD_CALLS.with(|c| c.get() > 0)
+}
+
+/// A guard marking the current thread as an audio thread for as long as it is alive, as reported
+/// by [`is_audio_thread`].
+///
+/// Clack already enters this guard while calling the plugin's own audio-thread functions, as well
+/// as the audio-thread functions of the extensions it provides (e.g. parameter flushes or thread
+/// pool tasks). Hosts calling other audio-thread plugin functions themselves (e.g. through a
+/// custom extension) should enter it for the duration of those calls.
+///
+/// This guard is neither [`Send`] nor [`Sync`], as it only affects the current thread.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::host::{is_audio_thread, AudioThreadGuard};
+///
+/// assert!(!is_audio_thread());
+///
+/// let guard = AudioThreadGuard::enter();
+/// assert!(is_audio_thread());
+///
+/// drop(guard);
+/// assert!(!is_audio_thread());
+/// ```
+pub struct AudioThreadGuard {
+    _no_send: PhantomData<*const ()>,
+}
+
+impl AudioThreadGuard {
+    /// Marks the current thread as an audio thread, until the returned guard is dropped.
+    ///
+    /// Guards can be nested: the current thread stays marked until all of them are dropped.
+    #[inline]
+    pub fn enter() -> Self {
+        AUDIO_THREAD_CALLS.with(|c| c.set(c.get() + 1));
+
+        Self {
+            _no_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for AudioThreadGuard {
+    #[inline]
+    fn drop(&mut self) {
+        AUDIO_THREAD_CALLS.with(|c| c.set(c.get() - 1));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate static_assertions as sa;
+    use super::*;
+
+    sa::assert_eq_size!(MainThreadToken, ());
+    sa::assert_not_impl_any!(MainThreadToken: Send, Sync);
+    sa::assert_not_impl_any!(crate::plugin::PluginMainThreadHandle<'static>: Send, Sync);
+
+    #[test]
+    fn audio_thread_guards_nest() {
+        assert!(!is_audio_thread());
+
+        let outer = AudioThreadGuard::enter();
+        let inner = AudioThreadGuard::enter();
+        assert!(is_audio_thread());
+
+        drop(inner);
+        assert!(is_audio_thread());
+        drop(outer);
+        assert!(!is_audio_thread());
+
+        let _guard = AudioThreadGuard::enter();
+        std::thread::spawn(|| assert!(!is_audio_thread()))
+            .join()
+            .unwrap();
+    }
+}
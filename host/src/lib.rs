@@ -261,10 +261,13 @@
 //! ```
 
 pub mod bundle;
+#[cfg(feature = "c-api")]
+pub mod c_api;
 pub mod extensions;
 pub mod factory;
 pub mod host;
 pub mod plugin;
+pub mod presets;
 pub mod process;
 mod util;
 
@@ -282,7 +285,7 @@ pub mod prelude {
         },
         host::{
             AudioProcessorHandler, HostError, HostExtensions, HostHandlers, HostInfo,
-            MainThreadHandler, SharedHandler,
+            MainThreadHandler, MainThreadToken, SharedHandler,
         },
         plugin::{
             InitializedPluginHandle, InitializingPluginHandle, PluginAudioProcessorHandle,
@@ -290,8 +293,8 @@ pub mod prelude {
         },
         process::{
             audio_buffers::{
-                AudioPortBuffer, AudioPortBufferType, AudioPorts, InputAudioBuffers, InputChannel,
-                OutputAudioBuffers,
+                AudioPortBuffer, AudioPortBufferType, AudioPorts, InlineAudioPorts,
+                InputAudioBuffers, InputChannel, OutputAudioBuffers,
             },
             AudioPortProcessingInfo, PluginAudioConfiguration, ProcessStatus,
             StoppedPluginAudioProcessor,
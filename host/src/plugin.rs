@@ -1,5 +1,8 @@
 use crate::prelude::*;
+use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
 use clap_sys::plugin::clap_plugin;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
@@ -8,6 +11,7 @@ use std::sync::Arc;
 mod error;
 mod handle;
 pub(crate) mod instance;
+pub mod registry;
 
 pub use error::PluginInstanceError;
 pub use handle::*;
@@ -18,6 +22,7 @@ pub use clack_common::plugin::*;
 /// A plugin instance.
 pub struct PluginInstance<H: HostHandlers> {
     pub(crate) inner: ManuallyDrop<Arc<PluginInstanceInner<H>>>,
+    extension_cache: RefCell<HashMap<&'static CStr, Option<RawExtension<PluginExtensionSide>>>>,
     _no_send: PhantomData<*const ()>,
 }
 
@@ -45,6 +50,7 @@ impl<H: HostHandlers> PluginInstance<H> {
 
         Ok(Self {
             inner: ManuallyDrop::new(inner),
+            extension_cache: RefCell::new(HashMap::new()),
             _no_send: PhantomData,
         })
     }
@@ -67,6 +73,50 @@ impl<H: HostHandlers> PluginInstance<H> {
         Ok(StoppedPluginAudioProcessor::new(Arc::clone(&self.inner)))
     }
 
+    /// Deactivates the plugin using the given stopped audio processor, and then immediately
+    /// re-activates it with the given configuration.
+    ///
+    /// This is the main-thread half of handling a plugin's restart request: see
+    /// [`PluginAudioProcessor::finish_block_then_restart`](crate::process::PluginAudioProcessor::finish_block_then_restart)
+    /// for the full sequence.
+    ///
+    /// The audio processor has to be stopped (on the audio thread) before it can be passed to this
+    /// method, as the plugin's `stop_processing` function cannot be called from the main thread.
+    ///
+    /// # Errors
+    ///
+    /// If the plugin fails to re-activate, this returns the error returned by
+    /// [`activate`](Self::activate). The plugin is left deactivated in that case.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the given audio processor does not belong to this instance.
+    pub fn restart<FA>(
+        &mut self,
+        processor: StoppedPluginAudioProcessor<H>,
+        audio_processor: FA,
+        configuration: PluginAudioConfiguration,
+    ) -> Result<StoppedPluginAudioProcessor<H>, PluginInstanceError>
+    where
+        FA: for<'a> FnOnce(
+            &'a <H as HostHandlers>::Shared<'a>,
+            &mut <H as HostHandlers>::MainThread<'a>,
+        ) -> <H as HostHandlers>::AudioProcessor<'a>,
+    {
+        self.deactivate(processor);
+        self.activate(audio_processor, configuration)
+    }
+
+    /// Returns `true` if the plugin requested to be restarted (i.e. deactivated and re-activated),
+    /// and hasn't been re-activated since.
+    ///
+    /// If the plugin is currently inactive, the request can be fulfilled by simply activating it
+    /// the next time it is needed.
+    #[inline]
+    pub fn is_restart_requested(&self) -> bool {
+        self.inner.wrapper().is_restart_requested()
+    }
+
     #[inline]
     pub fn deactivate(&mut self, processor: StoppedPluginAudioProcessor<H>) {
         self.deactivate_with(processor, |_, _| ())
@@ -118,11 +168,29 @@ impl<H: HostHandlers> PluginInstance<H> {
         unsafe { self.inner.on_main_thread() }
     }
 
+    /// Returns a shared reference to the raw, C-FFI compatible plugin instance struct.
+    ///
+    /// This is the same as [`as_raw`](Self::as_raw), which should be used instead.
     #[inline]
+    #[deprecated = "Use as_raw instead"]
     pub fn raw_instance(&self) -> &clap_plugin {
         self.inner.raw_instance()
     }
 
+    /// Returns a shared reference to the raw, C-FFI compatible plugin instance struct.
+    ///
+    /// The returned reference is valid for as long as this instance is alive. Note that it is
+    /// only safe to call the instance's thread-safe functions through it: main-thread functions
+    /// should instead be called through the [`plugin_handle`](Self::plugin_handle).
+    ///
+    /// There is no matching `from_raw` constructor, as a [`PluginInstance`] owns the host-side
+    /// data the plugin instance was created with. To wrap a raw plugin instance that was created
+    /// elsewhere, use e.g. [`PluginSharedHandle::from_raw`] instead.
+    #[inline]
+    pub fn as_raw(&self) -> &clap_plugin {
+        self.inner.raw_instance()
+    }
+
     #[inline]
     pub fn is_active(&self) -> bool {
         self.inner.is_active()
@@ -161,10 +229,55 @@ impl<H: HostHandlers> PluginInstance<H> {
         self.inner.plugin_shared()
     }
 
+    /// Returns the [`PluginDescriptor`](crate::factory::PluginDescriptor) of this instance.
+    ///
+    /// This allows to display information about a loaded plugin instance (such as its name,
+    /// vendor or version) without having to keep the metadata from the plugin's factory around.
+    ///
+    /// This may return `None` if the underlying plugin implementation didn't properly populate
+    /// the descriptor pointer.
+    #[inline]
+    pub fn descriptor(&self) -> Option<crate::factory::PluginDescriptor<'_>> {
+        self.inner.plugin_shared().descriptor()
+    }
+
+    /// Returns the plugin's implementation of the extension `E`, if it has one.
+    ///
+    /// Unlike [`PluginSharedHandle::get_extension`], the plugin is only queried the first time a
+    /// given extension is requested: the result of the lookup (including the extension being
+    /// unsupported) is cached for the lifetime of this instance, per extension identifier.
+    ///
+    /// This is useful for generic code that needs to look up extensions repeatedly, as it avoids
+    /// having to cross the FFI boundary and to have the plugin match the identifier every time.
+    pub fn cached_extension<E: Extension<ExtensionSide = PluginExtensionSide>>(&self) -> Option<E> {
+        let raw = *self
+            .extension_cache
+            .borrow_mut()
+            .entry(E::IDENTIFIER)
+            .or_insert_with(|| {
+                let (raw, _) = self.inner.plugin_shared().query_extension_of::<E>()?;
+                Some(raw)
+            });
+
+        // SAFETY: pointer comes from one of the associated identifiers of E.
+        raw.map(|raw| unsafe { E::from_raw(raw) })
+    }
+
+    /// Returns a token proving the caller is on the main thread.
+    ///
+    /// See the [`MainThreadToken`] documentation for more information.
+    #[inline]
+    pub fn main_thread_token(&self) -> MainThreadToken {
+        // SAFETY: this type can only exist on the main thread.
+        unsafe { MainThreadToken::new_unchecked() }
+    }
+
     #[inline]
     pub fn plugin_handle(&mut self) -> PluginMainThreadHandle {
         // SAFETY: this type can only exist on the main thread.
-        unsafe { PluginMainThreadHandle::new(self.inner.raw_instance().into()) }
+        unsafe {
+            PluginMainThreadHandle::new(self.inner.raw_instance().into(), self.main_thread_token())
+        }
     }
 }
 
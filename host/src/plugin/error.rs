@@ -4,9 +4,10 @@ use clap_sys::ext::log::{clap_log_severity, CLAP_LOG_ERROR, CLAP_LOG_PLUGIN_MISB
 use core::fmt;
 use core::fmt::{Debug, Display, Formatter};
 use std::error::Error;
+use std::ffi::CString;
 
 /// All errors that can arise using plugin instances.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PluginInstanceError {
     /// The plugin's audio processing could not be started.
     StartProcessingFailed,
@@ -47,6 +48,12 @@ pub enum PluginInstanceError {
     ///
     /// This is a sign of a misbehaving plugin implementation.
     NullActivateFunction,
+    /// The host declared the same extension identifier more than once in its
+    /// [`declare_extensions`](crate::host::HostHandlers::declare_extensions) implementation.
+    ///
+    /// This happens when an extension is registered twice, or when two registered extensions
+    /// share one of their (compatibility) identifiers. This holds the duplicated identifier.
+    DuplicateExtension(CString),
 }
 
 impl PluginInstanceError {
@@ -71,6 +78,7 @@ impl PluginInstanceError {
             Self::NullFactoryCreatePluginFunction => {
                 "Plugin Factory's create_plugin function is null"
             }
+            Self::DuplicateExtension(_) => "Host declared the same extension more than once",
         }
     }
 
@@ -87,7 +95,12 @@ impl PluginInstanceError {
 
 impl Display for PluginInstanceError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.write_str(self.msg())
+        match self {
+            Self::DuplicateExtension(identifier) => {
+                write!(f, "{}: {}", self.msg(), identifier.to_string_lossy())
+            }
+            _ => f.write_str(self.msg()),
+        }
     }
 }
 
@@ -1,6 +1,10 @@
 use crate::factory::PluginDescriptor;
-use clack_common::extensions::{Extension, PluginExtensionSide, RawExtension};
+use crate::host::MainThreadToken;
+use clack_common::extensions::{
+    extension_identifiers, Extension, PluginExtensionSide, RawExtension,
+};
 use clap_sys::plugin::clap_plugin;
+use std::ffi::{c_void, CStr};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 use std::ops::Deref;
@@ -8,24 +12,43 @@ use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
+/// A handle to a plugin instance, which can only be used on the main thread.
+///
+/// This handle can only be created from a [`MainThreadToken`]. Main-thread-only plugin APIs
+/// (e.g. the GUI, state or parameter extensions) require it, which makes calling them from
+/// another thread a compile-time error.
 #[derive(Eq, PartialEq)]
 #[repr(transparent)]
 pub struct PluginMainThreadHandle<'a> {
     raw: NonNull<clap_plugin>,
     lifetime: PhantomData<&'a clap_plugin>,
+    main_thread: MainThreadToken,
 }
 
 impl<'a> PluginMainThreadHandle<'a> {
     /// # Safety
     /// The user must ensure the provided plugin pointer is valid.
-    /// This can only be called on the main thread.
-    pub(crate) unsafe fn new(raw: NonNull<clap_plugin>) -> Self {
+    pub(crate) unsafe fn new(raw: NonNull<clap_plugin>, main_thread: MainThreadToken) -> Self {
         Self {
             raw,
             lifetime: PhantomData,
+            main_thread,
         }
     }
 
+    /// Creates a new handle from a raw pointer to a C-FFI compatible plugin instance struct.
+    ///
+    /// # Safety
+    ///
+    /// The given pointer must point to a valid, initialized `clap_plugin` instance, which must
+    /// remain valid for the lifetime `'a`.
+    ///
+    /// This handle must only be created and used on the main thread.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_plugin>) -> Self {
+        Self::new(raw, MainThreadToken::new_unchecked())
+    }
+
     /// Returns a shared reference to the raw, C-FFI compatible plugin instance struct.
     ///
     /// This type enforces that the reference is valid for the lifetime of the instance (`'a`).
@@ -58,6 +81,14 @@ impl<'a> PluginMainThreadHandle<'a> {
         // SAFETY: this cast is valid since both types are just a NonNull<clap_host> and repr(transparent)
         unsafe { &*(self as *const Self as *const PluginSharedHandle<'a>) }
     }
+
+    /// Returns a token proving the caller is on the main thread.
+    ///
+    /// See the [`MainThreadToken`] documentation for more information.
+    #[inline]
+    pub fn main_thread_token(&self) -> MainThreadToken {
+        self.main_thread
+    }
 }
 
 impl Debug for PluginMainThreadHandle<'_> {
@@ -99,6 +130,22 @@ impl<'a> PluginSharedHandle<'a> {
         }
     }
 
+    /// Creates a new handle from a raw pointer to a C-FFI compatible plugin instance struct.
+    ///
+    /// # Safety
+    ///
+    /// The given pointer must point to a valid, initialized `clap_plugin` instance, which must
+    /// remain valid for the lifetime `'a`.
+    ///
+    /// This handle may be created and used on any thread, as it only exposes thread-safe methods.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_plugin>) -> Self {
+        Self {
+            raw,
+            lifetime: PhantomData,
+        }
+    }
+
     /// Returns the [`PluginDescriptor`] this instance corresponds to.
     ///
     /// This may return `None` if the underlying plugin implementation didn't properly populate
@@ -130,16 +177,76 @@ impl<'a> PluginSharedHandle<'a> {
     }
 
     pub fn get_extension<E: Extension<ExtensionSide = PluginExtensionSide>>(&self) -> Option<E> {
+        self.get_extension_with_identifier()
+            .map(|(extension, _)| extension)
+    }
+
+    /// Returns the plugin's implementation of the extension `E`, alongside the identifier the
+    /// plugin recognized it with.
+    ///
+    /// This is either [`E::IDENTIFIER`](Extension::IDENTIFIER), or one of the extension's
+    /// [`COMPAT_IDENTIFIERS`](Extension::COMPAT_IDENTIFIERS) if the plugin only supports an older
+    /// identifier.
+    pub fn get_extension_with_identifier<E: Extension<ExtensionSide = PluginExtensionSide>>(
+        &self,
+    ) -> Option<(E, &'static CStr)> {
+        let (raw, identifier) = self.query_extension_of::<E>()?;
+
+        // SAFETY: pointer comes from one of the associated identifiers of E.
+        unsafe { Some((E::from_raw(raw), identifier)) }
+    }
+
+    /// Returns a main-thread handle to this plugin instance.
+    ///
+    /// The given [`MainThreadToken`] proves this is called on the main thread.
+    #[inline]
+    pub fn to_main_thread(&self, token: MainThreadToken) -> PluginMainThreadHandle<'a> {
+        // SAFETY: This type ensures the provided pointer is valid for 'a.
+        unsafe { PluginMainThreadHandle::new(self.raw, token) }
+    }
+
+    /// Returns a raw pointer to the plugin's implementation of the extension matching the given
+    /// identifier, or `None` if the plugin doesn't support it.
+    ///
+    /// This is an escape hatch allowing to use extensions that don't have an associated
+    /// [`Extension`] type, such as third-party extensions. Prefer using
+    /// [`get_extension`](Self::get_extension) whenever possible.
+    ///
+    /// The CLAP specification guarantees the returned pointer is valid for as long as the plugin
+    /// instance exists. However, it is up to the caller to know which type it points to, and to
+    /// only use it from the threads the extension allows.
+    #[inline]
+    pub fn get_raw_extension(&self, identifier: &CStr) -> Option<NonNull<c_void>> {
+        self.query_extension(identifier)
+            .map(|raw| raw.as_ptr().cast())
+    }
+
+    /// Queries the plugin for the extension `E`, trying all of its identifiers in order.
+    pub(crate) fn query_extension_of<E: Extension<ExtensionSide = PluginExtensionSide>>(
+        &self,
+    ) -> Option<(RawExtension<PluginExtensionSide>, &'static CStr)> {
+        extension_identifiers::<E>()
+            .find_map(|identifier| Some((self.query_extension(identifier)?, identifier)))
+    }
+
+    /// Queries the plugin for the extension matching the given identifier.
+    pub(crate) fn query_extension(
+        &self,
+        identifier: &CStr,
+    ) -> Option<RawExtension<PluginExtensionSide>> {
+        crate::util::trace_span!(
+            DEBUG,
+            "query_extension",
+            plugin_id = crate::util::plugin_id(self.as_raw()),
+            extension = ?identifier,
+        );
+
         // SAFETY: This type ensures the function pointers are valid
-        let ext =
-            unsafe { self.as_raw().get_extension?(self.raw.as_ptr(), E::IDENTIFIER.as_ptr()) };
+        let ext = unsafe { self.as_raw().get_extension?(self.raw.as_ptr(), identifier.as_ptr()) };
 
         let ext = NonNull::new(ext as *mut _)?;
         // SAFETY: The CLAP spec guarantees that the extension lives as long as the instance.
-        let raw = unsafe { RawExtension::from_raw_plugin_extension(ext, self.raw) };
-
-        // SAFETY: pointer comes from the associated E::IDENTIFIER.
-        unsafe { Some(E::from_raw(raw)) }
+        Some(unsafe { RawExtension::from_raw_plugin_extension(ext, self.raw) })
     }
 
     /// Safely dereferences a [`RawExtension`] pointer produced by this plugin instance.
@@ -191,6 +298,22 @@ impl<'a> PluginAudioProcessorHandle<'a> {
         }
     }
 
+    /// Creates a new handle from a raw pointer to a C-FFI compatible plugin instance struct.
+    ///
+    /// # Safety
+    ///
+    /// The given pointer must point to a valid, initialized `clap_plugin` instance, which must
+    /// remain valid for the lifetime `'a`.
+    ///
+    /// This handle must only be created and used on the audio thread, while the plugin is active.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_plugin>) -> Self {
+        Self {
+            raw,
+            lifetime: PhantomData,
+        }
+    }
+
     /// Returns a shared reference to the raw, C-FFI compatible plugin instance struct.
     ///
     /// This type enforces that the reference is valid for the lifetime of the instance (`'a`).
@@ -1,5 +1,6 @@
 use crate::extensions::wrapper::descriptor::RawHostDescriptor;
 use crate::extensions::wrapper::HostWrapper;
+use crate::host::AudioThreadGuard;
 use crate::prelude::*;
 use clap_sys::plugin::clap_plugin;
 use std::ffi::CStr;
@@ -32,11 +33,19 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
             &'s <H as HostHandlers>::Shared<'s>,
         ) -> <H as HostHandlers>::MainThread<'s>,
     {
+        crate::util::trace_span!(INFO, "instantiate", plugin_id = ?plugin_id);
+
         let plugin_factory = plugin_bundle
             .get_plugin_factory()
             .ok_or(PluginInstanceError::MissingPluginFactory)?;
 
         let host_wrapper = HostWrapper::new(shared, main_thread);
+
+        if let Some(identifier) = HostExtensions::<H>::find_duplicate(host_wrapper.shared()) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(identifier = ?identifier, "Host declared a duplicate extension");
+            return Err(PluginInstanceError::DuplicateExtension(identifier));
+        }
         let host_descriptor = Box::pin(RawHostDescriptor::new::<H>(host_info));
 
         let mut instance = Arc::new(Self {
@@ -114,6 +123,15 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
             &mut <H as HostHandlers>::MainThread<'a>,
         ) -> <H as HostHandlers>::AudioProcessor<'a>,
     {
+        crate::util::trace_span!(
+            INFO,
+            "activate",
+            plugin_id = crate::util::plugin_id(self.raw_instance()),
+            sample_rate = configuration.sample_rate,
+            min_frames_count = configuration.min_frames_count,
+            max_frames_count = configuration.max_frames_count,
+        );
+
         let activate = self
             .raw_instance()
             .activate
@@ -124,6 +142,9 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
             self.host_wrapper.setup_audio_processor(audio_processor)?;
         }
 
+        // Any pending restart request is fulfilled by this activation.
+        self.host_wrapper.set_restart_requested(false);
+
         // SAFETY: this type ensures the function pointer is valid
         let success = unsafe {
             activate(
@@ -160,6 +181,12 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
             return Err(PluginInstanceError::DeactivatedPlugin);
         }
 
+        crate::util::trace_span!(
+            INFO,
+            "deactivate",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         if self.is_started.load(Ordering::Acquire) {
             // SAFETY: this method being &mut guarantees nothing can call any other main-thread method
             unsafe { self.stop_processing() }
@@ -180,6 +207,13 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
     /// on the audio thread.
     #[inline]
     pub unsafe fn start_processing(&self) -> Result<(), PluginInstanceError> {
+        let _audio_thread = AudioThreadGuard::enter();
+        crate::util::trace_audio_thread_span!(
+            DEBUG,
+            "start_processing",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         if let Some(start_processing) = self.raw_instance().start_processing {
             if start_processing(self.raw_instance()) {
                 self.is_started.store(true, Ordering::Release);
@@ -196,6 +230,13 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
     /// User must ensure that this is only called on the audio thread.
     #[inline]
     pub unsafe fn reset(&self) {
+        let _audio_thread = AudioThreadGuard::enter();
+        crate::util::trace_audio_thread_span!(
+            DEBUG,
+            "reset",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         if let Some(reset) = self.raw_instance().reset {
             reset(self.raw_instance())
         }
@@ -206,6 +247,13 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
     /// on the audio thread.
     #[inline]
     pub unsafe fn stop_processing(&self) {
+        let _audio_thread = AudioThreadGuard::enter();
+        crate::util::trace_audio_thread_span!(
+            DEBUG,
+            "stop_processing",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         if let Some(stop_processing) = self.raw_instance().stop_processing {
             stop_processing(self.raw_instance());
             self.is_started.store(false, Ordering::Release);
@@ -216,6 +264,12 @@ impl<H: HostHandlers> PluginInstanceInner<H> {
     /// User must ensure this is only called on the main thread.
     #[inline]
     pub unsafe fn on_main_thread(&self) {
+        crate::util::trace_span!(
+            DEBUG,
+            "on_main_thread",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         if let Some(on_main_thread) = self.raw_instance().on_main_thread {
             on_main_thread(self.raw_instance())
         }
@@ -235,6 +289,12 @@ impl<H: HostHandlers> Drop for PluginInstanceInner<H> {
             let _ = self.deactivate_with(|_, _| ());
         }
 
+        crate::util::trace_span!(
+            INFO,
+            "destroy",
+            plugin_id = crate::util::plugin_id(self.raw_instance())
+        );
+
         self.host_wrapper.start_instance_destroy();
         // SAFETY: we are in the drop impl, so this can only be called once and without any
         // other concurrent calls.
@@ -0,0 +1,303 @@
+//! An optional registry, tracking all live plugin instances and the bundles they belong to.
+//!
+//! See the [`InstanceRegistry`] documentation for more information.
+
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A unique identifier for a plugin instance created through an [`InstanceRegistry`].
+///
+/// Identifiers are never reused within the same registry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InstanceId(u64);
+
+impl InstanceId {
+    /// Returns the raw integer value of this identifier.
+    #[inline]
+    pub const fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for InstanceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Information about a live plugin instance tracked by an [`InstanceRegistry`].
+#[derive(Clone)]
+pub struct InstanceInfo {
+    id: InstanceId,
+    plugin_id: CString,
+    bundle: PluginBundle,
+}
+
+impl InstanceInfo {
+    /// The unique identifier of this instance in its registry.
+    #[inline]
+    pub fn id(&self) -> InstanceId {
+        self.id
+    }
+
+    /// The identifier of the plugin this is an instance of.
+    #[inline]
+    pub fn plugin_id(&self) -> &CStr {
+        &self.plugin_id
+    }
+
+    /// The bundle this instance was created from.
+    #[inline]
+    pub fn bundle(&self) -> &PluginBundle {
+        &self.bundle
+    }
+}
+
+impl Debug for InstanceInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstanceInfo")
+            .field("id", &self.id)
+            .field("plugin_id", &self.plugin_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Hooks called by an [`InstanceRegistry`] when plugin instances are created or destroyed.
+///
+/// All methods have empty default implementations. The hooks are called on the thread the
+/// instances are created and destroyed on (i.e. the main thread), after the registry has been
+/// updated.
+pub trait InstanceRegistryHooks: Send + Sync {
+    /// Called after a plugin instance has been successfully created.
+    #[allow(unused_variables)]
+    fn instantiated(&self, info: &InstanceInfo) {}
+
+    /// Called after a plugin instance has been destroyed.
+    #[allow(unused_variables)]
+    fn destroyed(&self, info: &InstanceInfo) {}
+}
+
+#[derive(Default)]
+struct RegistryInner {
+    next_id: AtomicU64,
+    instances: Mutex<BTreeMap<InstanceId, InstanceInfo>>,
+    hooks: Option<Box<dyn InstanceRegistryHooks>>,
+}
+
+impl RegistryInner {
+    #[inline]
+    fn instances(&self) -> MutexGuard<'_, BTreeMap<InstanceId, InstanceInfo>> {
+        // The registry's state is always consistent, even if a panic occurred while it was locked.
+        self.instances.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A registry that tracks all the live plugin instances that were created through it, for each
+/// plugin bundle.
+///
+/// Instances created using [`InstanceRegistry::instantiate`] are registered for as long as they
+/// are alive, and are unregistered when they are dropped. This allows plugin managers to
+/// enumerate live instances, crash reporters to know which plugins were loaded, or hosts to
+/// check that no instance of a bundle exists anymore before e.g. rescanning or unloading it.
+///
+/// [`InstanceRegistryHooks`] can also be provided to be notified when instances are created or
+/// destroyed.
+///
+/// This registry is cheap to clone: all clones share the same state.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::plugin::registry::InstanceRegistry;
+/// # use clack_host::prelude::*;
+/// # use std::ffi::CStr;
+///
+/// # fn foo<H: HostHandlers>(bundle: &PluginBundle, host_info: &HostInfo, shared: impl for<'b> FnOnce(&'b ()) -> H::Shared<'b>, main_thread: impl for<'b> FnOnce(&'b H::Shared<'b>) -> H::MainThread<'b>) -> Result<(), PluginInstanceError> {
+/// let registry = InstanceRegistry::new();
+/// let plugin_id = CStr::from_bytes_with_nul(b"com.u-he.diva\0").unwrap();
+///
+/// let instance = registry.instantiate::<H, _, _>(shared, main_thread, bundle, plugin_id, host_info)?;
+/// assert!(registry.has_instances_of(bundle));
+///
+/// drop(instance);
+/// assert!(!registry.has_instances_of(bundle));
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Default)]
+pub struct InstanceRegistry {
+    inner: Arc<RegistryInner>,
+}
+
+impl InstanceRegistry {
+    /// Creates a new, empty registry.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty registry, which calls the given hooks when instances are created or
+    /// destroyed.
+    pub fn with_hooks(hooks: impl InstanceRegistryHooks + 'static) -> Self {
+        Self {
+            inner: Arc::new(RegistryInner {
+                hooks: Some(Box::new(hooks)),
+                ..RegistryInner::default()
+            }),
+        }
+    }
+
+    /// Creates a new plugin instance, and registers it into this registry.
+    ///
+    /// This takes the same arguments as [`PluginInstance::new`]. The returned
+    /// [`RegisteredPluginInstance`] can be used just like a [`PluginInstance`], and unregisters
+    /// itself when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This returns the same errors as [`PluginInstance::new`]. Instances that failed to be
+    /// created are never registered.
+    pub fn instantiate<H, FS, FH>(
+        &self,
+        shared: FS,
+        main_thread: FH,
+        bundle: &PluginBundle,
+        plugin_id: &CStr,
+        host: &HostInfo,
+    ) -> Result<RegisteredPluginInstance<H>, PluginInstanceError>
+    where
+        H: HostHandlers,
+        FS: for<'b> FnOnce(&'b ()) -> <H as HostHandlers>::Shared<'b>,
+        FH: for<'b> FnOnce(
+            &'b <H as HostHandlers>::Shared<'b>,
+        ) -> <H as HostHandlers>::MainThread<'b>,
+    {
+        let instance = PluginInstance::new(shared, main_thread, bundle, plugin_id, host)?;
+
+        let info = InstanceInfo {
+            id: InstanceId(self.inner.next_id.fetch_add(1, Ordering::Relaxed)),
+            plugin_id: plugin_id.into(),
+            bundle: bundle.clone(),
+        };
+
+        self.inner.instances().insert(info.id, info.clone());
+
+        if let Some(hooks) = &self.inner.hooks {
+            hooks.instantiated(&info);
+        }
+
+        Ok(RegisteredPluginInstance {
+            instance,
+            registration: Registration {
+                registry: self.inner.clone(),
+                id: info.id,
+            },
+        })
+    }
+
+    /// Returns information about all the live instances in this registry, ordered by creation.
+    pub fn instances(&self) -> Vec<InstanceInfo> {
+        self.inner.instances().values().cloned().collect()
+    }
+
+    /// Returns information about all the live instances created from the given bundle, ordered
+    /// by creation.
+    pub fn instances_of(&self, bundle: &PluginBundle) -> Vec<InstanceInfo> {
+        self.inner
+            .instances()
+            .values()
+            .filter(|info| is_same_bundle(&info.bundle, bundle))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns `true` if any live instance in this registry was created from the given bundle.
+    pub fn has_instances_of(&self, bundle: &PluginBundle) -> bool {
+        self.inner
+            .instances()
+            .values()
+            .any(|info| is_same_bundle(&info.bundle, bundle))
+    }
+
+    /// Returns information about the live instance with the given identifier, if it exists.
+    pub fn get(&self, id: InstanceId) -> Option<InstanceInfo> {
+        self.inner.instances().get(&id).cloned()
+    }
+
+    /// Returns the number of live instances in this registry.
+    pub fn len(&self) -> usize {
+        self.inner.instances().len()
+    }
+
+    /// Returns `true` if there are no live instances in this registry.
+    pub fn is_empty(&self) -> bool {
+        self.inner.instances().is_empty()
+    }
+}
+
+impl Debug for InstanceRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstanceRegistry")
+            .field("instances", &self.instances())
+            .finish_non_exhaustive()
+    }
+}
+
+#[inline]
+fn is_same_bundle(a: &PluginBundle, b: &PluginBundle) -> bool {
+    core::ptr::eq(a.raw_entry(), b.raw_entry())
+}
+
+/// Unregisters an instance when dropped.
+struct Registration {
+    registry: Arc<RegistryInner>,
+    id: InstanceId,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let info = self.registry.instances().remove(&self.id);
+
+        if let (Some(info), Some(hooks)) = (info, &self.registry.hooks) {
+            hooks.destroyed(&info);
+        }
+    }
+}
+
+/// A plugin instance that is tracked by an [`InstanceRegistry`].
+///
+/// This dereferences to a [`PluginInstance`], and can be used just like one. The instance is
+/// unregistered when this is dropped, after the plugin instance itself was destroyed.
+pub struct RegisteredPluginInstance<H: HostHandlers> {
+    // Fields are dropped in order: the instance must be destroyed before it is unregistered.
+    instance: PluginInstance<H>,
+    registration: Registration,
+}
+
+impl<H: HostHandlers> RegisteredPluginInstance<H> {
+    /// Returns the unique identifier of this instance in its registry.
+    #[inline]
+    pub fn id(&self) -> InstanceId {
+        self.registration.id
+    }
+}
+
+impl<H: HostHandlers> Deref for RegisteredPluginInstance<H> {
+    type Target = PluginInstance<H>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.instance
+    }
+}
+
+impl<H: HostHandlers> DerefMut for RegisteredPluginInstance<H> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.instance
+    }
+}
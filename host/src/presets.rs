@@ -0,0 +1,603 @@
+#![deny(missing_docs)]
+
+//! A host-side, in-memory index of plugin presets, to build preset browsers upon.
+//!
+//! Presets are gathered from [`PresetProvider`]s into a [`PresetIndex`], which can then be
+//! queried by plugin ID, category, creator or name using a [`PresetQuery`]. Once the user picked
+//! a preset, the index gives it to a [`PresetLoader`], which is responsible for having the
+//! plugin instance load it.
+//!
+//! This module only handles the bookkeeping: providers and loaders are implemented by the host,
+//! e.g. on top of the plugin's own preset discovery and preset loading facilities, or of the
+//! host's own preset files.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::presets::*;
+//!
+//! struct FactoryPresets;
+//!
+//! impl PresetProvider for FactoryPresets {
+//!     fn id(&self) -> &str {
+//!         "com.example.factory-presets"
+//!     }
+//!
+//!     fn crawl(&mut self, indexer: &mut PresetIndexer) {
+//!         indexer.add(
+//!             PresetEntry::new("Warm Pad", PresetLocation::Plugin)
+//!                 .with_load_key("pad-1")
+//!                 .with_plugin_id("com.example.synth")
+//!                 .with_category("pad")
+//!                 .with_creator("Example Audio"),
+//!         );
+//!     }
+//! }
+//!
+//! let mut index = PresetIndex::new();
+//! assert_eq!(index.crawl(&mut FactoryPresets), 1);
+//!
+//! let query = PresetQuery::new().plugin_id("com.example.synth").category("pad");
+//! let (id, preset) = index.query(&query).next().unwrap();
+//! assert_eq!(preset.name(), "Warm Pad");
+//! # let _ = id;
+//! ```
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Where a preset is stored.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PresetLocation {
+    /// The preset is stored in a file, or in a container file holding multiple presets (in which
+    /// case the preset's [load key](PresetEntry::load_key) identifies it in the container).
+    File(PathBuf),
+    /// The preset is bundled within the plugin itself, and is identified by its
+    /// [load key](PresetEntry::load_key).
+    Plugin,
+}
+
+/// The metadata of a single preset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PresetEntry {
+    name: String,
+    location: PresetLocation,
+    load_key: Option<String>,
+    description: Option<String>,
+    plugin_ids: Vec<String>,
+    categories: Vec<String>,
+    creators: Vec<String>,
+}
+
+impl PresetEntry {
+    /// Creates a new preset entry, with the given name and location.
+    pub fn new(name: impl Into<String>, location: PresetLocation) -> Self {
+        Self {
+            name: name.into(),
+            location,
+            load_key: None,
+            description: None,
+            plugin_ids: Vec::new(),
+            categories: Vec::new(),
+            creators: Vec::new(),
+        }
+    }
+
+    /// Sets the key identifying this preset within its location.
+    pub fn with_load_key(mut self, load_key: impl Into<String>) -> Self {
+        self.load_key = Some(load_key.into());
+        self
+    }
+
+    /// Sets the description of this preset.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds the ID of a plugin that can load this preset.
+    pub fn with_plugin_id(mut self, plugin_id: impl Into<String>) -> Self {
+        self.plugin_ids.push(plugin_id.into());
+        self
+    }
+
+    /// Adds a category (or tag) to this preset, e.g. `bass` or `lead`.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// Adds a creator of this preset.
+    pub fn with_creator(mut self, creator: impl Into<String>) -> Self {
+        self.creators.push(creator.into());
+        self
+    }
+
+    /// The name of this preset.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Where this preset is stored.
+    #[inline]
+    pub fn location(&self) -> &PresetLocation {
+        &self.location
+    }
+
+    /// The path of the file this preset is stored in, if it is stored in a file.
+    #[inline]
+    pub fn file_path(&self) -> Option<&Path> {
+        match &self.location {
+            PresetLocation::File(path) => Some(path),
+            PresetLocation::Plugin => None,
+        }
+    }
+
+    /// The key identifying this preset within its location, if any.
+    #[inline]
+    pub fn load_key(&self) -> Option<&str> {
+        self.load_key.as_deref()
+    }
+
+    /// The description of this preset, if any.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The IDs of all the plugins that can load this preset.
+    #[inline]
+    pub fn plugin_ids(&self) -> impl Iterator<Item = &str> {
+        self.plugin_ids.iter().map(String::as_str)
+    }
+
+    /// The categories of this preset.
+    #[inline]
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.categories.iter().map(String::as_str)
+    }
+
+    /// The creators of this preset.
+    #[inline]
+    pub fn creators(&self) -> impl Iterator<Item = &str> {
+        self.creators.iter().map(String::as_str)
+    }
+}
+
+/// The identifier of a preset in a [`PresetIndex`].
+///
+/// Identifiers stay valid until the preset is removed from the index, e.g. when its provider is
+/// crawled again. Identifiers of removed presets are never reused: looking them up always fails,
+/// even if another preset took their place in the index.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PresetId {
+    index: usize,
+    generation: u64,
+}
+
+/// A source of presets, which can be crawled into a [`PresetIndex`].
+pub trait PresetProvider {
+    /// A unique identifier for this provider.
+    ///
+    /// Crawling a provider again replaces all the presets it previously provided.
+    fn id(&self) -> &str;
+
+    /// Adds all the presets of this provider to the given indexer.
+    fn crawl(&mut self, indexer: &mut PresetIndexer);
+}
+
+/// Receives the presets of a [`PresetProvider`] while it is crawled.
+pub struct PresetIndexer<'a> {
+    index: &'a mut PresetIndex,
+    provider: usize,
+    added: usize,
+}
+
+impl PresetIndexer<'_> {
+    /// Adds a preset to the index, and returns its identifier.
+    pub fn add(&mut self, preset: PresetEntry) -> PresetId {
+        self.added += 1;
+        self.index.insert(self.provider, preset)
+    }
+}
+
+/// Loads presets into a plugin instance.
+///
+/// This is implemented by the host, usually using the plugin's preset loading facilities, or by
+/// restoring the plugin's state from the preset file.
+pub trait PresetLoader {
+    /// Loads the given preset.
+    ///
+    /// # Errors
+    ///
+    /// This should return [`PresetLoadError::Unsupported`] if the preset can't be loaded into
+    /// the plugin at all, and [`PresetLoadError::LoadFailed`] if the plugin failed to load it.
+    fn load_preset(&mut self, preset: &PresetEntry) -> Result<(), PresetLoadError>;
+}
+
+/// Errors that can occur while loading a preset from a [`PresetIndex`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PresetLoadError {
+    /// The preset isn't in the index (anymore).
+    UnknownPreset,
+    /// The preset can't be loaded into this plugin.
+    Unsupported,
+    /// The plugin failed to load the preset.
+    LoadFailed,
+}
+
+impl Display for PresetLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresetLoadError::UnknownPreset => f.write_str("Unknown preset"),
+            PresetLoadError::Unsupported => f.write_str("Preset cannot be loaded into this plugin"),
+            PresetLoadError::LoadFailed => f.write_str("Plugin failed to load preset"),
+        }
+    }
+}
+
+impl Error for PresetLoadError {}
+
+/// A filter to select presets from a [`PresetIndex`].
+///
+/// All the given criteria must be matched for a preset to be selected. An empty query matches
+/// all presets.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PresetQuery {
+    plugin_id: Option<String>,
+    category: Option<String>,
+    creator: Option<String>,
+    name: Option<String>,
+}
+
+impl PresetQuery {
+    /// Creates a new empty query, which matches all presets.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches presets that can be loaded by the plugin with the given ID.
+    pub fn plugin_id(mut self, plugin_id: impl Into<String>) -> Self {
+        self.plugin_id = Some(plugin_id.into());
+        self
+    }
+
+    /// Only matches presets in the given category. Categories are compared case-insensitively.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Only matches presets from the given creator. Creators are compared case-insensitively.
+    pub fn creator(mut self, creator: impl Into<String>) -> Self {
+        self.creator = Some(creator.into());
+        self
+    }
+
+    /// Only matches presets whose name contains the given text, case-insensitively.
+    pub fn name_contains(mut self, text: impl Into<String>) -> Self {
+        self.name = Some(text.into().to_lowercase());
+        self
+    }
+
+    /// Returns `true` if the given preset matches this query.
+    pub fn matches(&self, preset: &PresetEntry) -> bool {
+        fn matches_any<'a>(
+            mut values: impl Iterator<Item = &'a str>,
+            expected: &Option<String>,
+        ) -> bool {
+            match expected {
+                None => true,
+                Some(expected) => values.any(|v| v.eq_ignore_ascii_case(expected)),
+            }
+        }
+
+        if let Some(plugin_id) = &self.plugin_id {
+            if !preset.plugin_ids().any(|id| id == plugin_id) {
+                return false;
+            }
+        }
+
+        if let Some(name) = &self.name {
+            if !preset.name.to_lowercase().contains(name.as_str()) {
+                return false;
+            }
+        }
+
+        matches_any(preset.categories(), &self.category)
+            && matches_any(preset.creators(), &self.creator)
+    }
+}
+
+/// An in-memory index of presets, gathered from [`PresetProvider`]s.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Clone, Debug, Default)]
+pub struct PresetIndex {
+    providers: Vec<String>,
+    presets: Vec<PresetSlot>,
+    free_slots: Vec<usize>,
+    len: usize,
+}
+
+/// A slot of a [`PresetIndex`], holding a preset alongside the index of its provider.
+///
+/// The generation is bumped every time the preset is removed, so that [`PresetId`]s of removed
+/// presets don't match the ones that later reuse the slot.
+#[derive(Clone, Debug, Default)]
+struct PresetSlot {
+    generation: u64,
+    preset: Option<(usize, PresetEntry)>,
+}
+
+impl PresetIndex {
+    /// Creates a new, empty preset index.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crawls the given provider, adding all its presets to the index.
+    ///
+    /// If this provider was already crawled before, all the presets it previously provided are
+    /// removed first.
+    ///
+    /// This returns the number of presets the provider added.
+    pub fn crawl(&mut self, provider: &mut impl PresetProvider) -> usize {
+        let provider_index = match self.providers.iter().position(|id| id == provider.id()) {
+            Some(index) => {
+                self.remove_provider_presets(index);
+                index
+            }
+            None => {
+                self.providers.push(provider.id().to_owned());
+                self.providers.len() - 1
+            }
+        };
+
+        let mut indexer = PresetIndexer {
+            index: self,
+            provider: provider_index,
+            added: 0,
+        };
+
+        provider.crawl(&mut indexer);
+        indexer.added
+    }
+
+    /// Removes all the presets of the provider with the given ID.
+    pub fn remove_provider(&mut self, provider_id: &str) {
+        if let Some(index) = self.providers.iter().position(|id| id == provider_id) {
+            self.remove_provider_presets(index);
+        }
+    }
+
+    /// Returns the number of presets in the index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no presets in the index.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the preset with the given identifier.
+    #[inline]
+    pub fn get(&self, id: PresetId) -> Option<&PresetEntry> {
+        let slot = self.presets.get(id.index)?;
+
+        if slot.generation != id.generation {
+            return None;
+        }
+
+        slot.preset.as_ref().map(|(_, preset)| preset)
+    }
+
+    /// Returns an iterator over all the presets in the index.
+    pub fn iter(&self) -> impl Iterator<Item = (PresetId, &PresetEntry)> {
+        self.presets.iter().enumerate().filter_map(|(index, slot)| {
+            let id = PresetId {
+                index,
+                generation: slot.generation,
+            };
+
+            Some((id, &slot.preset.as_ref()?.1))
+        })
+    }
+
+    /// Returns an iterator over all the presets matching the given query.
+    pub fn query<'a>(
+        &'a self,
+        query: &'a PresetQuery,
+    ) -> impl Iterator<Item = (PresetId, &'a PresetEntry)> + 'a {
+        self.iter().filter(|(_, preset)| query.matches(preset))
+    }
+
+    /// Returns all the categories used by the presets that match the given query, sorted and
+    /// de-duplicated.
+    ///
+    /// This is useful to populate the filters of a preset browser.
+    pub fn categories<'a>(&'a self, query: &PresetQuery) -> BTreeSet<&'a str> {
+        self.iter()
+            .filter(|(_, preset)| query.matches(preset))
+            .flat_map(|(_, preset)| preset.categories())
+            .collect()
+    }
+
+    /// Returns all the creators of the presets that match the given query, sorted and
+    /// de-duplicated.
+    pub fn creators<'a>(&'a self, query: &PresetQuery) -> BTreeSet<&'a str> {
+        self.iter()
+            .filter(|(_, preset)| query.matches(preset))
+            .flat_map(|(_, preset)| preset.creators())
+            .collect()
+    }
+
+    /// Loads the preset with the given identifier, using the given loader.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`PresetLoadError::UnknownPreset`] if there is no preset with this
+    /// identifier, or any error returned by the loader.
+    pub fn load(
+        &self,
+        id: PresetId,
+        loader: &mut impl PresetLoader,
+    ) -> Result<(), PresetLoadError> {
+        let preset = self.get(id).ok_or(PresetLoadError::UnknownPreset)?;
+        loader.load_preset(preset)
+    }
+
+    fn insert(&mut self, provider: usize, preset: PresetEntry) -> PresetId {
+        self.len += 1;
+
+        // Reuse the slots of removed presets.
+        let index = self.free_slots.pop().unwrap_or_else(|| {
+            self.presets.push(PresetSlot::default());
+            self.presets.len() - 1
+        });
+
+        let slot = &mut self.presets[index];
+        slot.preset = Some((provider, preset));
+
+        PresetId {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    fn remove_provider_presets(&mut self, provider: usize) {
+        for (index, slot) in self.presets.iter_mut().enumerate() {
+            if slot.preset.as_ref().is_some_and(|(p, _)| *p == provider) {
+                slot.preset = None;
+                slot.generation += 1;
+                self.free_slots.push(index);
+                self.len -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Provider {
+        id: &'static str,
+        presets: Vec<PresetEntry>,
+    }
+
+    impl PresetProvider for Provider {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn crawl(&mut self, indexer: &mut PresetIndexer) {
+            for preset in &self.presets {
+                indexer.add(preset.clone());
+            }
+        }
+    }
+
+    struct Loader(Vec<String>);
+
+    impl PresetLoader for Loader {
+        fn load_preset(&mut self, preset: &PresetEntry) -> Result<(), PresetLoadError> {
+            match preset.file_path() {
+                Some(path) => {
+                    self.0.push(path.to_string_lossy().into_owned());
+                    Ok(())
+                }
+                None => Err(PresetLoadError::Unsupported),
+            }
+        }
+    }
+
+    fn preset(name: &str, plugin_id: &str, category: &str) -> PresetEntry {
+        PresetEntry::new(name, PresetLocation::File(format!("/{name}.preset").into()))
+            .with_plugin_id(plugin_id)
+            .with_category(category)
+            .with_creator("Someone")
+    }
+
+    #[test]
+    fn indexes_and_queries_presets() {
+        let mut index = PresetIndex::new();
+        let mut factory = Provider {
+            id: "factory",
+            presets: vec![
+                preset("Deep Bass", "synth", "Bass"),
+                preset("Soft Lead", "synth", "Lead"),
+                preset("Small Room", "reverb", "Room"),
+            ],
+        };
+        let mut user = Provider {
+            id: "user",
+            presets: vec![preset("My Bass", "synth", "bass")],
+        };
+
+        assert_eq!(index.crawl(&mut factory), 3);
+        assert_eq!(index.crawl(&mut user), 1);
+        assert_eq!(index.len(), 4);
+
+        let bass = PresetQuery::new().plugin_id("synth").category("BASS");
+        let names: Vec<_> = index.query(&bass).map(|(_, p)| p.name()).collect();
+        assert_eq!(names, ["Deep Bass", "My Bass"]);
+
+        let synth = PresetQuery::new().plugin_id("synth");
+        assert_eq!(
+            index.categories(&synth).into_iter().collect::<Vec<_>>(),
+            ["Bass", "Lead", "bass"]
+        );
+        assert_eq!(index.query(&synth.name_contains("lead")).count(), 1);
+        assert_eq!(
+            index
+                .creators(&PresetQuery::new())
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["Someone"]
+        );
+
+        // Crawling again replaces the provider's presets.
+        factory.presets.truncate(1);
+        assert_eq!(index.crawl(&mut factory), 1);
+        assert_eq!(index.len(), 2);
+
+        let (id, _) = index
+            .query(&PresetQuery::new().name_contains("my"))
+            .next()
+            .unwrap();
+        let mut loader = Loader(Vec::new());
+        assert_eq!(index.load(id, &mut loader), Ok(()));
+        assert_eq!(loader.0, ["/My Bass.preset"]);
+
+        index.remove_provider("user");
+        assert_eq!(index.len(), 1);
+        assert_eq!(
+            index.load(id, &mut loader),
+            Err(PresetLoadError::UnknownPreset)
+        );
+
+        // Identifiers of removed presets don't match the presets reusing their slots.
+        let mut other = Provider {
+            id: "other",
+            presets: vec![preset("Other Bass", "synth", "Bass")],
+        };
+        assert_eq!(index.crawl(&mut other), 1);
+        assert!(index.get(id).is_none());
+
+        let (other_id, _) = index
+            .query(&PresetQuery::new().name_contains("other"))
+            .next()
+            .unwrap();
+        assert_ne!(other_id, id);
+        assert_eq!(index.get(other_id).unwrap().name(), "Other Bass");
+    }
+}
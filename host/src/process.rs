@@ -14,13 +14,17 @@
 #![deny(missing_docs)]
 
 use self::audio_buffers::InputAudioBuffers;
-use crate::host::HostHandlers;
+use crate::host::{AudioThreadGuard, HostHandlers};
 use crate::plugin::{PluginAudioProcessorHandle, PluginInstanceError, PluginSharedHandle};
 use crate::prelude::{OutputAudioBuffers, PluginInstance};
 use crate::process::PluginAudioProcessor::*;
+use crate::util::{guard_allocations, validate_input_events};
 use clack_common::events::event_types::TransportEvent;
 use clack_common::events::io::{InputEvents, OutputEvents};
-use clap_sys::process::clap_process;
+use clap_sys::audio_buffer::clap_audio_buffer;
+use clap_sys::events::clap_event_transport;
+use clap_sys::plugin::clap_plugin;
+use clap_sys::process::{clap_process, clap_process_status};
 use std::cell::UnsafeCell;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
@@ -32,6 +36,23 @@ pub use clack_common::process::*;
 
 #[allow(missing_docs)] // TODO: doc this
 pub mod audio_buffers;
+pub mod block_adapter;
+pub mod bypass;
+pub mod event_driver;
+pub mod frames_budget;
+pub mod graph;
+pub mod midi_out;
+pub mod mpe;
+pub mod notes;
+pub mod planar;
+pub mod recorder;
+pub mod recovery;
+#[cfg(feature = "resampling")]
+pub mod resampler;
+pub mod sleep;
+pub mod telemetry;
+pub mod transport;
+pub mod watchdog;
 
 /// A handle to a plugin's audio processor that can be in either its `started` or `stopped` state.
 ///
@@ -51,6 +72,77 @@ pub enum PluginAudioProcessor<H: HostHandlers> {
 }
 
 impl<H: HostHandlers> PluginAudioProcessor<H> {
+    /// Returns `true` if the plugin requested to be restarted, i.e. deactivated and re-activated.
+    ///
+    /// This flag is set as soon as the plugin calls the host's `request_restart` function, before
+    /// [`SharedHandler::request_restart`](crate::host::SharedHandler::request_restart) is called.
+    /// It is cleared once the plugin is re-activated.
+    ///
+    /// See [`finish_block_then_restart`](Self::finish_block_then_restart) for how to handle
+    /// restart requests.
+    #[inline]
+    pub fn is_restart_requested(&self) -> bool {
+        let inner = match self {
+            Started(s) => &s.inner,
+            Stopped(s) => &s.inner,
+        };
+
+        inner.wrapper().is_restart_requested()
+    }
+
+    /// Prepares this audio processor to be restarted, if the plugin requested it.
+    ///
+    /// This must be called on the audio thread, after the current block has been fully processed.
+    /// If the plugin requested a restart, this stops processing if needed, and returns `true`.
+    /// Otherwise, this does nothing and returns `false`.
+    ///
+    /// Plugins cannot be deactivated while they are processing, and their audio processor can
+    /// only be stopped from the audio thread, while deactivation must happen on the main thread.
+    /// Restarting a plugin is therefore done in the following sequence:
+    ///
+    /// 1. On the audio thread, after each processed block, call this method. If it returns `true`,
+    ///    stop calling [`process`](StartedPluginAudioProcessor::process), and send this audio
+    ///    processor (as a [`StoppedPluginAudioProcessor`], see [`into_stopped`](Self::into_stopped))
+    ///    back to the main thread. Until a new audio processor is received, the audio thread
+    ///    should output silence.
+    /// 2. On the main thread, call [`PluginInstance::restart`] with the stopped audio processor.
+    ///    This deactivates the plugin and re-activates it, possibly with a new audio
+    ///    configuration.
+    /// 3. Send the new audio processor back to the audio thread, which can then start processing
+    ///    it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_host::prelude::*;
+    /// use clack_host::process::PluginAudioProcessor;
+    /// use std::sync::mpsc::Sender;
+    ///
+    /// fn audio_thread_callback<H: HostHandlers>(
+    ///     processor: &mut Option<PluginAudioProcessor<H>>,
+    ///     to_main_thread: &Sender<StoppedPluginAudioProcessor<H>>,
+    /// ) {
+    ///     if let Some(p) = processor.as_mut() {
+    ///         if let Ok(_started) = p.ensure_processing_started() {
+    ///             // _started.process(...);
+    ///         }
+    ///
+    ///         if p.finish_block_then_restart() {
+    ///             let p = processor.take().unwrap();
+    ///             to_main_thread.send(p.into_stopped()).unwrap();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn finish_block_then_restart(&mut self) -> bool {
+        if !self.is_restart_requested() {
+            return false;
+        }
+
+        self.ensure_processing_stopped();
+        true
+    }
+
     /// Resets the plugin's audio processing state.
     ///
     /// This clears all the plugin's internal buffers, kills all voices, and resets all processing
@@ -421,6 +513,10 @@ impl<H: HostHandlers> StartedPluginAudioProcessor<H> {
     ///   Can be [`InputAudioBuffers::empty`] if the plugin takes no audio input at all.
     /// * `audio_output`: The [`OutputAudioBuffers`] the plugin is going to read audio frames from.
     ///   Can be [`OutputAudioBuffers::empty`] if the plugin produces no audio output at all.
+    ///   If both the inputs and outputs are empty, the plugin processes zero frames. Use
+    ///   [`InputAudioBuffers::empty_with_frames`] and [`OutputAudioBuffers::empty_with_frames`]
+    ///   to process event-only plugins (e.g. note effects) in blocks of a given size instead.
+    ///   See also [`EventDriver`](event_driver::EventDriver).
     /// * `input_events`: The [`InputEvents`] list the plugin is going to receive events from.
     ///   Can be [`InputEvents::empty`] if the plugin doesn't need to receive any events.
     /// * `output_events`: The [`OutputEvents`] buffer the plugin is going to write the events it
@@ -460,6 +556,16 @@ impl<H: HostHandlers> StartedPluginAudioProcessor<H> {
     /// This can also return [`PluginInstanceError::ProcessingFailed`] if the `process` function
     /// failed for any reason.
     ///
+    /// # Panics
+    ///
+    /// If the `assert-no-alloc` feature is enabled, this function panics if the plugin performed
+    /// any heap allocation while processing. See the documentation of `utils::assert_no_alloc` for
+    /// more information.
+    ///
+    /// If the `validate-events` feature is enabled, this function panics if the input events
+    /// are malformed, unordered, or outside of the processed block. See
+    /// [`InputEvents::validate`] for more information.
+    ///
     /// [`reset`]: Self::reset
     pub fn process(
         &mut self,
@@ -470,47 +576,54 @@ impl<H: HostHandlers> StartedPluginAudioProcessor<H> {
         steady_time: Option<u64>,
         transport: Option<&TransportEvent>,
     ) -> Result<ProcessStatus, PluginInstanceError> {
-        let frames_count = audio_inputs.min_available_frames_with(audio_outputs);
-
-        let audio_inputs = audio_inputs.as_raw_buffers();
-        let audio_outputs = audio_outputs.as_raw_buffers();
-
-        let process = clap_process {
-            frames_count,
-
-            in_events: input_events.as_raw(),
-            out_events: output_events.as_raw_mut(),
-
-            audio_inputs: audio_inputs.as_ptr(),
-            audio_outputs: audio_outputs.as_mut_ptr(),
-            audio_inputs_count: audio_inputs.len() as u32,
-            audio_outputs_count: audio_outputs.len() as u32,
-
-            steady_time: match steady_time {
-                None => -1,
-                // This is a wrapping conversion from u64 to i64.
-                // The wrapping allows smooth operation from the plugin if steady_time does actually overflow an i64.
-                Some(steady_time) => (steady_time & i64::MAX as u64) as i64,
-            },
-            transport: match transport {
-                None => core::ptr::null(),
-                Some(e) => e.as_raw(),
-            },
-        };
+        self.session()?.process(
+            audio_inputs,
+            audio_outputs,
+            input_events,
+            output_events,
+            steady_time,
+            transport,
+        )
+    }
 
+    /// Starts a [`ProcessSession`], which allows to perform many [`process`](Self::process) calls
+    /// with as little overhead as possible.
+    ///
+    /// See the [`ProcessSession`] documentation for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`PluginInstanceError::NullProcessFunction`] if the plugin
+    /// implementation did not provide a valid underlying `process` function pointer.
+    #[inline]
+    pub fn session(&mut self) -> Result<ProcessSession<'_>, PluginInstanceError> {
         let instance = self.inner.raw_instance();
 
         let process_fn = instance
             .process
             .ok_or(PluginInstanceError::NullProcessFunction)?;
 
-        // SAFETY: this type ensures the function pointer is valid
-        let status = unsafe { process_fn(instance, &process) };
+        Ok(ProcessSession {
+            instance,
+            process_fn,
+        })
+    }
 
-        match ProcessStatus::from_raw(status) {
-            None | Some(Err(())) => Err(PluginInstanceError::ProcessingFailed),
-            Some(Ok(status)) => Ok(status),
-        }
+    /// Prepares a [`ProcessBatch`], to process many consecutive blocks of frames with reusable
+    /// [`ProcessParams`].
+    ///
+    /// See the [`ProcessBatch`] documentation for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`PluginInstanceError::NullProcessFunction`] if the plugin
+    /// implementation did not provide a valid underlying `process` function pointer.
+    #[inline]
+    pub fn process_batch<'a>(
+        &'a mut self,
+        params: &'a mut ProcessParams,
+    ) -> Result<ProcessBatch<'a>, PluginInstanceError> {
+        Ok(self.session()?.into_batch(params))
     }
 
     /// Resets the plugin's audio processing state.
@@ -626,6 +739,424 @@ impl<H: HostHandlers> StartedPluginAudioProcessor<H> {
     }
 }
 
+/// A processing session, which exclusively borrows a [`StartedPluginAudioProcessor`] to perform
+/// a batch of [`process`](Self::process) calls.
+///
+/// This is obtained through the [`StartedPluginAudioProcessor::session`] method.
+///
+/// Looking up the plugin instance and its `process` function pointer is only done once, when the
+/// session is created, instead of on every call. This makes it slightly cheaper to call
+/// [`process`](Self::process) through a session, which can add up in hosts running hundreds of
+/// plugin instances.
+///
+/// Apart from that, calling [`ProcessSession::process`] is strictly equivalent to calling
+/// [`StartedPluginAudioProcessor::process`].
+pub struct ProcessSession<'a> {
+    instance: &'a clap_plugin,
+    process_fn:
+        unsafe extern "C" fn(*const clap_plugin, *const clap_process) -> clap_process_status,
+}
+
+impl<'a> ProcessSession<'a> {
+    /// Process a chunk of audio frames and events.
+    ///
+    /// See the [`StartedPluginAudioProcessor::process`] documentation for more information about
+    /// this method's arguments.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`PluginInstanceError::ProcessingFailed`] if the `process` function failed for
+    /// any reason.
+    ///
+    /// # Panics
+    ///
+    /// If the `assert-no-alloc` feature is enabled, this function panics if the plugin performed
+    /// any heap allocation while processing.
+    ///
+    /// If the `validate-events` feature is enabled, this function panics if the input events
+    /// are malformed, unordered, or outside of the processed block. See
+    /// [`InputEvents::validate`] for more information.
+    #[inline]
+    pub fn process(
+        &mut self,
+        audio_inputs: &InputAudioBuffers,
+        audio_outputs: &mut OutputAudioBuffers,
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+        steady_time: Option<u64>,
+        transport: Option<&TransportEvent>,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let frames_count = audio_inputs.min_available_frames_with(audio_outputs);
+        validate_input_events(input_events, frames_count);
+
+        #[cfg(feature = "tracing-audio-thread")]
+        let _span = crate::util::enter_process_span(self.instance, frames_count);
+
+        let audio_inputs = audio_inputs.as_raw_buffers();
+        let audio_outputs = audio_outputs.as_raw_buffers();
+
+        let process = clap_process {
+            frames_count,
+
+            in_events: input_events.as_raw(),
+            out_events: output_events.as_raw_mut(),
+
+            audio_inputs: audio_inputs.as_ptr(),
+            audio_outputs: audio_outputs.as_mut_ptr(),
+            audio_inputs_count: audio_inputs.len() as u32,
+            audio_outputs_count: audio_outputs.len() as u32,
+
+            steady_time: raw_steady_time(steady_time),
+            transport: raw_transport(transport),
+        };
+
+        let process_fn = self.process_fn;
+        let _audio_thread = AudioThreadGuard::enter();
+
+        // SAFETY: the borrowed processor ensures the instance and function pointer are valid
+        let status = guard_allocations(|| unsafe { process_fn(self.instance, &process) });
+
+        process_status(status)
+    }
+
+    /// Prepares a [`ProcessBatch`], to process many consecutive blocks of frames with reusable
+    /// [`ProcessParams`].
+    ///
+    /// See the [`ProcessBatch`] documentation for more information.
+    #[inline]
+    pub fn process_batch<'b>(&'b mut self, params: &'b mut ProcessParams) -> ProcessBatch<'b> {
+        ProcessBatch {
+            instance: self.instance,
+            process_fn: self.process_fn,
+            params,
+            process: PreparedProcess::new(),
+        }
+    }
+
+    #[inline]
+    fn into_batch(self, params: &'a mut ProcessParams) -> ProcessBatch<'a> {
+        ProcessBatch {
+            instance: self.instance,
+            process_fn: self.process_fn,
+            params,
+            process: PreparedProcess::new(),
+        }
+    }
+}
+
+/// A prepared batch of [`process`](Self::process) calls, which all use the same reusable
+/// [`ProcessParams`].
+///
+/// This is obtained through the [`StartedPluginAudioProcessor::process_batch`] or
+/// [`ProcessSession::process_batch`] methods.
+///
+/// The underlying `clap_process` struct is only built once, when the batch is created. Each
+/// [`process`](Self::process) call then only updates the audio buffers, the number of frames to
+/// process, the events, and the steady time and transport information from the `params`. This
+/// is useful for hosts processing many consecutive blocks, e.g. to drive event-only plugins at a
+/// fixed block rate, or to split a block into sub-blocks.
+///
+/// The audio buffers are only borrowed for the duration of each [`process`](Self::process) call,
+/// so hosts are free to fill the input buffers and read the output buffers in between calls.
+///
+/// # Example
+///
+/// ```no_run
+/// use clack_host::prelude::*;
+/// use clack_host::process::{ProcessParams, StartedPluginAudioProcessor};
+///
+/// # fn foo(mut processor: StartedPluginAudioProcessor<()>) -> Result<(), PluginInstanceError> {
+/// let mut params = ProcessParams::new().with_steady_time(0);
+/// let inputs = InputAudioBuffers::empty_with_frames(64);
+/// let mut outputs = OutputAudioBuffers::empty_with_frames(64);
+///
+/// let mut batch = processor.process_batch(&mut params)?;
+///
+/// for _ in 0..4 {
+///     batch.process(
+///         64,
+///         &inputs,
+///         &mut outputs,
+///         &InputEvents::empty(),
+///         &mut OutputEvents::void(),
+///     )?;
+/// }
+///
+/// assert_eq!(batch.params().steady_time(), Some(256));
+/// # Ok(()) }
+/// ```
+pub struct ProcessBatch<'a> {
+    instance: &'a clap_plugin,
+    process_fn: ProcessFn,
+    params: &'a mut ProcessParams,
+    process: PreparedProcess,
+}
+
+impl<'a> ProcessBatch<'a> {
+    /// Returns the processing parameters used by this batch.
+    #[inline]
+    pub fn params(&self) -> &ProcessParams {
+        self.params
+    }
+
+    /// Returns the processing parameters used by this batch, mutably.
+    ///
+    /// This can be used to update the transport information in between two blocks.
+    #[inline]
+    pub fn params_mut(&mut self) -> &mut ProcessParams {
+        self.params
+    }
+
+    /// Processes a block of `frames_count` frames, from the start of the given audio buffers.
+    ///
+    /// If `frames_count` is greater than the minimum number of frames available in all the audio
+    /// buffers, only that many frames are processed.
+    ///
+    /// The steady time and transport information are taken from the batch's
+    /// [`params`](Self::params). If it is enabled, the steady time counter is advanced by the
+    /// number of processed frames after this call.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`PluginInstanceError::ProcessingFailed`] if the `process` function failed for
+    /// any reason.
+    ///
+    /// # Panics
+    ///
+    /// If the `assert-no-alloc` feature is enabled, this function panics if the plugin performed
+    /// any heap allocation while processing.
+    ///
+    /// If the `validate-events` feature is enabled, this function panics if the input events
+    /// are malformed, unordered, or outside of the processed block. See
+    /// [`InputEvents::validate`] for more information.
+    pub fn process(
+        &mut self,
+        frames_count: u32,
+        audio_inputs: &InputAudioBuffers,
+        audio_outputs: &mut OutputAudioBuffers,
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let frames_count = frames_count.min(audio_inputs.min_available_frames_with(audio_outputs));
+
+        // SAFETY: the borrowed processor ensures the instance and function pointer are valid, and
+        // the frames count is bounded by the frames available in the given buffers.
+        unsafe {
+            self.process.process(
+                self.instance,
+                self.process_fn,
+                self.params,
+                frames_count,
+                audio_inputs.as_raw_buffers(),
+                audio_outputs.as_raw_buffers(),
+                input_events,
+                output_events,
+            )
+        }
+    }
+}
+
+type ProcessFn =
+    unsafe extern "C" fn(*const clap_plugin, *const clap_process) -> clap_process_status;
+
+/// A `clap_process` struct that is kept around and reused across many process calls.
+///
+/// All the borrowed pointers are set right before each call, and cleared once it returns. This
+/// struct therefore never holds any dangling pointer in between calls.
+#[derive(Copy, Clone)]
+struct PreparedProcess(clap_process);
+
+// SAFETY: the only pointers held outside of process calls are null.
+unsafe impl Send for PreparedProcess {}
+// SAFETY: the only pointers held outside of process calls are null.
+unsafe impl Sync for PreparedProcess {}
+
+impl PreparedProcess {
+    const fn new() -> Self {
+        Self(clap_process {
+            frames_count: 0,
+            steady_time: -1,
+            transport: core::ptr::null(),
+            audio_inputs: core::ptr::null(),
+            audio_outputs: core::ptr::null_mut(),
+            audio_inputs_count: 0,
+            audio_outputs_count: 0,
+            in_events: core::ptr::null(),
+            out_events: core::ptr::null(),
+        })
+    }
+
+    /// # Safety
+    ///
+    /// The instance and function pointer must be valid, and `frames_count` must not be greater
+    /// than the number of frames available in all the given audio buffers.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn process(
+        &mut self,
+        instance: &clap_plugin,
+        process_fn: ProcessFn,
+        params: &mut ProcessParams,
+        frames_count: u32,
+        audio_inputs: &[clap_audio_buffer],
+        audio_outputs: &mut [clap_audio_buffer],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        validate_input_events(input_events, frames_count);
+
+        #[cfg(feature = "tracing-audio-thread")]
+        let _span = crate::util::enter_process_span(instance, frames_count);
+
+        let process = &mut self.0;
+        process.frames_count = frames_count;
+        process.in_events = input_events.as_raw();
+        process.out_events = output_events.as_raw_mut();
+        process.audio_inputs = audio_inputs.as_ptr();
+        process.audio_outputs = audio_outputs.as_mut_ptr();
+        process.audio_inputs_count = audio_inputs.len() as u32;
+        process.audio_outputs_count = audio_outputs.len() as u32;
+        process.steady_time = raw_steady_time(params.steady_time);
+        process.transport = raw_transport(params.transport.as_ref());
+
+        let status = {
+            let process = &*process;
+            let _audio_thread = AudioThreadGuard::enter();
+
+            // SAFETY: the caller ensures the instance and function pointer are valid, and the
+            // borrowed buffers, events and params ensure all the process pointers are valid.
+            guard_allocations(|| unsafe { process_fn(instance, process) })
+        };
+
+        let process = &mut self.0;
+        process.in_events = core::ptr::null();
+        process.out_events = core::ptr::null();
+        process.audio_inputs = core::ptr::null();
+        process.audio_outputs = core::ptr::null_mut();
+        process.transport = core::ptr::null();
+
+        params.advance(frames_count);
+        process_status(status)
+    }
+}
+
+#[inline]
+fn raw_steady_time(steady_time: Option<u64>) -> i64 {
+    match steady_time {
+        None => -1,
+        // This is a wrapping conversion from u64 to i64.
+        // The wrapping allows smooth operation from the plugin if steady_time does actually overflow an i64.
+        Some(steady_time) => (steady_time & i64::MAX as u64) as i64,
+    }
+}
+
+#[inline]
+fn raw_transport(transport: Option<&TransportEvent>) -> *const clap_event_transport {
+    match transport {
+        None => core::ptr::null(),
+        Some(e) => e.as_raw(),
+    }
+}
+
+#[inline]
+fn process_status(status: clap_process_status) -> Result<ProcessStatus, PluginInstanceError> {
+    match ProcessStatus::from_raw(status) {
+        None | Some(Err(())) => Err(PluginInstanceError::ProcessingFailed),
+        Some(Ok(status)) => Ok(status),
+    }
+}
+
+/// Processing parameters that can be reused across many [`ProcessBatch::process`] calls.
+///
+/// This holds the steady time counter, as well as the transport information given to the plugin.
+/// Both are kept from one block to the next, so that only what changed needs to be updated:
+///
+/// * The steady time counter, if enabled with [`with_steady_time`](Self::with_steady_time), is
+///   automatically advanced by the number of processed frames after each block. It can also be
+///   changed at any time using [`set_steady_time`](Self::set_steady_time).
+/// * The transport information is left untouched, and can be updated in place using
+///   [`transport_mut`](Self::transport_mut).
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::ProcessParams;
+///
+/// let mut params = ProcessParams::new().with_steady_time(0);
+/// assert_eq!(params.steady_time(), Some(0));
+/// assert!(params.transport().is_none());
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ProcessParams {
+    steady_time: Option<u64>,
+    transport: Option<TransportEvent>,
+}
+
+impl ProcessParams {
+    /// Creates new processing parameters, with no steady time counter and no transport
+    /// information.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            steady_time: None,
+            transport: None,
+        }
+    }
+
+    /// Enables the steady time counter, starting at the given sample time.
+    #[inline]
+    pub const fn with_steady_time(mut self, steady_time: u64) -> Self {
+        self.steady_time = Some(steady_time);
+        self
+    }
+
+    /// Sets the transport information to give to the plugin.
+    #[inline]
+    pub const fn with_transport(mut self, transport: TransportEvent) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Returns the current value of the steady time counter, or `None` if it is disabled.
+    #[inline]
+    pub const fn steady_time(&self) -> Option<u64> {
+        self.steady_time
+    }
+
+    /// Sets the current value of the steady time counter, or disables it if `None` is given.
+    ///
+    /// Note the steady time is not allowed to jump backwards, unless the plugin was
+    /// [`reset`](StartedPluginAudioProcessor::reset) beforehand.
+    #[inline]
+    pub fn set_steady_time(&mut self, steady_time: Option<u64>) {
+        self.steady_time = steady_time;
+    }
+
+    /// Returns the transport information given to the plugin, if any.
+    #[inline]
+    pub const fn transport(&self) -> Option<&TransportEvent> {
+        self.transport.as_ref()
+    }
+
+    /// Returns a mutable reference to the transport information given to the plugin, if any.
+    #[inline]
+    pub fn transport_mut(&mut self) -> Option<&mut TransportEvent> {
+        self.transport.as_mut()
+    }
+
+    /// Sets the transport information given to the plugin, or removes it if `None` is given.
+    #[inline]
+    pub fn set_transport(&mut self, transport: Option<TransportEvent>) {
+        self.transport = transport;
+    }
+
+    #[inline]
+    fn advance(&mut self, frames_count: u32) {
+        if let Some(steady_time) = &mut self.steady_time {
+            *steady_time = steady_time.wrapping_add(frames_count as u64);
+        }
+    }
+}
+
 /// A handle to a plugin's audio processor that is in the `stopped` state.
 ///
 /// This is the default state the plugin's audio processor will be in after calling [`activate`].
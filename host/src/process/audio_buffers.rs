@@ -111,12 +111,20 @@ impl AudioPorts {
         }
     }
 
+    /// Creates a new set of buffer descriptors, with enough capacity for the given number of
+    /// channels and ports.
+    ///
+    /// As long as they are given no more channels and ports than this, the
+    /// [`with_input_buffers`](Self::with_input_buffers) and
+    /// [`with_output_buffers`](Self::with_output_buffers) methods never allocate.
+    ///
+    /// See [`InlineAudioPorts`] for a variant that doesn't rely on heap allocation at all.
     pub fn with_capacity(total_channel_count: usize, port_count: usize) -> Self {
         let mut bufs = Self {
             buffer_configs: Vec::with_capacity(port_count),
             buffer_lists: Vec::with_capacity(total_channel_count),
         };
-        bufs.resize_buffer_configs(port_count);
+        bufs.buffer_configs.prepare(port_count);
 
         bufs
     }
@@ -126,21 +134,6 @@ impl AudioPorts {
         self.buffer_configs.len()
     }
 
-    fn resize_buffer_configs(&mut self, new_size: usize) {
-        if new_size > self.buffer_configs.len() {
-            self.buffer_configs.resize(
-                new_size,
-                clap_audio_buffer {
-                    data32: core::ptr::null_mut(),
-                    data64: core::ptr::null_mut(),
-                    channel_count: 0,
-                    latency: 0,
-                    constant_mask: 0,
-                },
-            );
-        }
-    }
-
     pub fn with_input_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
         &'a mut self,
         iter: I,
@@ -151,103 +144,102 @@ impl AudioPorts {
         ChannelIter32: IntoIterator<Item = InputChannel<'a, f32>>,
         ChannelIter64: IntoIterator<Item = InputChannel<'a, f64>>,
     {
-        let iter = iter.into_iter();
-        self.resize_buffer_configs(iter.len());
-        self.buffer_lists.clear();
-
-        let mut min_channel_buffer_length = usize::MAX;
-        let mut total = 0;
-        let mut has_reallocated = false;
-
-        for (i, port) in iter.enumerate() {
-            total = i + 1;
-
-            let last = self.buffer_lists.len();
-
-            let mut constant_mask = 0u64;
-            let is_f64 = match port.channels {
-                AudioPortBufferType::F32(channels) => {
-                    for channel in channels {
-                        min_channel_buffer_length =
-                            min_channel_buffer_length.min(channel.buffer.len());
-                        if channel.is_constant {
-                            constant_mask |= 1 << i as u64
-                        }
-
-                        if self.buffer_lists.len() >= self.buffer_lists.capacity() {
-                            has_reallocated = true;
-                        }
-
-                        self.buffer_lists.push(channel.buffer.as_mut_ptr().cast())
-                    }
-                    false
-                }
-                AudioPortBufferType::F64(channels) => {
-                    for channel in channels {
-                        min_channel_buffer_length =
-                            min_channel_buffer_length.min(channel.buffer.len());
-                        if channel.is_constant {
-                            constant_mask |= 1 << i as u64
-                        }
-
-                        if self.buffer_lists.len() >= self.buffer_lists.capacity() {
-                            has_reallocated = true;
-                        }
-
-                        self.buffer_lists.push(channel.buffer.as_mut_ptr().cast())
-                    }
-                    true
-                }
-            };
-
-            let buffers = self.buffer_lists.get_mut(last..).unwrap_or(&mut []);
+        write_input_buffers(&mut self.buffer_configs, &mut self.buffer_lists, iter)
+    }
 
-            // PANIC: this can only panic with an invalid implementation of ExactSizeIterator
-            let descriptor = &mut self.buffer_configs[i];
-            descriptor.channel_count = buffers.len() as u32;
-            descriptor.latency = port.latency;
-            descriptor.constant_mask = constant_mask;
+    pub fn with_output_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
+        &'a mut self,
+        iter: I,
+    ) -> OutputAudioBuffers<'a>
+    where
+        I: IntoIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>, IntoIter = Iter>,
+        Iter: ExactSizeIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>>,
+        ChannelIter32: IntoIterator<Item = &'a mut [f32]>,
+        ChannelIter64: IntoIterator<Item = &'a mut [f64]>,
+    {
+        write_output_buffers(&mut self.buffer_configs, &mut self.buffer_lists, iter)
+    }
 
-            if is_f64 {
-                descriptor.data64 = buffers.as_ptr().cast();
-                descriptor.data32 = core::ptr::null();
-            } else {
-                descriptor.data64 = core::ptr::null();
-                descriptor.data32 = buffers.as_ptr() as *const *const _;
-            }
-        }
+    #[inline]
+    pub fn port_count(&self) -> usize {
+        self.buffer_configs.len()
+    }
+}
 
-        // If a realloc occurred, we must rewrite all the pointers.
-        // Thankfully, we know we wrote them sequentially, and we stored the lengths, so it's easy
-        // to find them back.
-        if has_reallocated {
-            let mut last_len = 0;
-            for descriptor in &mut self.buffer_configs[..total] {
-                let channel_count = descriptor.channel_count as usize;
-                let buffers = self
-                    .buffer_lists
-                    .get_mut(last_len..channel_count)
-                    .unwrap_or(&mut []);
-                last_len += channel_count;
+/// A set of buffer descriptors stored inline, which can assemble buffers for up to `PORTS` ports
+/// and `CHANNELS` channels in total without ever allocating.
+///
+/// This works just like [`AudioPorts`], except the descriptors are stored inside this type itself
+/// instead of on the heap. This allows it to live directly on the stack, or inside another
+/// structure of the audio thread.
+///
+/// If more ports or channels are ever needed, this falls back to heap allocation, much like a
+/// `SmallVec`. Once that happens (see [`is_spilled`](Self::is_spilled)), the allocated storage is
+/// kept and reused for all subsequent buffers.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::audio_buffers::*;
+///
+/// // Up to 2 ports, with up to 4 channels total.
+/// let mut ports = InlineAudioPorts::<2, 4>::new();
+/// let mut buffers = [[0f32; 64]; 2];
+///
+/// let outputs = ports.with_output_buffers([AudioPortBuffer {
+///     latency: 0,
+///     channels: AudioPortBufferType::f32_output_only(buffers.iter_mut().map(|b| b.as_mut_slice())),
+/// }]);
+///
+/// assert_eq!(outputs.port_count(), 1);
+/// assert_eq!(outputs.frames_count(), Some(64));
+/// assert!(!ports.is_spilled());
+/// ```
+pub struct InlineAudioPorts<const PORTS: usize, const CHANNELS: usize> {
+    buffer_lists: InlineChannelPointers<CHANNELS>,
+    buffer_configs: InlineDescriptors<PORTS>,
+}
 
-                if descriptor.data32.is_null() {
-                    descriptor.data64 = buffers.as_ptr().cast();
-                } else {
-                    descriptor.data32 = buffers.as_ptr().cast();
-                }
-            }
-        }
+// SAFETY: The pointers are only temporary storage, they are not used unless InlineAudioPorts is exclusively borrowed
+unsafe impl<const PORTS: usize, const CHANNELS: usize> Send for InlineAudioPorts<PORTS, CHANNELS> {}
+// SAFETY: The pointers are only temporary storage, they are not used unless InlineAudioPorts is exclusively borrowed
+unsafe impl<const PORTS: usize, const CHANNELS: usize> Sync for InlineAudioPorts<PORTS, CHANNELS> {}
 
-        InputAudioBuffers {
-            buffers: &self.buffer_configs[..total],
-            frames_count: if min_channel_buffer_length == usize::MAX {
-                None
-            } else {
-                Some(min_channel_buffer_length as u32)
+impl<const PORTS: usize, const CHANNELS: usize> InlineAudioPorts<PORTS, CHANNELS> {
+    /// Creates a new, empty set of inline buffer descriptors.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buffer_lists: InlineChannelPointers {
+                inline: [core::ptr::null_mut(); CHANNELS],
+                len: 0,
+                heap: Vec::new(),
+                spilled: false,
             },
+            buffer_configs: InlineDescriptors::Inline([EMPTY_DESCRIPTOR; PORTS]),
         }
     }
 
+    /// Returns `true` if this was given more ports or channels than it could store inline at some
+    /// point, and had to fall back to heap allocation.
+    #[inline]
+    pub fn is_spilled(&self) -> bool {
+        self.buffer_lists.spilled || matches!(self.buffer_configs, InlineDescriptors::Heap(_))
+    }
+
+    pub fn with_input_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
+        &'a mut self,
+        iter: I,
+    ) -> InputAudioBuffers<'a>
+    where
+        I: IntoIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>, IntoIter = Iter>,
+        Iter: ExactSizeIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>>,
+        ChannelIter32: IntoIterator<Item = InputChannel<'a, f32>>,
+        ChannelIter64: IntoIterator<Item = InputChannel<'a, f64>>,
+    {
+        write_input_buffers(&mut self.buffer_configs, &mut self.buffer_lists, iter)
+    }
+
     pub fn with_output_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
         &'a mut self,
         iter: I,
@@ -258,97 +250,332 @@ impl AudioPorts {
         ChannelIter32: IntoIterator<Item = &'a mut [f32]>,
         ChannelIter64: IntoIterator<Item = &'a mut [f64]>,
     {
-        let iter = iter.into_iter();
-        self.resize_buffer_configs(iter.len());
-        self.buffer_lists.clear();
+        write_output_buffers(&mut self.buffer_configs, &mut self.buffer_lists, iter)
+    }
+}
 
-        let mut min_channel_buffer_length = usize::MAX;
-        let mut total = 0;
-        let mut has_reallocated = false;
+impl<const PORTS: usize, const CHANNELS: usize> Default for InlineAudioPorts<PORTS, CHANNELS> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        for (i, port) in iter.enumerate() {
-            total = i + 1;
+const EMPTY_DESCRIPTOR: clap_audio_buffer = clap_audio_buffer {
+    data32: core::ptr::null_mut(),
+    data64: core::ptr::null_mut(),
+    channel_count: 0,
+    latency: 0,
+    constant_mask: 0,
+};
+
+/// Storage for the port descriptors.
+trait DescriptorStorage {
+    /// Returns at least `port_count` descriptors, growing the storage if needed.
+    fn prepare(&mut self, port_count: usize) -> &mut [clap_audio_buffer];
+}
 
-            let last = self.buffer_lists.len();
+impl DescriptorStorage for Vec<clap_audio_buffer> {
+    fn prepare(&mut self, port_count: usize) -> &mut [clap_audio_buffer] {
+        if port_count > self.len() {
+            self.resize(port_count, EMPTY_DESCRIPTOR);
+        }
 
-            let is_f64 = match port.channels {
-                AudioPortBufferType::F32(channels) => {
-                    for channel in channels {
-                        min_channel_buffer_length = min_channel_buffer_length.min(channel.len());
+        self
+    }
+}
 
-                        if self.buffer_lists.len() >= self.buffer_lists.capacity() {
-                            has_reallocated = true;
-                        }
+enum InlineDescriptors<const N: usize> {
+    Inline([clap_audio_buffer; N]),
+    Heap(Vec<clap_audio_buffer>),
+}
 
-                        self.buffer_lists.push(channel.as_mut_ptr().cast())
-                    }
-                    false
-                }
-                AudioPortBufferType::F64(channels) => {
-                    for channel in channels {
-                        min_channel_buffer_length = min_channel_buffer_length.min(channel.len());
+impl<const N: usize> DescriptorStorage for InlineDescriptors<N> {
+    fn prepare(&mut self, port_count: usize) -> &mut [clap_audio_buffer] {
+        if port_count > N {
+            if let Self::Inline(descriptors) = self {
+                *self = Self::Heap(descriptors.to_vec());
+            }
+        }
 
-                        if self.buffer_lists.len() >= self.buffer_lists.capacity() {
-                            has_reallocated = true;
-                        }
+        match self {
+            Self::Inline(descriptors) => descriptors,
+            Self::Heap(descriptors) => descriptors.prepare(port_count),
+        }
+    }
+}
 
-                        self.buffer_lists.push(channel.as_mut_ptr().cast())
-                    }
-                    true
-                }
-            };
+/// Storage for the channel buffer pointers the port descriptors point into.
+trait ChannelPointerStorage {
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    /// Pushes a new pointer. This returns `true` if the previously pushed pointers were moved.
+    fn push_pointer(&mut self, pointer: *mut f32) -> bool;
+    fn as_mut_slice(&mut self) -> &mut [*mut f32];
+}
 
-            let buffers = self.buffer_lists.get_mut(last..).unwrap_or(&mut []);
+impl ChannelPointerStorage for Vec<*mut f32> {
+    #[inline]
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
 
-            // PANIC: this can only panic with an invalid implementation of ExactSizeIterator
-            let descriptor = &mut self.buffer_configs[i];
-            descriptor.channel_count = buffers.len() as u32;
-            descriptor.latency = port.latency;
-            descriptor.constant_mask = 0;
+    #[inline]
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
 
-            if is_f64 {
-                descriptor.data64 = buffers.as_mut_ptr().cast();
-                descriptor.data32 = core::ptr::null();
-            } else {
-                descriptor.data64 = core::ptr::null();
-                descriptor.data32 = buffers.as_mut_ptr() as *const *const _;
-            }
+    #[inline]
+    fn push_pointer(&mut self, pointer: *mut f32) -> bool {
+        let reallocates = self.len() >= self.capacity();
+        self.push(pointer);
+        reallocates
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [*mut f32] {
+        self
+    }
+}
+
+struct InlineChannelPointers<const N: usize> {
+    inline: [*mut f32; N],
+    len: usize,
+    heap: Vec<*mut f32>,
+    spilled: bool,
+}
+
+impl<const N: usize> ChannelPointerStorage for InlineChannelPointers<N> {
+    #[inline]
+    fn clear(&mut self) {
+        self.len = 0;
+        self.heap.clear();
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        if self.spilled {
+            self.heap.len()
+        } else {
+            self.len
+        }
+    }
+
+    fn push_pointer(&mut self, pointer: *mut f32) -> bool {
+        if self.spilled {
+            return self.heap.push_pointer(pointer);
+        }
+
+        if let Some(slot) = self.inline.get_mut(self.len) {
+            *slot = pointer;
+            self.len += 1;
+            return false;
         }
 
-        // If a realloc occurred, we must rewrite all the pointers.
+        self.heap.reserve(N * 2 + 1);
+        self.heap.extend_from_slice(&self.inline[..self.len]);
+        self.heap.push(pointer);
+        self.spilled = true;
+
+        true
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [*mut f32] {
+        if self.spilled {
+            &mut self.heap
+        } else {
+            &mut self.inline[..self.len]
+        }
+    }
+}
+
+/// Writes port descriptors and their channel pointers into the given storage.
+struct DescriptorWriter<'s, P> {
+    descriptors: &'s mut [clap_audio_buffer],
+    pointers: &'s mut P,
+    port_count: usize,
+    port_start: usize,
+    constant_mask: u64,
+    min_channel_buffer_length: usize,
+    pointers_moved: bool,
+}
+
+impl<'s, P: ChannelPointerStorage> DescriptorWriter<'s, P> {
+    fn new(
+        descriptors: &'s mut impl DescriptorStorage,
+        pointers: &'s mut P,
+        port_count: usize,
+    ) -> Self {
+        pointers.clear();
+
+        Self {
+            descriptors: descriptors.prepare(port_count),
+            pointers,
+            port_count: 0,
+            port_start: 0,
+            constant_mask: 0,
+            min_channel_buffer_length: usize::MAX,
+            pointers_moved: false,
+        }
+    }
+
+    fn push_channel(&mut self, buffer: *mut f32, len: usize, is_constant: bool) {
+        let channel_index = self.pointers.len() - self.port_start;
+        if is_constant && channel_index < 64 {
+            self.constant_mask |= 1 << channel_index as u64;
+        }
+
+        self.min_channel_buffer_length = self.min_channel_buffer_length.min(len);
+        self.pointers_moved |= self.pointers.push_pointer(buffer);
+    }
+
+    fn finish_port(&mut self, latency: u32, is_f64: bool) {
+        let buffers = self
+            .pointers
+            .as_mut_slice()
+            .get_mut(self.port_start..)
+            .unwrap_or(&mut []);
+
+        // PANIC: this can only panic with an invalid implementation of ExactSizeIterator
+        let descriptor = &mut self.descriptors[self.port_count];
+        descriptor.channel_count = buffers.len() as u32;
+        descriptor.latency = latency;
+        descriptor.constant_mask = self.constant_mask;
+
+        if is_f64 {
+            descriptor.data64 = buffers.as_mut_ptr().cast();
+            descriptor.data32 = core::ptr::null();
+        } else {
+            descriptor.data64 = core::ptr::null();
+            descriptor.data32 = buffers.as_mut_ptr() as *const *const _;
+        }
+
+        self.port_count += 1;
+        self.port_start = self.pointers.len();
+        self.constant_mask = 0;
+    }
+
+    fn finish(self) -> (&'s mut [clap_audio_buffer], Option<u32>) {
+        let descriptors = &mut self.descriptors[..self.port_count];
+
+        // If the pointers were moved, we must rewrite all the pointers to them.
         // Thankfully, we know we wrote them sequentially, and we stored the lengths, so it's easy
         // to find them back.
-        if has_reallocated {
-            let mut last_len = 0;
-            for descriptor in &mut self.buffer_configs[..total] {
+        if self.pointers_moved {
+            let pointers = self.pointers.as_mut_slice();
+            let mut start = 0;
+
+            for descriptor in descriptors.iter_mut() {
                 let channel_count = descriptor.channel_count as usize;
-                let buffers = self
-                    .buffer_lists
-                    .get_mut(last_len..channel_count)
+                let buffers = pointers
+                    .get_mut(start..start + channel_count)
                     .unwrap_or(&mut []);
-                last_len += channel_count;
+                start += channel_count;
 
                 if descriptor.data32.is_null() {
-                    descriptor.data64 = buffers.as_ptr().cast();
+                    descriptor.data64 = buffers.as_mut_ptr().cast();
                 } else {
-                    descriptor.data32 = buffers.as_ptr().cast();
+                    descriptor.data32 = buffers.as_mut_ptr() as *const *const _;
                 }
             }
         }
 
-        OutputAudioBuffers {
-            buffers: &mut self.buffer_configs[..total],
-            frames_count: if min_channel_buffer_length == usize::MAX {
-                None
-            } else {
-                Some(min_channel_buffer_length as u32)
-            },
-        }
+        let frames_count = if self.min_channel_buffer_length == usize::MAX {
+            None
+        } else {
+            Some(self.min_channel_buffer_length as u32)
+        };
+
+        (descriptors, frames_count)
     }
+}
 
-    #[inline]
-    pub fn port_count(&self) -> usize {
-        self.buffer_configs.len()
+fn write_input_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
+    descriptors: &'a mut impl DescriptorStorage,
+    pointers: &'a mut impl ChannelPointerStorage,
+    iter: I,
+) -> InputAudioBuffers<'a>
+where
+    I: IntoIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>, IntoIter = Iter>,
+    Iter: ExactSizeIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>>,
+    ChannelIter32: IntoIterator<Item = InputChannel<'a, f32>>,
+    ChannelIter64: IntoIterator<Item = InputChannel<'a, f64>>,
+{
+    let iter = iter.into_iter();
+    let mut writer = DescriptorWriter::new(descriptors, pointers, iter.len());
+
+    for port in iter {
+        let is_f64 = match port.channels {
+            AudioPortBufferType::F32(channels) => {
+                for channel in channels {
+                    let len = channel.buffer.len();
+                    writer.push_channel(channel.buffer.as_mut_ptr(), len, channel.is_constant);
+                }
+                false
+            }
+            AudioPortBufferType::F64(channels) => {
+                for channel in channels {
+                    let len = channel.buffer.len();
+                    writer.push_channel(
+                        channel.buffer.as_mut_ptr().cast(),
+                        len,
+                        channel.is_constant,
+                    );
+                }
+                true
+            }
+        };
+
+        writer.finish_port(port.latency, is_f64);
+    }
+
+    let (buffers, frames_count) = writer.finish();
+
+    InputAudioBuffers {
+        buffers,
+        frames_count,
+    }
+}
+
+fn write_output_buffers<'a, I, Iter, ChannelIter32, ChannelIter64>(
+    descriptors: &'a mut impl DescriptorStorage,
+    pointers: &'a mut impl ChannelPointerStorage,
+    iter: I,
+) -> OutputAudioBuffers<'a>
+where
+    I: IntoIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>, IntoIter = Iter>,
+    Iter: ExactSizeIterator<Item = AudioPortBuffer<ChannelIter32, ChannelIter64>>,
+    ChannelIter32: IntoIterator<Item = &'a mut [f32]>,
+    ChannelIter64: IntoIterator<Item = &'a mut [f64]>,
+{
+    let iter = iter.into_iter();
+    let mut writer = DescriptorWriter::new(descriptors, pointers, iter.len());
+
+    for port in iter {
+        let is_f64 = match port.channels {
+            AudioPortBufferType::F32(channels) => {
+                for channel in channels {
+                    writer.push_channel(channel.as_mut_ptr(), channel.len(), false);
+                }
+                false
+            }
+            AudioPortBufferType::F64(channels) => {
+                for channel in channels {
+                    writer.push_channel(channel.as_mut_ptr().cast(), channel.len(), false);
+                }
+                true
+            }
+        };
+
+        writer.finish_port(port.latency, is_f64);
+    }
+
+    let (buffers, frames_count) = writer.finish();
+
+    OutputAudioBuffers {
+        buffers,
+        frames_count,
     }
 }
 
@@ -366,6 +593,18 @@ impl<'a> InputAudioBuffers<'a> {
         }
     }
 
+    /// Creates input buffers with no ports, but that still process the given number of frames.
+    ///
+    /// This is useful for plugins that don't have any audio input or output port (e.g. note
+    /// effects), but still need to process events in blocks of a given size.
+    #[inline]
+    pub const fn empty_with_frames(frames_count: u32) -> Self {
+        Self {
+            buffers: &[],
+            frames_count: Some(frames_count),
+        }
+    }
+
     /// Shortens the [`frames_count`] of these input buffers.
     ///
     /// This does not actually change the underlying buffers themselves, it only reduces the
@@ -477,6 +716,17 @@ impl<'a> OutputAudioBuffers<'a> {
         }
     }
 
+    /// Creates output buffers with no ports, but that still process the given number of frames.
+    ///
+    /// See [`InputAudioBuffers::empty_with_frames`].
+    #[inline]
+    pub fn empty_with_frames(frames_count: u32) -> Self {
+        Self {
+            buffers: &mut [],
+            frames_count: Some(frames_count),
+        }
+    }
+
     /// Shortens the [`frames_count`] of these output buffers.
     ///
     /// This does not actually change the underlying buffers themselves, it only reduces the
@@ -624,8 +874,121 @@ impl<'a> OutputAudioBuffers<'a> {
     pub fn port_infos(&self) -> impl Iterator<Item = AudioPortProcessingInfo> + '_ {
         self.buffers.iter().map(AudioPortProcessingInfo::from_raw)
     }
+
+    /// Returns the 32-bit channel buffers of the port at the given index, e.g. to read what the
+    /// plugin produced after a `process()` call.
+    ///
+    /// Each channel slice is [`frames_count`](Self::frames_count) samples long.
+    ///
+    /// This returns `None` if there is no port at the given index, or if it has no 32-bit
+    /// buffers.
+    #[inline]
+    pub fn channels_f32(&self, port_index: u32) -> Option<OutputChannels<'_, f32>> {
+        let buffer = self.buffers.get(port_index as usize)?;
+        // SAFETY: the validity of the buffers is guaranteed by this type
+        unsafe { OutputChannels::from_raw(buffer.data32, buffer.channel_count, self.frames_count) }
+    }
+
+    /// Returns the 64-bit channel buffers of the port at the given index, e.g. to read what the
+    /// plugin produced after a `process()` call.
+    ///
+    /// Each channel slice is [`frames_count`](Self::frames_count) samples long.
+    ///
+    /// This returns `None` if there is no port at the given index, or if it has no 64-bit
+    /// buffers.
+    #[inline]
+    pub fn channels_f64(&self, port_index: u32) -> Option<OutputChannels<'_, f64>> {
+        let buffer = self.buffers.get(port_index as usize)?;
+        // SAFETY: the validity of the buffers is guaranteed by this type
+        unsafe { OutputChannels::from_raw(buffer.data64, buffer.channel_count, self.frames_count) }
+    }
+}
+
+/// An iterator over the channel buffers of a port in [`OutputAudioBuffers`].
+///
+/// This is returned by [`OutputAudioBuffers::channels_f32`] and
+/// [`OutputAudioBuffers::channels_f64`].
+#[derive(Clone)]
+pub struct OutputChannels<'a, S> {
+    channels: &'a [*const S],
+    frames_count: usize,
 }
 
+impl<'a, S> OutputChannels<'a, S> {
+    /// # Safety
+    ///
+    /// If `channels` is non-null, it must point to `channel_count` channel pointers, which must
+    /// either be null or valid for reads of `frames_count` samples for 'a.
+    unsafe fn from_raw(
+        channels: *const *const S,
+        channel_count: u32,
+        frames_count: Option<u32>,
+    ) -> Option<Self> {
+        if channel_count == 0 {
+            return Some(Self {
+                channels: &[],
+                frames_count: 0,
+            });
+        }
+
+        if channels.is_null() {
+            return None;
+        }
+
+        Some(Self {
+            channels: core::slice::from_raw_parts(channels, channel_count as usize),
+            frames_count: frames_count.unwrap_or(0) as usize,
+        })
+    }
+
+    /// Returns the number of channels in the port.
+    #[inline]
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns the buffer of the channel at the given index, or `None` if it doesn't exist.
+    #[inline]
+    pub fn channel(&self, channel_index: usize) -> Option<&'a [S]> {
+        // SAFETY: the pointer's validity is guaranteed by from_raw's caller
+        self.channels
+            .get(channel_index)
+            .map(|ptr| unsafe { self.channel_from_raw(*ptr) })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be one of the channel pointers of this port.
+    #[inline]
+    unsafe fn channel_from_raw(&self, ptr: *const S) -> &'a [S] {
+        if ptr.is_null() {
+            &[]
+        } else {
+            core::slice::from_raw_parts(ptr, self.frames_count)
+        }
+    }
+}
+
+impl<'a, S> Iterator for OutputChannels<'a, S> {
+    type Item = &'a [S];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, rest) = self.channels.split_first()?;
+        self.channels = rest;
+
+        // SAFETY: the pointer's validity is guaranteed by from_raw's caller
+        Some(unsafe { self.channel_from_raw(*first) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.channels.len(), Some(self.channels.len()))
+    }
+}
+
+impl<S> ExactSizeIterator for OutputChannels<'_, S> {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -670,6 +1033,32 @@ mod test {
         assert_eq!(ports.port_count(), 1);
     }
 
+    #[test]
+    pub fn output_audio_buffers_expose_channels() {
+        let mut ports = AudioPorts::with_capacity(2, 1);
+        let mut bufs = [[1f32, 2.0, 3.0, 4.0], [5f32, 6.0, 7.0, 8.0]];
+
+        let mut buffers = ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only(
+                bufs.iter_mut().map(|b| b.as_mut_slice()),
+            ),
+        }]);
+        buffers.truncate(3);
+
+        let channels = buffers.channels_f32(0).unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels.channel(1), Some(&[5.0, 6.0, 7.0][..]));
+        assert_eq!(
+            channels.collect::<Vec<_>>(),
+            [&[1.0, 2.0, 3.0][..], &[5.0, 6.0, 7.0][..]]
+        );
+
+        assert!(buffers.channels_f64(0).is_none());
+        assert!(buffers.channels_f32(1).is_none());
+        assert!(OutputAudioBuffers::empty().channels_f32(0).is_none());
+    }
+
     #[test]
     pub fn input_audio_buffers_work_with_refcell() {
         let mut ports = AudioPorts::with_capacity(2, 1);
@@ -710,6 +1099,129 @@ mod test {
         assert_eq!(ports.port_count(), 1);
     }
 
+    #[test]
+    pub fn constant_mask_matches_channels() {
+        let mut ports = AudioPorts::with_capacity(3, 1);
+        let mut bufs = [[0f32; 4]; 3];
+
+        let buffers = ports.with_input_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_input_only(bufs.iter_mut().enumerate().map(
+                |(i, b)| InputChannel {
+                    buffer: b.as_mut_slice(),
+                    is_constant: i != 1,
+                },
+            )),
+        }]);
+
+        assert_eq!(buffers.buffers[0].constant_mask, 0b101);
+    }
+
+    #[test]
+    pub fn constant_mask_is_relative_to_each_port() {
+        let mut ports = AudioPorts::with_capacity(4, 2);
+        let mut bufs = [[[0f32; 4]; 2]; 2];
+
+        let buffers = ports.with_input_buffers(bufs.iter_mut().enumerate().map(|(port, bufs)| {
+            AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_input_only(bufs.iter_mut().enumerate().map(
+                    move |(channel, b)| InputChannel {
+                        buffer: b.as_mut_slice(),
+                        is_constant: port == 1 && channel == 0,
+                    },
+                )),
+            }
+        }));
+
+        assert_eq!(buffers.buffers[0].constant_mask, 0);
+        assert_eq!(buffers.buffers[1].constant_mask, 0b1);
+    }
+
+    #[test]
+    pub fn moved_pointers_are_rewritten_for_each_port() {
+        let mut ports = AudioPorts::with_capacity(1, 1);
+        let mut port_1 = [[1f32; 4]; 1];
+        let mut port_2 = [[2f32; 4]; 3];
+        let mut port_3 = [[3f32; 4]; 2];
+
+        let expected: Vec<Vec<*const f32>> = [&port_1[..], &port_2[..], &port_3[..]]
+            .iter()
+            .map(|bufs| bufs.iter().map(|b| b.as_ptr()).collect())
+            .collect();
+
+        let buffers = ports.with_output_buffers(
+            [&mut port_1[..], &mut port_2[..], &mut port_3[..]]
+                .into_iter()
+                .map(|bufs| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_output_only(
+                        bufs.iter_mut().map(|b| b.as_mut_slice()),
+                    ),
+                }),
+        );
+
+        assert_eq!(buffers.buffers.len(), 3);
+
+        for (descriptor, expected) in buffers.buffers.iter().zip(&expected) {
+            assert_eq!(descriptor.channel_count as usize, expected.len());
+
+            // SAFETY: the descriptor was just written, and points to channel_count pointers.
+            let pointers = unsafe {
+                core::slice::from_raw_parts(descriptor.data32, descriptor.channel_count as usize)
+            };
+
+            assert_eq!(pointers, expected.as_slice());
+        }
+    }
+
+    #[test]
+    pub fn inline_audio_buffers_work() {
+        let mut ports = InlineAudioPorts::<2, 4>::new();
+        let mut bufs = [[[0f32; 4]; 2]; 2];
+
+        let buffers = ports.with_output_buffers(bufs.iter_mut().map(|bufs| AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only(
+                bufs.iter_mut().map(|b| b.as_mut_slice()),
+            ),
+        }));
+
+        assert_eq!(buffers.buffers.len(), 2);
+        assert_eq!(buffers.frames_count, Some(4));
+        assert!(!ports.is_spilled());
+    }
+
+    #[test]
+    pub fn inline_audio_buffers_spill() {
+        let mut ports = InlineAudioPorts::<1, 1>::new();
+        let mut bufs = [[[42f32; 4]; 16], [[69f32; 4]; 16]];
+
+        let buffers = ports.with_input_buffers(bufs.iter_mut().map(|bufs| AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_input_only(bufs.iter_mut().map(|b| InputChannel {
+                buffer: b.as_mut_slice(),
+                is_constant: false,
+            })),
+        }));
+
+        assert_eq!(buffers.buffers.len(), 2);
+        assert_eq!(buffers.frames_count, Some(4));
+
+        for (descriptor, value) in buffers.buffers.iter().zip([42.0, 69.0]) {
+            assert_eq!(descriptor.channel_count, 16);
+
+            // SAFETY: the descriptors were just built from valid buffers.
+            let channels = unsafe { core::slice::from_raw_parts(descriptor.data32, 16) };
+            for channel in channels {
+                // SAFETY: same as above.
+                assert_eq!(unsafe { **channel }, value);
+            }
+        }
+
+        assert!(ports.is_spilled());
+    }
+
     #[test]
     pub fn audio_buffers_work_with_wrong_capacity() {
         let mut input_ports = AudioPorts::with_capacity(1, 1);
@@ -0,0 +1,336 @@
+//! An adapter that presents a constant block size to plugins, regardless of the size of the
+//! blocks the host processes.
+//!
+//! Some plugins perform best (or only work properly) when they are always given the same number of
+//! frames to process, e.g. because they perform FFT-based processing internally. However, most
+//! audio devices do not guarantee a constant callback size.
+//!
+//! The [`FixedBlockAdapter`] solves this by buffering the audio and events given to the plugin:
+//! input samples are accumulated until a full block is available, at which point the plugin
+//! processes it, and its output is played back during the next block. This adds exactly one
+//! [block of latency](FixedBlockAdapter::latency), which hosts should add to the latency reported
+//! by the plugin itself.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::process::block_adapter::FixedBlockAdapter;
+//!
+//! // A stereo effect that will always process 64 frames at a time.
+//! let mut adapter = FixedBlockAdapter::new(64, &[2], &[2]);
+//! assert_eq!(adapter.block_size(), 64);
+//! assert_eq!(adapter.latency(), 64);
+//! ```
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::audio_buffers::{
+    AudioPortBuffer, AudioPortBufferType, AudioPorts, InputChannel,
+};
+use crate::process::planar;
+use crate::process::{ProcessParams, ProcessStatus, StartedPluginAudioProcessor};
+use clack_common::events::io::{EventBuffer, InputEvents, OutputEvents};
+
+/// A buffering adapter that always gives the plugin blocks of the same size.
+///
+/// See the [module documentation](self) for more information.
+///
+/// The host's audio buffers are given to [`process`](Self::process) as one
+/// [planar buffer](super::planar) per port.
+///
+/// Events are re-timed to match the plugin's blocks: input events are given to the plugin in the
+/// block their samples end up in, and output events produced by the plugin are forwarded with the
+/// same added latency as the audio. Output events that would land after the end of the current
+/// host block are given the time of its last frame.
+///
+/// # Realtime Safety
+///
+/// See the [realtime safety of planar adapters](super::planar#realtime-safety).
+pub struct FixedBlockAdapter {
+    block_size: usize,
+    position: usize,
+
+    input_channel_counts: Vec<usize>,
+    output_channel_counts: Vec<usize>,
+    inputs: Vec<Vec<f32>>,
+    outputs: Vec<Vec<f32>>,
+    input_ports: AudioPorts,
+    output_ports: AudioPorts,
+
+    input_events: EventBuffer,
+    output_events: EventBuffer,
+    retimed_events: EventBuffer,
+
+    block_params: ProcessParams,
+    last_status: ProcessStatus,
+}
+
+impl FixedBlockAdapter {
+    /// Creates a new adapter, which will give the plugin blocks of `block_size` frames.
+    ///
+    /// The number of channels of each of the plugin's input and output ports are given by
+    /// `input_channel_counts` and `output_channel_counts` respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn new(
+        block_size: u32,
+        input_channel_counts: &[u32],
+        output_channel_counts: &[u32],
+    ) -> Self {
+        assert!(block_size > 0, "Block size must not be zero");
+        let block_size = block_size as usize;
+
+        let input_channel_counts: Vec<usize> =
+            input_channel_counts.iter().map(|c| *c as usize).collect();
+        let output_channel_counts: Vec<usize> =
+            output_channel_counts.iter().map(|c| *c as usize).collect();
+
+        let buffers = |counts: &[usize]| -> Vec<Vec<f32>> {
+            counts.iter().map(|c| vec![0.0; c * block_size]).collect()
+        };
+
+        Self {
+            block_size,
+            position: 0,
+            inputs: buffers(&input_channel_counts),
+            outputs: buffers(&output_channel_counts),
+            input_ports: AudioPorts::with_capacity(
+                input_channel_counts.iter().sum(),
+                input_channel_counts.len(),
+            ),
+            output_ports: AudioPorts::with_capacity(
+                output_channel_counts.iter().sum(),
+                output_channel_counts.len(),
+            ),
+            input_channel_counts,
+            output_channel_counts,
+            input_events: EventBuffer::with_capacity(256),
+            output_events: EventBuffer::with_capacity(256),
+            retimed_events: EventBuffer::with_capacity(256),
+            block_params: ProcessParams::new(),
+            last_status: ProcessStatus::Continue,
+        }
+    }
+
+    /// Returns the number of frames the plugin is given in each block.
+    #[inline]
+    pub fn block_size(&self) -> u32 {
+        self.block_size as u32
+    }
+
+    /// Returns the latency added by this adapter, in frames.
+    ///
+    /// This is always equal to the [block size](Self::block_size). Hosts should add it to the
+    /// latency reported by the plugin (e.g. through the `latency` extension) in order to properly
+    /// compensate for it.
+    #[inline]
+    pub fn latency(&self) -> u32 {
+        self.block_size as u32
+    }
+
+    /// Clears all buffered audio and events.
+    ///
+    /// This should be called whenever the plugin itself is reset, or when processing is
+    /// restarted after being stopped.
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.inputs.iter_mut().for_each(|b| b.fill(0.0));
+        self.outputs.iter_mut().for_each(|b| b.fill(0.0));
+        self.input_events.clear();
+        self.output_events.clear();
+        self.retimed_events.clear();
+        self.last_status = ProcessStatus::Continue;
+    }
+
+    /// Processes a block of audio frames and events of any size, through the given plugin audio
+    /// processor.
+    ///
+    /// Each of the given `audio_inputs` and `audio_outputs` buffers is the
+    /// [planar buffer](super::planar) of the matching port. The number of processed frames is
+    /// deduced from the size of those buffers.
+    ///
+    /// The steady time and transport information of `params` are the ones for the start of the
+    /// host's block, and the steady time counter is advanced by the number of processed frames
+    /// after this call, just like [`ProcessBatch::process`](crate::process::ProcessBatch::process).
+    /// Each block given to the plugin uses the transport information of the host block it
+    /// started in.
+    ///
+    /// The plugin may process any number of blocks during this call, including none if not enough
+    /// frames were buffered yet. The combined status of all the processed blocks is returned, or
+    /// the status of the last processed block if none were processed during this call.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if any of the plugin's `process` calls failed. In this case, the
+    /// remaining frames of this block are not processed, and the adapter should be
+    /// [reset](Self::reset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of given port buffers doesn't match the channel counts given to
+    /// [`new`](Self::new), or if the port buffers don't all hold the same number of frames.
+    pub fn process<H: HostHandlers>(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+        params: &mut ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let frames_count = planar::frames_count(
+            audio_inputs,
+            audio_outputs,
+            &self.input_channel_counts,
+            &self.output_channel_counts,
+        );
+        let mut event_index = 0;
+        let mut offset = 0;
+        let mut status = None;
+
+        while offset < frames_count {
+            if self.position == 0 {
+                self.block_params = ProcessParams {
+                    steady_time: params.steady_time.map(|t| t.wrapping_add(offset as u64)),
+                    transport: params.transport,
+                };
+            }
+
+            let chunk_len = (self.block_size - self.position).min(frames_count - offset);
+            let chunk_end = offset + chunk_len;
+            self.copy_chunk(audio_inputs, audio_outputs, frames_count, offset, chunk_len);
+
+            // Events past the end of the host's block are given in its last chunk.
+            while let Some(event) = input_events.get(event_index) {
+                let time = event.header().time() as usize;
+                if time >= chunk_end && chunk_end < frames_count {
+                    break;
+                }
+
+                let block_time =
+                    (self.position + time.saturating_sub(offset)).min(self.block_size - 1) as u32;
+                self.input_events.push_with_time(event, block_time);
+                event_index += 1;
+            }
+
+            self.position += chunk_len;
+            offset = chunk_end;
+
+            if self.position == self.block_size {
+                let block_status = self.process_block(processor)?;
+                status = Some(match status {
+                    None => block_status,
+                    Some(s) => block_status.combined_with(s),
+                });
+
+                self.forward_output_events(output_events, frames_count, offset);
+            }
+        }
+
+        params.advance(frames_count as u32);
+        Ok(status.unwrap_or(self.last_status))
+    }
+
+    /// Forwards the events produced by the plugin during the last block, which has been completed
+    /// at the given `offset` of the host's block.
+    fn forward_output_events(
+        &mut self,
+        output_events: &mut OutputEvents,
+        frames_count: usize,
+        offset: usize,
+    ) {
+        self.retimed_events.clear();
+        for event in &self.output_events {
+            let time =
+                (offset + event.header().time() as usize).min(frames_count.saturating_sub(1));
+            self.retimed_events.push_with_time(event, time as u32);
+        }
+
+        for event in &self.retimed_events {
+            // Events are dropped if the host's output buffer is full.
+            let _ = output_events.try_push(event);
+        }
+
+        self.output_events.clear();
+    }
+
+    fn copy_chunk(
+        &mut self,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        frames_count: usize,
+        offset: usize,
+        len: usize,
+    ) {
+        let block_size = self.block_size;
+        let position = self.position;
+
+        // Output first: those samples come from the previous block, and will be overwritten by the
+        // next plugin process call.
+        for ((host, buffered), channels) in audio_outputs
+            .iter_mut()
+            .zip(&self.outputs)
+            .zip(&self.output_channel_counts)
+        {
+            for channel in 0..*channels {
+                host[channel * frames_count + offset..][..len]
+                    .copy_from_slice(&buffered[channel * block_size + position..][..len]);
+            }
+        }
+
+        for ((host, buffered), channels) in audio_inputs
+            .iter()
+            .zip(&mut self.inputs)
+            .zip(&self.input_channel_counts)
+        {
+            for channel in 0..*channels {
+                buffered[channel * block_size + position..][..len]
+                    .copy_from_slice(&host[channel * frames_count + offset..][..len]);
+            }
+        }
+    }
+
+    fn process_block<H: HostHandlers>(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let block_size = self.block_size;
+        self.position = 0;
+
+        let inputs = self
+            .input_ports
+            .with_input_buffers(self.inputs.iter_mut().map(|port| {
+                AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_input_only(
+                        port.chunks_exact_mut(block_size)
+                            .map(InputChannel::variable),
+                    ),
+                }
+            }));
+
+        let mut outputs = self
+            .output_ports
+            .with_output_buffers(self.outputs.iter_mut().map(|port| AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_output_only(port.chunks_exact_mut(block_size)),
+            }));
+
+        let result = processor.process(
+            &inputs,
+            &mut outputs,
+            &self.input_events.as_input(),
+            &mut self.output_events.as_output(),
+            self.block_params.steady_time,
+            self.block_params.transport.as_ref(),
+        );
+
+        self.input_events.clear();
+
+        let status = result?;
+        self.last_status = status;
+        Ok(status)
+    }
+}
@@ -0,0 +1,526 @@
+//! A click-free, host-side bypass for audio nodes.
+//!
+//! Toggling a plugin's processing on and off abruptly produces clicks, as the output signal jumps
+//! from the processed signal to the dry signal. The [`SoftBypassNode`] wraps any [`AudioNode`]
+//! (typically a [`PluginNode`](super::graph::PluginNode)), and crossfades between these two
+//! signals over a given number of frames whenever the bypass is toggled.
+//!
+//! The dry signal is delayed by the [latency](AudioNode::latency) of the wrapped node, so that
+//! both signals stay aligned during the crossfade, and the latency reported to the rest of the
+//! graph doesn't change when bypassing.
+//!
+//! Alternatively, plugins that declare their own bypass parameter (using the `IS_BYPASS`
+//! parameter flag) can handle the bypass themselves. If this parameter is given to the node using
+//! [`with_bypass_param`](SoftBypassNode::with_bypass_param), toggling the bypass sends the
+//! corresponding parameter value events to the plugin instead, and its output is left untouched.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::events::io::{EventBuffer, OutputEvents};
+//! use clack_host::process::bypass::SoftBypassNode;
+//! use clack_host::process::graph::{AudioNode, GainNode};
+//! use clack_host::process::ProcessParams;
+//!
+//! // Crossfade over 4 frames.
+//! let mut node = SoftBypassNode::new(GainNode::new(1, 0.0), 4);
+//! node.set_bypassed(true);
+//!
+//! let input = [1.0; 4];
+//! let mut output = [0.0; 4];
+//!
+//! node.process(
+//!     4,
+//!     &ProcessParams::new(),
+//!     &[&input],
+//!     &mut [&mut output],
+//!     &EventBuffer::new().as_input(),
+//!     &mut OutputEvents::void(),
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(output, [0.25, 0.5, 0.75, 1.0]);
+//! assert!(node.is_fully_bypassed());
+//! ```
+
+use crate::process::graph::{AudioNode, AudioNodeError};
+use crate::process::{ProcessParams, ProcessStatus};
+use clack_common::events::event_types::ParamValueEvent;
+use clack_common::events::io::{EventBuffer, InputEvents, OutputEvents};
+use clack_common::events::Pckn;
+use clack_common::utils::{ClapId, Cookie};
+
+/// A delay line for a single dry channel, compensating for the latency of the wrapped node.
+#[derive(Clone, Debug, Default)]
+struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl DelayLine {
+    fn new(latency: u32) -> Self {
+        Self {
+            buffer: vec![0.0; latency as usize],
+            position: 0,
+        }
+    }
+
+    #[inline]
+    fn tick(&mut self, input: f32) -> f32 {
+        let Some(delayed) = self.buffer.get_mut(self.position) else {
+            return input;
+        };
+
+        let output = core::mem::replace(delayed, input);
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// An [`AudioNode`] wrapping another node, adding a click-free bypass to it.
+///
+/// The wrapped node is always processed, even while it is fully bypassed. This keeps its internal
+/// state (e.g. a reverb's tail) consistent with the rest of the graph, so that un-bypassing it
+/// doesn't produce any artifacts either.
+///
+/// The dry signal of each output channel is taken from the input channel at the same position
+/// in the same port. Output channels that have no matching input channel are faded to silence.
+///
+/// See the [module documentation](self) for more information.
+///
+/// # Realtime Safety
+///
+/// Processing is realtime-safe, except if the latency of the wrapped node changed since the last
+/// block, as the delay lines of the dry signal then have to be reallocated. Per the CLAP
+/// specification, a plugin's latency can only change while it is deactivated.
+pub struct SoftBypassNode<N> {
+    node: N,
+    crossfade_frames: u32,
+    /// How far into the crossfade the node is. See [`Crossfade`].
+    fade_position: u32,
+    is_bypassed: bool,
+    latency: u32,
+    delay_lines: Vec<DelayLine>,
+    bypass_param: Option<(ClapId, Cookie)>,
+    param_event_pending: bool,
+    events: EventBuffer,
+}
+
+impl<N: AudioNode> SoftBypassNode<N> {
+    /// The default number of input events per block the node pre-allocates room for, when using
+    /// the plugin's bypass parameter. See [`with_bypass_param`](Self::with_bypass_param).
+    pub const DEFAULT_MAX_INPUT_EVENTS: usize = 256;
+
+    /// Wraps the given node, crossfading between its processed and dry signals over
+    /// `crossfade_frames` frames.
+    ///
+    /// The node starts un-bypassed.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This pre-allocates a buffer for [`DEFAULT_MAX_INPUT_EVENTS`](Self::DEFAULT_MAX_INPUT_EVENTS)
+    /// input events, which is used if the node is later given the plugin's bypass parameter.
+    /// Processing with the bypass parameter stays realtime-safe as long as no block holds more
+    /// input events than this. Crossfading never uses this buffer.
+    pub fn new(node: N, crossfade_frames: u32) -> Self {
+        let latency = node.latency();
+
+        let mut bypass = Self {
+            node,
+            crossfade_frames,
+            fade_position: 0,
+            is_bypassed: false,
+            latency,
+            delay_lines: Vec::new(),
+            bypass_param: None,
+            param_event_pending: false,
+            events: EventBuffer::with_capacity(Self::DEFAULT_MAX_INPUT_EVENTS + 1),
+        };
+
+        bypass.reset_delay_lines();
+        bypass
+    }
+
+    /// Uses the plugin's own bypass parameter, with the given ID and cookie, instead of
+    /// crossfading.
+    ///
+    /// The parameter is set to `1.0` when bypassing, and to `0.0` otherwise. The plugin is
+    /// expected to handle the bypass without clicks itself.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This pre-allocates a buffer for `max_input_events` input events, in which the node's input
+    /// events are merged with the bypass parameter's events, replacing the one allocated by
+    /// [`new`](Self::new). Processing stays realtime-safe as long as no block holds more input
+    /// events than this.
+    pub fn with_bypass_param(
+        mut self,
+        param_id: ClapId,
+        cookie: Cookie,
+        max_input_events: usize,
+    ) -> Self {
+        self.bypass_param = Some((param_id, cookie));
+        self.events = EventBuffer::with_capacity(max_input_events + 1);
+        self
+    }
+
+    /// Returns the number of frames the crossfade lasts.
+    #[inline]
+    pub fn crossfade_frames(&self) -> u32 {
+        self.crossfade_frames
+    }
+
+    /// Returns `true` if the node is bypassed, or is fading towards being bypassed.
+    #[inline]
+    pub fn is_bypassed(&self) -> bool {
+        self.is_bypassed
+    }
+
+    /// Returns `true` if the node is bypassed, and the crossfade is complete.
+    ///
+    /// When using the plugin's bypass parameter, this is `true` as soon as the parameter event was
+    /// sent.
+    #[inline]
+    pub fn is_fully_bypassed(&self) -> bool {
+        if self.bypass_param.is_some() {
+            self.is_bypassed && !self.param_event_pending
+        } else {
+            self.is_bypassed && self.fade_position == self.crossfade_frames.max(1)
+        }
+    }
+
+    /// Bypasses or un-bypasses the node.
+    ///
+    /// The crossfade (or the bypass parameter change) starts on the next processed block. If the
+    /// bypass is toggled again in the middle of a crossfade, it fades back from its current
+    /// position.
+    pub fn set_bypassed(&mut self, bypassed: bool) {
+        if self.is_bypassed == bypassed {
+            return;
+        }
+
+        self.is_bypassed = bypassed;
+        self.param_event_pending = self.bypass_param.is_some();
+    }
+
+    /// Returns a reference to the wrapped node.
+    #[inline]
+    pub fn node(&self) -> &N {
+        &self.node
+    }
+
+    /// Returns a mutable reference to the wrapped node.
+    #[inline]
+    pub fn node_mut(&mut self) -> &mut N {
+        &mut self.node
+    }
+
+    /// Consumes this bypass, and returns the wrapped node.
+    #[inline]
+    pub fn into_inner(self) -> N {
+        self.node
+    }
+
+    fn reset_delay_lines(&mut self) {
+        let channels = self.node.output_channel_counts().iter().sum::<u32>() as usize;
+
+        self.delay_lines.clear();
+        self.delay_lines
+            .resize_with(channels, || DelayLine::new(self.latency));
+    }
+
+    fn process_with_bypass_param(
+        &mut self,
+        frames_count: u32,
+        params: &ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, AudioNodeError> {
+        let Some((param_id, cookie)) = self.bypass_param.filter(|_| self.param_event_pending)
+        else {
+            return self.node.process(
+                frames_count,
+                params,
+                audio_inputs,
+                audio_outputs,
+                input_events,
+                output_events,
+            );
+        };
+
+        let value = if self.is_bypassed { 1.0 } else { 0.0 };
+
+        self.events.clear();
+        self.events.push(&ParamValueEvent::new(
+            0,
+            param_id,
+            Pckn::match_all(),
+            value,
+            cookie,
+        ));
+        self.events.push_all(input_events);
+
+        self.param_event_pending = false;
+
+        self.node.process(
+            frames_count,
+            params,
+            audio_inputs,
+            audio_outputs,
+            &self.events.as_input(),
+            output_events,
+        )
+    }
+}
+
+impl<N: AudioNode> AudioNode for SoftBypassNode<N> {
+    #[inline]
+    fn input_channel_counts(&self) -> &[u32] {
+        self.node.input_channel_counts()
+    }
+
+    #[inline]
+    fn output_channel_counts(&self) -> &[u32] {
+        self.node.output_channel_counts()
+    }
+
+    #[inline]
+    fn latency(&self) -> u32 {
+        self.node.latency()
+    }
+
+    fn process(
+        &mut self,
+        frames_count: u32,
+        params: &ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, AudioNodeError> {
+        if self.bypass_param.is_some() {
+            return self.process_with_bypass_param(
+                frames_count,
+                params,
+                audio_inputs,
+                audio_outputs,
+                input_events,
+                output_events,
+            );
+        }
+
+        if self.node.latency() != self.latency {
+            self.latency = self.node.latency();
+            self.reset_delay_lines();
+        }
+
+        let status = self.node.process(
+            frames_count,
+            params,
+            audio_inputs,
+            audio_outputs,
+            input_events,
+            output_events,
+        )?;
+
+        let frames = frames_count as usize;
+        if frames == 0 {
+            return Ok(status);
+        }
+
+        let start_position = self.fade_position;
+        let mut end_position = start_position;
+        let mut delay_lines = self.delay_lines.iter_mut();
+
+        for (port_index, (output, channels)) in audio_outputs
+            .iter_mut()
+            .zip(self.node.output_channel_counts())
+            .enumerate()
+        {
+            let input = audio_inputs.get(port_index).copied().unwrap_or(&[]);
+
+            let Some(output) = output.get_mut(..*channels as usize * frames) else {
+                return Err(AudioNodeError::Node("Port buffer is too small"));
+            };
+
+            for (channel_index, output) in output.chunks_exact_mut(frames).enumerate() {
+                let Some(delay_line) = delay_lines.next() else {
+                    break;
+                };
+
+                let dry = input.chunks_exact(frames).nth(channel_index);
+                let mut fade = Crossfade::new(start_position, self.crossfade_frames);
+
+                for (frame, output) in output.iter_mut().enumerate() {
+                    let dry = delay_line.tick(dry.map_or(0.0, |dry| dry[frame]));
+                    let mix = fade.next(self.is_bypassed);
+
+                    *output = *output * (1.0 - mix) + dry * mix;
+                }
+
+                end_position = fade.position;
+            }
+        }
+
+        self.fade_position = end_position;
+
+        if self.fade_position == 0 {
+            Ok(status)
+        } else {
+            // The dry signal is still being output, regardless of what the node reports.
+            Ok(ProcessStatus::Continue)
+        }
+    }
+}
+
+/// The position of a crossfade, from `0` (fully processed) to `length` (fully bypassed).
+struct Crossfade {
+    position: u32,
+    length: u32,
+}
+
+impl Crossfade {
+    #[inline]
+    fn new(position: u32, crossfade_frames: u32) -> Self {
+        // A zero-length crossfade switches between both signals instantly.
+        Self {
+            position,
+            length: crossfade_frames.max(1),
+        }
+    }
+
+    /// Advances the crossfade by one frame, and returns the mix of the dry signal for that frame.
+    #[inline]
+    fn next(&mut self, is_bypassed: bool) -> f32 {
+        self.position = if is_bypassed {
+            (self.position + 1).min(self.length)
+        } else {
+            self.position.saturating_sub(1)
+        };
+
+        self.position as f32 / self.length as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A node passing its input through with a delay, and recording the bypass events it receives.
+    struct DelayNode {
+        channel_counts: [u32; 1],
+        delay: DelayLine,
+        received: Vec<f64>,
+    }
+
+    impl DelayNode {
+        fn new(latency: u32) -> Self {
+            Self {
+                channel_counts: [1],
+                delay: DelayLine::new(latency),
+                received: Vec::new(),
+            }
+        }
+    }
+
+    impl AudioNode for DelayNode {
+        fn input_channel_counts(&self) -> &[u32] {
+            &self.channel_counts
+        }
+
+        fn output_channel_counts(&self) -> &[u32] {
+            &self.channel_counts
+        }
+
+        fn latency(&self) -> u32 {
+            self.delay.buffer.len() as u32
+        }
+
+        fn process(
+            &mut self,
+            frames_count: u32,
+            _params: &ProcessParams,
+            audio_inputs: &[&[f32]],
+            audio_outputs: &mut [&mut [f32]],
+            input_events: &InputEvents,
+            _output_events: &mut OutputEvents,
+        ) -> Result<ProcessStatus, AudioNodeError> {
+            let frames = frames_count as usize;
+            for (output, input) in audio_outputs[0][..frames].iter_mut().zip(audio_inputs[0]) {
+                // Invert the signal, to tell it apart from the dry signal.
+                *output = -self.delay.tick(*input);
+            }
+
+            for event in input_events {
+                if let Some(event) = event.as_event::<ParamValueEvent>() {
+                    self.received.push(event.value());
+                }
+            }
+
+            Ok(ProcessStatus::ContinueIfNotQuiet)
+        }
+    }
+
+    fn process(node: &mut impl AudioNode, input: &[f32]) -> (Vec<f32>, ProcessStatus) {
+        let mut output = vec![0.0; input.len()];
+        let status = node
+            .process(
+                input.len() as u32,
+                &ProcessParams::new(),
+                &[input],
+                &mut [&mut output],
+                &EventBuffer::new().as_input(),
+                &mut OutputEvents::void(),
+            )
+            .unwrap();
+
+        (output, status)
+    }
+
+    #[test]
+    fn crossfade_compensates_latency() {
+        let mut node = SoftBypassNode::new(DelayNode::new(2), 2);
+        assert_eq!(node.latency(), 2);
+
+        let (output, status) = process(&mut node, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(output, [0.0, 0.0, -1.0, -2.0]);
+        assert_eq!(status, ProcessStatus::ContinueIfNotQuiet);
+
+        node.set_bypassed(true);
+        let (output, status) = process(&mut node, &[5.0, 6.0, 7.0, 8.0]);
+        // Both signals are aligned: the crossfade only changes their polarity.
+        assert_eq!(output, [0.0, 4.0, 5.0, 6.0]);
+        assert_eq!(status, ProcessStatus::Continue);
+        assert!(node.is_fully_bypassed());
+
+        node.set_bypassed(false);
+        let (output, _) = process(&mut node, &[9.0, 10.0, 11.0, 12.0]);
+        assert_eq!(output, [0.0, -8.0, -9.0, -10.0]);
+        assert!(!node.is_bypassed());
+    }
+
+    #[test]
+    fn uses_bypass_param() {
+        let mut node = SoftBypassNode::new(DelayNode::new(0), 2).with_bypass_param(
+            ClapId::new(1),
+            Cookie::empty(),
+            16,
+        );
+
+        node.set_bypassed(true);
+        assert!(!node.is_fully_bypassed());
+
+        // The plugin's output is left untouched.
+        let (output, _) = process(&mut node, &[1.0, 2.0]);
+        assert_eq!(output, [-1.0, -2.0]);
+        assert!(node.is_fully_bypassed());
+
+        process(&mut node, &[1.0, 2.0]);
+        node.set_bypassed(false);
+        process(&mut node, &[1.0, 2.0]);
+
+        assert_eq!(node.into_inner().received, [1.0, 0.0]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Driving plugins that have no audio ports, and only process events.
+//!
+//! Plugins such as note effects (arpeggiators, chorders, MIDI filters...) don't have any audio
+//! input or output port, but still need to be processed in blocks of frames, so that the events
+//! they receive and produce can be timed. The [`EventDriver`] helps hosts process those plugins
+//! at a fixed block rate, without having to provide any audio buffer.
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::{PreparedProcess, ProcessParams, ProcessStatus, StartedPluginAudioProcessor};
+use clack_common::events::io::{InputEvents, OutputEvents};
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+/// A helper processing event-only plugins in blocks of a fixed size.
+///
+/// Each call to [`process`](Self::process) processes a single block of
+/// [`block_size`](Self::block_size) frames with no audio ports, using the
+/// [`ProcessParams`] held by this driver. The steady time counter is enabled by default, and is
+/// advanced after every block.
+///
+/// The underlying `clap_process` struct is kept in the driver, and reused for every block.
+///
+/// Hosts can use [`block_duration`](Self::block_duration) to trigger a new block at the
+/// appropriate rate, e.g. from a timer thread when the plugin isn't part of an audio graph.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::event_driver::EventDriver;
+/// use std::time::Duration;
+///
+/// let driver = EventDriver::new(48_000.0, 480);
+///
+/// assert_eq!(driver.block_duration(), Duration::from_millis(10));
+/// assert_eq!(driver.blocks_in(Duration::from_secs(1)), 100);
+/// assert_eq!(driver.params().steady_time(), Some(0));
+/// ```
+#[derive(Copy, Clone)]
+pub struct EventDriver {
+    sample_rate: f64,
+    block_size: u32,
+    params: ProcessParams,
+    process: PreparedProcess,
+}
+
+impl EventDriver {
+    /// Creates a new driver, processing blocks of `block_size` frames at the given sample rate.
+    ///
+    /// The block size should be within the frame count bounds the plugin was activated with.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `block_size` is zero, or if `sample_rate` isn't strictly
+    /// positive.
+    pub fn new(sample_rate: f64, block_size: u32) -> Self {
+        assert!(block_size > 0, "Block size must not be zero");
+        assert!(sample_rate > 0.0, "Sample rate must be strictly positive");
+
+        Self {
+            sample_rate,
+            block_size,
+            params: ProcessParams::new().with_steady_time(0),
+            process: PreparedProcess::new(),
+        }
+    }
+
+    /// Returns the sample rate this driver processes blocks at.
+    #[inline]
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Returns the number of frames processed in each block.
+    #[inline]
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    /// Returns the real-time duration of a single block.
+    #[inline]
+    pub fn block_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.block_size as f64 / self.sample_rate)
+    }
+
+    /// Returns the number of whole blocks that fit in the given duration.
+    ///
+    /// This is useful to catch up with real time, e.g. after a timer fired late.
+    #[inline]
+    pub fn blocks_in(&self, duration: Duration) -> u64 {
+        let frames = duration.as_secs_f64() * self.sample_rate;
+        // Rounds away float imprecisions, e.g. when the duration comes from block_duration.
+        (frames / self.block_size as f64 + 1e-9) as u64
+    }
+
+    /// Returns the processing parameters given to the plugin on every block.
+    #[inline]
+    pub fn params(&self) -> &ProcessParams {
+        &self.params
+    }
+
+    /// Returns the processing parameters given to the plugin on every block, mutably.
+    ///
+    /// This can be used to update the transport information, or to disable the steady time
+    /// counter.
+    #[inline]
+    pub fn params_mut(&mut self) -> &mut ProcessParams {
+        &mut self.params
+    }
+
+    /// Processes a single block of events with the given plugin audio processor.
+    ///
+    /// # Errors
+    ///
+    /// This returns the same errors as [`StartedPluginAudioProcessor::process`].
+    pub fn process<H: HostHandlers>(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let session = processor.session()?;
+
+        // SAFETY: the borrowed processor ensures the instance and function pointer are valid, and
+        // there are no audio buffers the frames count could exceed.
+        unsafe {
+            self.process.process(
+                session.instance,
+                session.process_fn,
+                &mut self.params,
+                self.block_size,
+                &[],
+                &mut [],
+                input_events,
+                output_events,
+            )
+        }
+    }
+}
+
+impl Debug for EventDriver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventDriver")
+            .field("sample_rate", &self.sample_rate)
+            .field("block_size", &self.block_size)
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for EventDriver {
+    fn eq(&self, other: &Self) -> bool {
+        self.sample_rate == other.sample_rate
+            && self.block_size == other.block_size
+            && self.params == other.params
+    }
+}
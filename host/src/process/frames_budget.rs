@@ -0,0 +1,171 @@
+//! Helpers to choose the frame count bounds a plugin is activated with.
+//!
+//! When activating a plugin, hosts have to give it the minimum and maximum number of frames it will
+//! ever be given in a single `process` call (see [`PluginAudioConfiguration`]). Plugins rely on
+//! those bounds to pre-allocate their buffers, and may also choose their processing strategy
+//! depending on them.
+//!
+//! Choosing those bounds is a trade-off:
+//!
+//! * A range that is too wide wastes memory, as plugins have to allocate for the maximum frame
+//!   count, even if it never actually happens.
+//! * A range that is too narrow is a promise hosts have to keep: giving the plugin a block outside
+//!   of its activation range is undefined behavior per the CLAP specification. In particular, a
+//!   minimum frame count larger than `1` forbids the host from splitting blocks (e.g. at parameter
+//!   changes for sample-accurate automation) or from processing partial blocks.
+//!
+//! The [`FramesCountBudget`] derives a recommended range from the constraints of the audio device
+//! and the latency requirements of the host.
+
+use crate::prelude::PluginAudioConfiguration;
+use std::ops::RangeInclusive;
+
+/// The maximum frames count that is assumed when the device doesn't report any constraints.
+const DEFAULT_MAX_FRAMES_COUNT: u32 = 4096;
+
+/// A helper to compute the recommended frame count bounds to activate a plugin with.
+///
+/// See the [module documentation](self) for more information.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::frames_budget::FramesCountBudget;
+///
+/// // A device that supports buffers of 16 to 8192 frames, but will most likely use 512.
+/// let budget = FramesCountBudget::new(16, 8192).with_likely_frames_count(512);
+/// assert_eq!(budget.frames_count_range(), 1..=512);
+///
+/// // We want at most 5ms of total latency at 48kHz, but the plugin has 64 frames of latency.
+/// let budget = budget.with_latency_budget(240, 64);
+/// assert_eq!(budget.frames_count_range(), 1..=176);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FramesCountBudget {
+    device_min: u32,
+    device_max: u32,
+    likely: Option<u32>,
+    latency_budget: Option<u32>,
+    splits_blocks: bool,
+}
+
+impl FramesCountBudget {
+    /// Creates a budget for a device that supports buffers of `device_min` to `device_max`
+    /// frames, inclusive.
+    ///
+    /// Invalid bounds are corrected: both are at least `1`, and the maximum is never lower than
+    /// the minimum.
+    #[inline]
+    pub fn new(device_min: u32, device_max: u32) -> Self {
+        let device_min = device_min.max(1);
+
+        Self {
+            device_min,
+            device_max: device_max.max(device_min),
+            likely: None,
+            latency_budget: None,
+            splits_blocks: true,
+        }
+    }
+
+    /// Creates a budget for a device that doesn't report its buffer size constraints.
+    ///
+    /// This assumes the device can use buffers of any size up to 4096 frames.
+    #[inline]
+    pub fn unknown() -> Self {
+        Self::new(1, DEFAULT_MAX_FRAMES_COUNT)
+    }
+
+    /// Sets the buffer size the device is most likely to use, in frames.
+    ///
+    /// Devices often support very large buffers they will never actually use, which would make
+    /// plugins allocate for nothing. If this is set, the maximum frames count is capped to this
+    /// value, which then becomes a hard limit: larger device buffers must be split by the host.
+    #[inline]
+    pub fn with_likely_frames_count(mut self, frames_count: u32) -> Self {
+        self.likely = Some(frames_count);
+        self
+    }
+
+    /// Caps the maximum frames count so that the total latency stays within `budget` frames,
+    /// given the plugin's own `plugin_latency` (e.g. as reported by the `latency` extension).
+    ///
+    /// Because the device buffer size can never be lower than the device's minimum, the budget
+    /// may not always be met, in which case the device's minimum is used.
+    #[inline]
+    pub fn with_latency_budget(mut self, budget: u32, plugin_latency: u32) -> Self {
+        self.latency_budget = Some(budget.saturating_sub(plugin_latency));
+        self
+    }
+
+    /// Sets whether the host may split blocks into smaller ones, or process partial blocks.
+    ///
+    /// This is `true` by default, which makes the minimum frames count always `1`. Hosts that
+    /// guarantee they always process full device buffers can set this to `false`, in which case
+    /// the device's minimum buffer size is used instead.
+    #[inline]
+    pub fn with_split_blocks(mut self, splits_blocks: bool) -> Self {
+        self.splits_blocks = splits_blocks;
+        self
+    }
+
+    /// Returns the recommended range of frames counts to activate the plugin with.
+    pub fn frames_count_range(&self) -> RangeInclusive<u32> {
+        let mut max = self
+            .likely
+            .unwrap_or(self.device_max)
+            .clamp(self.device_min, self.device_max);
+
+        if let Some(budget) = self.latency_budget {
+            max = max.min(budget).max(self.device_min);
+        }
+
+        let min = if self.splits_blocks {
+            1
+        } else {
+            self.device_min.min(max)
+        };
+
+        min..=max
+    }
+
+    /// Returns the plugin audio configuration matching this budget, for the given sample rate.
+    #[inline]
+    pub fn to_configuration(&self, sample_rate: f64) -> PluginAudioConfiguration {
+        let range = self.frames_count_range();
+        PluginAudioConfiguration::new(sample_rate, *range.start(), *range.end())
+    }
+}
+
+impl Default for FramesCountBudget {
+    #[inline]
+    fn default() -> Self {
+        Self::unknown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_within_device_bounds() {
+        let budget = FramesCountBudget::new(64, 256).with_split_blocks(false);
+        assert_eq!(budget.frames_count_range(), 64..=256);
+
+        let budget = budget.with_likely_frames_count(4096);
+        assert_eq!(budget.frames_count_range(), 64..=256);
+
+        // The device can't go below its minimum, whatever the latency budget.
+        let budget = budget.with_latency_budget(32, 0);
+        assert_eq!(budget.frames_count_range(), 64..=64);
+    }
+
+    #[test]
+    fn corrects_invalid_bounds() {
+        assert_eq!(FramesCountBudget::new(0, 0).frames_count_range(), 1..=1);
+        assert!(FramesCountBudget::unknown()
+            .to_configuration(44_100.0)
+            .is_valid());
+    }
+}
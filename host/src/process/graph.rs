@@ -0,0 +1,399 @@
+//! Building blocks for hosts that process plugins as part of an audio graph.
+//!
+//! Hosts rarely process plugins on their own: their audio usually also goes through built-in
+//! processing, such as gain stages, meters, or routing. The [`AudioNode`] trait abstracts over
+//! anything that processes a block of audio and events, so that hosts can store and process plugin
+//! nodes and built-in nodes the same way, e.g. as a list of `Box<dyn AudioNode>`.
+//!
+//! Plugins are turned into nodes by wrapping their started audio processor in a [`PluginNode`].
+//! This module also provides a simple [`GainNode`], which can serve as an example for custom nodes.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::events::io::{EventBuffer, OutputEvents};
+//! use clack_host::process::graph::{AudioNode, GainNode};
+//! use clack_host::process::ProcessParams;
+//!
+//! let mut nodes: Vec<Box<dyn AudioNode>> = vec![Box::new(GainNode::new(2, 0.5))];
+//!
+//! // A stereo block of 4 frames: the left channel, then the right channel.
+//! let input = [1.0; 8];
+//! let mut output = [0.0; 8];
+//!
+//! for node in &mut nodes {
+//!     node.process(
+//!         4,
+//!         &ProcessParams::new(),
+//!         &[&input],
+//!         &mut [&mut output],
+//!         &EventBuffer::new().as_input(),
+//!         &mut OutputEvents::void(),
+//!     )
+//!     .unwrap();
+//! }
+//!
+//! assert_eq!(output, [0.5; 8]);
+//! ```
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::audio_buffers::{
+    AudioPortBuffer, AudioPortBufferType, AudioPorts, InputChannel,
+};
+use crate::process::{ProcessParams, ProcessStatus, StartedPluginAudioProcessor};
+use clack_common::events::io::{InputEvents, OutputEvents};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors that can occur while processing an [`AudioNode`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AudioNodeError {
+    /// The plugin of a [`PluginNode`] failed to process the block.
+    Plugin(PluginInstanceError),
+    /// A built-in node failed to process the block, for the given reason.
+    Node(&'static str),
+}
+
+impl Display for AudioNodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioNodeError::Plugin(e) => Display::fmt(e, f),
+            AudioNodeError::Node(reason) => write!(f, "Audio node failed to process: {reason}"),
+        }
+    }
+}
+
+impl Error for AudioNodeError {}
+
+impl From<PluginInstanceError> for AudioNodeError {
+    #[inline]
+    fn from(e: PluginInstanceError) -> Self {
+        Self::Plugin(e)
+    }
+}
+
+/// A node of an audio graph, which processes blocks of audio and events.
+///
+/// Audio buffers are given as one planar buffer per port, in which the samples of all channels of
+/// that port are stored one channel after the other. Each buffer therefore holds exactly
+/// `frames_count` samples per channel of its port, as declared by
+/// [`input_channel_counts`](Self::input_channel_counts) and
+/// [`output_channel_counts`](Self::output_channel_counts).
+///
+/// Unlike the plugin's own `process` call, nodes don't advance the steady time of the given
+/// [`ProcessParams`]: all the nodes of a graph process the same block, and the host advances it
+/// once the whole graph was processed.
+pub trait AudioNode {
+    /// Returns the number of channels of each of this node's input ports.
+    fn input_channel_counts(&self) -> &[u32];
+
+    /// Returns the number of channels of each of this node's output ports.
+    fn output_channel_counts(&self) -> &[u32];
+
+    /// Returns the latency this node adds to its audio, in frames.
+    ///
+    /// Hosts should use it to compensate for the delay between the different paths of the graph.
+    /// This is `0` by default.
+    #[inline]
+    fn latency(&self) -> u32 {
+        0
+    }
+
+    /// Processes a block of `frames_count` frames.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the node failed to process the block. In that case, the contents
+    /// of the output buffers are unspecified.
+    fn process(
+        &mut self,
+        frames_count: u32,
+        params: &ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, AudioNodeError>;
+}
+
+/// An [`AudioNode`] that processes a plugin.
+///
+/// This wraps a plugin's [`StartedPluginAudioProcessor`], alongside the channel counts of each of
+/// its audio ports. The wrapped processor can be retrieved using
+/// [`into_processor`](Self::into_processor), e.g. to stop processing and deactivate the plugin.
+///
+/// # Realtime Safety
+///
+/// Input samples are copied into buffers allocated when the node is created, as the plugin is
+/// allowed to write into its input buffers. Processing is realtime-safe, as long as blocks don't
+/// exceed the maximum frames count given to [`new`](Self::new).
+pub struct PluginNode<H: HostHandlers> {
+    processor: StartedPluginAudioProcessor<H>,
+    input_channel_counts: Vec<u32>,
+    output_channel_counts: Vec<u32>,
+    inputs: Vec<Vec<f32>>,
+    input_ports: AudioPorts,
+    output_ports: AudioPorts,
+    max_frames_count: u32,
+    latency: u32,
+}
+
+impl<H: HostHandlers> PluginNode<H> {
+    /// Creates a new node for the given plugin audio processor.
+    ///
+    /// The number of channels of each of the plugin's input and output ports are given by
+    /// `input_channel_counts` and `output_channel_counts` respectively, and `max_frames_count`
+    /// should match the maximum frames count the plugin was activated with.
+    pub fn new(
+        processor: StartedPluginAudioProcessor<H>,
+        input_channel_counts: &[u32],
+        output_channel_counts: &[u32],
+        max_frames_count: u32,
+    ) -> Self {
+        let total_channels = |counts: &[u32]| counts.iter().map(|c| *c as usize).sum();
+
+        Self {
+            processor,
+            inputs: input_channel_counts
+                .iter()
+                .map(|c| vec![0.0; *c as usize * max_frames_count as usize])
+                .collect(),
+            input_ports: AudioPorts::with_capacity(
+                total_channels(input_channel_counts),
+                input_channel_counts.len(),
+            ),
+            output_ports: AudioPorts::with_capacity(
+                total_channels(output_channel_counts),
+                output_channel_counts.len(),
+            ),
+            input_channel_counts: input_channel_counts.to_vec(),
+            output_channel_counts: output_channel_counts.to_vec(),
+            max_frames_count,
+            latency: 0,
+        }
+    }
+
+    /// Sets the latency reported by this node, in frames.
+    ///
+    /// Hosts should keep it up to date with the latency reported by the plugin's `latency`
+    /// extension.
+    #[inline]
+    pub fn set_latency(&mut self, latency: u32) {
+        self.latency = latency;
+    }
+
+    /// Returns a reference to the wrapped plugin audio processor.
+    #[inline]
+    pub fn processor(&self) -> &StartedPluginAudioProcessor<H> {
+        &self.processor
+    }
+
+    /// Returns a mutable reference to the wrapped plugin audio processor.
+    #[inline]
+    pub fn processor_mut(&mut self) -> &mut StartedPluginAudioProcessor<H> {
+        &mut self.processor
+    }
+
+    /// Consumes this node, and returns the wrapped plugin audio processor.
+    #[inline]
+    pub fn into_processor(self) -> StartedPluginAudioProcessor<H> {
+        self.processor
+    }
+}
+
+impl<H: HostHandlers> AudioNode for PluginNode<H> {
+    #[inline]
+    fn input_channel_counts(&self) -> &[u32] {
+        &self.input_channel_counts
+    }
+
+    #[inline]
+    fn output_channel_counts(&self) -> &[u32] {
+        &self.output_channel_counts
+    }
+
+    #[inline]
+    fn latency(&self) -> u32 {
+        self.latency
+    }
+
+    /// Processes a block of `frames_count` frames through the wrapped plugin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given port buffers don't match the node's channel counts, or if
+    /// `frames_count` exceeds the maximum frames count given to [`PluginNode::new`].
+    fn process(
+        &mut self,
+        frames_count: u32,
+        params: &ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, AudioNodeError> {
+        assert!(frames_count <= self.max_frames_count, "Block is too large");
+        assert_eq!(audio_inputs.len(), self.input_channel_counts.len());
+        assert_eq!(audio_outputs.len(), self.output_channel_counts.len());
+
+        let frames = frames_count as usize;
+        if frames == 0 {
+            return Ok(ProcessStatus::Continue);
+        }
+
+        for ((buffer, host), channels) in self
+            .inputs
+            .iter_mut()
+            .zip(audio_inputs)
+            .zip(&self.input_channel_counts)
+        {
+            let len = *channels as usize * frames;
+            buffer[..len].copy_from_slice(&host[..len]);
+        }
+
+        let inputs = self.input_ports.with_input_buffers(
+            self.inputs
+                .iter_mut()
+                .zip(&self.input_channel_counts)
+                .map(|(port, channels)| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_input_only(
+                        port[..*channels as usize * frames]
+                            .chunks_exact_mut(frames)
+                            .map(InputChannel::variable),
+                    ),
+                }),
+        );
+
+        let mut outputs = self.output_ports.with_output_buffers(
+            audio_outputs
+                .iter_mut()
+                .zip(&self.output_channel_counts)
+                .map(|(port, channels)| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_output_only(
+                        port[..*channels as usize * frames].chunks_exact_mut(frames),
+                    ),
+                }),
+        );
+
+        Ok(self.processor.process(
+            &inputs,
+            &mut outputs,
+            input_events,
+            output_events,
+            params.steady_time,
+            params.transport.as_ref(),
+        )?)
+    }
+}
+
+/// A built-in [`AudioNode`] that applies a constant gain to its audio.
+///
+/// It has a single input port and a single output port, with the same number of channels. Events
+/// are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GainNode {
+    channel_counts: [u32; 1],
+    gain: f32,
+}
+
+impl GainNode {
+    /// Creates a new gain node, for ports of `channel_count` channels.
+    #[inline]
+    pub fn new(channel_count: u32, gain: f32) -> Self {
+        Self {
+            channel_counts: [channel_count],
+            gain,
+        }
+    }
+
+    /// Returns the linear gain applied by this node.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Sets the linear gain applied by this node.
+    #[inline]
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+}
+
+impl AudioNode for GainNode {
+    #[inline]
+    fn input_channel_counts(&self) -> &[u32] {
+        &self.channel_counts
+    }
+
+    #[inline]
+    fn output_channel_counts(&self) -> &[u32] {
+        &self.channel_counts
+    }
+
+    fn process(
+        &mut self,
+        frames_count: u32,
+        _params: &ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        _input_events: &InputEvents,
+        _output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, AudioNodeError> {
+        let ([input], [output]) = (audio_inputs, audio_outputs) else {
+            return Err(AudioNodeError::Node("Gain nodes have exactly one port"));
+        };
+
+        let len = self.channel_counts[0] as usize * frames_count as usize;
+        let (Some(input), Some(output)) = (input.get(..len), output.get_mut(..len)) else {
+            return Err(AudioNodeError::Node("Port buffer is too small"));
+        };
+
+        for (output, input) in output.iter_mut().zip(input) {
+            *output = input * self.gain;
+        }
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clack_common::events::io::EventBuffer;
+
+    #[test]
+    fn gain_node_checks_buffers() {
+        let mut node = GainNode::new(2, 2.0);
+        let input = [1.0, 2.0, 3.0, 4.0];
+        let mut output = [0.0; 4];
+
+        let mut process = |inputs: &[&[f32]], outputs: &mut [&mut [f32]]| {
+            node.process(
+                2,
+                &ProcessParams::new(),
+                inputs,
+                outputs,
+                &EventBuffer::new().as_input(),
+                &mut OutputEvents::void(),
+            )
+        };
+
+        assert_eq!(
+            process(&[&input], &mut [&mut output]),
+            Ok(ProcessStatus::ContinueIfNotQuiet)
+        );
+        assert_eq!(output, [2.0, 4.0, 6.0, 8.0]);
+
+        assert!(matches!(
+            process(&[&input[..3]], &mut [&mut output]),
+            Err(AudioNodeError::Node(_))
+        ));
+        assert!(matches!(
+            process(&[], &mut [&mut output]),
+            Err(AudioNodeError::Node(_))
+        ));
+    }
+}
@@ -0,0 +1,462 @@
+//! Conversion of the events produced by a plugin into raw MIDI messages.
+//!
+//! Plugins that output notes (e.g. arpeggiators, sequencers or MIDI effects) may produce CLAP note
+//! events, MIDI 1.0 events, MIDI 2.0 events, or a mix of all of them. Hardware devices (e.g. through
+//! `midir`) or non-CLAP plugins however only understand raw MIDI 1.0 byte streams.
+//!
+//! The [`MidiOutputConverter`] translates all of the MIDI-compatible events produced by a plugin
+//! into timestamped [`MidiPacket`]s of raw MIDI 1.0 bytes. This is the counterpart of the
+//! MIDI-to-CLAP translation hosts perform when feeding MIDI input to plugins.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::events::event_types::{NoteOffEvent, NoteOnEvent};
+//! use clack_host::events::io::EventBuffer;
+//! use clack_host::events::Pckn;
+//! use clack_host::process::midi_out::MidiOutputConverter;
+//!
+//! // The events the plugin produced during its last process call.
+//! let mut output_events = EventBuffer::new();
+//! output_events.push(&NoteOnEvent::new(0, Pckn::new(0u16, 1u16, 60u16, 0u32), 1.0));
+//! output_events.push(&NoteOffEvent::new(128, Pckn::new(0u16, 1u16, 60u16, 0u32), 0.0));
+//!
+//! let mut converter = MidiOutputConverter::new();
+//! converter.push_block(&output_events);
+//!
+//! let packets: Vec<_> = converter.packets().map(|p| (p.time(), p.data().to_vec())).collect();
+//! assert_eq!(packets, [(0, vec![0x91, 60, 127]), (128, vec![0x81, 60, 0])]);
+//! ```
+
+use crate::events::event_types::{MidiEvent, MidiSysExEvent, NoteChokeEvent, NoteExpressionType};
+use crate::events::spaces::CoreEventSpace;
+use crate::events::{Event, Match, Pckn, UnknownEvent};
+use std::ops::Range;
+
+/// The MIDI 1.0 "All Sound Off" control change number.
+const ALL_SOUND_OFF: u8 = 120;
+
+/// A raw MIDI 1.0 message, produced by a [`MidiOutputConverter`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MidiPacket<'a> {
+    time: u32,
+    port_index: u16,
+    data: &'a [u8],
+}
+
+impl<'a> MidiPacket<'a> {
+    /// The time of this message, in frames since the start of the block it was produced in.
+    #[inline]
+    pub const fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// The index of the plugin's note port this message was produced on.
+    #[inline]
+    pub const fn port_index(&self) -> u16 {
+        self.port_index
+    }
+
+    /// The raw bytes of this MIDI 1.0 message.
+    ///
+    /// This is a complete message, including its status byte. SysEx messages include both their
+    /// start (`0xF0`) and end (`0xF7`) bytes.
+    #[inline]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the time of this message in microseconds since the start of its block, given the
+    /// sample rate the plugin is processing at.
+    ///
+    /// This is useful to schedule the message on MIDI APIs that use wall-clock timestamps.
+    #[inline]
+    pub fn time_in_micros(&self, sample_rate: f64) -> u64 {
+        (self.time as f64 * 1_000_000.0 / sample_rate).round() as u64
+    }
+
+    /// Returns this message as a [`MidiEvent`], e.g. to send it to another plugin.
+    ///
+    /// This returns [`None`] for SysEx messages, which do not fit into a [`MidiEvent`].
+    pub fn to_midi_event(&self) -> Option<MidiEvent> {
+        if self.data.len() > 3 {
+            return None;
+        }
+
+        let mut data = [0; 3];
+        data[..self.data.len()].copy_from_slice(self.data);
+        Some(MidiEvent::new(self.time, self.port_index, data))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PacketRecord {
+    time: u32,
+    port_index: u16,
+    data: Range<usize>,
+}
+
+/// Converts the events produced by a plugin into a stream of raw MIDI 1.0 messages.
+///
+/// See the [module documentation](self) for more information.
+///
+/// The following events are converted:
+///
+/// * [`MidiEvent`]s are forwarded as-is, stripped from their unused trailing bytes.
+/// * [`MidiSysExEvent`]s are forwarded as-is, but must be given to
+///   [`push_sysex`](Self::push_sysex), as their payload is stored outside of the event.
+/// * [`Midi2Event`](crate::events::event_types::Midi2Event)s containing MIDI 1.0 channel voice or
+///   system messages are unwrapped, and MIDI 2.0 channel voice messages are downgraded to their
+///   MIDI 1.0 equivalent, with reduced resolution. Other MIDI 2.0 messages are ignored.
+/// * Note On and Note Off events are converted to MIDI Note On and Note Off messages.
+/// * Note Choke events are converted to Note Off messages with a velocity of `0`, or to "All
+///   Sound Off" control changes if they target a whole channel.
+/// * Pressure note expressions are converted to Polyphonic Key Pressure messages.
+///
+/// Note events that do not target a specific note port are ignored, as well as all other events.
+/// Packets are produced in the order the events were given.
+///
+/// # Realtime Safety
+///
+/// Conversion is realtime-safe, as long as the converted messages fit into the capacity the
+/// converter was created with. Otherwise, its internal buffers are grown to fit them.
+#[derive(Clone, Debug)]
+pub struct MidiOutputConverter {
+    packets: Vec<PacketRecord>,
+    data: Vec<u8>,
+    port_filter: Option<u16>,
+}
+
+impl MidiOutputConverter {
+    /// Creates a new converter, with capacity for a few hundred messages.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(256)
+    }
+
+    /// Creates a new converter, with capacity for `packets_capacity` messages of 3 bytes.
+    pub fn with_capacity(packets_capacity: usize) -> Self {
+        Self {
+            packets: Vec::with_capacity(packets_capacity),
+            data: Vec::with_capacity(packets_capacity * 3),
+            port_filter: None,
+        }
+    }
+
+    /// Only converts the events produced on the given note port, ignoring all others.
+    ///
+    /// By default, events from all note ports are converted.
+    #[inline]
+    pub fn with_port_filter(mut self, port_index: u16) -> Self {
+        self.port_filter = Some(port_index);
+        self
+    }
+
+    /// Returns the number of messages that were converted since the last call to
+    /// [`clear`](Self::clear).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Returns `true` if no messages were converted since the last call to [`clear`](Self::clear).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    /// Removes all the converted messages.
+    ///
+    /// This should be called after the messages of a block have been sent, before converting the
+    /// events of the next one.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.packets.clear();
+        self.data.clear();
+    }
+
+    /// Returns all the messages that were converted since the last call to
+    /// [`clear`](Self::clear).
+    pub fn packets(&self) -> impl Iterator<Item = MidiPacket<'_>> {
+        self.packets.iter().map(|p| MidiPacket {
+            time: p.time,
+            port_index: p.port_index,
+            data: &self.data[p.data.clone()],
+        })
+    }
+
+    /// Clears this converter, and converts all the events a plugin produced during a block.
+    ///
+    /// [`MidiSysExEvent`]s are ignored, see [`push_sysex`](Self::push_sysex).
+    pub fn push_block<'a>(&mut self, events: impl IntoIterator<Item = &'a UnknownEvent>) {
+        self.clear();
+        for event in events {
+            self.push_event(event);
+        }
+    }
+
+    /// Converts a single event, and returns `true` if it produced any MIDI message.
+    ///
+    /// [`MidiSysExEvent`]s are ignored, see [`push_sysex`](Self::push_sysex).
+    pub fn push_event(&mut self, event: &UnknownEvent) -> bool {
+        let time = event.header().time();
+        let count = self.packets.len();
+
+        match event.as_core_event() {
+            Some(CoreEventSpace::NoteOn(event)) => {
+                self.push_note(time, event.pckn(), 0x90, event.midi_velocity().max(1))
+            }
+            Some(CoreEventSpace::NoteOff(event)) => {
+                self.push_note(time, event.pckn(), 0x80, event.midi_velocity())
+            }
+            Some(CoreEventSpace::NoteChoke(event)) => self.push_choke(time, event),
+            Some(CoreEventSpace::NoteExpression(event))
+                if event.expression_type() == Some(NoteExpressionType::Pressure) =>
+            {
+                let value = (event.value().clamp(0.0, 1.0) * 127.0).round() as u8;
+                self.push_note(time, event.pckn(), 0xA0, value);
+            }
+            Some(CoreEventSpace::Midi(event)) => {
+                let data = event.data();
+                if let Some(len) = midi1_message_len(data[0]) {
+                    self.push_packet(time, event.port_index(), &data[..len]);
+                }
+            }
+            Some(CoreEventSpace::Midi2(event)) => {
+                if let Some((data, len)) = midi2_to_midi1(event.data()) {
+                    self.push_packet(time, event.port_index(), &data[..len]);
+                }
+            }
+            _ => {}
+        }
+
+        self.packets.len() > count
+    }
+
+    /// Converts a [`MidiSysExEvent`], given its payload, and returns `true` if it produced a MIDI
+    /// message.
+    ///
+    /// The payload of the event can be retrieved using [`MidiSysExEvent::data`], which is only
+    /// valid until the next call to the plugin's audio processor. Payloads that are not a
+    /// complete SysEx message (i.e. that don't start with `0xF0` and end with `0xF7`) are ignored.
+    pub fn push_sysex(&mut self, event: &MidiSysExEvent, data: &[u8]) -> bool {
+        if data.len() < 2 || data[0] != 0xF0 || data[data.len() - 1] != 0xF7 {
+            return false;
+        }
+
+        let count = self.packets.len();
+        self.push_packet(event.header().time(), event.port_index(), data);
+        self.packets.len() > count
+    }
+
+    fn push_note(&mut self, time: u32, pckn: Pckn, status: u8, value: u8) {
+        let (Match::Specific(channel @ 0..=15), Match::Specific(key @ 0..=127)) =
+            (pckn.channel, pckn.key)
+        else {
+            return;
+        };
+
+        if let Match::Specific(port_index) = pckn.port_index {
+            self.push_packet(
+                time,
+                port_index,
+                &[status | channel as u8, key as u8, value & 0x7F],
+            );
+        }
+    }
+
+    fn push_choke(&mut self, time: u32, event: &NoteChokeEvent) {
+        let pckn = event.pckn();
+        let Match::Specific(port_index) = pckn.port_index else {
+            return;
+        };
+
+        let channels = match pckn.channel {
+            Match::Specific(channel @ 0..=15) => channel..channel + 1,
+            Match::Specific(_) => return,
+            Match::All => 0..16,
+        };
+
+        for channel in channels {
+            let status = channel as u8;
+            match pckn.key {
+                Match::Specific(key @ 0..=127) => {
+                    self.push_packet(time, port_index, &[0x80 | status, key as u8, 0])
+                }
+                Match::Specific(_) => {}
+                Match::All => {
+                    self.push_packet(time, port_index, &[0xB0 | status, ALL_SOUND_OFF, 0])
+                }
+            }
+        }
+    }
+
+    fn push_packet(&mut self, time: u32, port_index: u16, data: &[u8]) {
+        if self.port_filter.is_some_and(|p| p != port_index) {
+            return;
+        }
+
+        let start = self.data.len();
+        self.data.extend_from_slice(data);
+        self.packets.push(PacketRecord {
+            time,
+            port_index,
+            data: start..self.data.len(),
+        });
+    }
+}
+
+impl Default for MidiOutputConverter {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the length of the MIDI 1.0 message starting with the given status byte, or [`None`] if
+/// it isn't a valid status byte for a short message.
+fn midi1_message_len(status: u8) -> Option<usize> {
+    match status {
+        0x80..=0xBF | 0xE0..=0xEF | 0xF2 => Some(3),
+        0xC0..=0xDF | 0xF1 | 0xF3 => Some(2),
+        0xF6 | 0xF8 | 0xFA..=0xFC | 0xFE | 0xFF => Some(1),
+        _ => None,
+    }
+}
+
+/// Converts the given MIDI 2.0 Universal MIDI Packet to a MIDI 1.0 message, if it has an
+/// equivalent.
+fn midi2_to_midi1(ump: [u32; 4]) -> Option<([u8; 3], usize)> {
+    let [word0, word1, ..] = ump;
+    let [_, status, index1, index2] = word0.to_be_bytes();
+
+    let data = match word0 >> 28 {
+        // System real-time and common messages, and MIDI 1.0 channel voice messages.
+        0x1 | 0x2 => [status, index1, index2],
+        // MIDI 2.0 channel voice messages.
+        0x4 => match status & 0xF0 {
+            0x80 | 0x90 => {
+                let velocity = (word1 >> 25) as u8;
+                let velocity = if status & 0xF0 == 0x90 {
+                    velocity.max(1)
+                } else {
+                    velocity
+                };
+
+                [status, index1 & 0x7F, velocity]
+            }
+            0xA0 | 0xB0 => [status, index1 & 0x7F, (word1 >> 25) as u8],
+            0xC0 => [status, ((word1 >> 24) & 0x7F) as u8, 0],
+            0xD0 => [status, (word1 >> 25) as u8, 0],
+            0xE0 => {
+                let bend = word1 >> 18;
+                [status, (bend & 0x7F) as u8, ((bend >> 7) & 0x7F) as u8]
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if word0 >> 28 == 0x1 && status < 0xF0 {
+        return None;
+    }
+
+    Some((data, midi1_message_len(data[0])?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_types::{Midi2Event, NoteExpressionEvent, NoteOnEvent};
+    use crate::events::io::EventBuffer;
+
+    fn packets(converter: &MidiOutputConverter) -> Vec<(u32, Vec<u8>)> {
+        converter
+            .packets()
+            .map(|p| (p.time(), p.data().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn converts_midi_events() {
+        let mut events = EventBuffer::new();
+        events.push(&MidiEvent::new(0, 0, [0xC2, 5, 0]));
+        events.push(&MidiEvent::new(1, 0, [0xF8, 0, 0]));
+        events.push(&MidiEvent::new(2, 0, [0x42, 0, 0]));
+        // MIDI 2.0 note on, channel 3, key 64, full velocity.
+        events.push(&Midi2Event::new(3, 0, [0x4093_4000, 0xFFFF_0000, 0, 0]));
+        // MIDI 2.0 pitch bend, channel 0, centered.
+        events.push(&Midi2Event::new(4, 0, [0x40E0_0000, 0x8000_0000, 0, 0]));
+
+        let mut converter = MidiOutputConverter::new();
+        converter.push_block(&events);
+
+        assert_eq!(
+            packets(&converter),
+            [
+                (0, vec![0xC2, 5]),
+                (1, vec![0xF8]),
+                (3, vec![0x93, 64, 127]),
+                (4, vec![0xE0, 0, 64]),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_note_events() {
+        let mut events = EventBuffer::new();
+        events.push(&NoteOnEvent::new(
+            0,
+            Pckn::new(1u16, 0u16, 60u16, 0u32),
+            0.0,
+        ));
+        events.push(&NoteExpressionEvent::new(
+            1,
+            Pckn::new(1u16, 0u16, 60u16, 0u32),
+            NoteExpressionType::Pressure,
+            0.5,
+        ));
+        events.push(&NoteChokeEvent::new(
+            2,
+            Pckn::new(1u16, 0u16, 60u16, Match::All),
+        ));
+        events.push(&NoteChokeEvent::new(
+            3,
+            Pckn::new(1u16, Match::All, Match::All, Match::All),
+        ));
+        events.push(&NoteOnEvent::new(
+            4,
+            Pckn::new(Match::All, 0u16, 60u16, 0u32),
+            1.0,
+        ));
+
+        let mut converter = MidiOutputConverter::new();
+        converter.push_block(&events);
+
+        let packets = packets(&converter);
+        assert_eq!(packets.len(), 3 + 16);
+        assert_eq!(packets[0], (0, vec![0x90, 60, 1]));
+        assert_eq!(packets[1], (1, vec![0xA0, 60, 64]));
+        assert_eq!(packets[2], (2, vec![0x80, 60, 0]));
+        assert_eq!(packets[18], (3, vec![0xBF, ALL_SOUND_OFF, 0]));
+
+        let mut filtered = MidiOutputConverter::new().with_port_filter(0);
+        filtered.push_block(&events);
+        assert!(filtered.is_empty());
+    }
+
+    #[test]
+    fn converts_sysex() {
+        let data = [0xF0, 1, 2, 3, 0xF7];
+        let event = MidiSysExEvent::new(5, 0, &data);
+
+        let mut converter = MidiOutputConverter::new();
+        assert!(!converter.push_event(event.as_ref()));
+        assert!(converter.push_sysex(&event, &data));
+        assert!(!converter.push_sysex(&event, &data[..4]));
+
+        let packet = converter.packets().next().unwrap();
+        assert_eq!(packet.data(), data);
+        assert_eq!(packet.to_midi_event(), None);
+    }
+}
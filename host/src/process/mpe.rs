@@ -0,0 +1,376 @@
+//! Interpretation of MIDI Polyphonic Expression (MPE) input into CLAP note expressions.
+//!
+//! MPE controllers give each note its own MIDI channel, so that channel-wide messages (pitch bend,
+//! channel pressure, and the "slide" CC 74) only affect a single note. CLAP instruments however
+//! expect per-note modulation to be sent as [`NoteExpressionEvent`]s, which target a specific note
+//! through its note ID.
+//!
+//! The [`MpeInterpreter`] tracks which note is playing on which channel, gives each of them a
+//! unique note ID, and converts the MPE messages it receives into the matching note events and
+//! note expressions.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::events::io::EventBuffer;
+//! use clack_host::process::mpe::MpeInterpreter;
+//!
+//! let mut interpreter = MpeInterpreter::new(0);
+//! let mut events = EventBuffer::new();
+//!
+//! // A note on member channel 2, slightly bent up, then pressed down.
+//! interpreter.push_midi(0, &[0xE1, 0x55, 0x42], &mut events);
+//! interpreter.push_midi(0, &[0x91, 60, 100], &mut events);
+//! interpreter.push_midi(64, &[0xD1, 127], &mut events);
+//!
+//! // Note On, then the initial tuning, then the pressure.
+//! assert_eq!(events.len(), 3);
+//! ```
+
+use crate::events::event_types::{
+    midi_velocity_to_clap, NoteExpressionEvent, NoteExpressionType, NoteOffEvent, NoteOnEvent,
+};
+use crate::events::io::EventBuffer;
+use crate::events::Pckn;
+
+/// The default pitch bend range of MPE member channels, in semitones.
+pub const DEFAULT_PITCH_BEND_RANGE: f64 = 48.0;
+
+/// The MIDI 1.0 control change number MPE uses for the "slide" (or "timbre") dimension.
+const SLIDE_CC: u8 = 74;
+
+/// The last expression values received on a channel, which apply to the notes it will start.
+#[derive(Copy, Clone, Debug, Default)]
+struct ChannelState {
+    tuning: Option<f64>,
+    pressure: Option<f64>,
+    brightness: Option<f64>,
+}
+
+/// A note started by the interpreter, which hasn't been released yet.
+#[derive(Copy, Clone, Debug)]
+struct ActiveNote {
+    channel: u8,
+    key: u8,
+    note_id: u32,
+}
+
+/// Converts MPE MIDI 1.0 messages into CLAP note events and note expressions.
+///
+/// See the [module documentation](self) for more information.
+///
+/// Messages are interpreted as follows, for all channels except the zone's master channel:
+///
+/// * Note On and Note Off messages are converted to [`NoteOnEvent`]s and [`NoteOffEvent`]s, with a
+///   new unique note ID for each note.
+/// * Pitch bend is converted to a [`Tuning`](NoteExpressionType::Tuning) expression, using the
+///   channel's [pitch bend range](Self::with_pitch_bend_range).
+/// * Channel pressure and polyphonic key pressure are converted to
+///   [`Pressure`](NoteExpressionType::Pressure) expressions.
+/// * CC 74 ("slide") is converted to a [`Brightness`](NoteExpressionType::Brightness) expression.
+///
+/// Expression messages received before a note starts (as MPE controllers usually send them) are
+/// applied to that note right after its Note On event.
+///
+/// Messages on the master channel apply to the whole zone, and have no per-note equivalent: they
+/// are not interpreted, and should be forwarded to the plugin as regular MIDI. Note events on the
+/// master channel are still converted, without any expression.
+///
+/// # Realtime Safety
+///
+/// Interpretation is realtime-safe, as long as no more than 128 notes are held at the same time,
+/// and the given event buffer has enough capacity for the produced events.
+#[derive(Clone, Debug)]
+pub struct MpeInterpreter {
+    port_index: u16,
+    master_channel: Option<u8>,
+    pitch_bend_range: f64,
+    next_note_id: u32,
+    channels: [ChannelState; 16],
+    notes: Vec<ActiveNote>,
+}
+
+impl MpeInterpreter {
+    /// Creates a new interpreter, producing events for the given plugin note port.
+    ///
+    /// This uses an MPE lower zone by default, where channel 1 (index `0`) is the master channel.
+    pub fn new(port_index: u16) -> Self {
+        Self {
+            port_index,
+            master_channel: Some(0),
+            pitch_bend_range: DEFAULT_PITCH_BEND_RANGE,
+            next_note_id: 0,
+            channels: [ChannelState::default(); 16],
+            notes: Vec::with_capacity(128),
+        }
+    }
+
+    /// Sets the master channel of the MPE zone, as a channel index in the `0..=15` range.
+    ///
+    /// This should be `0` for a lower zone, or `15` for an upper zone. [`None`] makes all channels
+    /// member channels, which is useful for controllers that don't use a master channel at all.
+    #[inline]
+    pub fn with_master_channel(mut self, master_channel: Option<u8>) -> Self {
+        self.master_channel = master_channel.map(|c| c & 0x0F);
+        self
+    }
+
+    /// Sets the pitch bend range of the member channels, in semitones.
+    ///
+    /// This defaults to [`DEFAULT_PITCH_BEND_RANGE`], as mandated by the MPE specification.
+    #[inline]
+    pub fn with_pitch_bend_range(mut self, semitones: f64) -> Self {
+        self.pitch_bend_range = semitones;
+        self
+    }
+
+    /// Returns the number of notes that are currently held.
+    #[inline]
+    pub fn held_notes_count(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Interprets a single MIDI 1.0 message received at the given `time`, and pushes the matching
+    /// CLAP events to the given `buffer`.
+    ///
+    /// This returns `false` if the message wasn't interpreted, in which case hosts may forward it
+    /// to the plugin as a regular MIDI event.
+    pub fn push_midi(&mut self, time: u32, data: &[u8], buffer: &mut EventBuffer) -> bool {
+        let [status, rest @ ..] = data else {
+            return false;
+        };
+
+        let channel = status & 0x0F;
+        let is_member = self.master_channel != Some(channel);
+
+        match (status & 0xF0, rest) {
+            (0x90, [key, velocity, ..]) if *velocity > 0 => {
+                self.note_on(time, channel, *key, *velocity, is_member, buffer)
+            }
+            (0x80 | 0x90, [key, velocity, ..]) => {
+                self.note_off(time, channel, *key, *velocity, buffer)
+            }
+            (0xE0, [lsb, msb, ..]) if is_member => {
+                let bend = (((*msb as u16 & 0x7F) << 7) | (*lsb as u16 & 0x7F)) as f64 - 8192.0;
+                let tuning = bend / 8192.0 * self.pitch_bend_range;
+                self.channels[channel as usize].tuning = Some(tuning);
+                self.expression(
+                    time,
+                    channel,
+                    None,
+                    NoteExpressionType::Tuning,
+                    tuning,
+                    buffer,
+                );
+            }
+            (0xD0, [pressure, ..]) if is_member => {
+                let pressure = (*pressure & 0x7F) as f64 / 127.0;
+                self.channels[channel as usize].pressure = Some(pressure);
+                self.expression(
+                    time,
+                    channel,
+                    None,
+                    NoteExpressionType::Pressure,
+                    pressure,
+                    buffer,
+                );
+            }
+            (0xA0, [key, pressure, ..]) if is_member => {
+                let pressure = (*pressure & 0x7F) as f64 / 127.0;
+                let key = Some(*key);
+                self.expression(
+                    time,
+                    channel,
+                    key,
+                    NoteExpressionType::Pressure,
+                    pressure,
+                    buffer,
+                );
+            }
+            (0xB0, [SLIDE_CC, value, ..]) if is_member => {
+                let brightness = (*value & 0x7F) as f64 / 127.0;
+                self.channels[channel as usize].brightness = Some(brightness);
+                let expression_type = NoteExpressionType::Brightness;
+                self.expression(time, channel, None, expression_type, brightness, buffer);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Releases all held notes, and forgets all the received expression values.
+    ///
+    /// This should be called when the MIDI input is disconnected, to avoid stuck notes.
+    pub fn release_all(&mut self, time: u32, buffer: &mut EventBuffer) {
+        for note in self.notes.drain(..) {
+            let pckn = Pckn::new(self.port_index, note.channel, note.key, note.note_id);
+            buffer.push(&NoteOffEvent::new(time, pckn, 0.0));
+        }
+
+        self.channels = [ChannelState::default(); 16];
+    }
+
+    fn note_on(
+        &mut self,
+        time: u32,
+        channel: u8,
+        key: u8,
+        velocity: u8,
+        is_member: bool,
+        buffer: &mut EventBuffer,
+    ) {
+        let note_id = self.next_note_id;
+        // Note IDs must stay in the 0..i32::MAX range.
+        self.next_note_id = (self.next_note_id + 1) % i32::MAX as u32;
+
+        let key = key & 0x7F;
+        let pckn = Pckn::new(self.port_index, channel, key, note_id);
+        buffer.push(&NoteOnEvent::new(
+            time,
+            pckn,
+            midi_velocity_to_clap(velocity),
+        ));
+        self.notes.push(ActiveNote {
+            channel,
+            key,
+            note_id,
+        });
+
+        if !is_member {
+            return;
+        }
+
+        let state = self.channels[channel as usize];
+        let initial_values = [
+            (NoteExpressionType::Tuning, state.tuning),
+            (NoteExpressionType::Pressure, state.pressure),
+            (NoteExpressionType::Brightness, state.brightness),
+        ];
+
+        for (expression_type, value) in initial_values {
+            if let Some(value) = value {
+                buffer.push(&NoteExpressionEvent::new(
+                    time,
+                    pckn,
+                    expression_type,
+                    value,
+                ));
+            }
+        }
+    }
+
+    fn note_off(
+        &mut self,
+        time: u32,
+        channel: u8,
+        key: u8,
+        velocity: u8,
+        buffer: &mut EventBuffer,
+    ) {
+        let key = key & 0x7F;
+        let Some(index) = self
+            .notes
+            .iter()
+            .position(|n| n.channel == channel && n.key == key)
+        else {
+            return;
+        };
+
+        let note = self.notes.remove(index);
+        let pckn = Pckn::new(self.port_index, channel, key, note.note_id);
+        buffer.push(&NoteOffEvent::new(
+            time,
+            pckn,
+            midi_velocity_to_clap(velocity),
+        ));
+    }
+
+    fn expression(
+        &self,
+        time: u32,
+        channel: u8,
+        key: Option<u8>,
+        expression_type: NoteExpressionType,
+        value: f64,
+        buffer: &mut EventBuffer,
+    ) {
+        let notes = self
+            .notes
+            .iter()
+            .filter(|n| n.channel == channel && key.map_or(true, |k| k & 0x7F == n.key));
+
+        for note in notes {
+            let pckn = Pckn::new(self.port_index, channel, note.key, note.note_id);
+            buffer.push(&NoteExpressionEvent::new(
+                time,
+                pckn,
+                expression_type,
+                value,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::spaces::CoreEventSpace;
+    use crate::events::Match;
+
+    #[test]
+    fn associates_expressions_with_notes() {
+        let mut interpreter = MpeInterpreter::new(0).with_pitch_bend_range(2.0);
+        let mut events = EventBuffer::new();
+
+        assert!(interpreter.push_midi(0, &[0xE3, 0x00, 0x60], &mut events));
+        assert!(interpreter.push_midi(0, &[0x93, 60, 127], &mut events));
+        assert!(interpreter.push_midi(1, &[0x94, 64, 127], &mut events));
+        assert!(interpreter.push_midi(2, &[0xB4, SLIDE_CC, 127], &mut events));
+        assert!(interpreter.push_midi(3, &[0x83, 60, 0], &mut events));
+        // The master channel is left to the host.
+        assert!(!interpreter.push_midi(4, &[0xE0, 0x00, 0x40], &mut events));
+
+        assert_eq!(interpreter.held_notes_count(), 1);
+
+        let mut log = Vec::new();
+        for event in &events {
+            match event.as_core_event() {
+                Some(CoreEventSpace::NoteOn(e)) => log.push(("on", e.pckn(), e.velocity())),
+                Some(CoreEventSpace::NoteOff(e)) => log.push(("off", e.pckn(), 0.0)),
+                Some(CoreEventSpace::NoteExpression(e)) => {
+                    log.push(("expression", e.pckn(), e.value()))
+                }
+                _ => panic!("Unexpected event"),
+            }
+        }
+
+        let pckn = |channel: u16, key: u16, note_id: u32| {
+            Pckn::new(0u16, channel, key, Match::Specific(note_id))
+        };
+
+        assert_eq!(
+            log,
+            [
+                ("on", pckn(3, 60, 0), 1.0),
+                ("expression", pckn(3, 60, 0), 1.0),
+                ("on", pckn(4, 64, 1), 1.0),
+                ("expression", pckn(4, 64, 1), 1.0),
+                ("off", pckn(3, 60, 0), 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn releases_all_notes() {
+        let mut interpreter = MpeInterpreter::new(0).with_master_channel(None);
+        let mut events = EventBuffer::new();
+
+        interpreter.push_midi(0, &[0x90, 60, 100], &mut events);
+        interpreter.push_midi(0, &[0x91, 60, 100], &mut events);
+        interpreter.release_all(10, &mut events);
+
+        assert_eq!(interpreter.held_notes_count(), 0);
+        assert_eq!(events.len(), 4);
+    }
+}
@@ -0,0 +1,413 @@
+//! Tracking of the lifecycle of the notes a host sends to a plugin.
+//!
+//! In CLAP, a note starts when the host sends a [`NoteOnEvent`] to the plugin, and only ends when
+//! the plugin sends a [`NoteEndEvent`] back, possibly long after the note was released with a
+//! [`NoteOffEvent`] (e.g. once its release tail is over). Notes can also be ended early by the
+//! host, using a [`NoteChokeEvent`].
+//!
+//! The [`NoteTracker`] follows this lifecycle, which hosts can use to e.g. display which notes are
+//! currently playing, or to detect plugins that never end their notes.
+
+use crate::events::event_types::{NoteChokeEvent, NoteEndEvent, NoteOffEvent, NoteOnEvent};
+use crate::events::spaces::CoreEventSpace;
+use crate::events::{Pckn, UnknownEvent};
+
+/// A note that was sent to a plugin, and that it hasn't ended yet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrackedNote {
+    pckn: Pckn,
+    velocity: f64,
+    age: u64,
+    released_for: Option<u64>,
+}
+
+impl TrackedNote {
+    /// The PCKN tuple the note was started with.
+    ///
+    /// The port, channel and key are always specific values, but the note ID may be
+    /// [`Match::All`](crate::events::Match::All) if the host didn't give one.
+    #[inline]
+    pub const fn pckn(&self) -> Pckn {
+        self.pckn
+    }
+
+    /// The velocity the note was started with.
+    #[inline]
+    pub const fn velocity(&self) -> f64 {
+        self.velocity
+    }
+
+    /// The number of frames that were processed since the note was started.
+    #[inline]
+    pub const fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Returns `true` if the note was released by the host, but not ended by the plugin yet.
+    #[inline]
+    pub const fn is_released(&self) -> bool {
+        self.released_for.is_some()
+    }
+
+    /// The number of frames that were processed since the note was released, or [`None`] if it
+    /// is still held.
+    #[inline]
+    pub const fn released_for(&self) -> Option<u64> {
+        self.released_for
+    }
+}
+
+/// Receives the changes in the lifecycle of the notes followed by a [`NoteTracker`].
+///
+/// This is mostly useful to update UI indicators. All methods do nothing by default, and this is
+/// also implemented for `()` for hosts that don't need to be notified.
+#[allow(unused_variables)]
+pub trait NoteLifecycleHandler {
+    /// A note was sent to the plugin.
+    fn note_started(&mut self, note: &TrackedNote) {}
+
+    /// A note was released by the host. The plugin may keep playing it for a while.
+    fn note_released(&mut self, note: &TrackedNote) {}
+
+    /// A note was ended by the plugin.
+    fn note_ended(&mut self, note: &TrackedNote) {}
+
+    /// A note was choked by the host.
+    fn note_choked(&mut self, note: &TrackedNote) {}
+
+    /// A note was released, but the plugin didn't end it before the tracker's leak timeout.
+    ///
+    /// The note isn't tracked anymore after this.
+    fn note_leaked(&mut self, note: &TrackedNote) {}
+}
+
+impl NoteLifecycleHandler for () {}
+
+/// A helper following the full lifecycle of the notes sent to a plugin, from the host's
+/// [`NoteOnEvent`] to the plugin's [`NoteEndEvent`].
+///
+/// The host gives the events of every block it processes to
+/// [`process_block`](Self::process_block): note starts, releases and chokes are read from the
+/// input events sent to the plugin, and note ends are read from the output events the plugin
+/// produced.
+///
+/// Notes are voice-stacking aware: multiple notes playing the same key at the same time are
+/// tracked separately, even if they don't have a note ID. In that case, each [`NoteEndEvent`]
+/// ends the oldest matching note, and notes are matched with their PCKN tuples as described in
+/// [`Pckn::matches`].
+///
+/// Notes that are released, but that the plugin doesn't end within the
+/// [leak timeout](Self::with_leak_timeout), are reported as leaked and stop being tracked. Note
+/// that this only makes sense for plugins that send [`NoteEndEvent`]s.
+///
+/// MIDI events aren't tracked.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::events::event_types::{NoteEndEvent, NoteOffEvent, NoteOnEvent};
+/// use clack_host::events::io::EventBuffer;
+/// use clack_host::events::{Match, Pckn};
+/// use clack_host::process::notes::NoteTracker;
+///
+/// let mut tracker = NoteTracker::new().with_leak_timeout(1024);
+/// let mut input_events = EventBuffer::new();
+/// input_events.push(&NoteOnEvent::new(0, Pckn::new(0u16, 0u16, 60u16, Match::All), 1.0));
+/// input_events.push(&NoteOnEvent::new(8, Pckn::new(0u16, 0u16, 60u16, Match::All), 1.0));
+///
+/// tracker.process_block(256, &input_events, &EventBuffer::new(), &mut ());
+/// assert_eq!(tracker.len(), 2);
+///
+/// // Releasing the key releases both stacked notes, but the plugin only ends one of them.
+/// let mut input_events = EventBuffer::new();
+/// input_events.push(&NoteOffEvent::new(0, Pckn::new(0u16, 0u16, 60u16, Match::All), 1.0));
+/// let mut output_events = EventBuffer::new();
+/// output_events.push(&NoteEndEvent::new(0, Pckn::new(0u16, 0u16, 60u16, Match::All)));
+///
+/// tracker.process_block(256, &input_events, &output_events, &mut ());
+/// assert_eq!(tracker.len(), 1);
+/// assert!(tracker.notes().all(|note| note.is_released()));
+///
+/// // The remaining note leaks once the timeout is over.
+/// tracker.advance(1024, &mut ());
+/// assert!(tracker.is_empty());
+/// assert_eq!(tracker.leaked_count(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteTracker {
+    notes: Vec<TrackedNote>,
+    leak_timeout: u64,
+    leaked_count: u64,
+}
+
+impl NoteTracker {
+    /// The default leak timeout, in frames. This is 10 seconds at 48kHz.
+    pub const DEFAULT_LEAK_TIMEOUT: u64 = 480_000;
+
+    /// Creates a new tracker, with no notes.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            notes: Vec::new(),
+            leak_timeout: Self::DEFAULT_LEAK_TIMEOUT,
+            leaked_count: 0,
+        }
+    }
+
+    /// Sets the number of frames a plugin has to end a note after it has been released, after
+    /// which the note is considered leaked.
+    ///
+    /// The default is [`DEFAULT_LEAK_TIMEOUT`](Self::DEFAULT_LEAK_TIMEOUT). A timeout of
+    /// [`u64::MAX`] disables leak detection.
+    #[inline]
+    pub fn with_leak_timeout(mut self, frames: u64) -> Self {
+        self.leak_timeout = frames;
+        self
+    }
+
+    /// Returns the number of notes that are currently playing.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Returns `true` if no notes are currently playing.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Returns the total number of notes that leaked since this tracker was created.
+    #[inline]
+    pub fn leaked_count(&self) -> u64 {
+        self.leaked_count
+    }
+
+    /// Returns an iterator over all the notes that are currently playing, from oldest to newest.
+    #[inline]
+    pub fn notes(&self) -> impl Iterator<Item = &TrackedNote> {
+        self.notes.iter()
+    }
+
+    /// Returns `true` if any currently playing note matches the given PCKN tuple.
+    #[inline]
+    pub fn is_playing(&self, pckn: &Pckn) -> bool {
+        self.notes.iter().any(|note| pckn.matches(&note.pckn))
+    }
+
+    /// Follows all the note events of a block of `frames_count` frames, then
+    /// [advances](Self::advance) the time of all the notes.
+    ///
+    /// `input_events` are the events the host sent to the plugin, and `output_events` are the
+    /// events the plugin produced during that block.
+    pub fn process_block<'a, 'b>(
+        &mut self,
+        frames_count: u32,
+        input_events: impl IntoIterator<Item = &'a UnknownEvent>,
+        output_events: impl IntoIterator<Item = &'b UnknownEvent>,
+        handler: &mut impl NoteLifecycleHandler,
+    ) {
+        for event in input_events {
+            match event.as_core_event() {
+                Some(CoreEventSpace::NoteOn(event)) => self.note_on(event, handler),
+                Some(CoreEventSpace::NoteOff(event)) => self.note_off(event, handler),
+                Some(CoreEventSpace::NoteChoke(event)) => self.note_choke(event, handler),
+                _ => {}
+            }
+        }
+
+        for event in output_events {
+            if let Some(CoreEventSpace::NoteEnd(event)) = event.as_core_event() {
+                self.note_end(event, handler);
+            }
+        }
+
+        self.advance(frames_count as u64, handler);
+    }
+
+    /// Starts tracking the note started by the given event.
+    ///
+    /// Events that don't target a specific port, channel and key are ignored, as they are invalid.
+    pub fn note_on(&mut self, event: &NoteOnEvent, handler: &mut impl NoteLifecycleHandler) {
+        let pckn = event.pckn();
+        if pckn.port_index.is_all() || pckn.channel.is_all() || pckn.key.is_all() {
+            return;
+        }
+
+        let note = TrackedNote {
+            pckn,
+            velocity: event.velocity(),
+            age: 0,
+            released_for: None,
+        };
+
+        handler.note_started(&note);
+        self.notes.push(note);
+    }
+
+    /// Marks all the held notes matching the given event as released.
+    pub fn note_off(&mut self, event: &NoteOffEvent, handler: &mut impl NoteLifecycleHandler) {
+        let pckn = event.pckn();
+
+        for note in &mut self.notes {
+            if note.released_for.is_none() && pckn.matches(&note.pckn) {
+                note.released_for = Some(0);
+                handler.note_released(note);
+            }
+        }
+    }
+
+    /// Stops tracking all the notes matching the given event.
+    pub fn note_choke(&mut self, event: &NoteChokeEvent, handler: &mut impl NoteLifecycleHandler) {
+        let pckn = event.pckn();
+
+        self.notes.retain(|note| {
+            let choked = pckn.matches(&note.pckn);
+            if choked {
+                handler.note_choked(note);
+            }
+
+            !choked
+        })
+    }
+
+    /// Stops tracking the oldest note matching the given event, if any.
+    pub fn note_end(&mut self, event: &NoteEndEvent, handler: &mut impl NoteLifecycleHandler) {
+        let pckn = event.pckn();
+
+        if let Some(index) = self.notes.iter().position(|note| pckn.matches(&note.pckn)) {
+            let note = self.notes.remove(index);
+            handler.note_ended(&note);
+        }
+    }
+
+    /// Advances the time of all the notes by the given number of frames, and reports the notes
+    /// that leaked.
+    ///
+    /// This is already called by [`process_block`](Self::process_block), but can also be used by
+    /// hosts that give the events to this tracker one by one.
+    pub fn advance(&mut self, frames_count: u64, handler: &mut impl NoteLifecycleHandler) {
+        let leak_timeout = self.leak_timeout;
+        let mut leaked_count = 0;
+
+        self.notes.retain_mut(|note| {
+            note.age = note.age.saturating_add(frames_count);
+
+            let Some(released_for) = &mut note.released_for else {
+                return true;
+            };
+
+            *released_for = released_for.saturating_add(frames_count);
+            if leak_timeout == u64::MAX || *released_for < leak_timeout {
+                return true;
+            }
+
+            leaked_count += 1;
+            handler.note_leaked(note);
+            false
+        });
+
+        self.leaked_count += leaked_count;
+    }
+
+    /// Ends all the currently playing notes, e.g. after the plugin was reset or deactivated.
+    pub fn end_all(&mut self, handler: &mut impl NoteLifecycleHandler) {
+        for note in self.notes.drain(..) {
+            handler.note_ended(&note);
+        }
+    }
+}
+
+impl Default for NoteTracker {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::Match;
+
+    #[derive(Default)]
+    struct Log(Vec<(&'static str, u16)>);
+
+    impl Log {
+        fn push(&mut self, kind: &'static str, note: &TrackedNote) {
+            self.0.push((kind, *note.pckn().key.as_specific().unwrap()))
+        }
+    }
+
+    impl NoteLifecycleHandler for Log {
+        fn note_started(&mut self, note: &TrackedNote) {
+            self.push("started", note)
+        }
+
+        fn note_released(&mut self, note: &TrackedNote) {
+            self.push("released", note)
+        }
+
+        fn note_ended(&mut self, note: &TrackedNote) {
+            self.push("ended", note)
+        }
+
+        fn note_choked(&mut self, note: &TrackedNote) {
+            self.push("choked", note)
+        }
+
+        fn note_leaked(&mut self, note: &TrackedNote) {
+            self.push("leaked", note)
+        }
+    }
+
+    fn pckn(key: u16, note_id: impl Into<Match<u32>>) -> Pckn {
+        Pckn::new(0u16, 0u16, key, note_id)
+    }
+
+    #[test]
+    fn follows_note_lifecycle() {
+        let mut tracker = NoteTracker::new().with_leak_timeout(100);
+        let mut log = Log::default();
+
+        tracker.note_on(&NoteOnEvent::new(0, pckn(60, 1u32), 1.0), &mut log);
+        tracker.note_on(&NoteOnEvent::new(0, pckn(60, 2u32), 1.0), &mut log);
+        tracker.note_on(&NoteOnEvent::new(0, pckn(64, 3u32), 0.5), &mut log);
+        // Invalid, as it doesn't target a specific key.
+        tracker.note_on(&NoteOnEvent::new(0, Pckn::match_all(), 0.5), &mut log);
+        assert_eq!(tracker.len(), 3);
+
+        // Only the note with ID 2 is released and ended.
+        tracker.note_off(&NoteOffEvent::new(0, pckn(60, 2u32), 0.0), &mut log);
+        tracker.advance(50, &mut log);
+        tracker.note_end(&NoteEndEvent::new(0, pckn(60, 2u32)), &mut log);
+        assert!(tracker.is_playing(&pckn(60, Match::All)));
+        assert!(!tracker.is_playing(&pckn(60, 2u32)));
+
+        tracker.note_choke(&NoteChokeEvent::new(0, pckn(64, Match::All)), &mut log);
+        assert_eq!(tracker.len(), 1);
+        assert_eq!(tracker.notes().next().unwrap().age(), 50);
+
+        // The last note is released, but never ended.
+        tracker.note_off(&NoteOffEvent::new(0, pckn(60, Match::All), 0.0), &mut log);
+        tracker.advance(99, &mut log);
+        assert_eq!(tracker.len(), 1);
+        tracker.advance(1, &mut log);
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.leaked_count(), 1);
+
+        assert_eq!(
+            log.0,
+            [
+                ("started", 60),
+                ("started", 60),
+                ("started", 64),
+                ("released", 60),
+                ("ended", 60),
+                ("choked", 64),
+                ("released", 60),
+                ("leaked", 60),
+            ]
+        );
+    }
+}
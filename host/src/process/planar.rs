@@ -0,0 +1,78 @@
+//! Utilities for planar audio buffers.
+//!
+//! Some host-side adapters, such as the [`FixedBlockAdapter`](super::block_adapter::FixedBlockAdapter),
+//! take the host's audio as one planar buffer per port, instead of one buffer per channel. A
+//! planar buffer stores the samples of all the channels of its port one channel after the other:
+//! for a block of `n` frames, the samples of channel `c` are located at `c * n..(c + 1) * n`.
+//!
+//! The number of frames of a block is therefore deduced from the sizes of those buffers and the
+//! channel counts of their ports, using [`frames_count`]. Only 32-bit sample buffers are
+//! supported.
+//!
+//! # Realtime Safety
+//!
+//! Adapters processing planar buffers allocate all their buffers when they are created.
+//! Processing is then realtime-safe, as long as the number of events per block doesn't exceed
+//! the pre-allocated event buffer capacity.
+
+/// Returns the number of frames held by the given planar port buffers, given the number of
+/// channels of each input and output port.
+///
+/// Ports that have no channel are ignored. If no port has any channel, this returns `0`.
+///
+/// # Panics
+///
+/// Panics if the number of given port buffers doesn't match the number of channel counts, if the
+/// size of a port buffer isn't a multiple of its channel count, or if the port buffers don't all
+/// hold the same number of frames.
+pub fn frames_count(
+    audio_inputs: &[&[f32]],
+    audio_outputs: &[&mut [f32]],
+    input_channel_counts: &[usize],
+    output_channel_counts: &[usize],
+) -> usize {
+    assert_eq!(audio_inputs.len(), input_channel_counts.len());
+    assert_eq!(audio_outputs.len(), output_channel_counts.len());
+
+    let inputs = audio_inputs.iter().map(|b| b.len());
+    let outputs = audio_outputs.iter().map(|b| b.len());
+
+    let mut frames_count = None;
+    for (len, channels) in inputs
+        .chain(outputs)
+        .zip(input_channel_counts.iter().chain(output_channel_counts))
+    {
+        if *channels == 0 {
+            continue;
+        }
+
+        let frames = len / channels;
+        assert_eq!(frames * channels, len, "Port buffer size mismatch");
+        assert_eq!(*frames_count.get_or_insert(frames), frames);
+    }
+
+    frames_count.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deduces_frames_count_from_port_buffers() {
+        let input = [0.0; 8];
+        let mut output = [0.0; 4];
+
+        assert_eq!(frames_count(&[&input], &[&mut output], &[2], &[1]), 4);
+        assert_eq!(frames_count(&[&[]], &[], &[0], &[]), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_mismatched_port_buffers() {
+        let input = [0.0; 8];
+        let mut output = [0.0; 3];
+
+        frames_count(&[&input], &[&mut output], &[2], &[1]);
+    }
+}
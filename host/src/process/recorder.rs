@@ -0,0 +1,457 @@
+//! Recording of the events and transport given to a plugin into a compact binary log, which can
+//! then be replayed deterministically.
+//!
+//! This is mostly useful to reproduce issues reported by users: a host records every block it
+//! processes using an [`EventRecorder`], and saves the resulting log alongside the bug report.
+//! The log can then be parsed back into an [`EventLog`], whose [`RecordedBlock`]s can be replayed
+//! into a plugin instance, e.g. in a test host.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::events::event_types::NoteOnEvent;
+//! use clack_host::events::io::EventBuffer;
+//! use clack_host::events::Pckn;
+//! use clack_host::process::recorder::{EventLog, EventRecorder};
+//!
+//! let mut input_events = EventBuffer::new();
+//! input_events.push(&NoteOnEvent::new(12, Pckn::new(0u16, 0u16, 60u16, 0u32), 1.0));
+//!
+//! let mut recorder = EventRecorder::new();
+//! recorder.record_block(256, Some(0), None, &input_events, &EventBuffer::new());
+//! recorder.record_block(256, Some(256), None, &EventBuffer::new(), &EventBuffer::new());
+//!
+//! let log = EventLog::parse(recorder.as_bytes()).unwrap();
+//! assert_eq!(log.blocks().len(), 2);
+//! assert_eq!(log.blocks()[0].input_events().len(), 1);
+//! assert_eq!(log.blocks()[1].steady_time(), Some(256));
+//! ```
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::audio_buffers::{InputAudioBuffers, OutputAudioBuffers};
+use crate::process::{ProcessStatus, StartedPluginAudioProcessor};
+use clack_common::events::event_types::{MidiSysExEvent, TransportEvent};
+use clack_common::events::io::{EventBuffer, OutputEvents};
+use clack_common::events::UnknownEvent;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The magic bytes every event log starts with.
+const MAGIC: &[u8; 8] = b"CLACKEVT";
+
+/// The current version of the event log format.
+const FORMAT_VERSION: u32 = 1;
+
+/// The value recorded in place of a missing steady time.
+const NO_STEADY_TIME: u64 = u64::MAX;
+
+/// Records the events and transport information given to (or produced by) a plugin, block by
+/// block, into a compact binary log.
+///
+/// Each block is recorded using [`record_block`](Self::record_block), typically right after the
+/// plugin processed it. The resulting log can be retrieved using [`as_bytes`](Self::as_bytes),
+/// and parsed back using [`EventLog::parse`].
+///
+/// Events are recorded as raw bytes, which makes this compatible with any event type, including
+/// events from custom event spaces. However, events referencing external memory (i.e.
+/// [`MidiSysExEvent`]s) cannot be recorded this way: they are skipped, and counted in
+/// [`skipped_events`](Self::skipped_events).
+#[derive(Clone, Debug)]
+pub struct EventRecorder {
+    data: Vec<u8>,
+    block_count: usize,
+    skipped_events: usize,
+}
+
+impl EventRecorder {
+    /// Creates a new, empty recorder.
+    pub fn new() -> Self {
+        let mut data = Vec::with_capacity(4096);
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+
+        Self {
+            data,
+            block_count: 0,
+            skipped_events: 0,
+        }
+    }
+
+    /// Records a processed block.
+    ///
+    /// The given `frames_count`, `steady_time` and `transport` are the ones given to the plugin's
+    /// `process()` call, alongside the `input_events`. The `output_events` are the ones the plugin
+    /// produced during that call, if any.
+    pub fn record_block<'i, 'o>(
+        &mut self,
+        frames_count: u32,
+        steady_time: Option<u64>,
+        transport: Option<&TransportEvent>,
+        input_events: impl IntoIterator<Item = &'i UnknownEvent>,
+        output_events: impl IntoIterator<Item = &'o UnknownEvent>,
+    ) {
+        self.write_u32(frames_count);
+        self.write_u64(steady_time.unwrap_or(NO_STEADY_TIME));
+
+        match transport {
+            Some(transport) => {
+                self.data.push(1);
+                self.write_event(transport.as_ref());
+            }
+            None => self.data.push(0),
+        }
+
+        self.write_events(input_events);
+        self.write_events(output_events);
+
+        self.block_count += 1;
+    }
+
+    /// Returns the number of blocks recorded so far.
+    #[inline]
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    /// Returns the number of events that could not be recorded, because they referenced external
+    /// memory.
+    #[inline]
+    pub fn skipped_events(&self) -> usize {
+        self.skipped_events
+    }
+
+    /// Returns the recorded log, as bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the recorded log, as bytes, consuming this recorder.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn write_events<'e>(&mut self, events: impl IntoIterator<Item = &'e UnknownEvent>) {
+        let count_position = self.data.len();
+        self.write_u32(0);
+
+        let mut count = 0u32;
+        for event in events {
+            if event.as_event::<MidiSysExEvent>().is_some() {
+                self.skipped_events += 1;
+                continue;
+            }
+
+            self.write_event(event);
+            count += 1;
+        }
+
+        self.data[count_position..count_position + 4].copy_from_slice(&count.to_le_bytes());
+    }
+
+    fn write_event(&mut self, event: &UnknownEvent) {
+        let bytes = event.as_bytes();
+        self.write_u32(bytes.len() as u32);
+        self.data.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+impl Default for EventRecorder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Errors that can occur when parsing an [`EventLog`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventLogError {
+    /// The data doesn't start with a valid event log header.
+    InvalidHeader,
+    /// The event log was recorded using an unsupported format version.
+    UnsupportedVersion(u32),
+    /// The event log ended unexpectedly.
+    Truncated,
+    /// The event log contains a malformed event.
+    InvalidEvent,
+}
+
+impl Display for EventLogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventLogError::InvalidHeader => f.write_str("Invalid event log header"),
+            EventLogError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported event log format version: {version}")
+            }
+            EventLogError::Truncated => f.write_str("Event log is truncated"),
+            EventLogError::InvalidEvent => f.write_str("Event log contains a malformed event"),
+        }
+    }
+}
+
+impl Error for EventLogError {}
+
+/// A block recorded by an [`EventRecorder`].
+#[derive(Debug)]
+pub struct RecordedBlock {
+    frames_count: u32,
+    steady_time: Option<u64>,
+    transport: Option<TransportEvent>,
+    input_events: EventBuffer,
+    output_events: EventBuffer,
+}
+
+impl RecordedBlock {
+    /// Returns the number of frames that were processed in this block.
+    #[inline]
+    pub fn frames_count(&self) -> u32 {
+        self.frames_count
+    }
+
+    /// Returns the steady time that was given to the plugin for this block, if any.
+    #[inline]
+    pub fn steady_time(&self) -> Option<u64> {
+        self.steady_time
+    }
+
+    /// Returns the transport information that was given to the plugin for this block, if any.
+    #[inline]
+    pub fn transport(&self) -> Option<&TransportEvent> {
+        self.transport.as_ref()
+    }
+
+    /// Returns the input events that were given to the plugin for this block.
+    #[inline]
+    pub fn input_events(&self) -> &EventBuffer {
+        &self.input_events
+    }
+
+    /// Returns the output events the plugin produced during this block.
+    #[inline]
+    pub fn output_events(&self) -> &EventBuffer {
+        &self.output_events
+    }
+
+    /// Replays this block into the given audio processor.
+    ///
+    /// This calls the plugin's `process()` with the recorded input events, steady time and
+    /// transport information. The given audio buffers must hold at least
+    /// [`frames_count`](Self::frames_count) frames.
+    ///
+    /// The events the plugin produces are pushed to `output_events`, and can be compared to the
+    /// recorded [`output_events`](Self::output_events).
+    ///
+    /// # Errors
+    ///
+    /// Returns any error the plugin's `process()` call returned.
+    pub fn replay<H: HostHandlers>(
+        &self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+        audio_inputs: &InputAudioBuffers,
+        audio_outputs: &mut OutputAudioBuffers,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        processor.process(
+            audio_inputs,
+            audio_outputs,
+            &self.input_events.as_input(),
+            output_events,
+            self.steady_time,
+            self.transport.as_ref(),
+        )
+    }
+}
+
+/// An event log, as recorded by an [`EventRecorder`] and parsed back using
+/// [`parse`](Self::parse).
+#[derive(Debug, Default)]
+pub struct EventLog {
+    blocks: Vec<RecordedBlock>,
+}
+
+impl EventLog {
+    /// Parses an event log from the bytes produced by an [`EventRecorder`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EventLogError`] if the data isn't a valid event log.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EventLogError> {
+        let mut reader = LogReader {
+            remaining: bytes,
+            event: Vec::new(),
+        };
+
+        if reader.read_bytes(MAGIC.len()) != Ok(MAGIC.as_slice()) {
+            return Err(EventLogError::InvalidHeader);
+        }
+
+        match reader.read_u32()? {
+            FORMAT_VERSION => {}
+            version => return Err(EventLogError::UnsupportedVersion(version)),
+        }
+
+        let mut blocks = Vec::new();
+
+        while !reader.remaining.is_empty() {
+            let frames_count = reader.read_u32()?;
+            let steady_time = match reader.read_u64()? {
+                NO_STEADY_TIME => None,
+                steady_time => Some(steady_time),
+            };
+
+            let transport = match reader.read_bytes(1)?[0] {
+                0 => None,
+                _ => Some(
+                    *reader
+                        .read_event()?
+                        .as_event::<TransportEvent>()
+                        .ok_or(EventLogError::InvalidEvent)?,
+                ),
+            };
+
+            blocks.push(RecordedBlock {
+                frames_count,
+                steady_time,
+                transport,
+                input_events: reader.read_events()?,
+                output_events: reader.read_events()?,
+            });
+        }
+
+        Ok(Self { blocks })
+    }
+
+    /// Returns all the recorded blocks, in order.
+    #[inline]
+    pub fn blocks(&self) -> &[RecordedBlock] {
+        &self.blocks
+    }
+}
+
+struct LogReader<'a> {
+    remaining: &'a [u8],
+    /// Aligned storage for the event currently being read.
+    event: Vec<u64>,
+}
+
+impl<'a> LogReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], EventLogError> {
+        if self.remaining.len() < len {
+            return Err(EventLogError::Truncated);
+        }
+
+        let (bytes, remaining) = self.remaining.split_at(len);
+        self.remaining = remaining;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, EventLogError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, EventLogError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_event(&mut self) -> Result<&UnknownEvent, EventLogError> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.read_bytes(len)?;
+
+        // Copy the event into aligned storage, as the log itself gives no alignment guarantees.
+        self.event.clear();
+        self.event.resize((len + 7) / 8, 0);
+
+        // SAFETY: the storage spans at least len bytes, and u64s are valid as bytes.
+        let event =
+            unsafe { core::slice::from_raw_parts_mut(self.event.as_mut_ptr() as *mut u8, len) };
+        event.copy_from_slice(bytes);
+
+        UnknownEvent::from_bytes(event).ok_or(EventLogError::InvalidEvent)
+    }
+
+    fn read_events(&mut self) -> Result<EventBuffer, EventLogError> {
+        let count = self.read_u32()?;
+        let mut events = EventBuffer::with_capacity(count.min(1024) as usize);
+
+        for _ in 0..count {
+            events.push(self.read_event()?);
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::process::transport::TransportPlayhead;
+    use clack_common::events::event_types::{NoteOffEvent, NoteOnEvent};
+    use clack_common::events::Pckn;
+
+    #[test]
+    fn round_trips_events_and_transport() {
+        let note = Pckn::new(0u16, 0u16, 60u16, 0u32);
+        let mut input_events = EventBuffer::new();
+        input_events.push(&NoteOnEvent::new(3, note, 0.5));
+        input_events.push(&NoteOffEvent::new(10, note, 0.0));
+
+        let mut output_events = EventBuffer::new();
+        output_events.push(&NoteOffEvent::new(10, note, 0.0));
+
+        let mut playhead = TransportPlayhead::new(48_000.0).with_tempo(140.0);
+        playhead.play();
+        let transport = playhead.process_block(64, &mut EventBuffer::new());
+
+        let mut recorder = EventRecorder::new();
+        recorder.record_block(64, None, Some(&transport), &input_events, &output_events);
+        recorder.record_block(32, Some(64), None, &EventBuffer::new(), &EventBuffer::new());
+        assert_eq!(recorder.block_count(), 2);
+        assert_eq!(recorder.skipped_events(), 0);
+
+        let log = EventLog::parse(recorder.as_bytes()).unwrap();
+        let [first, second] = log.blocks() else {
+            panic!("Expected 2 blocks, got {}", log.blocks().len());
+        };
+
+        assert_eq!(first.frames_count(), 64);
+        assert_eq!(first.steady_time(), None);
+        assert_eq!(first.transport(), Some(&transport));
+        assert_eq!(
+            first.input_events()[0].as_event::<NoteOnEvent>(),
+            input_events[0].as_event::<NoteOnEvent>()
+        );
+        assert_eq!(first.input_events().len(), 2);
+        assert_eq!(first.output_events().len(), 1);
+
+        assert_eq!(second.frames_count(), 32);
+        assert_eq!(second.steady_time(), Some(64));
+        assert_eq!(second.transport(), None);
+        assert!(second.input_events().is_empty());
+
+        let bytes = recorder.into_bytes();
+        assert_eq!(
+            EventLog::parse(&bytes[..bytes.len() - 1]).unwrap_err(),
+            EventLogError::Truncated
+        );
+        assert_eq!(
+            EventLog::parse(b"NOTALOG!").unwrap_err(),
+            EventLogError::InvalidHeader
+        );
+    }
+}
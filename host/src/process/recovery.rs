@@ -0,0 +1,247 @@
+//! Recovery from failed `process()` calls.
+//!
+//! When a plugin's `process()` call fails (i.e. it returns `CLAP_PROCESS_ERROR`), the contents of
+//! its output buffers are unspecified, and calling it again is unlikely to succeed. Hosts that
+//! keep processing failing plugins on every block end up reporting the same error dozens of times
+//! per second, while outputting garbage.
+//!
+//! The [`ProcessRecovery`] helper tracks whether a plugin's audio processor is errored. After a
+//! failure, it stops calling `process()` and reports the plugin as [bypassed](ProcessOutcome::Bypassed)
+//! for all subsequent blocks, so that the host can output silence or the dry signal instead.
+//! Optionally, it can also try to [restart](ProcessRecovery::with_max_restarts) processing a few
+//! times, by stopping processing, resetting the plugin, and starting processing again.
+//!
+//! Every failure is reported exactly once to the helper's callback, alongside the recovery action
+//! that was taken.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::prelude::*;
+//! use clack_host::process::recovery::{ProcessFailure, ProcessOutcome, ProcessRecovery};
+//! use clack_host::process::PluginAudioProcessor;
+//!
+//! fn audio_thread_callback<H: HostHandlers>(
+//!     processor: &mut PluginAudioProcessor<H>,
+//!     recovery: &mut ProcessRecovery<fn(&ProcessFailure)>,
+//!     output: &mut [f32],
+//! ) {
+//!     let outcome = recovery.process(processor, |_started| {
+//!         // _started.process(...)
+//!         Ok(ProcessStatus::Continue)
+//!     });
+//!
+//!     if outcome == ProcessOutcome::Bypassed {
+//!         // The plugin is errored: output silence instead of its output.
+//!         output.fill(0.0);
+//!     }
+//! }
+//! ```
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::{PluginAudioProcessor, ProcessStatus, StartedPluginAudioProcessor};
+use std::fmt::{Debug, Formatter};
+
+/// The action a [`ProcessRecovery`] took after a failure.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RecoveryAction {
+    /// Processing was stopped and the plugin was reset. Processing will be started again on the
+    /// next block.
+    Restart,
+    /// The plugin is bypassed for all subsequent blocks, until the host
+    /// [clears the error](ProcessRecovery::clear_error).
+    Bypass,
+}
+
+/// A report of a failure, given to the callback of a [`ProcessRecovery`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProcessFailure {
+    /// The error that occurred.
+    ///
+    /// This is [`PluginInstanceError::StartProcessingFailed`] if the plugin failed to restart
+    /// processing.
+    pub error: PluginInstanceError,
+    /// The action that was taken to recover from this failure.
+    pub action: RecoveryAction,
+    /// How many restarts were attempted since the error was last cleared, including this one if
+    /// [`action`](Self::action) is [`RecoveryAction::Restart`].
+    pub restarts: u32,
+    /// The total number of failures since this helper was created.
+    pub total_failures: u64,
+}
+
+/// The outcome of a block processed through a [`ProcessRecovery`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProcessOutcome {
+    /// The plugin processed the block successfully, and returned the given status.
+    Processed(ProcessStatus),
+    /// The plugin did not process the block, either because it just failed to, or because it is
+    /// errored.
+    ///
+    /// The contents of the output buffers are unspecified: hosts should output silence or the dry
+    /// signal instead.
+    Bypassed,
+}
+
+/// A helper tracking failures of a plugin's audio processor, and recovering from them.
+///
+/// See the [module documentation](self) for more information.
+///
+/// Note that the callback is called on the audio thread: it must therefore be realtime-safe, and
+/// should only e.g. set an atomic flag or push to a lock-free queue.
+pub struct ProcessRecovery<F> {
+    max_restarts: u32,
+    restarts: u32,
+    total_failures: u64,
+    is_errored: bool,
+    restart_pending: bool,
+    on_failure: F,
+}
+
+impl<F: FnMut(&ProcessFailure)> ProcessRecovery<F> {
+    /// Creates a new recovery helper, which calls the given callback on every failure.
+    ///
+    /// By default, failing plugins are bypassed right away, and no restarts are attempted.
+    pub fn new(on_failure: F) -> Self {
+        Self {
+            max_restarts: 0,
+            restarts: 0,
+            total_failures: 0,
+            is_errored: false,
+            restart_pending: false,
+            on_failure,
+        }
+    }
+
+    /// Sets how many times processing may be restarted after a failure, before the plugin is
+    /// bypassed for good.
+    ///
+    /// The restart count is only reset by [`clear_error`](Self::clear_error), so that plugins
+    /// failing periodically end up being bypassed too.
+    #[inline]
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Returns `true` if the plugin failed, and is now bypassed.
+    #[inline]
+    pub fn is_errored(&self) -> bool {
+        self.is_errored
+    }
+
+    /// Returns `true` if processing was stopped to be restarted on the next block.
+    #[inline]
+    pub fn is_restart_pending(&self) -> bool {
+        self.restart_pending
+    }
+
+    /// Returns how many restarts were attempted since the error was last cleared.
+    #[inline]
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Returns the total number of failures since this helper was created.
+    #[inline]
+    pub fn total_failures(&self) -> u64 {
+        self.total_failures
+    }
+
+    /// Marks the plugin as errored, bypassing it for all subsequent blocks.
+    ///
+    /// This can be used by hosts that detected a failure by other means, e.g. using a
+    /// [`ProcessWatchdog`](super::watchdog::ProcessWatchdog).
+    #[inline]
+    pub fn set_errored(&mut self) {
+        self.is_errored = true;
+        self.restart_pending = false;
+    }
+
+    /// Clears the error state, and resets the restart count.
+    ///
+    /// The plugin is processed again on the next block. Hosts should only do this after the cause
+    /// of the failure is dealt with, e.g. after the plugin was deactivated and re-activated, or
+    /// when the user explicitly asks to.
+    #[inline]
+    pub fn clear_error(&mut self) {
+        self.is_errored = false;
+        self.restarts = 0;
+    }
+
+    /// Processes a block through the given closure, unless the plugin is errored.
+    ///
+    /// The processor is started first, if needed. If the plugin is errored, the closure isn't
+    /// called and this returns [`ProcessOutcome::Bypassed`].
+    ///
+    /// If the closure fails, the failure is reported to the callback, and the plugin is either
+    /// bypassed or restarted, depending on the [maximum restart count](Self::with_max_restarts).
+    /// If it is restarted, processing is stopped and the plugin is reset right away, and processing
+    /// is started again on the next block.
+    pub fn process<H: HostHandlers>(
+        &mut self,
+        processor: &mut PluginAudioProcessor<H>,
+        process: impl FnOnce(
+            &mut StartedPluginAudioProcessor<H>,
+        ) -> Result<ProcessStatus, PluginInstanceError>,
+    ) -> ProcessOutcome {
+        if self.is_errored {
+            return ProcessOutcome::Bypassed;
+        }
+
+        let started = match processor.ensure_processing_started() {
+            Ok(started) => started,
+            Err(error) => {
+                self.fail(error, RecoveryAction::Bypass);
+                return ProcessOutcome::Bypassed;
+            }
+        };
+
+        self.restart_pending = false;
+
+        match process(started) {
+            Ok(status) => ProcessOutcome::Processed(status),
+            Err(error) => {
+                if self.restarts < self.max_restarts {
+                    self.restarts += 1;
+                    self.restart_pending = true;
+
+                    processor.ensure_processing_stopped().reset();
+                    self.fail(error, RecoveryAction::Restart);
+                } else {
+                    self.fail(error, RecoveryAction::Bypass);
+                }
+
+                ProcessOutcome::Bypassed
+            }
+        }
+    }
+
+    fn fail(&mut self, error: PluginInstanceError, action: RecoveryAction) {
+        self.total_failures += 1;
+
+        if action == RecoveryAction::Bypass {
+            self.set_errored();
+        }
+
+        (self.on_failure)(&ProcessFailure {
+            error,
+            action,
+            restarts: self.restarts,
+            total_failures: self.total_failures,
+        });
+    }
+}
+
+impl<F> Debug for ProcessRecovery<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessRecovery")
+            .field("max_restarts", &self.max_restarts)
+            .field("restarts", &self.restarts)
+            .field("total_failures", &self.total_failures)
+            .field("is_errored", &self.is_errored)
+            .field("restart_pending", &self.restart_pending)
+            .finish_non_exhaustive()
+    }
+}
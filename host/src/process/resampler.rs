@@ -0,0 +1,637 @@
+//! An adapter that converts audio between the sample rate of an audio device and the sample rate
+//! a plugin was activated with.
+//!
+//! Hosts are usually expected to activate plugins at the sample rate of the audio device they
+//! drive. Some hosts however cannot reconfigure their device, or need to run a plugin at a fixed
+//! sample rate regardless (e.g. 48 kHz) when the device runs at another one (e.g. 44.1 kHz). The
+//! [`ResamplingAdapter`] allows to do that, by converting the audio inputs to the plugin's sample
+//! rate, processing them, and converting the plugin's outputs back to the device's sample rate.
+//!
+//! Conversion is performed by a built-in, windowed-sinc polyphase resampler. This adds a
+//! constant [latency](ResamplingAdapter::latency), which hosts should add to the latency reported
+//! by the plugin itself.
+//!
+//! This module is only available when the `resampling` feature is enabled.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::process::resampler::ResamplingAdapter;
+//!
+//! // A stereo effect running at 48 kHz, driven by a 44.1 kHz device.
+//! let adapter = ResamplingAdapter::new(44_100.0, 48_000.0, &[2], &[2], 512);
+//!
+//! // This is the maximum frames count the plugin must be activated with.
+//! assert!(adapter.max_plugin_frames() >= 558);
+//! ```
+
+use crate::host::HostHandlers;
+use crate::plugin::PluginInstanceError;
+use crate::process::audio_buffers::{
+    AudioPortBuffer, AudioPortBufferType, AudioPorts, InputChannel,
+};
+use crate::process::planar;
+use crate::process::{ProcessParams, ProcessStatus, StartedPluginAudioProcessor};
+use clack_common::events::io::{EventBuffer, InputEvents, OutputEvents};
+use std::f64::consts::PI;
+
+/// Half the number of taps of the interpolation filter.
+const HALF_TAPS: usize = 16;
+/// The number of taps of the interpolation filter.
+const TAPS: usize = HALF_TAPS * 2;
+/// The number of precomputed filter phases. Coefficients are linearly interpolated in-between.
+const PHASES: usize = 256;
+
+/// A buffering adapter that runs a plugin at a different sample rate than the audio device.
+///
+/// See the [module documentation](self) for more information.
+///
+/// Just like for the [`FixedBlockAdapter`](super::block_adapter::FixedBlockAdapter), the host's
+/// audio buffers are given to [`process`](Self::process) as one [planar buffer](super::planar)
+/// per port.
+///
+/// The number of frames given to the plugin varies from one block to the next, in order to match
+/// the sample rate ratio. It never exceeds [`max_plugin_frames`](Self::max_plugin_frames),
+/// which should be used as the plugin's maximum frames count when activating it.
+///
+/// # Realtime Safety
+///
+/// See the [realtime safety of planar adapters](super::planar#realtime-safety).
+pub struct ResamplingAdapter {
+    device_sample_rate: f64,
+    plugin_sample_rate: f64,
+    max_device_frames: usize,
+    max_plugin_frames: usize,
+    latency: usize,
+
+    input_channel_counts: Vec<usize>,
+    output_channel_counts: Vec<usize>,
+    input_resampler: Resampler,
+    output_resampler: Resampler,
+    inputs: Vec<Vec<f32>>,
+    outputs: Vec<Vec<f32>>,
+    input_ports: AudioPorts,
+    output_ports: AudioPorts,
+
+    pending_events: EventBuffer,
+    block_events: EventBuffer,
+    output_events: EventBuffer,
+
+    device_frames: u64,
+    plugin_frames: u64,
+    steady_time: Option<u64>,
+    last_status: ProcessStatus,
+}
+
+impl ResamplingAdapter {
+    /// Creates a new adapter, which converts audio from and to the given `device_sample_rate`,
+    /// for a plugin activated at the given `plugin_sample_rate`.
+    ///
+    /// The number of channels of each of the plugin's input and output ports are given by
+    /// `input_channel_counts` and `output_channel_counts` respectively. `max_device_frames` is the
+    /// maximum number of frames the host will ever give to [`process`](Self::process) at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either sample rate isn't a strictly positive number.
+    pub fn new(
+        device_sample_rate: f64,
+        plugin_sample_rate: f64,
+        input_channel_counts: &[u32],
+        output_channel_counts: &[u32],
+        max_device_frames: u32,
+    ) -> Self {
+        assert!(
+            device_sample_rate > 0.0 && plugin_sample_rate > 0.0,
+            "Sample rates must be strictly positive"
+        );
+
+        let max_device_frames = max_device_frames as usize;
+        let plugin_per_device = plugin_sample_rate / device_sample_rate;
+        let max_plugin_frames = (max_device_frames as f64 * plugin_per_device).ceil() as usize + 2;
+
+        // The inputs are delayed enough for both filters to always have all the samples they
+        // need. Because the filters are symmetric, this is also the total latency.
+        let latency = HALF_TAPS + (HALF_TAPS as f64 / plugin_per_device).ceil() as usize + 3;
+
+        let input_channel_counts: Vec<usize> =
+            input_channel_counts.iter().map(|c| *c as usize).collect();
+        let output_channel_counts: Vec<usize> =
+            output_channel_counts.iter().map(|c| *c as usize).collect();
+
+        let buffers = |counts: &[usize]| -> Vec<Vec<f32>> {
+            counts
+                .iter()
+                .map(|c| vec![0.0; c * max_plugin_frames])
+                .collect()
+        };
+
+        let mut adapter = Self {
+            device_sample_rate,
+            plugin_sample_rate,
+            max_device_frames,
+            max_plugin_frames,
+            latency,
+            input_resampler: Resampler::new(
+                1.0 / plugin_per_device,
+                input_channel_counts.iter().sum(),
+                max_device_frames + latency,
+            ),
+            output_resampler: Resampler::new(
+                plugin_per_device,
+                output_channel_counts.iter().sum(),
+                max_plugin_frames,
+            ),
+            inputs: buffers(&input_channel_counts),
+            outputs: buffers(&output_channel_counts),
+            input_ports: AudioPorts::with_capacity(
+                input_channel_counts.iter().sum(),
+                input_channel_counts.len(),
+            ),
+            output_ports: AudioPorts::with_capacity(
+                output_channel_counts.iter().sum(),
+                output_channel_counts.len(),
+            ),
+            input_channel_counts,
+            output_channel_counts,
+            pending_events: EventBuffer::with_capacity(256),
+            block_events: EventBuffer::with_capacity(256),
+            output_events: EventBuffer::with_capacity(256),
+            device_frames: 0,
+            plugin_frames: 0,
+            steady_time: None,
+            last_status: ProcessStatus::Continue,
+        };
+
+        adapter.reset();
+        adapter
+    }
+
+    /// Returns the sample rate of the audio device.
+    #[inline]
+    pub fn device_sample_rate(&self) -> f64 {
+        self.device_sample_rate
+    }
+
+    /// Returns the sample rate the plugin runs at.
+    #[inline]
+    pub fn plugin_sample_rate(&self) -> f64 {
+        self.plugin_sample_rate
+    }
+
+    /// Returns the maximum number of frames the plugin can be given in a single block.
+    ///
+    /// This should be used as the plugin's
+    /// [`max_frames_count`](crate::prelude::PluginAudioConfiguration::max_frames_count) when
+    /// activating it. Note the plugin may also be given less frames than the host's blocks, or
+    /// none at all, so the minimum frames count should be `1`.
+    #[inline]
+    pub fn max_plugin_frames(&self) -> u32 {
+        self.max_plugin_frames as u32
+    }
+
+    /// Returns the latency added by this adapter, in frames at the device's sample rate.
+    ///
+    /// Hosts should add it to the latency reported by the plugin (e.g. through the `latency`
+    /// extension), after converting the latter to the device's sample rate using
+    /// [`to_device_frames`](Self::to_device_frames).
+    #[inline]
+    pub fn latency(&self) -> u32 {
+        self.latency as u32
+    }
+
+    /// Converts a number of frames at the plugin's sample rate to the matching number of frames
+    /// at the device's sample rate, rounded up.
+    #[inline]
+    pub fn to_device_frames(&self, plugin_frames: u32) -> u32 {
+        (plugin_frames as f64 * self.device_sample_rate / self.plugin_sample_rate).ceil() as u32
+    }
+
+    /// Clears all buffered audio and events.
+    ///
+    /// This should be called whenever the plugin itself is reset, or when processing is
+    /// restarted after being stopped.
+    pub fn reset(&mut self) {
+        self.input_resampler.reset();
+        self.output_resampler.reset();
+        self.input_resampler.push_silence(self.latency);
+
+        self.pending_events.clear();
+        self.block_events.clear();
+        self.output_events.clear();
+
+        self.device_frames = 0;
+        self.plugin_frames = 0;
+        self.steady_time = None;
+        self.last_status = ProcessStatus::Continue;
+    }
+
+    /// Processes a block of audio frames and events at the device's sample rate, through the given
+    /// plugin audio processor.
+    ///
+    /// Each of the given `audio_inputs` and `audio_outputs` buffers is the
+    /// [planar buffer](super::planar) of the matching port. The number of processed frames is
+    /// deduced from the size of those buffers.
+    ///
+    /// The steady time counter given to the plugin is derived from the first steady time of
+    /// `params`, scaled to the plugin's sample rate, and then advanced by the number of frames the
+    /// plugin processed. The steady time counter of `params` itself is advanced by the number of
+    /// device frames after this call, just like
+    /// [`ProcessBatch::process`](crate::process::ProcessBatch::process). Transport information
+    /// is passed as-is, as it doesn't depend on the sample rate.
+    ///
+    /// Input events are re-timed to the plugin's sample rate, alongside the audio. Output events
+    /// produced by the plugin are re-timed to the device's sample rate, and clamped to the
+    /// current block.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin's `process` call failed. In this case, the adapter
+    /// should be [reset](Self::reset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of given port buffers doesn't match the channel counts given to
+    /// [`new`](Self::new), if the port buffers don't all hold the same number of frames, or if
+    /// they hold more than the maximum device frames count given to [`new`](Self::new).
+    pub fn process<H: HostHandlers>(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+        params: &mut ProcessParams,
+        audio_inputs: &[&[f32]],
+        audio_outputs: &mut [&mut [f32]],
+        input_events: &InputEvents,
+        output_events: &mut OutputEvents,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let frames_count = planar::frames_count(
+            audio_inputs,
+            audio_outputs,
+            &self.input_channel_counts,
+            &self.output_channel_counts,
+        );
+        assert!(
+            frames_count <= self.max_device_frames,
+            "Too many frames for this adapter"
+        );
+
+        if self.device_frames == 0 {
+            self.steady_time = params.steady_time.map(|t| {
+                (t as f64 * self.plugin_sample_rate / self.device_sample_rate).round() as u64
+            });
+        }
+
+        // How many plugin frames are needed to produce all the device's output frames.
+        let plugin_frames_count =
+            self.output_resampler
+                .input_needed_for(frames_count)
+                .saturating_sub(self.output_resampler.pushed) as usize;
+
+        let mut offset = 0;
+        for (port, channels) in audio_inputs.iter().zip(&self.input_channel_counts) {
+            for channel in port.chunks_exact(frames_count.max(1)).take(*channels) {
+                self.input_resampler.push(offset, channel);
+                offset += 1;
+            }
+        }
+        self.input_resampler.commit(frames_count);
+
+        self.queue_input_events(input_events);
+
+        let status = if plugin_frames_count > 0 {
+            let status = self.process_plugin(processor, params, plugin_frames_count);
+            self.forward_output_events(output_events, frames_count, plugin_frames_count);
+            Some(status?)
+        } else {
+            None
+        };
+
+        let mut offset = 0;
+        for (port, channels) in audio_outputs.iter_mut().zip(&self.output_channel_counts) {
+            for channel in port.chunks_exact_mut(frames_count.max(1)).take(*channels) {
+                self.output_resampler.pull(offset, channel);
+                offset += 1;
+            }
+        }
+        self.output_resampler.advance(frames_count);
+
+        self.device_frames += frames_count as u64;
+        params.advance(frames_count as u32);
+
+        Ok(status.unwrap_or(self.last_status))
+    }
+
+    fn process_plugin<H: HostHandlers>(
+        &mut self,
+        processor: &mut StartedPluginAudioProcessor<H>,
+        params: &ProcessParams,
+        plugin_frames_count: usize,
+    ) -> Result<ProcessStatus, PluginInstanceError> {
+        let mut offset = 0;
+        for (port, channels) in self.inputs.iter_mut().zip(&self.input_channel_counts) {
+            for channel in port.chunks_exact_mut(plugin_frames_count).take(*channels) {
+                self.input_resampler.pull(offset, channel);
+                offset += 1;
+            }
+        }
+        self.input_resampler.advance(plugin_frames_count);
+        self.split_block_events(plugin_frames_count as u32);
+
+        let inputs = self.input_ports.with_input_buffers(
+            self.inputs
+                .iter_mut()
+                .zip(&self.input_channel_counts)
+                .map(|(port, channels)| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_input_only(
+                        port.chunks_exact_mut(plugin_frames_count)
+                            .take(*channels)
+                            .map(InputChannel::variable),
+                    ),
+                }),
+        );
+
+        let mut outputs = self.output_ports.with_output_buffers(
+            self.outputs
+                .iter_mut()
+                .zip(&self.output_channel_counts)
+                .map(|(port, channels)| AudioPortBuffer {
+                    latency: 0,
+                    channels: AudioPortBufferType::f32_output_only(
+                        port.chunks_exact_mut(plugin_frames_count).take(*channels),
+                    ),
+                }),
+        );
+
+        let result = processor.process(
+            &inputs,
+            &mut outputs,
+            &self.block_events.as_input(),
+            &mut self.output_events.as_output(),
+            self.steady_time,
+            params.transport.as_ref(),
+        );
+
+        self.block_events.clear();
+        self.plugin_frames += plugin_frames_count as u64;
+        if let Some(steady_time) = &mut self.steady_time {
+            *steady_time = steady_time.wrapping_add(plugin_frames_count as u64);
+        }
+
+        let mut offset = 0;
+        for (port, channels) in self.outputs.iter().zip(&self.output_channel_counts) {
+            for channel in port.chunks_exact(plugin_frames_count).take(*channels) {
+                self.output_resampler.push(offset, channel);
+                offset += 1;
+            }
+        }
+        self.output_resampler.commit(plugin_frames_count);
+
+        let status = result?;
+        self.last_status = status;
+        Ok(status)
+    }
+
+    /// Converts the given input events to the plugin's sample rate, and queues them.
+    ///
+    /// Queued event times are relative to the start of the next block to be given to the plugin.
+    fn queue_input_events(&mut self, input_events: &InputEvents) {
+        // Device input frame `d` ends up at the plugin's frame `(d + latency) * ratio`.
+        let ratio = self.plugin_sample_rate / self.device_sample_rate;
+        let first_frame = (self.device_frames + self.latency as u64) as f64;
+
+        for event in input_events {
+            let device_time = first_frame + event.header().time() as f64;
+            let plugin_time = (device_time * ratio).round() as u64;
+            let time = plugin_time.saturating_sub(self.plugin_frames);
+
+            self.pending_events
+                .push_with_time(event, time.min(u32::MAX as u64) as u32);
+        }
+    }
+
+    /// Moves the queued events that belong to the next plugin block of `frames_count` frames to
+    /// the block's event buffer.
+    fn split_block_events(&mut self, frames_count: u32) {
+        self.output_events.clear();
+
+        for event in &self.pending_events {
+            let time = event.header().time();
+            if time < frames_count {
+                self.block_events.push(event);
+            } else {
+                // The output buffer is free at this point, use it as scratch space.
+                self.output_events
+                    .push_with_time(event, time - frames_count);
+            }
+        }
+
+        core::mem::swap(&mut self.pending_events, &mut self.output_events);
+        self.output_events.clear();
+    }
+
+    /// Forwards the events produced by the plugin during its last block of `plugin_frames_count`
+    /// frames.
+    fn forward_output_events(
+        &mut self,
+        output_events: &mut OutputEvents,
+        frames_count: usize,
+        plugin_frames_count: usize,
+    ) {
+        // Plugin output frame `p` ends up at the device's frame `p / ratio`.
+        let ratio = self.device_sample_rate / self.plugin_sample_rate;
+        let block_start = self.plugin_frames - plugin_frames_count as u64;
+        let last_frame = frames_count.saturating_sub(1) as f64;
+
+        for event in &self.output_events {
+            let plugin_time = (block_start + event.header().time() as u64) as f64;
+            let time = (plugin_time * ratio - self.device_frames as f64)
+                .round()
+                .clamp(0.0, last_frame);
+
+            self.block_events.push_with_time(event, time as u32);
+        }
+
+        for event in &self.block_events {
+            // Events are dropped if the host's output buffer is full.
+            let _ = output_events.try_push(event);
+        }
+
+        self.block_events.clear();
+        self.output_events.clear();
+    }
+}
+
+/// A streaming, multichannel windowed-sinc resampler.
+///
+/// Input samples are pushed in, and output samples are computed at positions that are
+/// `ratio` input frames apart. Output frame `n` is located at input frame `n * ratio`, and can
+/// only be computed once all input frames up to `n * ratio + HALF_TAPS` have been pushed.
+struct Resampler {
+    /// The number of input frames per output frame.
+    ratio: f64,
+    /// The filter coefficients, for each phase in `0..=PHASES`.
+    table: Vec<[f32; TAPS]>,
+    /// The input samples that may still be needed, for each channel.
+    history: Vec<Vec<f32>>,
+    /// The absolute input frame index of the first frame in `history`.
+    base: i64,
+    /// The total number of input frames pushed.
+    pushed: i64,
+    /// The absolute index of the next output frame.
+    produced: i64,
+}
+
+impl Resampler {
+    fn new(ratio: f64, channel_count: usize, max_input_frames: usize) -> Self {
+        // Lower the cutoff frequency when downsampling, to avoid aliasing.
+        let cutoff = 0.95 * (1.0 / ratio).min(1.0);
+
+        let table = (0..=PHASES)
+            .map(|phase| {
+                let fraction = phase as f64 / PHASES as f64;
+                core::array::from_fn(|tap| {
+                    let t = fraction + (HALF_TAPS - 1) as f64 - tap as f64;
+                    windowed_sinc(t, cutoff) as f32
+                })
+            })
+            .collect();
+
+        let capacity = max_input_frames + TAPS * 4;
+
+        Self {
+            ratio,
+            table,
+            history: (0..channel_count)
+                .map(|_| Vec::with_capacity(capacity))
+                .collect(),
+            base: 0,
+            pushed: 0,
+            produced: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.produced = 0;
+        self.pushed = 0;
+
+        // Frames before the start of the stream are silent.
+        self.base = -(HALF_TAPS as i64);
+        for channel in &mut self.history {
+            channel.clear();
+            channel.resize(HALF_TAPS, 0.0);
+        }
+    }
+
+    fn push_silence(&mut self, frames_count: usize) {
+        for channel in &mut self.history {
+            channel.resize(channel.len() + frames_count, 0.0);
+        }
+
+        self.commit(frames_count);
+    }
+
+    /// Pushes the given input samples to the given channel. This must be followed by a call to
+    /// [`commit`](Self::commit), once all channels have been pushed.
+    #[inline]
+    fn push(&mut self, channel: usize, samples: &[f32]) {
+        if let Some(history) = self.history.get_mut(channel) {
+            history.extend_from_slice(samples);
+        }
+    }
+
+    #[inline]
+    fn commit(&mut self, frames_count: usize) {
+        self.pushed += frames_count as i64;
+
+        // Channels that weren't given any samples are silent.
+        let len = (self.pushed - self.base) as usize;
+        for channel in &mut self.history {
+            channel.resize(len, 0.0);
+        }
+    }
+
+    /// Returns the total number of input frames needed to compute the next `frames_count` output
+    /// frames.
+    #[inline]
+    fn input_needed_for(&self, frames_count: usize) -> i64 {
+        if frames_count == 0 {
+            return self.pushed;
+        }
+
+        let last = (self.produced + frames_count as i64 - 1) as f64 * self.ratio;
+        last.floor() as i64 + HALF_TAPS as i64 + 1
+    }
+
+    /// Computes the next output frames for the given channel. This must be followed by a call to
+    /// [`advance`](Self::advance), once all channels have been pulled.
+    fn pull(&self, channel: usize, output: &mut [f32]) {
+        debug_assert!(self.pushed >= self.input_needed_for(output.len()));
+
+        let Some(history) = self.history.get(channel) else {
+            output.fill(0.0);
+            return;
+        };
+
+        for (n, sample) in (self.produced..).zip(output.iter_mut()) {
+            let position = n as f64 * self.ratio;
+            let index = position.floor();
+            let phase = (position - index) * PHASES as f64;
+            let phase_index = phase.floor();
+            let phase_fraction = (phase - phase_index) as f32;
+
+            let phase_index = phase_index as usize;
+            let first = &self.table[phase_index];
+            let second = &self.table[(phase_index + 1).min(PHASES)];
+
+            let start = index as i64 - HALF_TAPS as i64 + 1 - self.base;
+            let mut value = 0.0;
+            for tap in 0..TAPS {
+                let input = usize::try_from(start + tap as i64)
+                    .ok()
+                    .and_then(|i| history.get(i))
+                    .copied()
+                    .unwrap_or(0.0);
+
+                let coefficient = first[tap] + (second[tap] - first[tap]) * phase_fraction;
+                value += input * coefficient;
+            }
+
+            *sample = value;
+        }
+    }
+
+    /// Advances the output position by the given number of frames, and discards the input frames
+    /// that are not needed anymore.
+    fn advance(&mut self, frames_count: usize) {
+        self.produced += frames_count as i64;
+
+        let first_needed =
+            (self.produced as f64 * self.ratio).floor() as i64 - HALF_TAPS as i64 + 1;
+        let discarded = (first_needed - self.base).clamp(0, self.pushed - self.base) as usize;
+
+        if discarded > 0 {
+            for channel in &mut self.history {
+                channel.drain(..discarded.min(channel.len()));
+            }
+
+            self.base += discarded as i64;
+        }
+    }
+}
+
+/// A sinc function, with the given normalized cutoff frequency, windowed by a Blackman window of
+/// `HALF_TAPS` frames on each side.
+fn windowed_sinc(t: f64, cutoff: f64) -> f64 {
+    let half_width = HALF_TAPS as f64;
+    if t.abs() >= half_width {
+        return 0.0;
+    }
+
+    let x = PI * cutoff * t;
+    let sinc = if x.abs() < 1e-9 { 1.0 } else { x.sin() / x };
+
+    let w = PI * t / half_width;
+    let window = 0.42 + 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+
+    cutoff * sinc * window
+}
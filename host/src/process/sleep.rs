@@ -0,0 +1,280 @@
+//! Silence detection and automatic sleeping of plugin audio processors.
+
+use crate::process::ProcessStatus;
+
+/// What a host should do with a plugin's audio processor after it processed a block, as decided
+/// by an [`AutoSleep`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SleepDecision {
+    /// The plugin must keep processing.
+    KeepProcessing,
+    /// The plugin can be put to sleep, by [stopping](crate::process::PluginAudioProcessor::stop_processing)
+    /// its audio processor.
+    Sleep,
+}
+
+/// A helper implementing the CLAP sleep semantics, to decide when a plugin can be put to sleep,
+/// and when it must be woken up again.
+///
+/// After each `process()` call, the host gives the returned [`ProcessStatus`] to
+/// [`after_process`](Self::after_process), alongside whether the inputs and outputs were
+/// [silent](Self::is_silent). If a [`SleepDecision::Sleep`] is returned, the host can stop the
+/// plugin's audio processor, and skip processing it altogether.
+///
+/// While the plugin is asleep, the host must call [`should_wake`](Self::should_wake) every
+/// block, before processing. If it returns `true`, the host must start the plugin's audio
+/// processor again, and process the block as usual.
+///
+/// The statuses are interpreted as follows:
+///
+/// * [`ProcessStatus::Continue`]: the plugin keeps processing;
+/// * [`ProcessStatus::ContinueIfNotQuiet`]: the plugin sleeps as soon as its outputs are silent;
+/// * [`ProcessStatus::Tail`]: the plugin keeps processing until its tail is over, as reported
+///   with [`set_tail`](Self::set_tail). The tail only starts counting down once the inputs are
+///   silent, and starts over every time they aren't. If the tail length is unknown, this behaves
+///   like [`ProcessStatus::ContinueIfNotQuiet`];
+/// * [`ProcessStatus::Sleep`]: the plugin sleeps right away.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::sleep::{AutoSleep, SleepDecision};
+/// use clack_host::process::ProcessStatus;
+///
+/// let mut auto_sleep = AutoSleep::new();
+/// let output = [0.0f32; 256];
+///
+/// let is_silent = auto_sleep.is_silent([&output[..]]);
+/// assert_eq!(
+///     auto_sleep.after_process(ProcessStatus::ContinueIfNotQuiet, 256, true, is_silent),
+///     SleepDecision::Sleep
+/// );
+/// assert!(auto_sleep.is_asleep());
+///
+/// // Nothing happened during this block, the plugin can keep sleeping.
+/// assert!(!auto_sleep.should_wake(false, true, false));
+/// // A new event arrived: the plugin must be woken up to process it.
+/// assert!(auto_sleep.should_wake(true, true, false));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoSleep {
+    silence_threshold: f32,
+    tail_frames: Option<u32>,
+    tail_remaining: Option<u64>,
+    is_asleep: bool,
+}
+
+impl AutoSleep {
+    /// The default threshold under which samples are considered silent, roughly -100 dB.
+    pub const DEFAULT_SILENCE_THRESHOLD: f32 = 1e-5;
+
+    /// Creates a new helper, for a plugin that is currently awake.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            silence_threshold: Self::DEFAULT_SILENCE_THRESHOLD,
+            tail_frames: None,
+            tail_remaining: None,
+            is_asleep: false,
+        }
+    }
+
+    /// Sets the threshold under which samples are considered silent.
+    ///
+    /// This is compared with the absolute value of each sample. The default is
+    /// [`DEFAULT_SILENCE_THRESHOLD`](Self::DEFAULT_SILENCE_THRESHOLD).
+    #[inline]
+    pub fn with_silence_threshold(mut self, threshold: f32) -> Self {
+        self.silence_threshold = threshold.abs();
+        self
+    }
+
+    /// Sets the plugin's current tail length, in frames, as reported by the plugin through the
+    /// `tail` extension.
+    ///
+    /// A value of [`u32::MAX`] means the tail is infinite, and [`None`] means it is unknown, e.g.
+    /// if the plugin doesn't implement the `tail` extension.
+    ///
+    /// This should be called again every time the plugin notifies the host of a tail change.
+    #[inline]
+    pub fn set_tail(&mut self, tail_frames: Option<u32>) {
+        self.tail_frames = tail_frames;
+        self.tail_remaining = None;
+    }
+
+    /// Returns `true` if the plugin is currently asleep.
+    #[inline]
+    pub fn is_asleep(&self) -> bool {
+        self.is_asleep
+    }
+
+    /// Returns `true` if all the samples of the given channels are silent, i.e. under the
+    /// configured silence threshold.
+    pub fn is_silent<'a>(&self, channels: impl IntoIterator<Item = &'a [f32]>) -> bool {
+        channels
+            .into_iter()
+            .all(|channel| channel.iter().all(|s| s.abs() <= self.silence_threshold))
+    }
+
+    /// Returns `true` if all the samples of the given 64-bit channels are silent, i.e. under the
+    /// configured silence threshold.
+    pub fn is_silent_f64<'a>(&self, channels: impl IntoIterator<Item = &'a [f64]>) -> bool {
+        let threshold = self.silence_threshold as f64;
+
+        channels
+            .into_iter()
+            .all(|channel| channel.iter().all(|s| s.abs() <= threshold))
+    }
+
+    /// Decides whether the plugin can be put to sleep, after it processed a block of
+    /// `frames_count` frames and returned the given `status`.
+    ///
+    /// `inputs_silent` and `outputs_silent` indicate whether all of the plugin's inputs and
+    /// outputs, respectively, were silent during that block, e.g. as computed by
+    /// [`is_silent`](Self::is_silent). Plugins without any audio input should be considered to
+    /// have silent inputs.
+    pub fn after_process(
+        &mut self,
+        status: ProcessStatus,
+        frames_count: u32,
+        inputs_silent: bool,
+        outputs_silent: bool,
+    ) -> SleepDecision {
+        let can_sleep = match status {
+            ProcessStatus::Continue => {
+                self.tail_remaining = None;
+                false
+            }
+            ProcessStatus::ContinueIfNotQuiet => {
+                self.tail_remaining = None;
+                outputs_silent
+            }
+            ProcessStatus::Tail => match self.tail_frames {
+                None => outputs_silent,
+                Some(u32::MAX) => false,
+                // The tail only starts once the input is silent.
+                Some(_) if !inputs_silent => {
+                    self.tail_remaining = None;
+                    false
+                }
+                Some(tail_frames) => {
+                    let remaining = self.tail_remaining.get_or_insert(tail_frames as u64);
+                    *remaining = remaining.saturating_sub(frames_count as u64);
+                    *remaining == 0
+                }
+            },
+            ProcessStatus::Sleep => true,
+        };
+
+        if can_sleep {
+            self.is_asleep = true;
+            self.tail_remaining = None;
+            SleepDecision::Sleep
+        } else {
+            SleepDecision::KeepProcessing
+        }
+    }
+
+    /// Decides whether a sleeping plugin must be woken up to process the current block.
+    ///
+    /// The plugin must be woken up if there are `has_input_events` to give to it, if its audio
+    /// inputs aren't `inputs_silent`, or if it explicitly requested to process through
+    /// `request_process` since the last block (`process_requested`).
+    ///
+    /// This returns `true` if the plugin must be woken up, and `false` if it can keep sleeping.
+    /// This always returns `true` if the plugin isn't asleep.
+    pub fn should_wake(
+        &mut self,
+        has_input_events: bool,
+        inputs_silent: bool,
+        process_requested: bool,
+    ) -> bool {
+        if has_input_events || !inputs_silent || process_requested {
+            self.is_asleep = false;
+        }
+
+        !self.is_asleep
+    }
+
+    /// Marks the plugin as awake, e.g. after it was re-activated.
+    #[inline]
+    pub fn wake(&mut self) {
+        self.is_asleep = false;
+        self.tail_remaining = None;
+    }
+}
+
+impl Default for AutoSleep {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn follows_process_statuses() {
+        let mut auto_sleep = AutoSleep::new();
+
+        assert!(!auto_sleep.is_silent([&[0.0, 0.5][..]]));
+        assert!(auto_sleep.is_silent([&[0.0, 1e-6][..], &[][..]]));
+        assert!(auto_sleep.is_silent_f64([&[0.0, -1e-6][..]]));
+
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Continue, 64, true, true),
+            SleepDecision::KeepProcessing
+        );
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::ContinueIfNotQuiet, 64, true, false),
+            SleepDecision::KeepProcessing
+        );
+        assert!(!auto_sleep.is_asleep());
+
+        // Tails are counted down once the inputs are silent, regardless of the outputs.
+        auto_sleep.set_tail(Some(100));
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, 64, true, true),
+            SleepDecision::KeepProcessing
+        );
+
+        // Non-silent inputs start the tail over.
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, 64, false, true),
+            SleepDecision::KeepProcessing
+        );
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, 64, true, true),
+            SleepDecision::KeepProcessing
+        );
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, 64, true, false),
+            SleepDecision::Sleep
+        );
+        assert!(auto_sleep.is_asleep());
+
+        assert!(!auto_sleep.should_wake(false, true, false));
+        assert!(auto_sleep.should_wake(false, true, true));
+        assert!(!auto_sleep.is_asleep());
+
+        // Infinite tails never sleep, unknown tails sleep on silence.
+        auto_sleep.set_tail(Some(u32::MAX));
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, u32::MAX, true, true),
+            SleepDecision::KeepProcessing
+        );
+        auto_sleep.set_tail(None);
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Tail, 64, true, true),
+            SleepDecision::Sleep
+        );
+
+        assert!(auto_sleep.should_wake(false, false, false));
+        assert_eq!(
+            auto_sleep.after_process(ProcessStatus::Sleep, 64, true, false),
+            SleepDecision::Sleep
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Telemetry for plugin `process()` calls, aggregated into rolling statistics.
+//!
+//! A [`ProcessTelemetry`] lives on the audio thread next to a plugin's audio processor, and
+//! measures every `process()` call it observes. Optionally, a callback can be given to receive a
+//! [`ProcessReport`] for every call.
+//!
+//! The measurements are aggregated into rolling statistics (processing load, total frames
+//! processed, last returned [`ProcessStatus`], etc.), which can be read from any other thread
+//! (e.g. the main thread, to draw CPU meters) using a [`ProcessStatsReader`].
+//!
+//! # Example
+//!
+//! ```
+//! use clack_host::process::telemetry::ProcessTelemetry;
+//! use clack_host::prelude::*;
+//!
+//! let mut telemetry = ProcessTelemetry::new(48_000.0);
+//! let stats = telemetry.stats();
+//!
+//! // On the audio thread:
+//! let status = telemetry.observe(256, || {
+//!     /* processor.process(...) */
+//!     Ok::<_, PluginInstanceError>(ProcessStatus::Continue)
+//! });
+//!
+//! // On the main thread:
+//! let snapshot = stats.snapshot();
+//! assert_eq!(snapshot.calls, 1);
+//! assert_eq!(snapshot.frames_processed, 256);
+//! assert_eq!(snapshot.last_status, Some(ProcessStatus::Continue));
+//! ```
+
+use clack_common::process::ProcessStatus;
+use clap_sys::process::CLAP_PROCESS_ERROR;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A report of a single `process()` call, given to the callback of a [`ProcessTelemetry`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ProcessReport {
+    /// How long the `process()` call took.
+    pub duration: Duration,
+    /// The number of frames that were processed.
+    pub frames_count: u32,
+    /// The status the plugin returned, or `None` if the `process()` call failed.
+    pub status: Option<ProcessStatus>,
+}
+
+impl ProcessReport {
+    /// Returns the processing load of this call, i.e. the ratio between the time the call took
+    /// and the real-time duration of the processed block, at the given sample rate.
+    ///
+    /// A load of `1.0` or more means the plugin cannot keep up with real-time processing.
+    pub fn load(&self, sample_rate: f64) -> f64 {
+        if self.frames_count == 0 || sample_rate <= 0.0 {
+            return 0.0;
+        }
+
+        self.duration.as_secs_f64() * sample_rate / self.frames_count as f64
+    }
+}
+
+/// The value of [`SharedStats::last_status`] when no call was recorded yet.
+const NO_STATUS: i32 = -1;
+
+#[derive(Default)]
+struct SharedStats {
+    calls: AtomicU64,
+    failed_calls: AtomicU64,
+    frames_processed: AtomicU64,
+    total_duration_nanos: AtomicU64,
+    average_load: AtomicU64,
+    peak_load: AtomicU64,
+    last_status: AtomicI32,
+}
+
+impl SharedStats {
+    fn new() -> Self {
+        Self {
+            last_status: AtomicI32::new(NO_STATUS),
+            ..Default::default()
+        }
+    }
+}
+
+/// Measures plugin `process()` calls on the audio thread, and aggregates them into rolling
+/// statistics.
+///
+/// The processing load of each call (see [`ProcessReport::load`]) is smoothed over time using an
+/// exponential moving average, whose [time constant](Self::with_time_constant) defaults to
+/// [`DEFAULT_TIME_CONSTANT`](Self::DEFAULT_TIME_CONSTANT). The peak load decays at the same rate.
+///
+/// Recording a call never allocates nor blocks, and only performs a handful of atomic operations.
+/// However, the optional callback is called on the audio thread, right after each `process()`
+/// call: it must therefore be realtime-safe as well.
+///
+/// See the [module documentation](self) for an example.
+pub struct ProcessTelemetry<F = fn(&ProcessReport)> {
+    sample_rate: f64,
+    time_constant: Duration,
+    stats: Arc<SharedStats>,
+    on_report: F,
+}
+
+impl ProcessTelemetry {
+    /// The default time constant used to smooth the processing load.
+    pub const DEFAULT_TIME_CONSTANT: Duration = Duration::from_millis(300);
+
+    /// Creates a new telemetry for a plugin processing at the given sample rate, without any
+    /// callback.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            time_constant: Self::DEFAULT_TIME_CONSTANT,
+            stats: Arc::new(SharedStats::new()),
+            on_report: |_| {},
+        }
+    }
+}
+
+impl<F: FnMut(&ProcessReport)> ProcessTelemetry<F> {
+    /// Sets the callback that receives a [`ProcessReport`] for every recorded `process()` call.
+    pub fn with_callback<G: FnMut(&ProcessReport)>(self, on_report: G) -> ProcessTelemetry<G> {
+        ProcessTelemetry {
+            sample_rate: self.sample_rate,
+            time_constant: self.time_constant,
+            stats: self.stats,
+            on_report,
+        }
+    }
+
+    /// Sets the time constant used to smooth the processing load and to decay the peak load.
+    ///
+    /// Longer time constants give steadier, but slower reacting, CPU meters.
+    #[inline]
+    pub fn with_time_constant(mut self, time_constant: Duration) -> Self {
+        self.time_constant = time_constant;
+        self
+    }
+
+    /// Sets the sample rate the plugin is processing at, e.g. after it has been re-activated with
+    /// a different configuration.
+    #[inline]
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Returns a reader for the statistics gathered by this telemetry.
+    ///
+    /// Readers can be freely cloned and sent to other threads.
+    #[inline]
+    pub fn stats(&self) -> ProcessStatsReader {
+        ProcessStatsReader {
+            stats: self.stats.clone(),
+        }
+    }
+
+    /// Times the given `process` closure, which processes a block of `frames_count` frames, and
+    /// records the result.
+    ///
+    /// The result of the closure is returned as-is.
+    pub fn observe<E>(
+        &mut self,
+        frames_count: u32,
+        process: impl FnOnce() -> Result<ProcessStatus, E>,
+    ) -> Result<ProcessStatus, E> {
+        let start = Instant::now();
+        let result = process();
+
+        self.record(&ProcessReport {
+            duration: start.elapsed(),
+            frames_count,
+            status: result.as_ref().ok().copied(),
+        });
+
+        result
+    }
+
+    /// Records the given `process()` call report.
+    ///
+    /// This is useful for hosts that already measure the time `process()` calls take. Otherwise,
+    /// [`observe`](Self::observe) can be used instead.
+    pub fn record(&mut self, report: &ProcessReport) {
+        let stats = &*self.stats;
+
+        // The audio thread is the only writer, so relaxed ordering is enough.
+        stats.calls.fetch_add(1, Ordering::Relaxed);
+        stats
+            .frames_processed
+            .fetch_add(report.frames_count as u64, Ordering::Relaxed);
+        stats.total_duration_nanos.fetch_add(
+            u64::try_from(report.duration.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+
+        match report.status {
+            Some(status) => stats.last_status.store(status as i32, Ordering::Relaxed),
+            None => {
+                stats.failed_calls.fetch_add(1, Ordering::Relaxed);
+                stats
+                    .last_status
+                    .store(CLAP_PROCESS_ERROR, Ordering::Relaxed);
+            }
+        }
+
+        let load = report.load(self.sample_rate);
+        let block_duration = if self.sample_rate > 0.0 {
+            report.frames_count as f64 / self.sample_rate
+        } else {
+            0.0
+        };
+
+        let time_constant = self.time_constant.as_secs_f64();
+        let decay = if time_constant > 0.0 {
+            (-block_duration / time_constant).exp()
+        } else {
+            0.0
+        };
+
+        let average = load_f64(&stats.average_load);
+        let average = if stats.calls.load(Ordering::Relaxed) == 1 {
+            load
+        } else {
+            load + (average - load) * decay
+        };
+        store_f64(&stats.average_load, average);
+
+        let peak = load_f64(&stats.peak_load) * decay;
+        store_f64(&stats.peak_load, peak.max(load).max(average));
+
+        (self.on_report)(report);
+    }
+
+    /// Resets all the gathered statistics, e.g. after the plugin has been re-activated.
+    pub fn reset(&mut self) {
+        let stats = &*self.stats;
+
+        stats.calls.store(0, Ordering::Relaxed);
+        stats.failed_calls.store(0, Ordering::Relaxed);
+        stats.frames_processed.store(0, Ordering::Relaxed);
+        stats.total_duration_nanos.store(0, Ordering::Relaxed);
+        stats.average_load.store(0, Ordering::Relaxed);
+        stats.peak_load.store(0, Ordering::Relaxed);
+        stats.last_status.store(NO_STATUS, Ordering::Relaxed);
+    }
+}
+
+impl<F> Debug for ProcessTelemetry<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessTelemetry")
+            .field("sample_rate", &self.sample_rate)
+            .field("time_constant", &self.time_constant)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A handle to the statistics gathered by a [`ProcessTelemetry`], which can be read from any
+/// thread.
+#[derive(Clone)]
+pub struct ProcessStatsReader {
+    stats: Arc<SharedStats>,
+}
+
+impl ProcessStatsReader {
+    /// Returns a snapshot of the current statistics.
+    ///
+    /// Note that the statistics are updated by the audio thread without any synchronization:
+    /// the different values of a snapshot might be one `process()` call apart from each other.
+    pub fn snapshot(&self) -> ProcessStats {
+        let stats = &*self.stats;
+
+        let last_status = match stats.last_status.load(Ordering::Relaxed) {
+            NO_STATUS => None,
+            raw => ProcessStatus::from_raw(raw).and_then(Result::ok),
+        };
+
+        ProcessStats {
+            calls: stats.calls.load(Ordering::Relaxed),
+            failed_calls: stats.failed_calls.load(Ordering::Relaxed),
+            frames_processed: stats.frames_processed.load(Ordering::Relaxed),
+            total_duration: Duration::from_nanos(
+                stats.total_duration_nanos.load(Ordering::Relaxed),
+            ),
+            average_load: load_f64(&stats.average_load),
+            peak_load: load_f64(&stats.peak_load),
+            last_status,
+        }
+    }
+}
+
+impl Debug for ProcessStatsReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProcessStatsReader")
+            .field(&self.snapshot())
+            .finish()
+    }
+}
+
+/// A snapshot of the statistics gathered by a [`ProcessTelemetry`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProcessStats {
+    /// The number of recorded `process()` calls.
+    pub calls: u64,
+    /// The number of recorded `process()` calls that failed.
+    pub failed_calls: u64,
+    /// The total number of frames processed.
+    pub frames_processed: u64,
+    /// The total time spent in `process()` calls.
+    pub total_duration: Duration,
+    /// The smoothed processing load. See [`ProcessReport::load`].
+    pub average_load: f64,
+    /// The decaying peak processing load. See [`ProcessReport::load`].
+    pub peak_load: f64,
+    /// The status returned by the latest `process()` call, or `None` if no call was recorded yet,
+    /// or if the latest call failed.
+    pub last_status: Option<ProcessStatus>,
+}
+
+#[inline]
+fn load_f64(value: &AtomicU64) -> f64 {
+    f64::from_bits(value.load(Ordering::Relaxed))
+}
+
+#[inline]
+fn store_f64(value: &AtomicU64, new: f64) {
+    value.store(new.to_bits(), Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aggregates_reports() {
+        let mut reports = Vec::new();
+        let mut telemetry = ProcessTelemetry::new(1000.0)
+            .with_callback(|report: &ProcessReport| reports.push(*report));
+        let stats = telemetry.stats();
+
+        assert_eq!(stats.snapshot().last_status, None);
+
+        telemetry.record(&ProcessReport {
+            duration: Duration::from_millis(5),
+            frames_count: 10,
+            status: Some(ProcessStatus::Sleep),
+        });
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.calls, 1);
+        assert_eq!(snapshot.frames_processed, 10);
+        assert_eq!(snapshot.average_load, 0.5);
+        assert_eq!(snapshot.peak_load, 0.5);
+        assert_eq!(snapshot.last_status, Some(ProcessStatus::Sleep));
+
+        let result = telemetry.observe(10, || Err::<ProcessStatus, _>(()));
+        assert_eq!(result, Err(()));
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.calls, 2);
+        assert_eq!(snapshot.failed_calls, 1);
+        assert_eq!(snapshot.frames_processed, 20);
+        assert_eq!(snapshot.last_status, None);
+        assert!(snapshot.average_load < 0.5);
+        assert!(snapshot.peak_load < 0.5);
+        assert!(snapshot.peak_load >= snapshot.average_load);
+
+        telemetry.reset();
+        assert_eq!(stats.snapshot().calls, 0);
+        assert_eq!(stats.snapshot().last_status, None);
+
+        drop(telemetry);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].status, None);
+    }
+}
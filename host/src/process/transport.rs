@@ -0,0 +1,416 @@
+//! A playhead keeping track of the song position, to produce the transport information given
+//! to plugins.
+
+use clack_common::events::event_types::{TransportEvent, TransportFlags};
+use clack_common::events::io::EventBuffer;
+use clack_common::events::{EventFlags, EventHeader};
+use clack_common::utils::{BeatTime, SecondsTime};
+
+/// A playhead that advances the song position with each processed block, and produces the
+/// matching [`TransportEvent`]s to give to plugins.
+///
+/// Each call to [`process_block`](Self::process_block) returns the transport information as of
+/// the start of the block, which is to be given to the plugin's `process()` call, and then
+/// advances the playhead to the end of the block.
+///
+/// This playhead supports:
+///
+/// * Looping: when the playhead reaches the end of the loop, it jumps back to its start. Because
+///   this is a discontinuity in the song position, a new [`TransportEvent`] is pushed in the
+///   block's input events, at the exact sample the jump happens.
+/// * Linear tempo ramps, using [`ramp_tempo`](Self::ramp_tempo). The tempo increment of each
+///   sample is reported to plugins through [`TransportEvent::tempo_inc`].
+/// * A fixed time signature, from which the current bar number and bar start are computed.
+///
+/// The song position is tracked in beats. The seconds timeline advances in real time while
+/// playing, but is re-computed from the current tempo (i.e. as if the tempo had been constant
+/// since the start of the song) whenever the playhead [seeks](Self::seek) or wraps around a loop.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::events::io::EventBuffer;
+/// use clack_host::process::transport::TransportPlayhead;
+///
+/// let mut playhead = TransportPlayhead::new(48_000.0).with_tempo(120.0);
+/// playhead.set_loop(0.0, 4.0);
+/// playhead.play();
+///
+/// let mut input_events = EventBuffer::new();
+///
+/// // At 120 BPM and 48kHz, a beat lasts 24000 samples.
+/// let transport = playhead.process_block(24_000, &mut input_events);
+/// assert_eq!(transport.song_pos_beats.to_float(), 0.0);
+/// assert!((playhead.position_beats() - 1.0).abs() < 1e-6);
+///
+/// // This block goes past the loop end: a transport event is pushed on the wrap-around sample.
+/// playhead.process_block(96_000, &mut input_events);
+/// assert_eq!(input_events.len(), 1);
+/// assert!((playhead.position_beats() - 1.0).abs() < 1e-6);
+///
+/// // Pass `transport` alongside the input events to the plugin's process() call.
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransportPlayhead {
+    sample_rate: f64,
+    is_playing: bool,
+    is_recording: bool,
+    position_beats: f64,
+    position_seconds: f64,
+    tempo: f64,
+    tempo_ramp: Option<TempoRamp>,
+    loop_beats: Option<(f64, f64)>,
+    time_signature: (i16, i16),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct TempoRamp {
+    increment: f64,
+    target: f64,
+    remaining_frames: u64,
+}
+
+impl TransportPlayhead {
+    /// The default tempo of a new playhead, in beats per minute.
+    pub const DEFAULT_TEMPO: f64 = 120.0;
+
+    /// Creates a new, stopped playhead at the start of the song, for the given sample rate.
+    ///
+    /// The tempo defaults to [`DEFAULT_TEMPO`](Self::DEFAULT_TEMPO), and the time signature to
+    /// 4/4. Looping is disabled.
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            is_playing: false,
+            is_recording: false,
+            position_beats: 0.0,
+            position_seconds: 0.0,
+            tempo: Self::DEFAULT_TEMPO,
+            tempo_ramp: None,
+            loop_beats: None,
+            time_signature: (4, 4),
+        }
+    }
+
+    /// Sets the tempo, in beats per minute.
+    #[inline]
+    pub fn with_tempo(mut self, tempo: f64) -> Self {
+        self.set_tempo(tempo);
+        self
+    }
+
+    /// Sets the time signature.
+    #[inline]
+    pub fn with_time_signature(mut self, numerator: i16, denominator: i16) -> Self {
+        self.set_time_signature(numerator, denominator);
+        self
+    }
+
+    /// Returns the sample rate this playhead advances at.
+    #[inline]
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Sets the sample rate this playhead advances at, e.g. after the plugin was re-activated.
+    #[inline]
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Starts playback from the current position.
+    #[inline]
+    pub fn play(&mut self) {
+        self.is_playing = true;
+    }
+
+    /// Stops playback (and recording), leaving the playhead at its current position.
+    #[inline]
+    pub fn stop(&mut self) {
+        self.is_playing = false;
+        self.is_recording = false;
+    }
+
+    /// Returns `true` if the playhead is currently playing.
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    /// Sets whether the host is currently recording. This is only reported to plugins.
+    #[inline]
+    pub fn set_recording(&mut self, is_recording: bool) {
+        self.is_recording = is_recording;
+    }
+
+    /// Returns `true` if the host is currently recording.
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.is_recording
+    }
+
+    /// Returns the current song position, in beats.
+    #[inline]
+    pub fn position_beats(&self) -> f64 {
+        self.position_beats
+    }
+
+    /// Returns the current song position, in seconds.
+    #[inline]
+    pub fn position_seconds(&self) -> f64 {
+        self.position_seconds
+    }
+
+    /// Moves the playhead to the given song position, in beats.
+    #[inline]
+    pub fn seek(&mut self, position_beats: f64) {
+        self.position_beats = position_beats;
+        self.position_seconds = self.beats_to_seconds(position_beats);
+    }
+
+    /// Returns the current tempo, in beats per minute.
+    #[inline]
+    pub fn tempo(&self) -> f64 {
+        self.tempo
+    }
+
+    /// Sets the tempo, in beats per minute. This cancels any ongoing tempo ramp.
+    #[inline]
+    pub fn set_tempo(&mut self, tempo: f64) {
+        self.tempo = tempo;
+        self.tempo_ramp = None;
+    }
+
+    /// Linearly ramps the tempo from its current value to the given target tempo, over the
+    /// given number of samples.
+    ///
+    /// The ramp only progresses while playing. If `duration_frames` is zero, the target tempo is
+    /// applied immediately.
+    pub fn ramp_tempo(&mut self, target_tempo: f64, duration_frames: u64) {
+        if duration_frames == 0 {
+            return self.set_tempo(target_tempo);
+        }
+
+        self.tempo_ramp = Some(TempoRamp {
+            increment: (target_tempo - self.tempo) / duration_frames as f64,
+            target: target_tempo,
+            remaining_frames: duration_frames,
+        });
+    }
+
+    /// Returns the current time signature, as a `(numerator, denominator)` pair.
+    #[inline]
+    pub fn time_signature(&self) -> (i16, i16) {
+        self.time_signature
+    }
+
+    /// Sets the time signature.
+    ///
+    /// The time signature is assumed to be constant over the whole song, in order to compute
+    /// bar numbers.
+    #[inline]
+    pub fn set_time_signature(&mut self, numerator: i16, denominator: i16) {
+        self.time_signature = (numerator, denominator);
+    }
+
+    /// Returns the current loop range, as `(start, end)` song positions in beats, or `None` if
+    /// looping is disabled.
+    #[inline]
+    pub fn loop_range(&self) -> Option<(f64, f64)> {
+        self.loop_beats
+    }
+
+    /// Enables looping between the given song positions, in beats.
+    ///
+    /// Looping is disabled instead if the loop range is empty, i.e. if `end_beats` isn't after
+    /// `start_beats`.
+    #[inline]
+    pub fn set_loop(&mut self, start_beats: f64, end_beats: f64) {
+        self.loop_beats = (end_beats > start_beats).then_some((start_beats, end_beats));
+    }
+
+    /// Disables looping.
+    #[inline]
+    pub fn clear_loop(&mut self) {
+        self.loop_beats = None;
+    }
+
+    /// Returns the transport information for the current position of the playhead, with the
+    /// given sample time.
+    pub fn transport(&self, time: u32) -> TransportEvent {
+        let mut flags = TransportFlags::HAS_TEMPO
+            | TransportFlags::HAS_BEATS_TIMELINE
+            | TransportFlags::HAS_SECONDS_TIMELINE
+            | TransportFlags::HAS_TIME_SIGNATURE;
+
+        flags.set(TransportFlags::IS_PLAYING, self.is_playing);
+        flags.set(TransportFlags::IS_RECORDING, self.is_recording);
+        flags.set(TransportFlags::IS_LOOP_ACTIVE, self.loop_beats.is_some());
+
+        let (loop_start, loop_end) = self.loop_beats.unwrap_or_default();
+        let bar_length = self.bar_length_beats();
+        let bar_number = (self.position_beats / bar_length).floor();
+
+        TransportEvent {
+            header: EventHeader::new_core(time, EventFlags::empty()),
+            flags,
+            song_pos_beats: BeatTime::from_float(self.position_beats),
+            song_pos_seconds: SecondsTime::from_float(self.position_seconds),
+            tempo: self.tempo,
+            tempo_inc: match (self.is_playing, &self.tempo_ramp) {
+                (true, Some(ramp)) => ramp.increment,
+                _ => 0.0,
+            },
+            loop_start_beats: BeatTime::from_float(loop_start),
+            loop_end_beats: BeatTime::from_float(loop_end),
+            loop_start_seconds: SecondsTime::from_float(self.beats_to_seconds(loop_start)),
+            loop_end_seconds: SecondsTime::from_float(self.beats_to_seconds(loop_end)),
+            bar_start: BeatTime::from_float(bar_number * bar_length),
+            bar_number: bar_number as i32,
+            time_signature_numerator: self.time_signature.0,
+            time_signature_denominator: self.time_signature.1,
+        }
+    }
+
+    /// Returns the transport information for the start of the next block, and advances the
+    /// playhead to the end of that block.
+    ///
+    /// If the playhead wraps around the loop during this block, a [`TransportEvent`] with the
+    /// updated song position is pushed to the given event buffer, which must then be given to
+    /// the plugin as part of the block's input events. Additional transport events are also
+    /// pushed when a tempo ramp ends during the block. If the buffer also contains other events,
+    /// make sure to [sort](EventBuffer::sort) it before processing.
+    ///
+    /// If the playhead is stopped, this only returns the current transport information.
+    pub fn process_block(&mut self, frames_count: u32, events: &mut EventBuffer) -> TransportEvent {
+        if !self.is_playing {
+            return self.transport(0);
+        }
+
+        // Discontinuities on the first sample are reported by the block's transport itself.
+        self.apply_discontinuities();
+        let transport = self.transport(0);
+
+        for frame in 0..frames_count {
+            if frame > 0 && self.apply_discontinuities() {
+                events.push(&self.transport(frame));
+            }
+
+            self.advance_frame();
+        }
+
+        transport
+    }
+
+    /// Wraps around the loop and ends the tempo ramp if needed. Returns `true` if any of this
+    /// happened.
+    fn apply_discontinuities(&mut self) -> bool {
+        let mut discontinuity = false;
+
+        if let Some((loop_start, loop_end)) = self.loop_beats {
+            if self.position_beats >= loop_end {
+                let overshoot = (self.position_beats - loop_end) % (loop_end - loop_start);
+                self.seek(loop_start + overshoot);
+                discontinuity = true;
+            }
+        }
+
+        if let Some(ramp) = self.tempo_ramp {
+            if ramp.remaining_frames == 0 {
+                self.tempo = ramp.target;
+                self.tempo_ramp = None;
+                discontinuity = true;
+            }
+        }
+
+        discontinuity
+    }
+
+    fn advance_frame(&mut self) {
+        self.position_beats += self.tempo / (60.0 * self.sample_rate);
+        self.position_seconds += 1.0 / self.sample_rate;
+
+        if let Some(ramp) = &mut self.tempo_ramp {
+            self.tempo += ramp.increment;
+            ramp.remaining_frames -= 1;
+        }
+    }
+
+    #[inline]
+    fn beats_to_seconds(&self, beats: f64) -> f64 {
+        beats * 60.0 / self.tempo
+    }
+
+    #[inline]
+    fn bar_length_beats(&self) -> f64 {
+        let (numerator, denominator) = self.time_signature;
+        let length = numerator as f64 * 4.0 / denominator as f64;
+
+        if length > 0.0 {
+            length
+        } else {
+            4.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clack_common::events::Event;
+
+    #[test]
+    fn wraps_around_loops() {
+        // 60 BPM at 4Hz: one beat lasts 4 samples.
+        let mut playhead = TransportPlayhead::new(4.0).with_tempo(60.0);
+        playhead.set_loop(1.0, 3.0);
+        playhead.seek(2.0);
+        playhead.play();
+
+        let mut events = EventBuffer::new();
+        let transport = playhead.process_block(8, &mut events);
+
+        assert_eq!(transport.song_pos_beats.to_float(), 2.0);
+        assert!(transport.flags.contains(TransportFlags::IS_LOOP_ACTIVE));
+        assert_eq!(transport.loop_end_seconds.to_float(), 3.0);
+
+        let wrap = events.get(0).unwrap().as_event::<TransportEvent>().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(wrap.header().time(), 4);
+        assert_eq!(wrap.song_pos_beats.to_float(), 1.0);
+        assert_eq!(wrap.song_pos_seconds.to_float(), 1.0);
+        assert_eq!(playhead.position_beats(), 2.0);
+    }
+
+    #[test]
+    fn ramps_tempo_and_tracks_bars() {
+        let mut playhead = TransportPlayhead::new(4.0)
+            .with_tempo(60.0)
+            .with_time_signature(3, 4);
+        playhead.ramp_tempo(120.0, 4);
+
+        // Stopped playheads don't progress.
+        let mut events = EventBuffer::new();
+        let transport = playhead.process_block(8, &mut events);
+        assert_eq!(transport.tempo_inc, 0.0);
+        assert_eq!(playhead.position_beats(), 0.0);
+
+        playhead.play();
+        let transport = playhead.process_block(8, &mut events);
+        assert_eq!(transport.tempo_inc, 15.0);
+        assert_eq!(playhead.tempo(), 120.0);
+
+        // The end of the ramp is signalled to the plugin.
+        let ramp_end = events.get(0).unwrap().as_event::<TransportEvent>().unwrap();
+        assert_eq!(ramp_end.header().time(), 4);
+        assert_eq!(ramp_end.tempo, 120.0);
+        assert_eq!(ramp_end.tempo_inc, 0.0);
+
+        // (60 + 75 + 90 + 105) / 240 beats during the ramp, then 4 * 120 / 240 beats.
+        assert_eq!(playhead.position_beats(), 3.375);
+
+        let transport = playhead.transport(0);
+        assert_eq!(transport.bar_number, 1);
+        assert_eq!(transport.bar_start.to_float(), 3.0);
+    }
+}
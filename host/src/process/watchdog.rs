@@ -0,0 +1,229 @@
+//! A watchdog measuring the time plugins take to process audio, and reporting overruns.
+
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
+
+/// A report of a plugin exceeding its processing time budget, given to the callback of a
+/// [`ProcessWatchdog`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ProcessOverrun {
+    /// How long the offending `process()` call took.
+    pub duration: Duration,
+    /// The time budget that was allotted to this `process()` call.
+    pub budget: Duration,
+    /// How many consecutive `process()` calls exceeded their budget, including this one.
+    pub consecutive_overruns: u32,
+    /// The total number of `process()` calls that exceeded their budget since this watchdog was
+    /// created, or last [reset](ProcessWatchdog::reset).
+    pub total_overruns: u64,
+}
+
+/// A watchdog that measures how long each `process()` call of a plugin takes, and reports when
+/// the plugin repeatedly exceeds its time budget.
+///
+/// The time budget of each call is the real-time duration of the processed block (i.e. its frame
+/// count divided by the sample rate), multiplied by a [budget ratio](Self::with_budget_ratio).
+/// Once [`threshold`](Self::with_threshold) consecutive calls exceeded their budget, the
+/// watchdog's callback is called with a [`ProcessOverrun`] report for every following overrun,
+/// until a call fits in its budget again.
+///
+/// This can be used by hosts to implement per-plugin overload indicators, or to automatically
+/// bypass misbehaving plugins.
+///
+/// Note that the callback is called on the audio thread, right after the `process()` call: it
+/// must therefore be realtime-safe, and should only e.g. set an atomic flag or push to a
+/// lock-free queue.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::process::watchdog::ProcessWatchdog;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// static OVERLOADED: AtomicBool = AtomicBool::new(false);
+///
+/// // Allow plugins to use at most 70% of the real-time duration of each block.
+/// let mut watchdog = ProcessWatchdog::new(48_000.0, |_overrun| {
+///     OVERLOADED.store(true, Ordering::Relaxed)
+/// })
+/// .with_budget_ratio(0.7);
+///
+/// // On the audio thread:
+/// watchdog.watch(256, || {
+///     /* processor.process(...) */
+/// });
+/// ```
+pub struct ProcessWatchdog<F> {
+    sample_rate: f64,
+    budget_ratio: f64,
+    threshold: u32,
+    consecutive_overruns: u32,
+    total_overruns: u64,
+    on_overrun: F,
+}
+
+impl<F: FnMut(&ProcessOverrun)> ProcessWatchdog<F> {
+    /// The default number of consecutive overruns after which they get reported.
+    pub const DEFAULT_THRESHOLD: u32 = 3;
+
+    /// Creates a new watchdog for a plugin processing at the given sample rate, which calls the
+    /// given callback when the plugin repeatedly overruns.
+    ///
+    /// The budget ratio defaults to `1.0`, and the threshold to
+    /// [`DEFAULT_THRESHOLD`](Self::DEFAULT_THRESHOLD).
+    pub fn new(sample_rate: f64, on_overrun: F) -> Self {
+        Self {
+            sample_rate,
+            budget_ratio: 1.0,
+            threshold: Self::DEFAULT_THRESHOLD,
+            consecutive_overruns: 0,
+            total_overruns: 0,
+            on_overrun,
+        }
+    }
+
+    /// Sets the ratio of each block's real-time duration that the plugin is allowed to use.
+    ///
+    /// For instance, a ratio of `0.5` only allows the plugin to use half of the block's duration.
+    #[inline]
+    pub fn with_budget_ratio(mut self, budget_ratio: f64) -> Self {
+        self.budget_ratio = budget_ratio;
+        self
+    }
+
+    /// Sets how many consecutive overruns are needed before they get reported.
+    ///
+    /// A threshold of `0` or `1` reports every single overrun.
+    #[inline]
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the sample rate the plugin is processing at, e.g. after it has been re-activated with
+    /// a different configuration.
+    #[inline]
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Returns the time budget for processing a block of the given number of frames.
+    pub fn budget(&self, frames_count: u32) -> Duration {
+        let seconds = frames_count as f64 / self.sample_rate * self.budget_ratio;
+        Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX)
+    }
+
+    /// Times the given `process` closure, which processes a block of `frames_count` frames, and
+    /// reports an overrun if needed.
+    ///
+    /// The result of the closure is returned as-is.
+    pub fn watch<R>(&mut self, frames_count: u32, process: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = process();
+        self.record(frames_count, start.elapsed());
+
+        result
+    }
+
+    /// Records a `process()` call that processed `frames_count` frames and took the given
+    /// duration, and reports an overrun if needed.
+    ///
+    /// This is useful for hosts that already measure the time `process()` calls take. Otherwise,
+    /// [`watch`](Self::watch) can be used instead.
+    pub fn record(&mut self, frames_count: u32, duration: Duration) {
+        let budget = self.budget(frames_count);
+
+        if duration <= budget {
+            self.consecutive_overruns = 0;
+            return;
+        }
+
+        self.consecutive_overruns = self.consecutive_overruns.saturating_add(1);
+        self.total_overruns = self.total_overruns.saturating_add(1);
+
+        if self.consecutive_overruns >= self.threshold {
+            (self.on_overrun)(&ProcessOverrun {
+                duration,
+                budget,
+                consecutive_overruns: self.consecutive_overruns,
+                total_overruns: self.total_overruns,
+            });
+        }
+    }
+
+    /// Returns how many consecutive `process()` calls exceeded their budget so far.
+    #[inline]
+    pub fn consecutive_overruns(&self) -> u32 {
+        self.consecutive_overruns
+    }
+
+    /// Returns the total number of `process()` calls that exceeded their budget.
+    #[inline]
+    pub fn total_overruns(&self) -> u64 {
+        self.total_overruns
+    }
+
+    /// Resets the overrun counters, e.g. after re-enabling a bypassed plugin.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.consecutive_overruns = 0;
+        self.total_overruns = 0;
+    }
+}
+
+impl<F> Debug for ProcessWatchdog<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessWatchdog")
+            .field("sample_rate", &self.sample_rate)
+            .field("budget_ratio", &self.budget_ratio)
+            .field("threshold", &self.threshold)
+            .field("consecutive_overruns", &self.consecutive_overruns)
+            .field("total_overruns", &self.total_overruns)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_repeated_overruns() {
+        let mut reports = Vec::new();
+        let mut watchdog =
+            ProcessWatchdog::new(1000.0, |overrun: &ProcessOverrun| reports.push(*overrun))
+                .with_threshold(2);
+
+        assert_eq!(watchdog.budget(10), Duration::from_millis(10));
+
+        watchdog.record(10, Duration::from_millis(20));
+        assert_eq!(watchdog.consecutive_overruns(), 1);
+
+        // Fitting in the budget breaks the streak.
+        watchdog.record(10, Duration::from_millis(5));
+        assert_eq!(watchdog.consecutive_overruns(), 0);
+
+        watchdog.record(10, Duration::from_millis(20));
+        watchdog.record(10, Duration::from_millis(30));
+        let value = watchdog.watch(1, || {
+            std::thread::sleep(Duration::from_millis(2));
+            42
+        });
+        assert_eq!(value, 42);
+
+        assert_eq!(watchdog.total_overruns(), 4);
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(
+            reports[0],
+            ProcessOverrun {
+                duration: Duration::from_millis(30),
+                budget: Duration::from_millis(10),
+                consecutive_overruns: 2,
+                total_overruns: 3,
+            }
+        );
+        assert_eq!(reports[1].consecutive_overruns, 3);
+        assert_eq!(reports[1].budget, Duration::from_millis(1));
+    }
+}
@@ -1,3 +1,4 @@
+use clack_common::events::io::InputEvents;
 use std::cell::UnsafeCell;
 use std::mem::MaybeUninit;
 use std::ptr;
@@ -83,3 +84,101 @@ impl<T> Drop for UnsafeOptionCell<T> {
         *is_some = false;
     }
 }
+
+/// Runs the given closure, checking it doesn't allocate if the `assert-no-alloc` feature is
+/// enabled.
+#[inline]
+pub(crate) fn guard_allocations<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "assert-no-alloc")]
+    return crate::utils::assert_no_alloc(f);
+
+    #[cfg(not(feature = "assert-no-alloc"))]
+    f()
+}
+
+/// Checks the given input events are valid for a block of the given number of frames, if the
+/// `validate-events` feature is enabled.
+///
+/// # Panics
+///
+/// If the `validate-events` feature is enabled, this panics if the input events are invalid.
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn validate_input_events(input_events: &InputEvents, frames_count: u32) {
+    #[cfg(feature = "validate-events")]
+    if let Err(e) = input_events.validate(frames_count) {
+        panic!("Invalid input events given to the plugin: {e}");
+    }
+}
+
+/// Enters a `tracing` span until the end of the current scope, if the `tracing` feature is
+/// enabled. Otherwise, this does nothing, and the span's fields aren't evaluated.
+macro_rules! trace_span {
+    ($level:ident, $name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $name $(, $($fields)*)?).entered();
+    };
+}
+
+pub(crate) use trace_span;
+
+/// Enters a `tracing` span until the end of the current scope, if the `tracing-audio-thread`
+/// feature is enabled. This is used for calls made on the audio thread, where creating spans
+/// isn't realtime-safe.
+macro_rules! trace_audio_thread_span {
+    ($level:ident, $name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing-audio-thread")]
+        let _span = tracing::span!(tracing::Level::$level, $name $(, $($fields)*)?).entered();
+    };
+}
+
+pub(crate) use trace_audio_thread_span;
+
+/// Returns the ID of the given plugin instance, for tracing purposes.
+#[cfg(feature = "tracing")]
+pub(crate) fn plugin_id(plugin: &clap_sys::plugin::clap_plugin) -> &str {
+    // SAFETY: the CLAP spec requires the descriptor and its ID to be valid for the lifetime of the
+    // plugin instance, if they are non-null.
+    let id = unsafe { plugin.desc.as_ref() }
+        .filter(|desc| !desc.id.is_null())
+        // SAFETY: see above.
+        .map(|desc| unsafe { std::ffi::CStr::from_ptr(desc.id) });
+
+    id.and_then(|id| id.to_str().ok()).unwrap_or("<unknown>")
+}
+
+/// Enters a span for a single `process` call.
+///
+/// Spans are emitted on every call at the `TRACE` level, but only one out of every
+/// [`PROCESS_SPAN_SAMPLING`] calls (per thread) is emitted at the `DEBUG` level, so that
+/// processing can be profiled without flooding the subscriber.
+///
+/// Those spans are only emitted if the `tracing-audio-thread` feature is enabled.
+#[cfg(feature = "tracing-audio-thread")]
+pub(crate) fn enter_process_span(
+    plugin: &clap_sys::plugin::clap_plugin,
+    frames_count: u32,
+) -> tracing::span::EnteredSpan {
+    use std::cell::Cell;
+
+    const PROCESS_SPAN_SAMPLING: u32 = 1024;
+
+    thread_local! {
+        static PROCESS_CALLS: Cell<u32> = const { Cell::new(0) };
+    }
+
+    let calls = PROCESS_CALLS.with(|calls| {
+        let current = calls.get();
+        calls.set(current.wrapping_add(1));
+        current
+    });
+
+    let plugin_id = plugin_id(plugin);
+    let span = if calls % PROCESS_SPAN_SAMPLING == 0 {
+        tracing::debug_span!("process", plugin_id, frames_count)
+    } else {
+        tracing::trace_span!("process", plugin_id, frames_count)
+    };
+
+    span.entered()
+}
@@ -0,0 +1,148 @@
+use clack_host::events::io::EventBuffer;
+use clack_host::prelude::*;
+use clack_host::process::graph::{AudioNode, GainNode, PluginNode};
+use clack_host::process::ProcessParams;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+pub struct DoublerPlugin;
+pub struct DoublerPluginMainThread;
+
+impl PluginMainThread<'_, ()> for DoublerPluginMainThread {}
+
+impl Plugin for DoublerPlugin {
+    type AudioProcessor<'a> = DoublerPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = DoublerPluginMainThread;
+}
+
+impl DefaultPluginFactory for DoublerPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("doubler", "Doubler plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(DoublerPluginMainThread)
+    }
+}
+
+pub struct DoublerPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), DoublerPluginMainThread> for DoublerPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut DoublerPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        mut audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        let mut port_pair = audio.port_pair(0).unwrap();
+        for pair in port_pair.channels()?.into_f32().unwrap().iter_mut() {
+            if let ChannelPair::InputOutput(i, o) = pair {
+                for (o, i) in o.iter_mut().zip(i.iter()) {
+                    *o = i * 2.0;
+                }
+            }
+        }
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+}
+
+static DOUBLER_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<DoublerPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn processes_plugin_and_builtin_nodes() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&DOUBLER_ENTRY, "/doubler") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"doubler\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 16,
+    };
+
+    let processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut nodes: Vec<Box<dyn AudioNode>> = vec![
+        Box::new(PluginNode::new(processor, &[2], &[2], 16)),
+        Box::new(GainNode::new(2, 0.25)),
+    ];
+
+    // A stereo block of 8 frames, going through each node in series.
+    let mut buffers = [
+        (1..=16).map(|i| i as f32).collect::<Vec<_>>(),
+        vec![0.0; 16],
+    ];
+    let params = ProcessParams::new().with_steady_time(0);
+
+    for node in &mut nodes {
+        assert_eq!(node.input_channel_counts(), [2]);
+        assert_eq!(node.output_channel_counts(), [2]);
+
+        let [input, output] = &mut buffers;
+        let status = node
+            .process(
+                8,
+                &params,
+                &[input],
+                &mut [output],
+                &EventBuffer::new().as_input(),
+                &mut OutputEvents::void(),
+            )
+            .unwrap();
+
+        assert_eq!(status, ProcessStatus::ContinueIfNotQuiet);
+        buffers.swap(0, 1);
+    }
+
+    let expected: Vec<f32> = (1..=16).map(|i| i as f32 * 0.5).collect();
+    assert_eq!(buffers[0], expected);
+
+    drop(nodes);
+}
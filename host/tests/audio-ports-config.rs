@@ -0,0 +1,198 @@
+use clack_extensions::audio_ports::{
+    AudioPortFlags, AudioPortInfo, AudioPortInfoBuffer, AudioPortInfoWriter, AudioPortType,
+};
+use clack_extensions::audio_ports_config::*;
+use clack_extensions::error::CallFailure;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const MONO_ID: ClapId = ClapId::new(1);
+const STEREO_ID: ClapId = ClapId::new(2);
+
+pub struct ConfigPlugin;
+pub struct ConfigPluginMainThread {
+    selected: ClapId,
+}
+
+impl PluginMainThread<'_, ()> for ConfigPluginMainThread {}
+
+impl Plugin for ConfigPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ConfigPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder
+            .register::<PluginAudioPortsConfig>()
+            .register::<PluginAudioPortsConfigInfo>();
+    }
+}
+
+impl DefaultPluginFactory for ConfigPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("config", "Config plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(ConfigPluginMainThread { selected: MONO_ID })
+    }
+}
+
+fn channel_count(config_id: ClapId) -> u32 {
+    if config_id == STEREO_ID {
+        2
+    } else {
+        1
+    }
+}
+
+impl PluginAudioPortsConfigImpl for ConfigPluginMainThread {
+    fn count(&mut self) -> u32 {
+        2
+    }
+
+    fn get(&mut self, index: u32, writer: &mut AudioPortConfigWriter) {
+        let (id, name): (_, &[u8]) = match index {
+            0 => (MONO_ID, b"Mono"),
+            1 => (STEREO_ID, b"Stereo"),
+            _ => return,
+        };
+
+        let main_port = Some(MainPortInfo {
+            channel_count: channel_count(id),
+            port_type: None,
+        });
+
+        writer.write(&AudioPortsConfiguration {
+            id,
+            name,
+            input_port_count: 1,
+            output_port_count: 1,
+            main_input: main_port,
+            main_output: main_port,
+        });
+    }
+
+    fn select(&mut self, config_id: ClapId) -> Result<(), PluginError> {
+        if config_id != MONO_ID && config_id != STEREO_ID {
+            return Err(PluginError::Message("Unknown configuration"));
+        }
+
+        self.selected = config_id;
+        Ok(())
+    }
+}
+
+impl PluginAudioPortsConfigInfoImpl for ConfigPluginMainThread {
+    fn current_config(&mut self) -> ClapId {
+        self.selected
+    }
+
+    fn get(
+        &mut self,
+        config_id: ClapId,
+        port_index: u32,
+        _is_input: bool,
+        writer: &mut AudioPortInfoWriter,
+    ) {
+        if port_index != 0 {
+            return;
+        }
+
+        let port_type = if config_id == STEREO_ID {
+            AudioPortType::STEREO
+        } else {
+            AudioPortType::MONO
+        };
+
+        writer.set(&AudioPortInfo {
+            id: ClapId::new(0),
+            name: b"Main",
+            channel_count: channel_count(config_id),
+            flags: AudioPortFlags::IS_MAIN,
+            port_type: Some(port_type),
+            in_place_pair: None,
+        });
+    }
+}
+
+static CONFIG_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<ConfigPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn selects_configurations() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&CONFIG_ENTRY, "/config") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"config\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let config = plugin.get_extension::<PluginAudioPortsConfig>().unwrap();
+    let config_info = plugin
+        .get_extension::<PluginAudioPortsConfigInfo>()
+        .unwrap();
+
+    let mut buffer = AudioPortsConfigBuffer::new();
+    let stereo = config.find(&mut plugin, STEREO_ID, &mut buffer).unwrap();
+    assert_eq!(stereo.name, b"Stereo");
+    assert!(config
+        .find(&mut plugin, ClapId::new(42), &mut buffer)
+        .is_none());
+
+    // Ports of unselected configurations can be inspected.
+    let mut port_buffer = AudioPortInfoBuffer::new();
+    let port = config_info
+        .get(&mut plugin, STEREO_ID, 0, false, &mut port_buffer)
+        .unwrap();
+    assert_eq!(port.port_type, Some(AudioPortType::STEREO));
+    assert!(config_info
+        .get(&mut plugin, STEREO_ID, 1, false, &mut port_buffer)
+        .is_none());
+
+    assert_eq!(config_info.current_config(&mut plugin), Some(MONO_ID));
+
+    let selected = config.select_matching(&mut plugin, |c| {
+        c.main_output.is_some_and(|p| p.channel_count == 2)
+    });
+    assert_eq!(selected, Ok(Some(STEREO_ID)));
+    assert_eq!(config_info.current_config(&mut plugin), Some(STEREO_ID));
+
+    let selected = config.select_matching(&mut plugin, |c| c.input_port_count > 1);
+    assert_eq!(selected, Ok(None));
+
+    assert_eq!(
+        config.select(&mut plugin, ClapId::new(42)),
+        Err(AudioPortConfigSelectError::new(CallFailure::Declined))
+    );
+}
@@ -0,0 +1,248 @@
+use clack_extensions::audio_ports::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the plugin exposes its second port layout.
+static CHANGED: AtomicBool = AtomicBool::new(false);
+/// Whether the plugin fails to provide information about its first output port.
+static BROKEN_FIRST_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub struct PortsPlugin;
+pub struct PortsPluginMainThread;
+pub struct PortsPluginAudioProcessor;
+
+impl PluginMainThread<'_, ()> for PortsPluginMainThread {}
+
+impl Plugin for PortsPlugin {
+    type AudioProcessor<'a> = PortsPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = PortsPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginAudioPorts>();
+    }
+}
+
+impl DefaultPluginFactory for PortsPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("ports", "Ports plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(PortsPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), PortsPluginMainThread> for PortsPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut PortsPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        mut audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        // Fill each output channel with the channel count of its port.
+        for mut port in audio.output_ports() {
+            let channel_count = port.channel_count() as f32;
+
+            if let Some(mut channels) = port.channels()?.into_f32() {
+                for channel in channels.iter_mut() {
+                    channel.fill(channel_count);
+                }
+            }
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+fn current_output_channel_counts() -> &'static [u32] {
+    if CHANGED.load(Ordering::SeqCst) {
+        &[2, 1]
+    } else {
+        &[1]
+    }
+}
+
+impl PluginAudioPortsImpl for PortsPluginMainThread {
+    fn count(&mut self, is_input: bool) -> u32 {
+        if is_input {
+            1
+        } else {
+            current_output_channel_counts().len() as u32
+        }
+    }
+
+    fn get(&mut self, index: u32, is_input: bool, writer: &mut AudioPortInfoWriter) {
+        if !is_input && index == 0 && BROKEN_FIRST_OUTPUT.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let channel_count = if is_input {
+            2
+        } else {
+            let Some(count) = current_output_channel_counts().get(index as usize) else {
+                return;
+            };
+            *count
+        };
+
+        writer.set(&AudioPortInfo {
+            id: ClapId::new(index),
+            name: b"Port",
+            channel_count,
+            flags: AudioPortFlags::empty(),
+            port_type: AudioPortType::from_channel_count(channel_count),
+            in_place_pair: None,
+        });
+    }
+}
+
+static PORTS_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<PortsPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+fn process(instance: &mut PluginInstance<MyHost>, pool: &mut AudioPortsBufferPool) {
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: pool.max_frames_count(),
+    };
+
+    let processor = instance.activate(|_, _| (), config).unwrap();
+    let mut processor = processor.start_processing().unwrap();
+
+    let (inputs, mut outputs) = pool.buffers();
+    processor
+        .process(
+            &inputs,
+            &mut outputs,
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    instance.deactivate(processor.stop_processing());
+}
+
+#[test]
+fn reallocates_buffers_on_rescan() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&PORTS_ENTRY, "/ports") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"ports\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let ports = instance
+        .plugin_handle()
+        .get_extension::<PluginAudioPorts>()
+        .unwrap();
+
+    let snapshot = AudioPortsSnapshot::scan(&mut instance.plugin_handle(), &ports).unwrap();
+    let mut pool = ports.allocate_buffers(&mut instance.plugin_handle(), 4);
+    assert_eq!(pool.input_channel_counts(), [2]);
+    assert_eq!(pool.output_channel_counts(), [1]);
+
+    process(&mut instance, &mut pool);
+    assert_eq!(pool.output_channels(0).unwrap(), [vec![1.0; 4]]);
+
+    CHANGED.store(true, Ordering::SeqCst);
+
+    // Renaming ports doesn't affect the buffers, and can be done at any time.
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let processor = instance.activate(|_, _| (), config).unwrap();
+
+    assert_eq!(
+        ports.handle_rescan(&mut instance, RescanType::NAMES, &mut pool),
+        Ok(AudioPortsRescanAction::UpdateNames)
+    );
+    assert_eq!(pool.output_channel_counts(), [1]);
+
+    // Layout changes require the plugin to be deactivated.
+    let flags = RescanType::NAMES | RescanType::LIST;
+    assert_eq!(
+        ports.handle_rescan(&mut instance, flags, &mut pool),
+        Err(AudioPortsRescanError::PluginActive)
+    );
+    instance.deactivate(processor);
+
+    assert_eq!(
+        ports.handle_rescan(&mut instance, flags, &mut pool),
+        Ok(AudioPortsRescanAction::Reactivate)
+    );
+    assert_eq!(pool.input_channel_counts(), [2]);
+    assert_eq!(pool.output_channel_counts(), [2, 1]);
+    assert_eq!(pool.max_frames_count(), 4);
+
+    let new_snapshot = AudioPortsSnapshot::scan(&mut instance.plugin_handle(), &ports).unwrap();
+    let diff = AudioPortsSnapshot::diff(&snapshot, &new_snapshot);
+    assert!(diff.inputs.is_empty());
+    assert_eq!(diff.outputs.added, [ClapId::new(1)]);
+    assert_eq!(diff.outputs.changed, [ClapId::new(0)]);
+    assert!(diff.requires_reactivation());
+    assert_eq!(
+        new_snapshot.allocate_buffers(4).output_channel_counts(),
+        pool.output_channel_counts()
+    );
+
+    process(&mut instance, &mut pool);
+    assert_eq!(
+        pool.output_channels(0).unwrap(),
+        [vec![2.0; 4], vec![2.0; 4]]
+    );
+    assert_eq!(pool.output_channels(1).unwrap(), [vec![1.0; 4]]);
+
+    // Snapshots fail instead of skipping ports the plugin can't describe.
+    BROKEN_FIRST_OUTPUT.store(true, Ordering::SeqCst);
+    assert_eq!(
+        AudioPortsSnapshot::scan(&mut instance.plugin_handle(), &ports),
+        Err(AudioPortsScanError::MissingPort {
+            index: 0,
+            is_input: false
+        })
+    );
+}
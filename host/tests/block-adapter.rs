@@ -0,0 +1,186 @@
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::io::EventBuffer;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::process::block_adapter::FixedBlockAdapter;
+use clack_host::process::ProcessParams;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+/// The steady time and events times of every block processed by the plugin.
+static BLOCKS: Mutex<Vec<(Option<u64>, Vec<u32>)>> = Mutex::new(Vec::new());
+
+pub struct FixedPlugin;
+pub struct FixedPluginMainThread;
+
+impl PluginMainThread<'_, ()> for FixedPluginMainThread {}
+
+impl Plugin for FixedPlugin {
+    type AudioProcessor<'a> = FixedPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = FixedPluginMainThread;
+}
+
+impl DefaultPluginFactory for FixedPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("fixed", "Fixed block size plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(FixedPluginMainThread)
+    }
+}
+
+pub struct FixedPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), FixedPluginMainThread> for FixedPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut FixedPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        process: Process,
+        mut audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        assert_eq!(audio.frames_count(), 8);
+
+        let mut port_pair = audio.port_pair(0).unwrap();
+        for pair in port_pair.channels()?.into_f32().unwrap().iter_mut() {
+            if let ChannelPair::InputOutput(i, o) = pair {
+                o.copy_from_slice(i);
+            }
+        }
+
+        let times = events.input.iter().map(|e| e.header().time()).collect();
+        BLOCKS.lock().unwrap().push((process.steady_time, times));
+
+        for event in events.input {
+            events.output.try_push(event)?;
+        }
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+}
+
+static FIXED_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<FixedPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn processes_in_fixed_blocks() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&FIXED_ENTRY, "/fixed") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"fixed\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 8,
+        max_frames_count: 8,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut adapter = FixedBlockAdapter::new(8, &[1], &[1]);
+    let mut params = ProcessParams::new().with_steady_time(0);
+
+    let input: Vec<f32> = (1..=32).map(|i| i as f32).collect();
+    let mut output = [0.0; 32];
+    let mut received_events = Vec::new();
+
+    let mut start = 0;
+    for (host_block, frames) in [3, 5, 12, 4, 8].into_iter().enumerate() {
+        let end = start + frames;
+
+        let mut input_events = EventBuffer::new();
+        if host_block == 2 {
+            // Absolute frame 10, i.e. the third frame of the plugin's second block.
+            input_events.push(&NoteOnEvent::new(
+                2,
+                Pckn::new(0u16, 0u16, 60u16, 0u32),
+                1.0,
+            ));
+        }
+
+        let mut output_events = EventBuffer::new();
+
+        adapter
+            .process(
+                &mut processor,
+                &mut params,
+                &[&input[start..end]],
+                &mut [&mut output[start..end]],
+                &input_events.as_input(),
+                &mut output_events.as_output(),
+            )
+            .unwrap();
+
+        received_events.extend(
+            output_events
+                .iter()
+                .map(|e| start as u32 + e.header().time()),
+        );
+        start = end;
+    }
+
+    assert_eq!(params.steady_time(), Some(32));
+
+    assert_eq!(
+        *BLOCKS.lock().unwrap(),
+        [
+            (Some(0), vec![]),
+            (Some(8), vec![2]),
+            (Some(16), vec![]),
+            (Some(24), vec![]),
+        ]
+    );
+
+    // Audio and events are both delayed by exactly one block.
+    assert_eq!(adapter.latency(), 8);
+    assert_eq!(&output[..8], &[0.0; 8]);
+    assert_eq!(&output[8..], &input[..24]);
+    assert_eq!(received_events, [18]);
+
+    instance.deactivate(processor.stop_processing());
+}
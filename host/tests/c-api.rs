@@ -0,0 +1,191 @@
+#![cfg(feature = "c-api")]
+
+use clack_host::c_api::*;
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clap_sys::process::{clap_process, CLAP_PROCESS_CONTINUE, CLAP_PROCESS_ERROR};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static PROCESSED_FRAMES: AtomicU32 = AtomicU32::new(0);
+static RECEIVED_EVENTS: AtomicU32 = AtomicU32::new(0);
+
+pub struct CApiPlugin;
+pub struct CApiPluginAudioProcessor;
+
+impl Plugin for CApiPlugin {
+    type AudioProcessor<'a> = CApiPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for CApiPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("c-api", "C API plugin")
+    }
+
+    fn new_shared(host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        host.request_process();
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for CApiPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        PROCESSED_FRAMES.fetch_add(audio.frames_count(), Ordering::SeqCst);
+        RECEIVED_EVENTS.fetch_add(events.input.len(), Ordering::SeqCst);
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static C_API_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<CApiPlugin>);
+
+/// # Safety
+///
+/// `user_data` must point to a valid `AtomicU32`.
+unsafe extern "C" fn count_request(user_data: *mut c_void) {
+    (*(user_data as *const AtomicU32)).fetch_add(1, Ordering::SeqCst);
+}
+
+struct SendProcessor(*mut ClackAudioProcessor);
+
+// SAFETY: audio processor handles are meant to be sent to the audio thread.
+unsafe impl Send for SendProcessor {}
+
+impl SendProcessor {
+    fn into_inner(self) -> *mut ClackAudioProcessor {
+        self.0
+    }
+}
+
+#[test]
+fn can_drive_plugins_through_c_api() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&C_API_ENTRY, "/c-api") }.unwrap();
+    let bundle = ClackBundle::raw_handle(bundle);
+
+    let process_requests = AtomicU32::new(0);
+    let callbacks = ClackHostCallbacks {
+        user_data: &process_requests as *const AtomicU32 as *mut c_void,
+        request_restart: None,
+        request_process: Some(count_request),
+        request_callback: None,
+    };
+
+    unsafe {
+        let instance = clack_host_instance_new(
+            bundle,
+            b"c-api\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"1.0\0".as_ptr().cast(),
+            &callbacks,
+        );
+        assert!(!instance.is_null());
+        assert_eq!(process_requests.load(Ordering::SeqCst), 1);
+
+        // Unknown plugins can't be instantiated.
+        assert!(clack_host_instance_new(
+            bundle,
+            b"unknown\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"host\0".as_ptr().cast(),
+            b"1.0\0".as_ptr().cast(),
+            core::ptr::null(),
+        )
+        .is_null());
+
+        // The bundle can be released while instances are alive.
+        clack_host_bundle_destroy(bundle);
+
+        let mut events = EventBuffer::new();
+        events.push(&NoteOnEvent::new(
+            0,
+            Pckn::new(0u16, 0u16, 60u16, 0u32),
+            1.0,
+        ));
+        let input_events = events.as_input();
+
+        let process = clap_process {
+            steady_time: 0,
+            frames_count: 32,
+            transport: core::ptr::null(),
+            audio_inputs: core::ptr::null(),
+            audio_outputs: core::ptr::null_mut(),
+            audio_inputs_count: 0,
+            audio_outputs_count: 0,
+            in_events: input_events.as_raw(),
+            out_events: core::ptr::null(),
+        };
+
+        // Processing requires an audio processor.
+        assert_eq!(
+            clack_host_processor_process(core::ptr::null_mut(), &process),
+            CLAP_PROCESS_ERROR
+        );
+
+        let processor = clack_host_instance_activate(instance, 44_100.0, 1, 32);
+        assert!(!processor.is_null());
+
+        // Active instances can't be activated again.
+        assert!(clack_host_instance_activate(instance, 44_100.0, 1, 32).is_null());
+
+        // The audio processor is owned by the audio thread.
+        let processor = SendProcessor(processor);
+        let processor = std::thread::spawn(move || {
+            let processor = processor.into_inner();
+            assert!(clack_host_processor_start_processing(processor));
+            assert_eq!(
+                clack_host_processor_process(processor, &process),
+                CLAP_PROCESS_CONTINUE
+            );
+            assert_eq!(
+                clack_host_processor_process(processor, &process),
+                CLAP_PROCESS_CONTINUE
+            );
+            clack_host_processor_stop_processing(processor);
+            SendProcessor(processor)
+        })
+        .join()
+        .unwrap()
+        .into_inner();
+
+        clack_host_instance_on_main_thread(instance);
+        clack_host_instance_deactivate(instance, processor);
+
+        // Instances can be re-activated once deactivated.
+        let processor = clack_host_instance_activate(instance, 48_000.0, 1, 32);
+        assert!(!processor.is_null());
+        clack_host_instance_deactivate(instance, processor);
+
+        clack_host_instance_destroy(instance);
+    }
+
+    assert_eq!(PROCESSED_FRAMES.load(Ordering::SeqCst), 64);
+    assert_eq!(RECEIVED_EVENTS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,95 @@
+use clack_host::prelude::*;
+use clack_plugin::prelude::*;
+use clack_plugin::{clack_entry, combine_entries};
+use std::ffi::CStr;
+
+macro_rules! test_plugin {
+    ($name:ident, $id:literal) => {
+        pub struct $name;
+
+        impl Plugin for $name {
+            type AudioProcessor<'a> = ();
+            type Shared<'a> = ();
+            type MainThread<'a> = ();
+        }
+
+        impl DefaultPluginFactory for $name {
+            fn get_descriptor() -> PluginDescriptor {
+                PluginDescriptor::new($id, stringify!($name))
+            }
+
+            fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+                Ok(())
+            }
+
+            fn new_main_thread<'a>(
+                _host: HostMainThreadHandle<'a>,
+                _shared: &'a Self::Shared<'a>,
+            ) -> Result<Self::MainThread<'a>, PluginError> {
+                Ok(())
+            }
+        }
+    };
+}
+
+test_plugin!(ReverbPlugin, "suite.reverb");
+test_plugin!(DelayPlugin, "suite.delay");
+test_plugin!(DuplicateDelayPlugin, "suite.delay");
+
+static SUITE_ENTRY: EntryDescriptor = clack_entry!(combine_entries!(
+    SinglePluginEntry<ReverbPlugin>,
+    SinglePluginEntry<DelayPlugin>,
+    SinglePluginEntry<DuplicateDelayPlugin>,
+));
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn exposes_plugins_of_all_combined_entries() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&SUITE_ENTRY, "/suite") }.unwrap();
+    let factory = bundle.get_plugin_factory().unwrap();
+
+    let ids: Vec<_> = factory
+        .plugin_descriptors()
+        .map(|d| d.id().unwrap().to_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(ids, ["suite.reverb", "suite.delay", "suite.delay"]);
+    assert!(factory.plugin_descriptor(3).is_none());
+
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    for id in ["suite.reverb\0", "suite.delay\0"] {
+        let instance = PluginInstance::<MyHost>::new(
+            |_| MyHostShared,
+            |_| (),
+            &bundle,
+            CStr::from_bytes_with_nul(id.as_bytes()).unwrap(),
+            &host,
+        );
+
+        assert!(instance.is_ok());
+    }
+
+    let unknown = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"suite.unknown\0").unwrap(),
+        &host,
+    );
+    assert!(unknown.is_err());
+}
@@ -0,0 +1,144 @@
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::process::event_driver::EventDriver;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+/// The frame count, steady time and port counts of every block the plugin processed.
+static PROCESSED: Mutex<Vec<(u32, Option<u64>, usize)>> = Mutex::new(Vec::new());
+
+pub struct NoteEffectPlugin;
+pub struct NoteEffectPluginAudioProcessor;
+
+impl Plugin for NoteEffectPlugin {
+    type AudioProcessor<'a> = NoteEffectPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for NoteEffectPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("notes", "Note effect plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for NoteEffectPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        process: Process,
+        audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        PROCESSED.lock().unwrap().push((
+            audio.frames_count(),
+            process.steady_time,
+            audio.input_port_count() + audio.output_port_count(),
+        ));
+
+        for event in events.input {
+            events.output.try_push(event)?;
+        }
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+}
+
+static NOTES_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<NoteEffectPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn can_process_event_only_plugins() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&NOTES_ENTRY, "/notes") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"notes\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 64,
+        max_frames_count: 64,
+    };
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut driver = EventDriver::new(config.sample_rate, 64);
+    let mut output_events = EventBuffer::new();
+
+    for block in 0..3u32 {
+        let mut input_events = EventBuffer::new();
+        // Events can be anywhere in the block, even if there is no audio buffer.
+        input_events.push(&NoteOnEvent::new(
+            63,
+            Pckn::new(0u16, 0u16, 60 + block as u16, 0u32),
+            1.0,
+        ));
+
+        output_events.clear();
+        let status = driver
+            .process(
+                &mut processor,
+                &input_events.as_input(),
+                &mut output_events.as_output(),
+            )
+            .unwrap();
+
+        assert_eq!(status, ProcessStatus::ContinueIfNotQuiet);
+        assert_eq!(output_events.len(), 1);
+    }
+
+    assert_eq!(driver.params().steady_time(), Some(192));
+    assert_eq!(
+        *PROCESSED.lock().unwrap(),
+        [(64, Some(0), 0), (64, Some(64), 0), (64, Some(128), 0)]
+    );
+
+    instance.deactivate(processor.stop_processing());
+}
@@ -0,0 +1,173 @@
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::process::recorder::{EventLog, EventRecorder};
+use clack_host::process::transport::TransportPlayhead;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+/// The steady time and tempo of every block the plugin processed.
+static PROCESSED: Mutex<Vec<(Option<u64>, Option<f64>)>> = Mutex::new(Vec::new());
+
+pub struct EchoPlugin;
+pub struct EchoPluginAudioProcessor;
+
+impl Plugin for EchoPlugin {
+    type AudioProcessor<'a> = EchoPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for EchoPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("echo", "Echo plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for EchoPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        process: Process,
+        _audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        PROCESSED
+            .lock()
+            .unwrap()
+            .push((process.steady_time, process.transport.map(|t| t.tempo)));
+
+        for event in events.input {
+            events.output.try_push(event)?;
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static ECHO_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<EchoPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn replays_recorded_blocks() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&ECHO_ENTRY, "/echo") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"echo\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 48_000.0,
+        min_frames_count: 1,
+        max_frames_count: 32,
+    };
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut playhead = TransportPlayhead::new(48_000.0).with_tempo(128.0);
+    playhead.play();
+
+    let mut recorder = EventRecorder::new();
+    let mut output_events = EventBuffer::new();
+
+    for block in 0..4u32 {
+        let mut input_events = EventBuffer::new();
+        input_events.push(&NoteOnEvent::new(
+            block,
+            Pckn::new(0u16, 0u16, 60 + block as u16, 0u32),
+            1.0,
+        ));
+        let transport = playhead.process_block(32, &mut input_events);
+
+        output_events.clear();
+        processor
+            .process(
+                &InputAudioBuffers::empty(),
+                &mut OutputAudioBuffers::empty(),
+                &input_events.as_input(),
+                &mut output_events.as_output(),
+                Some(block as u64 * 32),
+                Some(&transport),
+            )
+            .unwrap();
+
+        recorder.record_block(
+            32,
+            Some(block as u64 * 32),
+            Some(&transport),
+            &input_events,
+            &output_events,
+        );
+    }
+
+    let recorded = core::mem::take(&mut *PROCESSED.lock().unwrap());
+    assert_eq!(recorded.len(), 4);
+
+    let log = EventLog::parse(recorder.as_bytes()).unwrap();
+    processor.reset();
+
+    for block in log.blocks() {
+        output_events.clear();
+        block
+            .replay(
+                &mut processor,
+                &InputAudioBuffers::empty(),
+                &mut OutputAudioBuffers::empty(),
+                &mut output_events.as_output(),
+            )
+            .unwrap();
+
+        let replayed: Vec<_> = output_events.iter().map(|e| e.as_bytes()).collect();
+        let expected: Vec<_> = block.output_events().iter().map(|e| e.as_bytes()).collect();
+        assert_eq!(replayed, expected);
+    }
+
+    assert_eq!(*PROCESSED.lock().unwrap(), recorded);
+
+    instance.deactivate(processor.stop_processing());
+}
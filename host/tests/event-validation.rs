@@ -0,0 +1,134 @@
+#![cfg(feature = "validate-events")]
+
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::panic::AssertUnwindSafe;
+
+pub struct SilentPlugin;
+pub struct SilentPluginMainThread;
+pub struct SilentPluginAudioProcessor;
+
+impl PluginMainThread<'_, ()> for SilentPluginMainThread {}
+
+impl Plugin for SilentPlugin {
+    type AudioProcessor<'a> = SilentPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = SilentPluginMainThread;
+}
+
+impl DefaultPluginFactory for SilentPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("silent", "Silent plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(SilentPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), SilentPluginMainThread> for SilentPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut SilentPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static SILENT_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<SilentPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn panics_on_invalid_input_events() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&SILENT_ENTRY, "/silent") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"silent\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut output_ports = AudioPorts::with_capacity(1, 1);
+    let mut output_buffer = [0f32; 4];
+
+    let mut process = |events: &[NoteOnEvent]| {
+        let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only([output_buffer.as_mut_slice()]),
+        }]);
+
+        processor.process(
+            &InputAudioBuffers::empty(),
+            &mut outputs,
+            &InputEvents::from_buffer(&events),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+    };
+
+    let note = |time| NoteOnEvent::new(time, Pckn::match_all(), 1.0);
+
+    assert!(process(&[note(0), note(3)]).is_ok());
+
+    // Unordered events.
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| process(&[note(2), note(1)])));
+    assert!(result.is_err());
+
+    // Events past the end of the block.
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| process(&[note(4)])));
+    assert!(result.is_err());
+}
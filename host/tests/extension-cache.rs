@@ -0,0 +1,114 @@
+use clack_extensions::latency::{PluginLatency, PluginLatencyImpl};
+use clack_extensions::state::PluginState;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of times the plugin was queried for an extension.
+static EXTENSION_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct LatencyPlugin;
+pub struct LatencyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for LatencyPluginMainThread {}
+
+impl PluginLatencyImpl for LatencyPluginMainThread {
+    fn get(&mut self) -> u32 {
+        42
+    }
+}
+
+impl Plugin for LatencyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = LatencyPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        EXTENSION_QUERIES.fetch_add(1, Ordering::SeqCst);
+        builder.register::<PluginLatency>();
+    }
+}
+
+impl DefaultPluginFactory for LatencyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("latency", "Latency plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(LatencyPluginMainThread)
+    }
+}
+
+static LATENCY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<LatencyPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {
+        unimplemented!()
+    }
+    fn request_process(&self) {
+        unimplemented!()
+    }
+    fn request_callback(&self) {
+        unimplemented!()
+    }
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+pub fn caches_extension_lookups() {
+    // SAFETY: the entry is a valid, Clack-generated entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&LATENCY_PLUGIN_ENTRY, "/latency.so") };
+    let host_info = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle.unwrap(),
+        CStr::from_bytes_with_nul(b"latency\0").unwrap(),
+        &host_info,
+    )
+    .unwrap();
+
+    let queries_before = EXTENSION_QUERIES.load(Ordering::SeqCst);
+
+    let latency = instance.cached_extension::<PluginLatency>().unwrap();
+    assert_eq!(latency.get(&mut instance.plugin_handle()), 42);
+    assert_eq!(EXTENSION_QUERIES.load(Ordering::SeqCst), queries_before + 1);
+
+    for _ in 0..10 {
+        let latency = instance.cached_extension::<PluginLatency>().unwrap();
+        assert_eq!(latency.get(&mut instance.plugin_handle()), 42);
+    }
+
+    assert_eq!(EXTENSION_QUERIES.load(Ordering::SeqCst), queries_before + 1);
+
+    // Unsupported extensions are cached too.
+    assert!(instance.cached_extension::<PluginState>().is_none());
+    assert!(instance.cached_extension::<PluginState>().is_none());
+    assert_eq!(EXTENSION_QUERIES.load(Ordering::SeqCst), queries_before + 2);
+
+    // Uncached lookups still query the plugin every time.
+    assert!(instance
+        .plugin_shared_handle()
+        .get_extension::<PluginLatency>()
+        .is_some());
+    assert_eq!(EXTENSION_QUERIES.load(Ordering::SeqCst), queries_before + 3);
+}
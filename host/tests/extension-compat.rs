@@ -0,0 +1,184 @@
+use clack_host::extensions::prelude::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const NEW_ID: &CStr = match CStr::from_bytes_with_nul(b"clack.test-greeting/1\0") {
+    Ok(id) => id,
+    Err(_) => panic!(),
+};
+
+const OLD_ID: &CStr = match CStr::from_bytes_with_nul(b"clack.test-greeting.draft/0\0") {
+    Ok(id) => id,
+    Err(_) => panic!(),
+};
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct RawGreeting {
+    value: u32,
+}
+
+static GREETING: RawGreeting = RawGreeting { value: 42 };
+
+/// A plugin extension that's only known by its old identifier.
+#[derive(Copy, Clone)]
+struct OldPluginGreeting(#[allow(dead_code)] RawExtension<PluginExtensionSide, RawGreeting>);
+
+// SAFETY: the identifier is unique to this test.
+unsafe impl Extension for OldPluginGreeting {
+    const IDENTIFIER: &'static CStr = OLD_ID;
+    type ExtensionSide = PluginExtensionSide;
+
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+// SAFETY: the implementation matches the extension type.
+unsafe impl<P: Plugin> clack_plugin::extensions::ExtensionImplementation<P> for OldPluginGreeting {
+    const IMPLEMENTATION: RawExtensionImplementation = RawExtensionImplementation::new(&GREETING);
+}
+
+/// The same plugin extension, known by its new identifier.
+#[derive(Copy, Clone)]
+struct PluginGreeting(RawExtension<PluginExtensionSide, RawGreeting>);
+
+// SAFETY: the identifiers are unique to this test, and both share the same ABI.
+unsafe impl Extension for PluginGreeting {
+    const IDENTIFIER: &'static CStr = NEW_ID;
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[OLD_ID];
+    type ExtensionSide = PluginExtensionSide;
+
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// A host extension that's only known by its old identifier.
+#[derive(Copy, Clone)]
+struct OldHostGreeting(#[allow(dead_code)] RawExtension<HostExtensionSide, RawGreeting>);
+
+// SAFETY: the identifier is unique to this test.
+unsafe impl Extension for OldHostGreeting {
+    const IDENTIFIER: &'static CStr = OLD_ID;
+    type ExtensionSide = HostExtensionSide;
+
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+/// The same host extension, known by its new identifier.
+#[derive(Copy, Clone)]
+struct HostGreeting(#[allow(dead_code)] RawExtension<HostExtensionSide, RawGreeting>);
+
+// SAFETY: the identifiers are unique to this test, and both share the same ABI.
+unsafe impl Extension for HostGreeting {
+    const IDENTIFIER: &'static CStr = NEW_ID;
+    const COMPAT_IDENTIFIERS: &'static [&'static CStr] = &[OLD_ID];
+    type ExtensionSide = HostExtensionSide;
+
+    unsafe fn from_raw(raw: RawExtension<Self::ExtensionSide>) -> Self {
+        Self(raw.cast())
+    }
+}
+
+// SAFETY: the implementation matches the extension type.
+unsafe impl<H: HostHandlers> ExtensionImplementation<H> for HostGreeting {
+    const IMPLEMENTATION: RawExtensionImplementation = RawExtensionImplementation::new(&GREETING);
+}
+
+/// Whether the plugin found the host's extension through its old identifier.
+static PLUGIN_FOUND_HOST_GREETING: AtomicBool = AtomicBool::new(false);
+
+pub struct OldPlugin;
+pub struct OldPluginMainThread;
+
+impl PluginMainThread<'_, ()> for OldPluginMainThread {}
+
+impl Plugin for OldPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = OldPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<OldPluginGreeting>();
+    }
+}
+
+impl DefaultPluginFactory for OldPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("old", "Old plugin")
+    }
+
+    fn new_shared(host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        let (_, identifier) = host
+            .get_extension_with_identifier::<OldHostGreeting>()
+            .ok_or(PluginError::Message("Missing host extension"))?;
+
+        assert_eq!(identifier, OLD_ID);
+        PLUGIN_FOUND_HOST_GREETING.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(OldPluginMainThread)
+    }
+}
+
+static OLD_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<OldPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostGreeting>();
+    }
+}
+
+#[test]
+pub fn finds_extensions_through_compat_identifiers() {
+    // SAFETY: the entry is a valid, Clack-generated entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&OLD_PLUGIN_ENTRY, "/old.so") };
+    let host_info = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle.unwrap(),
+        CStr::from_bytes_with_nul(b"old\0").unwrap(),
+        &host_info,
+    )
+    .unwrap();
+
+    assert!(PLUGIN_FOUND_HOST_GREETING.load(Ordering::SeqCst));
+
+    let (greeting, identifier) = instance
+        .plugin_shared_handle()
+        .get_extension_with_identifier::<PluginGreeting>()
+        .unwrap();
+
+    assert_eq!(identifier, OLD_ID);
+    // SAFETY: the pointer comes from the plugin's static RawGreeting.
+    assert_eq!(unsafe { greeting.0.as_ptr().as_ref().value }, 42);
+
+    assert!(instance.cached_extension::<PluginGreeting>().is_some());
+}
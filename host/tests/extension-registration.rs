@@ -0,0 +1,215 @@
+use clack_extensions::latency::{PluginLatency, PluginLatencyImpl};
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_host::extensions::Extension;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::extensions::RawExtensionImplementation;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const THIRD_PARTY_ID: &CStr = match CStr::from_bytes_with_nul(b"com.example.third-party/1\0") {
+    Ok(id) => id,
+    Err(_) => panic!(),
+};
+
+#[repr(C)]
+struct RawThirdParty {
+    value: u32,
+}
+
+static THIRD_PARTY: RawThirdParty = RawThirdParty { value: 42 };
+
+/// Whether the plugin found the host's log extension.
+static PLUGIN_FOUND_LOG: AtomicBool = AtomicBool::new(false);
+/// Whether the plugin found the host's third-party extension.
+static PLUGIN_FOUND_THIRD_PARTY: AtomicBool = AtomicBool::new(false);
+
+pub struct DynamicPlugin;
+pub struct DynamicPluginMainThread;
+
+impl PluginMainThread<'_, ()> for DynamicPluginMainThread {}
+
+impl PluginLatencyImpl for DynamicPluginMainThread {
+    fn get(&mut self) -> u32 {
+        0
+    }
+}
+
+impl Plugin for DynamicPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = DynamicPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, shared: Option<&()>) {
+        // Latency is only exposed once the plugin is initialized.
+        builder.register_if::<PluginLatency>(shared.is_some());
+
+        // SAFETY: the implementation matches the third-party extension's ABI.
+        unsafe {
+            builder.register_raw(
+                THIRD_PARTY_ID,
+                RawExtensionImplementation::new(&THIRD_PARTY),
+            );
+        }
+    }
+}
+
+impl DefaultPluginFactory for DynamicPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("dynamic", "Dynamic plugin")
+    }
+
+    fn new_shared(host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        PLUGIN_FOUND_LOG.store(host.get_extension::<HostLog>().is_some(), Ordering::SeqCst);
+
+        let third_party = host.get_raw_extension(THIRD_PARTY_ID);
+        PLUGIN_FOUND_THIRD_PARTY.store(
+            third_party.map(|p| p.as_ptr() as *const _)
+                == Some(&THIRD_PARTY as *const RawThirdParty),
+            Ordering::SeqCst,
+        );
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(DynamicPluginMainThread)
+    }
+}
+
+static DYNAMIC_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<DynamicPlugin>);
+
+struct MyHostShared {
+    logging_enabled: bool,
+    duplicate: Option<Duplicate>,
+}
+
+#[derive(Copy, Clone)]
+enum Duplicate {
+    /// The same extension type is registered twice.
+    SameExtension,
+    /// A raw extension is registered with the identifier of an extension type.
+    ConflictingIdentifier,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, _severity: LogSeverity, _message: &str) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, shared: &Self::Shared<'_>) {
+        builder.register_if::<HostLog>(shared.logging_enabled);
+
+        // SAFETY: the implementation matches the third-party extension's ABI.
+        unsafe {
+            builder.register_raw(
+                THIRD_PARTY_ID,
+                clack_host::extensions::RawExtensionImplementation::new(&THIRD_PARTY),
+            );
+        }
+
+        match shared.duplicate {
+            Some(Duplicate::SameExtension) => {
+                builder.register::<HostLog>();
+            }
+            // SAFETY: this is never actually exposed to the plugin.
+            Some(Duplicate::ConflictingIdentifier) => unsafe {
+                builder.register_raw(
+                    HostLog::IDENTIFIER,
+                    clack_host::extensions::RawExtensionImplementation::new(&THIRD_PARTY),
+                );
+            },
+            None => {}
+        }
+    }
+}
+
+fn try_instantiate(
+    logging_enabled: bool,
+    duplicate: Option<Duplicate>,
+) -> Result<PluginInstance<MyHost>, PluginInstanceError> {
+    // SAFETY: the entry is a valid, Clack-generated entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&DYNAMIC_PLUGIN_ENTRY, "/dynamic.so") };
+    let host_info = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            logging_enabled,
+            duplicate,
+        },
+        |_| (),
+        &bundle.unwrap(),
+        CStr::from_bytes_with_nul(b"dynamic\0").unwrap(),
+        &host_info,
+    )
+}
+
+fn instantiate(logging_enabled: bool) -> PluginInstance<MyHost> {
+    try_instantiate(logging_enabled, None).unwrap()
+}
+
+#[test]
+pub fn registers_extensions_conditionally() {
+    let instance = instantiate(true);
+    assert!(PLUGIN_FOUND_LOG.load(Ordering::SeqCst));
+    assert!(instance
+        .plugin_shared_handle()
+        .get_extension::<PluginLatency>()
+        .is_some());
+
+    let instance = instantiate(false);
+    assert!(!PLUGIN_FOUND_LOG.load(Ordering::SeqCst));
+
+    // Raw extensions are registered regardless.
+    assert!(PLUGIN_FOUND_THIRD_PARTY.load(Ordering::SeqCst));
+
+    let third_party = instance
+        .plugin_shared_handle()
+        .get_raw_extension(THIRD_PARTY_ID)
+        .unwrap();
+
+    assert_eq!(
+        third_party.as_ptr() as *const RawThirdParty,
+        &THIRD_PARTY as *const _
+    );
+    // SAFETY: the pointer comes from the plugin's static RawThirdParty.
+    assert_eq!(
+        unsafe { third_party.cast::<RawThirdParty>().as_ref().value },
+        42
+    );
+}
+
+#[test]
+pub fn rejects_duplicate_registrations() {
+    for duplicate in [Duplicate::SameExtension, Duplicate::ConflictingIdentifier] {
+        let error = try_instantiate(true, Some(duplicate)).err();
+        assert_eq!(
+            error,
+            Some(PluginInstanceError::DuplicateExtension(
+                HostLog::IDENTIFIER.to_owned()
+            ))
+        );
+
+        // The duplicated identifier is included in the error message.
+        let message = error.unwrap().to_string();
+        assert!(message.ends_with(HostLog::IDENTIFIER.to_str().unwrap()));
+    }
+
+    // Conditionally skipped registrations are not duplicates.
+    assert!(try_instantiate(false, Some(Duplicate::SameExtension)).is_ok());
+}
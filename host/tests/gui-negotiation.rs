@@ -0,0 +1,179 @@
+use clack_extensions::gui::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SUPPORTS_EMBEDDED: AtomicBool = AtomicBool::new(true);
+static SUPPORTS_FLOATING: AtomicBool = AtomicBool::new(true);
+static PREFERS_FLOATING: AtomicBool = AtomicBool::new(false);
+
+pub struct GuiPlugin;
+pub struct GuiPluginMainThread;
+
+impl PluginMainThread<'_, ()> for GuiPluginMainThread {}
+
+impl Plugin for GuiPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = GuiPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginGui>();
+    }
+}
+
+impl DefaultPluginFactory for GuiPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("gui", "GUI plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(GuiPluginMainThread)
+    }
+}
+
+impl PluginGuiImpl for GuiPluginMainThread {
+    fn is_api_supported(&mut self, configuration: GuiConfiguration) -> bool {
+        configuration.api_type == GuiApiType::X11
+            && if configuration.is_floating {
+                SUPPORTS_FLOATING.load(Ordering::SeqCst)
+            } else {
+                SUPPORTS_EMBEDDED.load(Ordering::SeqCst)
+            }
+    }
+
+    fn get_preferred_api(&mut self) -> Option<GuiConfiguration> {
+        PREFERS_FLOATING
+            .load(Ordering::SeqCst)
+            .then_some(GuiConfiguration {
+                api_type: GuiApiType::X11,
+                is_floating: true,
+            })
+    }
+
+    fn create(&mut self, _configuration: GuiConfiguration) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn destroy(&mut self) {}
+
+    fn set_scale(&mut self, _scale: f64) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn get_size(&mut self) -> Option<GuiSize> {
+        Some(GuiSize {
+            width: 640,
+            height: 480,
+        })
+    }
+
+    fn set_size(&mut self, _size: GuiSize) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn set_parent(&mut self, _window: Window) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn set_transient(&mut self, _window: Window) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn show(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+}
+
+static GUI_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<GuiPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn negotiates_gui_configuration() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&GUI_ENTRY, "/gui") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"gui\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let gui = plugin.get_extension::<PluginGui>().unwrap();
+
+    let embedded = GuiConfiguration {
+        api_type: GuiApiType::X11,
+        is_floating: false,
+    };
+    let floating = GuiConfiguration {
+        is_floating: true,
+        ..embedded
+    };
+
+    // Embedding is favored when the plugin has no preference.
+    let negotiation = gui.negotiate(&mut plugin, &[GuiApiType::WAYLAND, GuiApiType::X11]);
+    assert_eq!(negotiation, GuiNegotiation::Embedded(embedded));
+    assert_eq!(negotiation.is_floating(), Some(false));
+
+    PREFERS_FLOATING.store(true, Ordering::SeqCst);
+    assert_eq!(
+        gui.negotiate(&mut plugin, &[GuiApiType::X11]),
+        GuiNegotiation::Preferred(floating)
+    );
+
+    // The preference is ignored if the host can't use it, or if the plugin doesn't accept it.
+    assert_eq!(
+        gui.negotiate(&mut plugin, &[GuiApiType::WIN32]),
+        GuiNegotiation::Headless
+    );
+    SUPPORTS_FLOATING.store(false, Ordering::SeqCst);
+    assert_eq!(
+        gui.negotiate(&mut plugin, &[GuiApiType::X11]),
+        GuiNegotiation::Embedded(embedded)
+    );
+
+    PREFERS_FLOATING.store(false, Ordering::SeqCst);
+    SUPPORTS_FLOATING.store(true, Ordering::SeqCst);
+    SUPPORTS_EMBEDDED.store(false, Ordering::SeqCst);
+    assert_eq!(
+        gui.negotiate(&mut plugin, &[GuiApiType::X11]),
+        GuiNegotiation::Floating(floating)
+    );
+
+    SUPPORTS_FLOATING.store(false, Ordering::SeqCst);
+    let negotiation = gui.negotiate(&mut plugin, &[GuiApiType::X11]);
+    assert_eq!(negotiation, GuiNegotiation::Headless);
+    assert_eq!(negotiation.configuration(), None);
+}
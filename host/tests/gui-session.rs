@@ -0,0 +1,342 @@
+use clack_extensions::gui::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::cell::Cell;
+use std::ffi::CStr;
+
+thread_local! {
+    /// The number of times the plugin's GUI was destroyed on the current thread.
+    static DESTROY_CALLS: Cell<usize> = const { Cell::new(0) };
+    /// The current size of the plugin's GUI on the current thread.
+    static SIZE: Cell<GuiSize> = const { Cell::new(GuiSize { width: 640, height: 480 }) };
+    /// The scale last set to the plugin's GUI on the current thread.
+    static SCALE: Cell<Option<f64>> = const { Cell::new(None) };
+}
+
+pub struct GuiPlugin;
+pub struct GuiPluginMainThread;
+
+impl PluginMainThread<'_, ()> for GuiPluginMainThread {}
+
+impl Plugin for GuiPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = GuiPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginGui>();
+    }
+}
+
+impl DefaultPluginFactory for GuiPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("gui", "GUI plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(GuiPluginMainThread)
+    }
+}
+
+impl PluginGuiImpl for GuiPluginMainThread {
+    fn is_api_supported(&mut self, _configuration: GuiConfiguration) -> bool {
+        true
+    }
+
+    fn get_preferred_api(&mut self) -> Option<GuiConfiguration> {
+        None
+    }
+
+    fn create(&mut self, _configuration: GuiConfiguration) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn destroy(&mut self) {
+        DESTROY_CALLS.with(|c| c.set(c.get() + 1));
+    }
+
+    fn set_scale(&mut self, scale: f64) -> Result<(), PluginError> {
+        SCALE.with(|s| s.set(Some(scale)));
+        Ok(())
+    }
+
+    fn get_size(&mut self) -> Option<GuiSize> {
+        Some(SIZE.with(Cell::get))
+    }
+
+    fn can_resize(&mut self) -> bool {
+        true
+    }
+
+    fn adjust_size(&mut self, size: GuiSize) -> Option<GuiSize> {
+        Some(GuiSize {
+            width: size.width.min(1920),
+            height: size.height.min(1080),
+        })
+    }
+
+    fn set_size(&mut self, size: GuiSize) -> Result<(), PluginError> {
+        SIZE.with(|s| s.set(size));
+        Ok(())
+    }
+
+    fn set_parent(&mut self, _window: Window) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn set_transient(&mut self, _window: Window) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn show(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<(), PluginError> {
+        Ok(())
+    }
+}
+
+static GUI_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<GuiPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn enforces_gui_call_ordering() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&GUI_ENTRY, "/gui") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"gui\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let mut session = GuiSession::new(plugin.get_extension::<PluginGui>().unwrap());
+    let window = Window::from_x11_handle(42);
+    let configuration = GuiConfiguration {
+        api_type: GuiApiType::X11,
+        is_floating: false,
+    };
+
+    assert_eq!(session.state(), GuiSessionState::Destroyed);
+    assert_eq!(
+        session.show(&mut plugin),
+        Err(GuiSessionError::InvalidState {
+            operation: "show",
+            state: GuiSessionState::Destroyed
+        })
+    );
+
+    session.create(&mut plugin, configuration).unwrap();
+    assert!(matches!(
+        session.create(&mut plugin, configuration),
+        Err(GuiSessionError::InvalidState { .. })
+    ));
+
+    // Embedded GUIs must be attached before being shown.
+    assert!(session.show(&mut plugin).is_err());
+    assert_eq!(
+        session.suggest_title(&mut plugin, CStr::from_bytes_with_nul(b"Title\0").unwrap()),
+        Err(GuiSessionError::NotFloating {
+            operation: "suggest_title"
+        })
+    );
+
+    session.set_scale(&mut plugin, 2.0).unwrap();
+    unsafe { session.set_parent(&mut plugin, window) }.unwrap();
+    assert!(unsafe { session.set_parent(&mut plugin, window) }.is_err());
+
+    session.show(&mut plugin).unwrap();
+    assert!(session.is_visible());
+    session.hide(&mut plugin).unwrap();
+    assert!(session.hide(&mut plugin).is_err());
+    session.show(&mut plugin).unwrap();
+
+    session.handle_closed(&mut plugin, GuiClosedReason::Destroyed);
+    assert_eq!(session.state(), GuiSessionState::Destroyed);
+
+    // Floating GUIs can be shown right after being created.
+    let configuration = GuiConfiguration {
+        is_floating: true,
+        ..configuration
+    };
+    session.create(&mut plugin, configuration).unwrap();
+    assert!(session.is_floating());
+    assert!(matches!(
+        unsafe { session.set_parent(&mut plugin, window) },
+        Err(GuiSessionError::NotEmbedded { .. })
+    ));
+    session.show(&mut plugin).unwrap();
+
+    session.handle_closed(&mut plugin, GuiClosedReason::Hidden);
+    assert_eq!(session.state(), GuiSessionState::Hidden);
+
+    session.destroy(&mut plugin);
+    assert_eq!(session.state(), GuiSessionState::Destroyed);
+}
+
+#[test]
+fn acknowledges_closed_notifications() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&GUI_ENTRY, "/gui") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"gui\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let listener = GuiClosedListener::new();
+    let mut plugin = instance.plugin_handle();
+    let mut session = GuiSession::new(plugin.get_extension::<PluginGui>().unwrap())
+        .with_closed_listener(listener.clone());
+
+    let configuration = GuiConfiguration {
+        api_type: GuiApiType::X11,
+        is_floating: true,
+    };
+
+    session.create(&mut plugin, configuration).unwrap();
+    session.show(&mut plugin).unwrap();
+
+    // The user closed the floating window: it can be shown again.
+    listener.notify(GuiClosedReason::Hidden);
+    assert_eq!(session.state(), GuiSessionState::Hidden);
+    session.show(&mut plugin).unwrap();
+    assert!(session.is_visible());
+
+    // The GUI was destroyed by the plugin: it must not be called into anymore.
+    let destroy_calls = DESTROY_CALLS.with(Cell::get);
+    listener.notify(GuiClosedReason::Destroyed);
+    listener.notify(GuiClosedReason::Hidden);
+    assert_eq!(session.state(), GuiSessionState::Destroyed);
+    assert_eq!(session.get_size(&mut plugin), None);
+    assert_eq!(
+        session.hide(&mut plugin),
+        Err(GuiSessionError::InvalidState {
+            operation: "hide",
+            state: GuiSessionState::Destroyed
+        })
+    );
+
+    // The destruction was acknowledged exactly once.
+    assert_eq!(DESTROY_CALLS.with(Cell::get), destroy_calls + 1);
+    assert_eq!(listener.pending(), None);
+
+    session.handle_closed(&mut plugin, GuiClosedReason::Destroyed);
+    session.destroy(&mut plugin);
+    assert_eq!(DESTROY_CALLS.with(Cell::get), destroy_calls + 1);
+
+    // A new GUI can then be created.
+    session.create(&mut plugin, configuration).unwrap();
+    assert_eq!(session.state(), GuiSessionState::Created);
+    session.destroy(&mut plugin);
+}
+
+#[test]
+fn persists_window_state() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&GUI_ENTRY, "/gui") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"gui\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let mut session = GuiSession::new(plugin.get_extension::<PluginGui>().unwrap());
+    let mut store: Option<GuiWindowState> = None;
+
+    let configuration = GuiConfiguration {
+        api_type: GuiApiType::X11,
+        is_floating: false,
+    };
+
+    // Nothing to restore yet.
+    session
+        .create_restored(&mut plugin, configuration, &mut store)
+        .unwrap();
+    assert_eq!(SCALE.with(Cell::get), None);
+
+    session.set_scale(&mut plugin, 1.5).unwrap();
+    let size = GuiSize {
+        width: 3000,
+        height: 900,
+    };
+    let size = session.adjust_size(&mut plugin, size).unwrap();
+    session.set_size(&mut plugin, size).unwrap();
+
+    session.destroy_and_save(&mut plugin, &mut store);
+    assert_eq!(session.state(), GuiSessionState::Destroyed);
+    assert_eq!(
+        store,
+        Some(GuiWindowState {
+            size: Some(GuiSize {
+                width: 1920,
+                height: 900
+            }),
+            scale: Some(1.5),
+            is_floating: false,
+        })
+    );
+
+    // The plugin's GUI is re-created with the saved state.
+    SIZE.with(|s| {
+        s.set(GuiSize {
+            width: 640,
+            height: 480,
+        })
+    });
+    SCALE.with(|s| s.set(None));
+
+    let floating = GuiConfiguration {
+        is_floating: true,
+        ..configuration
+    };
+    session
+        .create_restored(&mut plugin, floating, &mut store)
+        .unwrap();
+
+    assert!(!session.is_floating());
+    assert_eq!(session.scale(), Some(1.5));
+    assert_eq!(SCALE.with(Cell::get), Some(1.5));
+    assert_eq!(SIZE.with(Cell::get).width, 1920);
+    assert_eq!(session.capture_window_state(&mut plugin), store);
+
+    session.destroy(&mut plugin);
+    assert_eq!(session.capture_window_state(&mut plugin), None);
+}
@@ -0,0 +1,133 @@
+use clack_host::plugin::registry::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::{Arc, Mutex};
+
+pub struct MyPlugin;
+pub struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my-plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[derive(Default, Clone)]
+struct RecordingHooks(Arc<Mutex<Vec<(&'static str, InstanceId)>>>);
+
+impl InstanceRegistryHooks for RecordingHooks {
+    fn instantiated(&self, info: &InstanceInfo) {
+        self.0.lock().unwrap().push(("instantiated", info.id()));
+    }
+
+    fn destroyed(&self, info: &InstanceInfo) {
+        self.0.lock().unwrap().push(("destroyed", info.id()));
+    }
+}
+
+fn instantiate(
+    registry: &InstanceRegistry,
+    bundle: &PluginBundle,
+    plugin_id: &[u8],
+) -> Result<RegisteredPluginInstance<MyHost>, PluginInstanceError> {
+    let host_info = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    registry.instantiate::<MyHost, _, _>(
+        |_| MyHostShared,
+        |_| (),
+        bundle,
+        CStr::from_bytes_with_nul(plugin_id).unwrap(),
+        &host_info,
+    )
+}
+
+#[test]
+pub fn tracks_live_instances() {
+    // SAFETY: the entry is a valid, Clack-generated entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my-plugin.so") }.unwrap();
+    let hooks = RecordingHooks::default();
+    let registry = InstanceRegistry::with_hooks(hooks.clone());
+
+    assert!(registry.is_empty());
+    assert!(!registry.has_instances_of(&bundle));
+
+    let mut first = instantiate(&registry, &bundle, b"my-plugin\0").unwrap();
+    let second = instantiate(&registry, &bundle, b"my-plugin\0").unwrap();
+
+    // Registered instances can be used just like regular instances.
+    assert!(first.descriptor().is_some());
+    let _ = first.plugin_handle();
+
+    assert_eq!(registry.len(), 2);
+    assert!(registry.has_instances_of(&bundle));
+
+    let instances = registry.instances_of(&bundle);
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[0].id(), first.id());
+    assert_eq!(instances[1].id(), second.id());
+    assert_eq!(instances[0].plugin_id().to_bytes(), b"my-plugin");
+
+    // Failed instantiations are never registered.
+    assert!(instantiate(&registry, &bundle, b"unknown\0").is_err());
+    assert_eq!(registry.len(), 2);
+
+    let (first_id, second_id) = (first.id(), second.id());
+    drop(first);
+
+    assert_eq!(registry.len(), 1);
+    assert!(registry.get(first_id).is_none());
+    assert!(registry.get(second_id).is_some());
+
+    drop(second);
+    assert!(registry.is_empty());
+    assert!(!registry.has_instances_of(&bundle));
+
+    assert_eq!(
+        *hooks.0.lock().unwrap(),
+        [
+            ("instantiated", first_id),
+            ("instantiated", second_id),
+            ("destroyed", first_id),
+            ("destroyed", second_id),
+        ]
+    );
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "assert-no-alloc")]
+
+use clack_host::prelude::*;
+use clack_host::utils::{assert_no_alloc, AllocDetector};
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::alloc::System;
+use std::ffi::CStr;
+use std::panic::AssertUnwindSafe;
+
+#[global_allocator]
+static ALLOCATOR: AllocDetector<System> = AllocDetector::new(System);
+
+pub struct AllocatingPlugin;
+pub struct AllocatingPluginMainThread;
+
+impl PluginMainThread<'_, ()> for AllocatingPluginMainThread {}
+
+impl Plugin for AllocatingPlugin {
+    type AudioProcessor<'a> = AllocatingPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = AllocatingPluginMainThread;
+}
+
+impl DefaultPluginFactory for AllocatingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("allocating", "Allocating plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(AllocatingPluginMainThread)
+    }
+}
+
+pub struct AllocatingPluginAudioProcessor {
+    processed: Vec<u32>,
+}
+
+impl<'a> PluginAudioProcessor<'a, (), AllocatingPluginMainThread>
+    for AllocatingPluginAudioProcessor
+{
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut AllocatingPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self {
+            processed: Vec::with_capacity(1),
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        // Only the second push will need to reallocate.
+        self.processed.push(0);
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static ALLOCATING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<AllocatingPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn detects_allocations_in_process() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&ALLOCATING_ENTRY, "/allocating") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"allocating\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 5,
+        max_frames_count: 5,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut process = || {
+        processor.process(
+            &InputAudioBuffers::empty(),
+            &mut OutputAudioBuffers::empty(),
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+    };
+
+    // The first block fits in the preallocated capacity.
+    assert!(process().is_ok());
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(process));
+    assert!(result.is_err());
+}
+
+#[test]
+fn inline_audio_ports_do_not_allocate() {
+    let mut input_ports = InlineAudioPorts::<2, 4>::new();
+    let mut output_ports = InlineAudioPorts::<2, 4>::new();
+    let mut input_bufs = [[[0f32; 16]; 2]; 2];
+    let mut output_bufs = [[[0f32; 16]; 2]; 2];
+
+    assert_no_alloc(|| {
+        let inputs =
+            input_ports.with_input_buffers(input_bufs.iter_mut().map(|bufs| AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_input_only(
+                    bufs.iter_mut().map(InputChannel::variable),
+                ),
+            }));
+
+        let outputs =
+            output_ports.with_output_buffers(output_bufs.iter_mut().map(|bufs| AudioPortBuffer {
+                latency: 0,
+                channels: AudioPortBufferType::f32_output_only(
+                    bufs.iter_mut().map(|b| b.as_mut_slice()),
+                ),
+            }));
+
+        assert_eq!(inputs.min_available_frames_with(&outputs), 16);
+    });
+}
@@ -0,0 +1,160 @@
+use clack_extensions::note_ports::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the plugin exposes its second port layout.
+static CHANGED: AtomicBool = AtomicBool::new(false);
+/// Whether the plugin fails to provide information about its first input port.
+static BROKEN_FIRST_PORT: AtomicBool = AtomicBool::new(false);
+
+pub struct NotesPlugin;
+pub struct NotesPluginMainThread;
+
+impl PluginMainThread<'_, ()> for NotesPluginMainThread {}
+
+impl Plugin for NotesPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = NotesPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginNotePorts>();
+    }
+}
+
+impl DefaultPluginFactory for NotesPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("notes", "Notes plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(NotesPluginMainThread)
+    }
+}
+
+impl PluginNotePortsImpl for NotesPluginMainThread {
+    fn count(&mut self, is_input: bool) -> u32 {
+        match (is_input, CHANGED.load(Ordering::SeqCst)) {
+            (true, false) => 1,
+            (true, true) => 2,
+            (false, _) => 0,
+        }
+    }
+
+    fn get(&mut self, index: u32, _is_input: bool, writer: &mut NotePortInfoWriter) {
+        if index == 0 && BROKEN_FIRST_PORT.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let changed = CHANGED.load(Ordering::SeqCst);
+
+        writer.set(&NotePortInfo {
+            id: ClapId::new(index),
+            name: if changed { b"Renamed" } else { b"Notes" },
+            supported_dialects: NoteDialects::CLAP | NoteDialects::MIDI,
+            preferred_dialect: Some(if changed {
+                NoteDialect::Midi
+            } else {
+                NoteDialect::Clap
+            }),
+        });
+    }
+}
+
+static NOTES_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<NotesPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn handles_note_ports_rescans() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&NOTES_ENTRY, "/notes") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"notes\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let note_ports = instance
+        .plugin_handle()
+        .get_extension::<PluginNotePorts>()
+        .unwrap();
+
+    let mut scan = note_ports.scan(&mut instance.plugin_handle(), NoteDialects::all());
+    assert_eq!(scan.inputs().len(), 1);
+    assert_eq!(scan.dialect(0, true), Some(NoteDialect::Clap));
+
+    CHANGED.store(true, Ordering::SeqCst);
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 256,
+    };
+    let processor = instance.activate(|_, _| (), config).unwrap();
+
+    // Renaming ports is allowed while active, and doesn't change the layout.
+    assert_eq!(
+        scan.handle_rescan(&note_ports, &mut instance, NotePortRescanFlags::NAMES),
+        Ok(NotePortsRescanAction::UpdateNames)
+    );
+    assert_eq!(scan.inputs().len(), 1);
+    assert_eq!(scan.inputs()[0].name, b"Renamed");
+    assert_eq!(scan.dialect(0, true), Some(NoteDialect::Clap));
+
+    // Layout changes require the plugin to be deactivated.
+    assert_eq!(
+        scan.handle_rescan(&note_ports, &mut instance, NotePortRescanFlags::ALL),
+        Err(NotePortsRescanError::PluginActive)
+    );
+    assert_eq!(scan.inputs().len(), 1);
+
+    instance.deactivate(processor);
+
+    assert_eq!(
+        scan.handle_rescan(&note_ports, &mut instance, NotePortRescanFlags::ALL),
+        Ok(NotePortsRescanAction::Reactivate)
+    );
+    assert_eq!(scan.inputs().len(), 2);
+    assert_eq!(scan.dialect(1, true), Some(NoteDialect::Midi));
+
+    // Ports that fail to scan are skipped, but the other ports keep their plugin index.
+    BROKEN_FIRST_PORT.store(true, Ordering::SeqCst);
+    scan.rescan(
+        &note_ports,
+        &mut instance.plugin_handle(),
+        NotePortRescanFlags::ALL,
+    );
+    assert_eq!(scan.inputs().len(), 1);
+    assert_eq!(scan.inputs()[0].index, 1);
+    assert_eq!(scan.port(0, true), None);
+    assert_eq!(scan.dialect(1, true), Some(NoteDialect::Midi));
+}
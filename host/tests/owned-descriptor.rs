@@ -0,0 +1,65 @@
+use clack_host::factory::OwnedPluginDescriptor;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CString;
+
+pub struct DescribedPlugin;
+
+impl Plugin for DescribedPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for DescribedPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        use clack_plugin::plugin::features::*;
+
+        PluginDescriptor::new("my.plugin", "My Plugin")
+            .with_vendor("Me")
+            .with_version("1.2.3")
+            .with_features([AUDIO_EFFECT, STEREO])
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+static DESCRIBED_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<DescribedPlugin>);
+
+#[test]
+fn can_own_plugin_descriptors() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&DESCRIBED_ENTRY, "/described") }.unwrap();
+    let descriptor = bundle
+        .get_plugin_factory()
+        .unwrap()
+        .plugin_descriptors()
+        .next()
+        .unwrap();
+
+    let owned = OwnedPluginDescriptor::from(descriptor);
+    drop(bundle);
+
+    let c = |s: &str| Some(CString::new(s).unwrap());
+    assert_eq!(owned.id, c("my.plugin"));
+    assert_eq!(owned.name, c("My Plugin"));
+    assert_eq!(owned.vendor, c("Me"));
+    assert_eq!(owned.version, c("1.2.3"));
+    assert_eq!(owned.url, None);
+    assert_eq!(
+        owned.features,
+        [
+            CString::new("audio-effect").unwrap(),
+            CString::new("stereo").unwrap()
+        ]
+    );
+}
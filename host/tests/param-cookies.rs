@@ -0,0 +1,237 @@
+use clack_extensions::params::*;
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::utils::Cookie;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// The values the audio processor received, alongside whether they came with a valid cookie.
+static RECEIVED: Mutex<Vec<(ClapId, f64, bool)>> = Mutex::new(Vec::new());
+
+const PARAM_IDS: [u32; 3] = [10, 20, 30];
+
+fn param_lookup() -> ParamLookup<f64> {
+    let mut params = ParamLookup::new();
+    for id in PARAM_IDS {
+        params.push(ClapId::new(id), 0.0);
+    }
+    params
+}
+
+pub struct CookiePlugin;
+pub struct CookiePluginMainThread {
+    params: ParamLookup<f64>,
+}
+pub struct CookiePluginAudioProcessor {
+    params: ParamLookup<f64>,
+}
+
+impl PluginMainThread<'_, ()> for CookiePluginMainThread {}
+
+impl Plugin for CookiePlugin {
+    type AudioProcessor<'a> = CookiePluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = CookiePluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParams>();
+    }
+}
+
+impl DefaultPluginFactory for CookiePlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("cookies", "Cookie plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(CookiePluginMainThread {
+            params: param_lookup(),
+        })
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), CookiePluginMainThread> for CookiePluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut CookiePluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self {
+            params: param_lookup(),
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        self.flush(events.input, events.output);
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl PluginAudioProcessorParams for CookiePluginAudioProcessor {
+    fn flush(&mut self, input: &InputEvents, _output: &mut OutputEvents) {
+        for event in input {
+            let Some(event) = event.as_event::<ParamValueEvent>() else {
+                continue;
+            };
+
+            let param_id = event.param_id().unwrap();
+            let has_cookie = event.cookie().index().is_some();
+
+            if let Some(value) = self.params.for_value_event_mut(event) {
+                *value = event.value();
+                RECEIVED
+                    .lock()
+                    .unwrap()
+                    .push((param_id, event.value(), has_cookie));
+            }
+        }
+    }
+}
+
+impl PluginMainThreadParams for CookiePluginMainThread {
+    fn count(&mut self) -> u32 {
+        self.params.len() as u32
+    }
+
+    fn get_info(&mut self, param_index: u32, info: &mut ParamInfoWriter) {
+        let Some((id, _)) = self.params.get_index(param_index as usize) else {
+            return;
+        };
+
+        info.set(&ParamInfo {
+            id,
+            flags: ParamInfoFlags::IS_AUTOMATABLE,
+            cookie: self.params.cookie(param_index as usize).unwrap(),
+            name: b"Param",
+            module: b"",
+            min_value: 0.0,
+            max_value: 1.0,
+            default_value: 0.0,
+        })
+    }
+
+    fn get_value(&mut self, param_id: ClapId) -> Option<f64> {
+        self.params.get(param_id).copied()
+    }
+
+    fn value_to_text(
+        &mut self,
+        _param_id: ClapId,
+        value: f64,
+        writer: &mut ParamDisplayWriter,
+    ) -> std::fmt::Result {
+        write!(writer, "{value}")
+    }
+
+    fn text_to_value(&mut self, _param_id: ClapId, _text: &CStr) -> Option<f64> {
+        None
+    }
+
+    fn flush(&mut self, _input: &InputEvents, _output: &mut OutputEvents) {}
+}
+
+static COOKIES_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<CookiePlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn cache_events_carry_cookies() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&COOKIES_ENTRY, "/cookies") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"cookies\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let params = instance
+        .plugin_handle()
+        .get_extension::<PluginParams>()
+        .unwrap();
+    let cache = params.scan(&mut instance.plugin_handle());
+
+    let second = cache.get(ClapId::new(20)).unwrap();
+    assert_eq!(second.cookie, Cookie::from_index(1));
+
+    let mut input_events = EventBuffer::new();
+    input_events.push(
+        &cache
+            .value_event(0, ClapId::new(20), Pckn::match_all(), 0.5)
+            .unwrap(),
+    );
+    input_events.push(&ParamValueEvent::new(
+        1,
+        ClapId::new(30),
+        Pckn::match_all(),
+        0.75,
+        Cookie::empty(),
+    ));
+    assert!(cache
+        .value_event(0, ClapId::new(40), Pckn::match_all(), 0.5)
+        .is_none());
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    processor
+        .process(
+            &InputAudioBuffers::empty(),
+            &mut OutputAudioBuffers::empty(),
+            &input_events.as_input(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    instance.deactivate(processor.stop_processing());
+
+    // The event without a cookie is still handled, using the slower lookup.
+    assert_eq!(
+        *RECEIVED.lock().unwrap(),
+        [(ClapId::new(20), 0.5, true), (ClapId::new(30), 0.75, false)]
+    );
+}
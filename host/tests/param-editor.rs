@@ -0,0 +1,202 @@
+use clack_extensions::params::*;
+use clack_host::events::event_types::{ParamGestureBeginEvent, ParamGestureEndEvent};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::events::spaces::CoreEventSpace;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Whether the plugin requested a parameter flush from the host.
+static FLUSH_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// The last value applied by the plugin, as `f64` bits.
+static APPLIED_VALUE: AtomicU64 = AtomicU64::new(0);
+
+pub struct EditorPlugin;
+
+pub struct EditorPluginMainThread<'a> {
+    editor: ParamEditor<'a>,
+    receiver: ParamEditReceiver,
+}
+
+impl<'a> PluginMainThread<'a, ()> for EditorPluginMainThread<'a> {
+    fn on_main_thread(&mut self) {
+        // Pretend the user typed a new value in the GUI.
+        self.editor.edit(ClapId::new(1), 0.75).unwrap();
+    }
+}
+
+impl Plugin for EditorPlugin {
+    type AudioProcessor<'a> = EditorPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = EditorPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParams>();
+    }
+}
+
+impl DefaultPluginFactory for EditorPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("editor", "Editor plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        let (editor, receiver) = ParamEditor::new(host.shared(), 16);
+        Ok(EditorPluginMainThread { editor, receiver })
+    }
+}
+
+pub struct EditorPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), EditorPluginMainThread<'a>> for EditorPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut EditorPluginMainThread<'a>,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl PluginAudioProcessorParams for EditorPluginAudioProcessor {
+    fn flush(&mut self, _input: &InputEvents, _output: &mut OutputEvents) {}
+}
+
+impl PluginMainThreadParams for EditorPluginMainThread<'_> {
+    fn count(&mut self) -> u32 {
+        1
+    }
+
+    fn get_info(&mut self, _param_index: u32, _info: &mut ParamInfoWriter) {}
+
+    fn get_value(&mut self, _param_id: ClapId) -> Option<f64> {
+        None
+    }
+
+    fn value_to_text(
+        &mut self,
+        _param_id: ClapId,
+        _value: f64,
+        _writer: &mut ParamDisplayWriter,
+    ) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+
+    fn text_to_value(&mut self, _param_id: ClapId, _text: &CStr) -> Option<f64> {
+        None
+    }
+
+    fn flush(
+        &mut self,
+        _input_parameter_changes: &InputEvents,
+        output_parameter_changes: &mut OutputEvents,
+    ) {
+        self.receiver.receive(output_parameter_changes, |change| {
+            APPLIED_VALUE.store(change.value.to_bits(), Ordering::SeqCst)
+        });
+    }
+}
+
+static EDITOR_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<EditorPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostParamsImplShared for MyHostShared {
+    fn request_flush(&self) {
+        FLUSH_REQUESTED.store(true, Ordering::SeqCst);
+    }
+}
+
+struct MyHostMainThread;
+
+impl MainThreadHandler<'_> for MyHostMainThread {}
+
+impl HostParamsImplMainThread for MyHostMainThread {
+    fn rescan(&mut self, _flags: ParamRescanFlags) {}
+    fn clear(&mut self, _param_id: ClapId, _flags: ParamClearFlags) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = MyHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostParams>();
+    }
+}
+
+#[test]
+fn gui_edits_are_applied_and_reported_on_flush() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&EDITOR_ENTRY, "/editor") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| MyHostMainThread,
+        &bundle,
+        CStr::from_bytes_with_nul(b"editor\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.call_on_main_thread_callback();
+    assert!(FLUSH_REQUESTED.load(Ordering::SeqCst));
+    assert_eq!(f64::from_bits(APPLIED_VALUE.load(Ordering::SeqCst)), 0.0);
+
+    let params = instance
+        .plugin_handle()
+        .get_extension::<PluginParams>()
+        .unwrap();
+
+    let mut output = EventBuffer::new();
+    params.flush(
+        &mut instance.plugin_handle(),
+        &InputEvents::empty(),
+        &mut output.as_output(),
+    );
+
+    assert_eq!(f64::from_bits(APPLIED_VALUE.load(Ordering::SeqCst)), 0.75);
+
+    let events: Vec<_> = output.iter().map(|e| e.as_core_event().unwrap()).collect();
+    assert_eq!(events.len(), 3);
+
+    assert!(matches!(
+        events[0],
+        CoreEventSpace::ParamGestureBegin(e) if *e == ParamGestureBeginEvent::new(0, ClapId::new(1))
+    ));
+    assert!(matches!(
+        events[1],
+        CoreEventSpace::ParamValue(e) if e.param_id() == Some(ClapId::new(1)) && e.value() == 0.75
+    ));
+    assert!(matches!(
+        events[2],
+        CoreEventSpace::ParamGestureEnd(e) if *e == ParamGestureEndEvent::new(0, ClapId::new(1))
+    ));
+}
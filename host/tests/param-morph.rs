@@ -0,0 +1,225 @@
+use clack_extensions::params::*;
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::Cookie;
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// The value events the audio processor received, with their time and parameter ID.
+static RECEIVED: Mutex<Vec<(u32, u32, f64)>> = Mutex::new(Vec::new());
+
+const LEVEL_ID: u32 = 1;
+const MODE_ID: u32 = 2;
+
+pub struct MorphPlugin;
+pub struct MorphPluginMainThread;
+pub struct MorphPluginAudioProcessor;
+
+impl PluginMainThread<'_, ()> for MorphPluginMainThread {}
+
+impl Plugin for MorphPlugin {
+    type AudioProcessor<'a> = MorphPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = MorphPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParams>();
+    }
+}
+
+impl DefaultPluginFactory for MorphPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("morph", "Morph plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(MorphPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), MorphPluginMainThread> for MorphPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut MorphPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        self.flush(events.input, events.output);
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl PluginAudioProcessorParams for MorphPluginAudioProcessor {
+    fn flush(&mut self, input: &InputEvents, _output: &mut OutputEvents) {
+        let mut received = RECEIVED.lock().unwrap();
+
+        for event in input {
+            if let Some(event) = event.as_event::<ParamValueEvent>() {
+                let param_id = event.param_id().unwrap().get();
+                received.push((event.header().time(), param_id, event.value()));
+            }
+        }
+    }
+}
+
+impl PluginMainThreadParams for MorphPluginMainThread {
+    fn count(&mut self) -> u32 {
+        3
+    }
+
+    fn get_info(&mut self, param_index: u32, info: &mut ParamInfoWriter) {
+        let (id, flags, max_value) = match param_index {
+            0 => (LEVEL_ID, ParamInfoFlags::IS_AUTOMATABLE, 1.0),
+            1 => (MODE_ID, ParamInfoFlags::IS_STEPPED, 3.0),
+            2 => (3, ParamInfoFlags::IS_READONLY, 1.0),
+            _ => return,
+        };
+
+        info.set(&ParamInfo {
+            id: ClapId::new(id),
+            flags,
+            cookie: Cookie::empty(),
+            name: b"Param",
+            module: b"",
+            min_value: 0.0,
+            max_value,
+            default_value: 0.0,
+        })
+    }
+
+    fn get_value(&mut self, _param_id: ClapId) -> Option<f64> {
+        Some(0.0)
+    }
+
+    fn value_to_text(
+        &mut self,
+        _param_id: ClapId,
+        value: f64,
+        writer: &mut ParamDisplayWriter,
+    ) -> std::fmt::Result {
+        write!(writer, "{value}")
+    }
+
+    fn text_to_value(&mut self, _param_id: ClapId, _text: &CStr) -> Option<f64> {
+        None
+    }
+
+    fn flush(&mut self, _input: &InputEvents, _output: &mut OutputEvents) {}
+}
+
+static MORPH_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MorphPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn morphs_between_snapshots() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&MORPH_ENTRY, "/morph") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"morph\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let params = instance
+        .plugin_handle()
+        .get_extension::<PluginParams>()
+        .unwrap();
+    let from = params.scan(&mut instance.plugin_handle());
+
+    let mut to = from.clone();
+    to.set_value(ClapId::new(LEVEL_ID), 1.0);
+    to.set_value(ClapId::new(MODE_ID), 3.0);
+    to.set_value(ClapId::new(3), 1.0);
+
+    let mut morph = ParamMorph::new(&from, &to, 8).with_resolution(4);
+    // Read-only parameters are never morphed.
+    assert_eq!(morph.param_count(), 2);
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut input_events = EventBuffer::new();
+    let mut blocks = 0;
+
+    while !morph.is_finished() {
+        input_events.clear();
+        morph.fill_events(4, &mut input_events.as_output()).unwrap();
+
+        processor
+            .process(
+                &InputAudioBuffers::empty(),
+                &mut OutputAudioBuffers::empty(),
+                &input_events.as_input(),
+                &mut OutputEvents::void(),
+                None,
+                None,
+            )
+            .unwrap();
+
+        blocks += 1;
+    }
+
+    instance.deactivate(processor.stop_processing());
+
+    assert_eq!(blocks, 3);
+    assert_eq!(morph.progress(), 1.0);
+
+    // The stepped parameter is rounded.
+    assert_eq!(
+        *RECEIVED.lock().unwrap(),
+        [
+            (0, LEVEL_ID, 0.0),
+            (0, MODE_ID, 0.0),
+            (0, LEVEL_ID, 0.5),
+            (0, MODE_ID, 2.0),
+            (0, LEVEL_ID, 1.0),
+            (0, MODE_ID, 3.0),
+        ]
+    );
+}
@@ -0,0 +1,200 @@
+use clack_extensions::params::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the plugin exposes its second set of parameters.
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+pub struct ParamsPlugin;
+pub struct ParamsPluginMainThread;
+pub struct ParamsPluginAudioProcessor;
+
+impl PluginMainThread<'_, ()> for ParamsPluginMainThread {}
+
+impl Plugin for ParamsPlugin {
+    type AudioProcessor<'a> = ParamsPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ParamsPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParams>();
+    }
+}
+
+impl DefaultPluginFactory for ParamsPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("params", "Params plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(ParamsPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ParamsPluginMainThread> for ParamsPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut ParamsPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl PluginAudioProcessorParams for ParamsPluginAudioProcessor {
+    fn flush(&mut self, _input: &InputEvents, _output: &mut OutputEvents) {}
+}
+
+fn current_params() -> &'static [(u32, &'static [u8], f64)] {
+    if CHANGED.load(Ordering::SeqCst) {
+        &[(2, b"Renamed", 0.75), (3, b"New", 0.25)]
+    } else {
+        &[(1, b"First", 0.5), (2, b"Second", 0.5)]
+    }
+}
+
+impl PluginMainThreadParams for ParamsPluginMainThread {
+    fn count(&mut self) -> u32 {
+        current_params().len() as u32
+    }
+
+    fn get_info(&mut self, param_index: u32, info: &mut ParamInfoWriter) {
+        let Some((id, name, _)) = current_params().get(param_index as usize) else {
+            return;
+        };
+
+        info.set(&ParamInfo {
+            id: ClapId::new(*id),
+            flags: ParamInfoFlags::IS_AUTOMATABLE,
+            cookie: Default::default(),
+            name,
+            module: b"",
+            min_value: 0.0,
+            max_value: 1.0,
+            default_value: 0.5,
+        })
+    }
+
+    fn get_value(&mut self, param_id: ClapId) -> Option<f64> {
+        current_params()
+            .iter()
+            .find(|(id, _, _)| *id == param_id.get())
+            .map(|(_, _, value)| *value)
+    }
+
+    fn value_to_text(
+        &mut self,
+        _param_id: ClapId,
+        value: f64,
+        writer: &mut ParamDisplayWriter,
+    ) -> std::fmt::Result {
+        write!(writer, "{value}")
+    }
+
+    fn text_to_value(&mut self, _param_id: ClapId, _text: &CStr) -> Option<f64> {
+        None
+    }
+
+    fn flush(&mut self, _input: &InputEvents, _output: &mut OutputEvents) {}
+}
+
+static PARAMS_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<ParamsPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn handles_param_rescans() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&PARAMS_ENTRY, "/params") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"params\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let params = instance
+        .plugin_handle()
+        .get_extension::<PluginParams>()
+        .unwrap();
+    let mut cache = params.scan(&mut instance.plugin_handle());
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(ClapId::new(2)).unwrap().name, b"Second");
+    assert_eq!(cache.get(ClapId::new(2)).unwrap().value, Some(0.5));
+
+    CHANGED.store(true, Ordering::SeqCst);
+
+    // Values and info can be rescanned at any time, but don't add nor remove parameters.
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let processor = instance.activate(|_, _| (), config).unwrap();
+
+    let flags = ParamRescanFlags::VALUES | ParamRescanFlags::INFO;
+    let changes = params
+        .handle_rescan(&mut instance, flags, &mut cache)
+        .unwrap();
+
+    assert_eq!(changes, ParamRescanChanges::default());
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(ClapId::new(1)).unwrap().value, None);
+    assert_eq!(cache.get(ClapId::new(2)).unwrap().name, b"Renamed");
+    assert_eq!(cache.get(ClapId::new(2)).unwrap().value, Some(0.75));
+
+    // Full rescans require the plugin to be deactivated.
+    assert_eq!(
+        params.handle_rescan(&mut instance, ParamRescanFlags::ALL, &mut cache),
+        Err(ParamRescanError::PluginActive)
+    );
+    instance.deactivate(processor);
+
+    let changes = params
+        .handle_rescan(&mut instance, ParamRescanFlags::ALL, &mut cache)
+        .unwrap();
+
+    assert_eq!(changes.added, [ClapId::new(3)]);
+    assert_eq!(changes.removed, [ClapId::new(1)]);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(ClapId::new(3)).unwrap().value, Some(0.25));
+}
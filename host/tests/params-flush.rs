@@ -0,0 +1,208 @@
+use clack_extensions::params::*;
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::utils::Cookie;
+use clack_plugin::clack_entry;
+use clack_plugin::events::spaces::CoreEventSpace;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// The last value received by the main thread, as `f64` bits.
+static MAIN_THREAD_VALUE: AtomicU64 = AtomicU64::new(0);
+/// The last value received by the audio processor, as `f64` bits.
+static AUDIO_PROCESSOR_VALUE: AtomicU64 = AtomicU64::new(0);
+/// Whether the audio processor's last received value was flushed from an audio thread.
+static AUDIO_PROCESSOR_ON_AUDIO_THREAD: AtomicBool = AtomicBool::new(false);
+
+fn store_value(target: &AtomicU64, event: &UnknownEvent) {
+    if let Some(CoreEventSpace::ParamValue(event)) = event.as_core_event() {
+        target.store(event.value().to_bits(), Ordering::SeqCst);
+    }
+}
+
+fn load_value(target: &AtomicU64) -> f64 {
+    f64::from_bits(target.load(Ordering::SeqCst))
+}
+
+pub struct FlushPlugin;
+pub struct FlushPluginMainThread;
+pub struct FlushPluginAudioProcessor;
+
+impl PluginMainThread<'_, ()> for FlushPluginMainThread {}
+
+impl Plugin for FlushPlugin {
+    type AudioProcessor<'a> = FlushPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = FlushPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginParams>();
+    }
+}
+
+impl DefaultPluginFactory for FlushPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("flush", "Flush plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(FlushPluginMainThread)
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), FlushPluginMainThread> for FlushPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut FlushPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        for event in events.input {
+            self.handle_param_event(event);
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl FlushPluginAudioProcessor {
+    fn handle_param_event(&mut self, event: &UnknownEvent) {
+        AUDIO_PROCESSOR_ON_AUDIO_THREAD
+            .store(clack_host::host::is_audio_thread(), Ordering::SeqCst);
+        store_value(&AUDIO_PROCESSOR_VALUE, event)
+    }
+}
+
+impl PluginAudioProcessorParams for FlushPluginAudioProcessor {
+    fn flush(&mut self, input: &InputEvents, _output: &mut OutputEvents) {
+        forward_param_events(input, |event| self.handle_param_event(event))
+    }
+}
+
+impl PluginMainThreadParams for FlushPluginMainThread {
+    fn count(&mut self) -> u32 {
+        1
+    }
+
+    fn get_info(&mut self, _param_index: u32, _info: &mut ParamInfoWriter) {}
+
+    fn get_value(&mut self, _param_id: ClapId) -> Option<f64> {
+        None
+    }
+
+    fn value_to_text(
+        &mut self,
+        _param_id: ClapId,
+        _value: f64,
+        _writer: &mut ParamDisplayWriter,
+    ) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+
+    fn text_to_value(&mut self, _param_id: ClapId, _text: &CStr) -> Option<f64> {
+        None
+    }
+
+    fn flush(&mut self, input: &InputEvents, _output: &mut OutputEvents) {
+        forward_param_events(input, |event| store_value(&MAIN_THREAD_VALUE, event))
+    }
+}
+
+static FLUSH_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<FlushPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+fn value_change(value: f64) -> EventBuffer {
+    let mut buffer = EventBuffer::new();
+    buffer.push(&ParamValueEvent::new(
+        0,
+        ClapId::new(1),
+        Pckn::match_all(),
+        value,
+        Cookie::empty(),
+    ));
+    buffer
+}
+
+#[test]
+fn flushes_on_the_thread_matching_the_activation_state() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&FLUSH_ENTRY, "/flush") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"flush\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let params = instance
+        .plugin_handle()
+        .get_extension::<PluginParams>()
+        .unwrap();
+
+    // While inactive, flushes are handled by the main thread.
+    params.flush(
+        &mut instance.plugin_handle(),
+        &InputEvents::from_buffer(&value_change(0.25)),
+        &mut OutputEvents::void(),
+    );
+
+    assert_eq!(load_value(&MAIN_THREAD_VALUE), 0.25);
+    assert_eq!(load_value(&AUDIO_PROCESSOR_VALUE), 0.0);
+
+    // While active, flushes are handled by the audio processor instead.
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let mut processor = instance.activate(|_, _| (), config).unwrap();
+
+    params.flush_active(
+        &mut processor.plugin_handle(),
+        &InputEvents::from_buffer(&value_change(0.5)),
+        &mut OutputEvents::void(),
+    );
+
+    assert_eq!(load_value(&MAIN_THREAD_VALUE), 0.25);
+    assert_eq!(load_value(&AUDIO_PROCESSOR_VALUE), 0.5);
+    assert!(AUDIO_PROCESSOR_ON_AUDIO_THREAD.load(Ordering::SeqCst));
+    assert!(!clack_host::host::is_audio_thread());
+
+    instance.deactivate(processor);
+}
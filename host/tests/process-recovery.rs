@@ -0,0 +1,184 @@
+use clack_host::prelude::*;
+use clack_host::process::recovery::*;
+use clack_host::process::PluginAudioProcessor as HostAudioProcessor;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// How many of the next `process()` calls should fail.
+static FAILURES_LEFT: AtomicU32 = AtomicU32::new(0);
+static PROCESS_CALLS: AtomicU32 = AtomicU32::new(0);
+static RESETS: AtomicU32 = AtomicU32::new(0);
+static STARTS: AtomicU32 = AtomicU32::new(0);
+
+pub struct FailingPlugin;
+pub struct FailingPluginAudioProcessor;
+
+impl Plugin for FailingPlugin {
+    type AudioProcessor<'a> = FailingPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for FailingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("failing", "Failing plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for FailingPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        PROCESS_CALLS.fetch_add(1, Ordering::SeqCst);
+
+        let failures = FAILURES_LEFT.load(Ordering::SeqCst);
+        if failures > 0 {
+            FAILURES_LEFT.store(failures - 1, Ordering::SeqCst);
+            return Err(PluginError::Message("Failed"));
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+
+    fn reset(&mut self) {
+        RESETS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn start_processing(&mut self) -> Result<(), PluginError> {
+        STARTS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+static FAILING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<FailingPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn recovers_from_process_errors() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&FAILING_ENTRY, "/failing") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"failing\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+    let mut processor: HostAudioProcessor<MyHost> =
+        instance.activate(|_, _| (), config).unwrap().into();
+
+    let mut failures = Vec::new();
+    let mut recovery =
+        ProcessRecovery::new(|f: &ProcessFailure| failures.push(f.clone())).with_max_restarts(1);
+
+    let process = |recovery: &mut ProcessRecovery<_>, processor: &mut _| {
+        recovery.process(processor, |started| {
+            started.process(
+                &InputAudioBuffers::empty(),
+                &mut OutputAudioBuffers::empty(),
+                &InputEvents::empty(),
+                &mut OutputEvents::void(),
+                None,
+                None,
+            )
+        })
+    };
+
+    FAILURES_LEFT.store(2, Ordering::SeqCst);
+
+    // The first failure restarts processing.
+    assert_eq!(
+        process(&mut recovery, &mut processor),
+        ProcessOutcome::Bypassed
+    );
+    assert!(recovery.is_restart_pending());
+    assert!(!processor.is_started());
+    assert_eq!(RESETS.load(Ordering::SeqCst), 1);
+
+    // The second one bypasses the plugin, which isn't processed anymore.
+    for _ in 0..3 {
+        assert_eq!(
+            process(&mut recovery, &mut processor),
+            ProcessOutcome::Bypassed
+        );
+    }
+
+    assert!(recovery.is_errored());
+    assert_eq!(STARTS.load(Ordering::SeqCst), 2);
+    assert_eq!(PROCESS_CALLS.load(Ordering::SeqCst), 2);
+
+    recovery.clear_error();
+    assert_eq!(
+        process(&mut recovery, &mut processor),
+        ProcessOutcome::Processed(ProcessStatus::Continue)
+    );
+    assert_eq!(PROCESS_CALLS.load(Ordering::SeqCst), 3);
+
+    instance.deactivate(processor.into_stopped());
+
+    assert_eq!(
+        failures,
+        [
+            ProcessFailure {
+                error: PluginInstanceError::ProcessingFailed,
+                action: RecoveryAction::Restart,
+                restarts: 1,
+                total_failures: 1,
+            },
+            ProcessFailure {
+                error: PluginInstanceError::ProcessingFailed,
+                action: RecoveryAction::Bypass,
+                restarts: 1,
+                total_failures: 2,
+            },
+        ]
+    );
+}
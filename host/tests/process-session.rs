@@ -0,0 +1,227 @@
+use clack_host::prelude::*;
+use clack_host::process::ProcessParams;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+pub struct CountingPlugin;
+pub struct CountingPluginMainThread;
+
+impl PluginMainThread<'_, ()> for CountingPluginMainThread {}
+
+impl Plugin for CountingPlugin {
+    type AudioProcessor<'a> = CountingPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = CountingPluginMainThread;
+}
+
+impl DefaultPluginFactory for CountingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("counting", "Counting plugin").with_version("1.2.3")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(CountingPluginMainThread)
+    }
+}
+
+pub struct CountingPluginAudioProcessor {
+    processed_frames: u32,
+}
+
+impl<'a> PluginAudioProcessor<'a, (), CountingPluginMainThread> for CountingPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut CountingPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self {
+            processed_frames: 0,
+        })
+    }
+
+    fn process(
+        &mut self,
+        process: Process,
+        mut audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        assert_eq!(process.steady_time, Some(self.processed_frames as u64));
+
+        if let Some(mut port) = audio.output_port(0) {
+            for channel in port.channels()?.into_f32().unwrap().iter_mut() {
+                channel.fill(self.processed_frames as f32);
+            }
+        }
+
+        self.processed_frames += audio.frames_count();
+
+        if self.processed_frames >= 16 {
+            Err(PluginError::Message("Processed too many frames"))
+        } else {
+            Ok(ProcessStatus::Continue)
+        }
+    }
+}
+
+static COUNTING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<CountingPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn processes_through_session() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&COUNTING_ENTRY, "/counting") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"counting\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut output_ports = AudioPorts::with_capacity(1, 1);
+    let mut output_buffer = [0f32; 4];
+
+    let mut session = processor.session().unwrap();
+
+    for block in 0..4 {
+        let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only([output_buffer.as_mut_slice()]),
+        }]);
+
+        let status = session.process(
+            &InputAudioBuffers::empty(),
+            &mut outputs,
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            Some(block * 4),
+            None,
+        );
+
+        // The last block fails, and errors must still be reported through sessions.
+        if block < 3 {
+            assert_eq!(status.unwrap(), ProcessStatus::Continue);
+        } else {
+            assert!(matches!(status, Err(PluginInstanceError::ProcessingFailed)));
+        }
+    }
+
+    instance.deactivate(processor.stop_processing());
+}
+
+#[test]
+fn advances_steady_time_in_batches() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&COUNTING_ENTRY, "/counting") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"counting\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut output_ports = AudioPorts::with_capacity(1, 1);
+    let mut output_buffer = [0f32; 4];
+    let mut params = ProcessParams::new().with_steady_time(0);
+
+    let mut batch = processor.process_batch(&mut params).unwrap();
+
+    // The plugin checks the steady time matches the number of frames it processed so far.
+    for (frames, processed) in [(4, 0), (1, 4), (3, 5), (2, 8), (8, 10)] {
+        let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only([&mut output_buffer[..]]),
+        }]);
+
+        let status = batch.process(
+            frames,
+            &InputAudioBuffers::empty(),
+            &mut outputs,
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+        );
+        assert_eq!(status.unwrap(), ProcessStatus::Continue);
+
+        // The output buffer can be read in between two blocks.
+        assert_eq!(output_buffer[0], processed as f32);
+    }
+
+    // Blocks larger than the buffers are truncated.
+    assert_eq!(batch.params().steady_time(), Some(14));
+
+    instance.deactivate(processor.stop_processing());
+}
+
+#[test]
+fn exposes_instance_descriptor() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&COUNTING_ENTRY, "/counting") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"counting\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let descriptor = instance.descriptor().unwrap();
+
+    assert_eq!(descriptor.id().unwrap().to_bytes(), b"counting");
+    assert_eq!(descriptor.name().unwrap().to_bytes(), b"Counting plugin");
+    assert_eq!(descriptor.version().unwrap().to_bytes(), b"1.2.3");
+}
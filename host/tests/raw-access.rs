@@ -0,0 +1,82 @@
+use clack_host::factory::PluginDescriptor as HostPluginDescriptor;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+pub struct RawPlugin;
+
+impl Plugin for RawPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for RawPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My Plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+static RAW_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<RawPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn raw_pointers_round_trip() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&RAW_ENTRY, "/raw") }.unwrap();
+    let factory = bundle.get_plugin_factory().unwrap();
+    assert!(!factory.as_raw().is_null());
+
+    let descriptor = factory.plugin_descriptor(0).unwrap();
+    // SAFETY: this descriptor comes from a valid plugin factory, which outlives it.
+    let descriptor = unsafe { HostPluginDescriptor::from_raw(descriptor.as_raw()) };
+    assert_eq!(descriptor.id().unwrap().to_bytes(), b"my.plugin");
+
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let raw = NonNull::from(instance.as_raw());
+    assert_eq!(instance.plugin_handle().as_raw_ptr(), raw.as_ptr());
+
+    // SAFETY: the pointer comes from a live, initialized plugin instance.
+    let shared = unsafe { PluginSharedHandle::from_raw(raw) };
+    assert_eq!(shared, instance.plugin_shared_handle());
+    assert_eq!(
+        shared.descriptor().unwrap().id().unwrap().to_bytes(),
+        b"my.plugin"
+    );
+}
@@ -0,0 +1,142 @@
+use clack_extensions::params::{ParamInfo, ParamInfoFlags};
+use clack_extensions::remote_controls::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use clack_plugin::utils::Cookie;
+use std::ffi::CStr;
+
+fn param(id: u32, module: &'static [u8], flags: ParamInfoFlags) -> ParamInfo<'static> {
+    ParamInfo {
+        id: ClapId::new(id),
+        flags,
+        cookie: Cookie::empty(),
+        name: b"Param",
+        module,
+        min_value: 0.0,
+        max_value: 1.0,
+        default_value: 0.5,
+    }
+}
+
+pub struct ControlsPlugin;
+pub struct ControlsPluginMainThread {
+    pages: RemoteControlsPages,
+}
+
+impl PluginMainThread<'_, ()> for ControlsPluginMainThread {}
+
+impl Plugin for ControlsPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ControlsPluginMainThread;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginRemoteControls>();
+    }
+}
+
+impl DefaultPluginFactory for ControlsPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("controls", "Controls plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        let mut params: Vec<_> = (0..10)
+            .map(|id| param(id, b"Oscillator", ParamInfoFlags::IS_AUTOMATABLE))
+            .collect();
+        params.push(param(10, b"Filter", ParamInfoFlags::IS_AUTOMATABLE));
+        params.push(param(11, b"Filter", ParamInfoFlags::IS_HIDDEN));
+        params.push(param(12, b"Macros", ParamInfoFlags::IS_AUTOMATABLE));
+
+        let pages = RemoteControlsPagesBuilder::from_param_modules(&params)
+            .section("Macros")
+            .preset_page("Preset", &[ClapId::new(12), ClapId::new(11)])
+            .build()?;
+
+        Ok(ControlsPluginMainThread { pages })
+    }
+}
+
+impl PluginRemoteControlsImpl for ControlsPluginMainThread {
+    fn count(&mut self) -> u32 {
+        self.pages.len() as u32
+    }
+
+    fn get(&mut self, page_index: u32, writer: &mut RemoteControlsPageWriter) {
+        if let Some(page) = self.pages.get(page_index as usize) {
+            writer.set(&page);
+        }
+    }
+}
+
+static CONTROLS_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<ControlsPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn reads_remote_controls_pages() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&CONTROLS_ENTRY, "/controls") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"controls\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let controls = plugin.get_extension::<PluginRemoteControls>().unwrap();
+    let mut buffer = RemoteControlsPageBuffer::new();
+
+    // Oscillator (split in two pages), Filter, Macros, and the preset page.
+    assert_eq!(controls.count(&mut plugin), 5);
+
+    let page = controls.get(&mut plugin, 1, &mut buffer).unwrap();
+    assert_eq!(page.page_id, ClapId::new(1));
+    assert_eq!(page.section_name, b"Oscillator");
+    assert_eq!(page.page_name, b"Oscillator 2");
+    assert_eq!(
+        page.param_ids[..3],
+        [Some(ClapId::new(8)), Some(ClapId::new(9)), None]
+    );
+
+    // Hidden parameters are left out of the generated pages.
+    let page = controls.get(&mut plugin, 2, &mut buffer).unwrap();
+    assert_eq!(page.page_name, b"Filter");
+    assert_eq!(page.param_ids[..2], [Some(ClapId::new(10)), None]);
+
+    let page = controls.get(&mut plugin, 4, &mut buffer).unwrap();
+    assert_eq!(page.section_name, b"Macros");
+    assert!(page.is_for_preset);
+    assert_eq!(
+        page.param_ids[..2],
+        [Some(ClapId::new(12)), Some(ClapId::new(11))]
+    );
+
+    assert!(controls.get(&mut plugin, 5, &mut buffer).is_none());
+}
@@ -0,0 +1,164 @@
+use clack_extensions::timer::*;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+static REPAINTS: AtomicU32 = AtomicU32::new(0);
+static CALLBACK_REQUESTED: AtomicBool = AtomicBool::new(false);
+static TIMER_UNREGISTERED: AtomicBool = AtomicBool::new(false);
+
+pub struct RepaintPlugin;
+pub struct RepaintPluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+    repaint: RepaintTimer<'a>,
+}
+
+impl<'a> PluginMainThread<'a, ()> for RepaintPluginMainThread<'a> {
+    fn on_main_thread(&mut self) {
+        self.repaint.on_main_thread(|| {
+            REPAINTS.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+impl PluginTimerImpl for RepaintPluginMainThread<'_> {
+    fn on_timer(&mut self, timer_id: TimerId) {
+        self.repaint.on_timer(timer_id, || {
+            REPAINTS.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+impl Drop for RepaintPluginMainThread<'_> {
+    fn drop(&mut self) {
+        self.repaint.stop(&mut self.host).unwrap();
+    }
+}
+
+impl Plugin for RepaintPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = RepaintPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginTimer>();
+    }
+}
+
+impl DefaultPluginFactory for RepaintPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("repaint", "Repaint plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        mut host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        // SAFETY: the timer is stored in the main thread struct, which is dropped before the
+        // plugin instance is destroyed.
+        let repaint = unsafe { RepaintTimer::start(&mut host, 60) };
+        assert_eq!(repaint.period_ms(), 16);
+
+        Ok(RepaintPluginMainThread { host, repaint })
+    }
+}
+
+static REPAINT_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<RepaintPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {
+        CALLBACK_REQUESTED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A host which supports the timer extension.
+struct TimerHost;
+struct TimerHostMainThread;
+
+impl HostHandlers for TimerHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = TimerHostMainThread;
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostTimer>();
+    }
+}
+
+impl MainThreadHandler<'_> for TimerHostMainThread {}
+
+impl HostTimerImpl for TimerHostMainThread {
+    fn register_timer(&mut self, period_ms: u32) -> Result<TimerId, HostError> {
+        assert_eq!(period_ms, 16);
+        Ok(TimerId(7))
+    }
+
+    fn unregister_timer(&mut self, timer_id: TimerId) -> Result<(), HostError> {
+        assert_eq!(timer_id, TimerId(7));
+        TIMER_UNREGISTERED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// A host which doesn't support any extension.
+struct BasicHost;
+
+impl HostHandlers for BasicHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+#[test]
+fn repaints_using_host_timer_or_fallback_thread() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&REPAINT_ENTRY, "/repaint") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+    let plugin_id = CStr::from_bytes_with_nul(b"repaint\0").unwrap();
+
+    // With host timers.
+    let mut instance = PluginInstance::<TimerHost>::new(
+        |_| MyHostShared,
+        |_| TimerHostMainThread,
+        &bundle,
+        plugin_id,
+        &host,
+    )
+    .unwrap();
+
+    let mut plugin = instance.plugin_handle();
+    let timer = plugin.get_extension::<PluginTimer>().unwrap();
+    timer.on_timer(&mut plugin, TimerId(7));
+    timer.on_timer(&mut plugin, TimerId(8));
+    assert_eq!(REPAINTS.load(Ordering::SeqCst), 1);
+
+    instance.call_on_main_thread_callback();
+    assert_eq!(REPAINTS.load(Ordering::SeqCst), 1);
+
+    drop(instance);
+    assert!(TIMER_UNREGISTERED.load(Ordering::SeqCst));
+
+    // Without host timers, using the background thread.
+    let mut instance =
+        PluginInstance::<BasicHost>::new(|_| MyHostShared, |_| (), &bundle, plugin_id, &host)
+            .unwrap();
+
+    let start = Instant::now();
+    while !CALLBACK_REQUESTED.load(Ordering::SeqCst) {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    instance.call_on_main_thread_callback();
+    assert_eq!(REPAINTS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "resampling")]
+
+use clack_host::events::event_types::NoteOnEvent;
+use clack_host::events::io::EventBuffer;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_host::process::resampler::ResamplingAdapter;
+use clack_host::process::ProcessParams;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::f64::consts::TAU;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+/// The steady time and frames count of every block processed by the plugin, and the absolute
+/// time of every event it received.
+static BLOCKS: Mutex<Vec<(u64, u32)>> = Mutex::new(Vec::new());
+static EVENTS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+pub struct PassthroughPlugin;
+pub struct PassthroughPluginMainThread;
+
+impl PluginMainThread<'_, ()> for PassthroughPluginMainThread {}
+
+impl Plugin for PassthroughPlugin {
+    type AudioProcessor<'a> = PassthroughPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = PassthroughPluginMainThread;
+}
+
+impl DefaultPluginFactory for PassthroughPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("passthrough", "Passthrough plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(PassthroughPluginMainThread)
+    }
+}
+
+pub struct PassthroughPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), PassthroughPluginMainThread>
+    for PassthroughPluginAudioProcessor
+{
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut PassthroughPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        process: Process,
+        mut audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        let steady_time = process.steady_time.unwrap();
+        BLOCKS
+            .lock()
+            .unwrap()
+            .push((steady_time, audio.frames_count()));
+
+        let mut port_pair = audio.port_pair(0).unwrap();
+        for pair in port_pair.channels()?.into_f32().unwrap().iter_mut() {
+            if let ChannelPair::InputOutput(i, o) = pair {
+                o.copy_from_slice(i);
+            }
+        }
+
+        EVENTS.lock().unwrap().extend(
+            events
+                .input
+                .iter()
+                .map(|e| steady_time + e.header().time() as u64),
+        );
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+}
+
+static PASSTHROUGH_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<PassthroughPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+const DEVICE_RATE: f64 = 44_100.0;
+const PLUGIN_RATE: f64 = 48_000.0;
+
+fn sine(frame: f64) -> f32 {
+    (TAU * 440.0 * frame / DEVICE_RATE).sin() as f32
+}
+
+#[test]
+fn resamples_to_plugin_rate_and_back() {
+    let bundle =
+        unsafe { PluginBundle::load_from_raw(&PASSTHROUGH_ENTRY, "/passthrough") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"passthrough\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let mut adapter = ResamplingAdapter::new(DEVICE_RATE, PLUGIN_RATE, &[1], &[1], 512);
+
+    let config = PluginAudioConfiguration {
+        sample_rate: PLUGIN_RATE,
+        min_frames_count: 1,
+        max_frames_count: adapter.max_plugin_frames(),
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut params = ProcessParams::new().with_steady_time(0);
+
+    let input: Vec<f32> = (0..4410).map(|i| sine(i as f64)).collect();
+    let mut output = vec![0.0; input.len()];
+
+    let mut start = 0;
+    for frames in [1, 441, 512, 37, 300].into_iter().cycle() {
+        let end = (start + frames).min(input.len());
+
+        let mut input_events = EventBuffer::new();
+        if start == 1 {
+            input_events.push(&NoteOnEvent::new(
+                100,
+                Pckn::new(0u16, 0u16, 60u16, 0u32),
+                1.0,
+            ));
+        }
+
+        adapter
+            .process(
+                &mut processor,
+                &mut params,
+                &[&input[start..end]],
+                &mut [&mut output[start..end]],
+                &input_events.as_input(),
+                &mut OutputEvents::void(),
+            )
+            .unwrap();
+
+        start = end;
+        if start == input.len() {
+            break;
+        }
+    }
+
+    assert_eq!(params.steady_time(), Some(input.len() as u64));
+
+    // The plugin is given contiguous blocks, at its own sample rate.
+    let blocks = BLOCKS.lock().unwrap();
+    let mut plugin_frames = 0;
+    for (steady_time, frames_count) in blocks.iter() {
+        assert_eq!(*steady_time, plugin_frames);
+        assert!(*frames_count <= adapter.max_plugin_frames());
+        plugin_frames += *frames_count as u64;
+    }
+
+    let expected_frames = input.len() as f64 * PLUGIN_RATE / DEVICE_RATE;
+    assert!((plugin_frames as f64 - expected_frames).abs() < 64.0);
+
+    // Events are given to the plugin alongside the audio they were sent with.
+    let latency = adapter.latency() as f64;
+    let expected_time = ((101.0 + latency) * PLUGIN_RATE / DEVICE_RATE).round() as u64;
+    assert_eq!(*EVENTS.lock().unwrap(), [expected_time]);
+
+    // The output matches the input, delayed by the adapter's latency.
+    for (frame, sample) in output
+        .iter()
+        .enumerate()
+        .skip(adapter.latency() as usize + 64)
+    {
+        let expected = sine(frame as f64 - latency);
+        assert!(
+            (sample - expected).abs() < 0.01,
+            "Frame {frame}: expected {expected}, got {sample}"
+        );
+    }
+
+    instance.deactivate(processor.stop_processing());
+}
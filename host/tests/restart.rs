@@ -0,0 +1,151 @@
+use clack_host::prelude::*;
+use clack_host::process::PluginAudioProcessor as HostAudioProcessor;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static ACTIVATIONS: AtomicU32 = AtomicU32::new(0);
+
+pub struct RestartingPlugin;
+pub struct RestartingPluginAudioProcessor<'a> {
+    host: HostAudioProcessorHandle<'a>,
+    restart_requested: bool,
+}
+
+impl Plugin for RestartingPlugin {
+    type AudioProcessor<'a> = RestartingPluginAudioProcessor<'a>;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for RestartingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("restarting", "Restarting plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for RestartingPluginAudioProcessor<'a> {
+    fn activate(
+        host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        ACTIVATIONS.fetch_add(1, Ordering::Relaxed);
+        Ok(Self {
+            host,
+            restart_requested: false,
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        // Request a single restart per activation, as soon as processing starts.
+        if !self.restart_requested {
+            self.restart_requested = true;
+            self.host.request_restart();
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static RESTARTING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<RestartingPlugin>);
+
+struct MyHostShared {
+    restart_requested: AtomicBool,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {
+        self.restart_requested.store(true, Ordering::Relaxed);
+    }
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+fn process_block(processor: &mut HostAudioProcessor<MyHost>) {
+    processor
+        .ensure_processing_started()
+        .unwrap()
+        .process(
+            &InputAudioBuffers::empty_with_frames(16),
+            &mut OutputAudioBuffers::empty_with_frames(16),
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+}
+
+#[test]
+fn can_restart_plugin_after_processing_block() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&RESTARTING_ENTRY, "/restarting") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            restart_requested: AtomicBool::new(false),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"restarting\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 16,
+    };
+
+    let mut processor: HostAudioProcessor<_> = instance.activate(|_, _| (), config).unwrap().into();
+    assert!(!processor.finish_block_then_restart());
+
+    process_block(&mut processor);
+    assert!(processor.is_restart_requested());
+    assert!(processor.access_shared_handler(|h| h.restart_requested.load(Ordering::Relaxed)));
+
+    assert!(processor.finish_block_then_restart());
+    assert!(!processor.is_started());
+
+    let stopped = processor.into_stopped();
+    let mut processor: HostAudioProcessor<_> =
+        instance.restart(stopped, |_, _| (), config).unwrap().into();
+
+    assert_eq!(ACTIVATIONS.load(Ordering::Relaxed), 2);
+    assert!(!instance.is_restart_requested());
+    assert!(!processor.finish_block_then_restart());
+
+    process_block(&mut processor);
+    assert!(processor.finish_block_then_restart());
+
+    instance.deactivate(processor.into_stopped());
+    assert!(instance.is_restart_requested());
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "libloading")]
+
+use clack_host::bundle::scanner::*;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clack-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    dir
+}
+
+#[test]
+fn skips_loading_unchanged_bundles() {
+    let dir = temp_dir("scan-cache");
+    let cache_path = dir.join("cache/scan.cache");
+
+    // This isn't a valid library: loading it always fails.
+    let bundle_path = dir.join("vendor/plugin.clap");
+    std::fs::write(&bundle_path, b"not a library").unwrap();
+    std::fs::write(dir.join("vendor/readme.txt"), b"").unwrap();
+
+    let scanner = Scanner::new()
+        .with_path(&dir)
+        .with_cache(&cache_path)
+        .with_cache_validation(CacheValidation::ContentHash);
+    assert_eq!(scanner.discover(), std::slice::from_ref(&bundle_path));
+
+    let report = unsafe { scanner.scan() };
+    assert!(report.bundles.is_empty());
+    assert_eq!(report.failures.len(), 1);
+    assert!(matches!(report.failures[0].error, ScanError::Load(_)));
+    assert!(report.cache_error.is_none());
+
+    // Failures are never cached.
+    assert!(ScanCache::load(&cache_path).unwrap().is_empty());
+
+    // Pretend the bundle was successfully scanned before.
+    let file = BundleFileInfo::read(&bundle_path, CacheValidation::ContentHash).unwrap();
+    assert!(file.content_hash.is_some());
+
+    let mut cache = ScanCache::new();
+    cache.insert(ScannedBundle {
+        path: bundle_path.clone(),
+        file,
+        plugins: Vec::new(),
+    });
+    cache.save(&cache_path).unwrap();
+
+    let report = unsafe { scanner.scan() };
+    assert_eq!(report.cached_count, 1);
+    assert_eq!(report.bundles.len(), 1);
+    assert!(report.failures.is_empty());
+    assert_eq!(ScanCache::load(&cache_path).unwrap(), cache);
+
+    // Once the bundle changes, it's loaded again.
+    OpenOptions::new()
+        .append(true)
+        .open(&bundle_path)
+        .unwrap()
+        .write_all(b"!")
+        .unwrap();
+
+    let report = unsafe { scanner.scan() };
+    assert_eq!(report.cached_count, 0);
+    assert_eq!(report.failures.len(), 1);
+
+    // Corrupted caches are discarded.
+    std::fs::write(&cache_path, b"garbage").unwrap();
+    let report = unsafe { scanner.scan() };
+    assert_eq!(report.failures.len(), 1);
+    assert!(ScanCache::load(&cache_path).unwrap().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skips_quarantined_bundles() {
+    let dir = temp_dir("scan-quarantine");
+    let quarantine_path = dir.join("cache/scan.quarantine");
+
+    let bundle_path = dir.join("vendor/plugin.clap");
+    std::fs::write(&bundle_path, b"not a library").unwrap();
+
+    let scanner = Scanner::new()
+        .with_path(&dir)
+        .with_quarantine(&quarantine_path);
+    assert!(scanner.quarantine().unwrap().is_empty());
+
+    // Pretend the previous scan crashed while loading the bundle.
+    std::fs::create_dir_all(quarantine_path.parent().unwrap()).unwrap();
+    let mut marker = quarantine_path.clone().into_os_string();
+    marker.push(".scanning");
+    std::fs::write(&marker, bundle_path.to_str().unwrap()).unwrap();
+
+    let report = unsafe { scanner.scan() };
+    assert_eq!(report.quarantined, std::slice::from_ref(&bundle_path));
+    assert!(report.failures.is_empty());
+    assert!(report.quarantine_error.is_none());
+    assert!(!PathBuf::from(&marker).exists());
+
+    let quarantine = scanner.quarantine().unwrap();
+    let entry = quarantine.get(&bundle_path).unwrap();
+    assert_eq!(entry.reason, QuarantineReason::Crashed);
+
+    // Quarantined bundles stay skipped.
+    let report = unsafe { scanner.scan() };
+    assert_eq!(report.quarantined.len(), 1);
+
+    // Until they are retried.
+    assert!(scanner.retry_quarantined(&bundle_path).unwrap());
+    assert!(!scanner.retry_quarantined(&bundle_path).unwrap());
+
+    let report = unsafe { scanner.scan() };
+    assert!(report.quarantined.is_empty());
+    assert_eq!(report.failures.len(), 1);
+
+    // Bundles that take too long to load are quarantined too.
+    let scanner = scanner.with_scan_timeout(Duration::ZERO);
+    let report = unsafe { scanner.scan() };
+    assert!(matches!(
+        report.failures[0].error,
+        ScanError::TimedOut { .. }
+    ));
+
+    let quarantine = scanner.quarantine().unwrap();
+    let entry = quarantine.get(&bundle_path).unwrap();
+    assert!(matches!(entry.reason, QuarantineReason::TimedOut { .. }));
+
+    // Changed bundles are scanned again.
+    OpenOptions::new()
+        .append(true)
+        .open(&bundle_path)
+        .unwrap()
+        .write_all(b"!")
+        .unwrap();
+
+    let report = unsafe { scanner.scan() };
+    assert!(report.quarantined.is_empty());
+
+    scanner.clear_quarantine().unwrap();
+    assert!(scanner.quarantine().unwrap().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn discovery_survives_symlink_cycles() {
+    let dir = temp_dir("scan-symlinks");
+    let bundle_path = dir.join("vendor/plugin.clap");
+    std::fs::write(&bundle_path, b"not a library").unwrap();
+
+    // Both a cycle back to the search path, and a link to an already visited directory.
+    std::os::unix::fs::symlink(&dir, dir.join("vendor/loop")).unwrap();
+    std::os::unix::fs::symlink(dir.join("vendor"), dir.join("vendor-link")).unwrap();
+
+    let scanner = Scanner::new().with_path(&dir);
+    assert_eq!(scanner.discover(), std::slice::from_ref(&bundle_path));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
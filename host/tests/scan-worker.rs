@@ -0,0 +1,103 @@
+#![cfg(feature = "libloading")]
+
+use clack_host::bundle::scanner::*;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clack-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The entry point of worker processes, which are spawned by running only this test.
+#[test]
+fn worker() {
+    unsafe { ScanWorker::run_if_requested() };
+}
+
+fn test_worker() -> ScanWorker {
+    ScanWorker::current_exe()
+        .unwrap()
+        .with_arg("--exact")
+        .with_arg("worker")
+}
+
+#[test]
+fn reports_errors_from_worker() {
+    let dir = temp_dir("scan-worker");
+
+    // This isn't a valid library: loading it always fails.
+    let bundle_path = dir.join("plugin.clap");
+    std::fs::write(&bundle_path, b"not a library").unwrap();
+
+    let error = test_worker().scan(&bundle_path).unwrap_err();
+    assert!(matches!(error, ScanError::WorkerLoad(_)), "{error}");
+
+    let error = test_worker().scan(&dir.join("missing.clap")).unwrap_err();
+    assert!(matches!(error, ScanError::WorkerLoad(_)), "{error}");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn survives_crashing_and_hanging_workers() {
+    let dir = temp_dir("scan-worker-crash");
+    let quarantine_path = dir.join("scan.quarantine");
+
+    let bundle_path = dir.join("plugin.clap");
+    std::fs::write(&bundle_path, b"not a library").unwrap();
+
+    let error = ScanWorker::new("false").scan(&bundle_path).unwrap_err();
+    assert!(matches!(error, ScanError::WorkerCrashed(_)), "{error}");
+
+    let hanging = ScanWorker::new("sleep")
+        .with_arg("10")
+        .with_timeout(Duration::from_millis(50));
+    let error = hanging.scan(&bundle_path).unwrap_err();
+    assert!(matches!(error, ScanError::TimedOut { .. }), "{error}");
+
+    // The worker exits right away, but leaves a process behind that holds on to its output.
+    let leaking = ScanWorker::new("sh")
+        .with_arg("-c")
+        .with_arg("sleep 10 &")
+        .with_timeout(Duration::from_millis(50));
+    let error = leaking.scan(&bundle_path).unwrap_err();
+    assert!(matches!(error, ScanError::TimedOut { .. }), "{error}");
+
+    // Through a scanner, crashing bundles are quarantined.
+    let scanner = Scanner::new()
+        .with_path(&dir)
+        .with_quarantine(&quarantine_path)
+        .with_worker(ScanWorker::new("false"));
+
+    let report = unsafe { scanner.scan() };
+    assert!(matches!(
+        report.failures[0].error,
+        ScanError::WorkerCrashed(_)
+    ));
+
+    let quarantine = scanner.quarantine().unwrap();
+    let entry = quarantine.get(&bundle_path).unwrap();
+    assert_eq!(entry.reason, QuarantineReason::Crashed);
+
+    let report = unsafe { scanner.scan() };
+    assert!(report.failures.is_empty());
+    assert_eq!(report.quarantined, std::slice::from_ref(&bundle_path));
+
+    // The scanner's timeout kills hanging workers too.
+    scanner.clear_quarantine().unwrap();
+    let scanner = scanner
+        .with_worker(ScanWorker::new("sleep").with_arg("10"))
+        .with_scan_timeout(Duration::from_millis(50));
+
+    let report = unsafe { scanner.scan() };
+    assert!(matches!(
+        report.failures[0].error,
+        ScanError::TimedOut { .. }
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
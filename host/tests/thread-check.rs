@@ -0,0 +1,162 @@
+use clack_extensions::thread_check::{DefaultThreadCheck, HostThreadCheck, HostThreadCheckImpl};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+/// Where the plugin checked its current thread, and the main and audio thread checks results.
+type ThreadCheckResult = (&'static str, Option<bool>, Option<bool>);
+
+static CHECKS: Mutex<Vec<ThreadCheckResult>> = Mutex::new(Vec::new());
+
+fn check_thread(location: &'static str, host: &HostSharedHandle) {
+    let thread_check = host.get_extension::<HostThreadCheck>().unwrap();
+
+    CHECKS.lock().unwrap().push((
+        location,
+        thread_check.is_main_thread(host),
+        thread_check.is_audio_thread(host),
+    ));
+}
+
+pub struct CheckingPlugin;
+pub struct CheckingPluginAudioProcessor<'a> {
+    host: HostAudioProcessorHandle<'a>,
+}
+
+impl Plugin for CheckingPlugin {
+    type AudioProcessor<'a> = CheckingPluginAudioProcessor<'a>;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for CheckingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("checking", "Thread checking plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        check_thread("init", &host);
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for CheckingPluginAudioProcessor<'a> {
+    fn activate(
+        host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        check_thread("activate", &host);
+        Ok(Self { host })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        check_thread("process", &self.host);
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static CHECKING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<CheckingPlugin>);
+
+struct MyHostShared {
+    thread_check: DefaultThreadCheck,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostThreadCheckImpl for MyHostShared {
+    fn is_main_thread(&self) -> bool {
+        self.thread_check.is_main_thread()
+    }
+
+    fn is_audio_thread(&self) -> bool {
+        self.thread_check.is_audio_thread()
+    }
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostThreadCheck>();
+    }
+}
+
+#[test]
+fn default_thread_check_tracks_main_and_audio_threads() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&CHECKING_ENTRY, "/checking") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            thread_check: DefaultThreadCheck::new(),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"checking\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 32,
+    };
+
+    let processor = instance.activate(|_, _| (), config).unwrap();
+
+    let processor = std::thread::scope(|s| {
+        s.spawn(move || {
+            let mut processor = processor.start_processing().unwrap();
+            processor
+                .process(
+                    &InputAudioBuffers::empty_with_frames(32),
+                    &mut OutputAudioBuffers::empty_with_frames(32),
+                    &InputEvents::empty(),
+                    &mut OutputEvents::void(),
+                    None,
+                    None,
+                )
+                .unwrap();
+
+            processor.stop_processing()
+        })
+        .join()
+        .unwrap()
+    });
+
+    instance.deactivate(processor);
+
+    assert_eq!(
+        *CHECKS.lock().unwrap(),
+        [
+            ("init", Some(true), Some(false)),
+            ("activate", Some(true), Some(false)),
+            ("process", Some(false), Some(true)),
+        ]
+    );
+}
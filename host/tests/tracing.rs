@@ -0,0 +1,159 @@
+#![cfg(feature = "tracing")]
+
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+pub struct TracedPlugin;
+pub struct TracedPluginAudioProcessor;
+
+impl Plugin for TracedPlugin {
+    type AudioProcessor<'a> = TracedPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for TracedPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("traced", "Traced plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for TracedPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static TRACED_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<TracedPlugin>);
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+/// A subscriber that records the names of all the created spans.
+#[derive(Default)]
+struct SpanRecorder {
+    names: Arc<Mutex<Vec<&'static str>>>,
+    next_id: AtomicU64,
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.names.lock().unwrap().push(span.metadata().name());
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+    fn event(&self, _event: &Event<'_>) {}
+    fn enter(&self, _span: &Id) {}
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn emits_spans_around_host_calls() {
+    let recorder = SpanRecorder::default();
+    let names = recorder.names.clone();
+
+    tracing::subscriber::with_default(recorder, || {
+        let bundle = unsafe { PluginBundle::load_from_raw(&TRACED_ENTRY, "/traced") }.unwrap();
+        let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+        let mut instance = PluginInstance::<MyHost>::new(
+            |_| MyHostShared,
+            |_| (),
+            &bundle,
+            CStr::from_bytes_with_nul(b"traced\0").unwrap(),
+            &host,
+        )
+        .unwrap();
+
+        let config = PluginAudioConfiguration::new(44_100.0, 1, 32);
+        let mut processor = instance
+            .activate(|_, _| (), config)
+            .unwrap()
+            .start_processing()
+            .unwrap();
+
+        processor
+            .process(
+                &InputAudioBuffers::empty_with_frames(32),
+                &mut OutputAudioBuffers::empty_with_frames(32),
+                &InputEvents::empty(),
+                &mut OutputEvents::void(),
+                None,
+                None,
+            )
+            .unwrap();
+
+        instance.deactivate(processor.stop_processing());
+        instance.call_on_main_thread_callback();
+    });
+
+    let names = names.lock().unwrap();
+    for expected in [
+        "load_bundle",
+        "instantiate",
+        "activate",
+        "deactivate",
+        "on_main_thread",
+        "destroy",
+    ] {
+        assert!(names.contains(&expected), "Missing span {expected}");
+    }
+
+    for audio_thread_span in ["start_processing", "process", "stop_processing"] {
+        assert_eq!(
+            names.contains(&audio_thread_span),
+            cfg!(feature = "tracing-audio-thread"),
+            "Unexpected presence of span {audio_thread_span}"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_extensions::timer::HostTimer;
+use clack_host::extensions::Extension;
+use clack_host::host::UnsupportedExtensions;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const UNKNOWN_ID: &CStr = match CStr::from_bytes_with_nul(b"com.example.unknown/1\0") {
+    Ok(id) => id,
+    Err(_) => panic!(),
+};
+
+pub struct CuriousPlugin;
+
+impl Plugin for CuriousPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for CuriousPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("curious", "Curious plugin")
+    }
+
+    fn new_shared(host: HostSharedHandle<'_>) -> Result<Self::Shared<'_>, PluginError> {
+        assert!(host.get_extension::<HostLog>().is_some());
+        assert!(host.get_extension::<HostTimer>().is_none());
+        assert!(host.get_raw_extension(UNKNOWN_ID).is_none());
+        assert!(host.get_raw_extension(UNKNOWN_ID).is_none());
+
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+static CURIOUS_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<CuriousPlugin>);
+
+struct MyHostShared {
+    unsupported: UnsupportedExtensions,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, _severity: LogSeverity, _message: &str) {}
+}
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        // The handler is only called once all extensions are declared.
+        builder
+            .on_unsupported(|shared, identifier| shared.unsupported.record(identifier))
+            .register::<HostLog>();
+    }
+}
+
+#[test]
+pub fn records_unsupported_extensions() {
+    // SAFETY: the entry is a valid, Clack-generated entry.
+    let bundle = unsafe { PluginBundle::load_from_raw(&CURIOUS_PLUGIN_ENTRY, "/curious.so") };
+    let host_info = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            unsupported: UnsupportedExtensions::new(),
+        },
+        |_| (),
+        &bundle.unwrap(),
+        CStr::from_bytes_with_nul(b"curious\0").unwrap(),
+        &host_info,
+    )
+    .unwrap();
+
+    let unsupported = &instance.access_shared_handler(|s| s.unsupported.snapshot());
+
+    assert!(unsupported
+        .iter()
+        .all(|(id, _)| id.as_c_str() != HostLog::IDENTIFIER));
+    assert!(unsupported.contains(&(HostTimer::IDENTIFIER.to_owned(), 1)));
+    assert!(unsupported.contains(&(UNKNOWN_ID.to_owned(), 2)));
+
+    instance.access_shared_handler(|s| {
+        assert_eq!(s.unsupported.count(UNKNOWN_ID), 2);
+        assert_eq!(s.unsupported.count(HostLog::IDENTIFIER), 0);
+
+        s.unsupported.clear();
+        assert!(s.unsupported.is_empty());
+    });
+}
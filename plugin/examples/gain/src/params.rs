@@ -139,26 +139,14 @@ impl PluginMainThreadParams for GainPluginMainThread<'_> {
         }
     }
 
-    fn flush(
-        &mut self,
-        input_parameter_changes: &InputEvents,
-        _output_parameter_changes: &mut OutputEvents,
-    ) {
-        for event in input_parameter_changes {
-            self.shared.params.handle_event(event)
-        }
+    fn flush(&mut self, input_events: &InputEvents, _output_events: &mut OutputEvents) {
+        forward_param_events(input_events, |event| self.shared.params.handle_event(event))
     }
 }
 
 impl PluginAudioProcessorParams for GainPluginAudioProcessor<'_> {
-    fn flush(
-        &mut self,
-        input_parameter_changes: &InputEvents,
-        _output_parameter_changes: &mut OutputEvents,
-    ) {
-        for event in input_parameter_changes {
-            self.shared.params.handle_event(event)
-        }
+    fn flush(&mut self, input_events: &InputEvents, _output_events: &mut OutputEvents) {
+        forward_param_events(input_events, |event| self.shared.params.handle_event(event))
     }
 }
 
@@ -176,28 +176,18 @@ impl PluginMainThreadParams for PolySynthPluginMainThread<'_> {
         }
     }
 
-    fn flush(
-        &mut self,
-        input_parameter_changes: &InputEvents,
-        _output_parameter_changes: &mut OutputEvents,
-    ) {
-        for event in input_parameter_changes {
+    fn flush(&mut self, input_events: &InputEvents, _output_events: &mut OutputEvents) {
+        forward_param_events(input_events, |event| {
             if let Some(CoreEventSpace::ParamValue(event)) = event.as_core_event() {
                 self.shared.params.handle_event(event)
             }
-        }
+        })
     }
 }
 
 impl PluginAudioProcessorParams for PolySynthAudioProcessor<'_> {
-    fn flush(
-        &mut self,
-        input_parameter_changes: &InputEvents,
-        _output_parameter_changes: &mut OutputEvents,
-    ) {
-        for event in input_parameter_changes {
-            self.handle_event(event)
-        }
+    fn flush(&mut self, input_events: &InputEvents, _output_events: &mut OutputEvents) {
+        forward_param_events(input_events, |event| self.handle_event(event))
     }
 }
 
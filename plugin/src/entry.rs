@@ -16,7 +16,8 @@
 //!
 //! See the [`Entry`] trait documentation for information and examples on how to implement your own
 //! entry type, or see the provided [`SinglePluginEntry`] convenience type if you only need to
-//! expose a single plugin type to the host.
+//! expose a single plugin type to the host. To ship the plugins of multiple entries in a single
+//! bundle, see [`CombinedEntry`].
 
 use crate::extensions::wrapper::handle_panic;
 use crate::factory::Factory;
@@ -29,8 +30,10 @@ use std::sync::Mutex;
 
 pub use clack_common::entry::*;
 
+mod combined;
 mod single;
 
+pub use combined::{CombinedEntry, EntryList};
 pub use single::{DefaultPluginFactory, SinglePluginEntry};
 
 /// A prelude that's helpful for implementing custom [`Entry`] and [`PluginFactory`](crate::factory::plugin::PluginFactory) types.
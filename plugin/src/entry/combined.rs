@@ -0,0 +1,318 @@
+use crate::entry::prelude::*;
+use crate::extensions::wrapper::handle_panic;
+use clap_sys::factory::plugin_factory::{clap_plugin_factory, CLAP_PLUGIN_FACTORY_ID};
+use clap_sys::host::clap_host;
+use clap_sys::plugin::{clap_plugin, clap_plugin_descriptor};
+use std::ffi::{c_char, CStr};
+use std::panic::AssertUnwindSafe;
+
+/// An [`Entry`] that combines the plugins of multiple other entries into a single bundle.
+///
+/// This allows multiple crates based on `clack-plugin` to be linked and shipped as a single
+/// bundle, e.g. for vendors distributing plugin suites, without having to write a custom plugin
+/// factory that knows about every single plugin type.
+///
+/// The combined entries are given as a tuple type, e.g.
+/// `CombinedEntry<(SinglePluginEntry<MyFirstPlugin>, MySuiteEntry)>`. They are all initialized
+/// when this entry is, and de-initialized together with it. The [`combine_entries!`](crate::combine_entries) macro can be
+/// used as a shorthand for this type.
+///
+/// This entry exposes a single plugin factory to the host, which lists the plugins of every
+/// combined entry, in order. If multiple entries expose a plugin with the same ID, they are all
+/// listed, but only the first one can be instantiated. Any other factory is looked up in each
+/// combined entry in order, and the first one found is returned.
+///
+/// Note that crates meant to be combined must only expose their [`Entry`] type, and must *not*
+/// export it with [`clack_export_entry!`](crate::clack_export_entry) themselves.
+///
+/// # Example
+///
+/// ```
+/// use clack_plugin::combine_entries;
+/// use clack_plugin::prelude::*;
+///
+/// // These would usually come from different crates.
+/// # pub struct MyReverb;
+/// # impl Plugin for MyReverb {
+/// #     type AudioProcessor<'a> = ();
+/// #     type Shared<'a> = ();
+/// #     type MainThread<'a> = ();
+/// # }
+/// # impl DefaultPluginFactory for MyReverb {
+/// #     fn get_descriptor() -> PluginDescriptor {
+/// #         PluginDescriptor::new("my.suite.reverb", "My Reverb")
+/// #     }
+/// #     fn new_shared(_host: HostSharedHandle) -> Result<(), PluginError> {
+/// #         Ok(())
+/// #     }
+/// #     fn new_main_thread<'a>(_host: HostMainThreadHandle<'a>, _shared: &'a ()) -> Result<(), PluginError> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # pub struct MyDelay;
+/// # impl Plugin for MyDelay {
+/// #     type AudioProcessor<'a> = ();
+/// #     type Shared<'a> = ();
+/// #     type MainThread<'a> = ();
+/// # }
+/// # impl DefaultPluginFactory for MyDelay {
+/// #     fn get_descriptor() -> PluginDescriptor {
+/// #         PluginDescriptor::new("my.suite.delay", "My Delay")
+/// #     }
+/// #     fn new_shared(_host: HostSharedHandle) -> Result<(), PluginError> {
+/// #         Ok(())
+/// #     }
+/// #     fn new_main_thread<'a>(_host: HostMainThreadHandle<'a>, _shared: &'a ()) -> Result<(), PluginError> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// pub type MyReverbEntry = SinglePluginEntry<MyReverb>;
+/// pub type MyDelayEntry = SinglePluginEntry<MyDelay>;
+///
+/// // The host will see a single bundle containing both plugins.
+/// clack_export_entry!(combine_entries!(MyReverbEntry, MyDelayEntry));
+/// ```
+pub struct CombinedEntry<T: EntryList> {
+    plugin_factory: CombinedPluginFactory<T>,
+}
+
+impl<T: EntryList> CombinedEntry<T> {
+    /// Returns the combined entries.
+    #[inline]
+    pub fn entries(&self) -> &T {
+        &self.plugin_factory.entries
+    }
+}
+
+impl<T: EntryList> Entry for CombinedEntry<T> {
+    fn new(bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+        Ok(Self {
+            plugin_factory: CombinedPluginFactory::new(T::new(bundle_path)?),
+        })
+    }
+
+    fn declare_factories<'a>(&'a self, builder: &mut EntryFactories<'a>) {
+        builder.register_factory(&self.plugin_factory);
+
+        let mut index = 0;
+        while self.entries().declare_factories(index, builder) {
+            index += 1;
+        }
+    }
+}
+
+/// A list of [`Entry`] types, which can be combined using a [`CombinedEntry`].
+///
+/// This trait is implemented for tuples of up to 12 entry types.
+pub trait EntryList: Send + Sync + Sized + 'static {
+    /// Instantiates all the entries of this list, in order.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`Err`] if any of the entries failed to instantiate.
+    fn new(bundle_path: &CStr) -> Result<Self, EntryLoadError>;
+
+    /// Declares the factories of the entry at the given index in this list.
+    ///
+    /// This returns `false` if there is no entry at the given index.
+    fn declare_factories<'a>(&'a self, index: usize, builder: &mut EntryFactories<'a>) -> bool;
+}
+
+macro_rules! impl_entry_list {
+    ($($entry:ident: $index:tt),+) => {
+        impl<$($entry: Entry),+> EntryList for ($($entry,)+) {
+            fn new(bundle_path: &CStr) -> Result<Self, EntryLoadError> {
+                Ok(($($entry::new(bundle_path)?,)+))
+            }
+
+            fn declare_factories<'a>(&'a self, index: usize, builder: &mut EntryFactories<'a>) -> bool {
+                match index {
+                    $($index => self.$index.declare_factories(builder),)+
+                    _ => return false,
+                }
+
+                true
+            }
+        }
+    };
+}
+
+impl_entry_list!(A: 0);
+impl_entry_list!(A: 0, B: 1);
+impl_entry_list!(A: 0, B: 1, C: 2);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+impl_entry_list!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+
+/// A plugin factory forwarding to the plugin factories of all the combined entries.
+///
+/// This works directly with the raw factories the entries expose, as they may use any
+/// [`PluginFactory`] implementation.
+#[repr(C)]
+struct CombinedPluginFactory<T> {
+    raw: clap_plugin_factory,
+    entries: T,
+}
+
+// SAFETY: CombinedPluginFactory is #[repr(C)] with clap_plugin_factory as its first field, and
+// matches CLAP_PLUGIN_FACTORY_ID.
+unsafe impl<T> Factory for CombinedPluginFactory<T> {
+    const IDENTIFIER: &'static CStr = CLAP_PLUGIN_FACTORY_ID;
+}
+
+impl<T: EntryList> CombinedPluginFactory<T> {
+    fn new(entries: T) -> Self {
+        Self {
+            raw: clap_plugin_factory {
+                get_plugin_count: Some(Self::get_plugin_count),
+                get_plugin_descriptor: Some(Self::get_plugin_descriptor),
+                create_plugin: Some(Self::create_plugin),
+            },
+            entries,
+        }
+    }
+
+    /// Calls the given closure with the raw plugin factory of every entry that has one, until the
+    /// closure returns `Some`.
+    fn find_map<R>(&self, mut f: impl FnMut(&clap_plugin_factory) -> Option<R>) -> Option<R> {
+        let mut index = 0;
+
+        loop {
+            let mut builder = EntryFactories::new(CLAP_PLUGIN_FACTORY_ID);
+            if !self.entries.declare_factories(index, &mut builder) {
+                return None;
+            }
+
+            index += 1;
+
+            // SAFETY: the entry registered this pointer as a plugin factory, which remains valid
+            // for as long as the entry itself.
+            let Some(factory) = (unsafe { builder.found().cast::<clap_plugin_factory>().as_ref() })
+            else {
+                continue;
+            };
+
+            if let Some(result) = f(factory) {
+                return Some(result);
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The given pointer must be a valid pointer to a raw factory, as given by the entry.
+    unsafe fn handle<R>(
+        raw: *const clap_plugin_factory,
+        handler: impl FnOnce(&Self) -> Option<R>,
+    ) -> Option<R> {
+        let factory = (raw as *const Self).as_ref()?;
+        handle_panic(AssertUnwindSafe(|| handler(factory))).ok()?
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_plugin_count(raw: *const clap_plugin_factory) -> u32 {
+        Self::handle(raw, |factory| {
+            let mut count = 0u32;
+
+            factory.find_map::<()>(|child| {
+                // SAFETY: the child factory pointer is valid, as per find_map.
+                count = count.saturating_add(unsafe { child_plugin_count(child) });
+                None
+            });
+
+            Some(count)
+        })
+        .unwrap_or(0)
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn get_plugin_descriptor(
+        raw: *const clap_plugin_factory,
+        index: u32,
+    ) -> *const clap_plugin_descriptor {
+        Self::handle(raw, |factory| {
+            let mut index = index;
+
+            factory.find_map(|child| {
+                // SAFETY: the child factory pointer is valid, as per find_map.
+                let count = unsafe { child_plugin_count(child) };
+
+                if index >= count {
+                    index -= count;
+                    return None;
+                }
+
+                // SAFETY: same as above, and the index is in range.
+                Some(unsafe { child.get_plugin_descriptor?(child, index) })
+            })
+        })
+        .unwrap_or(core::ptr::null())
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    unsafe extern "C" fn create_plugin(
+        raw: *const clap_plugin_factory,
+        host: *const clap_host,
+        plugin_id: *const c_char,
+    ) -> *const clap_plugin {
+        if plugin_id.is_null() {
+            return core::ptr::null();
+        }
+
+        let requested = CStr::from_ptr(plugin_id);
+
+        Self::handle(raw, |factory| {
+            factory.find_map(|child| {
+                // SAFETY: the child factory pointer is valid, as per find_map.
+                unsafe {
+                    let get_plugin_descriptor = child.get_plugin_descriptor?;
+
+                    let exposes_plugin = (0..child_plugin_count(child)).any(|index| {
+                        let descriptor = get_plugin_descriptor(child, index);
+                        match descriptor.as_ref() {
+                            Some(d) if !d.id.is_null() => CStr::from_ptr(d.id) == requested,
+                            _ => false,
+                        }
+                    });
+
+                    if !exposes_plugin {
+                        return None;
+                    }
+
+                    Some(child.create_plugin?(child, host, plugin_id))
+                }
+            })
+        })
+        .unwrap_or(core::ptr::null())
+    }
+}
+
+/// # Safety
+///
+/// The given factory must be valid.
+unsafe fn child_plugin_count(factory: &clap_plugin_factory) -> u32 {
+    match factory.get_plugin_count {
+        Some(get_plugin_count) => get_plugin_count(factory),
+        None => 0,
+    }
+}
+
+/// Produces a [`CombinedEntry`] type combining the given [`Entry`] types.
+///
+/// `combine_entries!(A, B, C)` is a shorthand for `CombinedEntry<(A, B, C)>`. This can be used
+/// with the [`clack_export_entry`](crate::clack_export_entry) macro to export the combined entry
+/// to the host.
+///
+/// See the [`CombinedEntry`] documentation for more information.
+#[macro_export]
+macro_rules! combine_entries {
+    ($($entry:ty),+ $(,)?) => {
+        $crate::entry::CombinedEntry<($($entry,)+)>
+    };
+}
@@ -4,6 +4,9 @@
 //! If you want to use an existing extension in your plugin, see the `clack_extensions`
 //! crate instead.
 //!
+//! See the `clack-example-custom-extension` crate in the Clack repository for a complete example
+//! of a third-party extension, implemented on both the host and the plugin side.
+//!
 //! # Example
 //!
 //! This example shows a basic implementation for the plugin side of the CLAP State extension.
@@ -129,12 +132,55 @@ impl<'a, P: Plugin> PluginExtensions<'a, P> {
             return self;
         }
 
-        if E::IDENTIFIER == self.requested {
+        if extension_identifiers::<E>().any(|identifier| identifier == self.requested) {
             self.found = Some(E::IMPLEMENTATION.as_ptr())
         }
 
         self
     }
+
+    /// Adds a given extension implementation to the list of supported extensions, but only if
+    /// `condition` is `true`.
+    ///
+    /// This is a convenience method to conditionally register extensions from a chain of calls,
+    /// e.g. depending on a runtime configuration.
+    #[inline]
+    pub fn register_if<E: ExtensionImplementation<P, ExtensionSide = PluginExtensionSide>>(
+        &mut self,
+        condition: bool,
+    ) -> &mut Self {
+        if condition {
+            self.register::<E>();
+        }
+
+        self
+    }
+
+    /// Adds a raw extension implementation to the list of supported extensions, for the given
+    /// extension identifier.
+    ///
+    /// This allows to expose extensions that don't have an associated [`Extension`] type, such as
+    /// third-party extensions.
+    ///
+    /// # Safety
+    ///
+    /// The given implementation must point to a valid, C-FFI compatible extension struct that
+    /// matches the ABI of the extension designated by `identifier`.
+    pub unsafe fn register_raw(
+        &mut self,
+        identifier: &CStr,
+        implementation: RawExtensionImplementation,
+    ) -> &mut Self {
+        if self.found.is_some() {
+            return self;
+        }
+
+        if identifier == self.requested {
+            self.found = Some(implementation.as_ptr())
+        }
+
+        self
+    }
 }
 
 /// A prelude which re-exports all the types and traits used for custom extension implementation.
@@ -9,12 +9,27 @@ use crate::plugin::{logging, Plugin, PluginAudioProcessor, PluginBoxInner, Plugi
 use crate::process::PluginAudioConfiguration;
 use clap_sys::ext::log::*;
 use clap_sys::plugin::clap_plugin;
+use std::any::Any;
 use std::cell::UnsafeCell;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(any(feature = "thread-checks", debug_assertions))]
+mod thread_checks;
+
+#[cfg(any(feature = "thread-checks", debug_assertions))]
+use thread_checks::{ThreadChecker, ThreadType};
+
+#[cfg(feature = "validate-events")]
+use crate::process::Events;
+#[cfg(feature = "validate-events")]
+use clack_common::events::io::{OutputEventValidator, OutputEvents};
+#[cfg(feature = "validate-events")]
+use clap_sys::process::clap_process;
 
 #[cfg(not(test))]
 #[allow(unused)]
@@ -35,11 +50,38 @@ pub(crate) fn handle_panic<F: FnOnce() -> R, R>(f: F) -> std::thread::Result<R>
 ///
 /// The only way to access an instance of `PluginWrapper` is through the
 /// [`handle`](PluginWrapper::handle) function.
+///
+/// When the `thread-checks` feature is enabled, the wrapper also checks that the host calls
+/// main-thread and audio-thread functions from the correct thread, and reports it otherwise.
+/// See [`main_thread`](PluginWrapper::main_thread) and
+/// [`audio_processor`](PluginWrapper::audio_processor).
+///
+/// In debug builds, these checks are always performed, but violations are only logged to the
+/// host (once per thread type), instead of causing the call to fail.
+///
+/// # Poisoning
+///
+/// If the plugin panics while handling any call, the panic is caught before it can unwind across
+/// the FFI boundary, and is reported to the host's log. Because the plugin's state may have been
+/// left inconsistent by the panic, the wrapper is then considered *poisoned*: all further calls
+/// made through [`handle`](PluginWrapper::handle) fail immediately, without calling into the
+/// plugin's code. Failed calls return the matching CLAP failure value (e.g. `false`, or
+/// `CLAP_PROCESS_ERROR` for `process`).
+///
+/// A poisoned instance can still be destroyed by the host. Its audio processor is then dropped
+/// without its [`deactivate`](PluginAudioProcessor::deactivate) method being called.
+///
+/// See [`is_poisoned`](PluginWrapper::is_poisoned).
 pub struct PluginWrapper<'a, P: Plugin> {
     audio_processor: UnsafeOptionCell<P::AudioProcessor<'a>>,
     main_thread: UnsafeCell<P::MainThread<'a>>,
     shared: Pin<Box<P::Shared<'a>>>,
     host: HostSharedHandle<'a>,
+    poisoned: AtomicBool,
+    #[cfg(any(feature = "thread-checks", debug_assertions))]
+    thread_checker: ThreadChecker<'a>,
+    #[cfg(feature = "validate-events")]
+    event_validator: UnsafeCell<OutputEventValidator>,
 }
 
 impl<'a, P: Plugin> PluginWrapper<'a, P> {
@@ -56,6 +98,11 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
             shared,
             main_thread: UnsafeCell::new(main_thread),
             audio_processor: UnsafeOptionCell::new(),
+            poisoned: AtomicBool::new(false),
+            #[cfg(any(feature = "thread-checks", debug_assertions))]
+            thread_checker: ThreadChecker::new(host),
+            #[cfg(feature = "validate-events")]
+            event_validator: UnsafeCell::new(OutputEventValidator::new()),
         }
     }
 
@@ -82,6 +129,9 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
         // SAFETY: It is up to the caller to ensure this is never called simultaneously with deactivate()
         self.audio_processor.put(processor);
 
+        #[cfg(feature = "validate-events")]
+        self.reset_event_validator();
+
         Ok(())
     }
 
@@ -98,12 +148,70 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
         }
     }
 
+    /// Calls the given process handler with events from the given process struct, validating
+    /// all the output events pushed by the plugin.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure this method is only called on the audio thread, with a valid process
+    /// struct.
+    ///
+    /// # Panics
+    ///
+    /// This method logs an error and panics if the plugin pushed any invalid output event.
+    #[cfg(feature = "validate-events")]
+    pub(crate) unsafe fn validate_output_events<R>(
+        &self,
+        process: &clap_process,
+        handler: impl FnOnce(Events) -> R,
+    ) -> R {
+        let validator = &mut *self.event_validator.get();
+        let events = Events::from_raw(process);
+
+        let mut validated =
+            validator.validate_block(events.input, events.output, process.frames_count);
+        let mut output = OutputEvents::from_buffer(&mut validated);
+
+        let result = handler(Events {
+            input: events.input,
+            output: &mut output,
+        });
+
+        if let Some(e) = validated.error() {
+            let e = PluginWrapperError::Error(CLAP_LOG_PLUGIN_MISBEHAVING, Box::new(e));
+            logging::host_log(self.host.as_raw(), &e);
+            panic!("{e}");
+        }
+
+        result
+    }
+
+    /// Forgets all the notes tracked to validate output events.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure this is never called concurrently with
+    /// [`validate_output_events`](Self::validate_output_events).
+    #[cfg(feature = "validate-events")]
+    pub(crate) unsafe fn reset_event_validator(&self) {
+        (*self.event_validator.get()).reset();
+    }
+
     /// Returns if the current plugin has been activated or not.
     #[inline]
     pub fn is_active(&self) -> bool {
         self.audio_processor.is_some()
     }
 
+    /// Returns `true` if the plugin panicked during a previous call, in which case all further
+    /// calls made through [`handle`](PluginWrapper::handle) are rejected.
+    ///
+    /// See the [poisoning](PluginWrapper#poisoning) documentation for more information.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
     /// Returns a reference to a plugin's [`Shared`](Plugin::Shared) struct.
     ///
     /// This is always safe to call in any context, since the `Shared` struct is required to
@@ -121,8 +229,23 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     ///
     /// The pointer is safe to mutably dereference, as long as the caller ensures it is not being
     /// aliased, as per usual safety rules.
+    ///
+    /// # Panics
+    ///
+    /// If the `thread-checks` feature is enabled, this method panics if it is called from any
+    /// thread other than the main thread. When caught by [`handle`](PluginWrapper::handle), this
+    /// panic is turned into a [`PluginWrapperError::WrongThread`] error, and does not poison the
+    /// wrapper.
     #[inline]
     pub unsafe fn main_thread(&self) -> NonNull<P::MainThread<'a>> {
+        #[cfg(feature = "thread-checks")]
+        if self.thread_checker.check(ThreadType::Main).is_err() {
+            std::panic::resume_unwind(Box::new(WrongThreadPanic("main")));
+        }
+
+        #[cfg(all(not(feature = "thread-checks"), debug_assertions))]
+        self.thread_checker.report(ThreadType::Main);
+
         // SAFETY: pointer has been created from reference, it cannot be null.
         NonNull::new_unchecked(self.main_thread.get())
     }
@@ -138,6 +261,10 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     /// This is an extra safety check which ensures that hosts correctly activated plugins before
     /// calling any audio-thread method.
     ///
+    /// If the `thread-checks` feature is enabled, this method also returns
+    /// `PluginWrapperError::WrongThread` if it is called from any thread other than an audio
+    /// thread.
+    ///
     /// # Safety
     /// The caller must ensure this method is only called on the audio thread.
     ///
@@ -147,6 +274,57 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     pub unsafe fn audio_processor(
         &self,
     ) -> Result<NonNull<P::AudioProcessor<'a>>, PluginWrapperError> {
+        #[cfg(feature = "thread-checks")]
+        self.thread_checker.check(ThreadType::Audio)?;
+
+        let audio_processor = self.active_audio_processor()?;
+
+        // Some extensions probe for the audio processor from the main thread while the plugin is
+        // deactivated, so only report calls that actually reach it.
+        #[cfg(all(not(feature = "thread-checks"), debug_assertions))]
+        self.thread_checker.report(ThreadType::Audio);
+
+        Ok(audio_processor)
+    }
+
+    /// Returns a raw, non-null pointer to the plugin's audio processor
+    /// (i.e. [`Plugin`](PluginAudioProcessor)) struct, for functions that can be called from
+    /// either the main thread or the audio thread.
+    ///
+    /// This behaves exactly like [`audio_processor`](Self::audio_processor), except the
+    /// `thread-checks` feature accepts calls from both the main thread and audio threads.
+    ///
+    /// # Errors
+    ///
+    /// This method will return `PluginWrapperError::DeactivatedPlugin` if the plugin has not been
+    /// activated before calling this method.
+    ///
+    /// If the `thread-checks` feature is enabled, this method also returns
+    /// `PluginWrapperError::WrongThread` if it is called from any thread other than the main
+    /// thread or an audio thread.
+    ///
+    /// # Safety
+    /// The caller must ensure this method is only called on the main thread or the audio thread.
+    ///
+    /// The pointer is safe to dereference, as long as the caller ensures it is not being
+    /// aliased, as per usual safety rules.
+    #[inline]
+    pub unsafe fn audio_processor_on_main_or_audio_thread(
+        &self,
+    ) -> Result<NonNull<P::AudioProcessor<'a>>, PluginWrapperError> {
+        #[cfg(feature = "thread-checks")]
+        self.thread_checker.check(ThreadType::MainOrAudio)?;
+
+        let audio_processor = self.active_audio_processor()?;
+
+        #[cfg(all(not(feature = "thread-checks"), debug_assertions))]
+        self.thread_checker.report(ThreadType::MainOrAudio);
+
+        Ok(audio_processor)
+    }
+
+    #[inline]
+    fn active_audio_processor(&self) -> Result<NonNull<P::AudioProcessor<'a>>, PluginWrapperError> {
         self.audio_processor
             .as_ptr()
             // SAFETY: pointer has been created from reference, it cannot be null.
@@ -160,7 +338,8 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     ///
     /// * The given `clap_plugin` pointer is null-checked, as well as some other host-provided
     ///   pointers;
-    /// * The handler is wrapped in [`std::panic::catch_unwind`];
+    /// * The handler is wrapped in [`std::panic::catch_unwind`]. If it panics, the wrapper is
+    ///   [poisoned](PluginWrapper#poisoning), and the handler is never called again;
     /// * Any [`PluginWrapperError`] returned by the handler is caught.
     ///
     /// If any of the above safety check fails, an error message is logged (using the standard CLAP
@@ -179,6 +358,9 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     /// If any safety check failed, or any error or panic occurred inside the handler closure, this
     /// function returns `None`, and the error message is logged.
     ///
+    /// If the wrapper was already poisoned, this function returns `None` without calling the
+    /// handler, and without logging anything, as the original panic has already been reported.
+    ///
     /// # Safety
     ///
     /// The given plugin type `P` **must** be the correct type for the received pointer. Otherwise,
@@ -212,8 +394,9 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
     where
         F: FnOnce(&PluginWrapper<'a, P>) -> Result<T, PluginWrapperError>,
     {
-        match Self::from_raw(plugin).and_then(|p| Self::handle_panic(p, handler)) {
+        match Self::from_raw(plugin).and_then(|p| p.handle_poisoning(handler)) {
             Ok(value) => Some(value),
+            Err(PluginWrapperError::Poisoned) => None,
             Err(e) => {
                 logging::plugin_log::<P>(plugin, &e);
 
@@ -267,14 +450,48 @@ impl<'a, P: Plugin> PluginWrapper<'a, P> {
         NonNull::new(data).ok_or(PluginWrapperError::AlreadyDestroyed)
     }
 
+    #[inline]
+    fn handle_poisoning<T, F>(&self, handler: F) -> Result<T, PluginWrapperError>
+    where
+        F: FnOnce(&Self) -> Result<T, PluginWrapperError>,
+    {
+        if self.is_poisoned() {
+            return Err(PluginWrapperError::Poisoned);
+        }
+
+        let result = Self::handle_panic(self, handler);
+
+        if let Err(PluginWrapperError::Panic) = result {
+            self.poisoned.store(true, Ordering::Release);
+        }
+
+        result
+    }
+
     #[inline]
     fn handle_panic<Pa, T, F>(parameter: Pa, handler: F) -> Result<T, PluginWrapperError>
     where
         F: FnOnce(Pa) -> Result<T, PluginWrapperError>,
     {
-        handle_panic(AssertUnwindSafe(|| handler(parameter)))
-            .map_err(|_| PluginWrapperError::Panic)?
+        handle_panic(AssertUnwindSafe(|| handler(parameter))).map_err(panic_to_error)?
+    }
+}
+
+/// The payload of the panics raised by failed thread checks.
+///
+/// These are raised with [`std::panic::resume_unwind`], which doesn't invoke the panic hook, and
+/// are turned back into [`PluginWrapperError::WrongThread`] errors when caught.
+#[cfg(feature = "thread-checks")]
+struct WrongThreadPanic(&'static str);
+
+#[cfg_attr(not(feature = "thread-checks"), allow(unused_variables))]
+fn panic_to_error(payload: Box<dyn Any + Send>) -> PluginWrapperError {
+    #[cfg(feature = "thread-checks")]
+    if let Some(WrongThreadPanic(thread)) = payload.downcast_ref() {
+        return PluginWrapperError::WrongThread(thread);
     }
+
+    PluginWrapperError::Panic
 }
 
 // SAFETY: the wrapper itself can be shared and used across threads, accessing any inner part that
@@ -318,8 +535,21 @@ pub enum PluginWrapperError {
     /// A function which requires the plugin to be deactivated was called while the plugin was still
     /// active.
     DeactivationRequiredForFunction(&'static str),
+    /// A function was called from a thread it is not allowed to be called from.
+    ///
+    /// The given string contains the name of the thread the function must be called from, i.e.
+    /// either `main` or `audio`.
+    ///
+    /// This error is only raised when the `thread-checks` feature is enabled. In debug builds
+    /// without that feature, it is only logged to the host.
+    WrongThread(&'static str),
     /// The plugin panicked during a function call.
+    ///
+    /// This [poisons](PluginWrapper#poisoning) the plugin instance.
     Panic,
+    /// A function was called on a plugin instance that was [poisoned](PluginWrapper#poisoning) by
+    /// a previous panic.
+    Poisoned,
     /// A given [`PluginError`] was raised during a function call.
     Plugin(PluginError),
     /// Bad UTF-8.
@@ -347,7 +577,7 @@ impl PluginWrapperError {
     pub fn severity(&self) -> clap_log_severity {
         match self {
             PluginWrapperError::Plugin(_) => CLAP_LOG_ERROR,
-            PluginWrapperError::Panic => CLAP_LOG_PLUGIN_MISBEHAVING,
+            PluginWrapperError::Panic | PluginWrapperError::Poisoned => CLAP_LOG_PLUGIN_MISBEHAVING,
             PluginWrapperError::Error(s, _) => *s,
             _ => CLAP_LOG_HOST_MISBEHAVING,
         }
@@ -417,6 +647,10 @@ impl Display for PluginWrapperError {
                 f,
                 "Host attempted to call '{function}' while plugin was still active"
             ),
+            PluginWrapperError::WrongThread(thread) => write!(
+                f,
+                "Host called a {thread}-thread plugin function from the wrong thread"
+            ),
             PluginWrapperError::StringEncoding(e) => {
                 write!(
                     f,
@@ -434,6 +668,9 @@ impl Display for PluginWrapperError {
             PluginWrapperError::Plugin(e) => std::fmt::Display::fmt(&e, f),
             PluginWrapperError::Error(_, e) => std::fmt::Display::fmt(e, f),
             PluginWrapperError::Panic => f.write_str("Plugin panicked"),
+            PluginWrapperError::Poisoned => {
+                f.write_str("Plugin instance was poisoned by a previous panic")
+            }
         }
     }
 }
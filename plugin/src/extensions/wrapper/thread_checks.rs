@@ -0,0 +1,122 @@
+use crate::extensions::wrapper::PluginWrapperError;
+use crate::host::HostSharedHandle;
+use crate::plugin::logging;
+use clap_sys::ext::thread_check::{clap_host_thread_check, CLAP_EXT_THREAD_CHECK};
+use clap_sys::host::clap_host;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::ThreadId;
+
+/// The symbolic CLAP threads a plugin function can be required to run on.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ThreadType {
+    Main,
+    Audio,
+    /// Either the main thread or an audio thread, for functions that can be called from both.
+    MainOrAudio,
+}
+
+impl ThreadType {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ThreadType::Main => "main",
+            ThreadType::Audio => "audio",
+            ThreadType::MainOrAudio => "main-or-audio",
+        }
+    }
+}
+
+/// Keeps track of the plugin's main thread, and checks host calls are made on the right thread.
+///
+/// If the host implements the `thread-check` extension, it is used to know which threads are
+/// the main or audio threads. Otherwise, the thread that initialized the plugin is considered to
+/// be the main thread, and audio-thread calls are not checked, as hosts are free to process
+/// on any thread (including the main thread) in that case.
+pub(crate) struct ThreadChecker<'a> {
+    main_thread: ThreadId,
+    host: &'a clap_host,
+    host_thread_check: Option<&'a clap_host_thread_check>,
+    main_thread_reported: AtomicBool,
+    audio_thread_reported: AtomicBool,
+    main_or_audio_thread_reported: AtomicBool,
+}
+
+impl<'a> ThreadChecker<'a> {
+    /// Creates a new thread checker. This must be called on the main thread.
+    pub(crate) fn new(host: HostSharedHandle<'a>) -> Self {
+        let raw_host = host.as_raw();
+
+        let host_thread_check = raw_host.get_extension.and_then(|get_extension| {
+            // SAFETY: the host pointer is valid, and we're on the main thread.
+            let ext = unsafe { get_extension(raw_host, CLAP_EXT_THREAD_CHECK.as_ptr()) };
+            // SAFETY: the host guarantees the extension pointer lives as long as its instance.
+            unsafe { (ext as *const clap_host_thread_check).as_ref() }
+        });
+
+        Self {
+            main_thread: std::thread::current().id(),
+            host: raw_host,
+            host_thread_check,
+            main_thread_reported: AtomicBool::new(false),
+            audio_thread_reported: AtomicBool::new(false),
+            main_or_audio_thread_reported: AtomicBool::new(false),
+        }
+    }
+
+    /// Checks the current thread is of the given type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginWrapperError::WrongThread`] if it is not.
+    pub(crate) fn check(&self, expected: ThreadType) -> Result<(), PluginWrapperError> {
+        if self.is_current(expected) {
+            Ok(())
+        } else {
+            Err(PluginWrapperError::WrongThread(expected.name()))
+        }
+    }
+
+    /// Checks the current thread is of the given type, and logs a violation to the host if it is
+    /// not.
+    ///
+    /// Only the first violation for each thread type is logged, to avoid flooding the host's log
+    /// with messages when a function is repeatedly called from the wrong thread (e.g. `process`).
+    #[cfg_attr(feature = "thread-checks", allow(dead_code))]
+    pub(crate) fn report(&self, expected: ThreadType) {
+        let Err(e) = self.check(expected) else {
+            return;
+        };
+
+        let reported = match expected {
+            ThreadType::Main => &self.main_thread_reported,
+            ThreadType::Audio => &self.audio_thread_reported,
+            ThreadType::MainOrAudio => &self.main_or_audio_thread_reported,
+        };
+
+        if !reported.swap(true, Ordering::Relaxed) {
+            // SAFETY: the host pointer is valid for the lifetime of the plugin instance.
+            unsafe { logging::host_log(self.host, &e) }
+        }
+    }
+
+    fn is_current(&self, thread_type: ThreadType) -> bool {
+        if thread_type == ThreadType::MainOrAudio {
+            return self.is_current(ThreadType::Main) || self.is_current(ThreadType::Audio);
+        }
+
+        let from_host = self.host_thread_check.and_then(|ext| {
+            let check = match thread_type {
+                ThreadType::Main => ext.is_main_thread?,
+                ThreadType::Audio => ext.is_audio_thread?,
+                ThreadType::MainOrAudio => unreachable!(),
+            };
+
+            // SAFETY: the host pointer is valid, and this function is thread-safe.
+            Some(unsafe { check(self.host) })
+        });
+
+        from_host.unwrap_or_else(|| match thread_type {
+            ThreadType::Main => std::thread::current().id() == self.main_thread,
+            ThreadType::Audio | ThreadType::MainOrAudio => true,
+        })
+    }
+}
@@ -1,9 +1,9 @@
 //! Types and handles for plugins to interact with the host.
 
-use clack_common::extensions::{Extension, HostExtensionSide, RawExtension};
+use clack_common::extensions::{extension_identifiers, Extension, HostExtensionSide, RawExtension};
 use clack_common::utils::ClapVersion;
 use clap_sys::host::clap_host;
-use std::ffi::CStr;
+use std::ffi::{c_void, CStr};
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::ptr::NonNull;
@@ -92,16 +92,46 @@ impl<'a> HostInfo<'a> {
     /// # }
     /// ```
     pub fn get_extension<E: Extension<ExtensionSide = HostExtensionSide>>(&self) -> Option<E> {
-        // SAFETY: this type ensures the function pointers are valid
-        let ext =
-            unsafe { self.as_raw().get_extension?(self.raw.as_ptr(), E::IDENTIFIER.as_ptr()) };
+        self.get_extension_with_identifier()
+            .map(|(extension, _)| extension)
+    }
 
-        let ext = NonNull::new(ext as *mut _)?;
-        // SAFETY: The CLAP spec guarantees that the extension lives as long as the instance.
-        let raw = unsafe { RawExtension::from_raw_host_extension(ext, self.raw) };
+    /// Retrieves the host's pointer to the given [extension type](Extension) `E`, alongside the
+    /// identifier the host recognized it with.
+    ///
+    /// This is either [`E::IDENTIFIER`](Extension::IDENTIFIER), or one of the extension's
+    /// [`COMPAT_IDENTIFIERS`](Extension::COMPAT_IDENTIFIERS) if the host only supports an older
+    /// identifier.
+    ///
+    /// This returns `None` if the host does not support the given extension.
+    pub fn get_extension_with_identifier<E: Extension<ExtensionSide = HostExtensionSide>>(
+        &self,
+    ) -> Option<(E, &'static CStr)> {
+        extension_identifiers::<E>().find_map(|identifier| {
+            let ext = self.get_raw_extension(identifier)?.cast();
+            // SAFETY: The CLAP spec guarantees that the extension lives as long as the instance.
+            let raw = unsafe { RawExtension::from_raw_host_extension(ext, self.raw) };
+
+            // SAFETY: pointer comes from one of the associated identifiers of E.
+            unsafe { Some((E::from_raw(raw), identifier)) }
+        })
+    }
 
-        // SAFETY: pointer comes from the associated E::IDENTIFIER.
-        unsafe { Some(E::from_raw(raw)) }
+    /// Returns a raw pointer to the host's implementation of the extension matching the given
+    /// identifier, or `None` if the host doesn't support it.
+    ///
+    /// This is an escape hatch allowing to use extensions that don't have an associated
+    /// [`Extension`] type, such as third-party extensions. Prefer using
+    /// [`get_extension`](Self::get_extension) whenever possible.
+    ///
+    /// The CLAP specification guarantees the returned pointer is valid for as long as the host
+    /// instance exists. However, it is up to the caller to know which type it points to, and to
+    /// only use it from the threads the extension allows.
+    pub fn get_raw_extension(&self, identifier: &CStr) -> Option<NonNull<c_void>> {
+        // SAFETY: this type ensures the function pointers are valid
+        let ext = unsafe { self.as_raw().get_extension?(self.raw.as_ptr(), identifier.as_ptr()) };
+
+        NonNull::new(ext as *mut _)
     }
 
     /// # Safety
@@ -139,6 +169,21 @@ unsafe impl Send for HostSharedHandle<'_> {}
 unsafe impl Sync for HostSharedHandle<'_> {}
 
 impl<'a> HostSharedHandle<'a> {
+    /// Creates a new handle from a given raw, C FFI compatible host pointer.
+    ///
+    /// # Safety
+    /// Pointer must be valid for the duration of the `'a` lifetime. Moreover, the contents of
+    /// the `clap_host` struct must all also be valid.
+    ///
+    /// The plugin instance this host pointer was given to must also be initialized.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_host>) -> Self {
+        Self {
+            raw,
+            _lifetime: PhantomData,
+        }
+    }
+
     /// Returns the host's information.
     #[inline]
     pub fn info(&self) -> HostInfo<'a> {
@@ -267,6 +312,21 @@ pub struct HostMainThreadHandle<'a> {
 }
 
 impl<'a> HostMainThreadHandle<'a> {
+    /// Creates a new handle from a given raw, C FFI compatible host pointer.
+    ///
+    /// # Safety
+    /// Pointer must be valid for the duration of the `'a` lifetime. Moreover, the contents of
+    /// the `clap_host` struct must all also be valid.
+    ///
+    /// This handle must only be created and used on the main thread.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_host>) -> Self {
+        Self {
+            raw,
+            _lifetime: PhantomData,
+        }
+    }
+
     /// Gets a thread-safe host handle from this handle.
     #[inline]
     pub fn shared(&self) -> HostSharedHandle<'a> {
@@ -320,6 +380,21 @@ pub struct HostAudioProcessorHandle<'a> {
 unsafe impl Send for HostAudioProcessorHandle<'_> {}
 
 impl<'a> HostAudioProcessorHandle<'a> {
+    /// Creates a new handle from a given raw, C FFI compatible host pointer.
+    ///
+    /// # Safety
+    /// Pointer must be valid for the duration of the `'a` lifetime. Moreover, the contents of
+    /// the `clap_host` struct must all also be valid.
+    ///
+    /// This handle must only be created and used on the audio thread, while the plugin is active.
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<clap_host>) -> Self {
+        Self {
+            raw,
+            _lifetime: PhantomData,
+        }
+    }
+
     /// Gets a thread-safe host handle from this handle.
     #[inline]
     pub fn shared(&self) -> HostSharedHandle<'a> {
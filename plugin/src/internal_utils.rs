@@ -110,3 +110,14 @@ impl<T> Drop for UnsafeOptionCell<T> {
         *is_some = false;
     }
 }
+
+/// Runs the given closure, checking it doesn't allocate if the `assert-no-alloc` feature is
+/// enabled.
+#[inline]
+pub(crate) fn guard_allocations<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "assert-no-alloc")]
+    return crate::utils::assert_no_alloc(f);
+
+    #[cfg(not(feature = "assert-no-alloc"))]
+    f()
+}
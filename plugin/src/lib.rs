@@ -31,8 +31,8 @@ pub mod prelude {
         extensions::PluginExtensions,
         host::{HostAudioProcessorHandle, HostMainThreadHandle, HostSharedHandle},
         plugin::{
-            Plugin, PluginAudioProcessor, PluginDescriptor, PluginError, PluginMainThread,
-            PluginShared,
+            AudioEffect, AudioEffectProcessor, Plugin, PluginAudioProcessor, PluginDescriptor,
+            PluginError, PluginMainThread, PluginShared,
         },
         process::{
             audio::{ChannelPair, SampleType},
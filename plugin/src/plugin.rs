@@ -51,11 +51,13 @@ use crate::host::HostAudioProcessorHandle;
 use crate::process::{Audio, Events, PluginAudioConfiguration, Process, ProcessStatus};
 
 mod descriptor;
+mod effect;
 mod error;
 mod instance;
 pub(crate) mod logging;
 
 pub use descriptor::*;
+pub use effect::*;
 pub use error::PluginError;
 pub use instance::*;
 
@@ -146,7 +148,14 @@ pub trait Plugin: 'static {
     /// A reference to the [`Shared`](Self::Shared) type is also given. However, it can be `None`,
     /// as the host is allowed to query extensions before the plugin has finished initializing.
     ///
+    /// Extensions can be registered conditionally, e.g. depending on some configuration held in
+    /// the shared data, using a regular `if` or [`register_if`]. Extensions that don't have an
+    /// associated extension type can be registered from their raw identifier and implementation
+    /// using [`register_raw`].
+    ///
     /// [`register`]: PluginExtensions::register
+    /// [`register_if`]: PluginExtensions::register_if
+    /// [`register_raw`]: PluginExtensions::register_raw
     #[inline]
     #[allow(unused_variables)]
     fn declare_extensions(builder: &mut PluginExtensions<Self>, shared: Option<&Self::Shared<'_>>) {
@@ -221,6 +230,15 @@ pub trait PluginAudioProcessor<'a, S: PluginShared<'a>, M: PluginMainThread<'a,
     /// This method *MUST* be realtime-safe, and is in fact the most performance-sensitive part
     /// of the whole plugin implementation.
     ///
+    /// To help catch accidental heap allocations, the `assert-no-alloc` feature wraps every call
+    /// to this method with `utils::assert_no_alloc`.
+    ///
+    /// # Output events
+    ///
+    /// To help catch invalid output events during development, the `validate-events` feature
+    /// checks every event pushed to `events.output` using an `OutputEventValidator`. Invalid
+    /// events are rejected, and the error is logged before panicking once this method returns.
+    ///
     /// # Errors
     ///
     /// This method may fail for any reason, depending on the plugin's implementation.
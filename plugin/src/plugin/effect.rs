@@ -0,0 +1,276 @@
+use crate::events::spaces::CoreEventSpace;
+use crate::events::UnknownEvent;
+use crate::host::HostAudioProcessorHandle;
+use crate::plugin::{PluginAudioProcessor, PluginError, PluginMainThread, PluginShared};
+use crate::process::audio::SampleType;
+use crate::process::{Audio, Events, PluginAudioConfiguration, Process, ProcessStatus};
+use clack_common::utils::ClapId;
+
+/// A simplified audio processor, for plain audio effects.
+///
+/// Most audio effects process each channel of a single input/output port pair, and only care about
+/// the current values of their parameters. This trait allows implementing just that, without
+/// having to deal with ports, sample types, channel pairing or event handling.
+///
+/// Types implementing this trait are used through the [`AudioEffectProcessor`] adapter, which
+/// implements the full [`PluginAudioProcessor`] trait:
+///
+/// * The main (first) port pair is copied from the input to the output buffers, and each output
+///   channel is then processed in-place by [`process_channel`](AudioEffect::process_channel). If
+///   the host only provides [`f64`] output buffers, each channel is converted to [`f32`] before
+///   processing, and back to [`f64`] afterward;
+/// * The [`Params`](AudioEffect::Params) snapshot is seeded from the plugin's main thread by
+///   [`initial_params`](AudioEffect::initial_params) on activation. Parameter value events are
+///   then applied to it using [`set_param`](AudioEffect::set_param), and processing is split at
+///   each event, so that parameter changes stay sample-accurate.
+///
+/// Effects that need more control (e.g. multiple ports, note events, or implementing extensions on
+/// the audio processor) should implement [`PluginAudioProcessor`] directly instead.
+///
+/// # Example
+///
+/// ```
+/// use clack_plugin::prelude::*;
+///
+/// pub struct GainPlugin;
+///
+/// impl Plugin for GainPlugin {
+///     type AudioProcessor<'a> = AudioEffectProcessor<Gain>;
+///     type Shared<'a> = ();
+///     type MainThread<'a> = ();
+/// }
+///
+/// pub struct Gain;
+///
+/// impl AudioEffect for Gain {
+///     type Params = f32;
+///     type MainThread<'a> = ();
+///
+///     fn initial_params(_main_thread: &()) -> f32 {
+///         1.0
+///     }
+///
+///     fn activate(_audio_config: PluginAudioConfiguration) -> Result<Self, PluginError> {
+///         Ok(Gain)
+///     }
+///
+///     fn set_param(volume: &mut f32, param_id: ClapId, value: f64) {
+///         if param_id == ClapId::new(0) {
+///             *volume = value as f32;
+///         }
+///     }
+///
+///     fn process_channel(&mut self, _channel_index: usize, buffer: &mut [f32], volume: &f32) {
+///         buffer.iter_mut().for_each(|sample| *sample *= volume);
+///     }
+/// }
+/// ```
+pub trait AudioEffect: Sized + Send {
+    /// A snapshot of the parameter values used by this effect.
+    ///
+    /// This is initialized by [`initial_params`](Self::initial_params) when the effect is
+    /// activated, and then updated by [`set_param`](Self::set_param) for every parameter value
+    /// event the host sends.
+    type Params: Copy + Default + Send;
+
+    /// The main thread type of the plugin, from which the initial parameter values are read.
+    ///
+    /// This must be the same type as the plugin's
+    /// [`Plugin::MainThread`](crate::plugin::Plugin::MainThread).
+    type MainThread<'a>;
+
+    /// Returns the parameter values the effect starts processing with, e.g. the ones the plugin's
+    /// main thread holds after a state was loaded.
+    ///
+    /// This method is always executed on the main thread, when the effect is activated.
+    ///
+    /// The default implementation of this method returns [`Default::default`].
+    #[allow(unused)]
+    #[inline]
+    fn initial_params(main_thread: &Self::MainThread<'_>) -> Self::Params {
+        Self::Params::default()
+    }
+
+    /// Creates and activates the effect.
+    ///
+    /// This method is always executed on the main thread. It can perform non-realtime-safe
+    /// initialization operations, such as allocating buffers using the provided
+    /// [`PluginAudioConfiguration`].
+    ///
+    /// See [`PluginAudioProcessor::activate`].
+    ///
+    /// # Errors
+    ///
+    /// This operation may fail for any reason, in which case `Err` is returned
+    /// and the plugin is not activated.
+    fn activate(audio_config: PluginAudioConfiguration) -> Result<Self, PluginError>;
+
+    /// Applies a new value for the parameter of the given ID to the given parameter snapshot.
+    ///
+    /// The default implementation of this method does nothing.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method is called from the audio thread, and *MUST* be realtime-safe.
+    #[allow(unused)]
+    #[inline]
+    fn set_param(params: &mut Self::Params, param_id: ClapId, value: f64) {}
+
+    /// Processes the samples of a single output channel, in-place.
+    ///
+    /// When this is called, the given `buffer` already contains the samples of the matching input
+    /// channel, and the effect is expected to overwrite them with its output. If there is no
+    /// matching input channel, the buffer's contents are left as the host provided them.
+    ///
+    /// Processing is split at every parameter change, which means this method may be called
+    /// multiple times per processing block for the same channel, with consecutive parts of that
+    /// channel's buffer each time.
+    ///
+    /// # Realtime Safety
+    ///
+    /// This method *MUST* be realtime-safe.
+    fn process_channel(&mut self, channel_index: usize, buffer: &mut [f32], params: &Self::Params);
+
+    /// Resets the effect's audio processing state.
+    ///
+    /// See [`PluginAudioProcessor::reset`].
+    ///
+    /// The default implementation of this method does nothing.
+    #[inline]
+    fn reset(&mut self) {}
+}
+
+/// An adapter implementing [`PluginAudioProcessor`] for any [`AudioEffect`].
+///
+/// This type can be used as a [`Plugin::AudioProcessor`](crate::plugin::Plugin::AudioProcessor)
+/// with any shared and main thread types. See the [`AudioEffect`] documentation for more
+/// information.
+pub struct AudioEffectProcessor<E: AudioEffect> {
+    effect: E,
+    params: E::Params,
+    /// A buffer used to convert the channels of f64 outputs to f32. It is `max_frames_count` long.
+    conversion_buffer: Vec<f32>,
+}
+
+impl<E: AudioEffect> AudioEffectProcessor<E> {
+    /// Returns a shared reference to the wrapped effect.
+    #[inline]
+    pub fn effect(&self) -> &E {
+        &self.effect
+    }
+
+    /// Returns a mutable reference to the wrapped effect.
+    #[inline]
+    pub fn effect_mut(&mut self) -> &mut E {
+        &mut self.effect
+    }
+
+    /// Returns the current snapshot of the effect's parameter values.
+    #[inline]
+    pub fn params(&self) -> &E::Params {
+        &self.params
+    }
+
+    #[inline]
+    fn handle_event(&mut self, event: &UnknownEvent) {
+        if let Some(CoreEventSpace::ParamValue(event)) = event.as_core_event() {
+            if let Some(param_id) = event.param_id() {
+                E::set_param(&mut self.params, param_id, event.value())
+            }
+        }
+    }
+}
+
+impl<'a, E, S> PluginAudioProcessor<'a, S, E::MainThread<'a>> for AudioEffectProcessor<E>
+where
+    E: AudioEffect + 'a,
+    S: PluginShared<'a>,
+    E::MainThread<'a>: PluginMainThread<'a, S>,
+{
+    #[inline]
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        main_thread: &mut E::MainThread<'a>,
+        _shared: &'a S,
+        audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self {
+            effect: E::activate(audio_config)?,
+            params: E::initial_params(main_thread),
+            conversion_buffer: vec![0.0; audio_config.max_frames_count as usize],
+        })
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        mut audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        let Some(mut port_pair) = audio.port_pair(0) else {
+            for event in events.input {
+                self.handle_event(event);
+            }
+
+            return Ok(ProcessStatus::ContinueIfNotQuiet);
+        };
+
+        let mut output = port_pair.mixed_channels()?.copy_to_output();
+
+        let frames_count = match &output {
+            SampleType::F32(output) | SampleType::Both(output, _) => output.frames_count(),
+            SampleType::F64(output) => output.frames_count(),
+        } as usize;
+
+        for batch in events.input.batch() {
+            for event in batch.events() {
+                self.handle_event(event);
+            }
+
+            let start = batch.first_sample().min(frames_count);
+            let end = batch
+                .next_batch_first_sample()
+                .unwrap_or(frames_count)
+                .clamp(start, frames_count);
+
+            match &mut output {
+                SampleType::F32(output) | SampleType::Both(output, _) => {
+                    for (channel_index, channel) in output.iter_mut().enumerate() {
+                        self.effect.process_channel(
+                            channel_index,
+                            &mut channel[start..end],
+                            &self.params,
+                        );
+                    }
+                }
+                SampleType::F64(output) => {
+                    let buffer = self.conversion_buffer.get_mut(..end - start).ok_or(
+                        PluginError::Message("Block is larger than max_frames_count"),
+                    )?;
+
+                    for (channel_index, channel) in output.iter_mut().enumerate() {
+                        let channel = &mut channel[start..end];
+
+                        for (converted, sample) in buffer.iter_mut().zip(channel.iter()) {
+                            *converted = *sample as f32;
+                        }
+
+                        self.effect
+                            .process_channel(channel_index, buffer, &self.params);
+
+                        for (sample, converted) in channel.iter_mut().zip(buffer.iter()) {
+                            *sample = f64::from(*converted);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(ProcessStatus::ContinueIfNotQuiet)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.effect.reset()
+    }
+}
@@ -1,10 +1,11 @@
 use crate::extensions::wrapper::{handle_panic, PluginWrapper, PluginWrapperError};
 use crate::extensions::PluginExtensions;
 use crate::host::{HostInfo, HostMainThreadHandle, HostSharedHandle};
+use crate::internal_utils::guard_allocations;
 use crate::plugin::instance::WrapperData::*;
 use crate::plugin::{Plugin, PluginAudioProcessor, PluginError, PluginMainThread};
 use crate::prelude::PluginDescriptor;
-use crate::process::{Audio, Events, PluginAudioConfiguration, Process};
+use crate::process::{Audio, PluginAudioConfiguration, Process};
 use clap_sys::plugin::{clap_plugin, clap_plugin_descriptor};
 use clap_sys::process::{clap_process, clap_process_status, CLAP_PROCESS_ERROR};
 use core::ffi::c_void;
@@ -12,6 +13,7 @@ use std::cell::UnsafeCell;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicU8, Ordering};
 
 pub(crate) trait PluginInitializer<'a, P: Plugin>: 'a {
@@ -187,20 +189,26 @@ impl<'a, P: Plugin> PluginBoxInner<'a, P> {
                 unreachable!()
             };
 
-            let init_result = initializer.init(data.host.as_main_thread_unchecked());
+            // Catch panics here, so that the instance doesn't stay stuck in the INITIALIZING state.
+            let host = data.host.as_main_thread_unchecked();
+            let init_result = handle_panic(AssertUnwindSafe(|| initializer.init(host)));
 
             match init_result {
-                Ok(wrapper) => {
+                Ok(Ok(wrapper)) => {
                     // We now guaranteed that the current state is INITIALIZING, so there is nothing to drop.
                     data.plugin_data.get().write(Initialized(wrapper));
                     // The write operation completed, we can now inform other threads that initialization is complete.
                     data.state.store(INITIALIZED, Ordering::Release);
                     Ok(())
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     data.state.store(INITIALIZATION_FAILED, Ordering::Release);
                     Err(e.into())
                 }
+                Err(_) => {
+                    data.state.store(INITIALIZATION_FAILED, Ordering::Release);
+                    Err(PluginWrapperError::Panic)
+                }
             }
         })
         .is_some()
@@ -224,7 +232,9 @@ impl<'a, P: Plugin> PluginBoxInner<'a, P> {
                 Err(e) => return Err(e),
             };
 
-            if wrapper.is_active() {
+            // Poisoned audio processors are dropped along with the instance, without calling
+            // into the plugin's code again.
+            if wrapper.is_active() && !wrapper.is_poisoned() {
                 wrapper.deactivate()
             } else {
                 Ok(())
@@ -283,6 +293,10 @@ impl<'a, P: Plugin> PluginBoxInner<'a, P> {
     unsafe extern "C" fn reset(plugin: *const clap_plugin) {
         PluginWrapper::<P>::handle(plugin, |p| {
             p.audio_processor()?.as_mut().reset();
+
+            #[cfg(feature = "validate-events")]
+            p.reset_event_validator();
+
             Ok(())
         });
     }
@@ -310,11 +324,26 @@ impl<'a, P: Plugin> PluginBoxInner<'a, P> {
     ) -> clap_process_status {
         // SAFETY: process ptr is never accessed later, and is guaranteed to be valid and unique by the host
         PluginWrapper::<P>::handle(plugin, |p| {
-            Ok(p.audio_processor()?.as_mut().process(
-                Process::from_raw(&*process),
-                Audio::from_raw(&*process),
-                Events::from_raw(&*process),
-            )?)
+            let processor = p.audio_processor()?.as_mut();
+            let process = &*process;
+
+            #[cfg(feature = "validate-events")]
+            let status = p.validate_output_events(process, |events| {
+                guard_allocations(|| {
+                    processor.process(Process::from_raw(process), Audio::from_raw(process), events)
+                })
+            });
+
+            #[cfg(not(feature = "validate-events"))]
+            let status = guard_allocations(|| {
+                processor.process(
+                    Process::from_raw(process),
+                    Audio::from_raw(process),
+                    crate::process::Events::from_raw(process),
+                )
+            });
+
+            Ok(status?)
         })
         .map(|s| s as clap_process_status)
         .unwrap_or(CLAP_PROCESS_ERROR)
@@ -24,6 +24,13 @@ unsafe fn get_logger<P: Plugin>(
         .host()
         .as_raw();
 
+    get_host_logger(host)
+}
+
+/// # Safety
+///
+/// Host pointer must be valid.
+unsafe fn get_host_logger(host: &clap_host) -> Option<(*const clap_host, ClapLoggingFn)> {
     let log = host.get_extension?(host, CLAP_EXT_LOG.as_ptr()) as *mut clap_host_log;
     Some((host, log.as_ref()?.log?))
 }
@@ -39,7 +46,26 @@ fn log_display<D: Display>(message: &D) -> Result<CString, Box<dyn Error>> {
 /// Plugin pointer must be non-dangling (but can be NULL).
 /// It *must* point to a plugin instance created by Clack.
 pub unsafe fn plugin_log<P: Plugin>(plugin: *const clap_plugin, e: &PluginWrapperError) {
-    if let Some((host, logger)) = get_logger::<P>(plugin) {
+    log_with(get_logger::<P>(plugin), e)
+}
+
+/// # Safety
+///
+/// Host pointer must be valid.
+#[cfg(any(
+    feature = "thread-checks",
+    feature = "validate-events",
+    debug_assertions
+))]
+pub unsafe fn host_log(host: &clap_host, e: &PluginWrapperError) {
+    log_with(get_host_logger(host), e)
+}
+
+/// # Safety
+///
+/// The logger's host pointer must be valid, and must match the logging function.
+unsafe fn log_with(logger: Option<(*const clap_host, ClapLoggingFn)>, e: &PluginWrapperError) {
+    if let Some((host, logger)) = logger {
         match log_display(e) {
             Ok(cstr) => {
                 logger(host, e.severity(), cstr.as_ptr());
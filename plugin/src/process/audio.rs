@@ -207,4 +207,54 @@ pub mod tests {
 
         assert_eq!(ins, outs);
     }
+
+    #[test]
+    fn can_copy_mismatched_pairs() {
+        let mut ins = [[0.5f32; 4]; 2];
+        let mut outs = [[0f64; 4]; 2];
+
+        let mut input_ports = AudioPorts::with_capacity(2, 1);
+        let mut output_ports = AudioPorts::with_capacity(2, 1);
+
+        let input_buffers = input_ports.with_input_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_input_only(
+                ins.iter_mut().map(InputChannel::variable),
+            ),
+        }]);
+
+        let output_buffers = output_ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f64_output_only(
+                outs.iter_mut().map(|b| b.as_mut_slice()),
+            ),
+        }]);
+
+        let mut audio = Audio {
+            inputs: input_buffers.as_raw_buffers(),
+            frames_count: input_buffers.min_available_frames_with(&output_buffers),
+            outputs: output_buffers.into_raw_buffers(),
+        };
+
+        let mut port = audio.port_pair(0).unwrap();
+        assert_eq!(
+            port.sample_types(),
+            Ok((SampleType::F32(()), SampleType::F64(())))
+        );
+        assert_eq!(port.is_mismatched(), Ok(true));
+        assert_eq!(
+            port.channels().err(),
+            Some(BufferError::MismatchedBufferPair)
+        );
+
+        let channels = port.mixed_channels().unwrap();
+        assert!(channels.is_mismatched());
+
+        let mut output = channels.copy_to_output().into_f64().unwrap();
+        for channel in output.iter_mut() {
+            channel.iter_mut().for_each(|s| *s *= 2.0);
+        }
+
+        assert_eq!(outs, [[1f64; 4]; 2]);
+    }
 }
@@ -114,6 +114,21 @@ impl<'a> InputPort<'a> {
         ))
     }
 
+    /// Returns which sample types the host provided buffers for in this input port, without
+    /// accessing the buffers themselves.
+    ///
+    /// This is useful to decide ahead of time which processing path to take, e.g. when the
+    /// host enabled 64-bit processing for some ports only.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`BufferError::InvalidChannelBuffer`] if the host provided neither
+    /// [`f32`] nor [`f64`] buffer type, which is invalid per the CLAP specification.
+    #[inline]
+    pub fn sample_type(&self) -> Result<SampleType<(), ()>, BufferError> {
+        SampleType::from_raw_buffer_kind(self.inner)
+    }
+
     /// Returns the number of frames to process in this block.
     ///
     /// This will always match the number of samples of every audio channel buffer.
@@ -147,8 +162,8 @@ impl<'a> InputPort<'a> {
 /// [`InputPort::channels`].
 #[derive(Copy, Clone)]
 pub struct InputChannels<'a, S> {
-    frames_count: u32,
-    data: &'a [*mut S],
+    pub(crate) frames_count: u32,
+    pub(crate) data: &'a [*mut S],
 }
 
 impl<'a, S> InputChannels<'a, S> {
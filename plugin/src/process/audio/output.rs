@@ -1,7 +1,7 @@
 use crate::internal_utils::{slice_from_external_parts, slice_from_external_parts_mut};
 use crate::prelude::Audio;
 use crate::process::audio::{BufferError, SampleType};
-use crate::process::InputChannelsIter;
+use crate::process::{InputChannels, InputChannelsIter};
 use clack_common::process::ConstantMask;
 use clap_sys::audio_buffer::clap_audio_buffer;
 use std::slice::IterMut;
@@ -114,6 +114,21 @@ impl<'a> OutputPort<'a> {
         ))
     }
 
+    /// Returns which sample types the host provided buffers for in this output port, without
+    /// accessing the buffers themselves.
+    ///
+    /// This is useful to decide ahead of time which processing path to take, e.g. when the
+    /// host enabled 64-bit processing for some ports only.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`BufferError::InvalidChannelBuffer`] if the host provided neither
+    /// [`f32`] nor [`f64`] buffer type, which is invalid per the CLAP specification.
+    #[inline]
+    pub fn sample_type(&self) -> Result<SampleType<(), ()>, BufferError> {
+        SampleType::from_raw_buffer_kind(self.inner)
+    }
+
     /// Returns the number of frames to process in this block.
     ///
     /// This will always match the number of samples of every audio channel buffer.
@@ -221,6 +236,54 @@ impl<S> OutputChannels<'_, S> {
         }
     }
 
+    /// Copies the samples of the given input channels into these output channels, converting
+    /// each sample using the given `convert` function.
+    ///
+    /// This is mainly useful as a fallback when the host provided input and output buffers of
+    /// different sample types (e.g. [`f32`] inputs and [`f64`] outputs): the input can be copied to
+    /// the output first, and then processed in-place in the output's sample type.
+    ///
+    /// Channels are copied pairwise by index. If one side has more channels than the other, the
+    /// extra output channels are left untouched. Channels that share the same buffer as their
+    /// matching output channel (i.e. when the host expects in-place processing) are also left
+    /// untouched, as their data is already in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_plugin::process::audio::{InputChannels, OutputChannels};
+    ///
+    /// fn copy_f32_to_f64(input: &InputChannels<f32>, output: &mut OutputChannels<f64>) {
+    ///     output.copy_from(input, f64::from);
+    /// }
+    /// ```
+    pub fn copy_from<I: Copy>(
+        &mut self,
+        input: &InputChannels<I>,
+        mut convert: impl FnMut(I) -> S,
+    ) {
+        let frames_count = self.frames_count.min(input.frames_count) as usize;
+
+        for (input, output) in input.data.iter().zip(self.data.iter()) {
+            if *input as *const u8 == *output as *const u8 {
+                continue;
+            }
+
+            // SAFETY: both types ensure the buffers are valid and at least frames_count-long. We
+            // also just checked the two buffers aren't the same.
+            let (input, output) = unsafe {
+                (
+                    slice_from_external_parts(*input as *const I, frames_count),
+                    slice_from_external_parts_mut(*output, frames_count),
+                )
+            };
+
+            for (output, input) in output.iter_mut().zip(input) {
+                *output = convert(*input);
+            }
+        }
+    }
+
     /// Divides the output channels into two at an index.
     ///
     /// The first will contain all channels with indices from `[0, mid)` (excluding
@@ -1,6 +1,8 @@
 use crate::internal_utils::{slice_from_external_parts, slice_from_external_parts_mut};
 use crate::process::audio::pair::ChannelPair::*;
-use crate::process::audio::{BufferError, InputPort, OutputPort, SampleType};
+use crate::process::audio::{
+    BufferError, InputChannels, InputPort, OutputChannels, OutputPort, SampleType,
+};
 use crate::process::Audio;
 use clack_common::process::{AudioPortProcessingInfo, ConstantMask};
 use clap_sys::audio_buffer::clap_audio_buffer;
@@ -146,6 +148,131 @@ impl<'a> PortPair<'a> {
         ))
     }
 
+    /// Returns which sample types the host provided for each port of this pair, as a tuple of
+    /// the input and output port's sample types, respectively.
+    ///
+    /// If one port isn't present in this pair, then [`SampleType::Both`] is returned for it, as
+    /// it can be matched with any sample type.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`BufferError::InvalidChannelBuffer`] if the host provided neither
+    /// [`f32`] nor [`f64`] buffer type for any of the ports, which is invalid per the CLAP
+    /// specification.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn sample_types(&self) -> Result<(SampleType<(), ()>, SampleType<(), ()>), BufferError> {
+        let input = match self.input {
+            None => SampleType::Both((), ()),
+            Some(buffer) => SampleType::from_raw_buffer_kind(buffer)?,
+        };
+
+        let output = match self.output.as_ref() {
+            None => SampleType::Both((), ()),
+            Some(buffer) => SampleType::from_raw_buffer_kind(buffer)?,
+        };
+
+        Ok((input, output))
+    }
+
+    /// Returns `true` if the two ports of this pair hold different sample types (i.e. one holds
+    /// [`f32`] and the other holds [`f64`]), in which case [`channels`](Self::channels) would
+    /// return a [`BufferError::MismatchedBufferPair`] error.
+    ///
+    /// See [`mixed_channels`](Self::mixed_channels) to access mismatched pairs.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`BufferError::InvalidChannelBuffer`] if the host provided neither
+    /// [`f32`] nor [`f64`] buffer type for any of the ports.
+    #[inline]
+    pub fn is_mismatched(&self) -> Result<bool, BufferError> {
+        let (input, output) = self.sample_types()?;
+        Ok(input.try_match_with(output).is_err())
+    }
+
+    /// Retrieves the port pair's channels, even if the two ports hold different sample types.
+    ///
+    /// Unlike [`channels`](Self::channels), this doesn't fail if one port holds [`f32`] buffers
+    /// and the other holds [`f64`] buffers. Instead, it returns the input and output channels
+    /// separately. See [`MixedPairedChannels`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method returns a [`BufferError::InvalidChannelBuffer`] if the host provided neither
+    /// [`f32`] nor [`f64`] buffer type, which is invalid per the CLAP specification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_plugin::process::audio::{MixedPairedChannels, PortPair, SampleType};
+    ///
+    /// # fn foo(mut port: PortPair) {
+    /// let mut port: PortPair = /* ... */
+    /// # port;
+    ///
+    /// // Copy the input to the output, converting samples if needed, and process in-place.
+    /// match port.mixed_channels().unwrap().copy_to_output() {
+    ///     SampleType::F32(buf) => { /* Process the 32-bit buffers in-place */ },
+    ///     SampleType::F64(buf) => { /* Process the 64-bit buffers in-place */ },
+    ///     SampleType::Both(buf32, buf64) => { /* We have both types of buffers available */ }
+    /// }
+    /// # }
+    /// ```
+    pub fn mixed_channels(&mut self) -> Result<MixedPairedChannels<'a>, BufferError> {
+        let input = match self.input {
+            None => SampleType::Both([].as_slice(), [].as_slice()),
+            // SAFETY: this type ensures the buffer is valid
+            Some(buffer) => unsafe { SampleType::from_raw_buffer(buffer)? },
+        };
+
+        let output = match self.output.as_mut() {
+            None => SampleType::Both([].as_mut_slice(), [].as_mut_slice()),
+            // SAFETY: this type ensures the buffer is valid
+            Some(buffer) => unsafe { SampleType::from_raw_buffer_mut(buffer)? },
+        };
+
+        let frames_count = self.frames_count;
+
+        Ok(match (input, output) {
+            (SampleType::F32(input), SampleType::F64(output)) => MixedPairedChannels::F32ToF64 {
+                input: InputChannels {
+                    data: input,
+                    frames_count,
+                },
+                output: OutputChannels {
+                    data: output,
+                    frames_count,
+                },
+            },
+            (SampleType::F64(input), SampleType::F32(output)) => MixedPairedChannels::F64ToF32 {
+                input: InputChannels {
+                    data: input,
+                    frames_count,
+                },
+                output: OutputChannels {
+                    data: output,
+                    frames_count,
+                },
+            },
+            (input, output) => MixedPairedChannels::Matched(
+                // All mismatched combinations were handled above, so this cannot fail
+                input.try_match_with(output)?.map(
+                    |(i, o)| PairedChannels {
+                        input_data: i,
+                        output_data: o,
+                        frames_count,
+                    },
+                    |(i, o)| PairedChannels {
+                        input_data: i,
+                        output_data: o,
+                        frames_count,
+                    },
+                ),
+            ),
+        })
+    }
+
     /// The number of channels in this port pair.
     ///
     /// Since there may be more channels in one port than in the other, this method also counts
@@ -278,6 +405,110 @@ impl<'a, S> PairedChannels<'a, S> {
     }
 }
 
+impl<'a, S> PairedChannels<'a, S> {
+    /// Splits these paired channels into separate input and output channels.
+    #[inline]
+    pub fn into_input_output(self) -> (InputChannels<'a, S>, OutputChannels<'a, S>) {
+        (
+            InputChannels {
+                data: self.input_data,
+                frames_count: self.frames_count,
+            },
+            OutputChannels {
+                data: self.output_data,
+                frames_count: self.frames_count,
+            },
+        )
+    }
+}
+
+impl<S: Copy> PairedChannels<'_, S> {
+    /// Copies the samples of all the input channels into their matching output channels.
+    ///
+    /// See [`OutputChannels::copy_from`] for more information.
+    #[inline]
+    pub fn copy_input_to_output(&mut self) {
+        let input = InputChannels {
+            data: self.input_data,
+            frames_count: self.frames_count,
+        };
+
+        let mut output = OutputChannels {
+            data: &mut *self.output_data,
+            frames_count: self.frames_count,
+        };
+
+        output.copy_from(&input, |s| s);
+    }
+}
+
+/// A [`PortPair`]'s channels, which may hold different sample types for the input and the output.
+///
+/// This is returned by [`PortPair::mixed_channels`]. Hosts are allowed to only enable 64-bit
+/// processing on some ports, which means an input port and its matching output port may not hold
+/// the same sample type.
+///
+/// The [`copy_to_output`](Self::copy_to_output) method provides a simple fallback to handle all
+/// cases: it copies (and converts, if needed) all inputs to the outputs, which the plugin can then
+/// process in-place.
+pub enum MixedPairedChannels<'a> {
+    /// The input and output ports hold the same sample type.
+    Matched(SampleType<PairedChannels<'a, f32>, PairedChannels<'a, f64>>),
+    /// The input port holds [`f32`] samples, while the output port holds [`f64`] samples.
+    F32ToF64 {
+        /// The input channels.
+        input: InputChannels<'a, f32>,
+        /// The output channels.
+        output: OutputChannels<'a, f64>,
+    },
+    /// The input port holds [`f64`] samples, while the output port holds [`f32`] samples.
+    F64ToF32 {
+        /// The input channels.
+        input: InputChannels<'a, f64>,
+        /// The output channels.
+        output: OutputChannels<'a, f32>,
+    },
+}
+
+impl<'a> MixedPairedChannels<'a> {
+    /// Returns `true` if the input and output ports hold different sample types.
+    #[inline]
+    pub fn is_mismatched(&self) -> bool {
+        !matches!(self, MixedPairedChannels::Matched(_))
+    }
+
+    /// Copies all the input channels into the output channels, converting samples to the output's
+    /// sample type if needed, and then returns the output channels.
+    ///
+    /// This allows plugins to always process audio in-place in the output's sample type,
+    /// regardless of the sample type of the input.
+    ///
+    /// Converting [`f64`] samples to [`f32`] samples loses precision.
+    /// See [`OutputChannels::copy_from`] for more information about how channels are copied.
+    pub fn copy_to_output(self) -> SampleType<OutputChannels<'a, f32>, OutputChannels<'a, f64>> {
+        match self {
+            MixedPairedChannels::Matched(channels) => channels.map(
+                |mut c| {
+                    c.copy_input_to_output();
+                    c.into_input_output().1
+                },
+                |mut c| {
+                    c.copy_input_to_output();
+                    c.into_input_output().1
+                },
+            ),
+            MixedPairedChannels::F32ToF64 { input, mut output } => {
+                output.copy_from(&input, f64::from);
+                SampleType::F64(output)
+            }
+            MixedPairedChannels::F64ToF32 { input, mut output } => {
+                output.copy_from(&input, |s| s as f32);
+                SampleType::F32(output)
+            }
+        }
+    }
+}
+
 impl<'a, S> IntoIterator for PairedChannels<'a, S> {
     type Item = ChannelPair<'a, S>;
     type IntoIter = PairedChannelsIter<'a, S>;
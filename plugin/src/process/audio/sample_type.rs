@@ -268,6 +268,21 @@ impl<F32, F64> SampleType<F32, F64> {
     }
 }
 
+impl SampleType<(), ()> {
+    /// Detects which sample types are available in the given raw buffer, without creating any
+    /// channel slices.
+    #[inline]
+    pub(crate) fn from_raw_buffer_kind(raw: &clap_audio_buffer) -> Result<Self, BufferError> {
+        match (raw.data32.is_null(), raw.data64.is_null()) {
+            (true, true) if raw.channel_count == 0 => Ok(SampleType::Both((), ())),
+            (true, true) => Err(BufferError::InvalidChannelBuffer),
+            (false, true) => Ok(SampleType::F32(())),
+            (true, false) => Ok(SampleType::F64(())),
+            (false, false) => Ok(SampleType::Both((), ())),
+        }
+    }
+}
+
 impl<'a> SampleType<&'a [*mut f32], &'a [*mut f64]> {
     /// # Safety
     ///
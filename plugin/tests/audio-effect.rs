@@ -0,0 +1,273 @@
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::prelude::*;
+use clack_host::utils::Cookie;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+
+const VOLUME: ClapId = ClapId::new(0);
+
+struct GainPlugin;
+
+impl Plugin for GainPlugin {
+    type AudioProcessor<'a> = AudioEffectProcessor<Gain>;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+}
+
+impl DefaultPluginFactory for GainPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(())
+    }
+}
+
+struct Gain;
+
+#[derive(Copy, Clone)]
+struct GainParams {
+    volume: f32,
+}
+
+impl Default for GainParams {
+    fn default() -> Self {
+        Self { volume: 1.0 }
+    }
+}
+
+impl AudioEffect for Gain {
+    type Params = GainParams;
+    type MainThread<'a> = ();
+
+    fn activate(_audio_config: PluginAudioConfiguration) -> Result<Self, PluginError> {
+        Ok(Gain)
+    }
+
+    fn set_param(params: &mut GainParams, param_id: ClapId, value: f64) {
+        if param_id == VOLUME {
+            params.volume = value as f32;
+        }
+    }
+
+    fn process_channel(&mut self, channel_index: usize, buffer: &mut [f32], params: &GainParams) {
+        let offset = channel_index as f32;
+        for sample in buffer {
+            *sample = *sample * params.volume + offset;
+        }
+    }
+}
+
+static GAIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<GainPlugin>);
+
+struct SavedGainPlugin;
+
+impl Plugin for SavedGainPlugin {
+    type AudioProcessor<'a> = AudioEffectProcessor<SavedGain>;
+    type Shared<'a> = ();
+    type MainThread<'a> = SavedGainMainThread;
+}
+
+impl DefaultPluginFactory for SavedGainPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.saved.plugin", "My saved plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        _host: HostMainThreadHandle<'a>,
+        _shared: &'a Self::Shared<'a>,
+    ) -> Result<Self::MainThread<'a>, PluginError> {
+        Ok(SavedGainMainThread { volume: 0.25 })
+    }
+}
+
+/// A main thread holding the volume, e.g. after loading a saved state.
+struct SavedGainMainThread {
+    volume: f32,
+}
+
+impl PluginMainThread<'_, ()> for SavedGainMainThread {}
+
+struct SavedGain;
+
+impl AudioEffect for SavedGain {
+    type Params = GainParams;
+    type MainThread<'a> = SavedGainMainThread;
+
+    fn initial_params(main_thread: &SavedGainMainThread) -> GainParams {
+        GainParams {
+            volume: main_thread.volume,
+        }
+    }
+
+    fn activate(_audio_config: PluginAudioConfiguration) -> Result<Self, PluginError> {
+        Ok(SavedGain)
+    }
+
+    fn set_param(params: &mut GainParams, param_id: ClapId, value: f64) {
+        Gain::set_param(params, param_id, value)
+    }
+
+    fn process_channel(&mut self, _channel_index: usize, buffer: &mut [f32], params: &GainParams) {
+        for sample in buffer {
+            *sample *= params.volume;
+        }
+    }
+}
+
+static SAVED_GAIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<SavedGainPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+}
+
+struct MyHostShared;
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+#[test]
+fn processes_channels_with_sample_accurate_params() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&GAIN_ENTRY, "/gain") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut input_ports = AudioPorts::with_capacity(2, 1);
+    let mut output_ports = AudioPorts::with_capacity(2, 1);
+    let mut ins = [[1f32; 4]; 2];
+    let mut outs = [[0f32; 4]; 2];
+
+    let inputs = input_ports.with_input_buffers([AudioPortBuffer {
+        latency: 0,
+        channels: AudioPortBufferType::f32_input_only(ins.iter_mut().map(InputChannel::variable)),
+    }]);
+
+    let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+        latency: 0,
+        channels: AudioPortBufferType::f32_output_only(outs.iter_mut().map(|b| b.as_mut_slice())),
+    }]);
+
+    let events = [ParamValueEvent::new(
+        2,
+        VOLUME,
+        Pckn::match_all(),
+        0.5,
+        Cookie::empty(),
+    )];
+
+    processor
+        .process(
+            &inputs,
+            &mut outputs,
+            &InputEvents::from_buffer(&events),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(outs, [[1.0, 1.0, 0.5, 0.5], [2.0, 2.0, 1.5, 1.5]]);
+}
+
+#[test]
+fn processes_f64_buffers_with_initial_params() {
+    let bundle = unsafe { PluginBundle::load_from_raw(&SAVED_GAIN_ENTRY, "/saved-gain") }.unwrap();
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared,
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.saved.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut input_ports = AudioPorts::with_capacity(1, 1);
+    let mut output_ports = AudioPorts::with_capacity(1, 1);
+    let mut ins = [[1f64; 4]; 1];
+    let mut outs = [[0f64; 4]; 1];
+
+    let inputs = input_ports.with_input_buffers([AudioPortBuffer {
+        latency: 0,
+        channels: AudioPortBufferType::f64_input_only(ins.iter_mut().map(InputChannel::variable)),
+    }]);
+
+    let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+        latency: 0,
+        channels: AudioPortBufferType::f64_output_only(outs.iter_mut().map(|b| b.as_mut_slice())),
+    }]);
+
+    let events = [ParamValueEvent::new(
+        3,
+        VOLUME,
+        Pckn::match_all(),
+        0.5,
+        Cookie::empty(),
+    )];
+
+    processor
+        .process(
+            &inputs,
+            &mut outputs,
+            &InputEvents::from_buffer(&events),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(outs, [[0.25, 0.25, 0.25, 0.5]]);
+}
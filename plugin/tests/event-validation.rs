@@ -0,0 +1,173 @@
+#![cfg(feature = "validate-events")]
+
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_host::events::event_types::{NoteEndEvent, NoteOffEvent, NoteOnEvent};
+use clack_host::events::spaces::CoreEventSpace;
+use clack_host::events::Pckn;
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = MyPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+struct MyPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), MyPluginMainThread> for MyPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut MyPluginMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        // Ends every note it hears about, even the ones that were never started.
+        for event in events.input {
+            let pckn = match event.as_core_event() {
+                Some(CoreEventSpace::NoteOn(e)) => e.pckn(),
+                Some(CoreEventSpace::NoteOff(e)) => e.pckn(),
+                _ => continue,
+            };
+
+            let _ = events.output.try_push(NoteEndEvent::new(0, pckn));
+        }
+
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostLog>();
+    }
+}
+
+struct MyHostShared {
+    errors: Mutex<Vec<String>>,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if severity == LogSeverity::PluginMisbehaving {
+            self.errors.lock().unwrap().push(message.to_owned());
+        }
+    }
+}
+
+#[test]
+fn rejects_notes_ended_without_being_started() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            errors: Mutex::new(Vec::new()),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut output_ports = AudioPorts::with_capacity(1, 1);
+    let mut output_buffer = [0f32; 4];
+    let mut output_events = EventBuffer::new();
+
+    let mut process = |input_events: &InputEvents, output_events: &mut EventBuffer| {
+        let mut outputs = output_ports.with_output_buffers([AudioPortBuffer {
+            latency: 0,
+            channels: AudioPortBufferType::f32_output_only([output_buffer.as_mut_slice()]),
+        }]);
+
+        processor.process(
+            &InputAudioBuffers::empty(),
+            &mut outputs,
+            input_events,
+            &mut output_events.as_output(),
+            None,
+            None,
+        )
+    };
+
+    let note = Pckn::new(0u16, 0u16, 60u16, 1u32);
+    let note_on = [NoteOnEvent::new(0, note, 1.0)];
+    assert!(process(&InputEvents::from_buffer(&note_on), &mut output_events).is_ok());
+    assert_eq!(output_events.len(), 1);
+
+    // That note has already ended.
+    output_events.clear();
+    let note_off = [NoteOffEvent::new(0, note, 1.0)];
+    assert!(process(&InputEvents::from_buffer(&note_off), &mut output_events).is_err());
+    assert!(output_events.is_empty());
+
+    instance.access_shared_handler(|h| {
+        let errors = h.errors.lock().unwrap();
+        assert!(errors
+            .first()
+            .unwrap()
+            .contains("Event #0 ends a note that was never started by the host"));
+    });
+}
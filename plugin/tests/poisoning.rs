@@ -0,0 +1,174 @@
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+
+static PROCESS_CALLS: AtomicU32 = AtomicU32::new(0);
+static MAIN_THREAD_CALLS: AtomicU32 = AtomicU32::new(0);
+static DEACTIVATED: AtomicBool = AtomicBool::new(false);
+static DROPPED: AtomicBool = AtomicBool::new(false);
+
+struct PanickingPlugin;
+
+impl Plugin for PanickingPlugin {
+    type AudioProcessor<'a> = PanickingAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = PanickingMainThread;
+}
+
+struct PanickingMainThread;
+
+impl PluginMainThread<'_, ()> for PanickingMainThread {
+    fn on_main_thread(&mut self) {
+        MAIN_THREAD_CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl DefaultPluginFactory for PanickingPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("panicking.plugin", "Panicking plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<PanickingMainThread, PluginError> {
+        Ok(PanickingMainThread)
+    }
+}
+
+struct PanickingAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), PanickingMainThread> for PanickingAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut PanickingMainThread,
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        PROCESS_CALLS.fetch_add(1, Ordering::SeqCst);
+        panic!("Oops");
+    }
+
+    fn deactivate(self, _main_thread: &mut PanickingMainThread) {
+        DEACTIVATED.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for PanickingAudioProcessor {
+    fn drop(&mut self) {
+        DROPPED.store(true, Ordering::SeqCst);
+    }
+}
+
+static PANICKING_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<PanickingPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostLog>();
+    }
+}
+
+struct MyHostShared {
+    errors: Mutex<Vec<String>>,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if severity == LogSeverity::PluginMisbehaving {
+            self.errors.lock().unwrap().push(message.to_owned());
+        }
+    }
+}
+
+#[test]
+fn panics_poison_the_plugin_instance() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let bundle = unsafe { PluginBundle::load_from_raw(&PANICKING_ENTRY, "/panicking") }.unwrap();
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            errors: Mutex::new(Vec::new()),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"panicking.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.call_on_main_thread_callback();
+    assert_eq!(MAIN_THREAD_CALLS.load(Ordering::SeqCst), 1);
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 4,
+        max_frames_count: 4,
+    };
+
+    let mut processor = instance
+        .activate(|_, _| (), config)
+        .unwrap()
+        .start_processing()
+        .unwrap();
+
+    let mut process = || {
+        processor.process(
+            &InputAudioBuffers::empty_with_frames(4),
+            &mut OutputAudioBuffers::empty_with_frames(4),
+            &InputEvents::empty(),
+            &mut OutputEvents::void(),
+            None,
+            None,
+        )
+    };
+
+    // The panic is caught, and reported as a processing error.
+    assert!(process().is_err());
+    assert_eq!(PROCESS_CALLS.load(Ordering::SeqCst), 1);
+
+    // The instance is poisoned: further calls fail without reaching the plugin.
+    assert!(process().is_err());
+    assert_eq!(PROCESS_CALLS.load(Ordering::SeqCst), 1);
+
+    instance.call_on_main_thread_callback();
+    assert_eq!(MAIN_THREAD_CALLS.load(Ordering::SeqCst), 1);
+
+    instance.access_shared_handler(|h| {
+        assert_eq!(*h.errors.lock().unwrap(), ["Plugin panicked"]);
+    });
+
+    // The poisoned audio processor is dropped, but not deactivated.
+    drop(processor);
+    drop(instance);
+    assert!(DROPPED.load(Ordering::SeqCst));
+    assert!(!DEACTIVATED.load(Ordering::SeqCst));
+}
@@ -0,0 +1,115 @@
+#![cfg(all(debug_assertions, not(feature = "thread-checks")))]
+
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+static CALLBACKS: AtomicU32 = AtomicU32::new(0);
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {
+    fn on_main_thread(&mut self) {
+        CALLBACKS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostLog>();
+    }
+}
+
+struct MyHostShared {
+    errors: Mutex<Vec<String>>,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if severity == LogSeverity::HostMisbehaving {
+            self.errors.lock().unwrap().push(message.to_owned());
+        }
+    }
+}
+
+#[test]
+fn logs_main_thread_calls_from_other_threads_once() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+    let instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            errors: Mutex::new(Vec::new()),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let plugin = instance.plugin_shared_handle();
+    let on_main_thread = plugin.as_raw().on_main_thread.unwrap();
+
+    std::thread::scope(|s| {
+        // SAFETY: the plugin pointer is valid. Calling this from another thread is exactly the
+        // host misbehavior being tested here.
+        s.spawn(|| unsafe {
+            on_main_thread(plugin.as_raw_ptr());
+            on_main_thread(plugin.as_raw_ptr());
+        });
+    });
+
+    // Without the thread-checks feature, the calls still go through.
+    assert_eq!(CALLBACKS.load(Ordering::Relaxed), 2);
+    instance.access_shared_handler(|h| {
+        let errors = h.errors.lock().unwrap();
+        assert_eq!(
+            *errors,
+            ["Host called a main-thread plugin function from the wrong thread"]
+        );
+    });
+}
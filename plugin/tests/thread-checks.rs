@@ -0,0 +1,246 @@
+#![cfg(feature = "thread-checks")]
+
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_extensions::tail::{PluginTail, PluginTailImpl, TailLength};
+use clack_extensions::thread_check::{DefaultThreadCheck, HostThreadCheck, HostThreadCheckImpl};
+use clack_host::prelude::*;
+use clack_plugin::clack_entry;
+use clack_plugin::prelude::*;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+static CALLBACKS: AtomicU32 = AtomicU32::new(0);
+
+struct MyPlugin;
+
+impl Plugin for MyPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = MyPluginMainThread;
+}
+
+struct MyPluginMainThread;
+
+impl PluginMainThread<'_, ()> for MyPluginMainThread {
+    fn on_main_thread(&mut self) {
+        CALLBACKS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl DefaultPluginFactory for MyPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("my.plugin", "My plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(
+        _host: HostMainThreadHandle,
+        _shared: &(),
+    ) -> Result<MyPluginMainThread, PluginError> {
+        Ok(MyPluginMainThread)
+    }
+}
+
+static MY_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<MyPlugin>);
+
+struct MyHost;
+
+impl HostHandlers for MyHost {
+    type Shared<'a> = MyHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostLog>();
+    }
+}
+
+struct MyHostShared {
+    errors: Mutex<Vec<String>>,
+}
+
+impl SharedHandler<'_> for MyHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostLogImpl for MyHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        if severity == LogSeverity::HostMisbehaving {
+            self.errors.lock().unwrap().push(message.to_owned());
+        }
+    }
+}
+
+#[test]
+fn rejects_main_thread_calls_from_other_threads() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let bundle = unsafe { PluginBundle::load_from_raw(&MY_PLUGIN_ENTRY, "/my/plugin") }.unwrap();
+    let mut instance = PluginInstance::<MyHost>::new(
+        |_| MyHostShared {
+            errors: Mutex::new(Vec::new()),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"my.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    instance.call_on_main_thread_callback();
+    assert_eq!(CALLBACKS.load(Ordering::Relaxed), 1);
+
+    let plugin = instance.plugin_shared_handle();
+    let on_main_thread = plugin.as_raw().on_main_thread.unwrap();
+
+    std::thread::scope(|s| {
+        // SAFETY: the plugin pointer is valid. Calling this from another thread is exactly the
+        // host misbehavior being tested here.
+        s.spawn(|| unsafe { on_main_thread(plugin.as_raw_ptr()) });
+    });
+
+    assert_eq!(CALLBACKS.load(Ordering::Relaxed), 1);
+    instance.access_shared_handler(|h| {
+        let errors = h.errors.lock().unwrap();
+        assert_eq!(
+            errors.first().map(String::as_str),
+            Some("Host called a main-thread plugin function from the wrong thread")
+        );
+    });
+
+    // Wrong-thread calls are rejected, but do not poison the instance.
+    instance.call_on_main_thread_callback();
+    assert_eq!(CALLBACKS.load(Ordering::Relaxed), 2);
+}
+
+struct TailPlugin;
+
+impl Plugin for TailPlugin {
+    type AudioProcessor<'a> = TailPluginAudioProcessor;
+    type Shared<'a> = ();
+    type MainThread<'a> = ();
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginTail>();
+    }
+}
+
+struct TailPluginAudioProcessor;
+
+impl<'a> PluginAudioProcessor<'a, (), ()> for TailPluginAudioProcessor {
+    fn activate(
+        _host: HostAudioProcessorHandle<'a>,
+        _main_thread: &mut (),
+        _shared: &'a (),
+        _audio_config: PluginAudioConfiguration,
+    ) -> Result<Self, PluginError> {
+        Ok(Self)
+    }
+
+    fn process(
+        &mut self,
+        _process: Process,
+        _audio: Audio,
+        _events: Events,
+    ) -> Result<ProcessStatus, PluginError> {
+        Ok(ProcessStatus::Continue)
+    }
+}
+
+impl PluginTailImpl for TailPluginAudioProcessor {
+    fn get(&self) -> TailLength {
+        TailLength::Finite(64)
+    }
+}
+
+impl DefaultPluginFactory for TailPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("tail.plugin", "Tail plugin")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<Self::Shared<'_>, PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread(_host: HostMainThreadHandle, _shared: &()) -> Result<(), PluginError> {
+        Ok(())
+    }
+}
+
+static TAIL_PLUGIN_ENTRY: EntryDescriptor = clack_entry!(SinglePluginEntry<TailPlugin>);
+
+struct ThreadCheckingHost;
+
+impl HostHandlers for ThreadCheckingHost {
+    type Shared<'a> = ThreadCheckingHostShared;
+    type MainThread<'a> = ();
+    type AudioProcessor<'a> = ();
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, _shared: &Self::Shared<'_>) {
+        builder.register::<HostThreadCheck>();
+    }
+}
+
+struct ThreadCheckingHostShared {
+    thread_check: DefaultThreadCheck,
+}
+
+impl SharedHandler<'_> for ThreadCheckingHostShared {
+    fn request_restart(&self) {}
+    fn request_process(&self) {}
+    fn request_callback(&self) {}
+}
+
+impl HostThreadCheckImpl for ThreadCheckingHostShared {
+    fn is_main_thread(&self) -> bool {
+        self.thread_check.is_main_thread()
+    }
+
+    fn is_audio_thread(&self) -> bool {
+        self.thread_check.is_audio_thread()
+    }
+}
+
+#[test]
+fn accepts_tail_queries_from_main_and_audio_threads() {
+    let host = HostInfo::new("host", "host", "host", "1.0").unwrap();
+
+    let bundle =
+        unsafe { PluginBundle::load_from_raw(&TAIL_PLUGIN_ENTRY, "/tail/plugin") }.unwrap();
+    let mut instance = PluginInstance::<ThreadCheckingHost>::new(
+        |_| ThreadCheckingHostShared {
+            thread_check: DefaultThreadCheck::new(),
+        },
+        |_| (),
+        &bundle,
+        CStr::from_bytes_with_nul(b"tail.plugin\0").unwrap(),
+        &host,
+    )
+    .unwrap();
+
+    let config = PluginAudioConfiguration {
+        sample_rate: 44_100.0,
+        min_frames_count: 1,
+        max_frames_count: 32,
+    };
+    let mut processor = instance.activate(|_, _| (), config).unwrap();
+
+    let tail = instance
+        .plugin_handle()
+        .get_extension::<PluginTail>()
+        .unwrap();
+
+    assert_eq!(
+        tail.get_on_main_thread(&mut instance.plugin_handle()),
+        TailLength::Finite(64)
+    );
+    assert_eq!(tail.get(&processor.plugin_handle()), TailLength::Finite(64));
+
+    instance.deactivate(processor);
+}
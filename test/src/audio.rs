@@ -0,0 +1,178 @@
+use clack_host::prelude::*;
+
+/// A set of owned audio buffers, split into ports and channels, to be fed to or filled by a plugin.
+///
+/// All channels of all ports always have the same number of frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioFixture {
+    ports: Vec<Vec<Vec<f32>>>,
+    frames_count: usize,
+}
+
+impl AudioFixture {
+    /// Creates a new fixture filled with silence, with the given number of channels for each
+    /// port, and the given number of frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clack_test::AudioFixture;
+    ///
+    /// // A single stereo port, with 256 frames.
+    /// let fixture = AudioFixture::silence(&[2], 256);
+    ///
+    /// assert_eq!(fixture.port_count(), 1);
+    /// assert_eq!(fixture.channel(0, 1), &[0.0; 256]);
+    /// ```
+    pub fn silence(channel_counts: &[usize], frames_count: usize) -> Self {
+        Self {
+            ports: channel_counts
+                .iter()
+                .map(|&channels| vec![vec![0.0; frames_count]; channels])
+                .collect(),
+            frames_count,
+        }
+    }
+
+    /// Creates a new fixture from the given buffers, indexed by port, then by channel.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if all the given channels do not have the same length.
+    pub fn from_ports(ports: Vec<Vec<Vec<f32>>>) -> Self {
+        let frames_count = ports.iter().flatten().next().map(|c| c.len()).unwrap_or(0);
+
+        assert!(
+            ports.iter().flatten().all(|c| c.len() == frames_count),
+            "All channels of an AudioFixture must have the same length"
+        );
+
+        Self {
+            ports,
+            frames_count,
+        }
+    }
+
+    /// Creates a new fixture with no ports, covering the given number of frames.
+    ///
+    /// This is useful to process plugins that have no audio ports, such as note effects.
+    #[inline]
+    pub fn empty(frames_count: usize) -> Self {
+        Self {
+            ports: Vec::new(),
+            frames_count,
+        }
+    }
+
+    /// Returns the number of frames of every channel in this fixture.
+    #[inline]
+    pub fn frames_count(&self) -> usize {
+        self.frames_count
+    }
+
+    /// Returns the number of ports in this fixture.
+    #[inline]
+    pub fn port_count(&self) -> usize {
+        self.ports.len()
+    }
+
+    /// Returns the number of channels of the port at the given index.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there is no port at the given index.
+    #[inline]
+    pub fn channel_count(&self, port_index: usize) -> usize {
+        self.ports[port_index].len()
+    }
+
+    /// Returns the buffer of a given channel.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there is no such port or channel.
+    #[inline]
+    pub fn channel(&self, port_index: usize, channel_index: usize) -> &[f32] {
+        &self.ports[port_index][channel_index]
+    }
+
+    /// Returns the buffer of a given channel, mutably.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there is no such port or channel.
+    #[inline]
+    pub fn channel_mut(&mut self, port_index: usize, channel_index: usize) -> &mut [f32] {
+        &mut self.ports[port_index][channel_index]
+    }
+
+    /// Returns all the buffers of this fixture, indexed by port, then by channel.
+    #[inline]
+    pub fn ports(&self) -> &[Vec<Vec<f32>>] {
+        &self.ports
+    }
+
+    /// Writes the given samples to every channel of every port, repeating them as needed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `samples` is empty while this fixture has any frames.
+    pub fn fill_with(&mut self, samples: &[f32]) {
+        for channel in self.ports.iter_mut().flatten() {
+            for (sample, value) in channel.iter_mut().zip(samples.iter().cycle()) {
+                *sample = *value;
+            }
+        }
+    }
+
+    pub(crate) fn as_input_buffers<'a>(
+        &'a mut self,
+        ports: &'a mut AudioPorts,
+        start: usize,
+        end: usize,
+    ) -> InputAudioBuffers<'a> {
+        if self.ports.is_empty() {
+            return empty_input_buffers((end - start) as u32);
+        }
+
+        ports.with_input_buffers(self.ports.iter_mut().map(|port| {
+            AudioPortBuffer {
+                channels: AudioPortBufferType::f32_input_only(
+                    port.iter_mut()
+                        .map(move |c| InputChannel::variable(&mut c[start..end])),
+                ),
+                latency: 0,
+            }
+        }))
+    }
+
+    pub(crate) fn as_output_buffers<'a>(
+        &'a mut self,
+        ports: &'a mut AudioPorts,
+        start: usize,
+        end: usize,
+    ) -> OutputAudioBuffers<'a> {
+        if self.ports.is_empty() {
+            return empty_output_buffers((end - start) as u32);
+        }
+
+        ports.with_output_buffers(self.ports.iter_mut().map(|port| AudioPortBuffer {
+            channels: AudioPortBufferType::f32_output_only(
+                port.iter_mut().map(move |c| &mut c[start..end]),
+            ),
+            latency: 0,
+        }))
+    }
+}
+
+/// Input buffers with no ports, but still covering a given number of frames.
+fn empty_input_buffers(frames_count: u32) -> InputAudioBuffers<'static> {
+    // SAFETY: there are no ports, so there are no buffers for frames_count to overflow.
+    unsafe { InputAudioBuffers::from_raw_buffers(&[], frames_count) }
+}
+
+/// Output buffers with no ports, but still covering a given number of frames.
+fn empty_output_buffers(frames_count: u32) -> OutputAudioBuffers<'static> {
+    // SAFETY: there are no ports, so there are no buffers for frames_count to overflow.
+    unsafe { OutputAudioBuffers::from_raw_buffers(&mut [], frames_count) }
+}
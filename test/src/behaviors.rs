@@ -0,0 +1,172 @@
+use clack_extensions::audio_ports::RescanType;
+use clack_extensions::gui::{GuiClosedReason, GuiSize};
+use clack_extensions::note_ports::{NoteDialects, NotePortRescanFlags};
+use clack_extensions::params::{ParamClearFlags, ParamRescanFlags};
+use clack_extensions::timer::TimerId;
+use clack_host::extensions::{Extension, HostExtensionSide};
+use clack_host::utils::ClapId;
+use std::ffi::{CStr, CString};
+
+/// Configures how the mock host reacts to the plugin, and which extensions it exposes.
+///
+/// This allows to exercise the code paths of a plugin that react to the host's capabilities, or to
+/// the host denying some of its requests.
+///
+/// By default, the mock host exposes all the extensions it supports, and accepts all requests.
+///
+/// # Example
+///
+/// ```
+/// use clack_extensions::gui::HostGui;
+/// use clack_host::extensions::Extension;
+/// use clack_test::HostBehaviors;
+///
+/// // A host that doesn't support GUIs, and refuses to register any timer.
+/// let behaviors = HostBehaviors::new()
+///     .without_extension::<HostGui>()
+///     .deny_timers();
+///
+/// assert!(!behaviors.is_extension_enabled(HostGui::IDENTIFIER));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HostBehaviors {
+    disabled_extensions: Vec<CString>,
+    accept_gui_requests: bool,
+    accept_timers: bool,
+    note_dialects: NoteDialects,
+    supported_rescan_flags: RescanType,
+}
+
+impl HostBehaviors {
+    /// Returns the default behaviors: all extensions are exposed, and all requests are accepted.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            disabled_extensions: Vec::new(),
+            accept_gui_requests: true,
+            accept_timers: true,
+            note_dialects: NoteDialects::all(),
+            supported_rescan_flags: RescanType::all(),
+        }
+    }
+
+    /// Stops the host from exposing the given extension to the plugin.
+    #[inline]
+    pub fn without_extension<E: Extension<ExtensionSide = HostExtensionSide>>(self) -> Self {
+        self.without_extension_id(E::IDENTIFIER)
+    }
+
+    /// Stops the host from exposing the extension matching the given identifier to the plugin.
+    pub fn without_extension_id(mut self, identifier: &CStr) -> Self {
+        self.disabled_extensions.push(identifier.to_owned());
+        self
+    }
+
+    /// Makes the host refuse all the plugin's GUI requests (resizing, showing and hiding).
+    #[inline]
+    pub fn deny_gui_requests(mut self) -> Self {
+        self.accept_gui_requests = false;
+        self
+    }
+
+    /// Makes the host refuse to register any timer.
+    #[inline]
+    pub fn deny_timers(mut self) -> Self {
+        self.accept_timers = false;
+        self
+    }
+
+    /// Sets the note dialects the host reports supporting through the `note-ports` extension.
+    #[inline]
+    pub fn with_note_dialects(mut self, dialects: NoteDialects) -> Self {
+        self.note_dialects = dialects;
+        self
+    }
+
+    /// Sets the audio ports rescan flags the host reports supporting through the `audio-ports`
+    /// extension.
+    #[inline]
+    pub fn with_supported_rescan_flags(mut self, flags: RescanType) -> Self {
+        self.supported_rescan_flags = flags;
+        self
+    }
+
+    /// Returns `true` if the extension matching the given identifier is exposed to the plugin.
+    pub fn is_extension_enabled(&self, identifier: &CStr) -> bool {
+        !self
+            .disabled_extensions
+            .iter()
+            .any(|id| id.as_c_str() == identifier)
+    }
+
+    /// Returns `true` if the host accepts the plugin's GUI requests.
+    #[inline]
+    pub fn accepts_gui_requests(&self) -> bool {
+        self.accept_gui_requests
+    }
+
+    /// Returns `true` if the host accepts to register timers.
+    #[inline]
+    pub fn accepts_timers(&self) -> bool {
+        self.accept_timers
+    }
+
+    /// Returns the note dialects the host reports supporting.
+    #[inline]
+    pub fn note_dialects(&self) -> NoteDialects {
+        self.note_dialects
+    }
+
+    /// Returns the audio ports rescan flags the host reports supporting.
+    #[inline]
+    pub fn supported_rescan_flags(&self) -> RescanType {
+        self.supported_rescan_flags
+    }
+}
+
+impl Default for HostBehaviors {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An extension call the plugin made to the mock host.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HostCall {
+    /// The plugin notified its GUI resize hints changed.
+    GuiResizeHintsChanged,
+    /// The plugin requested its GUI to be resized.
+    GuiRequestResize(GuiSize),
+    /// The plugin requested its GUI to be shown.
+    GuiRequestShow,
+    /// The plugin requested its GUI to be hidden.
+    GuiRequestHide,
+    /// The plugin notified its GUI was closed, for the given reason.
+    GuiClosed(GuiClosedReason),
+    /// The plugin notified its latency changed.
+    LatencyChanged,
+    /// The plugin notified its tail changed.
+    TailChanged,
+    /// The plugin notified its state changed.
+    StateMarkDirty,
+    /// The plugin requested its parameters to be rescanned.
+    ParamsRescan(ParamRescanFlags),
+    /// The plugin requested references to a parameter to be cleared.
+    ParamsClear(ClapId, ParamClearFlags),
+    /// The plugin requested a parameter flush.
+    ParamsRequestFlush,
+    /// The plugin requested its audio ports to be rescanned.
+    AudioPortsRescan(RescanType),
+    /// The plugin requested its note ports to be rescanned.
+    NotePortsRescan(NotePortRescanFlags),
+    /// The plugin registered a timer. The timer's ID is `None` if the host refused it.
+    TimerRegistered {
+        /// The period requested by the plugin, in milliseconds.
+        period_ms: u32,
+        /// The ID of the new timer, if it was registered.
+        id: Option<TimerId>,
+    },
+    /// The plugin unregistered a timer.
+    TimerUnregistered(TimerId),
+}
@@ -0,0 +1,379 @@
+use crate::audio::AudioFixture;
+use clack_host::events::UnknownEvent;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The tolerance to use when comparing audio samples against reference ones.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance {
+    /// Samples must be strictly equal.
+    Exact,
+    /// Samples may differ by at most the given number of [units in the last place](https://en.wikipedia.org/wiki/Unit_in_the_last_place).
+    ///
+    /// This is useful to tolerate rounding differences, e.g. due to operations being reordered.
+    Ulps(u32),
+    /// The difference between samples, expressed in dBFS, must be at or below the given level.
+    ///
+    /// For instance, `Decibels(-90.0)` accepts any difference below `-90dBFS`, i.e. an amplitude
+    /// difference of about `0.00003`.
+    Decibels(f32),
+}
+
+impl Tolerance {
+    /// Returns `true` if the given sample is close enough from the expected one.
+    ///
+    /// `NaN` samples are only accepted if the expected sample is also `NaN`.
+    pub fn accepts(&self, actual: f32, expected: f32) -> bool {
+        if actual.is_nan() || expected.is_nan() {
+            return actual.is_nan() && expected.is_nan();
+        }
+
+        match *self {
+            Tolerance::Exact => actual == expected,
+            Tolerance::Ulps(max) => ulps_distance(actual, expected) <= max,
+            Tolerance::Decibels(max) => {
+                let difference = (actual - expected).abs();
+                difference == 0.0 || 20.0 * difference.log10() <= max
+            }
+        }
+    }
+}
+
+/// Returns the number of representable floats between the two given ones.
+fn ulps_distance(a: f32, b: f32) -> u32 {
+    // Maps floats to integers so that consecutive floats map to consecutive integers.
+    fn ordered(f: f32) -> i64 {
+        let bits = f.to_bits() as i32;
+        if bits < 0 {
+            i64::from(i32::MIN) - i64::from(bits)
+        } else {
+            i64::from(bits)
+        }
+    }
+
+    (ordered(a) - ordered(b))
+        .unsigned_abs()
+        .min(u32::MAX as u64) as u32
+}
+
+/// The location and values of a sample that didn't match its reference.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SampleMismatch {
+    /// The index of the port the sample is in.
+    pub port_index: usize,
+    /// The index of the channel the sample is in.
+    pub channel_index: usize,
+    /// The index of the sample in the channel.
+    pub frame: usize,
+    /// The actual value of the sample.
+    pub actual: f32,
+    /// The expected value of the sample.
+    pub expected: f32,
+}
+
+/// The difference found between some audio and its reference, as returned by [`compare_audio`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioMismatch {
+    /// The two fixtures don't have the same number of ports.
+    PortCount {
+        /// The number of ports of the actual audio.
+        actual: usize,
+        /// The number of ports of the reference audio.
+        expected: usize,
+    },
+    /// A port doesn't have the same number of channels in both fixtures.
+    ChannelCount {
+        /// The index of the mismatched port.
+        port_index: usize,
+        /// The number of channels of the port in the actual audio.
+        actual: usize,
+        /// The number of channels of the port in the reference audio.
+        expected: usize,
+    },
+    /// The two fixtures don't have the same number of frames.
+    FramesCount {
+        /// The number of frames of the actual audio.
+        actual: usize,
+        /// The number of frames of the reference audio.
+        expected: usize,
+    },
+    /// Some samples are outside of the given tolerance.
+    Samples {
+        /// The first mismatched sample.
+        first: SampleMismatch,
+        /// The total number of mismatched samples.
+        count: usize,
+        /// The tolerance that was used.
+        tolerance: Tolerance,
+    },
+}
+
+impl Display for AudioMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioMismatch::PortCount { actual, expected } => write!(
+                f,
+                "Audio has {actual} ports, but the reference has {expected}"
+            ),
+            AudioMismatch::ChannelCount {
+                port_index,
+                actual,
+                expected,
+            } => write!(
+                f,
+                "Port {port_index} has {actual} channels, but the reference has {expected}"
+            ),
+            AudioMismatch::FramesCount { actual, expected } => write!(
+                f,
+                "Audio has {actual} frames, but the reference has {expected}"
+            ),
+            AudioMismatch::Samples {
+                first,
+                count,
+                tolerance,
+            } => {
+                write!(
+                    f,
+                    "{count} samples differ from the reference (tolerance: {tolerance:?}). First mismatch at port {}, channel {}, frame {}: got {}, expected {} (difference: {})",
+                    first.port_index,
+                    first.channel_index,
+                    first.frame,
+                    first.actual,
+                    first.expected,
+                    (first.actual - first.expected).abs()
+                )
+            }
+        }
+    }
+}
+
+impl Error for AudioMismatch {}
+
+/// Compares the given audio fixture against a reference one, using the given tolerance.
+///
+/// # Errors
+///
+/// This returns an [`AudioMismatch`] describing the difference if the fixtures don't have the same
+/// layout, or if any of their samples are outside of the given tolerance.
+///
+/// # Example
+///
+/// ```
+/// use clack_test::{compare_audio, AudioFixture, Tolerance};
+///
+/// let reference = AudioFixture::from_ports(vec![vec![vec![0.5, 0.25]]]);
+/// let actual = AudioFixture::from_ports(vec![vec![vec![0.5, 0.250001]]]);
+///
+/// assert!(compare_audio(&actual, &reference, Tolerance::Exact).is_err());
+/// assert!(compare_audio(&actual, &reference, Tolerance::Decibels(-90.0)).is_ok());
+/// ```
+pub fn compare_audio(
+    actual: &AudioFixture,
+    expected: &AudioFixture,
+    tolerance: Tolerance,
+) -> Result<(), AudioMismatch> {
+    if actual.port_count() != expected.port_count() {
+        return Err(AudioMismatch::PortCount {
+            actual: actual.port_count(),
+            expected: expected.port_count(),
+        });
+    }
+
+    for port_index in 0..actual.port_count() {
+        if actual.channel_count(port_index) != expected.channel_count(port_index) {
+            return Err(AudioMismatch::ChannelCount {
+                port_index,
+                actual: actual.channel_count(port_index),
+                expected: expected.channel_count(port_index),
+            });
+        }
+    }
+
+    if actual.frames_count() != expected.frames_count() {
+        return Err(AudioMismatch::FramesCount {
+            actual: actual.frames_count(),
+            expected: expected.frames_count(),
+        });
+    }
+
+    let mut first = None;
+    let mut count = 0;
+
+    for (port_index, (actual_port, expected_port)) in
+        actual.ports().iter().zip(expected.ports()).enumerate()
+    {
+        for (channel_index, (actual_channel, expected_channel)) in
+            actual_port.iter().zip(expected_port).enumerate()
+        {
+            for (frame, (&a, &e)) in actual_channel.iter().zip(expected_channel).enumerate() {
+                if tolerance.accepts(a, e) {
+                    continue;
+                }
+
+                count += 1;
+                first.get_or_insert(SampleMismatch {
+                    port_index,
+                    channel_index,
+                    frame,
+                    actual: a,
+                    expected: e,
+                });
+            }
+        }
+    }
+
+    match first {
+        None => Ok(()),
+        Some(first) => Err(AudioMismatch::Samples {
+            first,
+            count,
+            tolerance,
+        }),
+    }
+}
+
+/// Asserts the given audio fixture matches a reference one, using the given tolerance.
+///
+/// # Panics
+///
+/// This function panics with a description of the difference if [`compare_audio`] fails.
+#[track_caller]
+pub fn assert_audio_eq(actual: &AudioFixture, expected: &AudioFixture, tolerance: Tolerance) {
+    if let Err(e) = compare_audio(actual, expected, tolerance) {
+        panic!("{e}");
+    }
+}
+
+/// The difference found between an event stream and its reference, as returned by
+/// [`compare_events`].
+///
+/// Its [`Display`] implementation prints both streams side by side, highlighting the events that
+/// differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventMismatch {
+    actual: Vec<String>,
+    expected: Vec<String>,
+    mismatched: Vec<bool>,
+}
+
+impl EventMismatch {
+    /// Returns the index of the first event that differs from the reference.
+    #[inline]
+    pub fn first_difference(&self) -> usize {
+        // PANIC: an EventMismatch is only created if at least one event differs.
+        self.mismatched.iter().position(|m| *m).unwrap()
+    }
+
+    /// Returns the number of events in the actual stream.
+    #[inline]
+    pub fn actual_count(&self) -> usize {
+        self.actual.len()
+    }
+
+    /// Returns the number of events in the reference stream.
+    #[inline]
+    pub fn expected_count(&self) -> usize {
+        self.expected.len()
+    }
+}
+
+impl Display for EventMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Events differ from the reference, starting at index {} ({} events, expected {}):",
+            self.first_difference(),
+            self.actual.len(),
+            self.expected.len()
+        )?;
+
+        for (index, &mismatched) in self.mismatched.iter().enumerate() {
+            let actual = self.actual.get(index).map(String::as_str);
+            let expected = self.expected.get(index).map(String::as_str);
+
+            if !mismatched {
+                writeln!(f, "    {index}: {}", actual.unwrap_or_default())?;
+                continue;
+            }
+
+            writeln!(f, "  - {index}: {}", expected.unwrap_or("<none>"))?;
+            writeln!(f, "  + {index}: {}", actual.unwrap_or("<none>"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for EventMismatch {}
+
+/// Compares the given event stream against a reference one.
+///
+/// Core events are compared field by field, while events from other spaces are compared
+/// byte by byte.
+///
+/// # Errors
+///
+/// This returns an [`EventMismatch`] describing the differences if the two event streams differ.
+///
+/// # Example
+///
+/// ```
+/// use clack_host::events::event_types::NoteOnEvent;
+/// use clack_host::prelude::*;
+/// use clack_test::compare_events;
+///
+/// let mut actual = EventBuffer::new();
+/// actual.push(&NoteOnEvent::new(0, Pckn::new(0u16, 0u16, 60u16, 0u32), 1.0));
+///
+/// let expected = [NoteOnEvent::new(0, Pckn::new(0u16, 0u16, 60u16, 0u32), 0.5)];
+///
+/// let mismatch = compare_events(&actual, expected.iter().map(|e| e.as_ref())).unwrap_err();
+/// assert_eq!(mismatch.first_difference(), 0);
+/// ```
+pub fn compare_events<'a, 'b>(
+    actual: impl IntoIterator<Item = &'a UnknownEvent>,
+    expected: impl IntoIterator<Item = &'b UnknownEvent>,
+) -> Result<(), EventMismatch> {
+    let actual: Vec<&UnknownEvent> = actual.into_iter().collect();
+    let expected: Vec<&UnknownEvent> = expected.into_iter().collect();
+
+    let mismatched: Vec<bool> = (0..actual.len().max(expected.len()))
+        .map(|i| match (actual.get(i), expected.get(i)) {
+            (Some(a), Some(e)) => !events_eq(a, e),
+            _ => true,
+        })
+        .collect();
+
+    if !mismatched.contains(&true) {
+        return Ok(());
+    }
+
+    Err(EventMismatch {
+        actual: actual.iter().map(|e| format!("{e:?}")).collect(),
+        expected: expected.iter().map(|e| format!("{e:?}")).collect(),
+        mismatched,
+    })
+}
+
+/// Asserts the given event stream matches a reference one.
+///
+/// # Panics
+///
+/// This function panics with a description of the differences if [`compare_events`] fails.
+#[track_caller]
+pub fn assert_events_eq<'a, 'b>(
+    actual: impl IntoIterator<Item = &'a UnknownEvent>,
+    expected: impl IntoIterator<Item = &'b UnknownEvent>,
+) {
+    if let Err(e) = compare_events(actual, expected) {
+        panic!("{e}");
+    }
+}
+
+fn events_eq(a: &UnknownEvent, b: &UnknownEvent) -> bool {
+    match (a.as_core_event(), b.as_core_event()) {
+        (Some(a), Some(b)) => a == b,
+        (None, None) => a.as_bytes() == b.as_bytes(),
+        _ => false,
+    }
+}
@@ -0,0 +1,49 @@
+use clack_host::bundle::PluginBundleError;
+use clack_host::prelude::PluginInstanceError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors that can occur while driving a plugin through a [`TestPluginInstance`](crate::TestPluginInstance).
+#[derive(Debug)]
+pub enum TestError {
+    /// The plugin bundle could not be loaded.
+    Bundle(PluginBundleError),
+    /// The plugin instance returned an error.
+    Instance(PluginInstanceError),
+    /// The given input and output audio fixtures do not have the same number of frames.
+    FramesCountMismatch {
+        /// The number of frames in the input fixture.
+        inputs: usize,
+        /// The number of frames in the output fixture.
+        outputs: usize,
+    },
+}
+
+impl Display for TestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestError::Bundle(e) => write!(f, "Failed to load plugin bundle: {e}"),
+            TestError::Instance(e) => write!(f, "Plugin instance error: {e}"),
+            TestError::FramesCountMismatch { inputs, outputs } => write!(
+                f,
+                "Input and output fixtures have different frame counts (inputs: {inputs}, outputs: {outputs})"
+            ),
+        }
+    }
+}
+
+impl Error for TestError {}
+
+impl From<PluginBundleError> for TestError {
+    #[inline]
+    fn from(e: PluginBundleError) -> Self {
+        Self::Bundle(e)
+    }
+}
+
+impl From<PluginInstanceError> for TestError {
+    #[inline]
+    fn from(e: PluginInstanceError) -> Self {
+        Self::Instance(e)
+    }
+}
@@ -0,0 +1,350 @@
+use crate::behaviors::{HostBehaviors, HostCall};
+use clack_extensions::audio_ports::{HostAudioPorts, HostAudioPortsImpl, RescanType};
+use clack_extensions::gui::{GuiClosedReason, GuiSize, HostGui, HostGuiImpl};
+use clack_extensions::latency::{HostLatency, HostLatencyImpl};
+use clack_extensions::log::{HostLog, HostLogImpl, LogSeverity};
+use clack_extensions::note_ports::{
+    HostNotePorts, HostNotePortsImpl, NoteDialects, NotePortRescanFlags,
+};
+use clack_extensions::params::{
+    HostParams, HostParamsImplMainThread, HostParamsImplShared, ParamClearFlags, ParamRescanFlags,
+};
+use clack_extensions::state::{HostState, HostStateImpl};
+use clack_extensions::tail::{HostTail, HostTailImpl};
+use clack_extensions::timer::{HostTimer, HostTimerImpl, PluginTimer, TimerId};
+use clack_host::extensions::{ExtensionImplementation, HostExtensionSide};
+use clack_host::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// The mock host implementation used by [`TestPluginInstance`](crate::TestPluginInstance).
+///
+/// This host does not have any audio device, nor any event loop: every callback the plugin
+/// requests is only recorded, so that tests can inspect them and decide when to act on them.
+///
+/// How this host reacts to the plugin can be configured using [`HostBehaviors`].
+pub struct TestHost;
+
+impl HostHandlers for TestHost {
+    type Shared<'a> = TestHostShared;
+    type MainThread<'a> = TestHostMainThread<'a>;
+    type AudioProcessor<'a> = TestHostAudioProcessor<'a>;
+
+    fn declare_extensions(builder: &mut HostExtensions<Self>, shared: &Self::Shared<'_>) {
+        let behaviors = shared.behaviors();
+
+        register_if_enabled::<HostAudioPorts>(builder, behaviors);
+        register_if_enabled::<HostGui>(builder, behaviors);
+        register_if_enabled::<HostLatency>(builder, behaviors);
+        register_if_enabled::<HostLog>(builder, behaviors);
+        register_if_enabled::<HostNotePorts>(builder, behaviors);
+        register_if_enabled::<HostParams>(builder, behaviors);
+        register_if_enabled::<HostState>(builder, behaviors);
+        register_if_enabled::<HostTail>(builder, behaviors);
+        register_if_enabled::<HostTimer>(builder, behaviors);
+    }
+}
+
+fn register_if_enabled<E>(builder: &mut HostExtensions<TestHost>, behaviors: &HostBehaviors)
+where
+    E: ExtensionImplementation<TestHost, ExtensionSide = HostExtensionSide>,
+{
+    if behaviors.is_extension_enabled(E::IDENTIFIER) {
+        builder.register::<E>();
+    }
+}
+
+/// A single message logged by the plugin through the `log` extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogMessage {
+    /// The severity of the message.
+    pub severity: LogSeverity,
+    /// The message itself.
+    pub message: String,
+}
+
+/// The thread-safe part of the mock host, which records all requests made by the plugin.
+pub struct TestHostShared {
+    behaviors: HostBehaviors,
+    restart_requests: AtomicU32,
+    process_requests: AtomicU32,
+    callback_requests: AtomicU32,
+    callback_pending: AtomicBool,
+    logs: Mutex<Vec<LogMessage>>,
+    calls: Mutex<Vec<HostCall>>,
+    timers: Mutex<Vec<TimerId>>,
+    plugin_timer: OnceLock<Option<PluginTimer>>,
+}
+
+impl TestHostShared {
+    pub(crate) fn new(behaviors: HostBehaviors) -> Self {
+        Self {
+            behaviors,
+            restart_requests: AtomicU32::new(0),
+            process_requests: AtomicU32::new(0),
+            callback_requests: AtomicU32::new(0),
+            callback_pending: AtomicBool::new(false),
+            logs: Mutex::new(Vec::new()),
+            calls: Mutex::new(Vec::new()),
+            timers: Mutex::new(Vec::new()),
+            plugin_timer: OnceLock::new(),
+        }
+    }
+
+    /// Returns the behaviors this host was configured with.
+    #[inline]
+    pub fn behaviors(&self) -> &HostBehaviors {
+        &self.behaviors
+    }
+
+    /// Returns how many times the plugin called `request_restart`.
+    #[inline]
+    pub fn restart_requests(&self) -> u32 {
+        self.restart_requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many times the plugin called `request_process`.
+    #[inline]
+    pub fn process_requests(&self) -> u32 {
+        self.process_requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many times the plugin called `request_callback`.
+    #[inline]
+    pub fn callback_requests(&self) -> u32 {
+        self.callback_requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the plugin requested a main-thread callback that has not been run yet.
+    #[inline]
+    pub fn is_callback_pending(&self) -> bool {
+        self.callback_pending.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn take_callback_request(&self) -> bool {
+        self.callback_pending.swap(false, Ordering::Relaxed)
+    }
+
+    /// Returns a copy of all the messages the plugin logged so far.
+    pub fn logged_messages(&self) -> Vec<LogMessage> {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.logs.lock().unwrap().clone()
+    }
+
+    /// Returns a copy of all the extension calls the plugin made to the host so far, in order.
+    pub fn calls(&self) -> Vec<HostCall> {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Returns the IDs of all the timers the plugin currently has registered.
+    pub fn registered_timers(&self) -> Vec<TimerId> {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.timers.lock().unwrap().clone()
+    }
+
+    /// Returns the plugin's side of the timer extension, if the plugin supports it.
+    #[inline]
+    pub(crate) fn plugin_timer(&self) -> Option<PluginTimer> {
+        self.plugin_timer.get().copied().flatten()
+    }
+
+    fn record(&self, call: HostCall) {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn accept_gui_request(&self) -> Result<(), HostError> {
+        if self.behaviors.accepts_gui_requests() {
+            Ok(())
+        } else {
+            Err(HostError::Message(
+                "GUI requests are denied by the test host",
+            ))
+        }
+    }
+}
+
+impl<'a> SharedHandler<'a> for TestHostShared {
+    fn initializing(&self, instance: InitializingPluginHandle<'a>) {
+        let _ = self.plugin_timer.set(instance.get_extension());
+    }
+
+    fn request_restart(&self) {
+        self.restart_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn request_process(&self) {
+        self.process_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn request_callback(&self) {
+        self.callback_requests.fetch_add(1, Ordering::Relaxed);
+        self.callback_pending.store(true, Ordering::Relaxed);
+    }
+}
+
+impl HostLogImpl for TestHostShared {
+    fn log(&self, severity: LogSeverity, message: &str) {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.logs.lock().unwrap().push(LogMessage {
+            severity,
+            message: message.to_owned(),
+        });
+    }
+}
+
+impl HostGuiImpl for TestHostShared {
+    fn resize_hints_changed(&self) {
+        self.record(HostCall::GuiResizeHintsChanged);
+    }
+
+    fn request_resize(&self, new_size: GuiSize) -> Result<(), HostError> {
+        self.record(HostCall::GuiRequestResize(new_size));
+        self.accept_gui_request()
+    }
+
+    fn request_show(&self) -> Result<(), HostError> {
+        self.record(HostCall::GuiRequestShow);
+        self.accept_gui_request()
+    }
+
+    fn request_hide(&self) -> Result<(), HostError> {
+        self.record(HostCall::GuiRequestHide);
+        self.accept_gui_request()
+    }
+
+    fn closed(&self, reason: GuiClosedReason) {
+        self.record(HostCall::GuiClosed(reason));
+    }
+}
+
+impl HostParamsImplShared for TestHostShared {
+    fn request_flush(&self) {
+        self.record(HostCall::ParamsRequestFlush);
+    }
+}
+
+/// The main-thread part of the mock host.
+pub struct TestHostMainThread<'a> {
+    /// A reference to the shared part of the mock host.
+    shared: &'a TestHostShared,
+    /// The last timer ID that was issued.
+    latest_timer_id: u32,
+}
+
+impl<'a> TestHostMainThread<'a> {
+    pub(crate) fn new(shared: &'a TestHostShared) -> Self {
+        Self {
+            shared,
+            latest_timer_id: 0,
+        }
+    }
+}
+
+impl<'a> MainThreadHandler<'a> for TestHostMainThread<'a> {}
+
+impl HostAudioPortsImpl for TestHostMainThread<'_> {
+    fn is_rescan_flag_supported(&self, flag: RescanType) -> bool {
+        self.shared
+            .behaviors
+            .supported_rescan_flags()
+            .contains(flag)
+    }
+
+    fn rescan(&mut self, flag: RescanType) {
+        self.shared.record(HostCall::AudioPortsRescan(flag));
+    }
+}
+
+impl HostLatencyImpl for TestHostMainThread<'_> {
+    fn changed(&mut self) {
+        self.shared.record(HostCall::LatencyChanged);
+    }
+}
+
+impl HostNotePortsImpl for TestHostMainThread<'_> {
+    fn supported_dialects(&self) -> NoteDialects {
+        self.shared.behaviors.note_dialects()
+    }
+
+    fn rescan(&mut self, flags: NotePortRescanFlags) {
+        self.shared.record(HostCall::NotePortsRescan(flags));
+    }
+}
+
+impl HostParamsImplMainThread for TestHostMainThread<'_> {
+    fn rescan(&mut self, flags: ParamRescanFlags) {
+        self.shared.record(HostCall::ParamsRescan(flags));
+    }
+
+    fn clear(&mut self, param_id: ClapId, flags: ParamClearFlags) {
+        self.shared.record(HostCall::ParamsClear(param_id, flags));
+    }
+}
+
+impl HostStateImpl for TestHostMainThread<'_> {
+    fn mark_dirty(&mut self) {
+        self.shared.record(HostCall::StateMarkDirty);
+    }
+}
+
+impl HostTimerImpl for TestHostMainThread<'_> {
+    fn register_timer(&mut self, period_ms: u32) -> Result<TimerId, HostError> {
+        if !self.shared.behaviors.accepts_timers() {
+            self.shared.record(HostCall::TimerRegistered {
+                period_ms,
+                id: None,
+            });
+            return Err(HostError::Message("Timers are denied by the test host"));
+        }
+
+        self.latest_timer_id += 1;
+        let id = TimerId(self.latest_timer_id);
+
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        self.shared.timers.lock().unwrap().push(id);
+        self.shared.record(HostCall::TimerRegistered {
+            period_ms,
+            id: Some(id),
+        });
+
+        Ok(id)
+    }
+
+    fn unregister_timer(&mut self, timer_id: TimerId) -> Result<(), HostError> {
+        // PANIC: only poisoned if the plugin panicked while holding the lock, which it can't do.
+        let mut timers = self.shared.timers.lock().unwrap();
+
+        let Some(index) = timers.iter().position(|t| *t == timer_id) else {
+            return Err(HostError::Message("Unknown timer ID"));
+        };
+
+        timers.remove(index);
+        drop(timers);
+
+        self.shared.record(HostCall::TimerUnregistered(timer_id));
+        Ok(())
+    }
+}
+
+/// The audio-thread part of the mock host.
+pub struct TestHostAudioProcessor<'a> {
+    /// A reference to the shared part of the mock host.
+    shared: &'a TestHostShared,
+}
+
+impl<'a> TestHostAudioProcessor<'a> {
+    pub(crate) fn new(shared: &'a TestHostShared) -> Self {
+        Self { shared }
+    }
+}
+
+impl<'a> AudioProcessorHandler<'a> for TestHostAudioProcessor<'a> {}
+
+impl HostTailImpl for TestHostAudioProcessor<'_> {
+    fn changed(&mut self) {
+        self.shared.record(HostCall::TailChanged);
+    }
+}
@@ -0,0 +1,454 @@
+use crate::audio::AudioFixture;
+use crate::behaviors::HostBehaviors;
+use crate::error::TestError;
+use crate::host::{TestHost, TestHostAudioProcessor, TestHostMainThread, TestHostShared};
+use clack_host::bundle::EntryDescriptor;
+use clack_host::events::UnknownEvent;
+use clack_host::prelude::*;
+use clack_host::process::PluginAudioProcessor;
+use clap_sys::events::clap_event_header;
+use std::ffi::CStr;
+
+/// A plugin instance, hosted by an in-process, deterministic mock host.
+///
+/// This type drives a plugin through its whole lifecycle (instantiation, activation, processing,
+/// and deactivation), without requiring any audio device or event loop. The audio and events the
+/// plugin processes are provided as fixtures, and everything the plugin outputs or requests is
+/// recorded so that tests can inspect it.
+///
+/// All the calls made to the plugin happen on the thread that owns this type, which is treated as
+/// both the main thread and the audio thread.
+///
+/// See the [crate documentation](crate) for an example.
+pub struct TestPluginInstance {
+    instance: PluginInstance<TestHost>,
+    processor: Option<PluginAudioProcessor<TestHost>>,
+    queued_events: EventBuffer,
+    block_input_events: EventBuffer,
+    block_output_events: EventBuffer,
+    output_events: EventBuffer,
+    input_ports: AudioPorts,
+    output_ports: AudioPorts,
+    steady_time: u64,
+}
+
+impl TestPluginInstance {
+    /// Instantiates the plugin matching the given ID from an already loaded bundle.
+    ///
+    /// The mock host uses the default [`HostBehaviors`].
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin could not be instantiated.
+    #[inline]
+    pub fn new(bundle: &PluginBundle, plugin_id: &CStr) -> Result<Self, TestError> {
+        Self::with_behaviors(bundle, plugin_id, HostBehaviors::new())
+    }
+
+    /// Instantiates the plugin matching the given ID from an already loaded bundle, with the mock
+    /// host configured using the given [`HostBehaviors`].
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin could not be instantiated.
+    pub fn with_behaviors(
+        bundle: &PluginBundle,
+        plugin_id: &CStr,
+        behaviors: HostBehaviors,
+    ) -> Result<Self, TestError> {
+        let host_info = HostInfo::new(
+            "Clack test host",
+            "Clack",
+            "https://github.com/prokopyl/clack",
+            env!("CARGO_PKG_VERSION"),
+        )
+        // PANIC: none of the above strings contain NUL bytes.
+        .unwrap();
+
+        let instance = PluginInstance::<TestHost>::new(
+            |_| TestHostShared::new(behaviors),
+            |shared| TestHostMainThread::new(shared),
+            bundle,
+            plugin_id,
+            &host_info,
+        )?;
+
+        Ok(Self {
+            instance,
+            processor: None,
+            queued_events: EventBuffer::new(),
+            block_input_events: EventBuffer::new(),
+            block_output_events: EventBuffer::new(),
+            output_events: EventBuffer::new(),
+            input_ports: AudioPorts::with_capacity(0, 0),
+            output_ports: AudioPorts::with_capacity(0, 0),
+            steady_time: 0,
+        })
+    }
+
+    /// Loads the bundle from the given entry, and instantiates the plugin matching the given ID
+    /// from it.
+    ///
+    /// This is most useful with the `clap_entry` static produced by the `clack_export_entry!`
+    /// macro in the plugin crate being tested.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the bundle could not be loaded, or if the plugin could not be
+    /// instantiated.
+    ///
+    /// # Safety
+    ///
+    /// This has the same safety requirements as [`PluginBundle::load_from_raw`].
+    pub unsafe fn from_entry(
+        entry: &'static EntryDescriptor,
+        plugin_id: &CStr,
+    ) -> Result<Self, TestError> {
+        Self::from_entry_with_behaviors(entry, plugin_id, HostBehaviors::new())
+    }
+
+    /// Loads the bundle from the given entry, and instantiates the plugin matching the given ID
+    /// from it, with the mock host configured using the given [`HostBehaviors`].
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the bundle could not be loaded, or if the plugin could not be
+    /// instantiated.
+    ///
+    /// # Safety
+    ///
+    /// This has the same safety requirements as [`PluginBundle::load_from_raw`].
+    pub unsafe fn from_entry_with_behaviors(
+        entry: &'static EntryDescriptor,
+        plugin_id: &CStr,
+        behaviors: HostBehaviors,
+    ) -> Result<Self, TestError> {
+        let bundle = PluginBundle::load_from_raw(entry, "")?;
+        Self::with_behaviors(&bundle, plugin_id, behaviors)
+    }
+
+    /// Returns a reference to the underlying plugin instance.
+    #[inline]
+    pub fn instance(&self) -> &PluginInstance<TestHost> {
+        &self.instance
+    }
+
+    /// Returns a mutable reference to the underlying plugin instance.
+    #[inline]
+    pub fn instance_mut(&mut self) -> &mut PluginInstance<TestHost> {
+        &mut self.instance
+    }
+
+    /// Returns a main-thread handle to the plugin, to query and call its extensions.
+    #[inline]
+    pub fn plugin_handle(&mut self) -> PluginMainThreadHandle<'_> {
+        self.instance.plugin_handle()
+    }
+
+    /// Returns the shared part of the mock host, which records all requests made by the plugin.
+    #[inline]
+    pub fn host(&self) -> &TestHostShared {
+        self.instance.access_shared_handler(|s| s)
+    }
+
+    /// Returns `true` if the plugin is currently activated.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.processor.is_some()
+    }
+
+    /// Returns `true` if the plugin is currently processing.
+    #[inline]
+    pub fn is_processing(&self) -> bool {
+        self.processor.as_ref().is_some_and(|p| p.is_started())
+    }
+
+    /// Activates the plugin with the given audio configuration.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is already active, or if it failed to activate.
+    pub fn activate(&mut self, configuration: PluginAudioConfiguration) -> Result<(), TestError> {
+        if self.processor.is_some() {
+            return Err(PluginInstanceError::AlreadyActivatedPlugin.into());
+        }
+
+        let processor = self.instance.activate(
+            |shared, _| TestHostAudioProcessor::new(shared),
+            configuration,
+        )?;
+
+        self.processor = Some(processor.into());
+        self.steady_time = 0;
+
+        Ok(())
+    }
+
+    /// Starts the plugin's processing.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is not active, is already processing, or if it failed
+    /// to start processing.
+    pub fn start_processing(&mut self) -> Result<(), TestError> {
+        self.processor
+            .as_mut()
+            .ok_or(PluginInstanceError::DeactivatedPlugin)?
+            .start_processing()?;
+
+        Ok(())
+    }
+
+    /// Stops the plugin's processing.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is not active, or is not processing.
+    pub fn stop_processing(&mut self) -> Result<(), TestError> {
+        self.processor
+            .as_mut()
+            .ok_or(PluginInstanceError::DeactivatedPlugin)?
+            .stop_processing()?;
+
+        Ok(())
+    }
+
+    /// Deactivates the plugin, stopping its processing first if needed.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is not active.
+    pub fn deactivate(&mut self) -> Result<(), TestError> {
+        let processor = self
+            .processor
+            .take()
+            .ok_or(PluginInstanceError::DeactivatedPlugin)?;
+
+        self.instance.deactivate(processor.into_stopped());
+
+        Ok(())
+    }
+
+    /// Queues an event to be sent to the plugin in the next processing call.
+    ///
+    /// Events can be queued in any order: they are sorted by time before being sent.
+    #[inline]
+    pub fn push_event<E: AsRef<UnknownEvent> + ?Sized>(&mut self, event: &E) {
+        self.queued_events.push(event);
+    }
+
+    /// Returns all the events the plugin output during the last call to
+    /// [`process`](Self::process) or [`run`](Self::run).
+    #[inline]
+    pub fn output_events(&self) -> &EventBuffer {
+        &self.output_events
+    }
+
+    /// Runs the plugin's main-thread callback, if the plugin requested it.
+    ///
+    /// Returns `true` if the callback was run, `false` otherwise.
+    pub fn run_main_thread_callback(&mut self) -> bool {
+        if self.host().take_callback_request() {
+            self.instance.call_on_main_thread_callback();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Ticks every timer the plugin currently has registered, calling its `on_timer` callback once
+    /// for each of them.
+    ///
+    /// Returns the number of timers that were ticked.
+    pub fn tick_timers(&mut self) -> usize {
+        let Some(timer) = self.host().plugin_timer() else {
+            return 0;
+        };
+
+        let timers = self.host().registered_timers();
+        let mut plugin = self.instance.plugin_handle();
+
+        for &timer_id in &timers {
+            timer.on_timer(&mut plugin, timer_id);
+        }
+
+        timers.len()
+    }
+
+    /// Processes a single block, covering the whole given audio fixtures.
+    ///
+    /// All the queued events are sent to the plugin, and the events the plugin outputs can then be
+    /// retrieved using [`output_events`](Self::output_events).
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is not processing, if the fixtures have different
+    /// frame counts, or if the plugin failed to process.
+    pub fn process(
+        &mut self,
+        inputs: &mut AudioFixture,
+        outputs: &mut AudioFixture,
+    ) -> Result<ProcessStatus, TestError> {
+        check_frames_count(inputs, outputs)?;
+        let frames_count = inputs.frames_count();
+
+        self.output_events.clear();
+        self.queued_events.sort();
+
+        self.block_input_events.clear();
+        for event in &self.queued_events {
+            self.block_input_events.push(event);
+        }
+        self.queued_events.clear();
+
+        self.process_block(inputs, outputs, 0, frames_count)
+    }
+
+    /// Drives the plugin through a whole processing cycle, over the given audio fixtures.
+    ///
+    /// This activates the plugin with the given configuration, starts processing, processes the
+    /// whole fixtures in blocks of `max_frames_count` frames, then stops processing and
+    /// deactivates the plugin. The plugin's main-thread callback is run between blocks
+    /// whenever the plugin requests it.
+    ///
+    /// Queued events are dispatched to the block matching their time, which is relative to the
+    /// start of the fixtures. Likewise, the times of the events the plugin outputs (retrieved
+    /// using [`output_events`](Self::output_events)) are relative to the start of the fixtures.
+    ///
+    /// This returns the status the plugin returned for the last processed block.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the plugin is already active, if the fixtures have different
+    /// frame counts, or if any of the lifecycle operations failed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `configuration.max_frames_count` is zero.
+    pub fn run(
+        &mut self,
+        configuration: PluginAudioConfiguration,
+        inputs: &mut AudioFixture,
+        outputs: &mut AudioFixture,
+    ) -> Result<ProcessStatus, TestError> {
+        assert_ne!(
+            configuration.max_frames_count, 0,
+            "max_frames_count must not be zero"
+        );
+
+        check_frames_count(inputs, outputs)?;
+        let frames_count = inputs.frames_count();
+        let block_size = configuration.max_frames_count as usize;
+
+        self.activate(configuration)?;
+        self.start_processing()?;
+
+        self.output_events.clear();
+        self.queued_events.sort();
+
+        let mut start = 0;
+
+        let status = loop {
+            let end = (start + block_size).min(frames_count);
+            let is_last = end >= frames_count;
+
+            self.block_input_events.clear();
+            for event in &self.queued_events {
+                let time = event.header().time() as usize;
+                if time >= start && (time < end || is_last) {
+                    push_with_time(
+                        &mut self.block_input_events,
+                        event,
+                        (time.min(end.max(1) - 1) - start) as u32,
+                    );
+                }
+            }
+
+            let status = self.process_block(inputs, outputs, start, end)?;
+            self.run_main_thread_callback();
+
+            if is_last {
+                break status;
+            }
+            start = end;
+        };
+
+        self.queued_events.clear();
+
+        self.stop_processing()?;
+        self.deactivate()?;
+
+        Ok(status)
+    }
+
+    fn process_block(
+        &mut self,
+        inputs: &mut AudioFixture,
+        outputs: &mut AudioFixture,
+        start: usize,
+        end: usize,
+    ) -> Result<ProcessStatus, TestError> {
+        let processor = self
+            .processor
+            .as_mut()
+            .ok_or(PluginInstanceError::DeactivatedPlugin)?
+            .as_started_mut()?;
+
+        let input_buffers = inputs.as_input_buffers(&mut self.input_ports, start, end);
+        let mut output_buffers = outputs.as_output_buffers(&mut self.output_ports, start, end);
+
+        self.block_output_events.clear();
+
+        let status = processor.process(
+            &input_buffers,
+            &mut output_buffers,
+            &self.block_input_events.as_input(),
+            &mut self.block_output_events.as_output(),
+            Some(self.steady_time),
+            None,
+        )?;
+
+        self.steady_time += (end - start) as u64;
+
+        for event in &self.block_output_events {
+            let time = event.header().time() + start as u32;
+            push_with_time(&mut self.output_events, event, time);
+        }
+
+        Ok(status)
+    }
+}
+
+fn check_frames_count(inputs: &AudioFixture, outputs: &AudioFixture) -> Result<(), TestError> {
+    if inputs.frames_count() != outputs.frames_count() {
+        return Err(TestError::FramesCountMismatch {
+            inputs: inputs.frames_count(),
+            outputs: outputs.frames_count(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Pushes a copy of the given event to the buffer, with its time changed to the given one.
+pub(crate) fn push_with_time(buffer: &mut EventBuffer, event: &UnknownEvent, time: u32) {
+    if event.header().time() == time {
+        buffer.push(event);
+        return;
+    }
+
+    let bytes = event.as_bytes();
+    // Using u64 storage keeps the copy aligned like the original event.
+    let mut storage = vec![0u64; (bytes.len() + 7) / 8];
+    let header = storage.as_mut_ptr().cast::<clap_event_header>();
+
+    // SAFETY: the storage is at least as large as the event's bytes, and both don't overlap.
+    // The storage is also aligned enough for the event header, which is at its start.
+    let event = unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), header.cast::<u8>(), bytes.len());
+        (*header).time = time;
+        UnknownEvent::from_raw(header)
+    };
+
+    buffer.push(event);
+}
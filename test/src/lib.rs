@@ -0,0 +1,98 @@
+#![doc(html_logo_url = "https://raw.githubusercontent.com/prokopyl/clack/main/logo.svg")]
+#![deny(missing_docs, clippy::undocumented_unsafe_blocks)]
+
+//! An in-process testing harness for CLAP plugins, built on top of `clack-host`.
+//!
+//! This crate provides a deterministic mock host, which does not require any audio device nor
+//! event loop, and which can drive a plugin through its whole lifecycle: instantiation,
+//! activation, processing start, processing, processing stop, and deactivation.
+//!
+//! Audio is fed to and retrieved from the plugin using [`AudioFixture`]s, and events are queued
+//! using [`TestPluginInstance::push_event`]. Everything the plugin outputs or requests from the
+//! host is recorded, and can be inspected afterwards.
+//!
+//! The processed audio and events can then be compared against reference ones using
+//! [`compare_audio`] and [`compare_events`], which produce readable descriptions of any difference.
+//!
+//! The [`properties`] module also provides generators and checks for the properties of
+//! fixed-point time and parameter value conversions, which can be reused in any test suite.
+//!
+//! This allows plugin authors to write unit and integration tests for their plugins,
+//! without having to implement an entire host themselves.
+//!
+//! # Example
+//!
+//! ```
+//! use clack_plugin::prelude::*;
+//! use clack_host::prelude::PluginAudioConfiguration;
+//! use clack_test::{AudioFixture, TestPluginInstance};
+//! use std::ffi::CStr;
+//!
+//! // A tiny plugin, which doesn't do anything.
+//! pub struct MyPlugin;
+//!
+//! impl Plugin for MyPlugin {
+//!     type AudioProcessor<'a> = ();
+//!     type Shared<'a> = ();
+//!     type MainThread<'a> = ();
+//! }
+//!
+//! impl DefaultPluginFactory for MyPlugin {
+//!     fn get_descriptor() -> PluginDescriptor {
+//!         PluginDescriptor::new("org.rust-audio.clack.test-example", "Test Example")
+//!     }
+//!
+//!     fn new_shared(_host: HostSharedHandle) -> Result<(), PluginError> {
+//!         Ok(())
+//!     }
+//!
+//!     fn new_main_thread<'a>(
+//!         _host: HostMainThreadHandle<'a>,
+//!         _shared: &'a (),
+//!     ) -> Result<(), PluginError> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! clack_export_entry!(SinglePluginEntry<MyPlugin>);
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let plugin_id = CStr::from_bytes_with_nul(b"org.rust-audio.clack.test-example\0")?;
+//!
+//! // SAFETY: the entry is only loaded once here.
+//! let mut plugin = unsafe { TestPluginInstance::from_entry(&clap_entry, plugin_id)? };
+//!
+//! let configuration = PluginAudioConfiguration {
+//!     sample_rate: 44_100.0,
+//!     min_frames_count: 1,
+//!     max_frames_count: 64,
+//! };
+//!
+//! let mut inputs = AudioFixture::empty(256);
+//! let mut outputs = AudioFixture::empty(256);
+//!
+//! // Activate, process 256 frames in blocks of 64, then deactivate.
+//! plugin.run(configuration, &mut inputs, &mut outputs)?;
+//!
+//! assert!(plugin.output_events().is_empty());
+//! assert!(plugin.host().logged_messages().is_empty());
+//! # Ok(()) }
+//! ```
+
+mod audio;
+mod behaviors;
+mod compare;
+mod error;
+mod host;
+mod instance;
+pub mod properties;
+
+pub use audio::AudioFixture;
+pub use behaviors::{HostBehaviors, HostCall};
+pub use compare::{
+    assert_audio_eq, assert_events_eq, compare_audio, compare_events, AudioMismatch, EventMismatch,
+    SampleMismatch, Tolerance,
+};
+pub use error::TestError;
+pub use host::{LogMessage, TestHost, TestHostAudioProcessor, TestHostMainThread, TestHostShared};
+pub use instance::TestPluginInstance;
@@ -0,0 +1,354 @@
+//! Utilities to check properties of value conversions, for any given input.
+//!
+//! The generators in this module produce reproducible inputs, edge cases first, and the `check_*`
+//! functions verify a single property for a single input. They don't depend on any specific
+//! property-testing framework: inputs generated by e.g. `proptest` or `quickcheck` can be fed to
+//! the checks directly.
+
+use crate::TestPluginInstance;
+use clack_extensions::params::{ParamInfoBuffer, PluginParams};
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::prelude::*;
+use clack_host::utils::{Cookie, FixedPoint};
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt::{Display, Formatter};
+use std::mem::MaybeUninit;
+
+/// A small deterministic pseudo-random number generator, used to produce reproducible inputs for
+/// property checks.
+///
+/// This generator is not suitable for anything other than testing. Two generators created with the
+/// same seed always produce the same sequence of values.
+#[derive(Clone, Debug)]
+pub struct ValueGenerator {
+    state: u64,
+}
+
+impl ValueGenerator {
+    /// Creates a new generator from the given seed.
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next pseudo-random 64-bit integer.
+    pub fn next_u64(&mut self) -> u64 {
+        // SplitMix64
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns the next pseudo-random float, in the `[0; 1)` range.
+    #[inline]
+    pub fn next_unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns the next pseudo-random float, in the `[min; max]` range.
+    #[inline]
+    pub fn next_in(&mut self, min: f64, max: f64) -> f64 {
+        (min + (max - min) * self.next_unit()).clamp(min, max)
+    }
+}
+
+/// The largest magnitude of floats that can be converted to a [`FixedPoint`] without
+/// overflowing.
+pub const FIXED_POINT_MAX_FLOAT: f64 = (i64::MAX / FixedPoint::FACTOR) as f64;
+
+/// Returns `count` floats to be converted to [`BeatTime`](clack_host::utils::BeatTime) or
+/// [`SecondsTime`](clack_host::utils::SecondsTime) values.
+///
+/// Edge cases (zero, the smallest representable steps, and the largest representable values) are
+/// always generated first, followed by pseudo-random values spanning all magnitudes.
+pub fn fixed_point_values(seed: u64, count: usize) -> impl Iterator<Item = f64> {
+    let step = 1.0 / FixedPoint::FACTOR as f64;
+    let edge_cases = [
+        0.0,
+        step,
+        -step,
+        step / 2.0,
+        1.0,
+        -1.0,
+        0.5,
+        FIXED_POINT_MAX_FLOAT,
+        -FIXED_POINT_MAX_FLOAT,
+    ];
+
+    let mut generator = ValueGenerator::new(seed);
+    let random = std::iter::repeat_with(move || {
+        let magnitude = generator.next_in(-31.0, FIXED_POINT_MAX_FLOAT.log2());
+        let value = magnitude.exp2();
+        if generator.next_u64() % 2 == 0 {
+            value
+        } else {
+            -value
+        }
+    });
+
+    edge_cases.into_iter().chain(random).take(count)
+}
+
+/// Returns `count` values for a parameter with the given range.
+///
+/// Edge cases (the bounds, the middle of the range, and values just and far outside of the range)
+/// are always generated first, followed by pseudo-random values in the range.
+pub fn param_values(min: f64, max: f64, seed: u64, count: usize) -> impl Iterator<Item = f64> {
+    let span = (max - min).abs().max(1.0);
+    let edge_cases = [
+        min,
+        max,
+        (min + max) / 2.0,
+        min - span / 1000.0,
+        max + span / 1000.0,
+        min - span * 1000.0,
+        max + span * 1000.0,
+    ];
+
+    let mut generator = ValueGenerator::new(seed);
+    let random = std::iter::repeat_with(move || generator.next_in(min, max));
+
+    edge_cases.into_iter().chain(random).take(count)
+}
+
+/// A property that did not hold for a given input, as returned by the `check_*` functions of this
+/// crate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyViolation {
+    /// The name of the property that was checked.
+    pub property: &'static str,
+    /// The input that made the property fail.
+    pub input: f64,
+    /// A description of what went wrong.
+    pub details: String,
+}
+
+impl Display for PropertyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Property '{}' failed for input {}: {}",
+            self.property, self.input, self.details
+        )
+    }
+}
+
+impl Error for PropertyViolation {}
+
+/// Checks that converting the given float to a [`FixedPoint`] and back yields the same value,
+/// within the precision of the fixed-point format.
+///
+/// Values that are not finite, or too large to be represented (see [`FIXED_POINT_MAX_FLOAT`]),
+/// are ignored.
+///
+/// # Errors
+///
+/// Returns a [`PropertyViolation`] if the converted value is too far from the original one.
+pub fn check_fixed_point_roundtrip(value: f64) -> Result<(), PropertyViolation> {
+    if !value.is_finite() || value.abs() > FIXED_POINT_MAX_FLOAT {
+        return Ok(());
+    }
+
+    let converted = FixedPoint::from_float(value).to_float();
+
+    // Half a fixed-point step, plus the precision lost by the float multiplication itself.
+    let tolerance = 0.5 / FixedPoint::FACTOR as f64 + value.abs() * f64::EPSILON * 2.0;
+    let difference = (converted - value).abs();
+
+    if difference > tolerance {
+        return Err(PropertyViolation {
+            property: "fixed point round-trip",
+            input: value,
+            details: format!(
+                "converted back to {converted} (difference: {difference}, tolerance: {tolerance})"
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that converting the [`FixedPoint`] with the given bits to a float and back yields the
+/// exact same bits.
+///
+/// Only fixed-point values whose bits fit in a float's mantissa (i.e. up to 2<sup>53</sup> in
+/// magnitude) can always be converted losslessly. Other values are ignored.
+///
+/// # Errors
+///
+/// Returns a [`PropertyViolation`] if the bits differ after the conversions.
+pub fn check_fixed_point_bits_roundtrip(bits: i64) -> Result<(), PropertyViolation> {
+    if bits.unsigned_abs() > 1 << f64::MANTISSA_DIGITS {
+        return Ok(());
+    }
+
+    let converted = FixedPoint::from_float(FixedPoint::from_bits(bits).to_float()).to_bits();
+
+    if converted != bits {
+        return Err(PropertyViolation {
+            property: "fixed point bits round-trip",
+            input: bits as f64,
+            details: format!("converted back to bits {converted}, expected {bits}"),
+        });
+    }
+
+    Ok(())
+}
+
+/// The information about a parameter needed by the parameter property checks.
+struct ParamRange {
+    min: f64,
+    max: f64,
+}
+
+/// Looks up the params extension and the range of the given parameter.
+fn param_range(
+    instance: &mut TestPluginInstance,
+    param_id: ClapId,
+    property: &'static str,
+    value: f64,
+) -> Result<(PluginParams, ParamRange), PropertyViolation> {
+    let violation = |details: &str| PropertyViolation {
+        property,
+        input: value,
+        details: details.to_owned(),
+    };
+
+    let mut handle = instance.plugin_handle();
+    let params = handle
+        .get_extension::<PluginParams>()
+        .ok_or_else(|| violation("the plugin doesn't implement the params extension"))?;
+
+    let mut buffer = ParamInfoBuffer::new();
+    for index in 0..params.count(&mut handle) {
+        let Some(info) = params.get_info(&mut handle, index, &mut buffer) else {
+            continue;
+        };
+
+        if info.id == param_id {
+            let range = ParamRange {
+                min: info.min_value,
+                max: info.max_value,
+            };
+
+            return Ok((params, range));
+        }
+    }
+
+    Err(violation(&format!(
+        "the plugin has no parameter with ID {param_id}"
+    )))
+}
+
+/// Checks that setting the given parameter to the given value, through the params extension's
+/// `flush` method, always leaves it within its declared range.
+///
+/// The plugin must not be active when this is called, as it uses the main-thread `flush`.
+///
+/// # Errors
+///
+/// Returns a [`PropertyViolation`] if the plugin reports a value outside of the parameter's
+/// range, if it doesn't report any value at all, or if the parameter doesn't exist.
+pub fn check_param_clamping(
+    instance: &mut TestPluginInstance,
+    param_id: ClapId,
+    value: f64,
+) -> Result<(), PropertyViolation> {
+    const PROPERTY: &str = "param clamping";
+    let (params, range) = param_range(instance, param_id, PROPERTY, value)?;
+
+    let mut input_events = EventBuffer::new();
+    input_events.push(&ParamValueEvent::new(
+        0,
+        param_id,
+        Pckn::match_all(),
+        value,
+        Cookie::empty(),
+    ));
+
+    let mut handle = instance.plugin_handle();
+    params.flush(
+        &mut handle,
+        &input_events.as_input(),
+        &mut OutputEvents::void(),
+    );
+
+    let violation = |details: String| PropertyViolation {
+        property: PROPERTY,
+        input: value,
+        details,
+    };
+
+    match params.get_value(&mut handle, param_id) {
+        None => Err(violation(format!(
+            "the plugin returned no value for parameter {param_id}"
+        ))),
+        Some(actual) if !(range.min..=range.max).contains(&actual) => Err(violation(format!(
+            "parameter {param_id} was set to {actual}, outside of its range ({} to {})",
+            range.min, range.max
+        ))),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks that converting the given parameter value to text, back to a value, and to text again
+/// yields the same text.
+///
+/// Parameters that don't support text conversions are ignored.
+///
+/// # Errors
+///
+/// Returns a [`PropertyViolation`] if the texts differ, or if the parameter doesn't exist.
+pub fn check_param_text_roundtrip(
+    instance: &mut TestPluginInstance,
+    param_id: ClapId,
+    value: f64,
+) -> Result<(), PropertyViolation> {
+    const PROPERTY: &str = "param text round-trip";
+    let (params, _) = param_range(instance, param_id, PROPERTY, value)?;
+    let mut handle = instance.plugin_handle();
+
+    let Some(text) = value_text(&params, &mut handle, param_id, value) else {
+        return Ok(());
+    };
+
+    let violation = |details: String| PropertyViolation {
+        property: PROPERTY,
+        input: value,
+        details,
+    };
+
+    let Some(parsed) = params.text_to_value(&mut handle, param_id, &text) else {
+        return Err(violation(format!(
+            "the plugin could not parse its own text {text:?}"
+        )));
+    };
+
+    let text_again = value_text(&params, &mut handle, param_id, parsed);
+
+    if text_again.as_ref() != Some(&text) {
+        return Err(violation(format!(
+            "{text:?} was parsed as {parsed}, which is displayed as {text_again:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the text representation of the given parameter value, if the plugin supports it.
+fn value_text(
+    params: &PluginParams,
+    handle: &mut PluginMainThreadHandle,
+    param_id: ClapId,
+    value: f64,
+) -> Option<CString> {
+    let mut buffer = [MaybeUninit::uninit(); 256];
+    let text = params
+        .value_to_text(handle, param_id, value, &mut buffer)
+        .ok()?;
+
+    CString::new(text.to_vec()).ok()
+}
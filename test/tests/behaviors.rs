@@ -0,0 +1,123 @@
+use clack_extensions::state::HostState;
+use clack_extensions::timer::{HostTimer, PluginTimer, PluginTimerImpl, TimerId};
+use clack_plugin::prelude::*;
+use clack_test::{HostBehaviors, HostCall, TestPluginInstance};
+use std::ffi::CStr;
+
+/// A plugin that registers a timer on creation, and marks its state dirty on every tick.
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    type AudioProcessor<'a> = ();
+    type Shared<'a> = ();
+    type MainThread<'a> = TimerPluginMainThread<'a>;
+
+    fn declare_extensions(builder: &mut PluginExtensions<Self>, _shared: Option<&()>) {
+        builder.register::<PluginTimer>();
+    }
+}
+
+impl DefaultPluginFactory for TimerPlugin {
+    fn get_descriptor() -> PluginDescriptor {
+        PluginDescriptor::new("org.rust-audio.clack.test-timer", "Test Timer")
+    }
+
+    fn new_shared(_host: HostSharedHandle) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn new_main_thread<'a>(
+        mut host: HostMainThreadHandle<'a>,
+        _shared: &'a (),
+    ) -> Result<TimerPluginMainThread<'a>, PluginError> {
+        let timer_id = host
+            .get_extension::<HostTimer>()
+            .and_then(|timer| timer.register_timer(&mut host, 30).ok());
+
+        Ok(TimerPluginMainThread {
+            state: host.get_extension(),
+            host,
+            timer_id,
+        })
+    }
+}
+
+pub struct TimerPluginMainThread<'a> {
+    host: HostMainThreadHandle<'a>,
+    state: Option<HostState>,
+    timer_id: Option<TimerId>,
+}
+
+impl<'a> PluginMainThread<'a, ()> for TimerPluginMainThread<'a> {}
+
+impl PluginTimerImpl for TimerPluginMainThread<'_> {
+    fn on_timer(&mut self, timer_id: TimerId) {
+        if Some(timer_id) == self.timer_id {
+            if let Some(state) = &mut self.state {
+                state.mark_dirty(&self.host);
+            }
+        }
+    }
+}
+
+clack_export_entry!(SinglePluginEntry<TimerPlugin>);
+
+fn instantiate(behaviors: HostBehaviors) -> TestPluginInstance {
+    // SAFETY: this entry is a valid, compliant CLAP entry.
+    unsafe {
+        TestPluginInstance::from_entry_with_behaviors(
+            &clap_entry,
+            CStr::from_bytes_with_nul(b"org.rust-audio.clack.test-timer\0").unwrap(),
+            behaviors,
+        )
+    }
+    .unwrap()
+}
+
+#[test]
+pub fn records_extension_calls() {
+    let mut plugin = instantiate(HostBehaviors::new());
+
+    let timer_id = plugin.host().registered_timers()[0];
+    assert_eq!(plugin.tick_timers(), 1);
+
+    assert_eq!(
+        plugin.host().calls(),
+        &[
+            HostCall::TimerRegistered {
+                period_ms: 30,
+                id: Some(timer_id)
+            },
+            HostCall::StateMarkDirty
+        ]
+    );
+}
+
+#[test]
+pub fn denies_timers() {
+    let mut plugin = instantiate(HostBehaviors::new().deny_timers());
+
+    assert!(plugin.host().registered_timers().is_empty());
+    assert_eq!(plugin.tick_timers(), 0);
+
+    assert_eq!(
+        plugin.host().calls(),
+        &[HostCall::TimerRegistered {
+            period_ms: 30,
+            id: None
+        }]
+    );
+}
+
+#[test]
+pub fn hides_disabled_extensions() {
+    let mut plugin = instantiate(
+        HostBehaviors::new()
+            .without_extension::<HostTimer>()
+            .without_extension::<HostState>(),
+    );
+
+    assert!(plugin.host().registered_timers().is_empty());
+    assert_eq!(plugin.tick_timers(), 0);
+    assert!(plugin.host().calls().is_empty());
+}
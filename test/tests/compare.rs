@@ -0,0 +1,95 @@
+use clack_host::events::event_types::{NoteOffEvent, NoteOnEvent};
+use clack_host::prelude::*;
+use clack_test::*;
+
+#[test]
+pub fn tolerances() {
+    assert!(Tolerance::Exact.accepts(0.5, 0.5));
+    assert!(!Tolerance::Exact.accepts(0.5, 0.5 + f32::EPSILON));
+
+    let next = f32::from_bits(0.5f32.to_bits() + 2);
+    assert!(Tolerance::Ulps(2).accepts(next, 0.5));
+    assert!(!Tolerance::Ulps(1).accepts(next, 0.5));
+    assert!(Tolerance::Ulps(2).accepts(-0.0, 0.0));
+
+    assert!(Tolerance::Decibels(-60.0).accepts(0.5005, 0.5));
+    assert!(!Tolerance::Decibels(-80.0).accepts(0.5005, 0.5));
+
+    assert!(Tolerance::Exact.accepts(f32::NAN, f32::NAN));
+    assert!(!Tolerance::Decibels(0.0).accepts(f32::NAN, 0.0));
+}
+
+#[test]
+pub fn reports_audio_mismatches() {
+    let reference = AudioFixture::from_ports(vec![vec![vec![0.0, 0.5, 1.0], vec![0.0; 3]]]);
+    let mut actual = reference.clone();
+
+    assert_eq!(compare_audio(&actual, &reference, Tolerance::Exact), Ok(()));
+
+    actual.channel_mut(0, 1)[1] = 0.25;
+    actual.channel_mut(0, 1)[2] = 0.25;
+
+    let mismatch = compare_audio(&actual, &reference, Tolerance::Ulps(4)).unwrap_err();
+    assert_eq!(
+        mismatch,
+        AudioMismatch::Samples {
+            first: SampleMismatch {
+                port_index: 0,
+                channel_index: 1,
+                frame: 1,
+                actual: 0.25,
+                expected: 0.0
+            },
+            count: 2,
+            tolerance: Tolerance::Ulps(4)
+        }
+    );
+
+    assert_eq!(
+        compare_audio(
+            &AudioFixture::silence(&[2], 4),
+            &reference,
+            Tolerance::Exact
+        ),
+        Err(AudioMismatch::FramesCount {
+            actual: 4,
+            expected: 3
+        })
+    );
+
+    assert_eq!(
+        compare_audio(
+            &AudioFixture::silence(&[1], 3),
+            &reference,
+            Tolerance::Exact
+        ),
+        Err(AudioMismatch::ChannelCount {
+            port_index: 0,
+            actual: 1,
+            expected: 2
+        })
+    );
+}
+
+#[test]
+pub fn reports_event_mismatches() {
+    let pckn = Pckn::new(0u16, 0u16, 60u16, 0u32);
+
+    let mut actual = EventBuffer::new();
+    actual.push(&NoteOnEvent::new(0, pckn, 1.0));
+    actual.push(&NoteOffEvent::new(10, pckn, 1.0));
+
+    let mut expected_buffer = EventBuffer::new();
+    expected_buffer.push(&NoteOnEvent::new(0, pckn, 1.0));
+
+    assert_eq!(compare_events(&actual, &actual), Ok(()));
+
+    let mismatch = compare_events(&actual, &expected_buffer).unwrap_err();
+    assert_eq!(mismatch.first_difference(), 1);
+    assert_eq!(mismatch.actual_count(), 2);
+    assert_eq!(mismatch.expected_count(), 1);
+
+    let description = mismatch.to_string();
+    assert!(description.contains("  - 1: <none>"));
+    assert!(description.contains("  + 1: NoteOff"));
+}
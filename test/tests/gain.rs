@@ -0,0 +1,91 @@
+use clack_host::events::event_types::ParamValueEvent;
+use clack_host::prelude::*;
+use clack_host::utils::Cookie;
+use clack_plugin_gain::clap_entry;
+use clack_test::{AudioFixture, TestPluginInstance};
+use std::ffi::CStr;
+
+fn instantiate() -> TestPluginInstance {
+    // SAFETY: this entry is a valid, compliant CLAP entry.
+    unsafe {
+        TestPluginInstance::from_entry(
+            &clap_entry,
+            CStr::from_bytes_with_nul(b"org.rust-audio.clack.gain\0").unwrap(),
+        )
+    }
+    .unwrap()
+}
+
+const CONFIGURATION: PluginAudioConfiguration = PluginAudioConfiguration {
+    sample_rate: 44_100.0,
+    min_frames_count: 1,
+    max_frames_count: 32,
+};
+
+#[test]
+pub fn runs_whole_lifecycle() {
+    let mut plugin = instantiate();
+
+    let mut inputs = AudioFixture::silence(&[2], 100);
+    inputs.fill_with(&[1.0]);
+    let mut outputs = AudioFixture::silence(&[2], 100);
+
+    // Halve the volume at the start of the third block.
+    plugin.push_event(&ParamValueEvent::new(
+        64,
+        ClapId::new(1),
+        Pckn::match_all(),
+        0.5,
+        Cookie::empty(),
+    ));
+
+    plugin
+        .run(CONFIGURATION, &mut inputs, &mut outputs)
+        .unwrap();
+
+    assert!(!plugin.is_active());
+
+    for channel in 0..2 {
+        let output = outputs.channel(0, channel);
+        assert!(output[..64].iter().all(|s| *s == 1.0));
+        assert!(output[64..].iter().all(|s| *s == 0.5));
+    }
+}
+
+#[test]
+pub fn processes_single_blocks() {
+    let mut plugin = instantiate();
+
+    let mut inputs = AudioFixture::silence(&[2], 32);
+    inputs.fill_with(&[0.25, -0.25]);
+    let mut outputs = AudioFixture::silence(&[2], 32);
+
+    // Processing is not possible until the plugin is activated and started.
+    assert!(plugin.process(&mut inputs, &mut outputs).is_err());
+
+    plugin.activate(CONFIGURATION).unwrap();
+    plugin.start_processing().unwrap();
+    assert!(plugin.is_processing());
+
+    plugin.process(&mut inputs, &mut outputs).unwrap();
+    assert_eq!(inputs, outputs);
+
+    plugin.stop_processing().unwrap();
+    plugin.deactivate().unwrap();
+
+    assert!(plugin.output_events().is_empty());
+    assert_eq!(plugin.host().restart_requests(), 0);
+}
+
+#[test]
+pub fn rejects_mismatched_fixtures() {
+    let mut plugin = instantiate();
+
+    let mut inputs = AudioFixture::silence(&[2], 32);
+    let mut outputs = AudioFixture::silence(&[2], 16);
+
+    assert!(plugin
+        .run(CONFIGURATION, &mut inputs, &mut outputs)
+        .is_err());
+    assert!(!plugin.is_active());
+}
@@ -0,0 +1,83 @@
+use clack_host::prelude::*;
+use clack_plugin_gain::clap_entry;
+use clack_test::properties::*;
+use clack_test::TestPluginInstance;
+use std::ffi::CStr;
+
+fn instantiate() -> TestPluginInstance {
+    // SAFETY: this entry is a valid, compliant CLAP entry.
+    unsafe {
+        TestPluginInstance::from_entry(
+            &clap_entry,
+            CStr::from_bytes_with_nul(b"org.rust-audio.clack.gain\0").unwrap(),
+        )
+    }
+    .unwrap()
+}
+
+const VOLUME: ClapId = ClapId::new(1);
+
+#[test]
+pub fn generators_are_deterministic() {
+    let a: Vec<f64> = fixed_point_values(42, 100).collect();
+    let b: Vec<f64> = fixed_point_values(42, 100).collect();
+    let c: Vec<f64> = fixed_point_values(43, 100).collect();
+
+    assert_eq!(a.len(), 100);
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    let mut generator = ValueGenerator::new(0);
+    for _ in 0..1000 {
+        let value = generator.next_in(-2.0, 3.0);
+        assert!((-2.0..=3.0).contains(&value));
+    }
+}
+
+#[test]
+pub fn param_values_include_edge_cases() {
+    let values: Vec<f64> = param_values(0.0, 1.0, 0, 50).collect();
+
+    assert_eq!(values.len(), 50);
+    assert_eq!(&values[..3], &[0.0, 1.0, 0.5]);
+    assert!(values.iter().any(|v| *v < 0.0));
+    assert!(values.iter().any(|v| *v > 1.0));
+}
+
+#[test]
+pub fn fixed_point_conversions_roundtrip() {
+    for value in fixed_point_values(0, 10_000) {
+        check_fixed_point_roundtrip(value).unwrap();
+    }
+
+    let mut generator = ValueGenerator::new(0);
+    for _ in 0..10_000 {
+        let bits = (generator.next_u64() >> 10) as i64 - (1 << 53);
+        check_fixed_point_bits_roundtrip(bits).unwrap();
+    }
+
+    // Values that can't be represented are ignored.
+    check_fixed_point_roundtrip(f64::NAN).unwrap();
+    check_fixed_point_roundtrip(f64::MAX).unwrap();
+}
+
+#[test]
+pub fn gain_params_are_clamped() {
+    let mut plugin = instantiate();
+
+    for value in param_values(0.0, 1.0, 0, 200) {
+        check_param_clamping(&mut plugin, VOLUME, value).unwrap();
+    }
+
+    let violation = check_param_clamping(&mut plugin, ClapId::new(42), 0.5).unwrap_err();
+    assert_eq!(violation.property, "param clamping");
+}
+
+#[test]
+pub fn gain_params_text_roundtrips() {
+    let mut plugin = instantiate();
+
+    for value in param_values(0.0, 1.0, 0, 200) {
+        check_param_text_roundtrip(&mut plugin, VOLUME, value).unwrap();
+    }
+}